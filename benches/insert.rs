@@ -0,0 +1,77 @@
+//! Compares importing 1000 clients one row per statement with chunked inserts.
+//!
+//! Run with `cargo bench --bench insert`.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use pushkind_common::db::establish_connection_pool;
+use tempfile::NamedTempFile;
+
+use pushkind_crm::domain::client::NewClient;
+use pushkind_crm::domain::types::{ClientEmail, ClientName, HubId, ImportConflictStrategy};
+use pushkind_crm::models::config::DEFAULT_BATCH_CHUNK_SIZE;
+use pushkind_crm::repository::{ClientWriter, DieselRepository};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+const CLIENTS: usize = 1000;
+const RUNS: usize = 5;
+
+fn clients() -> Vec<NewClient> {
+    let hub_id = HubId::new(1).expect("valid hub id");
+    (0..CLIENTS)
+        .map(|index| {
+            NewClient::new(
+                hub_id,
+                ClientName::new(format!("Client {index}")).expect("valid name"),
+                Some(ClientEmail::new(format!("client{index}@example.com")).expect("valid email")),
+                None,
+                Some(BTreeMap::from([
+                    ("Stage".to_string(), "Lead".to_string()),
+                    ("Город".to_string(), "Казань".to_string()),
+                ])),
+            )
+        })
+        .collect()
+}
+
+/// Imports the clients into a fresh database and returns the elapsed time.
+fn import(batch_chunk_size: usize, clients: &[NewClient]) -> Duration {
+    let tempfile = NamedTempFile::new().expect("temp file");
+    let pool = establish_connection_pool(tempfile.path().to_str().expect("utf-8 path"))
+        .expect("connection pool");
+    pool.get()
+        .expect("connection")
+        .run_pending_migrations(MIGRATIONS)
+        .expect("migrations");
+    let repo = DieselRepository::new(pool).with_batch_chunk_size(batch_chunk_size);
+
+    let started = Instant::now();
+    let summary = repo
+        .create_clients(clients, ImportConflictStrategy::Skip)
+        .expect("import succeeds");
+    let elapsed = started.elapsed();
+    assert_eq!(summary.inserted, CLIENTS);
+
+    elapsed
+}
+
+fn main() {
+    let clients = clients();
+
+    for (label, batch_chunk_size) in [
+        ("one row per statement", 1),
+        ("chunked", DEFAULT_BATCH_CHUNK_SIZE),
+    ] {
+        let mut runs: Vec<Duration> = (0..RUNS)
+            .map(|_| import(batch_chunk_size, &clients))
+            .collect();
+        runs.sort();
+        println!(
+            "{label:>22} (chunk {batch_chunk_size:>3}): median {:?}, best {:?}",
+            runs[RUNS / 2],
+            runs[0]
+        );
+    }
+}
@@ -0,0 +1,17 @@
+import { StrictMode } from "react";
+import { createRoot } from "react-dom/client";
+
+import { HubDashboardBootstrap } from "../pages/HubDashboardBootstrap";
+import "../styles/foundation.css";
+
+const rootElement = document.getElementById("react-root");
+
+if (!rootElement) {
+  throw new Error("React root element not found.");
+}
+
+createRoot(rootElement).render(
+  <StrictMode>
+    <HubDashboardBootstrap />
+  </StrictMode>,
+);
@@ -33,6 +33,11 @@ import type {
   ClientEvent,
   ClientFieldDisplay,
   ClientListItem,
+  FieldMeta,
+  HubDashboardData,
+  HubSettings,
+  ImportJob,
+  ImportJobStatus,
   ImportantFieldSettingsData,
   Manager,
   ManagerModalData,
@@ -55,6 +60,18 @@ function readOptionalStringArray(record: Record<string, unknown>, key: string) {
   return value;
 }
 
+function readOptionalNumber(record: Record<string, unknown>, key: string) {
+  const value = record[key];
+  if (value == null) {
+    return undefined;
+  }
+  if (typeof value !== "number") {
+    throw new Error(`Invalid API response: expected number at ${key}.`);
+  }
+
+  return value;
+}
+
 function parseClientListItems(payload: unknown): ClientListItem[] {
   if (!Array.isArray(payload)) {
     throw new Error("Invalid client list payload.");
@@ -71,6 +88,7 @@ function parseClientListItems(payload: unknown): ClientListItem[] {
       name: readString(item, "name"),
       email: readOptionalString(item, "email"),
       phone: readOptionalString(item, "phone"),
+      rawPhone: readOptionalString(item, "raw_phone"),
       fieldBadges:
         readOptionalStringArray(item, "field_badges") ??
         Object.values(parseStringMap(item.fields)).slice(0, 8),
@@ -85,6 +103,7 @@ function parseClientDirectoryData(payload: unknown): ClientDirectoryData {
 
   return {
     searchQuery: readOptionalString(payload, "search_query"),
+    unassigned: readBoolean(payload, "unassigned"),
     clients: {
       items: parseClientListItems(payload.clients.items),
       page: readNumber(payload.clients, "page"),
@@ -103,6 +122,8 @@ function parseManager(item: unknown): Manager {
     name: readString(item, "name"),
     email: readString(item, "email"),
     isUser: readBoolean(item, "is_user"),
+    company: readOptionalString(item, "company"),
+    phone: readOptionalString(item, "phone"),
   };
 }
 
@@ -117,6 +138,18 @@ function parseClientFieldDisplay(item: unknown): ClientFieldDisplay {
   };
 }
 
+function parseFieldMeta(item: unknown): FieldMeta {
+  if (!isRecord(item)) {
+    throw new Error("Invalid field metadata payload.");
+  }
+
+  return {
+    name: readString(item, "name"),
+    valueCount: readNumber(item, "value_count"),
+    isImportant: readBoolean(item, "is_important"),
+  };
+}
+
 function parseEventData(item: unknown): Record<string, unknown> {
   if (!isRecord(item)) {
     return {};
@@ -151,7 +184,10 @@ function parseClientDetails(payload: unknown): ClientDetails {
       name: readString(payload.client, "name"),
       email: readOptionalString(payload.client, "email"),
       phone: readOptionalString(payload.client, "phone"),
+      phoneDisplay: readOptionalString(payload.client, "phone_display"),
+      rawPhone: readOptionalString(payload.client, "raw_phone"),
       fields: parseStringMap(payload.client.fields),
+      version: readNumber(payload.client, "version"),
     },
     managers: Array.isArray(payload.managers)
       ? payload.managers.map(parseManager)
@@ -163,9 +199,7 @@ function parseClientDetails(payload: unknown): ClientDetails {
       ? payload.documents.map(parseClientEvent)
       : [],
     availableFields: Array.isArray(payload.available_fields)
-      ? payload.available_fields.filter(
-          (item): item is string => typeof item === "string",
-        )
+      ? payload.available_fields.map(parseFieldMeta)
       : [],
     importantFields: Array.isArray(payload.important_fields)
       ? payload.important_fields.map(parseClientFieldDisplay)
@@ -176,6 +210,7 @@ function parseClientDetails(payload: unknown): ClientDetails {
     totalEvents: readNumber(payload, "total_events"),
     todoServiceUrl: readString(payload, "todo_service_url"),
     filesServiceUrl: readString(payload, "files_service_url"),
+    locale: readString(payload, "locale"),
   };
 }
 
@@ -221,6 +256,70 @@ function parseImportantFieldSettingsData(
   };
 }
 
+function parseHubSettings(payload: unknown): HubSettings {
+  if (!isRecord(payload)) {
+    throw new Error("Invalid hub settings payload.");
+  }
+
+  return {
+    itemsPerPage: readNumber(payload, "items_per_page"),
+    emailSenderName: readOptionalString(payload, "email_sender_name"),
+    allowManagerSelfAssign: readBoolean(payload, "allow_manager_self_assign"),
+    defaultPhoneRegion: readOptionalString(payload, "default_phone_region"),
+    eventRetentionDays: readOptionalNumber(payload, "event_retention_days"),
+    emailReplyTo: readOptionalString(payload, "email_reply_to"),
+  };
+}
+
+function parseHubDashboardData(payload: unknown): HubDashboardData {
+  if (!isRecord(payload)) {
+    throw new Error("Invalid hub dashboard payload.");
+  }
+
+  return {
+    totalClients: readNumber(payload, "total_clients"),
+    activeClients: readNumber(payload, "active_clients"),
+    totalManagers: readNumber(payload, "total_managers"),
+    eventsToday: readNumber(payload, "events_today"),
+    eventsThisWeek: readNumber(payload, "events_this_week"),
+    topManagerByEvents:
+      payload.top_manager_by_events == null
+        ? undefined
+        : parseManager(payload.top_manager_by_events),
+    unassignedCount: readNumber(payload, "unassigned_count"),
+  };
+}
+
+const IMPORT_JOB_STATUSES: ImportJobStatus[] = [
+  "pending",
+  "running",
+  "completed",
+  "failed",
+];
+
+function parseImportJob(payload: unknown): ImportJob {
+  if (!isRecord(payload)) {
+    throw new Error("Invalid import job payload.");
+  }
+
+  const status = readString(payload, "status");
+  if (!IMPORT_JOB_STATUSES.includes(status as ImportJobStatus)) {
+    throw new Error("Invalid import job status.");
+  }
+
+  return {
+    id: readNumber(payload, "id"),
+    status: status as ImportJobStatus,
+    totalRows: readNumber(payload, "total_rows"),
+    insertedRows: readNumber(payload, "inserted_rows"),
+    updatedRows: readNumber(payload, "updated_rows"),
+    errorRows: readNumber(payload, "error_rows"),
+    errorMessage: readOptionalString(payload, "error_message"),
+    startedAt: readString(payload, "started_at"),
+    completedAt: readOptionalString(payload, "completed_at"),
+  };
+}
+
 function parseAuthUsers(payload: unknown): AuthUserSearchItem[] {
   if (!Array.isArray(payload)) {
     throw new Error("Invalid auth users payload.");
@@ -315,6 +414,21 @@ export async function fetchImportantFieldSettingsData(): Promise<ImportantFieldS
   return parseImportantFieldSettingsData(payload);
 }
 
+export async function fetchHubSettings(): Promise<HubSettings> {
+  const payload = await fetchJson("/api/v1/settings");
+  return parseHubSettings(payload);
+}
+
+export async function fetchHubDashboardData(): Promise<HubDashboardData> {
+  const payload = await fetchJson("/api/v1/dashboard");
+  return parseHubDashboardData(payload);
+}
+
+export async function fetchImportJob(jobId: number): Promise<ImportJob> {
+  const payload = await fetchJson(`/api/v1/import/${jobId}/status`);
+  return parseImportJob(payload);
+}
+
 export async function fetchAuthUsers(
   authBaseUrl: string,
   query: string,
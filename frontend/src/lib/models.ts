@@ -18,6 +18,7 @@ export type ClientListItem = {
   name: string;
   email?: string;
   phone?: string;
+  rawPhone?: string;
   fieldBadges: string[];
 };
 
@@ -29,6 +30,7 @@ export type PaginatedClientList = {
 
 export type ClientDirectoryData = {
   searchQuery?: string;
+  unassigned: boolean;
   clients: PaginatedClientList;
 };
 
@@ -37,6 +39,8 @@ export type Manager = {
   name: string;
   email: string;
   isUser: boolean;
+  company: string | null;
+  phone: string | null;
 };
 
 export type ClientFieldDisplay = {
@@ -44,6 +48,12 @@ export type ClientFieldDisplay = {
   value?: string;
 };
 
+export type FieldMeta = {
+  name: string;
+  valueCount: number;
+  isImportant: boolean;
+};
+
 export type ClientEvent = {
   id: number;
   eventType: string;
@@ -59,17 +69,21 @@ export type ClientDetails = {
     name: string;
     email?: string;
     phone?: string;
+    phoneDisplay?: string;
+    rawPhone?: string;
     fields: Record<string, string>;
+    version: number;
   };
   managers: Manager[];
   events: ClientEvent[];
   documents: ClientEvent[];
-  availableFields: string[];
+  availableFields: FieldMeta[];
   importantFields: ClientFieldDisplay[];
   otherFields: ClientFieldDisplay[];
   totalEvents: number;
   todoServiceUrl: string;
   filesServiceUrl: string;
+  locale: string;
 };
 
 export type ManagerWithClients = {
@@ -86,6 +100,39 @@ export type ManagerModalData = {
   clients: ClientListItem[];
 };
 
+export type HubDashboardData = {
+  totalClients: number;
+  activeClients: number;
+  totalManagers: number;
+  eventsToday: number;
+  eventsThisWeek: number;
+  topManagerByEvents?: Manager;
+  unassignedCount: number;
+};
+
+export type ImportJobStatus = "pending" | "running" | "completed" | "failed";
+
+export type ImportJob = {
+  id: number;
+  status: ImportJobStatus;
+  totalRows: number;
+  insertedRows: number;
+  updatedRows: number;
+  errorRows: number;
+  errorMessage?: string;
+  startedAt: string;
+  completedAt?: string;
+};
+
+export type HubSettings = {
+  itemsPerPage: number;
+  emailSenderName?: string;
+  allowManagerSelfAssign: boolean;
+  defaultPhoneRegion?: string;
+  eventRetentionDays?: number;
+  emailReplyTo?: string;
+};
+
 export type ImportantFieldSettingsData = {
   fieldsText: string;
 };
@@ -1,6 +1,12 @@
 import { describe, expect, it } from "vitest";
 
-import { localizeTaskPriority, localizeTaskStatus } from "./ClientBootstrap";
+import {
+  attachmentFormBody,
+  formatDocumentMetadata,
+  formatFileSize,
+  localizeTaskPriority,
+  localizeTaskStatus,
+} from "./ClientBootstrap";
 
 describe("ClientBootstrap task copy", () => {
   it("localizes known task statuses to Russian copy", () => {
@@ -22,3 +28,48 @@ describe("ClientBootstrap task copy", () => {
     expect(localizeTaskPriority("Urgent")).toBe("Urgent");
   });
 });
+
+describe("ClientBootstrap document metadata", () => {
+  it("formats file sizes in Russian units", () => {
+    expect(formatFileSize(512)).toBe("512 Б");
+    expect(formatFileSize(1536)).toBe("1.5 КБ");
+    expect(formatFileSize(2 * 1024 * 1024)).toBe("2.0 МБ");
+  });
+
+  it("joins mime type and size when present", () => {
+    expect(
+      formatDocumentMetadata({ mime: "application/pdf", size_bytes: 2097152 }),
+    ).toBe("application/pdf, 2.0 МБ");
+    expect(formatDocumentMetadata({ size_bytes: 10 })).toBe("10 Б");
+  });
+
+  it("returns nothing for events without metadata", () => {
+    expect(
+      formatDocumentMetadata({ text: "Договор", url: "https://example.com" }),
+    ).toBeUndefined();
+  });
+});
+
+describe("ClientBootstrap attachment form", () => {
+  it("omits blank optional document fields", () => {
+    const formData = new FormData();
+    formData.append("text", "Договор");
+    formData.append("url", "https://example.com/contract.pdf");
+    formData.append("mime", "");
+    formData.append("size_bytes", "");
+
+    expect(attachmentFormBody(formData).toString()).toBe(
+      "text=%D0%94%D0%BE%D0%B3%D0%BE%D0%B2%D0%BE%D1%80&url=https%3A%2F%2Fexample.com%2Fcontract.pdf",
+    );
+  });
+
+  it("keeps document type and size when filled in", () => {
+    const formData = new FormData();
+    formData.append("mime", "application/pdf");
+    formData.append("size_bytes", "2048");
+
+    const body = attachmentFormBody(formData);
+    expect(body.get("mime")).toBe("application/pdf");
+    expect(body.get("size_bytes")).toBe("2048");
+  });
+});
@@ -13,6 +13,7 @@ import {
   fetchShellData,
   isApiMutationError,
   postForm,
+  postMultipartForm,
   toFieldErrorMap,
 } from "../lib/api";
 import type {
@@ -55,6 +56,19 @@ function parseClientIdFromLocation() {
   return Number(match[1]);
 }
 
+// Renders a stored UTC timestamp in the viewer's time zone and `locale`.
+function formatEventDate(value: string, locale: string) {
+  const date = new Date(`${value.replace(" ", "T")}Z`);
+  if (Number.isNaN(date.getTime())) {
+    return value;
+  }
+
+  return date.toLocaleString(locale, {
+    dateStyle: "medium",
+    timeStyle: "short",
+  });
+}
+
 function renderManagerPopover(manager: Manager, todoServiceUrl: string) {
   const todoLink =
     todoServiceUrl && manager.isUser
@@ -96,15 +110,74 @@ export function localizeTaskPriority(priority: string) {
   }
 }
 
+export function formatFileSize(bytes: number) {
+  if (bytes < 1024) {
+    return `${bytes} Б`;
+  }
+  if (bytes < 1024 * 1024) {
+    return `${(bytes / 1024).toFixed(1)} КБ`;
+  }
+  return `${(bytes / (1024 * 1024)).toFixed(1)} МБ`;
+}
+
+// Type and size of a document; events stored before they were recorded have neither.
+export function formatDocumentMetadata(data: Record<string, unknown>) {
+  const parts: string[] = [];
+  if (typeof data.mime === "string") {
+    parts.push(data.mime);
+  }
+  if (typeof data.size_bytes === "number") {
+    parts.push(formatFileSize(data.size_bytes));
+  }
+  return parts.length > 0 ? parts.join(", ") : undefined;
+}
+
+// Document types accepted by the server for attachments.
+const DOCUMENT_MIME_TYPES = [
+  ["application/pdf", "PDF"],
+  ["application/msword", "Word (.doc)"],
+  [
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "Word (.docx)",
+  ],
+  ["application/vnd.ms-excel", "Excel (.xls)"],
+  [
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "Excel (.xlsx)",
+  ],
+  ["application/zip", "ZIP"],
+  ["image/jpeg", "JPEG"],
+  ["image/png", "PNG"],
+  ["image/gif", "GIF"],
+  ["text/csv", "CSV"],
+  ["text/plain", "Текст"],
+] as const;
+
+// Optional attachment fields left blank are omitted so the server records nothing.
+export function attachmentFormBody(formData: FormData) {
+  const body = new URLSearchParams();
+  for (const [key, value] of formData.entries()) {
+    const text = String(value);
+    if ((key === "mime" || key === "size_bytes") && text.trim() === "") {
+      continue;
+    }
+    body.append(key, text);
+  }
+  return body;
+}
+
 function renderEventContent(event: ClientEvent, todoServiceUrl: string) {
   const data = event.eventData;
 
   if (event.eventType === "DocumentLink") {
+    const metadata = formatDocumentMetadata(data);
+
     return (
       <p className="mb-0">
         <a href={typeof data.url === "string" ? data.url : "#"}>
           {typeof data.text === "string" ? data.text : "Документ"}
         </a>
+        {metadata ? <span className="text-muted"> ({metadata})</span> : null}
       </p>
     );
   }
@@ -163,6 +236,14 @@ function renderEventContent(event: ClientEvent, todoServiceUrl: string) {
     );
   }
 
+  const attachment =
+    data.attachment && typeof data.attachment === "object"
+      ? (data.attachment as Record<string, unknown>)
+      : undefined;
+  const attachmentMetadata = attachment
+    ? formatDocumentMetadata(attachment)
+    : undefined;
+
   return (
     <>
       {typeof data.subject === "string" ? (
@@ -173,6 +254,15 @@ function renderEventContent(event: ClientEvent, todoServiceUrl: string) {
       {typeof data.text === "string" ? (
         <div dangerouslySetInnerHTML={{ __html: data.text }} />
       ) : null}
+      {attachment ? (
+        <p className="mb-0">
+          <span className="text-muted">Вложение:</span>{" "}
+          {typeof attachment.name === "string" ? attachment.name : "Документ"}
+          {attachmentMetadata ? (
+            <span className="text-muted"> ({attachmentMetadata})</span>
+          ) : null}
+        </p>
+      ) : null}
     </>
   );
 }
@@ -224,7 +314,7 @@ function renderEventTypeBadge(eventType: string) {
     default:
       return (
         <span className="badge bg-secondary bg-opacity-10 text-secondary ms-2">
-          другое
+          {eventType || "другое"}
         </span>
       );
   }
@@ -244,6 +334,8 @@ export function ClientBootstrap() {
   const [subject, setSubject] = useState("");
   const [message, setMessage] = useState("");
   const [eventType, setEventType] = useState("Comment");
+  const [eventSubtype, setEventSubtype] = useState("");
+  const [attachmentUrl, setAttachmentUrl] = useState("");
   const [editableFields, setEditableFields] = useState<EditableFieldRow[]>([]);
   const [commentErrors, setCommentErrors] = useState<Record<string, string>>(
     {},
@@ -422,20 +514,39 @@ export function ClientBootstrap() {
 
   async function handleCommentSubmit(event: FormEvent<HTMLFormElement>) {
     event.preventDefault();
+    const form = event.currentTarget;
     setIsCommentSubmitting(true);
     setCommentErrors({});
 
-    const body = new URLSearchParams();
+    const body = new FormData();
     body.set("subject", subject);
     body.set("message", renderedMessage);
     body.set("event_type", eventType);
+    if (eventType === "Other") {
+      body.set("event_subtype", eventSubtype);
+    }
+    if (eventType === "Email") {
+      const attachment = new FormData(form).get("attachment");
+      if (attachment instanceof File && attachment.size > 0) {
+        body.set("attachment", attachment);
+      }
+      if (attachmentUrl.trim()) {
+        body.set("attachment_url", attachmentUrl.trim());
+      }
+    }
 
     try {
-      const result = await postForm(`/client/${client.id}/comment`, body);
+      const result = await postMultipartForm(
+        `/client/${client.id}/comment`,
+        body,
+      );
       window.showFlashMessage?.(result.message, "success");
+      form.reset();
       setSubject("");
       setMessage("");
       setEventType("Comment");
+      setEventSubtype("");
+      setAttachmentUrl("");
       await loadClientData(client.id);
     } catch (error) {
       if (isApiMutationError(error)) {
@@ -487,12 +598,7 @@ export function ClientBootstrap() {
     setIsAttachmentSubmitting(true);
     setAttachmentErrors({});
 
-    const body = new URLSearchParams();
-    const formData = new FormData(form);
-
-    for (const [key, value] of formData.entries()) {
-      body.append(key, String(value));
-    }
+    const body = attachmentFormBody(new FormData(form));
 
     try {
       const result = await postForm(`/client/${client.id}/attachment`, body);
@@ -591,7 +697,10 @@ export function ClientBootstrap() {
                   href="#clientModal"
                   data-bs-toggle="modal"
                 >
-                  {client.phone ?? "Телефон"}
+                  {client.phoneDisplay ??
+                    client.phone ??
+                    client.rawPhone ??
+                    "Телефон"}
                 </a>
               </div>
             </div>
@@ -682,22 +791,31 @@ export function ClientBootstrap() {
                 <div className="col">
                   <span className="fw-bold">Документы:</span>
                   <ul>
-                    {clientState.data.documents.map((document) => (
-                      <li key={document.id}>
-                        <a
-                          className="link-warning link-offset-2 link-offset-2-hover link-underline link-underline-opacity-0 link-underline-opacity-75-hover"
-                          href={
-                            typeof document.eventData.url === "string"
-                              ? document.eventData.url
-                              : "#"
-                          }
-                        >
-                          {typeof document.eventData.text === "string"
-                            ? document.eventData.text
-                            : "Документ"}
-                        </a>
-                      </li>
-                    ))}
+                    {clientState.data.documents.map((document) => {
+                      const metadata = formatDocumentMetadata(
+                        document.eventData,
+                      );
+
+                      return (
+                        <li key={document.id}>
+                          <a
+                            className="link-warning link-offset-2 link-offset-2-hover link-underline link-underline-opacity-0 link-underline-opacity-75-hover"
+                            href={
+                              typeof document.eventData.url === "string"
+                                ? document.eventData.url
+                                : "#"
+                            }
+                          >
+                            {typeof document.eventData.text === "string"
+                              ? document.eventData.text
+                              : "Документ"}
+                          </a>
+                          {metadata ? (
+                            <span className="text-muted"> ({metadata})</span>
+                          ) : null}
+                        </li>
+                      );
+                    })}
                   </ul>
                 </div>
               </div>
@@ -793,9 +911,83 @@ export function ClientBootstrap() {
                       >
                         <option value="Comment">Комментарий</option>
                         <option value="Email">Email</option>
+                        <option value="Other">Другое</option>
                       </select>
                     </div>
                   </div>
+                  {eventType === "Email" ? (
+                    <div className="row">
+                      <div className="col">
+                        <input
+                          id="event-form-attachment"
+                          type="file"
+                          name="attachment"
+                          className={
+                            commentErrors.attachment
+                              ? "form-control form-control-sm mt-1 is-invalid"
+                              : "form-control form-control-sm mt-1"
+                          }
+                          accept=".pdf,.doc,.docx,.xls,.xlsx,.zip,.jpg,.jpeg,.png,.gif,.csv,.txt"
+                          onChange={() => clearCommentError("attachment")}
+                        />
+                        {commentErrors.attachment ? (
+                          <div className="invalid-feedback d-block">
+                            {commentErrors.attachment}
+                          </div>
+                        ) : null}
+                        <input
+                          id="event-form-attachment-url"
+                          type="url"
+                          name="attachment_url"
+                          className={
+                            commentErrors.attachment_url
+                              ? "form-control form-control-sm mt-1 is-invalid"
+                              : "form-control form-control-sm mt-1"
+                          }
+                          placeholder="или ссылка на документ"
+                          value={attachmentUrl}
+                          onChange={(event) => {
+                            setAttachmentUrl(event.target.value);
+                            clearCommentError("attachment_url");
+                          }}
+                        />
+                        {commentErrors.attachment_url ? (
+                          <div className="invalid-feedback d-block">
+                            {commentErrors.attachment_url}
+                          </div>
+                        ) : null}
+                      </div>
+                    </div>
+                  ) : null}
+                  {eventType === "Other" ? (
+                    <div className="row">
+                      <div className="col">
+                        <input
+                          id="event-form-event-subtype"
+                          type="text"
+                          name="event_subtype"
+                          className={
+                            commentErrors.event_subtype
+                              ? "form-control form-control-sm mt-1 is-invalid"
+                              : "form-control form-control-sm mt-1"
+                          }
+                          placeholder="Тип события"
+                          maxLength={64}
+                          required
+                          value={eventSubtype}
+                          onChange={(event) => {
+                            setEventSubtype(event.target.value);
+                            clearCommentError("event_subtype");
+                          }}
+                        />
+                        {commentErrors.event_subtype ? (
+                          <div className="invalid-feedback d-block">
+                            {commentErrors.event_subtype}
+                          </div>
+                        ) : null}
+                      </div>
+                    </div>
+                  ) : null}
                   <div className="row">
                     <div className="col">
                       <button
@@ -818,7 +1010,12 @@ export function ClientBootstrap() {
                 >
                   <div className="card-body">
                     <div className="d-flex justify-content-between small text-muted mb-2">
-                      <span>{event.createdAt}</span>
+                      <span>
+                        {formatEventDate(
+                          event.createdAt,
+                          clientState.data.locale,
+                        )}
+                      </span>
                       <span>
                         <a
                           className="text-nowrap link-offset-2 link-offset-2-hover link-underline link-underline-opacity-0 link-underline-opacity-75-hover link-dark"
@@ -875,6 +1072,7 @@ export function ClientBootstrap() {
                 key={`save-${clientFormVersion}`}
                 onSubmit={(event) => void handleSaveSubmit(event)}
               >
+                <input type="hidden" name="version" value={client.version} />
                 <div className="row mb-3">
                   <label
                     htmlFor="clientModalName"
@@ -1098,6 +1296,58 @@ export function ClientBootstrap() {
                     </div>
                   </div>
                 </div>
+                <div className="row mb-3">
+                  <div className="col-md">
+                    <select
+                      name="mime"
+                      className={
+                        attachmentErrors.mime
+                          ? "form-select my-1 is-invalid"
+                          : "form-select my-1"
+                      }
+                      defaultValue=""
+                      onChange={() => clearAttachmentError("mime")}
+                    >
+                      <option value="">Не указан</option>
+                      {DOCUMENT_MIME_TYPES.map(([mime, label]) => (
+                        <option value={mime} key={mime}>
+                          {label}
+                        </option>
+                      ))}
+                    </select>
+                    {attachmentErrors.mime ? (
+                      <div className="invalid-feedback d-block">
+                        {attachmentErrors.mime}
+                      </div>
+                    ) : null}
+                    <div className="form-text text-muted">
+                      Тип документа
+                    </div>
+                  </div>
+                  <div className="col-md">
+                    <input
+                      type="number"
+                      name="size_bytes"
+                      min={0}
+                      step={1}
+                      className={
+                        attachmentErrors.size_bytes
+                          ? "form-control my-1 is-invalid"
+                          : "form-control my-1"
+                      }
+                      placeholder="Размер в байтах"
+                      onChange={() => clearAttachmentError("size_bytes")}
+                    />
+                    {attachmentErrors.size_bytes ? (
+                      <div className="invalid-feedback d-block">
+                        {attachmentErrors.size_bytes}
+                      </div>
+                    ) : null}
+                    <div className="form-text text-muted">
+                      Размер документа в байтах
+                    </div>
+                  </div>
+                </div>
                 <div className="row mb-3">
                   <div className="col">
                     <button
@@ -1117,7 +1367,11 @@ export function ClientBootstrap() {
 
       <datalist id="available-custom-fields">
         {clientState.data.availableFields.map((field) => (
-          <option value={field} key={field} />
+          <option
+            value={field.name}
+            label={`${field.name} (${field.valueCount})`}
+            key={field.name}
+          />
         ))}
       </datalist>
     </CrmShell>
@@ -6,19 +6,55 @@ import { CrmShellFatalState } from "../components/CrmShellFatalState";
 import {
   fetchClientDirectoryData,
   fetchHubMenuItems,
+  fetchImportJob,
   fetchShellData,
   isApiMutationError,
   postForm,
   postMultipartForm,
   toFieldErrorMap,
 } from "../lib/api";
+import { isRecord } from "@pushkind/frontend-shell/json";
 import type {
   ClientDirectoryData,
+  ImportJob,
   ShellData,
   UserMenuItem,
 } from "../lib/models";
 import { useServiceShell } from "@pushkind/frontend-shell/useServiceShell";
 
+const IMPORT_POLL_INTERVAL_MS = 1000;
+
+async function waitForImportJob(jobId: number): Promise<ImportJob> {
+  for (;;) {
+    const job = await fetchImportJob(jobId);
+    if (job.status === "completed" || job.status === "failed") {
+      return job;
+    }
+    await new Promise((resolve) =>
+      window.setTimeout(resolve, IMPORT_POLL_INTERVAL_MS),
+    );
+  }
+}
+
+// Builds a client list URL keeping the current search and unassigned filter.
+function directoryHref(
+  data: ClientDirectoryData,
+  overrides: { page?: number; unassigned?: boolean },
+) {
+  const params = new URLSearchParams();
+  if (overrides.page !== undefined) {
+    params.set("page", String(overrides.page));
+  }
+  if (data.searchQuery) {
+    params.set("search", data.searchQuery);
+  }
+  if (overrides.unassigned ?? data.unassigned) {
+    params.set("unassigned", "true");
+  }
+  const query = params.toString();
+  return query ? `/?${query}` : "/";
+}
+
 type DashboardState =
   | { status: "loading" }
   | { status: "ready"; data: ClientDirectoryData }
@@ -39,6 +75,9 @@ export function DashboardBootstrap() {
     Record<string, string>
   >({});
   const [isAddClientSubmitting, setIsAddClientSubmitting] = useState(false);
+  const [addClientToken, setAddClientToken] = useState(() =>
+    crypto.randomUUID(),
+  );
   const [uploadError, setUploadError] = useState<string | null>(null);
   const [isUploadSubmitting, setIsUploadSubmitting] = useState(false);
   const canAddClient =
@@ -108,8 +147,13 @@ export function DashboardBootstrap() {
     try {
       const result = await postForm("/client/add", body);
       window.showFlashMessage?.(result.message, "success");
+      const warning = isRecord(result) ? result.warning : undefined;
+      if (typeof warning === "string") {
+        window.showFlashMessage?.(warning, "warning");
+      }
       window.bootstrap?.Modal.getOrCreateInstance("#clientModal", {}).hide();
       form.reset();
+      setAddClientToken(crypto.randomUUID());
       try {
         await loadDashboard();
       } catch (error) {
@@ -146,6 +190,24 @@ export function DashboardBootstrap() {
       );
       window.showFlashMessage?.(result.message, "success");
       form.reset();
+
+      const jobId = isRecord(result) ? result.job_id : undefined;
+      if (typeof jobId === "number") {
+        const job = await waitForImportJob(jobId);
+        if (job.status === "failed") {
+          const message = job.errorMessage ?? "Не удалось загрузить клиентов.";
+          setUploadError(message);
+          window.showFlashMessage?.(message, "danger");
+          return;
+        }
+        const skipped =
+          job.errorRows > 0 ? `, пропущено: ${job.errorRows}` : "";
+        window.showFlashMessage?.(
+          `Добавлено клиентов: ${job.insertedRows}, обновлено: ${job.updatedRows}${skipped}.`,
+          "success",
+        );
+      }
+
       try {
         await loadDashboard();
       } catch (error) {
@@ -197,6 +259,9 @@ export function DashboardBootstrap() {
               aria-label="Search"
               defaultValue={dashboardState.data.searchQuery ?? ""}
             />
+            {dashboardState.data.unassigned ? (
+              <input type="hidden" name="unassigned" value="true" />
+            ) : null}
             <button className="btn btn-outline-secondary" type="submit">
               <i className="bi bi-search" />
             </button>
@@ -221,6 +286,26 @@ export function DashboardBootstrap() {
             </div>
           ) : null}
 
+          {canAddClient ? (
+            <div className="row py-2">
+              <div className="col">
+                <a
+                  className={`btn btn-sm ${
+                    dashboardState.data.unassigned
+                      ? "btn-secondary"
+                      : "btn-outline-secondary"
+                  }`}
+                  aria-pressed={dashboardState.data.unassigned}
+                  href={directoryHref(dashboardState.data, {
+                    unassigned: !dashboardState.data.unassigned,
+                  })}
+                >
+                  Без менеджера
+                </a>
+              </div>
+            </div>
+          ) : null}
+
           <div className="row d-none d-sm-flex fw-bold">
             <div className="col overflow-hidden">Название</div>
             <div className="col overflow-hidden">Электронный адрес</div>
@@ -243,7 +328,7 @@ export function DashboardBootstrap() {
                     {client.email ?? "—"}
                   </div>
                   <div className="col-sm overflow-hidden">
-                    {client.phone ?? "—"}
+                    {client.phone ?? client.rawPhone ?? "—"}
                   </div>
                   <div className="col-sm overflow-hidden">
                     {client.fieldBadges.length > 0 ? (
@@ -276,13 +361,7 @@ export function DashboardBootstrap() {
                       <li className="page-item" key={`${page}-${index}`}>
                         <a
                           className="page-link"
-                          href={`/?page=${page}${
-                            dashboardState.data.searchQuery
-                              ? `&search=${encodeURIComponent(
-                                  dashboardState.data.searchQuery,
-                                )}`
-                              : ""
-                          }`}
+                          href={directoryHref(dashboardState.data, { page })}
                         >
                           {page}
                         </a>
@@ -330,6 +409,11 @@ export function DashboardBootstrap() {
                 </div>
                 <div className="modal-body">
                   <form onSubmit={(event) => void handleAddClientSubmit(event)}>
+                    <input
+                      type="hidden"
+                      name="idempotency_token"
+                      value={addClientToken}
+                    />
                     <div className="row mb-3">
                       <label
                         htmlFor="clientModalName"
@@ -452,6 +536,67 @@ export function DashboardBootstrap() {
                             </small>
                           </sup>
                         </div>
+                        <div className="form-check">
+                          <input
+                            className="form-check-input"
+                            type="checkbox"
+                            id="upload-allow-no-contact"
+                            name="require_contact"
+                            value="false"
+                          />
+                          <label
+                            className="form-check-label small"
+                            htmlFor="upload-allow-no-contact"
+                          >
+                            Загружать клиентов без email и телефона
+                          </label>
+                        </div>
+                        <select
+                          className="form-select form-select-sm mt-1"
+                          name="conflict_strategy"
+                          aria-label="Существующие клиенты"
+                          defaultValue="upsert"
+                        >
+                          <option value="upsert">
+                            Обновлять существующих клиентов
+                          </option>
+                          <option value="skip">
+                            Пропускать существующих клиентов
+                          </option>
+                          <option value="error">
+                            Отменять загрузку при совпадении
+                          </option>
+                        </select>
+                        <div className="form-check mt-1">
+                          <input
+                            className="form-check-input"
+                            type="checkbox"
+                            id="upload-match-by-name"
+                            name="match_by"
+                            value="name"
+                          />
+                          <label
+                            className="form-check-label small"
+                            htmlFor="upload-match-by-name"
+                          >
+                            Искать существующих клиентов по имени
+                          </label>
+                        </div>
+                        <div className="form-check">
+                          <input
+                            className="form-check-input"
+                            type="checkbox"
+                            id="upload-strict-headers"
+                            name="strict"
+                            value="true"
+                          />
+                          <label
+                            className="form-check-label small"
+                            htmlFor="upload-strict-headers"
+                          >
+                            Отклонять файл с неизвестными колонками
+                          </label>
+                        </div>
                       </div>
                       <div className="col-auto">
                         <button
@@ -0,0 +1,121 @@
+import { useEffect, useState } from "react";
+
+import { CrmShell } from "../components/CrmShell";
+import { CrmShellFatalState } from "../components/CrmShellFatalState";
+import {
+  fetchHubDashboardData,
+  fetchHubMenuItems,
+  fetchShellData,
+} from "../lib/api";
+import type { HubDashboardData, ShellData, UserMenuItem } from "../lib/models";
+import { useServiceShell } from "@pushkind/frontend-shell/useServiceShell";
+
+type HubDashboardState =
+  | { status: "loading" }
+  | { status: "ready"; data: HubDashboardData }
+  | { status: "error"; message: string };
+
+type StatCardProps = {
+  label: string;
+  value: number | string;
+};
+
+function StatCard({ label, value }: StatCardProps) {
+  return (
+    <div className="col-sm-6 col-lg-4">
+      <div className="card shadow-sm h-100">
+        <div className="card-body">
+          <div className="text-muted small">{label}</div>
+          <div className="fs-3 fw-semibold">{value}</div>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export function HubDashboardBootstrap() {
+  const shellState = useServiceShell<ShellData, UserMenuItem>({
+    errorMessage: "Не удалось загрузить React-оболочку CRM.",
+    menuLoadWarning:
+      "Failed to load auth navigation menu. Falling back to local CRM menu only.",
+    fetchShellData,
+    fetchHubMenuItems,
+  });
+  const [dashboardState, setDashboardState] = useState<HubDashboardState>({
+    status: "loading",
+  });
+
+  useEffect(() => {
+    let active = true;
+
+    void fetchHubDashboardData()
+      .then((data) => {
+        if (!active) {
+          return;
+        }
+
+        setDashboardState({ status: "ready", data });
+      })
+      .catch((error) => {
+        if (!active) {
+          return;
+        }
+
+        setDashboardState({
+          status: "error",
+          message:
+            error instanceof Error
+              ? error.message
+              : "Не удалось загрузить обзор хаба.",
+        });
+      });
+
+    return () => {
+      active = false;
+    };
+  }, []);
+
+  if (shellState.status === "loading" || dashboardState.status === "loading") {
+    return null;
+  }
+
+  if (shellState.status === "error") {
+    return <CrmShellFatalState message={shellState.message} />;
+  }
+
+  if (dashboardState.status === "error") {
+    return <CrmShellFatalState message={dashboardState.message} />;
+  }
+
+  const { data } = dashboardState;
+  const topManager = data.topManagerByEvents;
+
+  return (
+    <CrmShell
+      navigation={shellState.shell.navigation}
+      currentUserEmail={shellState.shell.currentUser.email}
+      homeUrl={shellState.shell.homeUrl}
+      localMenuItems={shellState.shell.localMenuItems}
+      fetchedMenuItems={shellState.authMenuItems}
+    >
+      <div className="container my-2">
+        <h1 className="h4 mb-3">Обзор</h1>
+        <div className="row g-3">
+          <StatCard label="Всего клиентов" value={data.totalClients} />
+          <StatCard
+            label="Активные клиенты (30 дней)"
+            value={data.activeClients}
+          />
+          <StatCard label="Без менеджера" value={data.unassignedCount} />
+          <StatCard label="Менеджеров" value={data.totalManagers} />
+          <StatCard label="Событий сегодня" value={data.eventsToday} />
+          <StatCard label="Событий за неделю" value={data.eventsThisWeek} />
+          <StatCard
+            label="Самый активный менеджер недели"
+            value={topManager ? topManager.name : "—"}
+          />
+        </div>
+      </div>
+    </CrmShell>
+  );
+}
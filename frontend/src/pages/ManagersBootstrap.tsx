@@ -16,6 +16,7 @@ import {
   fetchManagersData,
   fetchShellData,
   postForm,
+  postMultipartForm,
   toFieldErrorMap,
 } from "../lib/api";
 import type {
@@ -26,6 +27,7 @@ import type {
   ShellData,
   UserMenuItem,
 } from "../lib/models";
+import { isRecord } from "@pushkind/frontend-shell/json";
 import { useServiceShell } from "@pushkind/frontend-shell/useServiceShell";
 
 type ManagersState =
@@ -96,8 +98,15 @@ export function ManagersBootstrap() {
     Record<string, string>
   >({});
   const [assignErrors, setAssignErrors] = useState<Record<string, string>>({});
+  const [managerCompany, setManagerCompany] = useState("");
+  const [managerPhone, setManagerPhone] = useState("");
   const [isAddManagerSubmitting, setIsAddManagerSubmitting] = useState(false);
   const [isAssignSubmitting, setIsAssignSubmitting] = useState(false);
+  const [isAssignUploadSubmitting, setIsAssignUploadSubmitting] =
+    useState(false);
+  const [unresolvedAssignmentLines, setUnresolvedAssignmentLines] = useState<
+    number[]
+  >([]);
   const managerModalRequestId = useRef(0);
 
   const loadManagers = async () => {
@@ -256,12 +265,16 @@ export function ManagersBootstrap() {
     const body = new URLSearchParams();
     body.set("name", selectedUser?.name ?? "");
     body.set("email", selectedUser?.email ?? "");
+    body.set("company", managerCompany);
+    body.set("phone", managerPhone);
 
     try {
       const result = await postForm("/managers/add", body);
       window.showFlashMessage?.(result.message, "success");
       setSelectedUser(null);
       setManagerQuery("");
+      setManagerCompany("");
+      setManagerPhone("");
       setManagerSearchState({ status: "idle", items: [] });
       await loadManagers();
     } catch (error) {
@@ -312,6 +325,45 @@ export function ManagersBootstrap() {
     }
   }
 
+  async function handleAssignUploadSubmit(event: FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    const form = event.currentTarget;
+    setIsAssignUploadSubmitting(true);
+    setUnresolvedAssignmentLines([]);
+
+    try {
+      const result = await postMultipartForm(
+        "/managers/assign-upload",
+        new FormData(form),
+      );
+      const unresolved = isRecord(result) ? result.unresolved : undefined;
+      const lines = Array.isArray(unresolved)
+        ? unresolved
+            .map((row) => (isRecord(row) ? row.line : undefined))
+            .filter((line): line is number => typeof line === "number")
+        : [];
+      setUnresolvedAssignmentLines(lines);
+      window.showFlashMessage?.(
+        result.message,
+        lines.length > 0 ? "warning" : "success",
+      );
+      form.reset();
+      await loadManagers();
+    } catch (error) {
+      if (isApiMutationError(error)) {
+        window.showFlashMessage?.(error.message, "danger");
+      } else {
+        console.error("Failed to upload manager assignments.", error);
+        window.showFlashMessage?.(
+          "Не удалось загрузить назначения менеджеров.",
+          "danger",
+        );
+      }
+    } finally {
+      setIsAssignUploadSubmitting(false);
+    }
+  }
+
   function handleSelectedClientsChange(values: string[]) {
     const nextSelectedClientIds = values.map(Number);
     const nextSelectedClientIdSet = new Set(nextSelectedClientIds);
@@ -431,6 +483,30 @@ export function ManagersBootstrap() {
                     </div>
                   ) : null}
                 </div>
+                <div className="col">
+                  <input
+                    className="form-control my-1"
+                    name="company"
+                    placeholder="Компания"
+                    value={managerCompany}
+                    onChange={(event) => setManagerCompany(event.target.value)}
+                  />
+                </div>
+                <div className="col">
+                  <input
+                    className="form-control my-1"
+                    name="phone"
+                    type="tel"
+                    placeholder="Телефон"
+                    value={managerPhone}
+                    onChange={(event) => setManagerPhone(event.target.value)}
+                  />
+                  {addManagerErrors.phone ? (
+                    <div className="text-danger small mt-1">
+                      {addManagerErrors.phone}
+                    </div>
+                  ) : null}
+                </div>
                 <div className="col-auto">
                   <button
                     className="btn btn-primary my-1"
@@ -442,6 +518,41 @@ export function ManagersBootstrap() {
                 </div>
               </div>
             </form>
+            <form
+              className="row"
+              onSubmit={(event) => void handleAssignUploadSubmit(event)}
+            >
+              <div className="col">
+                <input
+                  className="form-control my-1"
+                  type="file"
+                  name="csv"
+                  accept=".csv"
+                  aria-label="Назначения из csv"
+                  required
+                />
+                <sup>
+                  <small className="text-muted">
+                    "client_email","manager_email"
+                  </small>
+                </sup>
+                {unresolvedAssignmentLines.length > 0 ? (
+                  <div className="text-danger small">
+                    Не найдены клиент или менеджер в строках:{" "}
+                    {unresolvedAssignmentLines.join(", ")}
+                  </div>
+                ) : null}
+              </div>
+              <div className="col-auto">
+                <button
+                  className="btn btn-success my-1"
+                  type="submit"
+                  disabled={isAssignUploadSubmitting}
+                >
+                  Назначить из csv
+                </button>
+              </div>
+            </form>
           </div>
         </div>
       </div>
@@ -450,6 +561,8 @@ export function ManagersBootstrap() {
         <div className="row mb-3 fw-bold pt-3">
           <div className="col overflow-hidden">Имя</div>
           <div className="col overflow-hidden">Email</div>
+          <div className="col overflow-hidden">Компания</div>
+          <div className="col overflow-hidden">Телефон</div>
           <div className="col overflow-hidden">Клиенты</div>
         </div>
 
@@ -464,6 +577,8 @@ export function ManagersBootstrap() {
           >
             <div className="col overflow-hidden">{manager.name}</div>
             <div className="col overflow-hidden">{manager.email}</div>
+            <div className="col overflow-hidden">{manager.company ?? "—"}</div>
+            <div className="col overflow-hidden">{manager.phone ?? "—"}</div>
             <div className="col overflow-hidden">
               {clients.map((client) => (
                 <span
@@ -5,6 +5,7 @@ import { CrmShell } from "../components/CrmShell";
 import { CrmShellFatalState } from "../components/CrmShellFatalState";
 import {
   fetchHubMenuItems,
+  fetchHubSettings,
   fetchImportantFieldSettingsData,
   fetchShellData,
   isApiMutationError,
@@ -13,6 +14,7 @@ import {
   toFieldErrorMap,
 } from "../lib/api";
 import type {
+  HubSettings,
   ImportantFieldSettingsData,
   ShellData,
   UserMenuItem,
@@ -21,7 +23,11 @@ import { useServiceShell } from "@pushkind/frontend-shell/useServiceShell";
 
 type SettingsState =
   | { status: "loading" }
-  | { status: "ready"; data: ImportantFieldSettingsData }
+  | {
+      status: "ready";
+      data: ImportantFieldSettingsData;
+      hubSettings: HubSettings;
+    }
   | { status: "error"; message: string };
 
 export function SettingsBootstrap() {
@@ -39,18 +45,40 @@ export function SettingsBootstrap() {
   const [fieldErrors, setFieldErrors] = useState<Record<string, string>>({});
   const [isSaving, setIsSaving] = useState(false);
   const [isCleaning, setIsCleaning] = useState(false);
+  const [deletingField, setDeletingField] = useState<string | null>(null);
+  const [itemsPerPage, setItemsPerPage] = useState("");
+  const [emailSenderName, setEmailSenderName] = useState("");
+  const [emailReplyTo, setEmailReplyTo] = useState("");
+  const [allowManagerSelfAssign, setAllowManagerSelfAssign] = useState(false);
+  const [defaultPhoneRegion, setDefaultPhoneRegion] = useState("");
+  const [eventRetentionDays, setEventRetentionDays] = useState("");
+  const [isCleaningEvents, setIsCleaningEvents] = useState(false);
+  const [hubSettingsErrors, setHubSettingsErrors] = useState<
+    Record<string, string>
+  >({});
+  const [isSavingHubSettings, setIsSavingHubSettings] = useState(false);
 
   useEffect(() => {
     let active = true;
 
-    void fetchImportantFieldSettingsData()
-      .then((data) => {
+    void Promise.all([fetchImportantFieldSettingsData(), fetchHubSettings()])
+      .then(([data, hubSettings]) => {
         if (!active) {
           return;
         }
 
-        setSettingsState({ status: "ready", data });
+        setSettingsState({ status: "ready", data, hubSettings });
         setFieldsText(data.fieldsText);
+        setItemsPerPage(String(hubSettings.itemsPerPage));
+        setEmailSenderName(hubSettings.emailSenderName ?? "");
+        setEmailReplyTo(hubSettings.emailReplyTo ?? "");
+        setAllowManagerSelfAssign(hubSettings.allowManagerSelfAssign);
+        setDefaultPhoneRegion(hubSettings.defaultPhoneRegion ?? "");
+        setEventRetentionDays(
+          hubSettings.eventRetentionDays == null
+            ? ""
+            : String(hubSettings.eventRetentionDays),
+        );
       })
       .catch((error) => {
         if (!active) {
@@ -83,6 +111,11 @@ export function SettingsBootstrap() {
     return <CrmShellFatalState message={settingsState.message} />;
   }
 
+  const savedFields = settingsState.data.fieldsText
+    .split("\n")
+    .map((line) => line.trim())
+    .filter((line) => line !== "");
+
   async function handleSave(event: FormEvent<HTMLFormElement>) {
     event.preventDefault();
     setIsSaving(true);
@@ -93,7 +126,11 @@ export function SettingsBootstrap() {
 
     try {
       const result = await postForm("/important-fields", body);
-      setSettingsState({ status: "ready", data: { fieldsText } });
+      setSettingsState({
+        status: "ready",
+        data: { fieldsText },
+        hubSettings: settingsState.hubSettings,
+      });
       window.showFlashMessage?.(result.message, "success");
     } catch (error) {
       if (isApiMutationError(error)) {
@@ -111,6 +148,79 @@ export function SettingsBootstrap() {
     }
   }
 
+  async function handleFieldDelete(field: string) {
+    if (settingsState.status !== "ready") {
+      return;
+    }
+    if (!window.confirm(`Удалить поле «${field}»?`)) {
+      return;
+    }
+
+    setDeletingField(field);
+
+    const body = new URLSearchParams();
+    body.set("field", field);
+
+    try {
+      const result = await postForm("/important-fields/delete", body);
+      const removeField = (text: string) =>
+        text
+          .split("\n")
+          .filter((line) => line.trim() !== field)
+          .join("\n");
+      setSettingsState({
+        status: "ready",
+        data: { fieldsText: removeField(settingsState.data.fieldsText) },
+        hubSettings: settingsState.hubSettings,
+      });
+      setFieldsText(removeField);
+      window.showFlashMessage?.(result.message, "success");
+    } catch (error) {
+      if (isApiMutationError(error)) {
+        window.showFlashMessage?.(error.message, "danger");
+      } else {
+        console.error("Failed to delete important field.", error);
+        window.showFlashMessage?.("Не удалось удалить поле.", "danger");
+      }
+    } finally {
+      setDeletingField(null);
+    }
+  }
+
+  async function handleHubSettingsSave(event: FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    setIsSavingHubSettings(true);
+    setHubSettingsErrors({});
+
+    const body = new URLSearchParams();
+    body.set("items_per_page", itemsPerPage);
+    body.set("email_sender_name", emailSenderName);
+    body.set("email_reply_to", emailReplyTo);
+    body.set("default_phone_region", defaultPhoneRegion);
+    body.set("event_retention_days", eventRetentionDays);
+    if (allowManagerSelfAssign) {
+      body.set("allow_manager_self_assign", "true");
+    }
+
+    try {
+      const result = await postForm("/settings", body);
+      window.showFlashMessage?.(result.message, "success");
+    } catch (error) {
+      if (isApiMutationError(error)) {
+        setHubSettingsErrors(toFieldErrorMap(error));
+        window.showFlashMessage?.(error.message, "danger");
+      } else {
+        console.error("Failed to save hub settings.", error);
+        window.showFlashMessage?.(
+          "Не удалось сохранить настройки хаба.",
+          "danger",
+        );
+      }
+    } finally {
+      setIsSavingHubSettings(false);
+    }
+  }
+
   async function handleCleanup() {
     if (!window.confirm("Удалить всех клиентов и связанные записи?")) {
       return;
@@ -133,6 +243,31 @@ export function SettingsBootstrap() {
     }
   }
 
+  async function handleEventCleanup() {
+    if (!window.confirm("Удалить события старше срока хранения?")) {
+      return;
+    }
+
+    setIsCleaningEvents(true);
+
+    try {
+      const result = await postEmpty("/admin/cleanup-events");
+      window.showFlashMessage?.(result.message, "success");
+    } catch (error) {
+      if (isApiMutationError(error)) {
+        window.showFlashMessage?.(error.message, "danger");
+      } else {
+        console.error("Failed to clean up old client events.", error);
+        window.showFlashMessage?.(
+          "Не удалось удалить старые события.",
+          "danger",
+        );
+      }
+    } finally {
+      setIsCleaningEvents(false);
+    }
+  }
+
   return (
     <CrmShell
       navigation={shellState.shell.navigation}
@@ -192,6 +327,191 @@ export function SettingsBootstrap() {
                     Сохранить
                   </button>
                 </form>
+                {savedFields.length > 0 ? (
+                  <ul className="list-group mt-3">
+                    {savedFields.map((field) => (
+                      <li
+                        key={field}
+                        className="list-group-item d-flex justify-content-between align-items-center"
+                      >
+                        {field}
+                        <button
+                          type="button"
+                          className="btn btn-sm btn-outline-danger"
+                          onClick={() => void handleFieldDelete(field)}
+                          disabled={deletingField !== null}
+                        >
+                          Удалить
+                        </button>
+                      </li>
+                    ))}
+                  </ul>
+                ) : null}
+                <hr className="my-4" />
+                <h2 className="h5 mb-3">Настройки хаба</h2>
+                <form onSubmit={(event) => void handleHubSettingsSave(event)}>
+                  <div className="mb-3">
+                    <label htmlFor="hub-items-per-page" className="form-label">
+                      Клиентов на странице
+                    </label>
+                    <input
+                      type="number"
+                      min={1}
+                      max={100}
+                      className={
+                        hubSettingsErrors.items_per_page
+                          ? "form-control is-invalid"
+                          : "form-control"
+                      }
+                      id="hub-items-per-page"
+                      name="items_per_page"
+                      value={itemsPerPage}
+                      onChange={(event) => setItemsPerPage(event.target.value)}
+                    />
+                    {hubSettingsErrors.items_per_page ? (
+                      <div className="invalid-feedback">
+                        {hubSettingsErrors.items_per_page}
+                      </div>
+                    ) : null}
+                  </div>
+                  <div className="mb-3">
+                    <label
+                      htmlFor="hub-email-sender-name"
+                      className="form-label"
+                    >
+                      Имя отправителя писем
+                    </label>
+                    <input
+                      type="text"
+                      className={
+                        hubSettingsErrors.email_sender_name
+                          ? "form-control is-invalid"
+                          : "form-control"
+                      }
+                      id="hub-email-sender-name"
+                      name="email_sender_name"
+                      value={emailSenderName}
+                      onChange={(event) =>
+                        setEmailSenderName(event.target.value)
+                      }
+                    />
+                    {hubSettingsErrors.email_sender_name ? (
+                      <div className="invalid-feedback">
+                        {hubSettingsErrors.email_sender_name}
+                      </div>
+                    ) : null}
+                  </div>
+                  <div className="mb-3">
+                    <label htmlFor="hub-email-reply-to" className="form-label">
+                      Адрес для ответов
+                    </label>
+                    <input
+                      type="email"
+                      className={
+                        hubSettingsErrors.email_reply_to
+                          ? "form-control is-invalid"
+                          : "form-control"
+                      }
+                      id="hub-email-reply-to"
+                      name="email_reply_to"
+                      value={emailReplyTo}
+                      onChange={(event) => setEmailReplyTo(event.target.value)}
+                    />
+                    {hubSettingsErrors.email_reply_to ? (
+                      <div className="invalid-feedback">
+                        {hubSettingsErrors.email_reply_to}
+                      </div>
+                    ) : null}
+                  </div>
+                  <div className="mb-3">
+                    <label
+                      htmlFor="hub-default-phone-region"
+                      className="form-label"
+                    >
+                      Страна телефонов по умолчанию
+                    </label>
+                    <input
+                      type="text"
+                      className={
+                        hubSettingsErrors.default_phone_region
+                          ? "form-control is-invalid"
+                          : "form-control"
+                      }
+                      id="hub-default-phone-region"
+                      name="default_phone_region"
+                      maxLength={2}
+                      placeholder="RU"
+                      value={defaultPhoneRegion}
+                      onChange={(event) =>
+                        setDefaultPhoneRegion(event.target.value)
+                      }
+                    />
+                    <div className="form-text">
+                      Код ISO 3166-1 для номеров без кода страны.
+                    </div>
+                    {hubSettingsErrors.default_phone_region ? (
+                      <div className="invalid-feedback">
+                        {hubSettingsErrors.default_phone_region}
+                      </div>
+                    ) : null}
+                  </div>
+                  <div className="mb-3">
+                    <label
+                      htmlFor="hub-event-retention-days"
+                      className="form-label"
+                    >
+                      Срок хранения событий, дней
+                    </label>
+                    <input
+                      type="number"
+                      className={
+                        hubSettingsErrors.event_retention_days
+                          ? "form-control is-invalid"
+                          : "form-control"
+                      }
+                      id="hub-event-retention-days"
+                      name="event_retention_days"
+                      min={1}
+                      value={eventRetentionDays}
+                      onChange={(event) =>
+                        setEventRetentionDays(event.target.value)
+                      }
+                    />
+                    <div className="form-text">
+                      Оставьте пустым, чтобы хранить события бессрочно.
+                    </div>
+                    {hubSettingsErrors.event_retention_days ? (
+                      <div className="invalid-feedback">
+                        {hubSettingsErrors.event_retention_days}
+                      </div>
+                    ) : null}
+                  </div>
+                  <div className="form-check mb-3">
+                    <input
+                      type="checkbox"
+                      className="form-check-input"
+                      id="hub-allow-manager-self-assign"
+                      name="allow_manager_self_assign"
+                      checked={allowManagerSelfAssign}
+                      onChange={(event) =>
+                        setAllowManagerSelfAssign(event.target.checked)
+                      }
+                    />
+                    <label
+                      htmlFor="hub-allow-manager-self-assign"
+                      className="form-check-label"
+                    >
+                      Менеджеры могут назначать себе клиентов
+                    </label>
+                  </div>
+                  <button
+                    type="submit"
+                    className="btn btn-primary"
+                    disabled={isSavingHubSettings}
+                  >
+                    Сохранить настройки
+                  </button>
+                </form>
                 <hr className="my-4" />
                 <div>
                   <button
@@ -202,6 +522,14 @@ export function SettingsBootstrap() {
                   >
                     Удалить клиентов
                   </button>
+                  <button
+                    type="button"
+                    className="btn btn-outline-danger ms-2"
+                    onClick={() => void handleEventCleanup()}
+                    disabled={isCleaningEvents}
+                  >
+                    Удалить старые события
+                  </button>
                 </div>
               </div>
             </div>
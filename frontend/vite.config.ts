@@ -32,6 +32,7 @@ export default defineConfig({
     rollupOptions: {
       input: {
         "app/client.html": resolve(__dirname, "app/client.html"),
+        "app/hub-dashboard.html": resolve(__dirname, "app/hub-dashboard.html"),
         "app/index.html": resolve(__dirname, "app/index.html"),
         "app/managers.html": resolve(__dirname, "app/managers.html"),
         "app/no-access.html": resolve(__dirname, "app/no-access.html"),
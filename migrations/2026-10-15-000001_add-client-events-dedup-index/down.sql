@@ -0,0 +1 @@
+DROP INDEX client_events_client_id_manager_id_event_type_idx;
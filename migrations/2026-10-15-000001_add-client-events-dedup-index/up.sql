@@ -0,0 +1 @@
+CREATE INDEX client_events_client_id_manager_id_event_type_idx ON client_events (client_id, manager_id, event_type);
@@ -0,0 +1,2 @@
+DROP INDEX client_notes_client_id_created_at_idx;
+DROP TABLE client_notes;
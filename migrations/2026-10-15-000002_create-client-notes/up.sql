@@ -0,0 +1,10 @@
+CREATE TABLE client_notes (
+    id INTEGER NOT NULL PRIMARY KEY,
+    client_id INTEGER NOT NULL REFERENCES clients(id),
+    manager_id INTEGER NOT NULL REFERENCES managers(id),
+    text TEXT NOT NULL,
+    is_pinned BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX client_notes_client_id_created_at_idx ON client_notes (client_id, created_at DESC);
@@ -0,0 +1 @@
+DROP TABLE hub_limits;
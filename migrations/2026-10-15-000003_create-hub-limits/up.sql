@@ -0,0 +1,4 @@
+CREATE TABLE hub_limits (
+    hub_id INTEGER NOT NULL PRIMARY KEY,
+    max_clients INTEGER NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE hub_contact_policies (
+    hub_id INTEGER NOT NULL PRIMARY KEY,
+    require_email BOOLEAN NOT NULL DEFAULT 0,
+    require_phone BOOLEAN NOT NULL DEFAULT 0
+);
@@ -0,0 +1,3 @@
+DROP INDEX clients_last_edited_by_idx;
+ALTER TABLE clients DROP COLUMN last_edited_at;
+ALTER TABLE clients DROP COLUMN last_edited_by;
@@ -0,0 +1,3 @@
+ALTER TABLE clients ADD COLUMN last_edited_by INTEGER REFERENCES managers(id);
+ALTER TABLE clients ADD COLUMN last_edited_at TIMESTAMP;
+CREATE INDEX clients_last_edited_by_idx ON clients (last_edited_by, last_edited_at);
@@ -0,0 +1,12 @@
+CREATE TABLE audit_log (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    actor_email TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    diff TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX audit_log_entity_idx ON audit_log (hub_id, entity_type, entity_id, created_at);
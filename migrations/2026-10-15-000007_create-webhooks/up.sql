@@ -0,0 +1,10 @@
+CREATE TABLE webhooks (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    event_type TEXT,
+    secret TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX webhooks_hub_id_idx ON webhooks (hub_id);
@@ -0,0 +1,10 @@
+CREATE TABLE client_segments (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    filter_json TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX client_segments_hub_id_idx ON client_segments (hub_id);
@@ -0,0 +1,2 @@
+-- Lowercasing client emails is not reversible.
+SELECT 1;
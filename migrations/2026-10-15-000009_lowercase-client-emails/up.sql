@@ -0,0 +1,14 @@
+-- Normalize legacy client emails stored before `ClientEmail` lowercased input.
+-- Rows whose lowercased email would collide with another client of the same hub
+-- are left untouched so they can be merged manually.
+UPDATE clients
+SET email = lower(email)
+WHERE email IS NOT NULL
+  AND email <> lower(email)
+  AND NOT EXISTS (
+    SELECT 1
+    FROM clients AS other
+    WHERE other.hub_id = clients.hub_id
+      AND other.id <> clients.id
+      AND lower(other.email) = lower(clients.email)
+  );
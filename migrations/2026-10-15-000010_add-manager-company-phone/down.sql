@@ -0,0 +1,2 @@
+ALTER TABLE managers DROP COLUMN phone;
+ALTER TABLE managers DROP COLUMN company;
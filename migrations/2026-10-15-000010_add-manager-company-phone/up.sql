@@ -0,0 +1,2 @@
+ALTER TABLE managers ADD COLUMN company VARCHAR;
+ALTER TABLE managers ADD COLUMN phone VARCHAR;
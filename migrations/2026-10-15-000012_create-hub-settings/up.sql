@@ -0,0 +1,6 @@
+CREATE TABLE hub_settings (
+    hub_id INTEGER NOT NULL PRIMARY KEY,
+    items_per_page INTEGER NOT NULL,
+    email_sender_name TEXT,
+    allow_manager_self_assign BOOLEAN NOT NULL DEFAULT 0
+);
@@ -0,0 +1,2 @@
+DROP INDEX idx_clients_hub_email;
+DROP INDEX idx_clients_hub_updated;
@@ -0,0 +1,6 @@
+-- idx_clients_hub_updated serves hub client lists sorted by `updated_at`
+-- (`ClientSort::UpdatedAsc` / `UpdatedDesc`).
+CREATE INDEX idx_clients_hub_updated ON clients (hub_id, updated_at);
+-- idx_clients_hub_email serves hub-scoped email lookups (`get_client_by_email`,
+-- exact email filters) without relying on the uniqueness index.
+CREATE INDEX idx_clients_hub_email ON clients (hub_id, email);
@@ -0,0 +1,2 @@
+-- Archived managers stay in history but are hidden from assignment lists.
+ALTER TABLE managers ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT 1;
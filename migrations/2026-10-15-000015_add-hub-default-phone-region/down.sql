@@ -0,0 +1 @@
+ALTER TABLE hub_settings DROP COLUMN default_phone_region;
@@ -0,0 +1,2 @@
+-- ISO 3166-1 alpha-2 region for phone numbers entered without a country code.
+ALTER TABLE hub_settings ADD COLUMN default_phone_region TEXT;
@@ -0,0 +1,2 @@
+-- Archived clients are hidden from client lists unless explicitly requested.
+ALTER TABLE clients ADD COLUMN status TEXT NOT NULL DEFAULT 'Active';
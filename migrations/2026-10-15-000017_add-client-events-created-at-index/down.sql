@@ -0,0 +1 @@
+DROP INDEX client_events_created_at_idx;
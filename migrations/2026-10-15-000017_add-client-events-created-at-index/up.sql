@@ -0,0 +1,2 @@
+-- Keeps the service-wide recent events count used by /metrics cheap.
+CREATE INDEX client_events_created_at_idx ON client_events (created_at);
@@ -0,0 +1,11 @@
+-- Keys authenticating server-to-server calls to the `/api` scope via `X-API-Key`.
+CREATE TABLE api_keys (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    -- Hex-encoded SHA-256 of the key; the key itself is never stored.
+    key_hash TEXT NOT NULL UNIQUE,
+    -- Comma-separated roles granted to requests using the key.
+    roles TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1 @@
+ALTER TABLE hub_settings DROP COLUMN event_retention_days;
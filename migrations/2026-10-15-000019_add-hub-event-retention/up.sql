@@ -0,0 +1,2 @@
+-- Days client events are kept before the cleanup removes them; NULL keeps them forever.
+ALTER TABLE hub_settings ADD COLUMN event_retention_days INTEGER;
@@ -0,0 +1,2 @@
+-- Rows that matched an existing client; inserted_rows now counts only new clients.
+ALTER TABLE import_jobs ADD COLUMN updated_rows INTEGER NOT NULL DEFAULT 0;
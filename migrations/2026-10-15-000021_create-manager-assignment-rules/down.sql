@@ -0,0 +1 @@
+DROP TABLE manager_assignment_rules;
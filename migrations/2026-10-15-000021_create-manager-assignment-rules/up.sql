@@ -0,0 +1,11 @@
+CREATE TABLE manager_assignment_rules (
+    id INTEGER NOT NULL PRIMARY KEY,
+    hub_id INTEGER NOT NULL,
+    manager_id INTEGER NOT NULL REFERENCES managers(id) ON DELETE CASCADE,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX manager_assignment_rules_hub_field_value_idx
+    ON manager_assignment_rules (hub_id, field, value);
@@ -0,0 +1,2 @@
+-- Set when a manager edits their own name; login syncs then keep the stored name.
+ALTER TABLE managers ADD COLUMN name_locked BOOLEAN NOT NULL DEFAULT 0;
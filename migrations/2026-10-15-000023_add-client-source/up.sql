@@ -0,0 +1,2 @@
+-- Channel a client was first created through; NULL for clients created before tracking.
+ALTER TABLE clients ADD COLUMN source TEXT;
@@ -0,0 +1 @@
+ALTER TABLE hub_settings DROP COLUMN email_reply_to;
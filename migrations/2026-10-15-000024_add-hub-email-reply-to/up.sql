@@ -0,0 +1,2 @@
+-- Reply-To address of outbound client emails; NULL replies to the sending manager.
+ALTER TABLE hub_settings ADD COLUMN email_reply_to TEXT;
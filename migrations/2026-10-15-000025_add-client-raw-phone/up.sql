@@ -0,0 +1,2 @@
+-- Phone contact kept as entered when it fails strict E.164 parsing.
+ALTER TABLE clients ADD COLUMN raw_phone TEXT;
@@ -0,0 +1,2 @@
+-- Incremented on every client save so stale edits can be rejected.
+ALTER TABLE clients ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1 @@
+CREATE INDEX idx_clients_hub_email ON clients (hub_id, email);
@@ -0,0 +1,2 @@
+-- clients_hub_id_email_idx (UNIQUE (hub_id, email)) already serves hub email lookups.
+DROP INDEX idx_clients_hub_email;
@@ -0,0 +1 @@
+DROP INDEX clients_hub_id_raw_phone_idx;
@@ -0,0 +1,2 @@
+-- Raw-phone-only import rows are matched to existing clients by this pair.
+CREATE INDEX clients_hub_id_raw_phone_idx ON clients (hub_id, raw_phone);
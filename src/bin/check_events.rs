@@ -1,40 +1,96 @@
 //! Background worker consuming ZeroMQ notifications and recording CRM client events.
+//!
+//! # Threading model
+//!
+//! Each subscribed socket (emailer, replier, clients, tasks and tracker) is
+//! read by its own receiver thread, so a slow handler of one topic never
+//! delays another. The receiver forwards messages over a channel to the
+//! topic's handler threads: `server.worker.email_threads`,
+//! `server.worker.reply_threads` and `server.worker.client_threads` size the
+//! emailer, replier and clients pools, while tasks and email opens use one
+//! handler each. Handlers share the SQLite connection pool, so the total
+//! thread count should stay within `server.db_pool_size`. Messages of one
+//! topic are processed in order only when it has a single handler thread.
+//!
+//! # Shutdown
+//!
+//! `SIGINT` and `SIGTERM` set a shutdown flag. Receivers poll their sockets
+//! without blocking and stop once the flag is set; handlers finish the
+//! messages already queued before the process exits.
+//!
+//! # Database maintenance
+//!
+//! A `db-maintenance` thread runs `PRAGMA optimize` and truncates the WAL
+//! every `server.maintenance_interval_hours` (zero disables it) on its own
+//! pooled connection. Once a week the run also issues `VACUUM`.
+//!
+//! An `event-cleanup` thread deletes client events past their hub's
+//! `event_retention_days` at startup and then once a day.
 
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration as StdDuration, Instant};
 
+use chrono::Duration;
 use config::Config;
 use dotenvy::dotenv;
-use pushkind_common::{
-    db::establish_connection_pool,
-    repository::errors::{RepositoryError, RepositoryResult},
-};
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 use pushkind_emailer::models::zmq::{ZMQReplyMessage, ZMQSendEmailMessage, ZMQUnsubscribeMessage};
 use pushkind_todo::dto::zmq::ZmqTask;
 use serde_json::json;
 
+use pushkind_common::db::DbPool;
+use pushkind_crm::db::{establish_connection_pool, run_maintenance};
 use pushkind_crm::models::config::Settings;
 use pushkind_crm::repository::{
     ClientEventReader, ClientEventWriter, ClientReader, ClientWriter, DieselRepository,
     ManagerWriter,
 };
+use pushkind_crm::services::client::cleanup_events_for_all_hubs;
 use pushkind_crm::{
     domain::{
-        client::NewClient,
+        client::{ClientSource, NewClient},
         client_event::{ClientEventType, NewClientEvent},
         manager::NewManager,
-        types::{ClientEmail, ClientName, HubId, PhoneNumber, PublicId},
+        types::{
+            ClientEmail, ClientName, HubId, ImportConflictStrategy, MaskedEmail, PhoneNumber,
+            PublicId,
+        },
     },
-    models::zmq::ZmqClientMessage,
+    models::zmq::{ZMQOpenMessage, ZmqClientMessage},
 };
 
-pub(crate) fn process_email_event<R>(msg: ZMQSendEmailMessage, repo: R) -> RepositoryResult<()>
+/// Whether an identical event was already recorded within the deduplication window.
+pub(crate) fn is_duplicate_event<R>(
+    repo: &R,
+    event: &NewClientEvent,
+    window: Duration,
+) -> RepositoryResult<bool>
+where
+    R: ClientEventReader,
+{
+    repo.client_event_exists_within_window(event, window)
+}
+
+pub(crate) fn process_email_event<R>(
+    msg: ZMQSendEmailMessage,
+    repo: R,
+    window: Duration,
+) -> RepositoryResult<()>
 where
     R: ClientEventWriter + ManagerWriter + ClientReader + ClientEventReader,
 {
     match msg {
         ZMQSendEmailMessage::NewEmail(boxed) => {
             let (user, new_email) = *boxed;
-            log::info!("New email from user {user:?}, {:?}", new_email.subject);
+            log::info!(
+                "New email from {} in hub#{}, {:?}",
+                MaskedEmail::new(&user.email),
+                user.hub_id,
+                new_email.subject
+            );
             let manager_payload = NewManager::try_from(&user).map_err(RepositoryError::from)?;
             let manager = repo.create_or_update_manager(&manager_payload)?;
 
@@ -57,7 +113,7 @@ where
                     }),
                 );
 
-                if repo.client_event_exists(&new_event)? {
+                if is_duplicate_event(&repo, &new_event, window)? {
                     log::info!(
                         "Skipping duplicate email event for client {} and manager {}",
                         client.id,
@@ -83,7 +139,11 @@ where
     Ok(())
 }
 
-pub(crate) fn process_task_message<R>(task: ZmqTask, repo: R) -> RepositoryResult<()>
+pub(crate) fn process_task_message<R>(
+    task: ZmqTask,
+    repo: R,
+    window: Duration,
+) -> RepositoryResult<()>
 where
     R: ClientEventWriter + ClientEventReader + ClientReader + ManagerWriter,
 {
@@ -159,7 +219,7 @@ where
 
     let event = NewClientEvent::new(client.id, manager.id, ClientEventType::Task, event_data);
 
-    if repo.client_event_exists(&event)? {
+    if is_duplicate_event(&repo, &event, window)? {
         log::info!(
             "Skipping duplicate task event for client {} and manager {}",
             client.id,
@@ -172,11 +232,19 @@ where
     Ok(())
 }
 
-pub(crate) fn process_reply_message<R>(reply: ZMQReplyMessage, repo: R) -> RepositoryResult<()>
+pub(crate) fn process_reply_message<R>(
+    reply: ZMQReplyMessage,
+    repo: R,
+    window: Duration,
+) -> RepositoryResult<()>
 where
     R: ClientEventWriter + ManagerWriter + ClientReader + ClientEventReader,
 {
-    log::info!("Reply from {} in hub#{}", reply.email, reply.hub_id);
+    log::info!(
+        "Reply from {} in hub#{}",
+        MaskedEmail::new(&reply.email),
+        reply.hub_id
+    );
 
     let hub_id = HubId::new(reply.hub_id).map_err(RepositoryError::from)?;
     let reply_email = ClientEmail::new(&reply.email).map_err(RepositoryError::from)?;
@@ -199,7 +267,7 @@ where
                     "text": ammonia::clean(&reply.message),
                 }),
             );
-            if repo.client_event_exists(&event)? {
+            if is_duplicate_event(&repo, &event, window)? {
                 log::info!(
                     "Skipping duplicate reply event for client {} and manager {}",
                     client.id,
@@ -217,13 +285,14 @@ where
 pub(crate) fn process_unsubscribe_message<R>(
     message: ZMQUnsubscribeMessage,
     repo: R,
+    window: Duration,
 ) -> RepositoryResult<()>
 where
     R: ClientEventWriter + ManagerWriter + ClientReader + ClientEventReader,
 {
     log::info!(
         "Unsubscribe notification for {} in hub#{}",
-        message.email,
+        MaskedEmail::new(&message.email),
         message.hub_id
     );
 
@@ -248,7 +317,7 @@ where
                 }),
             );
 
-            if repo.client_event_exists(&event)? {
+            if is_duplicate_event(&repo, &event, window)? {
                 log::info!(
                     "Skipping duplicate unsubscribe event for client {} and manager {}",
                     client.id,
@@ -265,10 +334,73 @@ where
     Ok(())
 }
 
+/// Records an `EmailOpen` event for the client who opened a tracked email.
+pub(crate) fn process_open_event<R>(
+    msg: ZMQOpenMessage,
+    repo: R,
+    window: Duration,
+) -> RepositoryResult<()>
+where
+    R: ClientEventWriter + ManagerWriter + ClientReader + ClientEventReader,
+{
+    log::info!(
+        "Email {} opened by {} in hub#{}",
+        msg.message_id,
+        MaskedEmail::new(&msg.email),
+        msg.hub_id
+    );
+
+    let hub_id = HubId::new(msg.hub_id).map_err(RepositoryError::from)?;
+    let open_email = ClientEmail::new(&msg.email).map_err(RepositoryError::from)?;
+    let Some(client) = repo.get_client_by_email(&open_email, hub_id)? else {
+        log::info!(
+            "No client {} in hub#{} for open event",
+            MaskedEmail::new(&msg.email),
+            msg.hub_id
+        );
+        return Ok(());
+    };
+
+    let new_manager = NewManager::try_new(
+        client.hub_id.get(),
+        client.name.as_str().to_string(),
+        msg.email.clone(),
+        false,
+    )
+    .map_err(RepositoryError::from)?;
+    let manager = repo.create_or_update_manager(&new_manager)?;
+    let event = NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Other("EmailOpen".to_string()),
+        json!({
+            "message_id": &msg.message_id,
+        }),
+    );
+
+    if is_duplicate_event(&repo, &event, window)? {
+        log::info!(
+            "Skipping duplicate open event for message {} and client {}",
+            msg.message_id,
+            client.id
+        );
+        return Ok(());
+    }
+
+    let _event = repo.create_client_event(&event)?;
+
+    Ok(())
+}
+
 fn process_client_message<R>(message: ZmqClientMessage, repo: R) -> RepositoryResult<()>
 where
     R: ClientWriter + ClientReader,
 {
+    if let Err(reason) = message.validate() {
+        log::warn!("Skipping ZMQ client message: {reason}");
+        return Ok(());
+    }
+
     let new_client = NewClient::new(
         HubId::new(message.hub_id)?,
         ClientName::new(&message.name)?,
@@ -281,12 +413,13 @@ where
             None => None,
         },
         message.fields,
-    );
+    )
+    .with_source(ClientSource::Zmq);
 
-    let inserted = repo.create_clients(&[new_client])?;
+    let summary = repo.create_clients(&[new_client], ImportConflictStrategy::Skip)?;
     log::info!(
         "Inserted {} client records via ZMQ payload, skipped existing ones",
-        inserted
+        summary.inserted
     );
 
     Ok(())
@@ -325,33 +458,24 @@ fn main() {
     };
 
     let app_config = settings.app;
+    let dedup_window = settings.server.dedup_window();
 
     let context = zmq::Context::new();
-    let responder = context.socket(zmq::SUB).expect("Cannot create zmq socket");
-    responder
-        .connect(&app_config.zmq_emailer_sub)
-        .expect("Cannot connect to zmq port");
-    responder.set_subscribe(b"").expect("SUBSCRIBE failed");
-
-    let replier = context.socket(zmq::SUB).expect("Cannot create zmq socket");
-    replier
-        .connect(&app_config.zmq_replier_sub)
-        .expect("Cannot connect to zmq port");
-    replier.set_subscribe(b"").expect("SUBSCRIBE failed");
-
-    let clients = context.socket(zmq::SUB).expect("Cannot create zmq socket");
-    clients
-        .connect(&app_config.zmq_clients_sub)
-        .expect("Cannot connect to zmq port");
-    clients.set_subscribe(b"").expect("SUBSCRIBE failed");
-
-    let tasks = context.socket(zmq::SUB).expect("Cannot create zmq socket");
-    tasks
-        .connect(&app_config.zmq_tasks_sub)
-        .expect("Cannot connect to zmq port");
-    tasks.set_subscribe(b"").expect("SUBSCRIBE failed");
-
-    let pool = match establish_connection_pool(&app_config.database_url) {
+    let subscribe = |endpoint: &str| {
+        let socket = context.socket(zmq::SUB).expect("Cannot create zmq socket");
+        socket
+            .connect(endpoint)
+            .expect("Cannot connect to zmq port");
+        socket.set_subscribe(b"").expect("SUBSCRIBE failed");
+        socket
+    };
+    let responder = subscribe(&app_config.zmq_emailer_sub);
+    let replier = subscribe(&app_config.zmq_replier_sub);
+    let clients = subscribe(&app_config.zmq_clients_sub);
+    let tasks = subscribe(&app_config.zmq_tasks_sub);
+    let tracker = subscribe(&app_config.zmq_tracker_sub);
+
+    let pool = match establish_connection_pool(&app_config.database_url, &settings.server) {
         Ok(pool) => pool,
         Err(e) => {
             log::error!("Failed to establish database connection: {e}");
@@ -359,87 +483,300 @@ fn main() {
         }
     };
 
-    let repo = DieselRepository::new(pool);
+    let repo =
+        DieselRepository::new(pool.clone()).with_batch_chunk_size(settings.server.batch_chunk_size);
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let signal_flag = Arc::clone(&shutdown);
+    if let Err(e) = ctrlc::set_handler(move || {
+        log::info!("Shutdown requested, draining queued messages");
+        signal_flag.store(true, Ordering::SeqCst);
+    }) {
+        log::error!("Failed to install the shutdown signal handler: {e}");
+        std::process::exit(1);
+    }
 
     log::info!("Starting event worker");
 
-    let reply_repo = repo.clone();
-    std::thread::spawn(move || {
-        loop {
-            let msg = replier.recv_bytes(0).unwrap();
+    let worker = &settings.server.worker;
+    let mut handles = Vec::new();
+
+    let email_repo = repo.clone();
+    handles.extend(spawn_topic(
+        "emailer",
+        worker.email_threads,
+        socket_receiver("emailer", responder, Arc::clone(&shutdown)),
+        move |msg| match serde_json::from_slice::<ZMQSendEmailMessage>(msg) {
+            Ok(parsed) => {
+                if let Err(e) = process_email_event(parsed, email_repo.clone(), dedup_window) {
+                    log::error!("Error processing email message: {e}");
+                }
+            }
+            Err(e) => log::error!("Error receiving message: {e}"),
+        },
+    ));
 
-            if let Ok(reply) = serde_json::from_slice::<ZMQReplyMessage>(&msg) {
-                let repo = reply_repo.clone();
-                if let Err(e) = process_reply_message(reply, repo) {
+    let reply_repo = repo.clone();
+    handles.extend(spawn_topic(
+        "replier",
+        worker.reply_threads,
+        socket_receiver("replier", replier, Arc::clone(&shutdown)),
+        move |msg| {
+            if let Ok(reply) = serde_json::from_slice::<ZMQReplyMessage>(msg) {
+                if let Err(e) = process_reply_message(reply, reply_repo.clone(), dedup_window) {
                     log::error!("Error processing reply message: {e}");
                 }
-                continue;
+                return;
             }
 
-            match serde_json::from_slice::<ZMQUnsubscribeMessage>(&msg) {
+            match serde_json::from_slice::<ZMQUnsubscribeMessage>(msg) {
                 Ok(unsubscribe) => {
-                    let repo = reply_repo.clone();
-                    if let Err(e) = process_unsubscribe_message(unsubscribe, repo) {
+                    if let Err(e) =
+                        process_unsubscribe_message(unsubscribe, reply_repo.clone(), dedup_window)
+                    {
                         log::error!("Error processing unsubscribe message: {e}");
                     }
                 }
                 Err(e) => log::error!("Error receiving replier message: {e}"),
             }
-        }
-    });
+        },
+    ));
 
     let client_repo = repo.clone();
-    std::thread::spawn(move || {
-        loop {
-            let msg = clients.recv_bytes(0).unwrap();
-            match serde_json::from_slice::<ZmqClientMessage>(&msg) {
-                Ok(parsed) => {
-                    if let Err(e) = process_client_message(parsed, client_repo.clone()) {
-                        log::error!("Error processing client message: {e}");
-                    }
+    handles.extend(spawn_topic(
+        "clients",
+        worker.client_threads,
+        socket_receiver("clients", clients, Arc::clone(&shutdown)),
+        move |msg| match serde_json::from_slice::<ZmqClientMessage>(msg) {
+            Ok(parsed) => {
+                if let Err(e) = process_client_message(parsed, client_repo.clone()) {
+                    log::error!("Error processing client message: {e}");
                 }
-                Err(e) => log::error!("Error receiving client message: {e}"),
             }
-        }
-    });
+            Err(e) => log::error!("Error receiving client message: {e}"),
+        },
+    ));
 
     let task_repo = repo.clone();
-    std::thread::spawn(move || {
-        loop {
-            let msg = tasks.recv_bytes(0).unwrap();
-            match serde_json::from_slice::<ZmqTask>(&msg) {
-                Ok(parsed) => {
-                    if let Err(e) = process_task_message(parsed, task_repo.clone()) {
-                        log::error!("Error processing task message: {e}");
-                    }
+    handles.extend(spawn_topic(
+        "tasks",
+        1,
+        socket_receiver("tasks", tasks, Arc::clone(&shutdown)),
+        move |msg| match serde_json::from_slice::<ZmqTask>(msg) {
+            Ok(parsed) => {
+                if let Err(e) = process_task_message(parsed, task_repo.clone(), dedup_window) {
+                    log::error!("Error processing task message: {e}");
                 }
-                Err(e) => log::error!("Error receiving task message: {e}"),
             }
-        }
-    });
+            Err(e) => log::error!("Error receiving task message: {e}"),
+        },
+    ));
 
-    loop {
-        let msg = responder.recv_bytes(0).unwrap();
-        match serde_json::from_slice::<ZMQSendEmailMessage>(&msg) {
+    handles.push(spawn_event_cleanup(repo.clone(), Arc::clone(&shutdown)));
+
+    let tracker_repo = repo;
+    handles.extend(spawn_topic(
+        "tracker",
+        1,
+        socket_receiver("tracker", tracker, Arc::clone(&shutdown)),
+        move |msg| match serde_json::from_slice::<ZMQOpenMessage>(msg) {
             Ok(parsed) => {
-                let repo = repo.clone();
-                if let Err(e) = process_email_event(parsed, repo) {
-                    log::error!("Error processing email message: {e}");
+                if let Err(e) = process_open_event(parsed, tracker_repo.clone(), dedup_window) {
+                    log::error!("Error processing open message: {e}");
                 }
             }
+            Err(e) => log::error!("Error receiving open message: {e}"),
+        },
+    ));
+
+    if let Some(interval) = settings.server.maintenance_interval() {
+        handles.push(spawn_maintenance(pool, interval, Arc::clone(&shutdown)));
+    }
+
+    for handle in handles {
+        if handle.join().is_err() {
+            log::error!("Event worker thread panicked");
+        }
+    }
+
+    log::info!("Event worker stopped");
+    std::process::exit(0);
+}
+
+/// Pause between polls of a socket without pending messages.
+const POLL_INTERVAL: StdDuration = StdDuration::from_millis(100);
+
+/// Reads messages from a ZeroMQ socket until shutdown or a receive error.
+fn socket_receiver(
+    socket_name: &'static str,
+    socket: zmq::Socket,
+    shutdown: Arc<AtomicBool>,
+) -> impl FnMut() -> Option<Vec<u8>> + Send + 'static {
+    move || next_message(socket_name, &shutdown, || socket.recv_bytes(zmq::DONTWAIT))
+}
+
+/// Polls `try_recv` until it yields a message, fails, or `shutdown` is set.
+fn next_message<F>(socket_name: &str, shutdown: &AtomicBool, mut try_recv: F) -> Option<Vec<u8>>
+where
+    F: FnMut() -> zmq::Result<Vec<u8>>,
+{
+    while !shutdown.load(Ordering::SeqCst) {
+        match try_recv() {
+            Ok(msg) => return Some(msg),
+            Err(zmq::Error::EAGAIN) => thread::sleep(POLL_INTERVAL),
             Err(e) => {
-                log::error!("Error receiving message: {e}");
-                continue;
+                log::error!("Failed to receive from the {socket_name} socket: {e}");
+                return None;
             }
         }
     }
+
+    None
+}
+
+/// Processes one topic on dedicated threads.
+///
+/// `recv` runs alone on a receiver thread, because ZeroMQ sockets must not be
+/// shared between threads, and forwards every message to `workers` handler
+/// threads (at least one). Returning `None` from `recv` stops the topic once
+/// the queued messages are handled.
+fn spawn_topic<Recv, Handle>(
+    socket_name: &'static str,
+    workers: usize,
+    mut recv: Recv,
+    handle: Handle,
+) -> Vec<JoinHandle<()>>
+where
+    Recv: FnMut() -> Option<Vec<u8>> + Send + 'static,
+    Handle: Fn(&[u8]) + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let handle = Arc::new(handle);
+    let workers = workers.max(1);
+
+    let mut handles = Vec::with_capacity(workers + 1);
+    handles.push(
+        thread::Builder::new()
+            .name(format!("{socket_name}-recv"))
+            .spawn(move || {
+                log::info!("Listening on the {socket_name} socket");
+                while let Some(msg) = recv() {
+                    if sender.send(msg).is_err() {
+                        break;
+                    }
+                }
+                log::info!("Stopped listening on the {socket_name} socket");
+            })
+            .expect("Cannot spawn a receiver thread"),
+    );
+
+    for index in 0..workers {
+        let receiver = Arc::clone(&receiver);
+        let handle = Arc::clone(&handle);
+        handles.push(
+            thread::Builder::new()
+                .name(format!("{socket_name}-{index}"))
+                .spawn(move || {
+                    log::info!("Started {socket_name} handler {index}");
+                    loop {
+                        let msg = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(poisoned) => poisoned.into_inner().recv(),
+                        };
+                        let Ok(msg) = msg else { break };
+                        handle(&msg);
+                    }
+                })
+                .expect("Cannot spawn a handler thread"),
+        );
+    }
+
+    handles
+}
+
+/// Pause between event retention cleanups.
+const EVENT_CLEANUP_INTERVAL: StdDuration = StdDuration::from_secs(24 * 3600);
+
+/// Applies every hub's event retention at startup and then once a day until
+/// `shutdown` is set.
+fn spawn_event_cleanup(repo: DieselRepository, shutdown: Arc<AtomicBool>) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name("event-cleanup".to_string())
+        .spawn(move || {
+            let mut next_run = Instant::now();
+            while !shutdown.load(Ordering::SeqCst) {
+                if Instant::now() < next_run {
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                next_run = Instant::now() + EVENT_CLEANUP_INTERVAL;
+
+                match cleanup_events_for_all_hubs(&repo) {
+                    Ok(deleted) => {
+                        log::info!("Removed {deleted} client events past their hub retention")
+                    }
+                    Err(e) => log::error!("Failed to clean up old client events: {e}"),
+                }
+            }
+        })
+        .expect("Cannot spawn the event cleanup thread")
+}
+
+/// Minimum time between maintenance runs that also `VACUUM` the database.
+const VACUUM_INTERVAL: StdDuration = StdDuration::from_secs(7 * 24 * 3600);
+
+/// Whether a maintenance run should also `VACUUM`: the first run of the
+/// process does, later ones once [`VACUUM_INTERVAL`] has passed.
+fn vacuum_due(last_vacuum: Option<Instant>) -> bool {
+    last_vacuum.is_none_or(|last| last.elapsed() >= VACUUM_INTERVAL)
+}
+
+/// Runs [`run_maintenance`] every `interval` until `shutdown` is set.
+fn spawn_maintenance(
+    pool: DbPool,
+    interval: StdDuration,
+    shutdown: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name("db-maintenance".to_string())
+        .spawn(move || {
+            let mut last_vacuum = None;
+            let mut next_run = Instant::now() + interval;
+            while !shutdown.load(Ordering::SeqCst) {
+                if Instant::now() < next_run {
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                next_run = Instant::now() + interval;
+
+                let vacuum = vacuum_due(last_vacuum);
+                let started = Instant::now();
+                let result = pool.get().map_err(|e| e.to_string()).and_then(|mut conn| {
+                    run_maintenance(&mut conn, vacuum).map_err(|e| e.to_string())
+                });
+                match result {
+                    Ok(()) => {
+                        if vacuum {
+                            last_vacuum = Some(Instant::now());
+                        }
+                        log::info!(
+                            "Database maintenance finished in {:?} (vacuum: {vacuum})",
+                            started.elapsed()
+                        );
+                    }
+                    Err(e) => log::error!("Database maintenance failed: {e}"),
+                }
+            }
+        })
+        .expect("Cannot spawn the maintenance thread")
 }
 
 #[cfg(all(test, feature = "test-mocks"))]
 mod tests {
     use super::*;
     use chrono::Utc;
-    use pushkind_crm::domain::client::Client;
+    use pushkind_crm::domain::client::{Client, ClientSource, ClientStatus, ClientUpsertSummary};
     use pushkind_crm::domain::client_event::ClientEvent;
     use pushkind_crm::domain::manager::Manager;
     use pushkind_crm::domain::types::{ClientEventId, ClientId, ClientName, HubId, PublicId};
@@ -477,8 +814,9 @@ mod tests {
 
         repo.expect_create_clients()
             .times(1)
-            .returning(move |new_clients| {
-                let mut count = 0;
+            .withf(|_, strategy| *strategy == ImportConflictStrategy::Skip)
+            .returning(move |new_clients, _| {
+                let mut summary = ClientUpsertSummary::default();
                 let mut clients = clients.lock().expect("lock poisoned");
                 let mut next_id = next_id.lock().expect("lock poisoned");
 
@@ -505,12 +843,16 @@ mod tests {
                         created_at: now,
                         updated_at: now,
                         fields: new.fields.clone(),
+                        status: ClientStatus::Active,
+                        source: new.source,
+                        raw_phone: new.raw_phone.clone(),
+                        version: 1,
                     };
                     clients.insert(id, client);
-                    count += 1;
+                    summary.inserted += 1;
                 }
 
-                Ok(count)
+                Ok(summary)
             });
 
         repo
@@ -542,6 +884,11 @@ mod tests {
         assert_eq!(snapshot.len(), 2);
         assert!(snapshot.values().any(|c| c.name.as_str() == "Alice"));
         assert!(snapshot.values().any(|c| c.name.as_str() == "Bob"));
+        assert!(
+            snapshot
+                .values()
+                .all(|c| c.source == Some(ClientSource::Zmq))
+        );
     }
 
     #[test]
@@ -613,6 +960,38 @@ mod tests {
         assert_eq!(client.hub_id.get(), 3);
     }
 
+    #[test]
+    fn skips_client_message_with_zero_hub_id() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_clients().times(0);
+        let message = ZmqClientMessage {
+            hub_id: 0,
+            name: "No Hub".to_string(),
+            email: Some("nohub@example.com".to_string()),
+            phone: None,
+            fields: None,
+        };
+
+        assert_eq!(message.validate(), Err("invalid hub_id 0".to_string()));
+        process_client_message(message, repo).expect("invalid message is skipped");
+    }
+
+    #[test]
+    fn skips_client_message_with_empty_name() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_clients().times(0);
+        let message = ZmqClientMessage {
+            hub_id: 1,
+            name: "   ".to_string(),
+            email: Some("noname@example.com".to_string()),
+            phone: None,
+            fields: None,
+        };
+
+        assert_eq!(message.validate(), Err("empty client name".to_string()));
+        process_client_message(message, repo).expect("invalid message is skipped");
+    }
+
     #[test]
     fn process_task_message_creates_event_for_matching_client() {
         let mut repo = MockRepository::new();
@@ -628,6 +1007,10 @@ mod tests {
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
             fields: None,
+            status: ClientStatus::Active,
+            source: None,
+            raw_phone: None,
+            version: 1,
         };
 
         let manager = Manager::try_new(
@@ -653,9 +1036,9 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(manager.clone()));
 
-        repo.expect_client_event_exists()
+        repo.expect_client_event_exists_within_window()
             .times(1)
-            .returning(|_| Ok(false));
+            .returning(|_, _| Ok(false));
 
         repo.expect_create_client_event()
             .times(1)
@@ -707,6 +1090,170 @@ mod tests {
             track: Some("Track A".to_string()),
         };
 
-        process_task_message(task, repo).expect("task processing failed");
+        process_task_message(task, repo, dedup_window()).expect("task processing failed");
+    }
+
+    fn dedup_window() -> Duration {
+        Duration::hours(24)
+    }
+
+    fn open_message() -> ZMQOpenMessage {
+        ZMQOpenMessage {
+            email: "Client@Example.com".to_string(),
+            hub_id: 1,
+            message_id: "msg-42".to_string(),
+        }
+    }
+
+    fn open_event_repo(event_exists: bool) -> MockRepository {
+        let mut repo = MockRepository::new();
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let client = Client {
+            id: ClientId::new(10).expect("valid client id"),
+            public_id: None,
+            hub_id,
+            name: ClientName::new("Client").expect("valid name"),
+            email: Some(ClientEmail::new("client@example.com").expect("valid email")),
+            phone: None,
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+            fields: None,
+            status: ClientStatus::Active,
+            source: None,
+            raw_phone: None,
+            version: 1,
+        };
+        let manager = Manager::try_new(
+            5,
+            hub_id.get(),
+            "Client".to_string(),
+            "client@example.com".to_string(),
+            false,
+        )
+        .expect("valid manager");
+
+        repo.expect_get_client_by_email()
+            .withf(move |email, hid| email == "client@example.com" && *hid == hub_id)
+            .times(1)
+            .returning(move |_, _| Ok(Some(client.clone())));
+        repo.expect_create_or_update_manager()
+            .times(1)
+            .returning(move |_| Ok(manager.clone()));
+        repo.expect_client_event_exists_within_window()
+            .withf(|event, window| {
+                *window == dedup_window()
+                    && event.event_type == ClientEventType::Other("EmailOpen".to_string())
+                    && event.event_data == json!({"message_id": "msg-42"})
+            })
+            .times(1)
+            .returning(move |_, _| Ok(event_exists));
+
+        repo
+    }
+
+    #[test]
+    fn process_open_event_records_email_open() {
+        let mut repo = open_event_repo(false);
+        repo.expect_create_client_event()
+            .withf(|event| {
+                event.client_id.get() == 10
+                    && event.manager_id.get() == 5
+                    && event.event_type == ClientEventType::Other("EmailOpen".to_string())
+                    && event.event_data["message_id"] == json!("msg-42")
+            })
+            .times(1)
+            .returning(|event| {
+                Ok(ClientEvent::new(
+                    ClientEventId::new(1).expect("valid event id"),
+                    event.client_id,
+                    event.manager_id,
+                    event.event_type.clone(),
+                    event.event_data.clone(),
+                    Utc::now().naive_utc(),
+                ))
+            });
+
+        process_open_event(open_message(), repo, dedup_window()).expect("open processing failed");
+    }
+
+    #[test]
+    fn process_open_event_skips_duplicates() {
+        let mut repo = open_event_repo(true);
+        repo.expect_create_client_event().times(0);
+
+        process_open_event(open_message(), repo, dedup_window()).expect("open processing failed");
+    }
+
+    #[test]
+    fn process_open_event_ignores_unknown_clients() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_email()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_create_or_update_manager().times(0);
+        repo.expect_create_client_event().times(0);
+
+        process_open_event(open_message(), repo, dedup_window()).expect("open processing failed");
+    }
+
+    #[test]
+    fn spawn_topic_handles_every_message_with_default_worker_threads() {
+        use pushkind_crm::models::config::WorkerConfig;
+        use std::sync::atomic::AtomicUsize;
+
+        let worker = WorkerConfig::default();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&handled);
+        let mut messages = vec![b"one".to_vec(), b"two".to_vec(), b"three".to_vec()].into_iter();
+
+        let handles = spawn_topic(
+            "test",
+            worker.email_threads,
+            move || messages.next(),
+            move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+
+        assert_eq!(handles.len(), worker.email_threads + 1);
+        for handle in handles {
+            handle.join().expect("topic thread finished");
+        }
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn topic_threads_exit_after_shutdown_is_requested() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let polled = Arc::clone(&shutdown);
+
+        let handles = spawn_topic(
+            "test",
+            2,
+            move || next_message("test", &polled, || Err(zmq::Error::EAGAIN)),
+            |_| {},
+        );
+        shutdown.store(true, Ordering::SeqCst);
+
+        let (done, finished) = mpsc::channel();
+        thread::spawn(move || {
+            for handle in handles {
+                handle.join().expect("topic thread finished");
+            }
+            done.send(()).expect("test is waiting");
+        });
+
+        finished
+            .recv_timeout(StdDuration::from_secs(2))
+            .expect("topic threads exit within the timeout");
+    }
+
+    #[test]
+    fn vacuum_is_due_on_the_first_run_and_then_weekly() {
+        assert!(vacuum_due(None));
+        assert!(!vacuum_due(Some(Instant::now())));
+        if let Some(week_ago) = Instant::now().checked_sub(VACUUM_INTERVAL) {
+            assert!(vacuum_due(Some(week_ago)));
+        }
     }
 }
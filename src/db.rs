@@ -0,0 +1,132 @@
+//! SQLite connection pool sized and tuned from [`ServerConfig`].
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+use diesel::QueryResult;
+use diesel::connection::SimpleConnection;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Error, Pool, PoolError};
+use diesel::sqlite::SqliteConnection;
+use pushkind_common::db::DbPool;
+
+use crate::models::config::ServerConfig;
+
+/// Connection setting applied with its own `PRAGMA` statement.
+///
+/// Values are typed, so no caller-provided text ever reaches the statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pragma {
+    /// Milliseconds to wait on a locked database before failing.
+    BusyTimeout(u64),
+    JournalModeWal,
+    SynchronousNormal,
+    ForeignKeys,
+}
+
+impl Pragma {
+    fn statement(self) -> Cow<'static, str> {
+        match self {
+            Self::BusyTimeout(millis) => Cow::Owned(format!("PRAGMA busy_timeout = {millis};")),
+            Self::JournalModeWal => Cow::Borrowed("PRAGMA journal_mode = WAL;"),
+            Self::SynchronousNormal => Cow::Borrowed("PRAGMA synchronous = NORMAL;"),
+            Self::ForeignKeys => Cow::Borrowed("PRAGMA foreign_keys = ON;"),
+        }
+    }
+}
+
+/// Applies [`Pragma`]s to every connection handed out by the pool.
+#[derive(Debug, Clone, Copy)]
+struct ConnectionOptions {
+    busy_timeout: Duration,
+}
+
+impl ConnectionOptions {
+    /// The busy timeout comes first so the later statements already wait on
+    /// a locked database instead of failing.
+    fn pragmas(&self) -> [Pragma; 4] {
+        let millis = u64::try_from(self.busy_timeout.as_millis()).unwrap_or(u64::MAX);
+        [
+            Pragma::BusyTimeout(millis),
+            Pragma::JournalModeWal,
+            Pragma::SynchronousNormal,
+            Pragma::ForeignKeys,
+        ]
+    }
+}
+
+impl CustomizeConnection<SqliteConnection, Error> for ConnectionOptions {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), Error> {
+        self.pragmas()
+            .into_iter()
+            .try_for_each(|pragma| conn.batch_execute(&pragma.statement()))
+            .map_err(Error::QueryError)
+    }
+}
+
+/// Builds the SQLite pool using `db_pool_size` and `db_busy_timeout_ms` from
+/// `config`.
+///
+/// `pushkind_common::db::establish_connection_pool` has a fixed size and
+/// timeout, so this builds the same pool with the configured values.
+pub fn establish_connection_pool(
+    database_url: &str,
+    config: &ServerConfig,
+) -> Result<DbPool, PoolError> {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+
+    Pool::builder()
+        .max_size(config.db_pool_size.max(1))
+        .connection_customizer(Box::new(ConnectionOptions {
+            busy_timeout: config.db_busy_timeout(),
+        }))
+        .build(manager)
+}
+
+/// Refreshes the query planner statistics and truncates the WAL file.
+///
+/// With `vacuum` the database file is also rebuilt to reclaim free pages,
+/// which locks it for the duration, so callers run it rarely.
+pub fn run_maintenance(conn: &mut SqliteConnection, vacuum: bool) -> QueryResult<()> {
+    conn.batch_execute("PRAGMA optimize; PRAGMA wal_checkpoint(TRUNCATE);")?;
+    if vacuum {
+        conn.batch_execute("VACUUM;")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::Connection;
+
+    use super::*;
+
+    #[test]
+    fn maintenance_runs_on_an_in_memory_database() {
+        let mut conn = SqliteConnection::establish(":memory:").expect("in-memory database");
+        conn.batch_execute(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY); INSERT INTO items DEFAULT VALUES;",
+        )
+        .expect("seed table");
+
+        run_maintenance(&mut conn, false).expect("optimize and checkpoint");
+        run_maintenance(&mut conn, true).expect("vacuum");
+    }
+
+    #[test]
+    fn connection_options_set_the_busy_timeout_first() {
+        let options = ConnectionOptions {
+            busy_timeout: Duration::from_millis(1_500),
+        };
+
+        let pragmas = options.pragmas();
+
+        assert_eq!(pragmas[0], Pragma::BusyTimeout(1_500));
+        assert_eq!(
+            pragmas[0].statement().as_ref(),
+            "PRAGMA busy_timeout = 1500;"
+        );
+
+        let mut conn = SqliteConnection::establish(":memory:").expect("in-memory database");
+        options.on_acquire(&mut conn).expect("pragmas apply");
+    }
+}
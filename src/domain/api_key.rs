@@ -0,0 +1,81 @@
+//! Domain model for API keys used by server-to-server integrations.
+
+use chrono::NaiveDateTime;
+
+use crate::domain::types::{ApiKeyId, HubId, TypeConstraintError};
+
+/// Key letting a hub integration call the `/api` scope without a browser session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    pub hub_id: HubId,
+    pub name: String,
+    /// Roles granted to requests authenticated with the key.
+    pub roles: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ApiKey {
+    /// Create an API key from raw values; `roles` is a comma-separated list.
+    pub fn try_new(
+        id: i32,
+        hub_id: i32,
+        name: String,
+        roles: &str,
+        created_at: NaiveDateTime,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self {
+            id: ApiKeyId::try_from(id)?,
+            hub_id: HubId::try_from(hub_id)?,
+            name,
+            roles: roles
+                .split(',')
+                .map(str::trim)
+                .filter(|role| !role.is_empty())
+                .map(str::to_string)
+                .collect(),
+            created_at,
+        })
+    }
+}
+
+/// Data required to store a new API key; only the hash of the key is kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewApiKey {
+    pub hub_id: HubId,
+    pub name: String,
+    /// Hex-encoded SHA-256 of the raw key.
+    pub key_hash: String,
+    pub roles: Vec<String>,
+}
+
+impl NewApiKey {
+    /// Create a new API key from already validated domain values.
+    #[must_use]
+    pub fn new(hub_id: HubId, name: String, key_hash: String, roles: Vec<String>) -> Self {
+        Self {
+            hub_id,
+            name,
+            key_hash,
+            roles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn try_new_splits_comma_separated_roles() {
+        let created_at = Utc::now().naive_utc();
+
+        let key = ApiKey::try_new(1, 2, "ERP".to_string(), " crm, crm_admin ,", created_at)
+            .expect("valid api key");
+
+        assert_eq!(key.roles, vec!["crm".to_string(), "crm_admin".to_string()]);
+        assert_eq!(key.hub_id.get(), 2);
+    }
+}
@@ -0,0 +1,67 @@
+//! Domain model for rules assigning managers to clients by custom field value.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{
+    HubId, ImportantFieldName, ManagerId, NonEmptyString, RuleId, TypeConstraintError,
+};
+
+/// Rule assigning `manager_id` to clients whose custom `field` equals `value`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ManagerAssignmentRule {
+    pub id: RuleId,
+    pub hub_id: HubId,
+    pub manager_id: ManagerId,
+    pub field: ImportantFieldName,
+    pub value: NonEmptyString,
+    pub created_at: NaiveDateTime,
+}
+
+impl ManagerAssignmentRule {
+    /// Create a rule from raw values, validating identifiers and the condition.
+    pub fn try_new(
+        id: i32,
+        hub_id: i32,
+        manager_id: i32,
+        field: String,
+        value: String,
+        created_at: NaiveDateTime,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self {
+            id: RuleId::try_from(id)?,
+            hub_id: HubId::try_from(hub_id)?,
+            manager_id: ManagerId::try_from(manager_id)?,
+            field: ImportantFieldName::new(field)?,
+            value: NonEmptyString::new(value)?,
+            created_at,
+        })
+    }
+}
+
+/// Data required to create a manager assignment rule.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewManagerAssignmentRule {
+    pub hub_id: HubId,
+    pub manager_id: ManagerId,
+    pub field: ImportantFieldName,
+    pub value: NonEmptyString,
+}
+
+impl NewManagerAssignmentRule {
+    /// Create a new rule from already validated domain values.
+    #[must_use]
+    pub fn new(
+        hub_id: HubId,
+        manager_id: ManagerId,
+        field: ImportantFieldName,
+        value: NonEmptyString,
+    ) -> Self {
+        Self {
+            hub_id,
+            manager_id,
+            field,
+            value,
+        }
+    }
+}
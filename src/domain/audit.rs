@@ -0,0 +1,199 @@
+//! Domain model for the audit log of changes made to CRM records.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::domain::client::Client;
+use crate::domain::types::{AuditEntryId, HubId, ManagerEmail, TypeConstraintError};
+
+/// Entity type recorded for client changes.
+pub const AUDIT_ENTITY_CLIENT: &str = "client";
+/// Action recorded when a record is updated.
+pub const AUDIT_ACTION_UPDATE: &str = "update";
+/// Action recorded when a record is deleted.
+pub const AUDIT_ACTION_DELETE: &str = "delete";
+
+/// Recorded change to a CRM entity.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub id: AuditEntryId,
+    pub hub_id: HubId,
+    pub actor_email: ManagerEmail,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub action: String,
+    /// Changed attributes as `{ "<attribute>": { "before": .., "after": .. } }`.
+    pub diff: Value,
+    pub created_at: NaiveDateTime,
+}
+
+impl AuditEntry {
+    /// Create an audit entry from raw values, validating identifiers and the actor email.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        id: i32,
+        hub_id: i32,
+        actor_email: String,
+        entity_type: String,
+        entity_id: i32,
+        action: String,
+        diff: Value,
+        created_at: NaiveDateTime,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self {
+            id: AuditEntryId::try_from(id)?,
+            hub_id: HubId::try_from(hub_id)?,
+            actor_email: ManagerEmail::new(actor_email)?,
+            entity_type,
+            entity_id,
+            action,
+            diff,
+            created_at,
+        })
+    }
+}
+
+/// Data required to persist a new audit entry.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewAuditEntry {
+    pub hub_id: HubId,
+    pub actor_email: ManagerEmail,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub action: String,
+    pub diff: Value,
+}
+
+impl NewAuditEntry {
+    /// Audit entry describing an update of `before` into `after`.
+    #[must_use]
+    pub fn client_updated(actor_email: ManagerEmail, before: &Client, after: &Client) -> Self {
+        Self {
+            hub_id: after.hub_id,
+            actor_email,
+            entity_type: AUDIT_ENTITY_CLIENT.to_string(),
+            entity_id: after.id.get(),
+            action: AUDIT_ACTION_UPDATE.to_string(),
+            diff: diff_snapshots(
+                &client_snapshot(Some(before)),
+                &client_snapshot(Some(after)),
+            ),
+        }
+    }
+
+    /// Audit entry describing the deletion of `before`.
+    #[must_use]
+    pub fn client_deleted(actor_email: ManagerEmail, before: &Client) -> Self {
+        Self {
+            hub_id: before.hub_id,
+            actor_email,
+            entity_type: AUDIT_ENTITY_CLIENT.to_string(),
+            entity_id: before.id.get(),
+            action: AUDIT_ACTION_DELETE.to_string(),
+            diff: diff_snapshots(&client_snapshot(Some(before)), &client_snapshot(None)),
+        }
+    }
+}
+
+/// Flattens the audited client attributes; custom fields appear as `fields.<name>`.
+fn client_snapshot(client: Option<&Client>) -> BTreeMap<String, Value> {
+    let mut snapshot = BTreeMap::new();
+    let Some(client) = client else {
+        return snapshot;
+    };
+
+    snapshot.insert("name".to_string(), json!(client.name.as_str()));
+    if let Some(email) = &client.email {
+        snapshot.insert("email".to_string(), json!(email.as_str()));
+    }
+    if let Some(phone) = &client.phone {
+        snapshot.insert("phone".to_string(), json!(phone.as_str()));
+    }
+    for (field, value) in client.fields.iter().flatten() {
+        snapshot.insert(format!("fields.{field}"), json!(value));
+    }
+    snapshot
+}
+
+/// Keeps only attributes whose value differs, absent values becoming `null`.
+fn diff_snapshots(before: &BTreeMap<String, Value>, after: &BTreeMap<String, Value>) -> Value {
+    let mut diff = Map::new();
+    for key in before.keys().chain(after.keys()) {
+        let old = before.get(key).cloned().unwrap_or(Value::Null);
+        let new = after.get(key).cloned().unwrap_or(Value::Null);
+        if old != new {
+            diff.insert(key.clone(), json!({ "before": old, "after": new }));
+        }
+    }
+    Value::Object(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn client(name: &str, email: Option<&str>, fields: &[(&str, &str)]) -> Client {
+        let now = Utc::now().naive_utc();
+        Client::try_new(
+            5,
+            None,
+            1,
+            name.to_string(),
+            email.map(str::to_string),
+            None,
+            now,
+            now,
+            Some(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            ),
+        )
+        .expect("valid client")
+    }
+
+    fn actor() -> ManagerEmail {
+        ManagerEmail::new("admin@example.com").expect("valid email")
+    }
+
+    #[test]
+    fn update_diff_lists_only_changed_attributes() {
+        let before = client("Alice", Some("alice@example.com"), &[("Tier", "silver")]);
+        let after = client("Alice", None, &[("Tier", "gold"), ("City", "Paris")]);
+
+        let entry = NewAuditEntry::client_updated(actor(), &before, &after);
+
+        assert_eq!(entry.entity_type, AUDIT_ENTITY_CLIENT);
+        assert_eq!(entry.entity_id, 5);
+        assert_eq!(entry.action, AUDIT_ACTION_UPDATE);
+        assert_eq!(
+            entry.diff,
+            json!({
+                "email": { "before": "alice@example.com", "after": null },
+                "fields.City": { "before": null, "after": "Paris" },
+                "fields.Tier": { "before": "silver", "after": "gold" },
+            })
+        );
+    }
+
+    #[test]
+    fn delete_diff_clears_every_attribute() {
+        let before = client("Alice", Some("alice@example.com"), &[]);
+
+        let entry = NewAuditEntry::client_deleted(actor(), &before);
+
+        assert_eq!(entry.action, AUDIT_ACTION_DELETE);
+        assert_eq!(
+            entry.diff,
+            json!({
+                "email": { "before": "alice@example.com", "after": null },
+                "name": { "before": "Alice", "after": null },
+            })
+        );
+    }
+}
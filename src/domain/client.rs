@@ -1,14 +1,107 @@
 //! Domain model describing CRM clients.
 
 use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
 use crate::domain::types::{
-    ClientEmail, ClientId, ClientName, HubId, PhoneNumber, PublicId, TypeConstraintError,
+    ClientEmail, ClientId, ClientName, HubId, ManagerId, PhoneNumber, PublicId, RawPhone,
+    TypeConstraintError,
 };
 
+/// Lifecycle state of a [`Client`].
+///
+/// Archived clients are hidden from client lists unless explicitly requested.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ClientStatus {
+    #[default]
+    Active,
+    Archived,
+}
+
+impl ClientStatus {
+    /// Returns the value stored in the `clients.status` column.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "Active",
+            Self::Archived => "Archived",
+        }
+    }
+}
+
+impl Display for ClientStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ClientStatus {
+    type Err = TypeConstraintError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "Active" => Ok(Self::Active),
+            "Archived" => Ok(Self::Archived),
+            _ => Err(TypeConstraintError::InvalidValue(
+                "client status".to_string(),
+            )),
+        }
+    }
+}
+
+/// Channel through which a [`Client`] was created.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientSource {
+    /// Added by hand through the dashboard form.
+    Form,
+    /// Imported from an uploaded CSV file.
+    Csv,
+    /// Received from another service over ZMQ.
+    Zmq,
+    /// Sent through the `POST /api/v1/clients/batch` API.
+    Api,
+}
+
+impl ClientSource {
+    /// Returns the value stored in the `clients.source` column.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Form => "form",
+            Self::Csv => "csv",
+            Self::Zmq => "zmq",
+            Self::Api => "api",
+        }
+    }
+}
+
+impl Display for ClientSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ClientSource {
+    type Err = TypeConstraintError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "form" => Ok(Self::Form),
+            "csv" => Ok(Self::Csv),
+            "zmq" => Ok(Self::Zmq),
+            "api" => Ok(Self::Api),
+            _ => Err(TypeConstraintError::InvalidValue(
+                "client source".to_string(),
+            )),
+        }
+    }
+}
+
 /// Represent a trusted CRM client stored in the system.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Client {
@@ -22,6 +115,16 @@ pub struct Client {
     pub updated_at: NaiveDateTime,
     /// Optional set of custom fields.
     pub fields: Option<BTreeMap<String, String>>,
+    pub status: ClientStatus,
+    /// Channel the client was created through; `None` for clients created
+    /// before it was recorded.
+    pub source: Option<ClientSource>,
+    /// Phone contact that failed strict parsing, kept as entered so it is not
+    /// lost; `phone` stays the validated number.
+    pub raw_phone: Option<RawPhone>,
+    /// Incremented on every save; an update carrying an older value is
+    /// rejected as stale.
+    pub version: i32,
 }
 
 impl Client {
@@ -48,9 +151,41 @@ impl Client {
             created_at,
             updated_at,
             fields: normalize_fields(fields),
+            status: ClientStatus::Active,
+            source: None,
+            raw_phone: None,
+            version: 1,
         }
     }
 
+    /// Sets the lifecycle status of the client.
+    #[must_use]
+    pub fn with_status(mut self, status: ClientStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the channel the client was created through.
+    #[must_use]
+    pub fn with_source(mut self, source: Option<ClientSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the phone contact kept as entered.
+    #[must_use]
+    pub fn with_raw_phone(mut self, raw_phone: Option<RawPhone>) -> Self {
+        self.raw_phone = raw_phone;
+        self
+    }
+
+    /// Sets the stored version of the client.
+    #[must_use]
+    pub fn with_version(mut self, version: i32) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Create a client from raw values, validating identifiers and inputs.
     #[allow(clippy::too_many_arguments)]
     pub fn try_new(
@@ -88,6 +223,10 @@ pub struct NewClient {
     pub phone: Option<PhoneNumber>,
     /// Optional set of custom fields.
     pub fields: Option<BTreeMap<String, String>>,
+    /// Channel the client is created through.
+    pub source: Option<ClientSource>,
+    /// Phone contact that failed strict parsing, kept as entered.
+    pub raw_phone: Option<RawPhone>,
 }
 
 impl NewClient {
@@ -107,9 +246,25 @@ impl NewClient {
             email,
             phone,
             fields: normalize_fields(fields),
+            source: None,
+            raw_phone: None,
         }
     }
 
+    /// Records the channel the client is created through.
+    #[must_use]
+    pub fn with_source(mut self, source: ClientSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Keeps a phone contact that failed strict parsing.
+    #[must_use]
+    pub fn with_raw_phone(mut self, raw_phone: RawPhone) -> Self {
+        self.raw_phone = Some(raw_phone);
+        self
+    }
+
     /// Create a new client from raw inputs, validating identifiers and values.
     pub fn try_new(
         hub_id: i32,
@@ -128,6 +283,57 @@ impl NewClient {
     }
 }
 
+/// Custom field name used in a hub, with how widely it is filled in.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldMeta {
+    pub name: String,
+    /// Number of hub clients with a value for the field.
+    pub value_count: usize,
+    /// Whether the field is configured as important for the hub.
+    pub is_important: bool,
+}
+
+/// Outcome of a bulk client write: how many rows were new and how many
+/// matched an existing client and updated it.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClientUpsertSummary {
+    pub inserted: usize,
+    pub updated: usize,
+}
+
+impl ClientUpsertSummary {
+    /// Number of rows written either way.
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.inserted + self.updated
+    }
+}
+
+impl FromIterator<ClientWriteOutcome> for ClientUpsertSummary {
+    fn from_iter<I: IntoIterator<Item = ClientWriteOutcome>>(outcomes: I) -> Self {
+        let mut summary = Self::default();
+        for outcome in outcomes {
+            match outcome {
+                ClientWriteOutcome::Inserted => summary.inserted += 1,
+                ClientWriteOutcome::Updated => summary.updated += 1,
+                ClientWriteOutcome::Skipped => {}
+            }
+        }
+        summary
+    }
+}
+
+/// What an upsert did with one client record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientWriteOutcome {
+    /// Stored as a new client.
+    Inserted,
+    /// Matched an existing client by email or phone and updated it.
+    Updated,
+    /// Not stored, e.g. its phone belongs to a client that could not be updated.
+    Skipped,
+}
+
 /// Data used to update an existing client.
 #[derive(Clone, Debug, Deserialize)]
 pub struct UpdateClient {
@@ -136,6 +342,13 @@ pub struct UpdateClient {
     pub phone: Option<PhoneNumber>,
     /// Updated map of custom fields.
     pub fields: Option<BTreeMap<String, String>>,
+    /// Manager recorded as the client's last editor, if known.
+    #[serde(default)]
+    pub edited_by: Option<ManagerId>,
+    /// [`Client::version`] the edit was based on; when set, the update fails
+    /// if the client has been saved since.
+    #[serde(default)]
+    pub expected_version: Option<i32>,
 }
 
 impl UpdateClient {
@@ -152,9 +365,42 @@ impl UpdateClient {
             email,
             phone,
             fields: normalize_fields(fields),
+            edited_by: None,
+            expected_version: None,
         }
     }
 
+    /// Attribute the update to the given manager.
+    #[must_use]
+    pub fn edited_by(mut self, manager_id: ManagerId) -> Self {
+        self.edited_by = Some(manager_id);
+        self
+    }
+
+    /// Only apply the update if the client is still at `version`.
+    #[must_use]
+    pub fn expected_version(mut self, version: i32) -> Self {
+        self.expected_version = Some(version);
+        self
+    }
+
+    /// Update applying an imported row to `client`, which it matched by name.
+    ///
+    /// Imported contacts and fields take precedence; contacts and fields
+    /// missing from the row keep their stored values, and the stored name is
+    /// kept.
+    #[must_use]
+    pub fn from_import(client: &Client, row: &NewClient) -> Self {
+        let mut fields = client.fields.clone().unwrap_or_default();
+        fields.extend(row.fields.clone().unwrap_or_default());
+        Self::new(
+            client.name.clone(),
+            row.email.clone().or_else(|| client.email.clone()),
+            row.phone.clone().or_else(|| client.phone.clone()),
+            Some(fields),
+        )
+    }
+
     /// Create an update payload from raw inputs, validating values.
     pub fn try_new(
         name: String,
@@ -220,6 +466,49 @@ mod tests {
         assert!(update.fields.is_none());
     }
 
+    #[test]
+    fn update_from_import_prefers_imported_contacts_and_keeps_the_name() {
+        let now = Utc::now().naive_utc();
+        let client = Client {
+            id: sample_client_id(),
+            public_id: Some(sample_public_uuid()),
+            hub_id: sample_hub_id(),
+            name: ClientName::new("Acme Ltd").expect("valid name"),
+            email: Some(ClientEmail::new("old@example.com").expect("valid email")),
+            phone: Some(PhoneNumber::new("+14155552671").expect("valid phone")),
+            created_at: now,
+            updated_at: now,
+            fields: Some(BTreeMap::from([
+                ("City".to_string(), "Kazan".to_string()),
+                ("Stage".to_string(), "Lead".to_string()),
+            ])),
+            status: ClientStatus::Active,
+            source: None,
+            raw_phone: None,
+            version: 1,
+        };
+        let row = NewClient::new(
+            sample_hub_id(),
+            ClientName::new("ACME LTD").expect("valid name"),
+            Some(ClientEmail::new("new@example.com").expect("valid email")),
+            None,
+            Some(BTreeMap::from([("Stage".to_string(), "Won".to_string())])),
+        );
+
+        let update = UpdateClient::from_import(&client, &row);
+
+        assert_eq!(update.name.as_str(), "Acme Ltd");
+        assert_eq!(update.email.unwrap().as_str(), "new@example.com");
+        assert_eq!(update.phone.unwrap().as_str(), "+14155552671");
+        assert_eq!(
+            update.fields,
+            Some(BTreeMap::from([
+                ("City".to_string(), "Kazan".to_string()),
+                ("Stage".to_string(), "Won".to_string()),
+            ]))
+        );
+    }
+
     #[test]
     fn client_struct_stores_typed_fields() {
         let now = Utc::now().naive_utc();
@@ -233,6 +522,10 @@ mod tests {
             created_at: now,
             updated_at: now,
             fields: None,
+            status: ClientStatus::Active,
+            source: Some(ClientSource::Form),
+            raw_phone: None,
+            version: 1,
         };
 
         assert_eq!(client.id.get(), 1);
@@ -6,7 +6,7 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::domain::types::{ClientEventId, ClientId, ManagerId, TypeConstraintError};
+use crate::domain::types::{ClientEventId, ClientId, ManagerEmail, ManagerId, TypeConstraintError};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ClientEvent {
@@ -164,6 +164,48 @@ impl From<String> for ClientEventType {
     }
 }
 
+/// Manager referenced by an `@email` or `@name` token of a comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mention {
+    Email(ManagerEmail),
+    Name(String),
+}
+
+/// Extracts the distinct mentions of `text` in order of appearance.
+///
+/// A mention is a whitespace-separated token starting with `@`, without its
+/// trailing punctuation. Tokens containing another `@` are read as emails and
+/// dropped when the email is invalid.
+#[must_use]
+pub fn parse_mentions(text: &str) -> Vec<Mention> {
+    let mut mentions = Vec::new();
+
+    for token in text.split_whitespace() {
+        let Some(target) = token.strip_prefix('@') else {
+            continue;
+        };
+        let target = target.trim_end_matches(|c: char| !c.is_alphanumeric());
+        if target.is_empty() {
+            continue;
+        }
+
+        let mention = if target.contains('@') {
+            match ManagerEmail::new(target) {
+                Ok(email) => Mention::Email(email),
+                Err(_) => continue,
+            }
+        } else {
+            Mention::Name(target.to_string())
+        };
+
+        if !mentions.contains(&mention) {
+            mentions.push(mention);
+        }
+    }
+
+    mentions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,4 +284,24 @@ mod tests {
         assert_eq!(event.event_data, json!({"msg": "hi"}));
         assert_eq!(event.created_at, created_at);
     }
+
+    #[test]
+    fn parse_mentions_reads_emails_and_names() {
+        let mentions = parse_mentions(
+            "@alice please follow up, cc @Bob@Example.com. @alice again, mail@host and @ alone",
+        );
+
+        assert_eq!(
+            mentions,
+            vec![
+                Mention::Name("alice".to_string()),
+                Mention::Email(ManagerEmail::new("bob@example.com").expect("valid email")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mentions_ignores_invalid_emails() {
+        assert!(parse_mentions("@x@-bad-.com and @@").is_empty());
+    }
 }
@@ -0,0 +1,123 @@
+//! Domain model for per-hub client contact requirements.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{ClientEmail, PhoneNumber};
+
+/// Contact details a hub requires on every client.
+///
+/// With neither flag set the policy falls back to "at least one of email or
+/// phone", which is the behaviour for hubs without a stored policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactPolicy {
+    pub require_email: bool,
+    pub require_phone: bool,
+}
+
+/// Single reason a client fails the hub [`ContactPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactPolicyViolation {
+    /// Neither email nor phone is present under the default policy.
+    MissingContact,
+    MissingEmail,
+    MissingPhone,
+}
+
+impl ContactPolicy {
+    /// Collects every violation for the given contact details.
+    ///
+    /// Returns an empty list when the contacts satisfy the policy.
+    pub fn violations(
+        &self,
+        email: Option<&ClientEmail>,
+        phone: Option<&PhoneNumber>,
+    ) -> Vec<ContactPolicyViolation> {
+        let mut violations = Vec::new();
+
+        if !self.require_email && !self.require_phone {
+            if email.is_none() && phone.is_none() {
+                violations.push(ContactPolicyViolation::MissingContact);
+            }
+            return violations;
+        }
+
+        if self.require_email && email.is_none() {
+            violations.push(ContactPolicyViolation::MissingEmail);
+        }
+        if self.require_phone && phone.is_none() {
+            violations.push(ContactPolicyViolation::MissingPhone);
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email() -> ClientEmail {
+        ClientEmail::new("client@example.com").expect("valid email")
+    }
+
+    fn phone() -> PhoneNumber {
+        PhoneNumber::new("+14155552671").expect("valid phone")
+    }
+
+    #[test]
+    fn default_policy_requires_at_least_one_contact() {
+        let policy = ContactPolicy::default();
+
+        assert!(policy.violations(Some(&email()), None).is_empty());
+        assert!(policy.violations(None, Some(&phone())).is_empty());
+        assert_eq!(
+            policy.violations(None, None),
+            vec![ContactPolicyViolation::MissingContact]
+        );
+    }
+
+    #[test]
+    fn require_email_policy_rejects_phone_only_clients() {
+        let policy = ContactPolicy {
+            require_email: true,
+            require_phone: false,
+        };
+
+        assert!(policy.violations(Some(&email()), None).is_empty());
+        assert_eq!(
+            policy.violations(None, Some(&phone())),
+            vec![ContactPolicyViolation::MissingEmail]
+        );
+    }
+
+    #[test]
+    fn require_phone_policy_rejects_email_only_clients() {
+        let policy = ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        };
+
+        assert!(policy.violations(None, Some(&phone())).is_empty());
+        assert_eq!(
+            policy.violations(Some(&email()), None),
+            vec![ContactPolicyViolation::MissingPhone]
+        );
+    }
+
+    #[test]
+    fn strict_policy_collects_every_missing_contact() {
+        let policy = ContactPolicy {
+            require_email: true,
+            require_phone: true,
+        };
+
+        assert!(policy.violations(Some(&email()), Some(&phone())).is_empty());
+        assert_eq!(
+            policy.violations(None, None),
+            vec![
+                ContactPolicyViolation::MissingEmail,
+                ContactPolicyViolation::MissingPhone,
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Domain model for per-hub CRM configuration.
+
+use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{HubId, ManagerEmail, NonEmptyString, PhoneRegion, TypeConstraintError};
+
+/// Largest page size a hub may configure.
+pub const MAX_ITEMS_PER_PAGE: u16 = 100;
+
+/// Behaviour a hub can tune for its own users.
+///
+/// Hubs without stored settings use [`HubSettings::defaults`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HubSettings {
+    pub hub_id: HubId,
+    /// Clients shown per page of the client list.
+    pub items_per_page: u16,
+    /// Sender name used for outbound emails instead of the service default.
+    pub email_sender_name: Option<NonEmptyString>,
+    pub allow_manager_self_assign: bool,
+    /// Region used to parse client phone numbers entered without a country code.
+    pub hub_default_phone_region: Option<PhoneRegion>,
+    /// Days client events are kept; `None` keeps them forever.
+    pub event_retention_days: Option<u32>,
+    /// Reply-To address of outbound client emails instead of the sending manager.
+    pub email_reply_to: Option<ManagerEmail>,
+}
+
+impl HubSettings {
+    /// Validates the page size and builds the settings.
+    pub fn new(
+        hub_id: HubId,
+        items_per_page: u16,
+        email_sender_name: Option<NonEmptyString>,
+        allow_manager_self_assign: bool,
+    ) -> Result<Self, TypeConstraintError> {
+        if items_per_page == 0 || items_per_page > MAX_ITEMS_PER_PAGE {
+            return Err(TypeConstraintError::InvalidValue(
+                "items per page".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            hub_id,
+            items_per_page,
+            email_sender_name,
+            allow_manager_self_assign,
+            hub_default_phone_region: None,
+            event_retention_days: None,
+            email_reply_to: None,
+        })
+    }
+
+    /// Sets the region used for phone numbers without a country code.
+    #[must_use]
+    pub fn with_default_phone_region(mut self, region: Option<PhoneRegion>) -> Self {
+        self.hub_default_phone_region = region;
+        self
+    }
+
+    /// Sets how many days client events are kept.
+    #[must_use]
+    pub fn with_event_retention_days(mut self, days: Option<u32>) -> Self {
+        self.event_retention_days = days;
+        self
+    }
+
+    /// Sets the Reply-To address of outbound client emails.
+    #[must_use]
+    pub fn with_email_reply_to(mut self, reply_to: Option<ManagerEmail>) -> Self {
+        self.email_reply_to = reply_to;
+        self
+    }
+
+    /// Settings of a hub that has not configured anything yet.
+    #[must_use]
+    pub fn defaults(hub_id: HubId) -> Self {
+        Self {
+            hub_id,
+            items_per_page: u16::try_from(DEFAULT_ITEMS_PER_PAGE).unwrap_or(MAX_ITEMS_PER_PAGE),
+            email_sender_name: None,
+            allow_manager_self_assign: false,
+            hub_default_phone_region: None,
+            event_retention_days: None,
+            email_reply_to: None,
+        }
+    }
+
+    /// Page size as used by list queries.
+    #[must_use]
+    pub fn page_size(&self) -> usize {
+        usize::from(self.items_per_page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hub_id() -> HubId {
+        HubId::new(1).expect("valid hub id")
+    }
+
+    #[test]
+    fn defaults_use_global_page_size() {
+        let settings = HubSettings::defaults(hub_id());
+
+        assert_eq!(settings.page_size(), DEFAULT_ITEMS_PER_PAGE);
+        assert!(settings.email_sender_name.is_none());
+        assert!(!settings.allow_manager_self_assign);
+        assert!(settings.hub_default_phone_region.is_none());
+        assert!(settings.event_retention_days.is_none());
+        assert!(settings.email_reply_to.is_none());
+    }
+
+    #[test]
+    fn new_rejects_out_of_range_page_sizes() {
+        assert!(HubSettings::new(hub_id(), 0, None, false).is_err());
+        assert!(HubSettings::new(hub_id(), MAX_ITEMS_PER_PAGE + 1, None, false).is_err());
+
+        let settings =
+            HubSettings::new(hub_id(), MAX_ITEMS_PER_PAGE, None, true).expect("valid settings");
+        assert_eq!(settings.page_size(), usize::from(MAX_ITEMS_PER_PAGE));
+    }
+}
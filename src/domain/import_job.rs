@@ -0,0 +1,247 @@
+//! Domain model tracking background CSV client imports.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{HubId, ImportJobId, TypeConstraintError};
+
+/// Lifecycle state of an [`ImportJob`].
+///
+/// Jobs move `Pending -> Running -> Completed`; a job that is not finished yet
+/// may also move to `Failed`. `Completed` and `Failed` are terminal.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ImportJobStatus {
+    /// Returns the value stored in the `import_jobs.status` column.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Whether the job has finished and will not change anymore.
+    #[must_use]
+    pub const fn is_terminal(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+
+    /// Whether moving from `self` to `next` is an allowed transition.
+    #[must_use]
+    pub const fn can_transition_to(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Pending, Self::Running)
+                | (Self::Pending, Self::Failed)
+                | (Self::Running, Self::Completed)
+                | (Self::Running, Self::Failed)
+        )
+    }
+}
+
+impl Display for ImportJobStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportJobStatus {
+    type Err = TypeConstraintError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(Self::Pending),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(TypeConstraintError::InvalidValue(
+                "import job status".to_string(),
+            )),
+        }
+    }
+}
+
+/// Progress record of a CSV client import running in the background.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ImportJob {
+    pub id: ImportJobId,
+    pub hub_id: HubId,
+    pub status: ImportJobStatus,
+    /// Parsed CSV rows, including rejected ones.
+    pub total_rows: usize,
+    /// Rows that created a new client.
+    pub inserted_rows: usize,
+    /// Rows that matched and updated an existing client.
+    pub updated_rows: usize,
+    /// Rows skipped because they violate the hub contact policy.
+    pub error_rows: usize,
+    /// User-facing reason of a `Failed` job.
+    pub error_message: Option<String>,
+    pub started_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl ImportJob {
+    /// Marks a pending job as running with the totals of the parsed file.
+    pub fn start(
+        &mut self,
+        total_rows: usize,
+        error_rows: usize,
+    ) -> Result<(), TypeConstraintError> {
+        self.transition(ImportJobStatus::Running)?;
+        self.total_rows = total_rows;
+        self.error_rows = error_rows;
+        Ok(())
+    }
+
+    /// Records how many rows have been inserted and updated so far.
+    pub fn record_progress(
+        &mut self,
+        inserted_rows: usize,
+        updated_rows: usize,
+    ) -> Result<(), TypeConstraintError> {
+        if self.status != ImportJobStatus::Running {
+            return Err(invalid_transition());
+        }
+        self.inserted_rows = inserted_rows;
+        self.updated_rows = updated_rows;
+        Ok(())
+    }
+
+    /// Marks a running job as successfully finished.
+    pub fn complete(&mut self, completed_at: NaiveDateTime) -> Result<(), TypeConstraintError> {
+        self.transition(ImportJobStatus::Completed)?;
+        self.completed_at = Some(completed_at);
+        Ok(())
+    }
+
+    /// Marks an unfinished job as failed with a user-facing reason.
+    pub fn fail(
+        &mut self,
+        message: impl Into<String>,
+        completed_at: NaiveDateTime,
+    ) -> Result<(), TypeConstraintError> {
+        self.transition(ImportJobStatus::Failed)?;
+        self.error_message = Some(message.into());
+        self.completed_at = Some(completed_at);
+        Ok(())
+    }
+
+    fn transition(&mut self, next: ImportJobStatus) -> Result<(), TypeConstraintError> {
+        if !self.status.can_transition_to(next) {
+            return Err(invalid_transition());
+        }
+        self.status = next;
+        Ok(())
+    }
+}
+
+fn invalid_transition() -> TypeConstraintError {
+    TypeConstraintError::InvalidValue("import job status transition".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn pending_job() -> ImportJob {
+        ImportJob {
+            id: ImportJobId::new(1).expect("valid job id"),
+            hub_id: HubId::new(1).expect("valid hub id"),
+            status: ImportJobStatus::Pending,
+            total_rows: 0,
+            inserted_rows: 0,
+            updated_rows: 0,
+            error_rows: 0,
+            error_message: None,
+            started_at: Utc::now().naive_utc(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn job_runs_through_to_completion() {
+        let mut job = pending_job();
+
+        job.start(3, 1).expect("pending job starts");
+        assert_eq!(job.status, ImportJobStatus::Running);
+        assert_eq!((job.total_rows, job.error_rows), (3, 1));
+
+        job.record_progress(2, 1)
+            .expect("running job records progress");
+        job.complete(Utc::now().naive_utc())
+            .expect("running job completes");
+
+        assert_eq!(job.status, ImportJobStatus::Completed);
+        assert_eq!((job.inserted_rows, job.updated_rows), (2, 1));
+        assert!(job.completed_at.is_some());
+    }
+
+    #[test]
+    fn unfinished_jobs_can_fail() {
+        let mut pending = pending_job();
+        pending
+            .fail("broken csv", Utc::now().naive_utc())
+            .expect("pending job fails");
+        assert_eq!(pending.status, ImportJobStatus::Failed);
+        assert_eq!(pending.error_message.as_deref(), Some("broken csv"));
+
+        let mut running = pending_job();
+        running.start(1, 0).expect("pending job starts");
+        running
+            .fail("limit", Utc::now().naive_utc())
+            .expect("running job fails");
+        assert_eq!(running.status, ImportJobStatus::Failed);
+    }
+
+    #[test]
+    fn terminal_jobs_reject_further_transitions() {
+        let mut job = pending_job();
+        job.start(1, 0).expect("pending job starts");
+        job.complete(Utc::now().naive_utc())
+            .expect("running job completes");
+
+        assert!(job.start(1, 0).is_err());
+        assert!(job.record_progress(1, 0).is_err());
+        assert!(job.fail("late", Utc::now().naive_utc()).is_err());
+        assert!(job.complete(Utc::now().naive_utc()).is_err());
+        assert_eq!(job.status, ImportJobStatus::Completed);
+    }
+
+    #[test]
+    fn pending_job_cannot_complete_or_record_progress() {
+        let mut job = pending_job();
+
+        assert!(job.record_progress(1, 0).is_err());
+        assert!(job.complete(Utc::now().naive_utc()).is_err());
+        assert_eq!(job.status, ImportJobStatus::Pending);
+    }
+
+    #[test]
+    fn status_round_trips_through_storage_value() {
+        for status in [
+            ImportJobStatus::Pending,
+            ImportJobStatus::Running,
+            ImportJobStatus::Completed,
+            ImportJobStatus::Failed,
+        ] {
+            assert_eq!(status.as_str().parse::<ImportJobStatus>().unwrap(), status);
+        }
+        assert!("unknown".parse::<ImportJobStatus>().is_err());
+    }
+}
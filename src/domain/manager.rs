@@ -4,9 +4,15 @@ use pushkind_common::domain::auth::AuthenticatedUser;
 use serde::{Deserialize, Serialize};
 
 use crate::domain::types::{
-    ClientId, HubId, ManagerEmail, ManagerId, ManagerName, TypeConstraintError,
+    ClientId, HubId, ManagerEmail, ManagerId, ManagerName, NonEmptyString, PhoneNumber,
+    TypeConstraintError,
 };
 
+/// Email of the per-hub placeholder manager that inherits the history of deleted managers.
+pub const SYSTEM_MANAGER_EMAIL: &str = "system@crm.pushkind.invalid";
+/// Display name of the per-hub system manager.
+pub const SYSTEM_MANAGER_NAME: &str = "System";
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Manager {
     pub id: ManagerId,
@@ -14,6 +20,12 @@ pub struct Manager {
     pub name: ManagerName,
     pub email: ManagerEmail,
     pub is_user: bool,
+    pub company: Option<NonEmptyString>,
+    pub phone: Option<PhoneNumber>,
+    /// Archived managers keep their history but are hidden from assignment lists.
+    pub is_active: bool,
+    /// Set once the manager edits their own name; login syncs keep it.
+    pub name_locked: bool,
 }
 
 impl Manager {
@@ -32,9 +44,39 @@ impl Manager {
             name,
             email,
             is_user,
+            company: None,
+            phone: None,
+            is_active: true,
+            name_locked: false,
         }
     }
 
+    /// Mark the manager as active or archived.
+    #[must_use]
+    pub fn with_active(mut self, is_active: bool) -> Self {
+        self.is_active = is_active;
+        self
+    }
+
+    /// Mark whether the manager's name was set by the manager themselves.
+    #[must_use]
+    pub fn with_name_locked(mut self, name_locked: bool) -> Self {
+        self.name_locked = name_locked;
+        self
+    }
+
+    /// Attach the optional company and phone to the manager.
+    #[must_use]
+    pub fn with_contact_details(
+        mut self,
+        company: Option<NonEmptyString>,
+        phone: Option<PhoneNumber>,
+    ) -> Self {
+        self.company = company;
+        self.phone = phone;
+        self
+    }
+
     /// Create a manager from raw values, validating identifiers and inputs.
     pub fn try_new(
         id: i32,
@@ -59,6 +101,10 @@ pub struct NewManager {
     pub name: ManagerName,
     pub email: ManagerEmail,
     pub is_user: bool,
+    /// Company the manager works for; `None` keeps the stored value on upsert.
+    pub company: Option<NonEmptyString>,
+    /// Contact phone; `None` keeps the stored value on upsert.
+    pub phone: Option<PhoneNumber>,
 }
 
 impl NewManager {
@@ -70,9 +116,33 @@ impl NewManager {
             name,
             email,
             is_user,
+            company: None,
+            phone: None,
         }
     }
 
+    /// Attach the optional company and phone to the new manager.
+    #[must_use]
+    pub fn with_contact_details(
+        mut self,
+        company: Option<NonEmptyString>,
+        phone: Option<PhoneNumber>,
+    ) -> Self {
+        self.company = company;
+        self.phone = phone;
+        self
+    }
+
+    /// The hub's system manager, used as author of orphaned history.
+    pub fn system(hub_id: HubId) -> Result<Self, TypeConstraintError> {
+        Ok(Self::new(
+            hub_id,
+            ManagerName::new(SYSTEM_MANAGER_NAME)?,
+            ManagerEmail::new(SYSTEM_MANAGER_EMAIL)?,
+            false,
+        ))
+    }
+
     /// Create a new manager from raw values, validating identifiers and inputs.
     pub fn try_new(
         hub_id: i32,
@@ -93,13 +163,32 @@ impl NewManager {
 pub struct UpdateManager {
     pub name: ManagerName,
     pub is_user: bool,
+    pub company: Option<NonEmptyString>,
+    pub phone: Option<PhoneNumber>,
 }
 
 impl UpdateManager {
     /// Create an update payload from already validated domain values.
     #[must_use]
     pub fn new(name: ManagerName, is_user: bool) -> Self {
-        Self { name, is_user }
+        Self {
+            name,
+            is_user,
+            company: None,
+            phone: None,
+        }
+    }
+
+    /// Attach the optional company and phone to the update payload.
+    #[must_use]
+    pub fn with_contact_details(
+        mut self,
+        company: Option<NonEmptyString>,
+        phone: Option<PhoneNumber>,
+    ) -> Self {
+        self.company = company;
+        self.phone = phone;
+        self
     }
 
     /// Create an update payload from raw values, validating inputs.
@@ -1,9 +1,18 @@
 //! Domain aggregates exposed by the CRM service layer.
 
+pub mod api_key;
+pub mod assignment_rule;
+pub mod audit;
 pub mod client;
 pub mod client_event;
+pub mod contact_policy;
+pub mod hub_settings;
+pub mod import_job;
 pub mod important_field;
 pub mod manager;
+pub mod note;
+pub mod segment;
 pub mod store_otp;
 pub mod store_session;
 pub mod types;
+pub mod webhook;
@@ -0,0 +1,128 @@
+//! Domain model for internal client notes kept apart from the event timeline.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::types::{
+    ClientId, ClientNoteId, CommentMessage, ManagerId, TypeConstraintError,
+};
+
+/// Internal note attached to a client by a manager.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClientNote {
+    pub id: ClientNoteId,
+    pub client_id: ClientId,
+    pub manager_id: ManagerId,
+    pub text: CommentMessage,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub is_pinned: bool,
+}
+
+impl ClientNote {
+    /// Create a trusted note from already validated domain values.
+    pub fn new(
+        id: ClientNoteId,
+        client_id: ClientId,
+        manager_id: ManagerId,
+        text: CommentMessage,
+        created_at: NaiveDateTime,
+        updated_at: NaiveDateTime,
+        is_pinned: bool,
+    ) -> Self {
+        Self {
+            id,
+            client_id,
+            manager_id,
+            text,
+            created_at,
+            updated_at,
+            is_pinned,
+        }
+    }
+
+    /// Create a note from raw values, validating identifiers and text.
+    pub fn try_new(
+        id: i32,
+        client_id: i32,
+        manager_id: i32,
+        text: String,
+        created_at: NaiveDateTime,
+        updated_at: NaiveDateTime,
+        is_pinned: bool,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self::new(
+            ClientNoteId::try_from(id)?,
+            ClientId::try_from(client_id)?,
+            ManagerId::try_from(manager_id)?,
+            CommentMessage::new(text)?,
+            created_at,
+            updated_at,
+            is_pinned,
+        ))
+    }
+}
+
+/// Data required to persist a new client note.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewClientNote {
+    pub client_id: ClientId,
+    pub manager_id: ManagerId,
+    pub text: CommentMessage,
+}
+
+impl NewClientNote {
+    /// Create a new note from already validated domain values.
+    #[must_use]
+    pub fn new(client_id: ClientId, manager_id: ManagerId, text: CommentMessage) -> Self {
+        Self {
+            client_id,
+            manager_id,
+            text,
+        }
+    }
+
+    /// Create a new note from raw values, validating identifiers and text.
+    pub fn try_new(
+        client_id: i32,
+        manager_id: i32,
+        text: String,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self::new(
+            ClientId::try_from(client_id)?,
+            ManagerId::try_from(manager_id)?,
+            CommentMessage::new(text)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_client_note_try_new_sanitizes_text() {
+        let note = NewClientNote::try_new(1, 2, "<script>x</script>Call back".to_string())
+            .expect("valid note");
+
+        assert_eq!(note.client_id.get(), 1);
+        assert_eq!(note.manager_id.get(), 2);
+        assert_eq!(note.text.as_str(), "Call back");
+    }
+
+    #[test]
+    fn new_client_note_try_new_rejects_empty_text() {
+        let err = NewClientNote::try_new(1, 2, "   ".to_string()).expect_err("empty text");
+        assert_eq!(err, TypeConstraintError::EmptyString);
+    }
+
+    #[test]
+    fn client_note_try_new_rejects_non_positive_ids() {
+        let now = chrono::DateTime::from_timestamp(0, 0)
+            .expect("valid timestamp")
+            .naive_utc();
+        let err = ClientNote::try_new(0, 1, 1, "Note".to_string(), now, now, false)
+            .expect_err("invalid id");
+        assert_eq!(err, TypeConstraintError::NonPositiveId);
+    }
+}
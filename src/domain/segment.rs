@@ -0,0 +1,142 @@
+//! Domain model for rule-based client segments.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::types::{HubId, NonEmptyString, SegmentId, TypeConstraintError};
+
+/// Named set of clients whose membership is computed from `filter_json`.
+///
+/// The filter has the shape `{"fields": {"<field>": "<value>", ...}}`; a
+/// client belongs to the segment when every listed custom field holds exactly
+/// the given value.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClientSegment {
+    pub id: SegmentId,
+    pub hub_id: HubId,
+    pub name: NonEmptyString,
+    pub filter_json: Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ClientSegment {
+    /// Create a segment from raw values, validating identifiers and the filter.
+    pub fn try_new(
+        id: i32,
+        hub_id: i32,
+        name: String,
+        filter_json: Value,
+        created_at: NaiveDateTime,
+        updated_at: NaiveDateTime,
+    ) -> Result<Self, TypeConstraintError> {
+        parse_field_matchers(&filter_json)?;
+
+        Ok(Self {
+            id: SegmentId::try_from(id)?,
+            hub_id: HubId::try_from(hub_id)?,
+            name: NonEmptyString::new(name)?,
+            filter_json,
+            created_at,
+            updated_at,
+        })
+    }
+
+    /// Returns the `(field, value)` pairs a member client must match.
+    #[must_use]
+    pub fn field_matchers(&self) -> Vec<(String, String)> {
+        parse_field_matchers(&self.filter_json).unwrap_or_default()
+    }
+}
+
+/// Data required to create or replace a segment definition.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewClientSegment {
+    pub hub_id: HubId,
+    pub name: NonEmptyString,
+    pub filter_json: Value,
+}
+
+impl NewClientSegment {
+    /// Create a segment definition, validating the filter shape.
+    pub fn try_new(
+        hub_id: HubId,
+        name: NonEmptyString,
+        filter_json: Value,
+    ) -> Result<Self, TypeConstraintError> {
+        parse_field_matchers(&filter_json)?;
+
+        Ok(Self {
+            hub_id,
+            name,
+            filter_json,
+        })
+    }
+}
+
+/// Extracts the field matchers from a segment filter.
+///
+/// Requires a non-empty `fields` object whose keys are non-blank and whose
+/// values are strings; field names and values are trimmed.
+pub fn parse_field_matchers(
+    filter_json: &Value,
+) -> Result<Vec<(String, String)>, TypeConstraintError> {
+    let invalid = || TypeConstraintError::InvalidValue("segment filter".to_string());
+
+    let fields = filter_json
+        .get("fields")
+        .and_then(Value::as_object)
+        .filter(|fields| !fields.is_empty())
+        .ok_or_else(invalid)?;
+
+    fields
+        .iter()
+        .map(|(field, value)| {
+            let field = field.trim();
+            let value = value.as_str().ok_or_else(invalid)?.trim();
+            if field.is_empty() {
+                return Err(invalid());
+            }
+            Ok((field.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_field_matchers_trims_fields_and_values() {
+        let matchers =
+            parse_field_matchers(&json!({"fields": {" Stage ": " Won ", "Tier": "VIP"}}))
+                .expect("valid filter");
+
+        assert_eq!(
+            matchers,
+            vec![
+                ("Stage".to_string(), "Won".to_string()),
+                ("Tier".to_string(), "VIP".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_field_matchers_rejects_malformed_filters() {
+        for filter in [
+            json!({}),
+            json!({"fields": {}}),
+            json!({"fields": ["Stage"]}),
+            json!({"fields": {"Stage": 1}}),
+            json!({"fields": {"  ": "Won"}}),
+        ] {
+            assert!(
+                parse_field_matchers(&filter).is_err(),
+                "filter {filter} should be rejected"
+            );
+        }
+    }
+}
@@ -6,10 +6,13 @@
 use std::{ops::Deref, str::FromStr};
 
 use ammonia;
-use phonenumber::{Mode, parse};
+use chrono::{NaiveDate, NaiveDateTime};
+use phonenumber::{Mode, country, parse};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
 use thiserror::Error;
+use url::{Host, Url};
 use uuid::Uuid;
 use validator::{ValidateEmail, ValidateUrl};
 
@@ -31,17 +34,46 @@ pub enum TypeConstraintError {
     /// Phone number did not meet expected format.
     #[error("invalid phone number")]
     InvalidPhone,
+    /// Phone region is not a known ISO 3166-1 alpha-2 code.
+    #[error("invalid phone region")]
+    InvalidPhoneRegion,
+    /// Locale is not a well-formed BCP 47 language tag.
+    #[error("invalid locale")]
+    InvalidLocale,
     /// Provided url failed format validation.
     #[error("invalid url address")]
     InvalidUrl,
     /// Provided uuid failed format validation.
     #[error("invalid uuid value")]
     InvalidUuid,
+    /// Provided url points at a loopback, private, or local-network host.
+    #[error("url points to an internal address")]
+    InternalAddress,
 }
 
 /// Normalizes and validates an email string.
+///
+/// Surrounding whitespace is trimmed and the address is split at the last `@`.
+/// The local part is lower-cased but otherwise kept verbatim, so plus-address
+/// tags survive. An internationalized domain is converted to its lower-case
+/// ASCII (punycode) form, so Unicode and punycode spellings of one domain
+/// normalize to the same string; ASCII domains are only lower-cased.
 fn normalize_email<S: Into<String>>(email: S) -> Result<String, TypeConstraintError> {
-    let normalized = email.into().trim().to_lowercase();
+    let email = email.into();
+    let (local, domain) = email
+        .trim()
+        .rsplit_once('@')
+        .ok_or(TypeConstraintError::InvalidEmail)?;
+    let domain = if domain.is_ascii() {
+        domain.to_lowercase()
+    } else {
+        match Host::parse(domain) {
+            Ok(Host::Domain(ascii)) => ascii,
+            _ => return Err(TypeConstraintError::InvalidEmail),
+        }
+    };
+
+    let normalized = format!("{}@{domain}", local.to_lowercase());
     if normalized.validate_email() {
         Ok(normalized)
     } else {
@@ -50,10 +82,16 @@ fn normalize_email<S: Into<String>>(email: S) -> Result<String, TypeConstraintEr
 }
 
 /// Macro to generate lightweight newtypes for positive identifiers.
+///
+/// Deserialization goes through `new`, so a non-positive id in a request path
+/// or payload is rejected before it reaches a service.
 macro_rules! id_newtype {
     ($name:ident, $doc:expr) => {
         #[doc = $doc]
-        #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+        #[derive(
+            Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+        )]
+        #[serde(try_from = "i32", into = "i32")]
         pub struct $name(i32);
 
         impl $name {
@@ -91,6 +129,30 @@ macro_rules! id_newtype {
                 value.0
             }
         }
+
+        impl PartialEq<i32> for $name {
+            fn eq(&self, other: &i32) -> bool {
+                self.get() == *other
+            }
+        }
+
+        impl PartialEq<$name> for i32 {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.get()
+            }
+        }
+
+        impl PartialOrd<i32> for $name {
+            fn partial_cmp(&self, other: &i32) -> Option<std::cmp::Ordering> {
+                self.get().partial_cmp(other)
+            }
+        }
+
+        impl PartialOrd<$name> for i32 {
+            fn partial_cmp(&self, other: &$name) -> Option<std::cmp::Ordering> {
+                self.partial_cmp(&other.get())
+            }
+        }
     };
 }
 
@@ -98,6 +160,16 @@ id_newtype!(ManagerId, "Unique identifier for a manager.");
 id_newtype!(HubId, "Unique identifier for a hub.");
 id_newtype!(ClientId, "Unique identifier for a customer.");
 id_newtype!(ClientEventId, "Unique identifier for a client event.");
+id_newtype!(
+    ClientNoteId,
+    "Unique identifier for an internal client note."
+);
+id_newtype!(AuditEntryId, "Unique identifier for an audit log entry.");
+id_newtype!(WebhookId, "Unique identifier for a registered webhook.");
+id_newtype!(ApiKeyId, "Unique identifier for an integration API key.");
+id_newtype!(SegmentId, "Unique identifier for a client segment.");
+id_newtype!(ImportJobId, "Unique identifier for a CSV import job.");
+id_newtype!(RuleId, "Unique identifier for a manager assignment rule.");
 
 /// Lower-cased and validated email address.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -149,7 +221,59 @@ impl From<ManagerEmail> for String {
     }
 }
 
+/// Implements comparisons between an email newtype and plain strings.
+///
+/// The string side is compared as is against the stored normalized address, so
+/// `ManagerEmail::new("A@B.com")? == "a@b.com"` holds but `== "A@B.com"` does not.
+macro_rules! email_string_eq {
+    ($name:ident) => {
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$name> for str {
+            fn eq(&self, other: &$name) -> bool {
+                self == other.0
+            }
+        }
+
+        impl PartialEq<$name> for &str {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialEq<$name> for String {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+    };
+}
+
+email_string_eq!(ManagerEmail);
+
 /// General email wrapper for client contact addresses.
+///
+/// Normalized like [`ManagerEmail`]: trimmed and lower-cased, with plus-address
+/// tags kept (`User+Tag@Example.COM` becomes `user+tag@example.com`) and
+/// internationalized domains stored in punycode (`ivan@пример.рф` becomes
+/// `ivan@xn--e1afmkfd.xn--p1ai`). Lookups such as `get_client_by_email` match
+/// exactly, so every address must pass through [`ClientEmail::new`].
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct ClientEmail(String);
 
@@ -199,6 +323,8 @@ impl From<ClientEmail> for String {
     }
 }
 
+email_string_eq!(ClientEmail);
+
 /// Wrapper for non-empty, trimmed strings.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct NonEmptyString(String);
@@ -324,6 +450,24 @@ non_empty_string_newtype!(
     "Customer name wrapper enforcing non-empty values."
 );
 
+impl ClientName {
+    /// Returns the name lower-cased with whitespace runs collapsed, so
+    /// spellings differing only in case or spacing compare equal.
+    #[must_use]
+    pub fn match_key(&self) -> String {
+        client_name_match_key(&self.0)
+    }
+}
+
+/// Normalizes a raw client name the way [`ClientName::match_key`] does.
+#[must_use]
+pub fn client_name_match_key(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 non_empty_string_newtype!(
     ImportantFieldName,
     "Configured important field label enforcing trimmed, non-empty values."
@@ -335,6 +479,9 @@ non_empty_string_newtype!(
 );
 
 /// Event message wrapper enforcing trimmed, non-empty values.
+///
+/// The value is HTML sanitized with `ammonia` on construction, like inbound
+/// replies, so it is safe to render as stored.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CommentMessage(String);
 
@@ -351,6 +498,15 @@ impl CommentMessage {
         &self.0
     }
 
+    /// Returns the message without markup, for plain-text email bodies.
+    ///
+    /// Tags are dropped and every HTML entity, named or numeric, is decoded.
+    #[must_use]
+    pub fn to_plain_text(&self) -> String {
+        let text = ammonia::Builder::empty().clean(&self.0).to_string();
+        html_escape::decode_html_entities(&text).into_owned()
+    }
+
     /// Consume the wrapper and return the owned string.
     pub fn into_inner(self) -> String {
         self.0
@@ -392,14 +548,153 @@ non_empty_string_newtype!(
 
 /// Normalizes a phone number string to E.164 format.
 pub fn normalize_phone_to_e164(value: &str) -> Result<String, TypeConstraintError> {
+    normalize_phone(value, None)
+}
+
+/// Parses `value`, resolving numbers without a country code against `region`.
+fn normalize_phone(
+    value: &str,
+    region: Option<country::Id>,
+) -> Result<String, TypeConstraintError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         return Err(TypeConstraintError::EmptyString);
     }
-    let parsed = parse(None, trimmed).map_err(|_| TypeConstraintError::InvalidPhone)?;
+    let parsed = parse(region, trimmed).map_err(|_| TypeConstraintError::InvalidPhone)?;
     Ok(parsed.format().mode(Mode::E164).to_string())
 }
 
+/// Upper-cased ISO 3166-1 alpha-2 region (e.g. `US`, `DE`) used to parse
+/// phone numbers entered without a country code.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
+pub struct PhoneRegion(String);
+
+impl PhoneRegion {
+    /// Trims and upper-cases the code, accepting only regions known to the
+    /// phone number metadata.
+    pub fn new<S: Into<String>>(value: S) -> Result<Self, TypeConstraintError> {
+        let code = value.into().trim().to_ascii_uppercase();
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(TypeConstraintError::InvalidPhoneRegion);
+        }
+        code.parse::<country::Id>()
+            .map_err(|_| TypeConstraintError::InvalidPhoneRegion)?;
+        Ok(Self(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    fn country(&self) -> Result<country::Id, TypeConstraintError> {
+        self.0
+            .parse()
+            .map_err(|_| TypeConstraintError::InvalidPhoneRegion)
+    }
+}
+
+impl Display for PhoneRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for PhoneRegion {
+    type Error = TypeConstraintError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<&str> for PhoneRegion {
+    type Error = TypeConstraintError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<PhoneRegion> for String {
+    fn from(value: PhoneRegion) -> Self {
+        value.0
+    }
+}
+
+/// Locale used when the configuration does not set one.
+pub const DEFAULT_LOCALE: &str = "ru-RU";
+
+/// Well-formed BCP 47 language tag (e.g. `ru-RU`, `de`) that browsers accept
+/// for `Intl` date formatting.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
+pub struct Locale(String);
+
+impl Locale {
+    /// Trims the tag and checks its syntax: a 2-3 or 5-8 letter language
+    /// followed by subtags of 1-8 letters or digits, where a single-character
+    /// subtag must be followed by another subtag. `ru_RU` is rejected.
+    pub fn new<S: Into<String>>(value: S) -> Result<Self, TypeConstraintError> {
+        let tag = value.into().trim().to_string();
+        let mut subtags = tag.split('-');
+        let language = subtags.next().unwrap_or_default();
+        if !matches!(language.len(), 2 | 3 | 5..=8)
+            || !language.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(TypeConstraintError::InvalidLocale);
+        }
+        let mut needs_subtag = false;
+        for subtag in subtags {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(TypeConstraintError::InvalidLocale);
+            }
+            needs_subtag = subtag.len() == 1;
+        }
+        if needs_subtag {
+            return Err(TypeConstraintError::InvalidLocale);
+        }
+        Ok(Self(tag))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self(DEFAULT_LOCALE.to_string())
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = TypeConstraintError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Locale> for String {
+    fn from(value: Locale) -> Self {
+        value.0
+    }
+}
+
 /// Normalized phone number wrapper (expected E.164).
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct PhoneNumber(String);
@@ -411,6 +706,13 @@ impl PhoneNumber {
         Ok(Self(normalized))
     }
 
+    /// Like [`PhoneNumber::new`], but numbers without a country code are read
+    /// as local numbers of `region`.
+    pub fn with_region(value: &str, region: &PhoneRegion) -> Result<Self, TypeConstraintError> {
+        let normalized = normalize_phone(value, Some(region.country()?))?;
+        Ok(Self(normalized))
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -418,6 +720,24 @@ impl PhoneNumber {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    /// Formats the number for users in `region`: numbers of that region in
+    /// national form, every other number in international form.
+    #[must_use]
+    pub fn format_for_region(&self, region: Option<&PhoneRegion>) -> String {
+        let Ok(parsed) = parse(None, &self.0) else {
+            return self.0.clone();
+        };
+        let local = region
+            .and_then(|region| region.country().ok())
+            .is_some_and(|id| parsed.country().id() == Some(id));
+        let mode = if local {
+            Mode::National
+        } else {
+            Mode::International
+        };
+        parsed.format().mode(mode).to_string()
+    }
 }
 
 impl Display for PhoneNumber {
@@ -448,20 +768,88 @@ impl From<PhoneNumber> for String {
     }
 }
 
+non_empty_string_newtype!(
+    RawPhone,
+    "Phone contact kept verbatim (trimmed) when it fails strict [`PhoneNumber`] parsing."
+);
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 /// Non-empty, trimmed menu URL.
 pub struct AttachmentUrl(String);
 
+/// Returns `true` when `ip` is routable on the public internet.
+///
+/// Rejects loopback, unspecified, private (10/8, 172.16/12, 192.168/16),
+/// shared (100.64/10), link-local (169.254/16, fe80::/10), unique local
+/// (fc00::/7), broadcast, multicast, documentation and reserved addresses,
+/// including IPv4 addresses mapped into IPv6.
+#[must_use]
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            let shared = first == 100 && (second & 0xc0) == 64;
+            let reserved = first == 0 || first >= 240;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || shared
+                || reserved)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                let documentation = ip.segments()[..2] == [0x2001, 0x0db8];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local()
+                    || documentation)
+            }
+        },
+    }
+}
+
+/// Returns `true` for hosts that resolve to the local machine or a private network.
+///
+/// Domain names are only checked by suffix here; callers that connect must
+/// also vet the addresses the name resolves to.
+fn is_internal_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain == "local"
+                || domain.ends_with(".local")
+        }
+        Host::Ipv4(ip) => !is_public_ip(IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => !is_public_ip(IpAddr::V6(*ip)),
+    }
+}
+
 impl AttachmentUrl {
-    /// Ensures a trimmed menu URL is non-empty before wrapping.
+    /// Ensures a trimmed menu URL is non-empty, well-formed, and not pointing at an
+    /// internal host before wrapping.
     pub fn new<S: Into<String>>(value: S) -> Result<Self, TypeConstraintError> {
         let url = NonEmptyString::new(value)?;
 
         if !url.as_str().validate_url() {
-            Err(TypeConstraintError::InvalidUrl)
-        } else {
-            Ok(Self(url.into_inner()))
+            return Err(TypeConstraintError::InvalidUrl);
         }
+
+        let parsed = Url::parse(url.as_str()).map_err(|_| TypeConstraintError::InvalidUrl)?;
+        let host = parsed.host().ok_or(TypeConstraintError::InvalidUrl)?;
+        if is_internal_host(&host) {
+            return Err(TypeConstraintError::InternalAddress);
+        }
+
+        Ok(Self(url.into_inner()))
     }
 
     /// Borrow the menu URL.
@@ -503,6 +891,110 @@ impl From<AttachmentUrl> for String {
     }
 }
 
+/// MIME types accepted as attachments of outbound emails.
+pub const ALLOWED_ATTACHMENT_MIME_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/zip",
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "text/csv",
+    "text/plain",
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Lower-cased MIME type of an email attachment, without parameters.
+pub struct AttachmentMime(String);
+
+impl AttachmentMime {
+    /// Strips parameters such as `charset` and ensures the type is one of
+    /// [`ALLOWED_ATTACHMENT_MIME_TYPES`] before wrapping.
+    pub fn new<S: Into<String>>(value: S) -> Result<Self, TypeConstraintError> {
+        let value = value.into();
+        let essence = value
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        if !ALLOWED_ATTACHMENT_MIME_TYPES.contains(&essence.as_str()) {
+            return Err(TypeConstraintError::InvalidValue(
+                "attachment mime type".to_string(),
+            ));
+        }
+
+        Ok(Self(essence))
+    }
+
+    /// Borrow the MIME type.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Extract the owned MIME type.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Display for AttachmentMime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Public `http`/`https` URL receiving webhook deliveries.
+pub struct WebhookUrl(String);
+
+impl WebhookUrl {
+    /// Ensures a trimmed URL is well-formed, uses `http` or `https`, and does not
+    /// point at an internal host before wrapping.
+    pub fn new<S: Into<String>>(value: S) -> Result<Self, TypeConstraintError> {
+        let url = NonEmptyString::new(value)?;
+
+        let parsed = Url::parse(url.as_str()).map_err(|_| TypeConstraintError::InvalidUrl)?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(TypeConstraintError::InvalidUrl);
+        }
+        let host = parsed.host().ok_or(TypeConstraintError::InvalidUrl)?;
+        if is_internal_host(&host) {
+            return Err(TypeConstraintError::InternalAddress);
+        }
+
+        Ok(Self(url.into_inner()))
+    }
+
+    /// Borrow the webhook URL.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Extract the owned webhook URL.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Display for WebhookUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for WebhookUrl {
+    type Error = TypeConstraintError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PublicId(Uuid);
 
@@ -546,3 +1038,628 @@ impl Default for PublicId {
         Self::new()
     }
 }
+
+/// How a bulk client import treats rows matching an existing client of the hub.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictStrategy {
+    /// Update the existing client with the imported row.
+    #[default]
+    Upsert,
+    /// Keep the existing client and drop the imported row.
+    Skip,
+    /// Abort the whole import on the first matching row.
+    Error,
+}
+
+impl ImportConflictStrategy {
+    /// Returns the value submitted by the upload form.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Upsert => "upsert",
+            Self::Skip => "skip",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl Display for ImportConflictStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a bulk client import finds the existing client a row refers to.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchBy {
+    /// Match on the email or phone of the row.
+    #[default]
+    Contact,
+    /// Match on the normalized name within the hub, then on email or phone.
+    ///
+    /// Rows whose name matches several clients are skipped and reported.
+    Name,
+}
+
+impl MatchBy {
+    /// Returns the value submitted by the upload form.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Contact => "contact",
+            Self::Name => "name",
+        }
+    }
+}
+
+impl Display for MatchBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serialization formats of the clients export.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row and one column per custom field.
+    Csv,
+    /// A single pretty-printed JSON array of client objects.
+    Json,
+    /// Newline-delimited JSON, one client object per line.
+    NdJson,
+}
+
+/// Inclusive range of calendar days; either bound may be left open.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Ensures `from` is not after `to` before wrapping.
+    pub fn new(
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Self, TypeConstraintError> {
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err(TypeConstraintError::InvalidValue("date range".to_string()));
+        }
+
+        Ok(Self { from, to })
+    }
+
+    /// First day of the range, if bounded.
+    #[must_use]
+    pub const fn from(&self) -> Option<NaiveDate> {
+        self.from
+    }
+
+    /// Last day of the range, if bounded.
+    #[must_use]
+    pub const fn to(&self) -> Option<NaiveDate> {
+        self.to
+    }
+
+    /// Whether `at` falls on one of the days of the range.
+    #[must_use]
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let day = at.date();
+        self.from.is_none_or(|from| from <= day) && self.to.is_none_or(|to| day <= to)
+    }
+}
+
+/// Email address as written to logs: at most its first two characters
+/// followed by `***@<domain>`, e.g. `al***@example.com`.
+///
+/// At least one character of the local part is always hidden.
+#[derive(Clone, Copy, Debug)]
+pub struct MaskedEmail<'a>(&'a str);
+
+impl<'a> MaskedEmail<'a> {
+    pub fn new(email: &'a str) -> Self {
+        Self(email)
+    }
+}
+
+impl Display for MaskedEmail<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some((local, domain)) = self.0.trim().rsplit_once('@') else {
+            return f.write_str("***");
+        };
+        let shown = local.chars().count().saturating_sub(1).min(2);
+        let prefix: String = local.chars().take(shown).collect();
+        write!(f, "{prefix}***@{domain}")
+    }
+}
+
+/// Phone number as written to logs: only its last four digits, e.g. `***2671`.
+///
+/// Numbers with four digits or fewer are hidden entirely.
+#[derive(Clone, Copy, Debug)]
+pub struct MaskedPhone<'a>(&'a str);
+
+impl<'a> MaskedPhone<'a> {
+    pub fn new(phone: &'a str) -> Self {
+        Self(phone)
+    }
+}
+
+impl Display for MaskedPhone<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits: Vec<char> = self.0.chars().filter(char::is_ascii_digit).collect();
+        if digits.len() <= 4 {
+            return f.write_str("***");
+        }
+        let last_four: String = digits[digits.len() - 4..].iter().collect();
+        write!(f, "***{last_four}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masked_email_keeps_two_characters_and_the_domain() {
+        let cases = [
+            ("alice@example.com", "al***@example.com"),
+            ("bob@example.com", "bo***@example.com"),
+            ("ab@example.com", "a***@example.com"),
+            ("a@example.com", "***@example.com"),
+            ("@example.com", "***@example.com"),
+            ("  Анна@пример.рф ", "Ан***@пример.рф"),
+            ("not-an-email", "***"),
+            ("", "***"),
+        ];
+
+        for (email, masked) in cases {
+            assert_eq!(MaskedEmail::new(email).to_string(), masked, "{email}");
+        }
+    }
+
+    #[test]
+    fn masked_phone_keeps_the_last_four_digits() {
+        let cases = [
+            ("+14155552671", "***2671"),
+            ("+1 (415) 555-2671", "***2671"),
+            ("12345", "***2345"),
+            ("1234", "***"),
+            ("12", "***"),
+            ("", "***"),
+        ];
+
+        for (phone, masked) in cases {
+            assert_eq!(MaskedPhone::new(phone).to_string(), masked, "{phone}");
+        }
+    }
+
+    #[test]
+    fn client_name_match_key_ignores_case_and_spacing() {
+        let name = ClientName::new("  Анна   Смирнова ").expect("valid name");
+
+        assert_eq!(name.match_key(), "анна смирнова");
+        assert_eq!(
+            client_name_match_key("АННА\tСмирнова"),
+            client_name_match_key("анна смирнова")
+        );
+        assert_ne!(
+            client_name_match_key("Анна"),
+            client_name_match_key("Анна С.")
+        );
+    }
+
+    #[test]
+    fn ids_reject_non_positive_values_when_deserialized() {
+        assert_eq!(
+            serde_json::from_str::<ClientId>("7").expect("valid client id"),
+            ClientId::new(7).unwrap()
+        );
+        assert!(serde_json::from_str::<ClientId>("0").is_err());
+        assert!(serde_json::from_str::<HubId>("-1").is_err());
+        assert_eq!(serde_json::to_string(&HubId::new(3).unwrap()).unwrap(), "3");
+    }
+
+    #[test]
+    fn ids_sort_by_their_raw_value() {
+        let mut ids = [3, 1, 2].map(|id| ClientId::new(id).expect("valid client id"));
+
+        ids.sort();
+
+        assert_eq!(ids.map(ClientId::get), [1, 2, 3]);
+        assert!(ManagerId::new(1).unwrap() < ManagerId::new(2).unwrap());
+        assert_eq!(
+            [HubId::new(7).unwrap(), HubId::new(4).unwrap()]
+                .iter()
+                .max(),
+            Some(&HubId::new(7).unwrap())
+        );
+    }
+
+    #[test]
+    fn ids_compare_with_raw_i32_in_both_directions() {
+        let low = ClientId::new(2).expect("valid client id");
+        let high = ClientId::new(5).expect("valid client id");
+
+        assert!(low < 3 && 3 < high);
+        assert!(low < high);
+        assert!(0 < low && high >= 5 && 5 <= high);
+        assert_eq!(low, 2);
+        assert_eq!(5, high);
+    }
+
+    #[test]
+    fn comment_message_strips_script_tags() {
+        let message =
+            CommentMessage::new("<p>Hi</p><script>alert('x')</script><img src=x onerror=alert(1)>")
+                .expect("valid message");
+
+        assert!(!message.as_str().contains("<script"));
+        assert!(!message.as_str().contains("alert"));
+        assert!(message.as_str().starts_with("<p>Hi</p>"));
+        assert!(CommentMessage::new("<script>alert('x')</script>").is_err());
+    }
+
+    #[test]
+    fn comment_message_plain_text_drops_markup() {
+        let message =
+            CommentMessage::new("<b>Tom</b> &amp; Jerry <i>1 &lt; 2</i>").expect("valid message");
+
+        assert_eq!(message.to_plain_text(), "Tom & Jerry 1 < 2");
+    }
+
+    #[test]
+    fn comment_message_plain_text_decodes_named_and_numeric_entities() {
+        let message = CommentMessage::new("&quot;Acme&quot;&nbsp;&#169; 2026 &mdash; &#x2713;")
+            .expect("valid message");
+
+        assert_eq!(message.to_plain_text(), "\"Acme\"\u{a0}© 2026 — ✓");
+    }
+
+    #[test]
+    fn attachment_url_accepts_public_urls() {
+        for url in [
+            "https://example.com/doc.pdf",
+            "http://files.example.org:8080/a?b=c",
+            "https://8.8.8.8/file",
+            "https://172.32.0.1/file",
+            "https://[2001:db8::1]/file",
+        ] {
+            assert!(AttachmentUrl::new(url).is_ok(), "{url} should be accepted");
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_localhost() {
+        for url in [
+            "http://localhost/admin",
+            "http://LOCALHOST:8080/",
+            "http://api.localhost/",
+        ] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_loopback_range() {
+        for url in ["http://127.0.0.1/", "http://127.255.0.9:9000/x"] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_ten_range() {
+        assert_eq!(
+            AttachmentUrl::new("http://10.1.2.3/"),
+            Err(TypeConstraintError::InternalAddress)
+        );
+    }
+
+    #[test]
+    fn attachment_url_rejects_172_16_to_31_range() {
+        for url in ["http://172.16.0.1/", "http://172.31.255.254/"] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_192_168_range() {
+        assert_eq!(
+            AttachmentUrl::new("https://192.168.1.10/file"),
+            Err(TypeConstraintError::InternalAddress)
+        );
+    }
+
+    #[test]
+    fn attachment_url_rejects_ipv6_loopback() {
+        for url in ["http://[::1]/", "http://[::ffff:127.0.0.1]/"] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_shared_link_local_and_unique_local_ranges() {
+        for url in [
+            "http://100.64.0.1/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://0.0.0.0/",
+        ] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn is_public_ip_accepts_only_globally_routable_addresses() {
+        for ip in ["93.184.216.34", "100.128.0.1", "2606:2800:220:1::1"] {
+            assert!(is_public_ip(ip.parse().expect("valid ip")), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "100.127.255.255",
+            "169.254.0.1",
+            "192.0.2.1",
+            "255.255.255.255",
+            "::1",
+            "fc00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().expect("valid ip")), "{ip}");
+        }
+    }
+
+    #[test]
+    fn attachment_url_rejects_local_tld() {
+        for url in ["http://printer.local/", "http://nas.LOCAL./share"] {
+            assert_eq!(
+                AttachmentUrl::new(url),
+                Err(TypeConstraintError::InternalAddress),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn webhook_url_requires_public_http_url() {
+        assert!(WebhookUrl::new("https://hooks.example.com/crm").is_ok());
+        assert_eq!(
+            WebhookUrl::new("ftp://hooks.example.com/crm"),
+            Err(TypeConstraintError::InvalidUrl)
+        );
+        assert_eq!(
+            WebhookUrl::new("http://127.0.0.1:8080/hook"),
+            Err(TypeConstraintError::InternalAddress)
+        );
+    }
+
+    #[test]
+    fn client_email_keeps_plus_tag_and_lowercases_domain() {
+        let email = ClientEmail::new("  user+tag@Example.COM ").expect("valid email");
+
+        assert_eq!(email.as_str(), "user+tag@example.com");
+    }
+
+    #[test]
+    fn client_email_normalizes_idn_domain_to_punycode() {
+        let unicode = ClientEmail::new("ivan@пример.рф").expect("valid email");
+        let upper = ClientEmail::new("Ivan@ПРИМЕР.РФ").expect("valid email");
+        let punycode = ClientEmail::new("ivan@xn--e1afmkfd.xn--p1ai").expect("valid email");
+
+        assert_eq!(unicode.as_str(), "ivan@xn--e1afmkfd.xn--p1ai");
+        assert_eq!(unicode, upper);
+        assert_eq!(unicode, punycode);
+    }
+
+    #[test]
+    fn client_email_rejects_invalid_addresses() {
+        for email in ["", "user", "user@", "@example.com", "user@exa mple.com"] {
+            assert_eq!(
+                ClientEmail::new(email),
+                Err(TypeConstraintError::InvalidEmail),
+                "{email:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn manager_email_uses_the_same_normalization() {
+        let manager = ManagerEmail::new("Rep+crm@Пример.рф").expect("valid email");
+        let client = ClientEmail::new("rep+crm@xn--e1afmkfd.xn--p1ai").expect("valid email");
+
+        assert_eq!(manager.as_str(), client.as_str());
+    }
+
+    #[test]
+    fn emails_compare_with_plain_strings() {
+        let owned = String::from("foo@bar.com");
+        let manager = ManagerEmail::new("foo@bar.com").unwrap();
+        assert!(manager == "foo@bar.com");
+        assert!("foo@bar.com" == manager);
+        assert!(manager == *"foo@bar.com");
+        assert!(manager == owned);
+        assert!(owned == manager);
+        assert!(manager != "bar@foo.com");
+
+        // Only the newtype side is normalized.
+        let client = ClientEmail::new("Foo@Bar.com").unwrap();
+        assert!(client == "foo@bar.com");
+        assert!("foo@bar.com" == client);
+        assert!(client != "Foo@Bar.com");
+        assert!(client == owned);
+        assert!(owned == client);
+    }
+
+    #[test]
+    fn equal_manager_emails_deduplicate_in_hash_sets() {
+        let emails: std::collections::HashSet<ManagerEmail> = [
+            ManagerEmail::new("foo@bar.com").unwrap(),
+            ManagerEmail::new(" Foo@Bar.com ").unwrap(),
+            ManagerEmail::new("baz@bar.com").unwrap(),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(emails.len(), 2);
+        assert!(emails.contains(&ManagerEmail::new("foo@bar.com").unwrap()));
+    }
+
+    #[test]
+    fn locale_accepts_only_well_formed_language_tags() {
+        for tag in ["ru-RU", "de", "zh-Hans-CN", "en-US-x-twain", "sr-Latn"] {
+            assert_eq!(Locale::new(tag).expect("valid locale").as_str(), tag);
+        }
+        assert_eq!(Locale::new(" en ").expect("valid locale").as_str(), "en");
+        for tag in [
+            "",
+            "ru_RU",
+            "r",
+            "russian1",
+            "de-",
+            "de--DE",
+            "en-x",
+            "en-toolongtag",
+        ] {
+            assert_eq!(
+                Locale::new(tag),
+                Err(TypeConstraintError::InvalidLocale),
+                "{tag:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn phone_region_is_validated_and_upper_cased() {
+        assert_eq!(
+            PhoneRegion::new(" de ").expect("valid region").as_str(),
+            "DE"
+        );
+        for region in ["", "D", "DEU", "1A", "XX"] {
+            assert_eq!(
+                PhoneRegion::new(region),
+                Err(TypeConstraintError::InvalidPhoneRegion),
+                "{region:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn phone_with_region_parses_us_local_number() {
+        let region = PhoneRegion::new("US").expect("valid region");
+
+        let phone = PhoneNumber::with_region("(415) 555-2671", &region).expect("valid phone");
+
+        assert_eq!(phone.as_str(), "+14155552671");
+        assert!(PhoneNumber::new("(415) 555-2671").is_err());
+    }
+
+    #[test]
+    fn phone_with_region_parses_de_local_number() {
+        let region = PhoneRegion::new("DE").expect("valid region");
+
+        let phone = PhoneNumber::with_region("030 12345678", &region).expect("valid phone");
+
+        assert_eq!(phone.as_str(), "+493012345678");
+    }
+
+    #[test]
+    fn phone_with_region_keeps_explicit_country_code() {
+        let region = PhoneRegion::new("DE").expect("valid region");
+
+        let phone = PhoneNumber::with_region("+1 415 555 2671", &region).expect("valid phone");
+
+        assert_eq!(phone.as_str(), "+14155552671");
+    }
+
+    #[test]
+    fn phone_format_for_region_uses_national_form_for_local_numbers() {
+        let phone = PhoneNumber::new("+493012345678").expect("valid phone");
+        let germany = PhoneRegion::new("DE").expect("valid region");
+        let us = PhoneRegion::new("US").expect("valid region");
+
+        assert_eq!(phone.format_for_region(Some(&germany)), "030 12345678");
+        assert_eq!(phone.format_for_region(Some(&us)), "+49 30 12345678");
+        assert_eq!(phone.format_for_region(None), "+49 30 12345678");
+    }
+
+    #[test]
+    fn raw_phone_keeps_numbers_rejected_by_strict_parsing() {
+        assert!(PhoneNumber::new("12-34").is_err());
+
+        let raw = RawPhone::new(" 12-34 ").expect("non-empty raw phone");
+
+        assert_eq!(raw.as_str(), "12-34");
+        assert!(RawPhone::new("   ").is_err());
+    }
+
+    #[test]
+    fn attachment_mime_strips_parameters_and_rejects_unknown_types() {
+        let mime = AttachmentMime::new("Text/Plain; charset=utf-8").expect("allowed type");
+
+        assert_eq!(mime.as_str(), "text/plain");
+        assert!(AttachmentMime::new("application/x-msdownload").is_err());
+        assert!(AttachmentMime::new("").is_err());
+    }
+
+    #[test]
+    fn date_range_includes_both_bounds_and_rejects_reversed_ones() {
+        let day = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date");
+        let range = DateRange::new(Some(day("2026-01-01")), Some(day("2026-01-31")))
+            .expect("ordered range");
+
+        assert!(range.contains(day("2026-01-01").and_hms_opt(0, 0, 0).expect("valid time")));
+        assert!(
+            range.contains(
+                day("2026-01-31")
+                    .and_hms_opt(23, 59, 59)
+                    .expect("valid time")
+            )
+        );
+        assert!(!range.contains(day("2026-02-01").and_hms_opt(0, 0, 0).expect("valid time")));
+        assert!(
+            DateRange::default()
+                .contains(day("1999-12-31").and_hms_opt(0, 0, 0).expect("valid time"))
+        );
+        assert!(DateRange::new(Some(day("2026-02-01")), Some(day("2026-01-01"))).is_err());
+    }
+
+    #[test]
+    fn export_format_reads_lowercase_names() {
+        assert_eq!(
+            serde_json::from_str::<ExportFormat>(r#""ndjson""#).expect("known format"),
+            ExportFormat::NdJson
+        );
+        assert_eq!(
+            serde_json::from_str::<ExportFormat>(r#""csv""#).expect("known format"),
+            ExportFormat::Csv
+        );
+        assert!(serde_json::from_str::<ExportFormat>(r#""xml""#).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Domain model for webhooks notified about new client events.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::client_event::ClientEventType;
+use crate::domain::types::{HubId, TypeConstraintError, WebhookId, WebhookUrl};
+
+/// Endpoint registered by a hub to receive client events.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    pub id: WebhookId,
+    pub hub_id: HubId,
+    pub url: WebhookUrl,
+    /// Only events of this type are delivered; `None` delivers every event.
+    pub event_type: Option<ClientEventType>,
+    /// Key used to sign delivery bodies with HMAC-SHA256.
+    pub secret: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl Webhook {
+    /// Create a webhook from raw values, validating identifiers and the URL.
+    pub fn try_new(
+        id: i32,
+        hub_id: i32,
+        url: String,
+        event_type: Option<String>,
+        secret: String,
+        created_at: NaiveDateTime,
+    ) -> Result<Self, TypeConstraintError> {
+        Ok(Self {
+            id: WebhookId::try_from(id)?,
+            hub_id: HubId::try_from(hub_id)?,
+            url: WebhookUrl::new(url)?,
+            event_type: event_type.map(ClientEventType::from),
+            secret,
+            created_at,
+        })
+    }
+
+    /// Returns `true` when events of `event_type` should be delivered here.
+    #[must_use]
+    pub fn matches(&self, event_type: &ClientEventType) -> bool {
+        self.event_type
+            .as_ref()
+            .is_none_or(|filter| filter == event_type)
+    }
+}
+
+/// Data required to register a new webhook.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NewWebhook {
+    pub hub_id: HubId,
+    pub url: WebhookUrl,
+    pub event_type: Option<ClientEventType>,
+    pub secret: String,
+}
+
+impl NewWebhook {
+    /// Create a new webhook from already validated domain values.
+    #[must_use]
+    pub fn new(
+        hub_id: HubId,
+        url: WebhookUrl,
+        event_type: Option<ClientEventType>,
+        secret: String,
+    ) -> Self {
+        Self {
+            hub_id,
+            url,
+            event_type,
+            secret,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn webhook(event_type: Option<&str>) -> Webhook {
+        Webhook::try_new(
+            1,
+            1,
+            "https://hooks.example.com/crm".to_string(),
+            event_type.map(str::to_string),
+            "secret".to_string(),
+            Utc::now().naive_utc(),
+        )
+        .expect("valid webhook")
+    }
+
+    #[test]
+    fn webhook_without_filter_matches_every_event() {
+        let webhook = webhook(None);
+
+        assert!(webhook.matches(&ClientEventType::Comment));
+        assert!(webhook.matches(&ClientEventType::Other("Transfer".to_string())));
+    }
+
+    #[test]
+    fn webhook_with_filter_matches_only_that_event_type() {
+        let webhook = webhook(Some("comment"));
+
+        assert!(webhook.matches(&ClientEventType::Comment));
+        assert!(!webhook.matches(&ClientEventType::Call));
+    }
+}
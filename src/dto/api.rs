@@ -5,19 +5,53 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::domain::client::Client;
+use crate::domain::api_key::ApiKey;
+use crate::domain::audit::AuditEntry;
+use crate::domain::client::{Client, ClientStatus, ClientWriteOutcome, FieldMeta};
 use crate::domain::client_event::ClientEvent;
+use crate::domain::hub_settings::HubSettings;
+use crate::domain::import_job::{ImportJob, ImportJobStatus};
 use crate::domain::manager::Manager;
-use crate::dto::client::ClientFieldDisplay;
+use crate::domain::note::ClientNote;
+use crate::domain::segment::ClientSegment;
+use crate::domain::types::PhoneRegion;
+use crate::domain::webhook::Webhook;
+use crate::dto::client::{ClientFieldDisplay, EmailDirection, EmailThread};
+use crate::dto::main::HubDashboardData;
+use crate::models::config::FeaturesConfig;
+use crate::repository::{ClientSort, FtsOperator};
 
 /// Query parameters accepted by the `/api/v1/clients` service.
 #[derive(Debug, Default, Deserialize)]
 pub struct ClientsQuery {
     /// Optional free-form search string applied to the client list.
     pub search: Option<String>,
+    /// Optional `and` (default) or `or` joining the search words.
+    pub fts: Option<FtsOperator>,
     pub public_id: Option<String>,
+    /// Optional email matched exactly (case-insensitive), bypassing FTS.
+    pub email: Option<String>,
+    /// Optional phone matched exactly after E.164 normalization, bypassing FTS.
+    pub phone: Option<String>,
+    /// Optional ordering (`created_desc` or `updated_desc`); defaults to id ascending.
+    pub sort: Option<ClientSort>,
     /// Optional page number for pagination.
     pub page: Option<usize>,
+    /// Optional keyset cursor: the last client id of the previous page, `0` for
+    /// the first one. Mutually exclusive with `page`.
+    pub after_id: Option<i32>,
+    /// Custom field names, paired by position with `value`.
+    #[serde(default)]
+    pub field: Vec<String>,
+    /// Substrings the custom field in the same position must contain.
+    #[serde(default)]
+    pub value: Vec<String>,
+    /// Also return archived clients, which are hidden by default.
+    #[serde(default)]
+    pub include_archived: bool,
+    /// Return only clients without an assigned manager.
+    #[serde(default)]
+    pub unassigned: bool,
 }
 
 /// Result payload returned by [`crate::services::api::list_clients`].
@@ -27,6 +61,16 @@ pub struct ClientsResponse {
     pub total: usize,
     /// Page of clients requested by the caller.
     pub clients: Vec<Client>,
+    /// Value of `after_id` for the next keyset page; `None` without `after_id`
+    /// or once the last page is reached.
+    pub next_cursor: Option<i32>,
+}
+
+/// Query parameters accepted by `/api/v1/clients/recent`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RecentClientsQuery {
+    /// Optional number of clients to return; clamped to the service maximum.
+    pub limit: Option<usize>,
 }
 
 /// A simplified client representation for React page-data APIs.
@@ -37,6 +81,8 @@ pub struct ClientListItemDto {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
+    /// Phone contact kept as entered when it failed strict parsing.
+    pub raw_phone: Option<String>,
     pub field_badges: Vec<String>,
 }
 
@@ -60,6 +106,10 @@ impl From<&Client> for ClientListItemDto {
                 .phone
                 .as_ref()
                 .map(|phone| phone.as_str().to_string()),
+            raw_phone: client
+                .raw_phone
+                .as_ref()
+                .map(|raw_phone| raw_phone.as_str().to_string()),
             field_badges,
         }
     }
@@ -77,6 +127,7 @@ pub struct PaginatedClientListDto {
 #[derive(Debug, Serialize)]
 pub struct ClientDirectoryDto {
     pub search_query: Option<String>,
+    pub unassigned: bool,
     pub clients: PaginatedClientListDto,
 }
 
@@ -87,6 +138,8 @@ pub struct ManagerDto {
     pub name: String,
     pub email: String,
     pub is_user: bool,
+    pub company: Option<String>,
+    pub phone: Option<String>,
 }
 
 impl From<&Manager> for ManagerDto {
@@ -96,6 +149,40 @@ impl From<&Manager> for ManagerDto {
             name: manager.name.as_str().to_string(),
             email: manager.email.as_str().to_string(),
             is_user: manager.is_user,
+            company: manager
+                .company
+                .as_ref()
+                .map(|company| company.as_str().to_string()),
+            phone: manager
+                .phone
+                .as_ref()
+                .map(|phone| phone.as_str().to_string()),
+        }
+    }
+}
+
+/// Hub dashboard counters for the React overview page.
+#[derive(Debug, Serialize)]
+pub struct HubDashboardDto {
+    pub total_clients: usize,
+    pub active_clients: usize,
+    pub total_managers: usize,
+    pub events_today: usize,
+    pub events_this_week: usize,
+    pub top_manager_by_events: Option<ManagerDto>,
+    pub unassigned_count: usize,
+}
+
+impl From<HubDashboardData> for HubDashboardDto {
+    fn from(data: HubDashboardData) -> Self {
+        Self {
+            total_clients: data.total_clients,
+            active_clients: data.active_clients,
+            total_managers: data.total_managers,
+            events_today: data.events_today,
+            events_this_week: data.events_this_week,
+            top_manager_by_events: data.top_manager_by_events.as_ref().map(ManagerDto::from),
+            unassigned_count: data.unassigned_count,
         }
     }
 }
@@ -148,24 +235,364 @@ impl ClientEventDto {
                 name: String::new(),
                 email: String::new(),
                 is_user: false,
+                company: None,
+                phone: None,
             },
         }
     }
 }
 
+/// Query parameters accepted by `/api/v1/clients/{client_id}/events`.
+#[derive(Debug, Default, Deserialize)]
+pub struct ClientEventsQuery {
+    /// Optional event type such as `Email` or a custom subtype; blank means any.
+    pub event_type: Option<String>,
+    /// Optional page number; defaults to the first page.
+    pub page: Option<usize>,
+}
+
+/// A client event paired with the name of the manager who recorded it.
+#[derive(Debug, Serialize)]
+pub struct ClientEventItemDto {
+    pub id: i32,
+    pub event_type: String,
+    pub event_data: Value,
+    pub created_at: String,
+    pub manager_name: String,
+}
+
+impl ClientEventItemDto {
+    pub fn from_event_pair(event: &ClientEvent, manager: &Manager) -> Self {
+        Self {
+            id: event.id.get(),
+            event_type: event.event_type.to_string(),
+            event_data: event.event_data.clone(),
+            created_at: event.created_at.to_string(),
+            manager_name: manager.name.as_str().to_string(),
+        }
+    }
+}
+
+/// Response of `GET /api/v1/clients/{client_id}/events`, newest events first.
+#[derive(Debug, Serialize)]
+pub struct ClientEventPageDto {
+    pub events: Vec<ClientEventItemDto>,
+    /// Matching events across all pages.
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+}
+
+/// A message within an email conversation on the client page.
+#[derive(Debug, Serialize)]
+pub struct EmailThreadMessageDto {
+    pub direction: EmailDirection,
+    pub event: ClientEventDto,
+}
+
+/// Email conversation grouped by subject for the client page.
+#[derive(Debug, Serialize)]
+pub struct EmailThreadDto {
+    pub subject: Option<String>,
+    pub messages: Vec<EmailThreadMessageDto>,
+}
+
+impl From<&EmailThread> for EmailThreadDto {
+    fn from(thread: &EmailThread) -> Self {
+        Self {
+            subject: thread.subject.clone(),
+            messages: thread
+                .messages
+                .iter()
+                .map(|message| EmailThreadMessageDto {
+                    direction: message.direction,
+                    event: ClientEventDto::from_event_pair(&message.event, &message.manager),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Typed internal note representation for the client page.
+#[derive(Debug, Serialize)]
+pub struct ClientNoteDto {
+    pub id: i32,
+    pub manager_id: i32,
+    pub text: String,
+    pub is_pinned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<&ClientNote> for ClientNoteDto {
+    fn from(note: &ClientNote) -> Self {
+        Self {
+            id: note.id.get(),
+            manager_id: note.manager_id.get(),
+            text: note.text.as_str().to_string(),
+            is_pinned: note.is_pinned,
+            created_at: note.created_at.to_string(),
+            updated_at: note.updated_at.to_string(),
+        }
+    }
+}
+
+/// Audit log entry returned by `/api/v1/clients/{id}/audit`.
+#[derive(Debug, Serialize)]
+pub struct AuditEntryDto {
+    pub id: i32,
+    pub actor_email: String,
+    pub action: String,
+    pub diff: Value,
+    pub created_at: String,
+}
+
+impl From<&AuditEntry> for AuditEntryDto {
+    fn from(entry: &AuditEntry) -> Self {
+        Self {
+            id: entry.id.get(),
+            actor_email: entry.actor_email.as_str().to_string(),
+            action: entry.action.clone(),
+            diff: entry.diff.clone(),
+            created_at: entry.created_at.to_string(),
+        }
+    }
+}
+
+/// Optional features returned by `/api/v1/features`.
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagsDto {
+    pub xlsx_export: bool,
+    pub hub_dashboard: bool,
+    pub webhooks: bool,
+    pub segments: bool,
+}
+
+impl From<&FeaturesConfig> for FeatureFlagsDto {
+    fn from(features: &FeaturesConfig) -> Self {
+        Self {
+            xlsx_export: features.xlsx_export,
+            hub_dashboard: features.hub_dashboard,
+            webhooks: features.webhooks,
+            segments: features.segments,
+        }
+    }
+}
+
+/// Progress of a CSV import returned by `/api/v1/import/{id}/status`.
+#[derive(Debug, Serialize)]
+pub struct ImportJobDto {
+    pub id: i32,
+    pub status: ImportJobStatus,
+    pub total_rows: usize,
+    pub inserted_rows: usize,
+    pub updated_rows: usize,
+    pub error_rows: usize,
+    pub error_message: Option<String>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl From<&ImportJob> for ImportJobDto {
+    fn from(job: &ImportJob) -> Self {
+        Self {
+            id: job.id.get(),
+            status: job.status,
+            total_rows: job.total_rows,
+            inserted_rows: job.inserted_rows,
+            updated_rows: job.updated_rows,
+            error_rows: job.error_rows,
+            error_message: job.error_message.clone(),
+            started_at: job.started_at.to_string(),
+            completed_at: job
+                .completed_at
+                .map(|completed_at| completed_at.to_string()),
+        }
+    }
+}
+
+/// Response of `POST /clients/upload` once the import job has been queued.
+#[derive(Debug, Serialize)]
+pub struct ImportJobAcceptedDto {
+    pub message: String,
+    pub redirect_to: Option<String>,
+    pub job_id: i32,
+}
+
+/// Response of `POST /client/add`; `warning` carries the duplicate-name note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddClientCreatedDto {
+    pub message: String,
+    pub redirect_to: Option<String>,
+    pub warning: Option<String>,
+}
+
+/// Client segment definition returned by `/api/v1/segments`.
+#[derive(Debug, Serialize)]
+pub struct ClientSegmentDto {
+    pub id: i32,
+    pub name: String,
+    pub filter_json: Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<&ClientSegment> for ClientSegmentDto {
+    fn from(segment: &ClientSegment) -> Self {
+        Self {
+            id: segment.id.get(),
+            name: segment.name.as_str().to_string(),
+            filter_json: segment.filter_json.clone(),
+            created_at: segment.created_at.to_string(),
+            updated_at: segment.updated_at.to_string(),
+        }
+    }
+}
+
+/// Integration API key returned by `/api/v1/api-keys`; the key itself is never
+/// listed.
+#[derive(Debug, Serialize)]
+pub struct ApiKeyDto {
+    pub id: i32,
+    pub name: String,
+    pub roles: Vec<String>,
+    pub created_at: String,
+}
+
+impl From<&ApiKey> for ApiKeyDto {
+    fn from(api_key: &ApiKey) -> Self {
+        Self {
+            id: api_key.id.get(),
+            name: api_key.name.clone(),
+            roles: api_key.roles.clone(),
+            created_at: api_key.created_at.to_string(),
+        }
+    }
+}
+
+/// Response of `POST /api-keys`, the only place the raw key is shown.
+#[derive(Debug, Serialize)]
+pub struct ApiKeyCreatedDto {
+    pub message: String,
+    pub api_key: ApiKeyDto,
+    pub key: String,
+}
+
+/// Registered webhook returned by `/api/v1/webhooks`.
+///
+/// The secret is included so admins can verify delivery signatures.
+#[derive(Debug, Serialize)]
+pub struct WebhookDto {
+    pub id: i32,
+    pub url: String,
+    pub event_type: Option<String>,
+    pub secret: String,
+    pub created_at: String,
+}
+
+impl From<&Webhook> for WebhookDto {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            id: webhook.id.get(),
+            url: webhook.url.as_str().to_string(),
+            event_type: webhook
+                .event_type
+                .as_ref()
+                .map(|event_type| event_type.to_string()),
+            secret: webhook.secret.clone(),
+            created_at: webhook.created_at.to_string(),
+        }
+    }
+}
+
+/// Custom fields of a client returned by `/api/v1/clients/{id}/fields`.
+#[derive(Debug, Serialize)]
+pub struct ClientFieldsResponse {
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Body of `PATCH /api/v1/clients/{id}/fields`; replaces every custom field.
+#[derive(Debug, Deserialize)]
+pub struct ClientFieldsPatch {
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Body of `PATCH /api/v1/clients/{id}/status`; archives or restores a client.
+#[derive(Debug, Deserialize)]
+pub struct ClientStatusPatch {
+    pub status: ClientStatus,
+}
+
+/// Response of `PATCH /api/v1/clients/{id}/status`.
+#[derive(Debug, Serialize)]
+pub struct ClientStatusResponse {
+    pub status: ClientStatus,
+}
+
+/// Outcome of one record of `POST /api/v1/clients/batch`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientBatchItemStatus {
+    /// Stored as a new client.
+    Inserted,
+    /// Matched an existing client by email or phone and updated it.
+    Updated,
+    /// Valid, but the upsert could not store it.
+    Skipped,
+    /// Failed validation and was left out of the batch.
+    Invalid,
+}
+
+impl From<ClientWriteOutcome> for ClientBatchItemStatus {
+    fn from(outcome: ClientWriteOutcome) -> Self {
+        match outcome {
+            ClientWriteOutcome::Inserted => Self::Inserted,
+            ClientWriteOutcome::Updated => Self::Updated,
+            ClientWriteOutcome::Skipped => Self::Skipped,
+        }
+    }
+}
+
+/// Per-record result of `POST /api/v1/clients/batch`, in request order.
+#[derive(Debug, Serialize)]
+pub struct ClientBatchItemDto {
+    pub index: usize,
+    pub status: ClientBatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response of `POST /api/v1/clients/batch`.
+///
+/// `skipped` counts invalid records plus valid ones the upsert could not
+/// store, such as a phone already used by another client.
+#[derive(Debug, Serialize)]
+pub struct ClientBatchResponse {
+    pub results: Vec<ClientBatchItemDto>,
+    pub inserted: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
 /// Typed client details payload for React-owned client pages.
 #[derive(Debug, Serialize)]
 pub struct ClientDetailsDto {
     pub client: ClientDetailsHeaderDto,
     pub managers: Vec<ManagerDto>,
     pub events: Vec<ClientEventDto>,
+    pub email_threads: Vec<EmailThreadDto>,
     pub documents: Vec<ClientEventDto>,
-    pub available_fields: Vec<String>,
+    pub notes: Vec<ClientNoteDto>,
+    pub available_fields: Vec<FieldMeta>,
     pub important_fields: Vec<ClientFieldDisplayDto>,
     pub other_fields: Vec<ClientFieldDisplayDto>,
     pub total_events: usize,
+    pub total_managers: usize,
     pub todo_service_url: String,
     pub files_service_url: String,
+    /// BCP 47 locale dates are rendered in.
+    pub locale: String,
 }
 
 /// Typed client header data for React-owned client pages.
@@ -176,11 +603,18 @@ pub struct ClientDetailsHeaderDto {
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
+    /// `phone` formatted for the hub's country; see [`ClientDetailsHeaderDto::new`].
+    pub phone_display: Option<String>,
+    /// Phone contact kept as entered when it failed strict parsing.
+    pub raw_phone: Option<String>,
     pub fields: BTreeMap<String, String>,
+    /// Sent back with the edit form so stale saves are rejected.
+    pub version: i32,
 }
 
-impl From<&Client> for ClientDetailsHeaderDto {
-    fn from(client: &Client) -> Self {
+impl ClientDetailsHeaderDto {
+    /// Builds the header, showing phones of `country` in national form.
+    pub fn new(client: &Client, country: Option<&PhoneRegion>) -> Self {
         Self {
             id: client.id.get(),
             public_id: client.public_id.as_ref().map(ToString::to_string),
@@ -193,7 +627,16 @@ impl From<&Client> for ClientDetailsHeaderDto {
                 .phone
                 .as_ref()
                 .map(|phone| phone.as_str().to_string()),
+            phone_display: client
+                .phone
+                .as_ref()
+                .map(|phone| phone.format_for_region(country)),
+            raw_phone: client
+                .raw_phone
+                .as_ref()
+                .map(|raw_phone| raw_phone.as_str().to_string()),
             fields: client.fields.clone().unwrap_or_default(),
+            version: client.version,
         }
     }
 }
@@ -218,8 +661,44 @@ pub struct ManagerModalDto {
     pub clients: Vec<ClientListItemDto>,
 }
 
+/// Per-hub configuration returned by `/api/v1/settings`.
+#[derive(Debug, Serialize)]
+pub struct HubSettingsDto {
+    pub items_per_page: u16,
+    pub email_sender_name: Option<String>,
+    pub allow_manager_self_assign: bool,
+    pub default_phone_region: Option<String>,
+    pub event_retention_days: Option<u32>,
+    pub email_reply_to: Option<String>,
+}
+
+impl From<&HubSettings> for HubSettingsDto {
+    fn from(settings: &HubSettings) -> Self {
+        Self {
+            items_per_page: settings.items_per_page,
+            email_sender_name: settings
+                .email_sender_name
+                .as_ref()
+                .map(|name| name.as_str().to_string()),
+            allow_manager_self_assign: settings.allow_manager_self_assign,
+            default_phone_region: settings
+                .hub_default_phone_region
+                .as_ref()
+                .map(|region| region.as_str().to_string()),
+            event_retention_days: settings.event_retention_days,
+            email_reply_to: settings
+                .email_reply_to
+                .as_ref()
+                .map(|email| email.as_str().to_string()),
+        }
+    }
+}
+
 /// Typed important-field settings payload for React-owned pages.
 #[derive(Debug, Serialize)]
 pub struct ImportantFieldSettingsDto {
+    /// Configured field names in display order.
+    pub fields: Vec<String>,
+    /// The same names, one per line, as edited on the settings page.
     pub fields_text: String,
 }
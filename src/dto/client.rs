@@ -2,9 +2,10 @@
 
 use serde::Serialize;
 
-use crate::domain::client::Client;
+use crate::domain::client::{Client, FieldMeta};
 use crate::domain::client_event::ClientEvent;
 use crate::domain::manager::Manager;
+use crate::domain::note::ClientNote;
 use crate::domain::types::ClientId;
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -13,17 +14,50 @@ pub struct ClientFieldDisplay {
     pub value: Option<String>,
 }
 
+/// Who sent a message within an [`EmailThread`].
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailDirection {
+    /// Sent by a manager to the client.
+    Outbound,
+    /// Received from the client.
+    Inbound,
+}
+
+/// A single `Email` or `Reply` event placed in a conversation.
+#[derive(Debug, Clone)]
+pub struct EmailThreadMessage {
+    pub event: ClientEvent,
+    pub manager: Manager,
+    pub direction: EmailDirection,
+}
+
+/// Email conversation grouped by normalized subject.
+#[derive(Debug, Clone)]
+pub struct EmailThread {
+    /// Subject without `Re:`/`Fwd:` prefixes; `None` for messages without one.
+    pub subject: Option<String>,
+    /// Messages ordered chronologically (oldest first).
+    pub messages: Vec<EmailThreadMessage>,
+}
+
 /// Aggregated data required to render the client details page.
 #[derive(Debug)]
 pub struct ClientPageData {
     pub client: Client,
     pub managers: Vec<Manager>,
     pub events_with_managers: Vec<(ClientEvent, Manager)>,
+    /// `Email` and `Reply` events grouped into conversations, most recent first.
+    pub email_threads: Vec<EmailThread>,
     pub documents: Vec<ClientEvent>,
-    pub available_fields: Vec<String>,
+    /// Internal notes, kept apart from the communication timeline.
+    pub notes: Vec<ClientNote>,
+    pub available_fields: Vec<FieldMeta>,
     pub important_fields: Vec<ClientFieldDisplay>,
     pub other_fields: Vec<ClientFieldDisplay>,
     pub total_events: usize,
+    /// Assigned managers in total; `managers` holds at most the first page.
+    pub total_managers: usize,
 }
 
 /// Generic result wrapper for client mutations so callers can redirect easily.
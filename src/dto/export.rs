@@ -0,0 +1,119 @@
+//! DTOs for client exports.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::client::Client;
+use crate::domain::types::{DateRange, ExportFormat, TypeConstraintError};
+
+/// Query parameters accepted by the clients XLSX export.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportQuery {
+    /// Adds an `Events` sheet with every client's timeline when `true`.
+    #[serde(default)]
+    pub include_events: bool,
+}
+
+/// Query parameters accepted by `GET /api/v1/clients/export`.
+#[derive(Debug, Deserialize)]
+pub struct ClientsExportQuery {
+    pub format: ExportFormat,
+    /// Comma-separated custom field names; every field is exported when absent.
+    #[serde(default)]
+    pub fields: Option<String>,
+    /// First creation day (inclusive) of the exported clients.
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    /// Last creation day (inclusive) of the exported clients.
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+}
+
+/// Validated settings of a clients export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Custom fields to export; an empty list exports every field.
+    pub include_fields: Vec<String>,
+    /// Restricts the export to clients created within the range.
+    pub date_range: Option<DateRange>,
+}
+
+impl ExportOptions {
+    /// Options exporting every client with all of its custom fields.
+    #[must_use]
+    pub const fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            include_fields: Vec::new(),
+            date_range: None,
+        }
+    }
+}
+
+impl TryFrom<ClientsExportQuery> for ExportOptions {
+    type Error = TypeConstraintError;
+
+    fn try_from(query: ClientsExportQuery) -> Result<Self, Self::Error> {
+        let include_fields = query
+            .fields
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        let date_range = match (query.from, query.to) {
+            (None, None) => None,
+            (from, to) => Some(DateRange::new(from, to)?),
+        };
+
+        Ok(Self {
+            format: query.format,
+            include_fields,
+            date_range,
+        })
+    }
+}
+
+/// One client object of the JSON and ND-JSON exports with its custom fields.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientExportRecord {
+    pub id: i32,
+    pub public_id: Option<String>,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub raw_phone: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl From<&Client> for ClientExportRecord {
+    fn from(client: &Client) -> Self {
+        Self {
+            id: client.id.get(),
+            public_id: client.public_id.as_ref().map(ToString::to_string),
+            name: client.name.as_str().to_string(),
+            email: client
+                .email
+                .as_ref()
+                .map(|email| email.as_str().to_string()),
+            phone: client
+                .phone
+                .as_ref()
+                .map(|phone| phone.as_str().to_string()),
+            raw_phone: client
+                .raw_phone
+                .as_ref()
+                .map(|raw_phone| raw_phone.as_str().to_string()),
+            created_at: client.created_at.to_string(),
+            updated_at: client.updated_at.to_string(),
+            fields: client.fields.clone().unwrap_or_default(),
+        }
+    }
+}
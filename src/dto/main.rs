@@ -4,16 +4,25 @@ use pushkind_common::pagination::Paginated;
 use serde::Deserialize;
 
 use crate::domain::client::Client;
+use crate::domain::manager::Manager;
+use crate::repository::{ClientSort, FtsOperator};
 
 /// Query parameters accepted by the index page service.
 #[derive(Debug, Default, Deserialize)]
 pub struct IndexQuery {
     /// Optional search string entered by the user.
     pub search: Option<String>,
+    /// Optional `and` (default) or `or` joining the search words.
+    pub fts: Option<FtsOperator>,
     /// Optional public_id
     pub public_id: Option<String>,
+    /// Optional ordering shared with the clients API.
+    pub sort: Option<ClientSort>,
     /// Page number requested by the user interface.
     pub page: Option<usize>,
+    /// Show only clients without an assigned manager.
+    #[serde(default)]
+    pub unassigned: bool,
 }
 
 /// Data required to render the main index template.
@@ -22,4 +31,24 @@ pub struct IndexPageData {
     pub clients: Paginated<Client>,
     /// Search query echoed back to the template when present.
     pub search_query: Option<String>,
+    /// Whether the list is limited to clients without a manager.
+    pub unassigned: bool,
+}
+
+/// Hub-wide counters shown on the admin dashboard.
+pub struct HubDashboardData {
+    /// Number of clients in the hub.
+    pub total_clients: usize,
+    /// Clients with at least one event in the last 30 days.
+    pub active_clients: usize,
+    /// Number of managers registered in the hub.
+    pub total_managers: usize,
+    /// Events created since midnight UTC.
+    pub events_today: usize,
+    /// Events created since Monday midnight UTC.
+    pub events_this_week: usize,
+    /// Manager with the most events this week, when any events exist.
+    pub top_manager_by_events: Option<Manager>,
+    /// Clients without any assigned manager.
+    pub unassigned_count: usize,
 }
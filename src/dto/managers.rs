@@ -1,7 +1,19 @@
 //! DTOs used in manager administration pages.
 
+use serde::{Deserialize, Serialize};
+
+use crate::domain::assignment_rule::ManagerAssignmentRule;
 use crate::domain::client::Client;
 use crate::domain::manager::Manager;
+use crate::forms::managers::ManagerAssignmentRow;
+use crate::repository::ManagerSort;
+
+/// Query parameters accepted by the managers collection service.
+#[derive(Debug, Default, Deserialize)]
+pub struct ManagersQuery {
+    /// Requested sort order; defaults to name ascending.
+    pub sort: Option<ManagerSort>,
+}
 
 /// Data required to render the managers index page.
 #[derive(Debug)]
@@ -16,3 +28,53 @@ pub struct ManagerModalData {
     pub manager: Manager,
     pub clients: Vec<Client>,
 }
+
+/// Manager assignment rule returned by `GET /managers/rules`.
+#[derive(Debug, Serialize)]
+pub struct ManagerAssignmentRuleDto {
+    pub id: i32,
+    pub manager_id: i32,
+    pub field: String,
+    pub value: String,
+    pub created_at: String,
+}
+
+impl From<&ManagerAssignmentRule> for ManagerAssignmentRuleDto {
+    fn from(rule: &ManagerAssignmentRule) -> Self {
+        Self {
+            id: rule.id.get(),
+            manager_id: rule.manager_id.get(),
+            field: rule.field.as_str().to_string(),
+            value: rule.value.as_str().to_string(),
+            created_at: rule.created_at.to_string(),
+        }
+    }
+}
+
+/// Result of `POST /managers/assign-upload`.
+#[derive(Debug, Serialize)]
+pub struct ManagerAssignmentsUploadDto {
+    pub message: String,
+    /// Number of client assignments that did not exist before the upload.
+    pub assigned: usize,
+    /// Rows whose client or manager was not found in the hub.
+    pub unresolved: Vec<UnresolvedAssignmentDto>,
+}
+
+/// Upload row that could not be applied, as written in the file.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct UnresolvedAssignmentDto {
+    pub line: u64,
+    pub client_email: String,
+    pub manager_email: String,
+}
+
+impl From<ManagerAssignmentRow> for UnresolvedAssignmentDto {
+    fn from(row: ManagerAssignmentRow) -> Self {
+        Self {
+            line: row.line,
+            client_email: row.client_email,
+            manager_email: row.manager_email,
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod api;
 pub mod client;
+pub mod export;
 pub mod important_fields;
 pub mod main;
 pub mod managers;
@@ -12,11 +12,18 @@ use crate::domain::types::TypeConstraintError;
 use crate::forms::FormError;
 #[cfg(feature = "server")]
 use crate::forms::store::StoreFormError;
+#[cfg(feature = "server")]
+use crate::repository::errors::ClientWriteError;
 
 #[cfg(feature = "data")]
 impl From<TypeConstraintError> for ServiceError {
+    /// A non-positive id can never name a stored record, so it reads as
+    /// [`ServiceError::NotFound`] rather than an internal failure.
     fn from(val: TypeConstraintError) -> Self {
-        ServiceError::TypeConstraint(val.to_string())
+        match val {
+            TypeConstraintError::NonPositiveId => ServiceError::NotFound,
+            val => ServiceError::TypeConstraint(val.to_string()),
+        }
     }
 }
 
@@ -40,3 +47,20 @@ impl From<TypeConstraintError> for RepositoryError {
         RepositoryError::ValidationError(val.to_string())
     }
 }
+
+#[cfg(feature = "server")]
+impl From<ClientWriteError> for RepositoryError {
+    fn from(val: ClientWriteError) -> Self {
+        match val {
+            ClientWriteError::Repository(err) => err,
+            val => RepositoryError::ConstraintViolation(val.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<ClientWriteError> for ServiceError {
+    fn from(val: ClientWriteError) -> Self {
+        RepositoryError::from(val).into()
+    }
+}
@@ -0,0 +1,90 @@
+//! Forms for provisioning integration API keys.
+
+use serde::Deserialize;
+
+use crate::forms::FormError;
+use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE, SERVICE_MANAGER_ROLE};
+
+/// Form submitted to create an API key.
+#[derive(Debug, Deserialize)]
+pub struct AddApiKeyForm {
+    pub name: String,
+    /// Comma-separated CRM roles granted to the key.
+    pub roles: String,
+}
+
+pub struct AddApiKeyPayload {
+    pub name: String,
+    pub roles: Vec<String>,
+}
+
+impl TryFrom<AddApiKeyForm> for AddApiKeyPayload {
+    type Error = FormError;
+
+    fn try_from(form: AddApiKeyForm) -> Result<Self, Self::Error> {
+        let name = form.name.trim();
+        if name.is_empty() {
+            return Err(FormError::InvalidName);
+        }
+
+        let mut roles: Vec<String> = Vec::new();
+        for role in form.roles.split(',').map(str::trim) {
+            if role.is_empty() || roles.iter().any(|known| known == role) {
+                continue;
+            }
+            if ![
+                SERVICE_ACCESS_ROLE,
+                SERVICE_MANAGER_ROLE,
+                SERVICE_ADMIN_ROLE,
+            ]
+            .contains(&role)
+            {
+                return Err(FormError::InvalidApiKeyRoles);
+            }
+            roles.push(role.to_string());
+        }
+        if roles.is_empty() {
+            return Err(FormError::InvalidApiKeyRoles);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            roles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_api_key_form_trims_and_dedupes_roles() {
+        let payload = AddApiKeyPayload::try_from(AddApiKeyForm {
+            name: " ERP ".to_string(),
+            roles: "crm, crm_admin,crm,".to_string(),
+        })
+        .expect("valid payload");
+
+        assert_eq!(payload.name, "ERP");
+        assert_eq!(
+            payload.roles,
+            vec!["crm".to_string(), "crm_admin".to_string()]
+        );
+    }
+
+    #[test]
+    fn add_api_key_form_rejects_foreign_or_missing_roles() {
+        for roles in ["crm,admin", " , "] {
+            let result = AddApiKeyPayload::try_from(AddApiKeyForm {
+                name: "ERP".to_string(),
+                roles: roles.to_string(),
+            });
+
+            assert!(
+                matches!(result, Err(FormError::InvalidApiKeyRoles)),
+                "{roles}"
+            );
+        }
+    }
+}
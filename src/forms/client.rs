@@ -1,18 +1,21 @@
 //! Forms that validate and normalize client input.
 
 use std::collections::BTreeMap;
+use std::io::Read;
 
+use actix_multipart::form::{MultipartForm, tempfile::TempFile, text::Text};
 use pushkind_common::routes::empty_string_as_none;
 use serde::Deserialize;
 use validator::Validate;
 
 use crate::domain::client::UpdateClient;
 use crate::domain::client_event::ClientEventType;
+use crate::domain::contact_policy::ContactPolicy;
 use crate::domain::types::{
-    AttachmentName, AttachmentUrl, ClientEmail, ClientName, CommentMessage, CommentSubject,
-    PhoneNumber,
+    AttachmentMime, AttachmentName, AttachmentUrl, ClientEmail, ClientName, CommentMessage,
+    CommentSubject, PhoneNumber,
 };
-use crate::forms::FormError;
+use crate::forms::{FormError, check_contact_policy};
 
 #[derive(Deserialize, Validate)]
 /// Form data for updating an existing client.
@@ -31,6 +34,9 @@ pub struct SaveClientForm {
     pub field: Vec<String>,
     #[serde(default)]
     pub value: Vec<String>,
+    /// Client version the edit form was loaded with.
+    #[serde(default)]
+    pub version: Option<i32>,
 }
 
 pub struct SaveClientPayload {
@@ -38,6 +44,7 @@ pub struct SaveClientPayload {
     pub email: Option<ClientEmail>,
     pub phone: Option<PhoneNumber>,
     pub fields: Option<BTreeMap<String, String>>,
+    pub version: Option<i32>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -52,12 +59,50 @@ pub struct AddCommentForm {
     /// Type of event associated with the comment.
     #[validate(length(min = 1, message = "Выберите тип события."))]
     pub event_type: String,
+    /// Custom label used when `event_type` is `other`, e.g. `contract_sent`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event_subtype: Option<String>,
+    /// Document the server fetches and attaches to an email.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub attachment_url: Option<String>,
+}
+
+/// Longest label accepted for a custom `Other` event type.
+pub const MAX_EVENT_SUBTYPE_LENGTH: usize = 64;
+
+/// Largest document attached to a comment email, uploaded or fetched.
+pub const MAX_COMMENT_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(MultipartForm)]
+/// Multipart variant of [`AddCommentForm`] that may carry an uploaded document.
+pub struct AddCommentUploadForm {
+    pub subject: Option<Text<String>>,
+    pub message: Text<String>,
+    pub event_type: Text<String>,
+    pub event_subtype: Option<Text<String>>,
+    pub attachment_url: Option<Text<String>>,
+    /// Uploaded document; browsers send an empty part when no file is chosen.
+    #[multipart(limit = "10MB")]
+    pub attachment: Option<TempFile>,
+}
+
+/// Document attached to an outbound comment email.
+pub enum CommentAttachment {
+    /// Fetched by the server when the email is enqueued.
+    Url(AttachmentUrl),
+    /// Uploaded with the comment form.
+    Upload {
+        name: AttachmentName,
+        mime: AttachmentMime,
+        content: Vec<u8>,
+    },
 }
 
 pub struct AddCommentPayload {
     pub subject: Option<CommentSubject>,
     pub message: CommentMessage,
     pub event_type: ClientEventType,
+    pub attachment: Option<CommentAttachment>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -69,11 +114,31 @@ pub struct AddAttachmentForm {
     /// URL pointing to the attachment.
     #[validate(url(message = "Укажите корректный URL."))]
     pub url: String,
+    /// Optional MIME type of the linked document.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub mime: Option<String>,
+    /// Optional size of the linked document in bytes.
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
 }
 
 pub struct AddAttachmentPayload {
     pub text: AttachmentName,
     pub url: AttachmentUrl,
+    pub mime: Option<AttachmentMime>,
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Deserialize, Validate)]
+/// Form data for adding an internal note to a client.
+pub struct AddNoteForm {
+    /// Note text content.
+    #[validate(length(min = 1, message = "Введите сообщение."))]
+    pub text: String,
+}
+
+pub struct AddNotePayload {
+    pub text: CommentMessage,
 }
 
 impl TryFrom<SaveClientForm> for SaveClientPayload {
@@ -108,14 +173,26 @@ impl TryFrom<SaveClientForm> for SaveClientPayload {
             email,
             phone,
             fields: Some(fields),
+            version: form.version,
         })
     }
 }
 
+impl SaveClientPayload {
+    /// Ensures the payload satisfies the hub contact policy.
+    pub fn check_contact_policy(&self, policy: &ContactPolicy) -> Result<(), FormError> {
+        check_contact_policy(policy, self.email.as_ref(), self.phone.as_ref())
+    }
+}
+
 impl From<SaveClientPayload> for UpdateClient {
     /// Convert the [`SaveClientPayload`] into an [`UpdateClient`] value for persistence.
     fn from(payload: SaveClientPayload) -> Self {
-        UpdateClient::new(payload.name, payload.email, payload.phone, payload.fields)
+        let update = UpdateClient::new(payload.name, payload.email, payload.phone, payload.fields);
+        match payload.version {
+            Some(version) => update.expected_version(version),
+            None => update,
+        }
     }
 }
 
@@ -136,16 +213,98 @@ impl TryFrom<AddCommentForm> for AddCommentPayload {
         let message =
             CommentMessage::new(form.message).map_err(|_| FormError::InvalidCommentMessage)?;
 
-        let event_type = ClientEventType::from(form.event_type.as_str());
+        let event_type = if form.event_type.trim().eq_ignore_ascii_case("other") {
+            parse_event_subtype(form.event_subtype.as_deref())?
+        } else {
+            ClientEventType::from(form.event_type.as_str())
+        };
+
+        // Internal-network URLs are rejected here; the fetch does not follow redirects.
+        let attachment = form
+            .attachment_url
+            .map(|url| AttachmentUrl::new(url).map_err(|_| FormError::InvalidAttachmentUrl))
+            .transpose()?
+            .map(CommentAttachment::Url);
 
         Ok(AddCommentPayload {
             subject,
             message,
             event_type,
+            attachment,
         })
     }
 }
 
+impl TryFrom<AddCommentUploadForm> for AddCommentPayload {
+    type Error = FormError;
+
+    /// Convert the [`AddCommentUploadForm`] into an [`AddCommentPayload`], reading
+    /// the uploaded document.
+    fn try_from(form: AddCommentUploadForm) -> Result<Self, Self::Error> {
+        let text = |value: Option<Text<String>>| {
+            value
+                .map(Text::into_inner)
+                .filter(|value| !value.trim().is_empty())
+        };
+
+        let mut payload = AddCommentPayload::try_from(AddCommentForm {
+            subject: text(form.subject),
+            message: form.message.into_inner(),
+            event_type: form.event_type.into_inner(),
+            event_subtype: text(form.event_subtype),
+            attachment_url: text(form.attachment_url),
+        })?;
+
+        let Some(mut file) = form.attachment.filter(|file| file.size > 0) else {
+            return Ok(payload);
+        };
+        if payload.attachment.is_some() {
+            return Err(FormError::MultipleAttachments);
+        }
+        if file.size > MAX_COMMENT_ATTACHMENT_BYTES {
+            return Err(FormError::InvalidAttachmentFile);
+        }
+
+        let mime = file
+            .content_type
+            .as_ref()
+            .map(|mime| AttachmentMime::new(mime.essence_str()))
+            .ok_or(FormError::InvalidAttachmentFile)?
+            .map_err(|_| FormError::InvalidAttachmentFile)?;
+        let name = AttachmentName::new(file.file_name.clone().unwrap_or_default())
+            .or_else(|_| AttachmentName::new("attachment"))
+            .map_err(|_| FormError::InvalidAttachmentFile)?;
+        let mut content = Vec::with_capacity(file.size);
+        file.file
+            .read_to_end(&mut content)
+            .map_err(|_| FormError::InvalidAttachmentFile)?;
+
+        payload.attachment = Some(CommentAttachment::Upload {
+            name,
+            mime,
+            content,
+        });
+
+        Ok(payload)
+    }
+}
+
+/// Builds the `Other` event type from a custom label.
+///
+/// Labels naming a built-in type are rejected so they cannot be confused with it.
+fn parse_event_subtype(subtype: Option<&str>) -> Result<ClientEventType, FormError> {
+    let subtype = subtype.map(str::trim).unwrap_or_default();
+
+    if subtype.is_empty() || subtype.chars().count() > MAX_EVENT_SUBTYPE_LENGTH {
+        return Err(FormError::InvalidEventSubtype);
+    }
+
+    match ClientEventType::from(subtype) {
+        ClientEventType::Other(_) => Ok(ClientEventType::Other(subtype.to_string())),
+        _ => Err(FormError::InvalidEventSubtype),
+    }
+}
+
 impl TryFrom<AddAttachmentForm> for AddAttachmentPayload {
     type Error = FormError;
 
@@ -154,9 +313,34 @@ impl TryFrom<AddAttachmentForm> for AddAttachmentPayload {
         form.validate().map_err(FormError::Validation)?;
 
         let text = AttachmentName::new(form.text).map_err(|_| FormError::InvalidAttachmentName)?;
+        // Malformed and internal-network URLs (`TypeConstraintError::InternalAddress`)
+        // are both reported to the user as an invalid URL.
         let url = AttachmentUrl::new(form.url).map_err(|_| FormError::InvalidUrl)?;
+        let mime = form
+            .mime
+            .map(AttachmentMime::new)
+            .transpose()
+            .map_err(|_| FormError::InvalidAttachmentMime)?;
 
-        Ok(AddAttachmentPayload { text, url })
+        Ok(AddAttachmentPayload {
+            text,
+            url,
+            mime,
+            size_bytes: form.size_bytes,
+        })
+    }
+}
+
+impl TryFrom<AddNoteForm> for AddNotePayload {
+    type Error = FormError;
+
+    /// Convert the [`AddNoteForm`] into an [`AddNotePayload`] value for persistence.
+    fn try_from(form: AddNoteForm) -> Result<Self, Self::Error> {
+        form.validate().map_err(FormError::Validation)?;
+
+        let text = CommentMessage::new(form.text).map_err(|_| FormError::InvalidCommentMessage)?;
+
+        Ok(AddNotePayload { text })
     }
 }
 
@@ -172,6 +356,7 @@ mod tests {
             phone: Some("+1 (415) 555-2671".to_string()),
             field: vec!["tier".to_string()],
             value: vec!["gold".to_string()],
+            version: Some(4),
         };
 
         let payload = SaveClientPayload::try_from(form).expect("expected normalized update client");
@@ -187,6 +372,8 @@ mod tests {
             Some("+14155552671")
         );
 
+        assert_eq!(update.expected_version, Some(4));
+
         let fields = update.fields.expect("fields should be populated");
         assert_eq!(fields.get("tier"), Some(&"gold".to_string()));
     }
@@ -199,6 +386,7 @@ mod tests {
             phone: None,
             field: Vec::new(),
             value: Vec::new(),
+            version: None,
         };
 
         let payload = SaveClientPayload::try_from(form).expect("expected normalized update client");
@@ -206,6 +394,7 @@ mod tests {
         let update = UpdateClient::from(payload);
 
         assert!(update.fields.is_none());
+        assert!(update.expected_version.is_none());
     }
 
     #[test]
@@ -214,6 +403,8 @@ mod tests {
             subject: Some("Follow up".to_string()),
             message: "<b>Hello</b>".to_string(),
             event_type: "email".to_string(),
+            event_subtype: None,
+            attachment_url: None,
         };
 
         let payload = AddCommentPayload::try_from(form).expect("expected comment payload");
@@ -226,16 +417,200 @@ mod tests {
         assert_eq!(payload.event_type, ClientEventType::Email);
     }
 
+    #[test]
+    fn add_comment_form_strips_script_tags_before_storage() {
+        let form = AddCommentForm {
+            subject: None,
+            message: "Hello<script>document.cookie</script>".to_string(),
+            event_type: "comment".to_string(),
+            event_subtype: None,
+            attachment_url: None,
+        };
+
+        let payload = AddCommentPayload::try_from(form).expect("expected comment payload");
+
+        assert_eq!(payload.message.as_str(), "Hello");
+    }
+
+    #[test]
+    fn add_comment_form_uses_subtype_for_other_events() {
+        let form = AddCommentForm {
+            subject: None,
+            message: "Sent the contract".to_string(),
+            event_type: "other".to_string(),
+            event_subtype: Some(" contract_sent ".to_string()),
+            attachment_url: None,
+        };
+
+        let payload = AddCommentPayload::try_from(form).expect("expected comment payload");
+
+        assert_eq!(
+            payload.event_type,
+            ClientEventType::Other("contract_sent".to_string())
+        );
+    }
+
+    #[test]
+    fn add_comment_form_rejects_invalid_other_subtypes() {
+        let form = |subtype: Option<&str>| AddCommentForm {
+            subject: None,
+            message: "Hello".to_string(),
+            event_type: "Other".to_string(),
+            event_subtype: subtype.map(str::to_string),
+            attachment_url: None,
+        };
+        let too_long = "x".repeat(MAX_EVENT_SUBTYPE_LENGTH + 1);
+
+        for subtype in [None, Some("   "), Some(too_long.as_str()), Some("email")] {
+            assert!(matches!(
+                AddCommentPayload::try_from(form(subtype)),
+                Err(FormError::InvalidEventSubtype)
+            ));
+        }
+    }
+
     #[test]
     fn add_attachment_form_into_payload_validates_fields() {
         let form = AddAttachmentForm {
             text: "Document".to_string(),
             url: "https://example.com/doc.pdf".to_string(),
+            mime: None,
+            size_bytes: None,
         };
 
         let payload = AddAttachmentPayload::try_from(form).expect("expected attachment payload");
 
         assert_eq!(payload.text.as_str(), "Document");
         assert_eq!(payload.url.as_str(), "https://example.com/doc.pdf");
+        assert!(payload.mime.is_none());
+        assert!(payload.size_bytes.is_none());
+    }
+
+    #[test]
+    fn add_attachment_form_keeps_optional_metadata() {
+        let form = |mime: &str| AddAttachmentForm {
+            text: "Contract".to_string(),
+            url: "https://example.com/contract.pdf".to_string(),
+            mime: Some(mime.to_string()),
+            size_bytes: Some(2_097_152),
+        };
+
+        let payload = AddAttachmentPayload::try_from(form("Application/PDF"))
+            .expect("expected attachment payload");
+        assert_eq!(
+            payload.mime.as_ref().map(AttachmentMime::as_str),
+            Some("application/pdf")
+        );
+        assert_eq!(payload.size_bytes, Some(2_097_152));
+
+        assert!(matches!(
+            AddAttachmentPayload::try_from(form("application/x-msdownload")),
+            Err(FormError::InvalidAttachmentMime)
+        ));
+    }
+
+    #[test]
+    fn add_note_form_rejects_empty_text() {
+        let form = AddNoteForm {
+            text: String::new(),
+        };
+
+        assert!(AddNotePayload::try_from(form).is_err());
+    }
+
+    #[test]
+    fn add_attachment_form_rejects_internal_url() {
+        let form = AddAttachmentForm {
+            text: "Metadata".to_string(),
+            url: "http://192.168.0.1/latest".to_string(),
+            mime: None,
+            size_bytes: None,
+        };
+
+        assert!(matches!(
+            AddAttachmentPayload::try_from(form),
+            Err(FormError::InvalidUrl)
+        ));
+    }
+
+    fn upload_form(
+        attachment_url: Option<&str>,
+        file: Option<(&str, &str, &[u8])>,
+    ) -> AddCommentUploadForm {
+        use std::io::{Seek, Write};
+
+        let attachment = file.map(|(name, mime, content)| {
+            let mut file = tempfile::NamedTempFile::new().expect("temp file");
+            file.write_all(content).expect("write attachment");
+            file.rewind().expect("rewind attachment");
+            TempFile {
+                file,
+                content_type: Some(mime.parse().expect("valid mime")),
+                file_name: Some(name.to_string()),
+                size: content.len(),
+            }
+        });
+
+        AddCommentUploadForm {
+            subject: Some(Text("Offer".to_string())),
+            message: Text("See attached".to_string()),
+            event_type: Text("email".to_string()),
+            event_subtype: Some(Text(String::new())),
+            attachment_url: attachment_url.map(|url| Text(url.to_string())),
+            attachment,
+        }
+    }
+
+    #[test]
+    fn add_comment_upload_form_reads_the_uploaded_document() {
+        let form = upload_form(None, Some(("offer.pdf", "application/pdf", b"%PDF-1.7")));
+
+        let payload = AddCommentPayload::try_from(form).expect("expected comment payload");
+
+        match payload.attachment {
+            Some(CommentAttachment::Upload {
+                name,
+                mime,
+                content,
+            }) => {
+                assert_eq!(name.as_str(), "offer.pdf");
+                assert_eq!(mime.as_str(), "application/pdf");
+                assert_eq!(content, b"%PDF-1.7");
+            }
+            _ => panic!("expected an uploaded attachment"),
+        }
+    }
+
+    #[test]
+    fn add_comment_upload_form_ignores_empty_file_parts() {
+        let form = upload_form(None, Some(("", "application/octet-stream", b"")));
+
+        let payload = AddCommentPayload::try_from(form).expect("expected comment payload");
+
+        assert!(payload.attachment.is_none());
+    }
+
+    #[test]
+    fn add_comment_upload_form_rejects_bad_attachments() {
+        let executable = upload_form(None, Some(("run.exe", "application/x-msdownload", b"MZ")));
+        assert!(matches!(
+            AddCommentPayload::try_from(executable),
+            Err(FormError::InvalidAttachmentFile)
+        ));
+
+        let both = upload_form(
+            Some("https://files.example.com/offer.pdf"),
+            Some(("offer.pdf", "application/pdf", b"%PDF-1.7")),
+        );
+        assert!(matches!(
+            AddCommentPayload::try_from(both),
+            Err(FormError::MultipleAttachments)
+        ));
+
+        let internal = upload_form(Some("http://127.0.0.1/offer.pdf"), None);
+        assert!(matches!(
+            AddCommentPayload::try_from(internal),
+            Err(FormError::InvalidAttachmentUrl)
+        ));
     }
 }
@@ -45,6 +45,21 @@ impl TryFrom<ImportantFieldsForm> for ImportantFieldsPayload {
     }
 }
 
+/// Form naming a single important field to remove.
+#[derive(Debug, Deserialize)]
+pub struct DeleteImportantFieldForm {
+    #[serde(default)]
+    pub field: String,
+}
+
+impl TryFrom<DeleteImportantFieldForm> for ImportantFieldName {
+    type Error = FormError;
+
+    fn try_from(form: DeleteImportantFieldForm) -> Result<Self, Self::Error> {
+        ImportantFieldName::new(form.field).map_err(|_| FormError::InvalidImportantFieldName)
+    }
+}
+
 impl ImportantFieldsPayload {
     pub fn into_domain(self, hub_id: HubId) -> Vec<ImportantField> {
         self.fields
@@ -81,4 +96,18 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_delete_important_field_form_trims_and_rejects_blank_names() {
+        let name = ImportantFieldName::try_from(DeleteImportantFieldForm {
+            field: "  Tier ".to_string(),
+        })
+        .unwrap();
+        assert_eq!(name.as_str(), "Tier");
+
+        let result = ImportantFieldName::try_from(DeleteImportantFieldForm {
+            field: "   ".to_string(),
+        });
+        assert!(matches!(result, Err(FormError::InvalidImportantFieldName)));
+    }
 }
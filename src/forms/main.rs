@@ -2,15 +2,19 @@
 
 use std::{collections::BTreeMap, io::Read};
 
-use actix_multipart::form::{MultipartForm, tempfile::TempFile};
+use actix_multipart::form::{MultipartForm, tempfile::TempFile, text::Text};
 use pushkind_common::routes::empty_string_as_none;
 use serde::Deserialize;
 use thiserror::Error;
 use validator::Validate;
 
-use crate::domain::client::NewClient;
-use crate::domain::types::{ClientEmail, ClientName, HubId, PhoneNumber, TypeConstraintError};
-use crate::forms::FormError;
+use crate::domain::client::{ClientSource, NewClient};
+use crate::domain::contact_policy::{ContactPolicy, ContactPolicyViolation};
+use crate::domain::types::{
+    ClientEmail, ClientName, HubId, ImportConflictStrategy, ImportantFieldName, MatchBy,
+    PhoneNumber, PhoneRegion, TypeConstraintError,
+};
+use crate::forms::{FormError, check_contact_policy};
 
 #[derive(Deserialize, Validate)]
 /// Form data used to add a new client.
@@ -25,6 +29,9 @@ pub struct AddClientForm {
     /// Contact phone number.
     #[serde(deserialize_with = "empty_string_as_none")]
     pub phone: Option<String>,
+    /// Token generated per form render; repeated submissions replay the first response.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub idempotency_token: Option<String>,
 }
 
 pub struct AddClientPayload {
@@ -33,45 +40,142 @@ pub struct AddClientPayload {
     pub phone: Option<PhoneNumber>,
 }
 
-impl TryFrom<AddClientForm> for AddClientPayload {
-    type Error = FormError;
-
-    fn try_from(form: AddClientForm) -> Result<Self, Self::Error> {
-        form.validate().map_err(FormError::Validation)?;
-
-        let name = ClientName::new(form.name).map_err(|_| FormError::InvalidName)?;
-        let email = form
+impl AddClientForm {
+    /// Validates the form, reading phone numbers without a country code as
+    /// local numbers of `default_region` when the hub configured one.
+    pub fn into_payload(
+        self,
+        default_region: Option<&PhoneRegion>,
+    ) -> Result<AddClientPayload, FormError> {
+        self.validate().map_err(FormError::Validation)?;
+
+        let name = ClientName::new(self.name).map_err(|_| FormError::InvalidName)?;
+        let email = self
             .email
             .map(ClientEmail::try_from)
             .transpose()
             .map_err(|_| FormError::InvalidEmail)?;
-        let phone = match form.phone {
-            Some(value) => {
-                Some(PhoneNumber::try_from(value).map_err(|_| FormError::InvalidPhoneNumber)?)
-            }
-            None => None,
-        };
+        let phone = self
+            .phone
+            .map(|value| parse_phone(value, default_region))
+            .transpose()
+            .map_err(|_| FormError::InvalidPhoneNumber)?;
 
         if email.is_none() && phone.is_none() {
             Err(FormError::MissingClientContact)
         } else {
-            Ok(Self { name, email, phone })
+            Ok(AddClientPayload { name, email, phone })
         }
     }
 }
 
+impl TryFrom<AddClientForm> for AddClientPayload {
+    type Error = FormError;
+
+    fn try_from(form: AddClientForm) -> Result<Self, Self::Error> {
+        form.into_payload(None)
+    }
+}
+
+/// Parses a user-entered phone number, using `region` when one is configured.
+fn parse_phone(
+    value: String,
+    region: Option<&PhoneRegion>,
+) -> Result<PhoneNumber, TypeConstraintError> {
+    match region {
+        Some(region) => PhoneNumber::with_region(&value, region),
+        None => PhoneNumber::new(value),
+    }
+}
+
 impl AddClientPayload {
+    /// Ensures the payload satisfies the hub contact policy.
+    pub fn check_contact_policy(&self, policy: &ContactPolicy) -> Result<(), FormError> {
+        check_contact_policy(policy, self.email.as_ref(), self.phone.as_ref())
+    }
+
     pub fn into_domain(self, hub_id: HubId) -> NewClient {
         NewClient::new(hub_id, self.name, self.email, self.phone, None)
     }
 }
 
+/// Maximum number of records accepted by one `POST /api/v1/clients/batch`.
+pub const MAX_CLIENT_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+/// One client record of a `POST /api/v1/clients/batch` body.
+pub struct ClientBatchRecord {
+    pub name: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Custom fields; entries with a blank name or value are dropped.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl ClientBatchRecord {
+    /// Validates the record like [`AddClientForm`] and checks it against the
+    /// hub contact `policy`.
+    pub fn into_new_client(
+        self,
+        hub_id: HubId,
+        policy: &ContactPolicy,
+        default_region: Option<&PhoneRegion>,
+    ) -> Result<NewClient, FormError> {
+        let non_blank = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let form = AddClientForm {
+            name: self.name.trim().to_string(),
+            email: non_blank(self.email),
+            phone: non_blank(self.phone),
+            idempotency_token: None,
+        };
+        let payload = form.into_payload(default_region)?;
+        payload.check_contact_policy(policy)?;
+
+        let fields = self
+            .fields
+            .into_iter()
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+            .collect();
+
+        Ok(NewClient::new(
+            hub_id,
+            payload.name,
+            payload.email,
+            payload.phone,
+            Some(fields),
+        )
+        .with_source(ClientSource::Api))
+    }
+}
+
 #[derive(MultipartForm)]
 /// Multipart form for uploading a CSV file with new clients.
 pub struct UploadClientsForm {
     #[multipart(limit = "10MB")]
     /// Uploaded CSV file containing client data.
     pub csv: TempFile,
+    /// Whether rows need an email or phone under the default contact policy.
+    ///
+    /// Defaults to `true`; `false` keeps name-only leads.
+    pub require_contact: Option<Text<bool>>,
+    /// How rows matching an existing client are handled; defaults to
+    /// [`ImportConflictStrategy::Upsert`].
+    pub conflict_strategy: Option<Text<ImportConflictStrategy>>,
+    /// How rows are matched to existing clients; defaults to
+    /// [`MatchBy::Contact`].
+    pub match_by: Option<Text<MatchBy>>,
+    /// Whether unknown column headers abort the upload.
+    ///
+    /// Defaults to `false`, which keeps unknown columns as custom fields.
+    pub strict: Option<Text<bool>>,
 }
 
 #[derive(Debug, Error)]
@@ -83,6 +187,25 @@ pub enum UploadClientsFormError {
     CsvParseError,
     #[error("Invalid client data: {0}")]
     ValidationError(#[from] TypeConstraintError),
+    #[error("Unexpected column: {0}")]
+    UnexpectedColumn(String),
+    #[error("Missing required column: name")]
+    MissingRequiredColumn,
+}
+
+/// CSV row dropped because it does not satisfy the hub contact policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedClientRow {
+    /// 1-based line number in the uploaded file, header included.
+    pub line: u64,
+    pub violations: Vec<ContactPolicyViolation>,
+}
+
+/// Result of parsing an uploaded clients CSV file.
+#[derive(Debug, Default)]
+pub struct ParsedClients {
+    pub clients: Vec<NewClient>,
+    pub rejected: Vec<RejectedClientRow>,
 }
 
 impl From<std::io::Error> for UploadClientsFormError {
@@ -98,16 +221,63 @@ impl From<csv::Error> for UploadClientsFormError {
 }
 
 impl UploadClientsForm {
+    /// Returns the submitted conflict strategy or the default one.
+    #[must_use]
+    pub fn conflict_strategy(&self) -> ImportConflictStrategy {
+        self.conflict_strategy
+            .as_ref()
+            .map(|strategy| strategy.0)
+            .unwrap_or_default()
+    }
+
+    /// Returns the submitted match mode or the default one.
+    #[must_use]
+    pub fn match_by(&self) -> MatchBy {
+        self.match_by
+            .as_ref()
+            .map(|match_by| match_by.0)
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the upload validates its column headers strictly.
+    #[must_use]
+    pub fn strict(&self) -> bool {
+        self.strict.as_ref().is_some_and(|strict| strict.0)
+    }
+
     /// Parse the uploaded CSV file into a list of [`NewClient`] records.
-    pub fn parse(&mut self, hub_id: HubId) -> Result<Vec<NewClient>, UploadClientsFormError> {
+    ///
+    /// Rows violating the hub contact `policy` are collected in
+    /// [`ParsedClients::rejected`] instead of being imported. Rows without any
+    /// contact are kept when [`Self::require_contact`] is `false`. Phone numbers
+    /// without a country code are read as local numbers of `default_region`;
+    /// numbers that still fail to parse are kept as [`NewClient::raw_phone`],
+    /// which counts as a contact but not as a phone for the hub policy.
+    ///
+    /// In [`Self::strict`] mode the file must have a `name` column and every
+    /// other header must be `email`, `phone` or one of `important_fields`.
+    pub fn parse(
+        &mut self,
+        hub_id: HubId,
+        policy: &ContactPolicy,
+        default_region: Option<&PhoneRegion>,
+        important_fields: &[ImportantFieldName],
+    ) -> Result<ParsedClients, UploadClientsFormError> {
         let mut csv_content = String::new();
         self.csv.file.read_to_string(&mut csv_content)?;
 
         let mut rdr = csv::Reader::from_reader(csv_content.as_bytes());
 
-        let mut clients = Vec::new();
+        let require_contact = self
+            .require_contact
+            .as_ref()
+            .is_none_or(|require_contact| require_contact.0);
+        let mut parsed = ParsedClients::default();
 
         let headers = rdr.headers()?.clone();
+        if self.strict() {
+            check_headers(&headers, important_fields)?;
+        }
 
         for result in rdr.records() {
             let record = result?;
@@ -159,25 +329,58 @@ impl UploadClientsForm {
                 .map(ClientEmail::try_from)
                 .and_then(|result| result.ok());
 
-            let phone = match phone {
-                Some(value) => Some(PhoneNumber::try_from(value)?),
-                None => None,
+            // Numbers failing strict parsing are kept verbatim instead of
+            // dropping the contact.
+            let (phone, raw_phone) = match phone {
+                Some(value) => match parse_phone(value.clone(), default_region) {
+                    Ok(phone) => (Some(phone), None),
+                    Err(_) => (None, RawPhone::new(value).ok()),
+                },
+                None => (None, None),
             };
 
-            if email.is_none() && phone.is_none() {
+            let mut violations = policy.violations(email.as_ref(), phone.as_ref());
+            if !require_contact || raw_phone.is_some() {
+                violations.retain(|violation| *violation != ContactPolicyViolation::MissingContact);
+            }
+            if !violations.is_empty() {
+                parsed.rejected.push(RejectedClientRow {
+                    line: record.position().map_or(0, |position| position.line()),
+                    violations,
+                });
                 continue;
             }
 
-            clients.push(NewClient::new(
-                hub_id,
-                name,
-                email,
-                phone,
-                Some(optional_fields),
-            ));
+            let mut client = NewClient::new(hub_id, name, email, phone, Some(optional_fields))
+                .with_source(ClientSource::Csv);
+            if let Some(raw_phone) = raw_phone {
+                client = client.with_raw_phone(raw_phone);
+            }
+            parsed.clients.push(client);
         }
 
-        Ok(clients)
+        Ok(parsed)
+    }
+}
+
+/// Rejects headers other than the core columns and the hub's important fields.
+fn check_headers(
+    headers: &csv::StringRecord,
+    important_fields: &[ImportantFieldName],
+) -> Result<(), UploadClientsFormError> {
+    if !headers.iter().any(|header| header == "name") {
+        return Err(UploadClientsFormError::MissingRequiredColumn);
+    }
+
+    let unexpected = headers.iter().find(|header| {
+        !matches!(*header, "name" | "email" | "phone")
+            && !important_fields
+                .iter()
+                .any(|field| field.as_str() == *header)
+    });
+    match unexpected {
+        Some(header) => Err(UploadClientsFormError::UnexpectedColumn(header.to_string())),
+        None => Ok(()),
     }
 }
 
@@ -191,6 +394,7 @@ mod tests {
             name: "Alice".to_string(),
             email: Some("Alice@Example.COM".to_string()),
             phone: Some("+1 (415) 555-2671".to_string()),
+            idempotency_token: None,
         };
 
         let payload = AddClientPayload::try_from(form).expect("expected valid payload");
@@ -216,10 +420,394 @@ mod tests {
             name: "Bob".to_string(),
             email: None,
             phone: None,
+            idempotency_token: None,
         };
 
         let payload = AddClientPayload::try_from(form);
 
         assert!(payload.is_err())
     }
+
+    fn upload_form(csv: &str) -> UploadClientsForm {
+        use std::io::{Seek, Write};
+
+        let mut file = tempfile::NamedTempFile::new().expect("temp file");
+        file.write_all(csv.as_bytes()).expect("write csv");
+        file.rewind().expect("rewind csv");
+
+        UploadClientsForm {
+            csv: TempFile {
+                file,
+                content_type: None,
+                file_name: None,
+                size: csv.len(),
+            },
+            require_contact: None,
+            conflict_strategy: None,
+            match_by: None,
+            strict: None,
+        }
+    }
+
+    const CONTACTS_CSV: &str = "name,email,phone\n\
+        Both,both@example.com,+14155552671\n\
+        EmailOnly,email@example.com,\n\
+        PhoneOnly,,+14155552672\n\
+        Nobody,,\n";
+
+    fn parsed_names(parsed: &ParsedClients) -> Vec<&str> {
+        parsed
+            .clients
+            .iter()
+            .map(|client| client.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn csv_parse_default_policy_requires_any_contact() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let parsed = upload_form(CONTACTS_CSV)
+            .parse(hub_id, &ContactPolicy::default(), None, &[])
+            .expect("csv parses");
+
+        assert_eq!(
+            parsed_names(&parsed),
+            vec!["Both", "EmailOnly", "PhoneOnly"]
+        );
+        assert_eq!(
+            parsed.rejected,
+            vec![RejectedClientRow {
+                line: 5,
+                violations: vec![ContactPolicyViolation::MissingContact],
+            }]
+        );
+    }
+
+    #[test]
+    fn csv_parse_without_contact_requirement_keeps_name_only_rows() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let mut form = upload_form(CONTACTS_CSV);
+        form.require_contact = Some(Text(false));
+
+        let parsed = form
+            .parse(hub_id, &ContactPolicy::default(), None, &[])
+            .expect("csv parses");
+
+        assert_eq!(
+            parsed_names(&parsed),
+            vec!["Both", "EmailOnly", "PhoneOnly", "Nobody"]
+        );
+        assert!(parsed.rejected.is_empty());
+        let nobody = &parsed.clients[3];
+        assert!(nobody.email.is_none() && nobody.phone.is_none());
+        assert!(
+            parsed
+                .clients
+                .iter()
+                .all(|client| client.source == Some(ClientSource::Csv))
+        );
+    }
+
+    #[test]
+    fn csv_parse_without_contact_requirement_keeps_explicit_hub_policy() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let mut form = upload_form(CONTACTS_CSV);
+        form.require_contact = Some(Text(false));
+        let policy = ContactPolicy {
+            require_email: true,
+            require_phone: false,
+        };
+
+        let parsed = form.parse(hub_id, &policy, None, &[]).expect("csv parses");
+
+        assert_eq!(parsed_names(&parsed), vec!["Both", "EmailOnly"]);
+        assert_eq!(
+            parsed
+                .rejected
+                .iter()
+                .map(|row| row.line)
+                .collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+    }
+
+    #[test]
+    fn csv_parse_require_email_policy_rejects_phone_only_rows() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let policy = ContactPolicy {
+            require_email: true,
+            require_phone: false,
+        };
+        let parsed = upload_form(CONTACTS_CSV)
+            .parse(hub_id, &policy, None, &[])
+            .expect("csv parses");
+
+        assert_eq!(parsed_names(&parsed), vec!["Both", "EmailOnly"]);
+        assert_eq!(
+            parsed
+                .rejected
+                .iter()
+                .map(|row| row.line)
+                .collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+    }
+
+    #[test]
+    fn csv_parse_require_phone_policy_rejects_email_only_rows() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let policy = ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        };
+        let parsed = upload_form(CONTACTS_CSV)
+            .parse(hub_id, &policy, None, &[])
+            .expect("csv parses");
+
+        assert_eq!(parsed_names(&parsed), vec!["Both", "PhoneOnly"]);
+        assert_eq!(
+            parsed.rejected,
+            vec![
+                RejectedClientRow {
+                    line: 3,
+                    violations: vec![ContactPolicyViolation::MissingPhone],
+                },
+                RejectedClientRow {
+                    line: 5,
+                    violations: vec![ContactPolicyViolation::MissingPhone],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_parse_reads_local_phones_with_hub_region() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let region = PhoneRegion::new("DE").expect("valid region");
+        let csv = "name,phone\nBerlin,030 12345678\nAbroad,+14155552671\n";
+
+        let parsed = upload_form(csv)
+            .parse(hub_id, &ContactPolicy::default(), Some(&region), &[])
+            .expect("csv parses");
+
+        let phones = parsed
+            .clients
+            .iter()
+            .map(|client| client.phone.as_ref().map(PhoneNumber::as_str))
+            .collect::<Vec<_>>();
+        assert_eq!(phones, vec![Some("+493012345678"), Some("+14155552671")]);
+    }
+
+    #[test]
+    fn csv_parse_keeps_unparseable_phone_as_raw() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let csv = "name,email,phone\nLocal,,12-34\nBoth,both@example.com,ext. 42\n";
+
+        let parsed = upload_form(csv)
+            .parse(hub_id, &ContactPolicy::default(), None, &[])
+            .expect("csv parses");
+
+        assert!(parsed.rejected.is_empty());
+        assert_eq!(parsed_names(&parsed), vec!["Local", "Both"]);
+        assert!(parsed.clients.iter().all(|client| client.phone.is_none()));
+        let raw_phones = parsed
+            .clients
+            .iter()
+            .map(|client| client.raw_phone.as_ref().map(RawPhone::as_str))
+            .collect::<Vec<_>>();
+        assert_eq!(raw_phones, vec![Some("12-34"), Some("ext. 42")]);
+
+        let require_phone = ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        };
+        let parsed = upload_form("name,phone\nLocal,12-34\n")
+            .parse(hub_id, &require_phone, None, &[])
+            .expect("csv parses");
+        assert!(parsed.clients.is_empty());
+        assert_eq!(
+            parsed.rejected[0].violations,
+            vec![ContactPolicyViolation::MissingPhone]
+        );
+    }
+
+    #[test]
+    fn csv_parse_lenient_mode_keeps_unknown_columns_as_fields() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let csv = "name,email,Notes\nAlice,alice@example.com,VIP\n";
+
+        let parsed = upload_form(csv)
+            .parse(hub_id, &ContactPolicy::default(), None, &[])
+            .expect("csv parses");
+
+        assert_eq!(
+            parsed.clients[0]
+                .fields
+                .as_ref()
+                .and_then(|fields| fields.get("Notes"))
+                .map(String::as_str),
+            Some("VIP")
+        );
+
+        let parsed = upload_form("email\nalice@example.com\n")
+            .parse(hub_id, &ContactPolicy::default(), None, &[])
+            .expect("csv parses");
+        assert!(parsed.clients.is_empty());
+    }
+
+    #[test]
+    fn csv_parse_strict_mode_accepts_core_and_important_columns() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let important = [ImportantFieldName::new("Город").expect("valid field")];
+        let mut form = upload_form("name,email,phone,Город\nAlice,alice@example.com,,Казань\n");
+        form.strict = Some(Text(true));
+
+        let parsed = form
+            .parse(hub_id, &ContactPolicy::default(), None, &important)
+            .expect("csv parses");
+
+        assert_eq!(parsed_names(&parsed), vec!["Alice"]);
+    }
+
+    #[test]
+    fn csv_parse_strict_mode_rejects_unexpected_columns() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let important = [ImportantFieldName::new("Город").expect("valid field")];
+        let mut form = upload_form("name,email,Notes\nAlice,alice@example.com,VIP\n");
+        form.strict = Some(Text(true));
+
+        let result = form.parse(hub_id, &ContactPolicy::default(), None, &important);
+
+        assert!(matches!(
+            result,
+            Err(UploadClientsFormError::UnexpectedColumn(column)) if column == "Notes"
+        ));
+    }
+
+    #[test]
+    fn csv_parse_strict_mode_requires_name_column() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let mut form = upload_form("email,phone\nalice@example.com,\n");
+        form.strict = Some(Text(true));
+
+        let result = form.parse(hub_id, &ContactPolicy::default(), None, &[]);
+
+        assert!(matches!(
+            result,
+            Err(UploadClientsFormError::MissingRequiredColumn)
+        ));
+    }
+
+    #[test]
+    fn add_client_form_reads_local_phone_with_hub_region() {
+        let region = PhoneRegion::new("US").expect("valid region");
+        let form = || AddClientForm {
+            name: "Alice".to_string(),
+            email: None,
+            phone: Some("(415) 555-2671".to_string()),
+            idempotency_token: None,
+        };
+
+        let payload = form().into_payload(Some(&region)).expect("valid payload");
+
+        assert_eq!(
+            payload.phone.as_ref().map(PhoneNumber::as_str),
+            Some("+14155552671")
+        );
+        assert!(matches!(
+            AddClientPayload::try_from(form()),
+            Err(FormError::InvalidPhoneNumber)
+        ));
+    }
+
+    fn batch_record(name: &str, email: Option<&str>, phone: Option<&str>) -> ClientBatchRecord {
+        ClientBatchRecord {
+            name: name.to_string(),
+            email: email.map(str::to_string),
+            phone: phone.map(str::to_string),
+            fields: BTreeMap::from([
+                (" Tier ".to_string(), " Gold ".to_string()),
+                ("Blank".to_string(), "  ".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn batch_record_normalizes_contacts_and_fields() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let client = batch_record(" Alice ", Some(" Alice@Example.com "), Some(""))
+            .into_new_client(hub_id, &ContactPolicy::default(), None)
+            .expect("valid record");
+
+        assert_eq!(client.name.as_str(), "Alice");
+        assert_eq!(
+            client.email.expect("email kept").as_str(),
+            "alice@example.com"
+        );
+        assert!(client.phone.is_none());
+        assert_eq!(
+            client.fields,
+            Some(BTreeMap::from([("Tier".to_string(), "Gold".to_string())]))
+        );
+        assert_eq!(client.source, Some(ClientSource::Api));
+    }
+
+    #[test]
+    fn batch_record_rejects_invalid_and_policy_violating_records() {
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let require_phone = ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        };
+
+        assert!(matches!(
+            batch_record("  ", Some("a@example.com"), None).into_new_client(
+                hub_id,
+                &ContactPolicy::default(),
+                None
+            ),
+            Err(FormError::Validation(_) | FormError::InvalidName)
+        ));
+        assert!(matches!(
+            batch_record("Bob", None, None).into_new_client(
+                hub_id,
+                &ContactPolicy::default(),
+                None
+            ),
+            Err(FormError::MissingClientContact)
+        ));
+        assert!(matches!(
+            batch_record("Bob", Some("bob@example.com"), None).into_new_client(
+                hub_id,
+                &require_phone,
+                None
+            ),
+            Err(FormError::ContactPolicy(_))
+        ));
+    }
+
+    #[test]
+    fn add_client_payload_checks_contact_policy() {
+        let payload = AddClientPayload::try_from(AddClientForm {
+            name: "Alice".to_string(),
+            email: None,
+            phone: Some("+14155552671".to_string()),
+            idempotency_token: None,
+        })
+        .expect("expected valid payload");
+
+        assert!(
+            payload
+                .check_contact_policy(&ContactPolicy::default())
+                .is_ok()
+        );
+        assert!(matches!(
+            payload.check_contact_policy(&ContactPolicy {
+                require_email: true,
+                require_phone: false,
+            }),
+            Err(FormError::ContactPolicy(violations))
+                if violations == vec![ContactPolicyViolation::MissingEmail]
+        ));
+    }
 }
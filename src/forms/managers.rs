@@ -1,12 +1,21 @@
 //! Forms for creating and assigning managers.
 
+use std::io::Read;
+
+use actix_multipart::form::{MultipartForm, tempfile::TempFile};
+use pushkind_common::routes::empty_string_as_none;
 use serde::Deserialize;
+use thiserror::Error;
 use validator::Validate;
 
 use crate::{
     domain::{
+        assignment_rule::NewManagerAssignmentRule,
         manager::NewManager,
-        types::{ClientId, HubId, ManagerEmail, ManagerId, ManagerName},
+        types::{
+            ClientId, HubId, ImportantFieldName, ManagerEmail, ManagerId, ManagerName,
+            NonEmptyString, PhoneNumber,
+        },
     },
     forms::FormError,
 };
@@ -17,11 +26,19 @@ pub struct AddManagerForm {
     pub name: String,
     #[validate(email(message = "Укажите корректный электронный адрес."))]
     pub email: String,
+    /// Optional company the manager works for.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub company: Option<String>,
+    /// Optional contact phone number.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub phone: Option<String>,
 }
 
 pub struct AddManagerPayload {
     pub name: ManagerName,
     pub email: ManagerEmail,
+    pub company: Option<NonEmptyString>,
+    pub phone: Option<PhoneNumber>,
 }
 
 #[derive(Deserialize)]
@@ -36,6 +53,133 @@ pub struct AssignManagerPayload {
     pub client_ids: Vec<ClientId>,
 }
 
+#[derive(MultipartForm)]
+/// Multipart form for uploading a CSV of `client_email,manager_email` pairs.
+pub struct UploadManagerAssignmentsForm {
+    #[multipart(limit = "10MB")]
+    pub csv: TempFile,
+}
+
+#[derive(Debug, Error)]
+/// Errors that can occur while parsing an uploaded manager assignments file.
+pub enum UploadManagerAssignmentsFormError {
+    #[error("Error reading csv file")]
+    FileReadError,
+    #[error("Error parsing csv file")]
+    CsvParseError,
+    #[error("Missing required column: {0}")]
+    MissingColumn(&'static str),
+}
+
+/// One row of an uploaded manager assignments file, as written in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagerAssignmentRow {
+    /// 1-based line number in the uploaded file, header included.
+    pub line: u64,
+    pub client_email: String,
+    pub manager_email: String,
+}
+
+impl From<std::io::Error> for UploadManagerAssignmentsFormError {
+    fn from(_: std::io::Error) -> Self {
+        UploadManagerAssignmentsFormError::FileReadError
+    }
+}
+
+impl From<csv::Error> for UploadManagerAssignmentsFormError {
+    fn from(_: csv::Error) -> Self {
+        UploadManagerAssignmentsFormError::CsvParseError
+    }
+}
+
+impl UploadManagerAssignmentsForm {
+    /// Parse the uploaded CSV file into trimmed assignment rows.
+    ///
+    /// The header must name the `client_email` and `manager_email` columns;
+    /// other columns are ignored. Rows are returned even when a value is
+    /// blank so the caller can report them.
+    pub fn parse(
+        &mut self,
+    ) -> Result<Vec<ManagerAssignmentRow>, UploadManagerAssignmentsFormError> {
+        let mut csv_content = String::new();
+        self.csv.file.read_to_string(&mut csv_content)?;
+
+        let mut rdr = csv::Reader::from_reader(csv_content.as_bytes());
+        let headers = rdr.headers()?.clone();
+        let column = |name: &'static str| {
+            headers
+                .iter()
+                .position(|header| header.trim() == name)
+                .ok_or(UploadManagerAssignmentsFormError::MissingColumn(name))
+        };
+        let client_column = column("client_email")?;
+        let manager_column = column("manager_email")?;
+
+        let mut rows = Vec::new();
+        for result in rdr.records() {
+            let record = result?;
+            let value = |index| record.get(index).unwrap_or_default().trim().to_string();
+            rows.push(ManagerAssignmentRow {
+                line: record.position().map_or(0, |position| position.line()),
+                client_email: value(client_column),
+                manager_email: value(manager_column),
+            });
+        }
+
+        Ok(rows)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TransferClientsForm {
+    pub from_manager_id: i32,
+    pub to_manager_id: i32,
+}
+
+pub struct TransferClientsPayload {
+    pub from_id: ManagerId,
+    pub to_id: ManagerId,
+}
+
+#[derive(Deserialize)]
+pub struct SetManagerActiveForm {
+    pub manager_id: i32,
+    pub is_active: bool,
+}
+
+pub struct SetManagerActivePayload {
+    pub manager_id: ManagerId,
+    pub is_active: bool,
+}
+
+/// Form a manager submits to change their own profile.
+#[derive(Deserialize)]
+pub struct UpdateOwnManagerForm {
+    pub name: String,
+    /// Optional contact phone; blank clears it.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub phone: Option<String>,
+}
+
+pub struct UpdateOwnManagerPayload {
+    pub name: ManagerName,
+    pub phone: Option<PhoneNumber>,
+}
+
+/// Form submitted to assign a manager to clients with a custom field value.
+#[derive(Deserialize)]
+pub struct NewManagerAssignmentRuleForm {
+    pub manager_id: i32,
+    pub field: String,
+    pub value: String,
+}
+
+pub struct NewManagerAssignmentRulePayload {
+    pub manager_id: ManagerId,
+    pub field: ImportantFieldName,
+    pub value: NonEmptyString,
+}
+
 impl TryFrom<AddManagerForm> for AddManagerPayload {
     type Error = FormError;
 
@@ -44,8 +188,21 @@ impl TryFrom<AddManagerForm> for AddManagerPayload {
 
         let name = ManagerName::new(value.name).map_err(|_| FormError::InvalidName)?;
         let email = ManagerEmail::try_from(value.email).map_err(|_| FormError::InvalidEmail)?;
+        let company = value
+            .company
+            .and_then(|company| NonEmptyString::new(company).ok());
+        let phone = value
+            .phone
+            .map(PhoneNumber::try_from)
+            .transpose()
+            .map_err(|_| FormError::InvalidPhoneNumber)?;
 
-        Ok(Self { name, email })
+        Ok(Self {
+            name,
+            email,
+            company,
+            phone,
+        })
     }
 }
 
@@ -68,8 +225,137 @@ impl TryFrom<AssignManagerForm> for AssignManagerPayload {
     }
 }
 
+impl TryFrom<TransferClientsForm> for TransferClientsPayload {
+    type Error = FormError;
+
+    fn try_from(value: TransferClientsForm) -> Result<Self, Self::Error> {
+        let from_id =
+            ManagerId::new(value.from_manager_id).map_err(|_| FormError::InvalidManagerId)?;
+        let to_id = ManagerId::new(value.to_manager_id).map_err(|_| FormError::InvalidManagerId)?;
+
+        Ok(Self { from_id, to_id })
+    }
+}
+
+impl TryFrom<SetManagerActiveForm> for SetManagerActivePayload {
+    type Error = FormError;
+
+    fn try_from(value: SetManagerActiveForm) -> Result<Self, Self::Error> {
+        let manager_id =
+            ManagerId::new(value.manager_id).map_err(|_| FormError::InvalidManagerId)?;
+
+        Ok(Self {
+            manager_id,
+            is_active: value.is_active,
+        })
+    }
+}
+
+impl TryFrom<UpdateOwnManagerForm> for UpdateOwnManagerPayload {
+    type Error = FormError;
+
+    fn try_from(value: UpdateOwnManagerForm) -> Result<Self, Self::Error> {
+        let name = ManagerName::new(value.name).map_err(|_| FormError::InvalidName)?;
+        let phone = value
+            .phone
+            .map(PhoneNumber::try_from)
+            .transpose()
+            .map_err(|_| FormError::InvalidPhoneNumber)?;
+
+        Ok(Self { name, phone })
+    }
+}
+
+impl TryFrom<NewManagerAssignmentRuleForm> for NewManagerAssignmentRulePayload {
+    type Error = FormError;
+
+    fn try_from(value: NewManagerAssignmentRuleForm) -> Result<Self, Self::Error> {
+        let manager_id =
+            ManagerId::new(value.manager_id).map_err(|_| FormError::InvalidManagerId)?;
+        let field =
+            ImportantFieldName::new(value.field).map_err(|_| FormError::InvalidRuleField)?;
+        let value = NonEmptyString::new(value.value).map_err(|_| FormError::InvalidRuleValue)?;
+
+        Ok(Self {
+            manager_id,
+            field,
+            value,
+        })
+    }
+}
+
 impl AddManagerPayload {
     pub fn into_domain(self, hub_id: HubId) -> NewManager {
         NewManager::new(hub_id, self.name, self.email, true)
+            .with_contact_details(self.company, self.phone)
+    }
+}
+
+impl NewManagerAssignmentRulePayload {
+    pub fn into_domain(self, hub_id: HubId) -> NewManagerAssignmentRule {
+        NewManagerAssignmentRule::new(hub_id, self.manager_id, self.field, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload_form(csv: &str) -> UploadManagerAssignmentsForm {
+        use std::io::{Seek, Write};
+
+        let mut file = tempfile::NamedTempFile::new().expect("temp file");
+        file.write_all(csv.as_bytes()).expect("write csv");
+        file.rewind().expect("rewind csv");
+
+        UploadManagerAssignmentsForm {
+            csv: TempFile {
+                file,
+                content_type: None,
+                file_name: None,
+                size: csv.len(),
+            },
+        }
+    }
+
+    #[test]
+    fn upload_manager_assignments_form_reads_rows_by_header() {
+        let mut form = upload_form(
+            "manager_email,note,client_email\n\
+             m@example.com,vip, alice@example.com \n\
+             ,,bob@example.com\n",
+        );
+
+        let rows = form.parse().expect("rows parse");
+
+        assert_eq!(
+            rows,
+            vec![
+                ManagerAssignmentRow {
+                    line: 2,
+                    client_email: "alice@example.com".to_string(),
+                    manager_email: "m@example.com".to_string(),
+                },
+                ManagerAssignmentRow {
+                    line: 3,
+                    client_email: "bob@example.com".to_string(),
+                    manager_email: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn upload_manager_assignments_form_requires_both_columns() {
+        let mut form = upload_form("client_email\nalice@example.com\n");
+
+        let result = form.parse();
+
+        assert!(matches!(
+            result,
+            Err(UploadManagerAssignmentsFormError::MissingColumn(
+                "manager_email"
+            ))
+        ));
     }
 }
@@ -6,11 +6,18 @@ use pushkind_common::dto::mutation::{ApiFieldErrorDto, ApiMutationErrorDto};
 use thiserror::Error;
 use validator::{ValidationError, ValidationErrors};
 
+use crate::domain::contact_policy::{ContactPolicy, ContactPolicyViolation};
+use crate::domain::types::{ClientEmail, PhoneNumber};
+
+pub mod api_keys;
 pub mod client;
 pub mod important_fields;
 pub mod main;
 pub mod managers;
+pub mod segments;
+pub mod settings;
 pub mod store;
+pub mod webhooks;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FormFieldError {
@@ -48,23 +55,77 @@ pub enum FormError {
     #[error("Укажите электронный адрес или телефон.")]
     MissingClientContact,
 
+    #[error("{}", contact_policy_display(.0))]
+    ContactPolicy(Vec<ContactPolicyViolation>),
+
     #[error("Введите сообщение.")]
     InvalidCommentMessage,
 
     #[error("Тема заполнена некорректно.")]
     InvalidCommentSubject,
 
+    #[error("Укажите тип события длиной до 64 символов.")]
+    InvalidEventSubtype,
+
     #[error("Укажите название вложения.")]
     InvalidAttachmentName,
 
+    #[error("Укажите корректную ссылку на документ.")]
+    InvalidAttachmentUrl,
+
+    #[error("Прикрепите документ PDF, Office, архив, изображение или текст размером до 10 МБ.")]
+    InvalidAttachmentFile,
+
+    #[error("Укажите тип документа, например application/pdf.")]
+    InvalidAttachmentMime,
+
+    #[error("Прикрепите либо файл, либо ссылку на документ.")]
+    MultipleAttachments,
+
     #[error("Название поля заполнено некорректно.")]
     InvalidImportantFieldName,
+
+    #[error("Укажите поле клиента.")]
+    InvalidRuleField,
+
+    #[error("Укажите значение поля.")]
+    InvalidRuleValue,
+
+    #[error("Фильтр сегмента заполнен некорректно.")]
+    InvalidSegmentFilter,
+
+    #[error("Укажите число клиентов на странице от 1 до 100.")]
+    InvalidItemsPerPage,
+
+    #[error("Имя отправителя заполнено некорректно.")]
+    InvalidEmailSenderName,
+
+    #[error("Укажите двухбуквенный код страны (ISO 3166-1), например RU.")]
+    InvalidPhoneRegion,
+
+    #[error("Укажите срок хранения событий в днях (целое число не меньше 1).")]
+    InvalidEventRetentionDays,
+
+    #[error("Адрес для ответов указан некорректно.")]
+    InvalidEmailReplyTo,
+
+    #[error("Укажите роли ключа: crm, crm_manager или crm_admin.")]
+    InvalidApiKeyRoles,
 }
 
 impl FormError {
     pub(crate) fn field_errors(&self) -> Vec<FormFieldError> {
         match self {
             Self::Validation(errors) => collect_validation_errors(errors),
+            Self::ContactPolicy(violations) => violations
+                .iter()
+                .map(|violation| {
+                    field_error(
+                        contact_policy_field(*violation),
+                        contact_policy_message(*violation),
+                    )
+                })
+                .collect(),
             _ => self
                 .field()
                 .map(|field| vec![field_error(field, self.to_string())])
@@ -83,10 +144,24 @@ impl FormError {
             Self::InvalidPhoneNumber => Some("phone"),
             Self::InvalidUrl => Some("url"),
             Self::MissingClientContact => Some("email"),
+            Self::ContactPolicy(_) => None,
             Self::InvalidCommentMessage => Some("message"),
             Self::InvalidCommentSubject => Some("subject"),
+            Self::InvalidEventSubtype => Some("event_subtype"),
             Self::InvalidAttachmentName => Some("text"),
+            Self::InvalidAttachmentUrl => Some("attachment_url"),
+            Self::InvalidAttachmentFile | Self::MultipleAttachments => Some("attachment"),
+            Self::InvalidAttachmentMime => Some("mime"),
             Self::InvalidImportantFieldName => Some("fields"),
+            Self::InvalidRuleField => Some("field"),
+            Self::InvalidRuleValue => Some("value"),
+            Self::InvalidSegmentFilter => Some("filter_json"),
+            Self::InvalidItemsPerPage => Some("items_per_page"),
+            Self::InvalidEmailSenderName => Some("email_sender_name"),
+            Self::InvalidPhoneRegion => Some("default_phone_region"),
+            Self::InvalidEventRetentionDays => Some("event_retention_days"),
+            Self::InvalidEmailReplyTo => Some("email_reply_to"),
+            Self::InvalidApiKeyRoles => Some("roles"),
         }
     }
 }
@@ -124,6 +199,43 @@ fn validation_errors_display(errors: &ValidationErrors) -> String {
     }
 }
 
+/// Checks client contacts against the hub policy, collecting every violation.
+pub(crate) fn check_contact_policy(
+    policy: &ContactPolicy,
+    email: Option<&ClientEmail>,
+    phone: Option<&PhoneNumber>,
+) -> Result<(), FormError> {
+    let violations = policy.violations(email, phone);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(FormError::ContactPolicy(violations))
+    }
+}
+
+fn contact_policy_field(violation: ContactPolicyViolation) -> &'static str {
+    match violation {
+        ContactPolicyViolation::MissingContact | ContactPolicyViolation::MissingEmail => "email",
+        ContactPolicyViolation::MissingPhone => "phone",
+    }
+}
+
+fn contact_policy_message(violation: ContactPolicyViolation) -> &'static str {
+    match violation {
+        ContactPolicyViolation::MissingContact => "Укажите электронный адрес или телефон.",
+        ContactPolicyViolation::MissingEmail => "Укажите электронный адрес.",
+        ContactPolicyViolation::MissingPhone => "Укажите телефон.",
+    }
+}
+
+fn contact_policy_display(violations: &[ContactPolicyViolation]) -> String {
+    violations
+        .iter()
+        .map(|violation| contact_policy_message(*violation))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn field_error(field: &'static str, message: impl Into<Cow<'static, str>>) -> FormFieldError {
     FormFieldError {
         field: Cow::Borrowed(field),
@@ -149,7 +261,9 @@ impl From<&FormError> for ApiMutationErrorDto {
 
 #[cfg(test)]
 mod tests {
-    use super::FormError;
+    use super::{FormError, check_contact_policy};
+    use crate::domain::contact_policy::{ContactPolicy, ContactPolicyViolation};
+    use crate::domain::types::{ClientEmail, PhoneNumber};
     use crate::forms::client::{AddAttachmentForm, AddCommentForm, SaveClientForm};
     use crate::forms::main::AddClientForm;
     use crate::forms::managers::AddManagerForm;
@@ -171,6 +285,7 @@ mod tests {
             name: String::new(),
             email: Some("invalid".to_string()),
             phone: None,
+            idempotency_token: None,
         };
 
         let error = FormError::from(form.validate().expect_err("form should be invalid"));
@@ -193,6 +308,8 @@ mod tests {
             AddManagerForm {
                 name: String::new(),
                 email: "invalid".to_string(),
+                company: None,
+                phone: None,
             }
             .validate()
             .expect_err("form should be invalid"),
@@ -216,6 +333,7 @@ mod tests {
                 phone: None,
                 field: Vec::new(),
                 value: Vec::new(),
+                version: None,
             }
             .validate()
             .expect_err("form should be invalid"),
@@ -239,6 +357,8 @@ mod tests {
             AddAttachmentForm {
                 text: String::new(),
                 url: "invalid-url".to_string(),
+                mime: None,
+                size_bytes: None,
             }
             .validate()
             .expect_err("form should be invalid"),
@@ -257,6 +377,8 @@ mod tests {
                 subject: None,
                 message: String::new(),
                 event_type: String::new(),
+                event_subtype: None,
+                attachment_url: None,
             }
             .validate()
             .expect_err("form should be invalid"),
@@ -292,4 +414,73 @@ mod tests {
             "Введите сообщение."
         );
     }
+
+    #[test]
+    fn contact_policy_violations_map_to_their_fields() {
+        let email = ClientEmail::new("client@example.com").expect("valid email");
+        let phone = PhoneNumber::new("+14155552671").expect("valid phone");
+
+        assert!(check_contact_policy(&ContactPolicy::default(), Some(&email), None).is_ok());
+        assert_eq!(
+            field_errors(
+                &check_contact_policy(&ContactPolicy::default(), None, None)
+                    .expect_err("default policy needs a contact")
+            ),
+            vec![(
+                "email".to_string(),
+                "Укажите электронный адрес или телефон.".to_string(),
+            )]
+        );
+
+        let require_email = ContactPolicy {
+            require_email: true,
+            require_phone: false,
+        };
+        assert_eq!(
+            field_errors(
+                &check_contact_policy(&require_email, None, Some(&phone))
+                    .expect_err("email is required")
+            ),
+            vec![(
+                "email".to_string(),
+                "Укажите электронный адрес.".to_string()
+            )]
+        );
+
+        let require_phone = ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        };
+        assert_eq!(
+            field_errors(
+                &check_contact_policy(&require_phone, Some(&email), None)
+                    .expect_err("phone is required")
+            ),
+            vec![("phone".to_string(), "Укажите телефон.".to_string())]
+        );
+
+        let require_both = ContactPolicy {
+            require_email: true,
+            require_phone: true,
+        };
+        let error = check_contact_policy(&require_both, None, None)
+            .expect_err("both contacts are required");
+        assert!(matches!(
+            &error,
+            FormError::ContactPolicy(violations) if violations == &[
+                ContactPolicyViolation::MissingEmail,
+                ContactPolicyViolation::MissingPhone,
+            ]
+        ));
+        assert_eq!(
+            field_errors(&error),
+            vec![
+                (
+                    "email".to_string(),
+                    "Укажите электронный адрес.".to_string()
+                ),
+                ("phone".to_string(), "Укажите телефон.".to_string()),
+            ]
+        );
+    }
 }
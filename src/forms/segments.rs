@@ -0,0 +1,64 @@
+//! Forms for defining rule-based client segments.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    domain::{segment::parse_field_matchers, types::NonEmptyString},
+    forms::FormError,
+};
+
+/// Form submitted to create or update a segment.
+#[derive(Debug, Deserialize)]
+pub struct SaveSegmentForm {
+    pub name: String,
+    /// JSON filter, e.g. `{"fields": {"Stage": "Won"}}`.
+    pub filter_json: String,
+}
+
+pub struct SaveSegmentPayload {
+    pub name: NonEmptyString,
+    pub filter_json: Value,
+}
+
+impl TryFrom<SaveSegmentForm> for SaveSegmentPayload {
+    type Error = FormError;
+
+    fn try_from(form: SaveSegmentForm) -> Result<Self, Self::Error> {
+        let name = NonEmptyString::new(form.name).map_err(|_| FormError::InvalidName)?;
+        let filter_json = serde_json::from_str::<Value>(&form.filter_json)
+            .map_err(|_| FormError::InvalidSegmentFilter)?;
+        parse_field_matchers(&filter_json).map_err(|_| FormError::InvalidSegmentFilter)?;
+
+        Ok(Self { name, filter_json })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_segment_form_parses_filter_json() {
+        let payload = SaveSegmentPayload::try_from(SaveSegmentForm {
+            name: " VIP ".to_string(),
+            filter_json: r#"{"fields": {"Tier": "VIP"}}"#.to_string(),
+        })
+        .expect("valid payload");
+
+        assert_eq!(payload.name.as_str(), "VIP");
+        assert_eq!(payload.filter_json["fields"]["Tier"], "VIP");
+    }
+
+    #[test]
+    fn save_segment_form_rejects_invalid_filter() {
+        for filter_json in ["not json", r#"{"fields": {}}"#] {
+            let result = SaveSegmentPayload::try_from(SaveSegmentForm {
+                name: "VIP".to_string(),
+                filter_json: filter_json.to_string(),
+            });
+
+            assert!(matches!(result, Err(FormError::InvalidSegmentFilter)));
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Forms for editing per-hub settings.
+
+use pushkind_common::routes::empty_string_as_none;
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        hub_settings::{HubSettings, MAX_ITEMS_PER_PAGE},
+        types::{HubId, ManagerEmail, NonEmptyString, PhoneRegion, TypeConstraintError},
+    },
+    forms::FormError,
+};
+
+/// Form submitted from the settings page.
+#[derive(Debug, Deserialize)]
+pub struct HubSettingsForm {
+    pub items_per_page: u16,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub email_sender_name: Option<String>,
+    /// Unchecked checkboxes are not submitted, hence the default.
+    #[serde(default)]
+    pub allow_manager_self_assign: bool,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub default_phone_region: Option<String>,
+    /// Blank keeps client events forever.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event_retention_days: Option<String>,
+    /// Blank sends replies to the manager who wrote the email.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub email_reply_to: Option<String>,
+}
+
+/// Validated hub settings ready to be stored for a hub.
+pub struct HubSettingsPayload {
+    pub items_per_page: u16,
+    pub email_sender_name: Option<NonEmptyString>,
+    pub allow_manager_self_assign: bool,
+    pub default_phone_region: Option<PhoneRegion>,
+    pub event_retention_days: Option<u32>,
+    pub email_reply_to: Option<ManagerEmail>,
+}
+
+impl HubSettingsPayload {
+    /// Binds the payload to the given hub.
+    pub fn into_domain(self, hub_id: HubId) -> Result<HubSettings, TypeConstraintError> {
+        Ok(HubSettings::new(
+            hub_id,
+            self.items_per_page,
+            self.email_sender_name,
+            self.allow_manager_self_assign,
+        )?
+        .with_default_phone_region(self.default_phone_region)
+        .with_event_retention_days(self.event_retention_days)
+        .with_email_reply_to(self.email_reply_to))
+    }
+}
+
+impl TryFrom<HubSettingsForm> for HubSettingsPayload {
+    type Error = FormError;
+
+    fn try_from(form: HubSettingsForm) -> Result<Self, Self::Error> {
+        if form.items_per_page == 0 || form.items_per_page > MAX_ITEMS_PER_PAGE {
+            return Err(FormError::InvalidItemsPerPage);
+        }
+        let email_sender_name = form
+            .email_sender_name
+            .map(NonEmptyString::new)
+            .transpose()
+            .map_err(|_| FormError::InvalidEmailSenderName)?;
+        let default_phone_region = form
+            .default_phone_region
+            .map(PhoneRegion::new)
+            .transpose()
+            .map_err(|_| FormError::InvalidPhoneRegion)?;
+        let event_retention_days = form
+            .event_retention_days
+            .map(|days| match days.trim().parse::<u32>() {
+                Ok(days) if days > 0 => Ok(days),
+                _ => Err(FormError::InvalidEventRetentionDays),
+            })
+            .transpose()?;
+        let email_reply_to = form
+            .email_reply_to
+            .map(ManagerEmail::new)
+            .transpose()
+            .map_err(|_| FormError::InvalidEmailReplyTo)?;
+
+        Ok(Self {
+            items_per_page: form.items_per_page,
+            email_sender_name,
+            allow_manager_self_assign: form.allow_manager_self_assign,
+            default_phone_region,
+            event_retention_days,
+            email_reply_to,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hub_settings_form_parses_checkbox_and_blank_sender() {
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&email_sender_name=").expect("valid form");
+        let payload = HubSettingsPayload::try_from(form).expect("valid payload");
+
+        assert_eq!(payload.items_per_page, 25);
+        assert!(payload.email_sender_name.is_none());
+        assert!(!payload.allow_manager_self_assign);
+        assert!(payload.default_phone_region.is_none());
+        assert!(payload.event_retention_days.is_none());
+        assert!(payload.email_reply_to.is_none());
+    }
+
+    #[test]
+    fn hub_settings_form_rejects_out_of_range_page_size() {
+        for items_per_page in [0, MAX_ITEMS_PER_PAGE + 1] {
+            let result = HubSettingsPayload::try_from(HubSettingsForm {
+                items_per_page,
+                email_sender_name: None,
+                allow_manager_self_assign: false,
+                default_phone_region: None,
+                event_retention_days: None,
+                email_reply_to: None,
+            });
+
+            assert!(matches!(result, Err(FormError::InvalidItemsPerPage)));
+        }
+    }
+
+    #[test]
+    fn hub_settings_form_validates_phone_region() {
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&default_phone_region=de")
+                .expect("valid form");
+        let payload = HubSettingsPayload::try_from(form).expect("valid payload");
+        assert_eq!(
+            payload
+                .default_phone_region
+                .as_ref()
+                .map(PhoneRegion::as_str),
+            Some("DE")
+        );
+
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&default_phone_region=Germany")
+                .expect("valid form");
+        assert!(matches!(
+            HubSettingsPayload::try_from(form),
+            Err(FormError::InvalidPhoneRegion)
+        ));
+    }
+
+    #[test]
+    fn hub_settings_form_validates_event_retention_days() {
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&event_retention_days=90")
+                .expect("valid form");
+        let payload = HubSettingsPayload::try_from(form).expect("valid payload");
+        assert_eq!(payload.event_retention_days, Some(90));
+
+        for value in ["0", "-1", "soon"] {
+            let form: HubSettingsForm = serde_html_form::from_str(&format!(
+                "items_per_page=25&event_retention_days={value}"
+            ))
+            .expect("valid form");
+            assert!(matches!(
+                HubSettingsPayload::try_from(form),
+                Err(FormError::InvalidEventRetentionDays)
+            ));
+        }
+    }
+
+    #[test]
+    fn hub_settings_form_validates_email_reply_to() {
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&email_reply_to=Sales%40Example.com")
+                .expect("valid form");
+        let payload = HubSettingsPayload::try_from(form).expect("valid payload");
+        assert_eq!(
+            payload.email_reply_to.as_ref().map(ManagerEmail::as_str),
+            Some("sales@example.com")
+        );
+
+        let form: HubSettingsForm =
+            serde_html_form::from_str("items_per_page=25&email_reply_to=sales")
+                .expect("valid form");
+        assert!(matches!(
+            HubSettingsPayload::try_from(form),
+            Err(FormError::InvalidEmailReplyTo)
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+//! Forms for registering client event webhooks.
+
+use serde::Deserialize;
+
+use crate::{
+    domain::{client_event::ClientEventType, types::WebhookUrl},
+    forms::FormError,
+};
+
+/// Form submitted to register a webhook.
+#[derive(Debug, Deserialize)]
+pub struct AddWebhookForm {
+    pub url: String,
+    /// Optional event type filter; blank delivers every event.
+    #[serde(default)]
+    pub event_type: Option<String>,
+}
+
+pub struct AddWebhookPayload {
+    pub url: WebhookUrl,
+    pub event_type: Option<ClientEventType>,
+}
+
+impl TryFrom<AddWebhookForm> for AddWebhookPayload {
+    type Error = FormError;
+
+    fn try_from(form: AddWebhookForm) -> Result<Self, Self::Error> {
+        let url = WebhookUrl::new(form.url).map_err(|_| FormError::InvalidUrl)?;
+        let event_type = form
+            .event_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ClientEventType::from);
+
+        Ok(Self { url, event_type })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_webhook_form_treats_blank_event_type_as_any() {
+        let payload = AddWebhookPayload::try_from(AddWebhookForm {
+            url: "https://hooks.example.com/crm".to_string(),
+            event_type: Some("  ".to_string()),
+        })
+        .expect("valid payload");
+
+        assert_eq!(payload.url.as_str(), "https://hooks.example.com/crm");
+        assert!(payload.event_type.is_none());
+    }
+
+    #[test]
+    fn add_webhook_form_rejects_internal_url() {
+        let result = AddWebhookPayload::try_from(AddWebhookForm {
+            url: "http://localhost:9000/hook".to_string(),
+            event_type: Some("Comment".to_string()),
+        });
+
+        assert!(matches!(result, Err(FormError::InvalidUrl)));
+    }
+}
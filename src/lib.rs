@@ -4,31 +4,71 @@
 use std::sync::Arc;
 
 #[cfg(feature = "server")]
-use crate::models::config::{AppConfig, Settings};
+use crate::db::establish_connection_pool;
+#[cfg(feature = "server")]
+use crate::models::config::{AppConfig, ServerConfig, Settings};
 #[cfg(feature = "server")]
 use crate::repository::DieselRepository;
 #[cfg(feature = "server")]
 use crate::routes::api::{
-    api_v1_client_details, api_v1_client_directory, api_v1_clients, api_v1_iam,
-    api_v1_important_fields, api_v1_manager_modal, api_v1_managers, api_v1_no_access,
+    api_v1_api_keys, api_v1_client_audit, api_v1_client_details, api_v1_client_directory,
+    api_v1_client_events, api_v1_client_fields, api_v1_client_managers, api_v1_client_notes,
+    api_v1_clients, api_v1_clients_batch, api_v1_clients_export, api_v1_dashboard, api_v1_features,
+    api_v1_iam, api_v1_import_status, api_v1_important_fields, api_v1_manager_modal,
+    api_v1_managers, api_v1_no_access, api_v1_patch_client_fields, api_v1_patch_client_status,
+    api_v1_recent_clients, api_v1_segment_clients, api_v1_segments, api_v1_settings,
+    api_v1_webhooks,
 };
 #[cfg(feature = "server")]
-use crate::routes::aux::not_assigned;
+use crate::routes::api_key::api_key_auth;
+#[cfg(feature = "server")]
+use crate::routes::api_keys::{add_api_key, delete_api_key};
+#[cfg(feature = "server")]
+use crate::routes::aux::{MetricsState, health, metrics, not_assigned};
 #[cfg(feature = "server")]
-use crate::routes::client::{attachment_client, comment_client, save_client, show_client};
+use crate::routes::client::{
+    attachment_client, comment_client, export_client_events, note_client, pin_client_note,
+    save_client, show_client,
+};
 #[cfg(feature = "server")]
-use crate::routes::main::{add_client, clients_upload, show_index};
+use crate::routes::idempotency::SeenTokens;
 #[cfg(feature = "server")]
-use crate::routes::managers::{add_manager, assign_manager, managers};
+use crate::routes::main::{
+    add_client, clients_export_xlsx, clients_upload, clients_upload_template, show_dashboard,
+    show_index,
+};
 #[cfg(feature = "server")]
-use crate::routes::rate_limit::{StoreOtpIpRateLimiter, TRUST_FORWARDED_HEADERS};
+use crate::routes::managers::{
+    add_manager, add_manager_rule, assign_manager, delete_manager, delete_manager_rule,
+    manager_rules, managers, reassign_manager_clients, set_manager_active, update_profile,
+    upload_manager_assignments,
+};
 #[cfg(feature = "server")]
-use crate::routes::settings::{cleanup_clients, save_important_fields, show_settings};
+use crate::routes::middleware::CspMiddleware;
+#[cfg(feature = "server")]
+use crate::routes::rate_limit::{
+    CommentRateLimiter, StoreOtpIpRateLimiter, TRUST_FORWARDED_HEADERS,
+};
+#[cfg(feature = "server")]
+use crate::routes::segments::{add_segment, delete_segment, update_segment};
+#[cfg(feature = "server")]
+use crate::routes::settings::{
+    cleanup_clients, cleanup_events, delete_important_field, save_hub_settings,
+    save_important_fields, show_settings,
+};
 #[cfg(feature = "server")]
 use crate::routes::store::{
     get_store_session, logout_store_session, request_store_auth_otp, verify_store_auth_otp,
 };
 #[cfg(feature = "server")]
+use crate::routes::webhooks::{add_webhook, delete_webhook};
+#[cfg(feature = "server")]
+use crate::services::main::ImportProgress;
+#[cfg(feature = "server")]
+use crate::state::AppState;
+#[cfg(feature = "server")]
+use crate::webhooks::WebhookDispatcher;
+#[cfg(feature = "server")]
 use actix_cors::Cors;
 #[cfg(feature = "server")]
 use actix_files::Files;
@@ -41,8 +81,6 @@ use actix_web::cookie::Key;
 #[cfg(feature = "server")]
 use actix_web::{App, HttpServer, dev::Server, middleware, web};
 #[cfg(feature = "server")]
-use pushkind_common::db::establish_connection_pool;
-#[cfg(feature = "server")]
 use pushkind_common::middleware::RedirectUnauthorized;
 #[cfg(feature = "server")]
 use pushkind_common::models::config::CommonServerConfig;
@@ -50,7 +88,11 @@ use pushkind_common::models::config::CommonServerConfig;
 use pushkind_common::routes::logout;
 #[cfg(feature = "server")]
 use pushkind_common::zmq::{ZmqSender, ZmqSenderOptions};
+#[cfg(feature = "server")]
+use tracing_actix_web::TracingLogger;
 
+#[cfg(feature = "server")]
+pub mod db;
 #[cfg(feature = "data")]
 pub mod domain;
 #[cfg(feature = "server")]
@@ -63,6 +105,8 @@ mod frontend;
 #[cfg(feature = "data")]
 pub mod models;
 #[cfg(feature = "server")]
+pub mod outbound;
+#[cfg(feature = "server")]
 pub mod repository;
 #[cfg(feature = "server")]
 pub mod routes;
@@ -70,6 +114,10 @@ pub mod routes;
 pub mod schema;
 #[cfg(feature = "server")]
 pub mod services;
+#[cfg(feature = "server")]
+pub mod state;
+#[cfg(feature = "server")]
+pub mod webhooks;
 
 pub const SERVICE_ACCESS_ROLE: &str = "crm";
 pub const SERVICE_ADMIN_ROLE: &str = "crm_admin";
@@ -81,13 +129,14 @@ pub async fn run(settings: Settings) -> std::io::Result<()> {
     let listener =
         std::net::TcpListener::bind((settings.server.address.clone(), settings.server.port))?;
 
-    build_server(listener, settings.app)?.await
+    build_server(listener, settings.app, settings.server)?.await
 }
 
 #[cfg(feature = "server")]
 pub fn build_server(
     listener: std::net::TcpListener,
     app_config: AppConfig,
+    server_config: ServerConfig,
 ) -> std::io::Result<Server> {
     let common_config = CommonServerConfig {
         auth_service_url: app_config.auth_service_url.to_string(),
@@ -102,17 +151,41 @@ pub fn build_server(
     let zmq_sender = Arc::new(zmq_sender);
     let sms_sender = ZmqSender::start(ZmqSenderOptions::pub_default(&app_config.zmq_sms_pub))
         .map_err(|e| std::io::Error::other(format!("Failed to start ZMQ SMS sender: {e}")))?;
+    let sms_sender = Arc::new(sms_sender);
+
+    // Background worker delivering signed client event webhooks.
+    let webhook_dispatcher = WebhookDispatcher::start()
+        .map_err(|e| std::io::Error::other(format!("Failed to start webhook dispatcher: {e}")))?;
 
     // Establish Diesel connection pool for the SQLite database.
-    let pool = establish_connection_pool(&app_config.database_url).map_err(|e| {
-        std::io::Error::other(format!("Failed to establish database connection: {e}"))
-    })?;
+    let pool =
+        establish_connection_pool(&app_config.database_url, &server_config).map_err(|e| {
+            std::io::Error::other(format!("Failed to establish database connection: {e}"))
+        })?;
 
-    let repo = DieselRepository::new(pool);
+    let repo = DieselRepository::new(pool).with_batch_chunk_size(server_config.batch_chunk_size);
 
     // Keys and stores for identity and sessions.
     let secret_key = Key::from(app_config.secret.as_bytes());
     let store_otp_rate_limiter = web::Data::new(StoreOtpIpRateLimiter::new());
+    let comment_rate_limiter = web::Data::new(CommentRateLimiter::new(
+        server_config.comment_rate_limit_per_minute,
+    ));
+    let metrics_state = web::Data::new(MetricsState::default());
+    let csp =
+        CspMiddleware::new(&server_config.content_security_policy(&app_config.auth_service_url))
+            .map_err(|e| std::io::Error::other(format!("Invalid csp_policy: {e}")))?;
+    let state = web::Data::new(AppState::new(
+        repo,
+        common_config.clone(),
+        app_config.clone(),
+        server_config,
+        zmq_sender,
+        sms_sender,
+        webhook_dispatcher,
+    ));
+    let seen_tokens = web::Data::new(SeenTokens::new());
+    let import_progress = web::Data::new(ImportProgress::new());
     if !TRUST_FORWARDED_HEADERS {
         log::warn!(
             "CRM store OTP rate limiter uses peer_addr() for client IP. \
@@ -130,14 +203,18 @@ If this service runs behind a trusted reverse proxy, set TRUST_FORWARDED_HEADERS
                     .cookie_domain(Some(format!(".{}", app_config.domain)))
                     .build(),
             )
+            .wrap(csp.clone())
             .wrap(middleware::Compress::default())
             .wrap(middleware::Logger::default())
+            // Outermost: every request gets a span with a generated request ID.
+            .wrap(TracingLogger::default())
             .service(Files::new("/assets", "./assets"))
             .service(not_assigned)
+            .service(metrics)
+            .service(health)
             .service(
                 web::scope("/api/v1/store")
                     .app_data(store_otp_rate_limiter.clone())
-                    .app_data(web::Data::new(sms_sender.clone()))
                     .service(request_store_auth_otp)
                     .service(verify_store_auth_otp)
                     .service(get_store_session)
@@ -145,37 +222,83 @@ If this service runs behind a trusted reverse proxy, set TRUST_FORWARDED_HEADERS
             )
             .service(
                 web::scope("/api")
+                    .wrap(middleware::from_fn(api_key_auth))
                     .service(api_v1_iam)
+                    .service(api_v1_features)
                     .service(api_v1_clients)
                     .service(api_v1_client_directory)
+                    .service(api_v1_clients_export)
+                    .service(api_v1_recent_clients)
                     .service(api_v1_client_details)
+                    .service(api_v1_client_events)
+                    .service(api_v1_client_notes)
+                    .service(api_v1_client_audit)
+                    .service(api_v1_client_fields)
+                    .service(api_v1_client_managers)
+                    .service(api_v1_patch_client_fields)
+                    .service(api_v1_patch_client_status)
+                    .service(api_v1_clients_batch)
+                    .service(api_v1_webhooks)
+                    .service(api_v1_api_keys)
+                    .service(api_v1_segments)
+                    .service(api_v1_segment_clients)
                     .service(api_v1_managers)
                     .service(api_v1_manager_modal)
+                    .service(api_v1_dashboard)
                     .service(api_v1_no_access)
-                    .service(api_v1_important_fields),
+                    .service(api_v1_important_fields)
+                    .service(api_v1_settings)
+                    .service(api_v1_import_status),
             )
             .service(add_client)
             .service(clients_upload)
+            .service(clients_upload_template)
+            .service(clients_export_xlsx)
             .service(save_client)
             .service(comment_client)
             .service(attachment_client)
+            .service(note_client)
+            .service(pin_client_note)
+            .service(export_client_events)
             .service(save_important_fields)
+            .service(save_hub_settings)
             .service(cleanup_clients)
+            .service(cleanup_events)
+            .service(delete_important_field)
             .service(add_manager)
             .service(assign_manager)
+            .service(upload_manager_assignments)
+            .service(reassign_manager_clients)
+            .service(set_manager_active)
+            .service(delete_manager)
+            .service(manager_rules)
+            .service(add_manager_rule)
+            .service(delete_manager_rule)
+            .service(update_profile)
+            .service(add_webhook)
+            .service(delete_webhook)
+            .service(add_api_key)
+            .service(delete_api_key)
+            .service(add_segment)
+            .service(update_segment)
+            .service(delete_segment)
             .service(
                 web::scope("")
                     .wrap(RedirectUnauthorized)
                     .service(show_index)
                     .service(show_client)
+                    .service(show_dashboard)
                     .service(show_settings)
                     .service(managers)
                     .service(logout),
             )
-            .app_data(web::Data::new(repo.clone()))
+            .app_data(state.clone())
+            // Read by the `AuthenticatedUser` extractor.
             .app_data(web::Data::new(common_config.clone()))
-            .app_data(web::Data::new(zmq_sender.clone()))
-            .app_data(web::Data::new(app_config.clone()))
+            .app_data(comment_rate_limiter.clone())
+            .app_data(metrics_state.clone())
+            .app_data(seen_tokens.clone())
+            .app_data(import_progress.clone())
     })
     .listen(listener)?
     .run();
@@ -5,13 +5,27 @@ use config::Config;
 use dotenvy::dotenv;
 
 use pushkind_crm::{models::config::Settings, run};
+use tracing_subscriber::EnvFilter;
 
 #[actix_web::main]
 async fn main() {
     // Load environment variables from `.env` in local development.
     dotenv().ok();
-    // Initialize logger with default level INFO if not provided.
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+    // Initialize tracing with default level INFO if `RUST_LOG` is not provided.
+    // `log` records from dependencies are forwarded into the same subscriber.
+    if let Err(err) = tracing_log::LogTracer::init() {
+        eprintln!("Error forwarding log records to tracing: {}", err);
+        std::process::exit(1);
+    }
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .finish();
+    if let Err(err) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Error installing tracing subscriber: {}", err);
+        std::process::exit(1);
+    }
 
     // Select config profile (defaults to `local`).
     let app_env = env::var("APP_ENV").unwrap_or_else(|_| "local".into());
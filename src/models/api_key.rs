@@ -0,0 +1,56 @@
+//! Diesel models for integration API keys.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    api_key::{ApiKey as DomainApiKey, NewApiKey as DomainNewApiKey},
+    types::TypeConstraintError,
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::api_keys)]
+/// Diesel model for [`crate::domain::api_key::ApiKey`].
+pub struct ApiKey {
+    pub id: i32,
+    pub hub_id: i32,
+    pub name: String,
+    pub key_hash: String,
+    pub roles: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::api_keys)]
+/// Insertable form of [`ApiKey`].
+pub struct NewApiKey<'a> {
+    pub hub_id: i32,
+    pub name: &'a str,
+    pub key_hash: &'a str,
+    pub roles: String,
+}
+
+impl TryFrom<ApiKey> for DomainApiKey {
+    type Error = TypeConstraintError;
+
+    fn try_from(api_key: ApiKey) -> Result<Self, Self::Error> {
+        DomainApiKey::try_new(
+            api_key.id,
+            api_key.hub_id,
+            api_key.name,
+            &api_key.roles,
+            api_key.created_at,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewApiKey> for NewApiKey<'a> {
+    fn from(api_key: &'a DomainNewApiKey) -> Self {
+        Self {
+            hub_id: api_key.hub_id.get(),
+            name: &api_key.name,
+            key_hash: &api_key.key_hash,
+            roles: api_key.roles.join(","),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Diesel models for manager assignment rules.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    assignment_rule::{
+        ManagerAssignmentRule as DomainManagerAssignmentRule,
+        NewManagerAssignmentRule as DomainNewManagerAssignmentRule,
+    },
+    types::TypeConstraintError,
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::manager_assignment_rules)]
+/// Diesel model for [`crate::domain::assignment_rule::ManagerAssignmentRule`].
+pub struct ManagerAssignmentRule {
+    pub id: i32,
+    pub hub_id: i32,
+    pub manager_id: i32,
+    pub field: String,
+    pub value: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::manager_assignment_rules)]
+/// Insertable form of [`ManagerAssignmentRule`].
+pub struct NewManagerAssignmentRule<'a> {
+    pub hub_id: i32,
+    pub manager_id: i32,
+    pub field: &'a str,
+    pub value: &'a str,
+}
+
+impl TryFrom<ManagerAssignmentRule> for DomainManagerAssignmentRule {
+    type Error = TypeConstraintError;
+
+    fn try_from(rule: ManagerAssignmentRule) -> Result<Self, Self::Error> {
+        DomainManagerAssignmentRule::try_new(
+            rule.id,
+            rule.hub_id,
+            rule.manager_id,
+            rule.field,
+            rule.value,
+            rule.created_at,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewManagerAssignmentRule> for NewManagerAssignmentRule<'a> {
+    fn from(rule: &'a DomainNewManagerAssignmentRule) -> Self {
+        Self {
+            hub_id: rule.hub_id.get(),
+            manager_id: rule.manager_id.get(),
+            field: rule.field.as_str(),
+            value: rule.value.as_str(),
+        }
+    }
+}
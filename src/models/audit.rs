@@ -0,0 +1,101 @@
+//! Diesel models for the audit log.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    audit::{AuditEntry as DomainAuditEntry, NewAuditEntry as DomainNewAuditEntry},
+    types::TypeConstraintError,
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::audit_log)]
+/// Diesel model for [`crate::domain::audit::AuditEntry`].
+pub struct AuditEntry {
+    pub id: i32,
+    pub hub_id: i32,
+    pub actor_email: String,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub action: String,
+    pub diff: String, // store JSON text in the DB
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::audit_log)]
+/// Insertable form of [`AuditEntry`].
+pub struct NewAuditEntry<'a> {
+    pub hub_id: i32,
+    pub actor_email: &'a str,
+    pub entity_type: &'a str,
+    pub entity_id: i32,
+    pub action: &'a str,
+    pub diff: String,
+}
+
+impl TryFrom<AuditEntry> for DomainAuditEntry {
+    type Error = TypeConstraintError;
+
+    fn try_from(entry: AuditEntry) -> Result<Self, Self::Error> {
+        let diff = serde_json::from_str(&entry.diff).map_err(|err| {
+            TypeConstraintError::InvalidValue(format!("audit diff is not valid JSON: {err}"))
+        })?;
+
+        DomainAuditEntry::try_new(
+            entry.id,
+            entry.hub_id,
+            entry.actor_email,
+            entry.entity_type,
+            entry.entity_id,
+            entry.action,
+            diff,
+            entry.created_at,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewAuditEntry> for NewAuditEntry<'a> {
+    fn from(entry: &'a DomainNewAuditEntry) -> Self {
+        Self {
+            hub_id: entry.hub_id.get(),
+            actor_email: entry.actor_email.as_str(),
+            entity_type: &entry.entity_type,
+            entity_id: entry.entity_id,
+            action: &entry.action,
+            diff: entry.diff.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn stored_entry(diff: &str) -> AuditEntry {
+        AuditEntry {
+            id: 1,
+            hub_id: 2,
+            actor_email: "admin@example.com".to_string(),
+            entity_type: "client".to_string(),
+            entity_id: 3,
+            action: "update".to_string(),
+            diff: diff.to_string(),
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn audit_entry_into_domain_parses_diff() {
+        let domain = DomainAuditEntry::try_from(stored_entry(r#"{"name":{"old":"A","new":"B"}}"#))
+            .expect("valid entry");
+        assert_eq!(domain.diff["name"]["new"], "B");
+    }
+
+    #[test]
+    fn audit_entry_into_domain_rejects_corrupt_diff() {
+        let err = DomainAuditEntry::try_from(stored_entry("{not json")).expect_err("corrupt diff");
+        assert!(matches!(err, TypeConstraintError::InvalidValue(_)));
+    }
+}
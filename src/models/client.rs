@@ -5,13 +5,14 @@ use diesel::prelude::*;
 use serde::Serialize;
 
 use crate::domain::client::{
-    Client as DomainClient, NewClient as DomainNewClient, UpdateClient as DomainUpdateClient,
+    Client as DomainClient, ClientSource, ClientStatus, NewClient as DomainNewClient,
+    UpdateClient as DomainUpdateClient,
 };
 use crate::domain::types::{
-    ClientEmail, ClientId, ClientName, HubId, PhoneNumber, PublicId, TypeConstraintError,
+    ClientEmail, ClientId, ClientName, HubId, PhoneNumber, PublicId, RawPhone, TypeConstraintError,
 };
 
-#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName)]
+#[derive(Debug, Clone, Identifiable, Queryable, QueryableByName, Selectable)]
 #[diesel(table_name = crate::schema::clients)]
 #[diesel(foreign_derive)]
 /// Diesel model for [`crate::domain::client::Client`].
@@ -25,6 +26,12 @@ pub struct Client {
     pub updated_at: NaiveDateTime,
     pub fields: Option<String>,
     pub public_id: Option<Vec<u8>>,
+    pub last_edited_by: Option<i32>,
+    pub last_edited_at: Option<NaiveDateTime>,
+    pub status: String,
+    pub source: Option<String>,
+    pub raw_phone: Option<String>,
+    pub version: i32,
 }
 
 #[derive(QueryableByName)]
@@ -35,6 +42,8 @@ pub struct ClientCount {
 
 #[derive(Insertable)]
 #[diesel(table_name = crate::schema::clients)]
+// Explicit NULLs keep multi-row inserts a single statement on SQLite.
+#[diesel(treat_none_as_default_value = false)]
 /// Insertable form of [`Client`].
 pub struct NewClient<'a> {
     pub public_id: &'a [u8],
@@ -42,8 +51,13 @@ pub struct NewClient<'a> {
     pub name: &'a str,
     pub email: Option<&'a str>,
     pub phone: Option<&'a str>,
+    pub source: Option<&'static str>,
+    pub raw_phone: Option<&'a str>,
 }
 
+/// Columns [`NewClient`] binds per inserted row.
+pub const NEW_CLIENT_COLUMNS: usize = 7;
+
 #[derive(AsChangeset)]
 #[diesel(table_name = crate::schema::clients)]
 #[diesel(treat_none_as_null = true)]
@@ -81,6 +95,14 @@ impl TryFrom<Client> for DomainClient {
             created_at: client.created_at,
             updated_at: client.updated_at,
             fields: None,
+            status: client.status.parse::<ClientStatus>()?,
+            source: client
+                .source
+                .as_deref()
+                .map(str::parse::<ClientSource>)
+                .transpose()?,
+            raw_phone: client.raw_phone.map(RawPhone::try_from).transpose()?,
+            version: client.version,
         })
     }
 }
@@ -93,6 +115,8 @@ impl<'a> From<&'a DomainNewClient> for NewClient<'a> {
             name: client.name.as_str(),
             email: client.email.as_ref().map(|email| email.as_str()),
             phone: client.phone.as_ref().map(|phone| phone.as_str()),
+            source: client.source.map(ClientSource::as_str),
+            raw_phone: client.raw_phone.as_ref().map(|phone| phone.as_str()),
         }
     }
 }
@@ -133,6 +157,10 @@ mod tests {
         assert_eq!(new.name, domain.name.as_str());
         assert_eq!(new.email, domain.email.as_ref().map(|email| email.as_str()));
         assert_eq!(new.phone, domain.phone.as_ref().map(|phone| phone.as_str()));
+        assert_eq!(new.source, None);
+        let sourced = domain.with_source(ClientSource::Zmq);
+        let new: NewClient = (&sourced).into();
+        assert_eq!(new.source, Some("zmq"));
     }
 
     #[test]
@@ -168,6 +196,12 @@ mod tests {
             created_at: now,
             updated_at: now,
             fields: None,
+            last_edited_by: None,
+            last_edited_at: None,
+            status: "Archived".to_string(),
+            source: Some("csv".to_string()),
+            raw_phone: Some("12-34".to_string()),
+            version: 3,
         };
         let domain = DomainClient::try_from(db_client).expect("valid domain client");
         assert_eq!(domain.id.get(), 1);
@@ -177,5 +211,9 @@ mod tests {
         assert_eq!(domain.phone.unwrap().as_str(), "+14155552671");
         assert_eq!(domain.created_at, now);
         assert_eq!(domain.updated_at, now);
+        assert_eq!(domain.status, ClientStatus::Archived);
+        assert_eq!(domain.source, Some(ClientSource::Csv));
+        assert_eq!(domain.raw_phone.unwrap().as_str(), "12-34");
+        assert_eq!(domain.version, 3);
     }
 }
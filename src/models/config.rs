@@ -1,6 +1,9 @@
 //! Configuration model loaded from external sources.
 
 use serde::Deserialize;
+use url::{Origin, Url};
+
+use crate::domain::types::{Locale, PhoneRegion};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Settings {
@@ -12,6 +15,195 @@ pub struct Settings {
 pub struct ServerConfig {
     pub address: String,
     pub port: u16,
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    /// Hours during which an identical client event is treated as a duplicate.
+    #[serde(default = "default_dedup_window_hours")]
+    pub dedup_window_hours: u64,
+    /// Comments a user may post to one client per minute before receiving `429`.
+    #[serde(default = "default_comment_rate_limit_per_minute")]
+    pub comment_rate_limit_per_minute: usize,
+    /// Maximum number of pooled SQLite connections.
+    #[serde(default = "default_db_pool_size")]
+    pub db_pool_size: u32,
+    /// How long a connection waits on a locked SQLite database before failing.
+    #[serde(default = "default_db_busy_timeout_ms")]
+    pub db_busy_timeout_ms: u64,
+    /// Hours between `PRAGMA optimize` runs of the `check_events` worker; zero disables them.
+    #[serde(default = "default_maintenance_interval_hours")]
+    pub maintenance_interval_hours: u64,
+    /// Clients inserted per statement by skip and error imports.
+    #[serde(default = "default_batch_chunk_size")]
+    pub batch_chunk_size: usize,
+    /// `Content-Security-Policy` sent with HTML pages; `None` uses
+    /// [`DEFAULT_CSP_POLICY`].
+    #[serde(default)]
+    pub csp_policy: Option<String>,
+    /// Per-topic thread counts of the `check_events` worker.
+    #[serde(default)]
+    pub worker: WorkerConfig,
+    /// BCP 47 locale the pages of every hub render dates in; a malformed tag
+    /// fails at startup instead of in the browser.
+    #[serde(default = "default_locale")]
+    pub locale: Locale,
+    /// Country whose phone numbers are shown in national form for hubs without
+    /// a `default_phone_region`; `None` shows every number internationally.
+    #[serde(default)]
+    pub country: Option<PhoneRegion>,
+}
+
+/// Default [`ServerConfig::dedup_window_hours`].
+pub const DEFAULT_DEDUP_WINDOW_HOURS: u64 = 24;
+
+fn default_dedup_window_hours() -> u64 {
+    DEFAULT_DEDUP_WINDOW_HOURS
+}
+
+/// Default [`ServerConfig::comment_rate_limit_per_minute`].
+pub const DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE: usize = 30;
+
+fn default_comment_rate_limit_per_minute() -> usize {
+    DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE
+}
+
+/// Default [`ServerConfig::db_pool_size`], the r2d2 default.
+pub const DEFAULT_DB_POOL_SIZE: u32 = 10;
+
+fn default_db_pool_size() -> u32 {
+    DEFAULT_DB_POOL_SIZE
+}
+
+/// Default [`ServerConfig::db_busy_timeout_ms`].
+pub const DEFAULT_DB_BUSY_TIMEOUT_MS: u64 = 30_000;
+
+fn default_db_busy_timeout_ms() -> u64 {
+    DEFAULT_DB_BUSY_TIMEOUT_MS
+}
+
+/// Default [`ServerConfig::maintenance_interval_hours`].
+pub const DEFAULT_MAINTENANCE_INTERVAL_HOURS: u64 = 24;
+
+fn default_maintenance_interval_hours() -> u64 {
+    DEFAULT_MAINTENANCE_INTERVAL_HOURS
+}
+
+/// Default [`ServerConfig::batch_chunk_size`].
+pub const DEFAULT_BATCH_CHUNK_SIZE: usize = 100;
+
+fn default_batch_chunk_size() -> usize {
+    DEFAULT_BATCH_CHUNK_SIZE
+}
+
+fn default_locale() -> Locale {
+    Locale::default()
+}
+
+/// Directives of the default [`ServerConfig::csp_policy`]; the pages load
+/// Bootstrap from jsDelivr. [`default_csp_policy`] adds their `connect-src`.
+pub const DEFAULT_CSP_POLICY: &str = "default-src 'self'; \
+script-src 'self' https://cdn.jsdelivr.net; \
+style-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net; \
+font-src 'self' https://cdn.jsdelivr.net; \
+img-src 'self' data:";
+
+/// Default [`ServerConfig::csp_policy`]: [`DEFAULT_CSP_POLICY`] plus a
+/// `connect-src` letting the pages call the auth service for the hub menu and
+/// user search.
+#[must_use]
+pub fn default_csp_policy(auth_service_url: &str) -> String {
+    let origin = Url::parse(auth_service_url)
+        .map(|url| url.origin())
+        .ok()
+        .filter(Origin::is_tuple)
+        .map(|origin| format!(" {}", origin.ascii_serialization()))
+        .unwrap_or_default();
+    format!("{DEFAULT_CSP_POLICY}; connect-src 'self'{origin}")
+}
+
+impl ServerConfig {
+    /// Message deduplication window used by the event worker.
+    #[must_use]
+    pub fn dedup_window(&self) -> chrono::Duration {
+        i64::try_from(self.dedup_window_hours)
+            .ok()
+            .and_then(chrono::Duration::try_hours)
+            .unwrap_or(chrono::Duration::MAX)
+    }
+
+    /// SQLite `busy_timeout` applied to every pooled connection.
+    #[must_use]
+    pub fn db_busy_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.db_busy_timeout_ms)
+    }
+
+    /// Configured `Content-Security-Policy` or the [`default_csp_policy`] for
+    /// the auth service at `auth_service_url`.
+    #[must_use]
+    pub fn content_security_policy(&self, auth_service_url: &str) -> String {
+        self.csp_policy
+            .clone()
+            .unwrap_or_else(|| default_csp_policy(auth_service_url))
+    }
+
+    /// Pause between database maintenance runs, or `None` when disabled.
+    #[must_use]
+    pub fn maintenance_interval(&self) -> Option<std::time::Duration> {
+        (self.maintenance_interval_hours > 0).then(|| {
+            std::time::Duration::from_secs(self.maintenance_interval_hours.saturating_mul(3600))
+        })
+    }
+}
+
+/// Switches for optional features; every feature is enabled by default.
+///
+/// Unknown keys fail deserialization so typos are caught at startup.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeaturesConfig {
+    /// `GET /clients/export.xlsx`.
+    pub xlsx_export: bool,
+    /// Admin overview page and `GET /api/v1/dashboard`.
+    pub hub_dashboard: bool,
+    /// Webhook registration endpoints.
+    pub webhooks: bool,
+    /// Client segment endpoints.
+    pub segments: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        Self {
+            xlsx_export: true,
+            hub_dashboard: true,
+            webhooks: true,
+            segments: true,
+        }
+    }
+}
+
+/// Threads of the `check_events` worker processing each ZeroMQ topic.
+///
+/// Every socket is read by one receiver thread; these counts set how many
+/// threads handle the received messages. Zero is treated as one.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerConfig {
+    /// Outbound email notifications from `zmq_emailer_sub`.
+    pub email_threads: usize,
+    /// Replies and unsubscribes from `zmq_replier_sub`.
+    pub reply_threads: usize,
+    /// Client upserts from `zmq_clients_sub`.
+    pub client_threads: usize,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            email_threads: 1,
+            reply_threads: 1,
+            client_threads: 1,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -25,9 +217,96 @@ pub struct AppConfig {
     pub zmq_clients_sub: String,
     pub zmq_replier_sub: String,
     pub zmq_tasks_sub: String,
+    pub zmq_tracker_sub: String,
     pub sms_sender: String,
     pub secret: String,
     pub auth_service_url: String,
     pub todo_service_url: String,
     pub files_service_url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_default_to_enabled_and_reject_unknown_flags() {
+        let features: FeaturesConfig =
+            serde_json::from_str(r#"{"xlsx_export": false}"#).expect("known flag parses");
+        assert_eq!(
+            features,
+            FeaturesConfig {
+                xlsx_export: false,
+                ..FeaturesConfig::default()
+            }
+        );
+
+        assert!(serde_json::from_str::<FeaturesConfig>(r#"{"soft_delete": true}"#).is_err());
+    }
+
+    #[test]
+    fn dedup_window_defaults_to_a_day() {
+        let server: ServerConfig =
+            serde_json::from_str(r#"{"address": "127.0.0.1", "port": 80}"#).expect("parses");
+
+        assert_eq!(server.dedup_window_hours, DEFAULT_DEDUP_WINDOW_HOURS);
+        assert_eq!(server.dedup_window(), chrono::Duration::hours(24));
+        assert_eq!(
+            server.comment_rate_limit_per_minute,
+            DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE
+        );
+        assert_eq!(server.db_pool_size, DEFAULT_DB_POOL_SIZE);
+        assert_eq!(server.batch_chunk_size, DEFAULT_BATCH_CHUNK_SIZE);
+        assert_eq!(server.db_busy_timeout(), std::time::Duration::from_secs(30));
+        assert_eq!(
+            server.maintenance_interval(),
+            Some(std::time::Duration::from_secs(24 * 3600))
+        );
+        assert_eq!(server.worker, WorkerConfig::default());
+        assert_eq!(
+            server.content_security_policy("https://users.pushkind.com/auth/signin"),
+            format!("{DEFAULT_CSP_POLICY}; connect-src 'self' https://users.pushkind.com")
+        );
+        assert_eq!(server.locale, Locale::default());
+        assert!(server.country.is_none());
+    }
+
+    #[test]
+    fn locale_and_country_are_validated() {
+        let server: ServerConfig = serde_json::from_str(
+            r#"{"address": "127.0.0.1", "port": 80, "locale": "de-DE", "country": "de"}"#,
+        )
+        .expect("parses");
+
+        assert_eq!(server.locale.as_str(), "de-DE");
+        assert_eq!(server.country.as_ref().map(PhoneRegion::as_str), Some("DE"));
+        assert!(
+            serde_json::from_str::<ServerConfig>(
+                r#"{"address": "127.0.0.1", "port": 80, "country": "Germany"}"#
+            )
+            .is_err()
+        );
+        assert!(
+            serde_json::from_str::<ServerConfig>(
+                r#"{"address": "127.0.0.1", "port": 80, "locale": "ru_RU"}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn worker_threads_default_to_one_per_topic() {
+        let worker: WorkerConfig =
+            serde_json::from_str(r#"{"email_threads": 4}"#).expect("known field parses");
+
+        assert_eq!(
+            worker,
+            WorkerConfig {
+                email_threads: 4,
+                reply_threads: 1,
+                client_threads: 1,
+            }
+        );
+        assert!(serde_json::from_str::<WorkerConfig>(r#"{"sms_threads": 2}"#).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Diesel models for per-hub settings.
+
+use diesel::prelude::*;
+
+use crate::domain::{
+    hub_settings::HubSettings as DomainHubSettings,
+    types::{HubId, ManagerEmail, NonEmptyString, PhoneRegion, TypeConstraintError},
+};
+
+#[derive(Debug, Clone, Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::hub_settings)]
+#[diesel(treat_none_as_null = true)]
+/// Diesel model for [`crate::domain::hub_settings::HubSettings`].
+pub struct HubSettings {
+    pub hub_id: i32,
+    pub items_per_page: i32,
+    pub email_sender_name: Option<String>,
+    pub allow_manager_self_assign: bool,
+    pub default_phone_region: Option<String>,
+    pub event_retention_days: Option<i32>,
+    pub email_reply_to: Option<String>,
+}
+
+impl TryFrom<HubSettings> for DomainHubSettings {
+    type Error = TypeConstraintError;
+
+    fn try_from(settings: HubSettings) -> Result<Self, Self::Error> {
+        let items_per_page = u16::try_from(settings.items_per_page)
+            .map_err(|_| TypeConstraintError::InvalidValue("items per page".to_string()))?;
+
+        let default_phone_region = settings
+            .default_phone_region
+            .map(PhoneRegion::new)
+            .transpose()?;
+        let event_retention_days = settings
+            .event_retention_days
+            .map(u32::try_from)
+            .transpose()
+            .map_err(|_| TypeConstraintError::InvalidValue("event retention days".to_string()))?;
+        let email_reply_to = settings.email_reply_to.map(ManagerEmail::new).transpose()?;
+
+        Ok(DomainHubSettings::new(
+            HubId::try_from(settings.hub_id)?,
+            items_per_page,
+            settings
+                .email_sender_name
+                .map(NonEmptyString::new)
+                .transpose()?,
+            settings.allow_manager_self_assign,
+        )?
+        .with_default_phone_region(default_phone_region)
+        .with_event_retention_days(event_retention_days)
+        .with_email_reply_to(email_reply_to))
+    }
+}
+
+impl From<&DomainHubSettings> for HubSettings {
+    fn from(settings: &DomainHubSettings) -> Self {
+        Self {
+            hub_id: settings.hub_id.get(),
+            items_per_page: i32::from(settings.items_per_page),
+            email_sender_name: settings
+                .email_sender_name
+                .as_ref()
+                .map(|name| name.as_str().to_string()),
+            allow_manager_self_assign: settings.allow_manager_self_assign,
+            default_phone_region: settings
+                .hub_default_phone_region
+                .as_ref()
+                .map(|region| region.as_str().to_string()),
+            event_retention_days: settings
+                .event_retention_days
+                .map(|days| i32::try_from(days).unwrap_or(i32::MAX)),
+            email_reply_to: settings
+                .email_reply_to
+                .as_ref()
+                .map(|email| email.as_str().to_string()),
+        }
+    }
+}
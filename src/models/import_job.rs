@@ -0,0 +1,87 @@
+//! Diesel models for CSV import jobs.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    import_job::{ImportJob as DomainImportJob, ImportJobStatus},
+    types::{HubId, ImportJobId, TypeConstraintError},
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::import_jobs)]
+/// Diesel model for [`crate::domain::import_job::ImportJob`].
+pub struct ImportJob {
+    pub id: i32,
+    pub hub_id: i32,
+    pub status: String,
+    pub total_rows: i32,
+    pub inserted_rows: i32,
+    pub error_rows: i32,
+    pub error_message: Option<String>,
+    pub started_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub updated_rows: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::import_jobs)]
+/// Insertable form of [`ImportJob`].
+pub struct NewImportJob {
+    pub hub_id: i32,
+    pub status: &'static str,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::import_jobs)]
+#[diesel(treat_none_as_null = true)]
+/// Progress fields written back while an import runs.
+pub struct UpdateImportJob<'a> {
+    pub status: &'static str,
+    pub total_rows: i32,
+    pub inserted_rows: i32,
+    pub updated_rows: i32,
+    pub error_rows: i32,
+    pub error_message: Option<&'a str>,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<ImportJob> for DomainImportJob {
+    type Error = TypeConstraintError;
+
+    fn try_from(job: ImportJob) -> Result<Self, Self::Error> {
+        let count = |value: i32| {
+            usize::try_from(value)
+                .map_err(|_| TypeConstraintError::InvalidValue("import job row count".to_string()))
+        };
+
+        Ok(DomainImportJob {
+            id: ImportJobId::try_from(job.id)?,
+            hub_id: HubId::try_from(job.hub_id)?,
+            status: job.status.parse::<ImportJobStatus>()?,
+            total_rows: count(job.total_rows)?,
+            inserted_rows: count(job.inserted_rows)?,
+            updated_rows: count(job.updated_rows)?,
+            error_rows: count(job.error_rows)?,
+            error_message: job.error_message,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+        })
+    }
+}
+
+impl<'a> From<&'a DomainImportJob> for UpdateImportJob<'a> {
+    fn from(job: &'a DomainImportJob) -> Self {
+        let count = |value: usize| i32::try_from(value).unwrap_or(i32::MAX);
+
+        Self {
+            status: job.status.as_str(),
+            total_rows: count(job.total_rows),
+            inserted_rows: count(job.inserted_rows),
+            updated_rows: count(job.updated_rows),
+            error_rows: count(job.error_rows),
+            error_message: job.error_message.as_deref(),
+            completed_at: job.completed_at,
+        }
+    }
+}
@@ -7,10 +7,10 @@ use crate::domain::manager::{
     NewClientManager as DomainNewClientManager, NewManager as DomainNewManager,
     UpdateManager as DomainUpdateManager,
 };
-use crate::domain::types::TypeConstraintError;
+use crate::domain::types::{NonEmptyString, PhoneNumber, TypeConstraintError};
 use crate::models::client::Client;
 
-#[derive(Debug, Clone, Identifiable, Queryable)]
+#[derive(Debug, Clone, Identifiable, Queryable, Selectable)]
 #[diesel(table_name = crate::schema::managers)]
 /// Diesel model for [`crate::domain::manager::Manager`].
 pub struct Manager {
@@ -19,6 +19,10 @@ pub struct Manager {
     pub name: String,
     pub email: String,
     pub is_user: bool,
+    pub company: Option<String>,
+    pub phone: Option<String>,
+    pub is_active: bool,
+    pub name_locked: bool,
 }
 
 #[derive(Insertable)]
@@ -29,6 +33,8 @@ pub struct NewManager<'a> {
     pub name: &'a str,
     pub email: &'a str,
     pub is_user: bool,
+    pub company: Option<&'a str>,
+    pub phone: Option<&'a str>,
 }
 
 #[derive(AsChangeset)]
@@ -38,6 +44,17 @@ pub struct NewManager<'a> {
 pub struct UpdateManager<'a> {
     pub name: &'a str,
     pub is_user: bool,
+    pub company: Option<&'a str>,
+    pub phone: Option<&'a str>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::managers)]
+#[diesel(treat_none_as_null = true)]
+/// Contact details saved from the admin manager form; `None` clears the column.
+pub struct ManagerContactDetails<'a> {
+    pub company: Option<&'a str>,
+    pub phone: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Queryable, Associations, Identifiable)]
@@ -63,13 +80,19 @@ impl TryFrom<Manager> for DomainManager {
     type Error = TypeConstraintError;
 
     fn try_from(manager: Manager) -> Result<Self, Self::Error> {
-        DomainManager::try_new(
+        let company = manager.company.map(NonEmptyString::new).transpose()?;
+        let phone = manager.phone.map(PhoneNumber::try_from).transpose()?;
+
+        Ok(DomainManager::try_new(
             manager.id,
             manager.hub_id,
             manager.name,
             manager.email,
             manager.is_user,
-        )
+        )?
+        .with_contact_details(company, phone)
+        .with_active(manager.is_active)
+        .with_name_locked(manager.name_locked))
     }
 }
 
@@ -80,6 +103,8 @@ impl<'a> From<&'a DomainNewManager> for NewManager<'a> {
             name: manager.name.as_str(),
             email: manager.email.as_str(),
             is_user: manager.is_user,
+            company: manager.company.as_ref().map(|company| company.as_str()),
+            phone: manager.phone.as_ref().map(|phone| phone.as_str()),
         }
     }
 }
@@ -89,6 +114,8 @@ impl<'a> From<&'a DomainUpdateManager> for UpdateManager<'a> {
         Self {
             name: manager.name.as_str(),
             is_user: manager.is_user,
+            company: manager.company.as_ref().map(|company| company.as_str()),
+            phone: manager.phone.as_ref().map(|phone| phone.as_str()),
         }
     }
 }
@@ -98,6 +125,17 @@ impl<'a> From<&'a DomainNewManager> for UpdateManager<'a> {
         Self {
             name: manager.name.as_str(),
             is_user: manager.is_user,
+            company: manager.company.as_ref().map(|company| company.as_str()),
+            phone: manager.phone.as_ref().map(|phone| phone.as_str()),
+        }
+    }
+}
+
+impl<'a> From<&'a DomainNewManager> for ManagerContactDetails<'a> {
+    fn from(manager: &'a DomainNewManager) -> Self {
+        Self {
+            company: manager.company.as_ref().map(|company| company.as_str()),
+            phone: manager.phone.as_ref().map(|phone| phone.as_str()),
         }
     }
 }
@@ -107,6 +145,8 @@ impl<'a> From<&NewManager<'a>> for UpdateManager<'a> {
         Self {
             name: manager.name,
             is_user: manager.is_user,
+            company: manager.company,
+            phone: manager.phone,
         }
     }
 }
@@ -188,11 +228,22 @@ mod tests {
             name: "Bob".into(),
             email: "b@c.d".into(),
             is_user: true,
+            company: Some("Acme".into()),
+            phone: None,
+            is_active: false,
+            name_locked: true,
         };
         let domain: DomainManager = DomainManager::try_from(db).expect("valid manager");
         assert_eq!(domain.id.get(), 1);
         assert_eq!(domain.hub_id.get(), 2);
         assert_eq!(domain.name.as_str(), "Bob");
         assert_eq!(domain.email.as_str(), "b@c.d");
+        assert_eq!(
+            domain.company.as_ref().map(|company| company.as_str()),
+            Some("Acme")
+        );
+        assert!(domain.phone.is_none());
+        assert!(!domain.is_active);
+        assert!(domain.name_locked);
     }
 }
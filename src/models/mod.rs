@@ -1,9 +1,17 @@
 //! Database models shared across the CRM repository.
 
+pub mod api_key;
+pub mod assignment_rule;
+pub mod audit;
 pub mod client;
 pub mod client_event;
 pub mod config;
+pub mod hub_settings;
+pub mod import_job;
 pub mod important_field;
 pub mod manager;
+pub mod note;
+pub mod segment;
 pub mod store_otp;
+pub mod webhook;
 pub mod zmq;
@@ -0,0 +1,95 @@
+//! Diesel models for internal client notes.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    note::{ClientNote as DomainClientNote, NewClientNote as DomainNewClientNote},
+    types::TypeConstraintError,
+};
+use crate::models::client::Client;
+use crate::models::manager::Manager;
+
+#[derive(Debug, Clone, Identifiable, Queryable, Associations)]
+#[diesel(belongs_to(Client, foreign_key = client_id))]
+#[diesel(belongs_to(Manager, foreign_key = manager_id))]
+#[diesel(table_name = crate::schema::client_notes)]
+/// Diesel model for [`crate::domain::note::ClientNote`].
+pub struct ClientNote {
+    pub id: i32,
+    pub client_id: i32,
+    pub manager_id: i32,
+    pub text: String,
+    pub is_pinned: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::client_notes)]
+/// Insertable form of [`ClientNote`].
+pub struct NewClientNote<'a> {
+    pub client_id: i32,
+    pub manager_id: i32,
+    pub text: &'a str,
+}
+
+impl TryFrom<ClientNote> for DomainClientNote {
+    type Error = TypeConstraintError;
+
+    fn try_from(note: ClientNote) -> Result<Self, Self::Error> {
+        DomainClientNote::try_new(
+            note.id,
+            note.client_id,
+            note.manager_id,
+            note.text,
+            note.created_at,
+            note.updated_at,
+            note.is_pinned,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewClientNote> for NewClientNote<'a> {
+    fn from(note: &'a DomainNewClientNote) -> Self {
+        Self {
+            client_id: note.client_id.get(),
+            manager_id: note.manager_id.get(),
+            text: note.text.as_str(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn from_domain_new_client_note() {
+        let domain =
+            DomainNewClientNote::try_new(3, 4, "Follow up".to_string()).expect("valid note");
+        let insertable: NewClientNote = (&domain).into();
+        assert_eq!(insertable.client_id, 3);
+        assert_eq!(insertable.manager_id, 4);
+        assert_eq!(insertable.text, "Follow up");
+    }
+
+    #[test]
+    fn client_note_into_domain() {
+        let now = Utc::now().naive_utc();
+        let db = ClientNote {
+            id: 1,
+            client_id: 2,
+            manager_id: 3,
+            text: "Pinned".to_string(),
+            is_pinned: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let domain = DomainClientNote::try_from(db).expect("valid note");
+        assert_eq!(domain.id.get(), 1);
+        assert_eq!(domain.text.as_str(), "Pinned");
+        assert!(domain.is_pinned);
+    }
+}
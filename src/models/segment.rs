@@ -0,0 +1,59 @@
+//! Diesel models for client segments.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    segment::{ClientSegment as DomainClientSegment, NewClientSegment as DomainNewClientSegment},
+    types::TypeConstraintError,
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::client_segments)]
+/// Diesel model for [`crate::domain::segment::ClientSegment`].
+pub struct ClientSegment {
+    pub id: i32,
+    pub hub_id: i32,
+    pub name: String,
+    /// JSON filter stored as text.
+    pub filter_json: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::client_segments)]
+/// Insertable form of [`ClientSegment`].
+pub struct NewClientSegment<'a> {
+    pub hub_id: i32,
+    pub name: &'a str,
+    pub filter_json: String,
+}
+
+impl TryFrom<ClientSegment> for DomainClientSegment {
+    type Error = TypeConstraintError;
+
+    fn try_from(segment: ClientSegment) -> Result<Self, Self::Error> {
+        let filter_json = serde_json::from_str(&segment.filter_json)
+            .map_err(|_| TypeConstraintError::InvalidValue("segment filter".to_string()))?;
+
+        DomainClientSegment::try_new(
+            segment.id,
+            segment.hub_id,
+            segment.name,
+            filter_json,
+            segment.created_at,
+            segment.updated_at,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewClientSegment> for NewClientSegment<'a> {
+    fn from(segment: &'a DomainNewClientSegment) -> Self {
+        Self {
+            hub_id: segment.hub_id.get(),
+            name: segment.name.as_str(),
+            filter_json: segment.filter_json.to_string(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Diesel models for registered webhooks.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::{
+    types::TypeConstraintError,
+    webhook::{NewWebhook as DomainNewWebhook, Webhook as DomainWebhook},
+};
+
+#[derive(Debug, Clone, Identifiable, Queryable)]
+#[diesel(table_name = crate::schema::webhooks)]
+/// Diesel model for [`crate::domain::webhook::Webhook`].
+pub struct Webhook {
+    pub id: i32,
+    pub hub_id: i32,
+    pub url: String,
+    pub event_type: Option<String>,
+    pub secret: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::webhooks)]
+/// Insertable form of [`Webhook`].
+pub struct NewWebhook<'a> {
+    pub hub_id: i32,
+    pub url: &'a str,
+    pub event_type: Option<String>,
+    pub secret: &'a str,
+}
+
+impl TryFrom<Webhook> for DomainWebhook {
+    type Error = TypeConstraintError;
+
+    fn try_from(webhook: Webhook) -> Result<Self, Self::Error> {
+        DomainWebhook::try_new(
+            webhook.id,
+            webhook.hub_id,
+            webhook.url,
+            webhook.event_type,
+            webhook.secret,
+            webhook.created_at,
+        )
+    }
+}
+
+impl<'a> From<&'a DomainNewWebhook> for NewWebhook<'a> {
+    fn from(webhook: &'a DomainNewWebhook) -> Self {
+        Self {
+            hub_id: webhook.hub_id.get(),
+            url: webhook.url.as_str(),
+            event_type: webhook
+                .event_type
+                .as_ref()
+                .map(|event_type| event_type.to_string()),
+            secret: &webhook.secret,
+        }
+    }
+}
@@ -2,6 +2,14 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+/// Email open notification published by the tracking pixel service.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ZMQOpenMessage {
+    pub email: String,
+    pub hub_id: i32,
+    pub message_id: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ZmqClientMessage {
     pub hub_id: i32,
@@ -11,3 +19,16 @@ pub struct ZmqClientMessage {
     #[serde(default)]
     pub fields: Option<BTreeMap<String, String>>,
 }
+
+impl ZmqClientMessage {
+    /// Checks the hub and name every client needs before the message is stored.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hub_id <= 0 {
+            return Err(format!("invalid hub_id {}", self.hub_id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("empty client name".to_string());
+        }
+        Ok(())
+    }
+}
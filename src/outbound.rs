@@ -0,0 +1,78 @@
+//! Guards for outbound HTTP requests to user-supplied URLs.
+
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use url::{Host, Url};
+
+use crate::domain::types::is_public_ip;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// DNS resolver that fails when a name resolves to any non-public address.
+///
+/// Installed as the client resolver, it hands the connector exactly the
+/// addresses it vetted, so the connection is pinned to them and a second
+/// lookup cannot swap in an internal address.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PublicAddrResolver;
+
+impl Resolve for PublicAddrResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs =
+                actix_web::rt::task::spawn_blocking(move || resolve_public(&host)).await??;
+            Ok::<Addrs, BoxError>(Box::new(addrs.into_iter()))
+        })
+    }
+}
+
+/// Resolves `host` and returns its addresses when every one of them is public.
+fn resolve_public(host: &str) -> Result<Vec<SocketAddr>, BoxError> {
+    let addrs = (host, 0).to_socket_addrs()?.collect::<Vec<_>>();
+    if addrs.is_empty() {
+        return Err(format!("{host} did not resolve to any address").into());
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!("{host} resolves to non-public address {}", addr.ip()).into());
+    }
+    Ok(addrs)
+}
+
+/// Returns `true` unless the URL is malformed or names a non-public IP literal.
+///
+/// IP literals never reach the resolver, so they are checked here; domain
+/// names are vetted by [`PublicAddrResolver`] at connect time.
+#[must_use]
+pub fn is_public_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    match url.host() {
+        Some(Host::Domain(_)) => true,
+        Some(Host::Ipv4(ip)) => is_public_ip(ip.into()),
+        Some(Host::Ipv6(ip)) => is_public_ip(ip.into()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_public_url_rejects_internal_ip_literals() {
+        assert!(is_public_url("https://hooks.example.com/crm"));
+        assert!(is_public_url("https://93.184.216.34/crm"));
+        assert!(!is_public_url("http://127.0.0.1:8080/hook"));
+        assert!(!is_public_url("http://169.254.169.254/latest"));
+        assert!(!is_public_url("http://[fd12::1]/hook"));
+        assert!(!is_public_url("not a url"));
+    }
+
+    #[test]
+    fn resolve_public_rejects_names_resolving_to_loopback() {
+        assert!(resolve_public("localhost").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Integration API key repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::api_key::{ApiKey as DomainApiKey, NewApiKey as DomainNewApiKey};
+use crate::domain::types::{ApiKeyId, HubId};
+use crate::models::api_key::{ApiKey as DbApiKey, NewApiKey as DbNewApiKey};
+use crate::repository::{ApiKeyReader, ApiKeyWriter, DieselRepository};
+
+impl ApiKeyReader for DieselRepository {
+    fn find_api_key(&self, key_hash: &str) -> RepositoryResult<Option<DomainApiKey>> {
+        use crate::schema::api_keys;
+
+        let mut conn = self.conn()?;
+        let record = api_keys::table
+            .filter(api_keys::key_hash.eq(key_hash))
+            .first::<DbApiKey>(&mut conn)
+            .optional()?;
+
+        Ok(record.map(DomainApiKey::try_from).transpose()?)
+    }
+
+    fn list_api_keys(&self, hub_id: HubId) -> RepositoryResult<Vec<DomainApiKey>> {
+        use crate::schema::api_keys;
+
+        let mut conn = self.conn()?;
+        api_keys::table
+            .filter(api_keys::hub_id.eq(hub_id.get()))
+            .order(api_keys::id.asc())
+            .load::<DbApiKey>(&mut conn)?
+            .into_iter()
+            .map(|api_key| DomainApiKey::try_from(api_key).map_err(RepositoryError::from))
+            .collect()
+    }
+}
+
+impl ApiKeyWriter for DieselRepository {
+    fn create_api_key(&self, new_api_key: &DomainNewApiKey) -> RepositoryResult<DomainApiKey> {
+        use crate::schema::api_keys;
+
+        let mut conn = self.conn()?;
+        let db_api_key = diesel::insert_into(api_keys::table)
+            .values(&DbNewApiKey::from(new_api_key))
+            .get_result::<DbApiKey>(&mut conn)?;
+
+        DomainApiKey::try_from(db_api_key).map_err(RepositoryError::from)
+    }
+
+    fn delete_api_key(&self, id: ApiKeyId, hub_id: HubId) -> RepositoryResult<bool> {
+        use crate::schema::api_keys;
+
+        let mut conn = self.conn()?;
+        let deleted = diesel::delete(
+            api_keys::table
+                .filter(api_keys::id.eq(id.get()))
+                .filter(api_keys::hub_id.eq(hub_id.get())),
+        )
+        .execute(&mut conn)?;
+
+        Ok(deleted > 0)
+    }
+}
@@ -0,0 +1,64 @@
+//! Manager assignment rule repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::assignment_rule::{
+    ManagerAssignmentRule as DomainManagerAssignmentRule,
+    NewManagerAssignmentRule as DomainNewManagerAssignmentRule,
+};
+use crate::domain::types::{HubId, RuleId};
+use crate::models::assignment_rule::{
+    ManagerAssignmentRule as DbManagerAssignmentRule,
+    NewManagerAssignmentRule as DbNewManagerAssignmentRule,
+};
+use crate::repository::{DieselRepository, RuleReader, RuleWriter};
+
+impl RuleReader for DieselRepository {
+    fn list_rules(&self, hub_id: HubId) -> RepositoryResult<Vec<DomainManagerAssignmentRule>> {
+        use crate::schema::manager_assignment_rules;
+
+        let mut conn = self.conn()?;
+        manager_assignment_rules::table
+            .filter(manager_assignment_rules::hub_id.eq(hub_id.get()))
+            .order(manager_assignment_rules::id.asc())
+            .load::<DbManagerAssignmentRule>(&mut conn)?
+            .into_iter()
+            .map(|rule| DomainManagerAssignmentRule::try_from(rule).map_err(RepositoryError::from))
+            .collect()
+    }
+}
+
+impl RuleWriter for DieselRepository {
+    fn create_rule(
+        &self,
+        rule: &DomainNewManagerAssignmentRule,
+    ) -> RepositoryResult<DomainManagerAssignmentRule> {
+        use crate::schema::manager_assignment_rules;
+
+        let mut conn = self.conn()?;
+        let db_rule = diesel::insert_into(manager_assignment_rules::table)
+            .values(&DbNewManagerAssignmentRule::from(rule))
+            .get_result::<DbManagerAssignmentRule>(&mut conn)?;
+
+        DomainManagerAssignmentRule::try_from(db_rule).map_err(RepositoryError::from)
+    }
+
+    fn delete_rule(&self, rule_id: RuleId, hub_id: HubId) -> RepositoryResult<()> {
+        use crate::schema::manager_assignment_rules;
+
+        let mut conn = self.conn()?;
+        let deleted = diesel::delete(
+            manager_assignment_rules::table
+                .filter(manager_assignment_rules::id.eq(rule_id.get()))
+                .filter(manager_assignment_rules::hub_id.eq(hub_id.get())),
+        )
+        .execute(&mut conn)?;
+
+        if deleted == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+//! Audit log repository implementation with Diesel.
+
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::audit::{AuditEntry as DomainAuditEntry, NewAuditEntry as DomainNewAuditEntry};
+use crate::domain::types::HubId;
+use crate::models::audit::{AuditEntry as DbAuditEntry, NewAuditEntry as DbNewAuditEntry};
+use crate::repository::{AuditReader, AuditWriter, DieselRepository};
+
+/// Inserts an audit entry on an existing connection so writers can record it
+/// inside their own transaction.
+pub(crate) fn insert_audit_entry(
+    conn: &mut SqliteConnection,
+    entry: &DomainNewAuditEntry,
+) -> RepositoryResult<()> {
+    use crate::schema::audit_log;
+
+    diesel::insert_into(audit_log::table)
+        .values(&DbNewAuditEntry::from(entry))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+impl AuditReader for DieselRepository {
+    fn list_audit_entries(
+        &self,
+        hub_id: HubId,
+        entity_type: &str,
+        entity_id: i32,
+    ) -> RepositoryResult<Vec<DomainAuditEntry>> {
+        use crate::schema::audit_log;
+
+        let mut conn = self.conn()?;
+        audit_log::table
+            .filter(audit_log::hub_id.eq(hub_id.get()))
+            .filter(audit_log::entity_type.eq(entity_type))
+            .filter(audit_log::entity_id.eq(entity_id))
+            .order((audit_log::created_at.desc(), audit_log::id.desc()))
+            .load::<DbAuditEntry>(&mut conn)?
+            .into_iter()
+            .map(|entry| DomainAuditEntry::try_from(entry).map_err(RepositoryError::from))
+            .collect()
+    }
+}
+
+impl AuditWriter for DieselRepository {
+    fn record_audit(&self, entry: &DomainNewAuditEntry) -> RepositoryResult<()> {
+        let mut conn = self.conn()?;
+        insert_audit_entry(&mut conn, entry)
+    }
+}
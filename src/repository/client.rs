@@ -1,36 +1,934 @@
 //! Repository implementation handling CRM clients.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use diesel::dsl::exists;
+use chrono::NaiveTime;
+use diesel::dsl::{count_star, exists, not};
 use diesel::prelude::*;
 use diesel::result::DatabaseErrorKind;
 use diesel::sql_types::{Bool, Nullable, Text};
+use diesel::sqlite::SqliteConnection;
 use diesel::upsert::excluded;
-use pushkind_common::repository::build_fts_match_query;
+use pushkind_common::pagination::Pagination;
 use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
 
+use crate::domain::audit::NewAuditEntry;
 use crate::domain::important_field::ImportantField as DomainImportantField;
 use crate::domain::types::{
-    ClientEmail, ClientId, HubId, ManagerEmail, PhoneNumber, PublicId, TypeConstraintError,
+    ClientEmail, ClientId, ClientName, HubId, ImportConflictStrategy, ImportantFieldName,
+    ManagerEmail, ManagerId, PhoneNumber, PublicId, TypeConstraintError, client_name_match_key,
 };
 use crate::models::client::ClientField;
 use crate::models::important_field::{
     ImportantField as DbImportantField, NewImportantField as DbNewImportantField,
 };
+use crate::repository::audit::insert_audit_entry;
+use crate::repository::errors::{ClientWriteError, ClientWriteResult};
+use crate::repository::segment::load_segment;
 use crate::{
-    domain::client::{Client, NewClient, UpdateClient},
+    domain::client::{
+        Client, ClientStatus, ClientUpsertSummary, ClientWriteOutcome, FieldMeta, NewClient,
+        UpdateClient,
+    },
     domain::manager::Manager,
     models::client::{
         Client as DbClient, NewClient as DbNewClient, UpdateClient as DbUpdateClient,
     },
     models::manager::Manager as DbManager,
     repository::{
-        ClientListQuery, ClientReader, ClientWriter, DieselRepository, ImportantFieldReader,
-        ImportantFieldWriter,
+        ClientListQuery, ClientReader, ClientSort, ClientWriter, DieselRepository,
+        ImportantFieldReader, ImportantFieldWriter, SQLITE_MAX_BIND_PARAMS, fts::FtsQueryBuilder,
     },
 };
 
+/// Columns [`ClientField`] binds per inserted row.
+const CLIENT_FIELD_COLUMNS: usize = 3;
+
+/// Event types a manager sends to a client; a newer `Reply` is still pending.
+const OUTBOUND_EVENT_TYPES: [&str; 3] = ["Comment", "Call", "Email"];
+
+diesel::alias!(crate::schema::client_events as outbound_events: OutboundEvents);
+
+/// Escapes `LIKE` wildcards so `value` matches literally with `ESCAPE '\\'`.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Lowercased name-word prefixes used to find fuzzy candidates for `term`.
+///
+/// Each word keeps its first half (at least two characters), so "Jon" becomes
+/// "jo" and also finds "John"; one-letter words are ignored.
+fn fuzzy_name_prefixes(term: &str) -> Vec<String> {
+    term.split_whitespace()
+        .map(str::to_lowercase)
+        .filter_map(|word| {
+            let chars: Vec<char> = word.chars().filter(|ch| ch.is_alphanumeric()).collect();
+            (chars.len() >= 2).then(|| chars[..chars.len().div_ceil(2).max(2)].iter().collect())
+        })
+        .collect()
+}
+
+/// Character trigrams of `word` padded with spaces at both ends.
+fn trigrams(word: &str) -> BTreeSet<[char; 3]> {
+    let padded: Vec<char> = format!("  {word} ").chars().collect();
+    padded
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .collect()
+}
+
+/// Sum over the words of `term` of the best trigram similarity with a word of
+/// `name`.
+fn fuzzy_name_score(term: &str, name: &str) -> f64 {
+    let name_words: Vec<BTreeSet<[char; 3]>> = name
+        .split_whitespace()
+        .map(|word| trigrams(&word.to_lowercase()))
+        .collect();
+
+    term.split_whitespace()
+        .map(|word| {
+            let grams = trigrams(&word.to_lowercase());
+            name_words
+                .iter()
+                .map(|candidate| {
+                    let shared = grams.intersection(candidate).count();
+                    let union = grams.union(candidate).count();
+                    shared as f64 / union as f64
+                })
+                .fold(0.0, f64::max)
+        })
+        .sum()
+}
+
+/// Fallback of [`ClientReader::list_clients`] when FTS finds nothing for
+/// `term`: keeps clients whose name has a word starting with one of the
+/// [`fuzzy_name_prefixes`] and orders them by [`fuzzy_name_score`], ties by id.
+///
+/// SQLite `LIKE` only folds ASCII case, so each prefix is also tried with its
+/// first letter capitalized.
+fn list_clients_fuzzy(
+    conn: &mut SqliteConnection,
+    items: crate::schema::clients::BoxedQuery<'_, diesel::sqlite::Sqlite>,
+    term: &str,
+    query: &ClientListQuery,
+) -> RepositoryResult<(usize, Vec<Client>)> {
+    use crate::schema::clients;
+
+    type NameFilter =
+        Box<dyn BoxableExpression<clients::table, diesel::sqlite::Sqlite, SqlType = Bool>>;
+
+    let patterns = fuzzy_name_prefixes(term)
+        .into_iter()
+        .flat_map(|prefix| {
+            let mut chars = prefix.chars();
+            let capitalized = chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default();
+            [prefix, capitalized]
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .flat_map(|prefix| {
+            let prefix = escape_like(&prefix);
+            [format!("{prefix}%"), format!("% {prefix}%")]
+        });
+
+    let Some(name_filter) = patterns
+        .map(|pattern| -> NameFilter { Box::new(clients::name.like(pattern).escape('\\')) })
+        .reduce(|acc, next| Box::new(acc.or(next)))
+    else {
+        return Ok((0, Vec::new()));
+    };
+
+    let mut candidates = items
+        .filter(name_filter)
+        .load::<DbClient>(conn)?
+        .into_iter()
+        .map(|client| (fuzzy_name_score(term, &client.name), client))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(left_score, left), (right_score, right)| {
+        right_score
+            .total_cmp(left_score)
+            .then_with(|| left.id.cmp(&right.id))
+    });
+
+    let total = candidates.len();
+    let (offset, limit) = match &query.pagination {
+        Some(pagination) => (
+            (pagination.page.max(1) - 1) * pagination.per_page,
+            pagination.per_page,
+        ),
+        None => (0, total),
+    };
+    let page = candidates
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|(_, client)| client)
+        .collect();
+
+    Ok((total, with_client_fields(conn, page)?))
+}
+
+/// Converts loaded client rows into domain clients with their custom fields.
+fn with_client_fields(
+    conn: &mut SqliteConnection,
+    db_clients: Vec<DbClient>,
+) -> RepositoryResult<Vec<Client>> {
+    if db_clients.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Load recipient fields, grouped by recipient
+    let db_fields = ClientField::belonging_to(&db_clients)
+        .select(ClientField::as_select())
+        .load::<ClientField>(conn)?
+        .grouped_by(&db_clients);
+
+    db_clients
+        .into_iter()
+        .zip(db_fields)
+        .map(|(c, f)| {
+            let mut client = Client::try_from(c).map_err(RepositoryError::from)?;
+            let fields = f.into_iter().map(|f| (f.field, f.value)).collect();
+            client.fields = Some(fields);
+            Ok(client)
+        })
+        .collect()
+}
+
+/// Loads a single client with its custom fields.
+fn load_client(
+    conn: &mut SqliteConnection,
+    client_id: ClientId,
+) -> RepositoryResult<Option<Client>> {
+    use crate::schema::clients;
+
+    let db_clients = clients::table
+        .find(client_id.get())
+        .load::<DbClient>(conn)?;
+
+    Ok(with_client_fields(conn, db_clients)?.pop())
+}
+
+/// Replaces the custom fields of a client and refreshes the denormalized
+/// `clients.fields` search column.
+fn replace_fields(
+    conn: &mut SqliteConnection,
+    client_id: ClientId,
+    fields: Option<&BTreeMap<String, String>>,
+) -> QueryResult<()> {
+    use crate::schema::{client_fields, clients};
+
+    diesel::delete(client_fields::table.filter(client_fields::client_id.eq(client_id.get())))
+        .execute(conn)?;
+
+    for (field, value) in fields.into_iter().flatten() {
+        let new_field = ClientField {
+            client_id: client_id.get(),
+            field: field.to_string(),
+            value: value.to_string(),
+        };
+        diesel::insert_into(client_fields::table)
+            .values(&new_field)
+            .execute(conn)?;
+    }
+
+    // Update denormalized `clients.fields` using a Diesel subselect
+    diesel::update(clients::table.find(client_id.get()))
+        .set(
+            clients::fields.eq(client_fields::table
+                .filter(client_fields::client_id.eq(client_id.get()))
+                .select(diesel::dsl::sql::<Nullable<Text>>(
+                    "trim(COALESCE(group_concat(value, ' '), ''))",
+                ))
+                .single_value()),
+        )
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Increments the stored version of a client, marking open edits as stale.
+fn bump_version(conn: &mut SqliteConnection, client_id: ClientId) -> QueryResult<()> {
+    use crate::schema::clients;
+
+    diesel::update(clients::table.find(client_id.get()))
+        .set(clients::version.eq(clients::version + 1))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Inserts a chunk of new clients with one statement and returns the ids of
+/// the inserted rows keyed by public id.
+///
+/// [`ImportConflictStrategy::Skip`] lets SQLite drop conflicting rows. With
+/// [`ImportConflictStrategy::Error`] a conflicting chunk is retried row by row
+/// so the error names the offending client.
+fn insert_client_chunk(
+    conn: &mut SqliteConnection,
+    chunk: &[NewClient],
+    strategy: ImportConflictStrategy,
+) -> ClientWriteResult<HashMap<Vec<u8>, i32>> {
+    use crate::schema::clients;
+
+    let db_chunk: Vec<DbNewClient> = chunk.iter().map(DbNewClient::from).collect();
+
+    let inserted = if strategy == ImportConflictStrategy::Skip {
+        diesel::insert_into(clients::table)
+            .values(&db_chunk)
+            .on_conflict_do_nothing()
+            .get_results::<DbClient>(conn)?
+    } else {
+        match diesel::insert_into(clients::table)
+            .values(&db_chunk)
+            .get_results::<DbClient>(conn)
+        {
+            Ok(inserted) => inserted,
+            Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                return insert_clients_one_by_one(conn, chunk);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    };
+
+    Ok(inserted
+        .into_iter()
+        .filter_map(|client| client.public_id.map(|public_id| (public_id, client.id)))
+        .collect())
+}
+
+/// Inserts `chunk` one row at a time, failing on the first client that
+/// already exists in its hub.
+fn insert_clients_one_by_one(
+    conn: &mut SqliteConnection,
+    chunk: &[NewClient],
+) -> ClientWriteResult<HashMap<Vec<u8>, i32>> {
+    use crate::schema::clients;
+
+    let mut inserted = HashMap::with_capacity(chunk.len());
+    for new in chunk {
+        match diesel::insert_into(clients::table)
+            .values(&DbNewClient::from(new))
+            .get_result::<DbClient>(conn)
+        {
+            Ok(client) => {
+                inserted.insert(new.public_id.as_bytes().to_vec(), client.id);
+            }
+            Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                return Err(ClientWriteError::ClientExists {
+                    hub_id: new.hub_id.get(),
+                    name: new.name.as_str().to_string(),
+                });
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// Stores the custom fields of the freshly inserted clients of `chunk` and
+/// refreshes their denormalized `clients.fields`.
+fn insert_new_client_fields(
+    conn: &mut SqliteConnection,
+    chunk: &[NewClient],
+    inserted: &HashMap<Vec<u8>, i32>,
+) -> RepositoryResult<()> {
+    use crate::schema::{client_fields, clients};
+
+    let new_fields: Vec<ClientField> = chunk
+        .iter()
+        .filter_map(|new| {
+            let client_id = *inserted.get(new.public_id.as_bytes().as_slice())?;
+            Some(
+                new.fields
+                    .iter()
+                    .flatten()
+                    .map(move |(field, value)| ClientField {
+                        client_id,
+                        field: field.clone(),
+                        value: value.clone(),
+                    }),
+            )
+        })
+        .flatten()
+        .collect();
+    if new_fields.is_empty() {
+        return Ok(());
+    }
+
+    let client_ids: BTreeSet<i32> = new_fields.iter().map(|field| field.client_id).collect();
+    // One client can carry many fields, so these rows are chunked on their own.
+    for fields in new_fields.chunks(SQLITE_MAX_BIND_PARAMS / CLIENT_FIELD_COLUMNS) {
+        diesel::insert_into(client_fields::table)
+            .values(fields)
+            .execute(conn)?;
+    }
+
+    diesel::update(clients::table.filter(clients::id.eq_any(client_ids.iter().copied())))
+        .set(clients::fields.eq(diesel::dsl::sql::<Nullable<Text>>(
+            "(SELECT trim(COALESCE(group_concat(value, ' '), '')) \
+             FROM client_fields WHERE client_fields.client_id = clients.id)",
+        )))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Raw phone of a row that has no email or valid phone to be matched on.
+fn raw_phone_only(new: &NewClient) -> Option<&str> {
+    if new.email.is_some() || new.phone.is_some() {
+        return None;
+    }
+    new.raw_phone.as_ref().map(|raw_phone| raw_phone.as_str())
+}
+
+/// Client of the hub already holding `raw_phone`, lowest id first.
+fn find_client_by_raw_phone(
+    conn: &mut SqliteConnection,
+    hub_id: i32,
+    raw_phone: &str,
+) -> QueryResult<Option<DbClient>> {
+    use crate::schema::clients;
+
+    clients::table
+        .filter(clients::hub_id.eq(hub_id))
+        .filter(clients::raw_phone.eq(raw_phone))
+        .order(clients::id.asc())
+        .first::<DbClient>(conn)
+        .optional()
+}
+
+/// Indexes of the raw-phone-only rows of `chunk` whose raw phone an existing
+/// hub client, or an earlier row of the chunk, already holds.
+fn known_raw_phone_rows(
+    conn: &mut SqliteConnection,
+    chunk: &[NewClient],
+) -> QueryResult<BTreeSet<usize>> {
+    let mut seen = HashSet::new();
+    let mut known = BTreeSet::new();
+    for (index, new) in chunk.iter().enumerate() {
+        let Some(raw_phone) = raw_phone_only(new) else {
+            continue;
+        };
+        let hub_id = new.hub_id.get();
+        if !seen.insert((hub_id, raw_phone))
+            || find_client_by_raw_phone(conn, hub_id, raw_phone)?.is_some()
+        {
+            known.insert(index);
+        }
+    }
+    Ok(known)
+}
+
+/// Inserts `new_clients` and updates the ones matching an existing email or
+/// phone of the hub, without checking the hub client limits.
+fn upsert_client_rows(
+    conn: &mut SqliteConnection,
+    new_clients: &[NewClient],
+) -> ClientWriteResult<Vec<ClientWriteOutcome>> {
+    use crate::schema::clients;
+
+    let mut outcomes = Vec::with_capacity(new_clients.len());
+
+    for new in new_clients {
+        let db_new: DbNewClient = new.into();
+
+        // Rows with only a raw phone have no unique column to conflict on, so
+        // match them to the hub client holding the same raw phone.
+        let raw_phone_match = match raw_phone_only(new) {
+            Some(raw_phone) => find_client_by_raw_phone(conn, new.hub_id.get(), raw_phone)?,
+            None => None,
+        };
+        if let Some(existing) = raw_phone_match {
+            diesel::update(clients::table.find(existing.id))
+                .set((
+                    clients::name.eq(new.name.as_str()),
+                    clients::version.eq(clients::version + 1),
+                ))
+                .execute(conn)?;
+            replace_upserted_fields(conn, existing.id, new.fields.as_ref())?;
+            outcomes.push(ClientWriteOutcome::Updated);
+            continue;
+        }
+
+        // The upsert below reports the row either way, so look up the
+        // email first to tell updates from inserts.
+        let email_taken = match &new.email {
+            Some(email) => diesel::select(exists(
+                clients::table
+                    .filter(clients::hub_id.eq(new.hub_id.get()))
+                    .filter(clients::email.eq(email.as_str())),
+            ))
+            .get_result::<bool>(conn)?,
+            None => false,
+        };
+
+        let inserted = diesel::insert_into(clients::table)
+            .values(&db_new)
+            .on_conflict((clients::email, clients::hub_id))
+            .do_update()
+            .set((
+                clients::name.eq(new.name.as_str()),
+                clients::email.eq(new.email.as_ref().map(|email| email.as_str())),
+                clients::phone.eq(new.phone.as_ref().map(|phone| phone.as_str())),
+                clients::raw_phone.eq(db_new.raw_phone),
+                clients::version.eq(clients::version + 1),
+            ))
+            .get_result::<DbClient>(conn);
+
+        let (client_id, updated) = match inserted {
+            Ok(client) => (client.id, email_taken),
+            Err(err) => {
+                if let diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) =
+                    err
+                {
+                    // likely conflict on (hub_id, phone), try to find and update existing record
+                    let Some(phone) = &new.phone else {
+                        outcomes.push(ClientWriteOutcome::Skipped);
+                        continue;
+                    };
+
+                    let existing = match clients::table
+                        .filter(clients::hub_id.eq(new.hub_id.get()))
+                        .filter(clients::phone.eq(phone.as_str()))
+                        .first::<DbClient>(conn)
+                    {
+                        Ok(client) => client,
+                        Err(_) => {
+                            outcomes.push(ClientWriteOutcome::Skipped);
+                            continue;
+                        }
+                    };
+
+                    if diesel::update(clients::table.find(existing.id))
+                        .set((
+                            clients::name.eq(new.name.as_str()),
+                            clients::email.eq(new.email.as_ref().map(|email| email.as_str())),
+                            clients::phone.eq(new.phone.as_ref().map(|phone| phone.as_str())),
+                            clients::raw_phone.eq(db_new.raw_phone),
+                            clients::version.eq(clients::version + 1),
+                        ))
+                        .execute(conn)
+                        .is_err()
+                    {
+                        outcomes.push(ClientWriteOutcome::Skipped);
+                        continue;
+                    }
+
+                    (existing.id, true)
+                } else {
+                    outcomes.push(ClientWriteOutcome::Skipped);
+                    continue;
+                }
+            }
+        };
+
+        replace_upserted_fields(conn, client_id, new.fields.as_ref())?;
+
+        outcomes.push(if updated {
+            ClientWriteOutcome::Updated
+        } else {
+            ClientWriteOutcome::Inserted
+        });
+    }
+
+    Ok(outcomes)
+}
+
+/// Replaces the custom fields of an upserted client and refreshes its
+/// denormalized `clients.fields`.
+fn replace_upserted_fields(
+    conn: &mut SqliteConnection,
+    client_id: i32,
+    fields: Option<&BTreeMap<String, String>>,
+) -> QueryResult<()> {
+    use crate::schema::{client_fields, clients};
+
+    // Update fields (delete all → insert new)
+    diesel::delete(client_fields::table.filter(client_fields::client_id.eq(client_id)))
+        .execute(conn)?;
+
+    // Insert optional fields
+    if let Some(fields) = fields {
+        let new_fields: Vec<ClientField> = fields
+            .iter()
+            .map(|(f, v)| ClientField {
+                client_id,
+                field: f.clone(),
+                value: v.clone(),
+            })
+            .collect();
+        if !new_fields.is_empty() {
+            for field in new_fields {
+                diesel::insert_into(client_fields::table)
+                    .values(&field)
+                    .on_conflict((client_fields::client_id, client_fields::field))
+                    .do_update()
+                    .set(client_fields::value.eq(excluded(client_fields::value)))
+                    .execute(conn)?;
+            }
+        }
+    }
+
+    // Update denormalized `clients.fields` using a Diesel subselect
+    diesel::update(clients::table.find(client_id))
+        .set(
+            clients::fields.eq(client_fields::table
+                .filter(client_fields::client_id.eq(client_id))
+                .select(diesel::dsl::sql::<Nullable<Text>>(
+                    "trim(COALESCE(group_concat(value, ' '), ''))",
+                ))
+                .single_value()),
+        )
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Writes one chunk of new clients according to `strategy`, without checking
+/// the hub client limits.
+fn write_client_chunk(
+    conn: &mut SqliteConnection,
+    chunk: &[NewClient],
+    strategy: ImportConflictStrategy,
+) -> ClientWriteResult<ClientUpsertSummary> {
+    if strategy == ImportConflictStrategy::Upsert {
+        return Ok(upsert_client_rows(conn, chunk)?.into_iter().collect());
+    }
+
+    // Raw-phone-only rows have no unique column to conflict on, so the
+    // existing ones are resolved here.
+    let known = known_raw_phone_rows(conn, chunk)?;
+    if let (ImportConflictStrategy::Error, Some(&index)) = (strategy, known.first()) {
+        return Err(ClientWriteError::ClientExists {
+            hub_id: chunk[index].hub_id.get(),
+            name: chunk[index].name.as_str().to_string(),
+        });
+    }
+    let fresh: Vec<NewClient>;
+    let chunk = if known.is_empty() {
+        chunk
+    } else {
+        fresh = chunk
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !known.contains(index))
+            .map(|(_, new)| new.clone())
+            .collect();
+        &fresh
+    };
+
+    let inserted = insert_client_chunk(conn, chunk, strategy)?;
+    insert_new_client_fields(conn, chunk, &inserted)?;
+
+    Ok(ClientUpsertSummary {
+        inserted: inserted.len(),
+        updated: 0,
+    })
+}
+
+/// Writes `new_clients` in chunks of `chunk_size`, adding to `summary` and
+/// passing the running totals to `on_chunk` after each chunk, then checks the
+/// hub client limits.
+fn write_client_chunks(
+    conn: &mut SqliteConnection,
+    new_clients: &[NewClient],
+    chunk_size: usize,
+    strategy: ImportConflictStrategy,
+    mut summary: ClientUpsertSummary,
+    on_chunk: &dyn Fn(ClientUpsertSummary),
+) -> ClientWriteResult<ClientUpsertSummary> {
+    for chunk in new_clients.chunks(chunk_size) {
+        let written = write_client_chunk(conn, chunk, strategy)?;
+        summary.inserted += written.inserted;
+        summary.updated += written.updated;
+        on_chunk(summary);
+    }
+
+    let hub_ids = new_clients.iter().map(|new| new.hub_id.get()).collect();
+    ensure_hub_client_limits(conn, &hub_ids)?;
+
+    Ok(summary)
+}
+
+/// Applies `updates` to the loaded client `before` and records the audit entry.
+fn update_loaded_client(
+    conn: &mut SqliteConnection,
+    before: Client,
+    updates: &UpdateClient,
+    actor: &ManagerEmail,
+) -> ClientWriteResult<Client> {
+    use crate::schema::clients;
+
+    let client_id = before.id;
+    // Guard on the version the edit was based on so a concurrent save
+    // is not silently overwritten.
+    let expected_version = updates.expected_version.unwrap_or(before.version);
+    let db_updates: DbUpdateClient = updates.into();
+    let updated = diesel::update(
+        clients::table
+            .filter(clients::id.eq(client_id.get()))
+            .filter(clients::version.eq(expected_version)),
+    )
+    .set((&db_updates, clients::version.eq(clients::version + 1)))
+    .execute(conn)?;
+    if updated == 0 {
+        return Err(ClientWriteError::StaleVersion {
+            client_id: client_id.get(),
+            expected_version,
+        });
+    }
+
+    if let Some(editor) = updates.edited_by {
+        diesel::update(clients::table.find(client_id.get()))
+            .set((
+                clients::last_edited_by.eq(editor.get()),
+                clients::last_edited_at.eq(diesel::dsl::now.nullable()),
+            ))
+            .execute(conn)?;
+    }
+
+    replace_fields(conn, client_id, updates.fields.as_ref())?;
+
+    // Reload the client row with its fields.
+    let after = load_client(conn, client_id)?.ok_or(diesel::result::Error::NotFound)?;
+
+    insert_audit_entry(
+        conn,
+        &NewAuditEntry::client_updated(actor.clone(), &before, &after),
+    )?;
+
+    Ok(after)
+}
+
+/// Rejects the pending transaction when any of `hub_ids` now holds more clients
+/// than its `hub_limits.max_clients`. Hubs without a limit row are unlimited.
+///
+/// Called after inserting so rows skipped as duplicates or upserted in place do
+/// not count towards the limit; the error rolls the whole batch back.
+fn ensure_hub_client_limits(
+    conn: &mut SqliteConnection,
+    hub_ids: &BTreeSet<i32>,
+) -> ClientWriteResult<()> {
+    use crate::schema::{clients, hub_limits};
+
+    for &hub_id in hub_ids {
+        let Some(max_clients) = hub_limits::table
+            .find(hub_id)
+            .select(hub_limits::max_clients)
+            .first::<i32>(conn)
+            .optional()?
+        else {
+            continue;
+        };
+
+        let total = clients::table
+            .filter(clients::hub_id.eq(hub_id))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        if total > i64::from(max_clients) {
+            return Err(ClientWriteError::LimitExceeded {
+                hub_id,
+                max_clients,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Hub clients matching `query` and the segment field matchers, before
+/// sorting and paging.
+fn filtered_clients<'a>(
+    query: &'a ClientListQuery,
+    segment_matchers: &'a [(String, String)],
+    use_fts: bool,
+) -> crate::schema::clients::BoxedQuery<'a, diesel::sqlite::Sqlite> {
+    use crate::schema::{
+        client_events, client_fields, client_fts, client_manager, clients, managers,
+    };
+
+    // Start with boxed query on clients
+    let mut items = clients::table
+        .filter(clients::hub_id.eq(query.hub_id.get()))
+        .into_boxed::<diesel::sqlite::Sqlite>();
+
+    if !query.include_archived {
+        items = items.filter(clients::status.ne(ClientStatus::Archived.as_str()));
+    }
+
+    if let Some(public_id) = &query.public_id {
+        items = items.filter(clients::public_id.eq(public_id.as_bytes()))
+    }
+
+    if let Some(source) = query.source {
+        items = items.filter(clients::source.eq(source.as_str()));
+    }
+
+    if query.unassigned {
+        items = items.filter(not(exists(
+            client_manager::table.filter(client_manager::client_id.eq(clients::id)),
+        )));
+    }
+
+    if let Some(range) = query.created {
+        if let Some(from) = range.from() {
+            items = items.filter(clients::created_at.ge(from.and_time(NaiveTime::MIN)));
+        }
+        if let Some(next_day) = range.to().and_then(|to| to.succ_opt()) {
+            items = items.filter(clients::created_at.lt(next_day.and_time(NaiveTime::MIN)));
+        }
+    }
+
+    if let Some(email) = &query.email_exact {
+        items = items.filter(clients::email.eq(email.as_str()));
+    }
+
+    if let Some(phone) = &query.phone_exact {
+        items = items.filter(clients::phone.eq(phone.as_str()));
+    }
+
+    if let Some(manager_email) = &query.manager_email {
+        let manager_id = || {
+            managers::table
+                .filter(managers::email.eq(manager_email.as_str()))
+                .filter(managers::hub_id.eq(query.hub_id.get()))
+                .select(managers::id)
+                .single_value()
+        };
+
+        items = items.filter(
+            clients::id.eq_any(
+                client_manager::table
+                    .filter(client_manager::manager_id.nullable().eq(manager_id()))
+                    .select(client_manager::client_id),
+            ),
+        );
+
+        if query.pending_replies {
+            // A reply is pending when no outbound event of the manager
+            // was recorded at or after it.
+            let answered = exists(
+                outbound_events
+                    .filter(
+                        outbound_events
+                            .field(client_events::client_id)
+                            .eq(client_events::client_id),
+                    )
+                    .filter(
+                        outbound_events
+                            .field(client_events::manager_id)
+                            .nullable()
+                            .eq(manager_id()),
+                    )
+                    .filter(
+                        outbound_events
+                            .field(client_events::event_type)
+                            .eq_any(OUTBOUND_EVENT_TYPES),
+                    )
+                    .filter(
+                        outbound_events
+                            .field(client_events::created_at)
+                            .ge(client_events::created_at),
+                    ),
+            );
+
+            items = items.filter(exists(
+                client_events::table
+                    .filter(client_events::client_id.eq(clients::id))
+                    .filter(client_events::event_type.eq("Reply"))
+                    .filter(not(answered)),
+            ));
+        }
+    }
+
+    for (field, value) in &segment_matchers {
+        items = items.filter(exists(
+            client_fields::table
+                .filter(client_fields::client_id.eq(clients::id))
+                .filter(client_fields::field.eq(field))
+                .filter(client_fields::value.eq(value)),
+        ));
+    }
+
+    for (field, value) in &query.field_filters {
+        items = items.filter(exists(
+            client_fields::table
+                .filter(client_fields::client_id.eq(clients::id))
+                .filter(client_fields::field.eq(field))
+                .filter(
+                    client_fields::value
+                        .like(format!("%{}%", escape_like(value)))
+                        .escape('\\'),
+                ),
+        ));
+    }
+
+    if use_fts
+        && let Some(term) = query.search.as_ref()
+        && let Some(fts_query) =
+            FtsQueryBuilder::new(term.as_str(), query.fts_operator).to_match_expression()
+    {
+        let fts_filter = exists(
+            client_fts::table
+                .filter(client_fts::rowid.eq(clients::id))
+                .filter(diesel::dsl::sql::<Bool>("client_fts MATCH ").bind::<Text, _>(fts_query)),
+        );
+        items = items.filter(fts_filter);
+    }
+
+    items
+}
+
+/// Query loading a page of [`ClientReader::list_clients`] results for queries
+/// without a cursor.
+pub fn client_page_query<'a>(
+    query: &'a ClientListQuery,
+    segment_matchers: &'a [(String, String)],
+) -> crate::schema::clients::BoxedQuery<'a, diesel::sqlite::Sqlite> {
+    use crate::schema::clients;
+
+    let mut items = filtered_clients(query, segment_matchers, true);
+
+    // Apply pagination if requested
+    if let Some(pagination) = &query.pagination {
+        let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
+        let limit = pagination.per_page as i64;
+        items = items.offset(offset).limit(limit);
+    }
+
+    match query.sort {
+        ClientSort::Id => items.order(clients::id.asc()),
+        ClientSort::CreatedDesc => items.order((clients::created_at.desc(), clients::id.desc())),
+        ClientSort::UpdatedDesc => items.order((clients::updated_at.desc(), clients::id.desc())),
+    }
+}
+
+/// Query behind [`ClientReader::get_client_by_email`].
+pub fn client_by_email_query(
+    email: &ClientEmail,
+    hub_id: HubId,
+) -> crate::schema::clients::BoxedQuery<'_, diesel::sqlite::Sqlite> {
+    use crate::schema::clients;
+
+    clients::table
+        .filter(clients::email.eq(email.as_str()))
+        .filter(clients::hub_id.eq(hub_id.get()))
+        .into_boxed()
+}
+
 impl ClientReader for DieselRepository {
     fn get_client_by_public_id(
         &self,
@@ -62,28 +960,46 @@ impl ClientReader for DieselRepository {
         Ok(Some(result))
     }
 
-    fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<String>> {
+    fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<FieldMeta>> {
         use crate::schema::{client_fields, clients, important_fields};
 
         let mut conn = self.conn()?;
 
-        let mut fields = client_fields::table
+        let counts = client_fields::table
             .inner_join(clients::table)
             .filter(clients::hub_id.eq(hub_id.get()))
-            .select(client_fields::field)
-            .distinct()
-            .load::<String>(&mut conn)?;
+            .group_by(client_fields::field)
+            .select((client_fields::field, count_star()))
+            .load::<(String, i64)>(&mut conn)?;
 
         let important = important_fields::table
             .filter(important_fields::hub_id.eq(hub_id.get()))
             .select(important_fields::field)
             .load::<String>(&mut conn)?;
 
-        fields.extend(important);
-        fields.sort();
-        fields.dedup();
+        let mut fields: BTreeMap<String, FieldMeta> = counts
+            .into_iter()
+            .map(|(name, count)| {
+                let meta = FieldMeta {
+                    name: name.clone(),
+                    value_count: count as usize,
+                    is_important: false,
+                };
+                (name, meta)
+            })
+            .collect();
+        for name in important {
+            fields
+                .entry(name.clone())
+                .or_insert_with(|| FieldMeta {
+                    name,
+                    value_count: 0,
+                    is_important: false,
+                })
+                .is_important = true;
+        }
 
-        Ok(fields)
+        Ok(fields.into_values().collect())
     }
 
     fn get_client_by_id(&self, id: ClientId, hub_id: HubId) -> RepositoryResult<Option<Client>> {
@@ -112,17 +1028,30 @@ impl ClientReader for DieselRepository {
         Ok(Some(result))
     }
 
+    fn get_clients_by_ids(&self, ids: &[ClientId], hub_id: HubId) -> RepositoryResult<Vec<Client>> {
+        use crate::schema::clients;
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.conn()?;
+        let db_clients = clients::table
+            .filter(clients::id.eq_any(ids.iter().map(|id| id.get())))
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .order(clients::id.asc())
+            .load::<DbClient>(&mut conn)?;
+
+        with_client_fields(&mut conn, db_clients)
+    }
+
     fn get_client_by_email(
         &self,
         email: &ClientEmail,
         hub_id: HubId,
     ) -> RepositoryResult<Option<Client>> {
-        use crate::schema::clients;
-
         let mut conn = self.conn()?;
-        let client = clients::table
-            .filter(clients::email.eq(email.as_str()))
-            .filter(clients::hub_id.eq(hub_id.get()))
+        let client = client_by_email_query(email, hub_id)
             .first::<DbClient>(&mut conn)
             .optional()?;
 
@@ -157,109 +1086,189 @@ impl ClientReader for DieselRepository {
     }
 
     fn list_clients(&self, query: ClientListQuery) -> RepositoryResult<(usize, Vec<Client>)> {
-        use crate::schema::{client_fts, client_manager, clients, managers};
+        use crate::schema::clients;
 
         let mut conn = self.conn()?;
 
-        let query_builder = || {
-            // Start with boxed query on clients
-            let mut items = clients::table
-                .filter(clients::hub_id.eq(query.hub_id.get()))
-                .into_boxed::<diesel::sqlite::Sqlite>();
+        let segment_matchers = match query.segment_id {
+            Some(segment_id) => load_segment(&mut conn, segment_id, query.hub_id)?
+                .ok_or(RepositoryError::NotFound)?
+                .field_matchers(),
+            None => Vec::new(),
+        };
 
-            if let Some(public_id) = &query.public_id {
-                items = items.filter(clients::public_id.eq(public_id.as_bytes()))
-            }
+        // Get the total count before applying pagination
+        let total = filtered_clients(&query, &segment_matchers, true)
+            .count()
+            .get_result::<i64>(&mut conn)? as usize;
+
+        if total == 0
+            && query.fuzzy
+            && let Some(term) = query.search.as_deref()
+        {
+            let items = filtered_clients(&query, &segment_matchers, false);
+            return list_clients_fuzzy(&mut conn, items, term, &query);
+        }
 
-            if let Some(manager_email) = &query.manager_email {
-                items = items.filter(
-                    clients::id.eq_any(
-                        client_manager::table
-                            .filter(
-                                client_manager::manager_id.nullable().eq(managers::table
-                                    .filter(managers::email.eq(manager_email.as_str()))
-                                    .filter(managers::hub_id.eq(query.hub_id.get()))
-                                    .select(managers::id)
-                                    .single_value()),
-                            )
-                            .select(client_manager::client_id),
-                    ),
-                );
-            }
+        if let Some(cursor) = &query.cursor {
+            let db_clients = filtered_clients(&query, &segment_matchers, true)
+                .filter(clients::id.gt(cursor.after_id))
+                .order(clients::id.asc())
+                .limit(cursor.limit as i64)
+                .load::<DbClient>(&mut conn)?;
 
-            if let Some(term) = query.search.as_ref()
-                && let Some(fts_query) = build_fts_match_query(term)
-            {
-                let fts_filter = exists(
-                    client_fts::table
-                        .filter(client_fts::rowid.eq(clients::id))
-                        .filter(
-                            diesel::dsl::sql::<Bool>("client_fts MATCH ")
-                                .bind::<Text, _>(fts_query),
-                        ),
-                );
-                items = items.filter(fts_filter);
-            }
+            return Ok((total, with_client_fields(&mut conn, db_clients)?));
+        }
+
+        // Final load
+        let db_clients =
+            client_page_query(&query, &segment_matchers).load::<DbClient>(&mut conn)?;
+
+        let clients = with_client_fields(&mut conn, db_clients)?;
+
+        Ok((total, clients))
+    }
+
+    fn list_clients_edited_by(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>> {
+        use crate::schema::clients;
+
+        let mut conn = self.conn()?;
+
+        let db_clients = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(clients::last_edited_by.eq(manager_id.get()))
+            .order((clients::last_edited_at.desc(), clients::id.desc()))
+            .limit(limit as i64)
+            .load::<DbClient>(&mut conn)?;
+
+        with_client_fields(&mut conn, db_clients)
+    }
+
+    fn list_recent_clients(
+        &self,
+        hub_id: HubId,
+        manager_email: Option<ManagerEmail>,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>> {
+        use crate::schema::{client_manager, clients, managers};
 
-            items
+        let mut conn = self.conn()?;
+
+        let mut items = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(clients::status.ne(ClientStatus::Archived.as_str()))
+            .into_boxed::<diesel::sqlite::Sqlite>();
+
+        if let Some(manager_email) = &manager_email {
+            let manager_id = managers::table
+                .filter(managers::email.eq(manager_email.as_str()))
+                .filter(managers::hub_id.eq(hub_id.get()))
+                .select(managers::id)
+                .single_value();
+
+            items = items.filter(
+                clients::id.eq_any(
+                    client_manager::table
+                        .filter(client_manager::manager_id.nullable().eq(manager_id))
+                        .select(client_manager::client_id),
+                ),
+            );
+        }
+
+        let db_clients = items
+            .order((clients::created_at.desc(), clients::id.desc()))
+            .limit(limit as i64)
+            .load::<DbClient>(&mut conn)?;
+
+        with_client_fields(&mut conn, db_clients)
+    }
+
+    fn list_managers_for_client(
+        &self,
+        client_id: ClientId,
+        pagination: Option<Pagination>,
+    ) -> RepositoryResult<(usize, Vec<Manager>)> {
+        use crate::schema::{client_manager, clients, managers};
+        let mut conn = self.conn()?;
+        let query_builder = || {
+            let client_hub_id = clients::table
+                .filter(clients::id.eq(client_id.get()))
+                .select(clients::hub_id)
+                .single_value();
+            client_manager::table
+                .filter(client_manager::client_id.eq(client_id.get()))
+                .inner_join(managers::table)
+                .filter(managers::hub_id.nullable().eq(client_hub_id))
+                .into_boxed::<diesel::sqlite::Sqlite>()
         };
 
         // Get the total count before applying pagination
         let total = query_builder().count().get_result::<i64>(&mut conn)? as usize;
 
         let mut items = query_builder();
-
-        // Apply pagination if requested
-        if let Some(pagination) = &query.pagination {
+        if let Some(pagination) = &pagination {
             let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
             let limit = pagination.per_page as i64;
             items = items.offset(offset).limit(limit);
         }
 
-        // Final load
-        let db_clients = items.order(clients::id.asc()).load::<DbClient>(&mut conn)?;
-
-        if db_clients.is_empty() {
-            return Ok((total, Vec::new()));
-        }
-
-        // Load recipient fields, grouped by recipient
-        let db_fields = ClientField::belonging_to(&db_clients)
-            .select(ClientField::as_select())
-            .load::<ClientField>(&mut conn)?
-            .grouped_by(&db_clients);
-
-        let clients = db_clients
+        let managers = items
+            .order(managers::id.asc())
+            .select(DbManager::as_select())
+            .load::<DbManager>(&mut conn)?
             .into_iter()
-            .zip(db_fields)
-            .map(|(c, f)| {
-                let mut client = Client::try_from(c).map_err(RepositoryError::from)?;
-                let fields = f.into_iter().map(|f| (f.field, f.value)).collect();
-                client.fields = Some(fields);
-                Ok(client)
-            })
+            .map(|db_manager| Manager::try_from(db_manager).map_err(RepositoryError::from))
             .collect::<Result<Vec<_>, RepositoryError>>()?;
+        Ok((total, managers))
+    }
 
-        Ok((total, clients))
+    fn count_clients_by_name(
+        &self,
+        hub_id: HubId,
+        name: &ClientName,
+        except: Option<ClientId>,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::clients;
+
+        let mut conn = self.conn()?;
+        let mut items = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(clients::name.eq(name.as_str()))
+            .into_boxed::<diesel::sqlite::Sqlite>();
+        if let Some(except) = except {
+            items = items.filter(clients::id.ne(except.get()));
+        }
+        let total = items.count().get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
     }
 
-    fn list_managers(&self, id: ClientId) -> RepositoryResult<Vec<Manager>> {
-        use crate::schema::{client_manager, clients, managers};
+    fn client_ids_by_name_key(
+        &self,
+        hub_id: HubId,
+    ) -> RepositoryResult<HashMap<String, Vec<ClientId>>> {
+        use crate::schema::clients;
+
         let mut conn = self.conn()?;
-        let client_hub_id = clients::table
-            .filter(clients::id.eq(id.get()))
-            .select(clients::hub_id)
-            .single_value();
-        let managers = client_manager::table
-            .filter(client_manager::client_id.eq(id.get()))
-            .inner_join(managers::table)
-            .filter(managers::hub_id.nullable().eq(client_hub_id))
-            .select(managers::all_columns)
-            .load::<DbManager>(&mut conn)?
-            .into_iter()
-            .map(|db_manager| Manager::try_from(db_manager).map_err(RepositoryError::from))
-            .collect::<Result<Vec<_>, RepositoryError>>()?;
-        Ok(managers)
+        let names = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .order(clients::id.asc())
+            .select((clients::id, clients::name))
+            .load::<(i32, String)>(&mut conn)?;
+
+        let mut by_key: HashMap<String, Vec<ClientId>> = HashMap::new();
+        for (id, name) in names {
+            by_key
+                .entry(client_name_match_key(&name))
+                .or_default()
+                .push(ClientId::new(id)?);
+        }
+        Ok(by_key)
     }
 
     fn check_client_assigned_to_manager(
@@ -285,182 +1294,80 @@ impl ClientReader for DieselRepository {
 }
 
 impl ClientWriter for DieselRepository {
-    fn create_or_replace_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize> {
-        use crate::schema::{client_fields, clients};
+    fn create_or_replace_clients(
+        &self,
+        new_clients: &[NewClient],
+    ) -> ClientWriteResult<ClientUpsertSummary> {
+        Ok(self.upsert_clients(new_clients)?.into_iter().collect())
+    }
 
+    fn upsert_clients(
+        &self,
+        new_clients: &[NewClient],
+    ) -> ClientWriteResult<Vec<ClientWriteOutcome>> {
         let mut conn = self.conn()?;
 
-        conn.transaction::<usize, RepositoryError, _>(|conn| {
-            let mut count_inserted: usize = 0;
-
-            for new in new_clients {
-                let db_new: DbNewClient = new.into();
-
-                let inserted = diesel::insert_into(clients::table)
-                    .values(&db_new)
-                    .on_conflict((clients::email, clients::hub_id))
-                    .do_update()
-                    .set((
-                        clients::name.eq(new.name.as_str()),
-                        clients::email.eq(new.email.as_ref().map(|email| email.as_str())),
-                        clients::phone.eq(new.phone.as_ref().map(|phone| phone.as_str())),
-                    ))
-                    .get_result::<DbClient>(conn);
-
-                let client_id = match inserted {
-                    Ok(client) => client.id,
-                    Err(err) => {
-                        if let diesel::result::Error::DatabaseError(
-                            DatabaseErrorKind::UniqueViolation,
-                            _,
-                        ) = err
-                        {
-                            // likely conflict on (hub_id, phone), try to find and update existing record
-                            let Some(phone) = &new.phone else { continue };
-
-                            let existing = match clients::table
-                                .filter(clients::hub_id.eq(new.hub_id.get()))
-                                .filter(clients::phone.eq(phone.as_str()))
-                                .first::<DbClient>(conn)
-                            {
-                                Ok(client) => client,
-                                Err(_) => continue,
-                            };
-
-                            if diesel::update(clients::table.find(existing.id))
-                                .set((
-                                    clients::name.eq(new.name.as_str()),
-                                    clients::email
-                                        .eq(new.email.as_ref().map(|email| email.as_str())),
-                                    clients::phone
-                                        .eq(new.phone.as_ref().map(|phone| phone.as_str())),
-                                ))
-                                .execute(conn)
-                                .is_err()
-                            {
-                                continue;
-                            }
-
-                            existing.id
-                        } else {
-                            continue;
-                        }
-                    }
-                };
-
-                // Update fields (delete all → insert new)
-                diesel::delete(client_fields::table.filter(client_fields::client_id.eq(client_id)))
-                    .execute(conn)?;
+        conn.transaction::<Vec<ClientWriteOutcome>, ClientWriteError, _>(|conn| {
+            let outcomes = upsert_client_rows(conn, new_clients)?;
 
-                // Insert optional fields
-                if let Some(fields) = &new.fields {
-                    let new_fields: Vec<ClientField> = fields
-                        .iter()
-                        .map(|(f, v)| ClientField {
-                            client_id,
-                            field: f.clone(),
-                            value: v.clone(),
-                        })
-                        .collect();
-                    if !new_fields.is_empty() {
-                        for field in new_fields {
-                            diesel::insert_into(client_fields::table)
-                                .values(&field)
-                                .on_conflict((client_fields::client_id, client_fields::field))
-                                .do_update()
-                                .set(client_fields::value.eq(excluded(client_fields::value)))
-                                .execute(conn)?;
-                        }
-                    }
-                }
+            let hub_ids = new_clients.iter().map(|new| new.hub_id.get()).collect();
+            ensure_hub_client_limits(conn, &hub_ids)?;
 
-                // Update denormalized `clients.fields` using a Diesel subselect
-                diesel::update(clients::table.find(client_id))
-                    .set(
-                        clients::fields.eq(client_fields::table
-                            .filter(client_fields::client_id.eq(client_id))
-                            .select(diesel::dsl::sql::<Nullable<Text>>(
-                                "trim(COALESCE(group_concat(value, ' '), ''))",
-                            ))
-                            .single_value()),
-                    )
-                    .execute(conn)?;
-
-                count_inserted += 1;
-            }
-
-            Ok(count_inserted)
+            Ok(outcomes)
         })
     }
 
-    fn create_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize> {
-        use crate::schema::{client_fields, clients};
-
+    fn create_clients(
+        &self,
+        new_clients: &[NewClient],
+        strategy: ImportConflictStrategy,
+    ) -> ClientWriteResult<ClientUpsertSummary> {
         let mut conn = self.conn()?;
 
-        conn.transaction::<usize, RepositoryError, _>(|conn| {
-            let mut count_inserted: usize = 0;
-
-            for new in new_clients {
-                let db_new: DbNewClient = new.into();
-
-                let inserted = diesel::insert_into(clients::table)
-                    .values(&db_new)
-                    .get_result::<DbClient>(conn);
-
-                let client_id = match inserted {
-                    Ok(client) => client.id,
-                    Err(diesel::result::Error::DatabaseError(
-                        DatabaseErrorKind::UniqueViolation,
-                        _,
-                    )) => {
-                        continue;
-                    }
-                    Err(err) => return Err(RepositoryError::from(err)),
-                };
-
-                // Update fields (delete all → insert new)
-                diesel::delete(client_fields::table.filter(client_fields::client_id.eq(client_id)))
-                    .execute(conn)?;
+        conn.transaction::<ClientUpsertSummary, ClientWriteError, _>(|conn| {
+            write_client_chunks(
+                conn,
+                new_clients,
+                self.batch_chunk_size,
+                strategy,
+                ClientUpsertSummary::default(),
+                &|_| {},
+            )
+        })
+    }
 
-                // Insert optional fields
-                if let Some(fields) = &new.fields {
-                    let new_fields: Vec<ClientField> = fields
-                        .iter()
-                        .map(|(f, v)| ClientField {
-                            client_id,
-                            field: f.clone(),
-                            value: v.clone(),
-                        })
-                        .collect();
-                    if !new_fields.is_empty() {
-                        for field in new_fields {
-                            diesel::insert_into(client_fields::table)
-                                .values(&field)
-                                .on_conflict((client_fields::client_id, client_fields::field))
-                                .do_update()
-                                .set(client_fields::value.eq(excluded(client_fields::value)))
-                                .execute(conn)?;
-                        }
-                    }
-                }
+    fn import_clients(
+        &self,
+        new_clients: &[NewClient],
+        name_matches: &[(ClientId, NewClient)],
+        strategy: ImportConflictStrategy,
+        actor: &ManagerEmail,
+        on_chunk: &dyn Fn(ClientUpsertSummary),
+    ) -> ClientWriteResult<ClientUpsertSummary> {
+        let mut conn = self.conn()?;
 
-                // Update denormalized `clients.fields` using a Diesel subselect
-                diesel::update(clients::table.find(client_id))
-                    .set(
-                        clients::fields.eq(client_fields::table
-                            .filter(client_fields::client_id.eq(client_id))
-                            .select(diesel::dsl::sql::<Nullable<Text>>(
-                                "trim(COALESCE(group_concat(value, ' '), ''))",
-                            ))
-                            .single_value()),
-                    )
-                    .execute(conn)?;
+        conn.transaction::<ClientUpsertSummary, ClientWriteError, _>(|conn| {
+            let mut summary = ClientUpsertSummary::default();
 
-                count_inserted += 1;
+            for (client_id, row) in name_matches {
+                let before =
+                    load_client(conn, *client_id)?.ok_or(diesel::result::Error::NotFound)?;
+                let updates = UpdateClient::from_import(&before, row);
+                update_loaded_client(conn, before, &updates, actor)?;
+                summary.updated += 1;
+            }
+            if !name_matches.is_empty() {
+                on_chunk(summary);
             }
 
-            Ok(count_inserted)
+            write_client_chunks(
+                conn,
+                new_clients,
+                self.batch_chunk_size,
+                strategy,
+                summary,
+                on_chunk,
+            )
         })
     }
 
@@ -468,88 +1375,55 @@ impl ClientWriter for DieselRepository {
         &self,
         client_id: ClientId,
         updates: &UpdateClient,
-    ) -> RepositoryResult<Client> {
-        use crate::schema::{client_fields, clients};
+        actor: &ManagerEmail,
+    ) -> ClientWriteResult<Client> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<Client, ClientWriteError, _>(|conn| {
+            let before = load_client(conn, client_id)?.ok_or(diesel::result::Error::NotFound)?;
+            update_loaded_client(conn, before, updates, actor)
+        })
+    }
 
+    fn replace_client_fields(
+        &self,
+        client_id: ClientId,
+        fields: &BTreeMap<String, String>,
+        actor: &ManagerEmail,
+    ) -> RepositoryResult<Client> {
         let mut conn = self.conn()?;
 
-        let (updated_record, updated_fields) = conn
-            .transaction::<(DbClient, BTreeMap<String, String>), diesel::result::Error, _>(
-                |conn| {
-                    let db_updates: DbUpdateClient = updates.into();
-                    let updated = diesel::update(clients::table.find(client_id.get()))
-                        .set(&db_updates)
-                        .execute(conn)?;
-                    if updated == 0 {
-                        return Err(diesel::result::Error::NotFound);
-                    }
+        conn.transaction::<Client, RepositoryError, _>(|conn| {
+            let before = load_client(conn, client_id)?.ok_or(diesel::result::Error::NotFound)?;
 
-                    // Update fields (delete all → insert new)
-                    diesel::delete(
-                        client_fields::table.filter(client_fields::client_id.eq(client_id.get())),
-                    )
-                    .execute(conn)?;
+            replace_fields(conn, client_id, Some(fields))?;
+            bump_version(conn, client_id)?;
 
-                    if let Some(fields) = &updates.fields {
-                        for (field, value) in fields {
-                            let new_field = ClientField {
-                                client_id: client_id.get(),
-                                field: field.to_string(),
-                                value: value.to_string(),
-                            };
-                            diesel::insert_into(client_fields::table)
-                                .values(&new_field)
-                                .execute(conn)?;
-                        }
-                    }
+            let after = load_client(conn, client_id)?.ok_or(diesel::result::Error::NotFound)?;
 
-                    // Update denormalized `clients.fields` using a Diesel subselect
-                    diesel::update(clients::table.find(client_id.get()))
-                        .set(
-                            clients::fields.eq(client_fields::table
-                                .filter(client_fields::client_id.eq(client_id.get()))
-                                .select(diesel::dsl::sql::<Nullable<Text>>(
-                                    "trim(COALESCE(group_concat(value, ' '), ''))",
-                                ))
-                                .single_value()),
-                        )
-                        .execute(conn)?;
-
-                    // Reload the client row with its fields.
-                    let updated_client = clients::table
-                        .find(client_id.get())
-                        .first::<DbClient>(conn)?;
-
-                    let fields_vec = client_fields::table
-                        .filter(client_fields::client_id.eq(client_id.get()))
-                        .select(ClientField::as_select())
-                        .load::<ClientField>(conn)?;
-
-                    let fields_map = fields_vec
-                        .into_iter()
-                        .map(|f| (f.field, f.value))
-                        .collect::<BTreeMap<_, _>>();
-
-                    Ok((updated_client, fields_map))
-                },
+            insert_audit_entry(
+                conn,
+                &NewAuditEntry::client_updated(actor.clone(), &before, &after),
             )?;
 
-        let mut updated = Client::try_from(updated_record).map_err(RepositoryError::from)?;
-        updated.fields = Some(updated_fields);
-
-        Ok(updated)
+            Ok(after)
+        })
     }
 
-    fn delete_client(&self, client_id: ClientId) -> RepositoryResult<()> {
-        use crate::schema::{client_events, client_fields, client_manager, clients};
+    fn delete_client(&self, client_id: ClientId, actor: &ManagerEmail) -> RepositoryResult<()> {
+        use crate::schema::{client_events, client_fields, client_manager, client_notes, clients};
 
         let mut conn = self.conn()?;
 
-        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+        conn.transaction::<(), RepositoryError, _>(|conn| {
+            let before = load_client(conn, client_id)?.ok_or(diesel::result::Error::NotFound)?;
+
             diesel::delete(
                 client_events::table.filter(client_events::client_id.eq(client_id.get())),
             )
             .execute(conn)?;
+            diesel::delete(client_notes::table.filter(client_notes::client_id.eq(client_id.get())))
+                .execute(conn)?;
             diesel::delete(
                 client_manager::table.filter(client_manager::client_id.eq(client_id.get())),
             )
@@ -558,17 +1432,16 @@ impl ClientWriter for DieselRepository {
                 client_fields::table.filter(client_fields::client_id.eq(client_id.get())),
             )
             .execute(conn)?;
-            let deleted = diesel::delete(clients::table.find(client_id.get())).execute(conn)?;
-            if deleted == 0 {
-                return Err(diesel::result::Error::NotFound);
-            }
+            diesel::delete(clients::table.find(client_id.get())).execute(conn)?;
+
+            insert_audit_entry(conn, &NewAuditEntry::client_deleted(actor.clone(), &before))?;
+
             Ok(())
         })
-        .map_err(RepositoryError::from)
     }
 
     fn delete_all_clients(&self, hub_id: HubId) -> RepositoryResult<()> {
-        use crate::schema::{client_events, client_fields, client_manager, clients};
+        use crate::schema::{client_events, client_fields, client_manager, client_notes, clients};
 
         let mut conn = self.conn()?;
 
@@ -582,6 +1455,10 @@ impl ClientWriter for DieselRepository {
                 client_events::table.filter(client_events::client_id.eq_any(client_ids())),
             )
             .execute(conn)?;
+            diesel::delete(
+                client_notes::table.filter(client_notes::client_id.eq_any(client_ids())),
+            )
+            .execute(conn)?;
             diesel::delete(
                 client_manager::table.filter(client_manager::client_id.eq_any(client_ids())),
             )
@@ -596,6 +1473,29 @@ impl ClientWriter for DieselRepository {
         })
         .map_err(RepositoryError::from)
     }
+
+    fn set_client_status(
+        &self,
+        client_id: ClientId,
+        hub_id: HubId,
+        status: ClientStatus,
+    ) -> RepositoryResult<()> {
+        use crate::schema::clients;
+
+        let mut conn = self.conn()?;
+        let updated = diesel::update(
+            clients::table
+                .filter(clients::id.eq(client_id.get()))
+                .filter(clients::hub_id.eq(hub_id.get())),
+        )
+        .set(clients::status.eq(status.as_str()))
+        .execute(&mut conn)?;
+
+        if updated == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
 }
 
 impl ImportantFieldReader for DieselRepository {
@@ -648,4 +1548,23 @@ impl ImportantFieldWriter for DieselRepository {
         })
         .map_err(RepositoryError::from)
     }
+
+    fn delete_important_field(
+        &self,
+        hub: HubId,
+        name: &ImportantFieldName,
+    ) -> RepositoryResult<bool> {
+        use crate::schema::important_fields;
+
+        let mut conn = self.conn()?;
+
+        let deleted = diesel::delete(
+            important_fields::table
+                .filter(important_fields::hub_id.eq(hub.get()))
+                .filter(important_fields::field.eq(name.as_str())),
+        )
+        .execute(&mut conn)?;
+
+        Ok(deleted > 0)
+    }
 }
@@ -1,5 +1,6 @@
 //! Repository implementation for CRM client events.
 
+use chrono::{Duration, NaiveDateTime, Utc};
 use diesel::dsl::{exists, select};
 use diesel::prelude::*;
 use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
@@ -8,7 +9,7 @@ use std::convert::TryInto;
 use crate::domain::manager::Manager;
 use crate::domain::{
     client_event::{ClientEvent, NewClientEvent},
-    types::TypeConstraintError,
+    types::{HubId, TypeConstraintError},
 };
 use crate::models::client_event::{
     ClientEvent as DbClientEvent, NewClientEvent as DbNewClientEvent,
@@ -18,43 +19,59 @@ use crate::repository::{
     ClientEventListQuery, ClientEventReader, ClientEventWriter, DieselRepository,
 };
 
+/// Events of [`ClientEventListQuery::client_id`] matching the query, before
+/// ordering and paging.
+fn client_events_query(
+    query: &ClientEventListQuery,
+) -> crate::schema::client_events::BoxedQuery<'_, diesel::sqlite::Sqlite> {
+    use crate::schema::client_events;
+
+    let mut items = client_events::table
+        .filter(client_events::client_id.eq(query.client_id.get()))
+        .into_boxed::<diesel::sqlite::Sqlite>();
+
+    if let Some(event_type) = &query.event_type {
+        items = items.filter(client_events::event_type.eq(event_type.to_string()));
+    }
+    items
+}
+
+/// Query loading a page of [`ClientEventReader::list_client_events`] results,
+/// newest first.
+pub fn client_events_page_query(
+    query: &ClientEventListQuery,
+) -> crate::schema::client_events::BoxedQuery<'_, diesel::sqlite::Sqlite> {
+    use crate::schema::client_events;
+
+    let mut items = client_events_query(query);
+
+    // Apply pagination if requested
+    if let Some(pagination) = &query.pagination {
+        let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
+        let limit = pagination.per_page as i64;
+        items = items.offset(offset).limit(limit);
+    }
+
+    items.order(client_events::created_at.desc())
+}
+
 impl ClientEventReader for DieselRepository {
     fn list_client_events(
         &self,
         query: ClientEventListQuery,
     ) -> RepositoryResult<(usize, Vec<(ClientEvent, Manager)>)> {
-        use crate::schema::{client_events, managers};
+        use crate::schema::managers;
         use std::collections::{HashMap, HashSet};
 
         let mut conn = self.conn()?;
 
-        let query_builder = || {
-            // Start with boxed query on clients
-            let mut items = client_events::table
-                .filter(client_events::client_id.eq(query.client_id.get()))
-                .into_boxed::<diesel::sqlite::Sqlite>();
-
-            if let Some(event_type) = &query.event_type {
-                items = items.filter(client_events::event_type.eq(event_type.to_string()));
-            }
-            items
-        };
-
         // Get the total count before applying pagination
-        let total = query_builder().count().get_result::<i64>(&mut conn)? as usize;
-
-        let mut items = query_builder();
-        // Apply pagination if requested
-        if let Some(pagination) = &query.pagination {
-            let offset = ((pagination.page.max(1) - 1) * pagination.per_page) as i64;
-            let limit = pagination.per_page as i64;
-            items = items.offset(offset).limit(limit);
-        }
+        let total = client_events_query(&query)
+            .count()
+            .get_result::<i64>(&mut conn)? as usize;
 
         // --- 4. Load the events ---
-        let db_events = items
-            .order(client_events::created_at.desc())
-            .load::<DbClientEvent>(&mut conn)?;
+        let db_events = client_events_page_query(&query).load::<DbClientEvent>(&mut conn)?;
 
         // --- 5. Load the managers using IN clause ---
         let manager_ids: Vec<i32> = db_events.iter().map(|e| e.manager_id).collect();
@@ -90,6 +107,10 @@ impl ClientEventReader for DieselRepository {
         Ok((total, combined))
     }
 
+    /// Checks for an identical event with a single bounded `EXISTS` probe.
+    ///
+    /// The lookup is served by the `(client_id, manager_id, event_type)` index, so
+    /// only events of the same type written by the same manager are compared.
     fn client_event_exists(&self, event: &NewClientEvent) -> RepositoryResult<bool> {
         use crate::schema::client_events;
 
@@ -106,6 +127,34 @@ impl ClientEventReader for DieselRepository {
 
         Ok(exists)
     }
+
+    /// Same probe as [`Self::client_event_exists`], limited to events newer than
+    /// `now - window` so a message re-sent much later is recorded again.
+    fn client_event_exists_within_window(
+        &self,
+        event: &NewClientEvent,
+        window: Duration,
+    ) -> RepositoryResult<bool> {
+        use crate::schema::client_events;
+
+        let mut conn = self.conn()?;
+        let db_event: DbNewClientEvent = event.into();
+        let cutoff = Utc::now()
+            .naive_utc()
+            .checked_sub_signed(window)
+            .unwrap_or(NaiveDateTime::MIN);
+
+        let query = client_events::table
+            .filter(client_events::client_id.eq(db_event.client_id))
+            .filter(client_events::manager_id.eq(db_event.manager_id))
+            .filter(client_events::event_type.eq(db_event.event_type))
+            .filter(client_events::event_data.eq(db_event.event_data))
+            .filter(client_events::created_at.gt(cutoff));
+
+        let exists = select(exists(query)).get_result::<bool>(&mut conn)?;
+
+        Ok(exists)
+    }
 }
 
 impl ClientEventWriter for DieselRepository {
@@ -124,4 +173,27 @@ impl ClientEventWriter for DieselRepository {
             .try_into()
             .map_err(|err: TypeConstraintError| RepositoryError::ValidationError(err.to_string()))
     }
+
+    fn delete_events_older_than(
+        &self,
+        hub_id: HubId,
+        cutoff: NaiveDateTime,
+    ) -> RepositoryResult<usize> {
+        use crate::schema::{client_events, clients};
+
+        let mut conn = self.conn()?;
+
+        let hub_clients = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .select(clients::id);
+
+        let deleted = diesel::delete(
+            client_events::table
+                .filter(client_events::client_id.eq_any(hub_clients))
+                .filter(client_events::created_at.lt(cutoff)),
+        )
+        .execute(&mut conn)?;
+
+        Ok(deleted)
+    }
 }
@@ -0,0 +1,54 @@
+//! Hub contact policy repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::RepositoryResult;
+
+use crate::domain::contact_policy::ContactPolicy;
+use crate::domain::types::HubId;
+use crate::repository::{ContactPolicyReader, ContactPolicyWriter, DieselRepository};
+
+impl ContactPolicyReader for DieselRepository {
+    fn get_contact_policy(&self, hub_id: HubId) -> RepositoryResult<ContactPolicy> {
+        use crate::schema::hub_contact_policies;
+
+        let mut conn = self.conn()?;
+        let policy = hub_contact_policies::table
+            .find(hub_id.get())
+            .select((
+                hub_contact_policies::require_email,
+                hub_contact_policies::require_phone,
+            ))
+            .first::<(bool, bool)>(&mut conn)
+            .optional()?
+            .map(|(require_email, require_phone)| ContactPolicy {
+                require_email,
+                require_phone,
+            })
+            .unwrap_or_default();
+
+        Ok(policy)
+    }
+}
+
+impl ContactPolicyWriter for DieselRepository {
+    fn save_contact_policy(&self, hub_id: HubId, policy: &ContactPolicy) -> RepositoryResult<()> {
+        use crate::schema::hub_contact_policies;
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(hub_contact_policies::table)
+            .values((
+                hub_contact_policies::hub_id.eq(hub_id.get()),
+                hub_contact_policies::require_email.eq(policy.require_email),
+                hub_contact_policies::require_phone.eq(policy.require_phone),
+            ))
+            .on_conflict(hub_contact_policies::hub_id)
+            .do_update()
+            .set((
+                hub_contact_policies::require_email.eq(policy.require_email),
+                hub_contact_policies::require_phone.eq(policy.require_phone),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+//! Repository errors that services must tell apart from generic failures.
+
+use pushkind_common::repository::errors::RepositoryError;
+use thiserror::Error;
+
+/// Failure of a write to the `clients` table.
+#[derive(Debug, Error)]
+pub enum ClientWriteError {
+    /// The write would leave a hub with more clients than its
+    /// `hub_limits.max_clients`; the whole batch was rolled back.
+    #[error("hub {hub_id} client limit of {max_clients} exceeded")]
+    LimitExceeded { hub_id: i32, max_clients: i32 },
+    /// [`ImportConflictStrategy::Error`] met a client that already exists in
+    /// its hub; the whole batch was rolled back.
+    ///
+    /// [`ImportConflictStrategy::Error`]: crate::domain::types::ImportConflictStrategy::Error
+    #[error("client {name} already exists in hub {hub_id}")]
+    ClientExists { hub_id: i32, name: String },
+    /// An update was based on `expected_version`, but the client has been
+    /// modified since; nothing was written.
+    #[error("client {client_id} was modified since version {expected_version}")]
+    StaleVersion {
+        client_id: i32,
+        expected_version: i32,
+    },
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl From<diesel::result::Error> for ClientWriteError {
+    fn from(err: diesel::result::Error) -> Self {
+        Self::Repository(err.into())
+    }
+}
+
+pub type ClientWriteResult<T> = Result<T, ClientWriteError>;
@@ -0,0 +1,104 @@
+//! FTS5 `MATCH` expressions for client search terms.
+
+use crate::repository::FtsOperator;
+
+/// Turns a user search term into an FTS5 `MATCH` expression.
+///
+/// Every whitespace-separated word becomes a quoted prefix query (`"word"*`),
+/// so FTS5 syntax in the term (`"`, `*`, `-`, `:`, parentheses, `AND`/`OR`/`NOT`)
+/// is matched literally instead of being parsed. Words without a letter or
+/// digit carry nothing to match and are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FtsQueryBuilder {
+    term: String,
+    operator: FtsOperator,
+}
+
+impl FtsQueryBuilder {
+    /// Creates a builder joining the words of `term` with `operator`.
+    pub fn new(term: impl Into<String>, operator: FtsOperator) -> Self {
+        Self {
+            term: term.into(),
+            operator,
+        }
+    }
+
+    /// Returns the `MATCH` expression, or `None` when the term has no
+    /// searchable words.
+    pub fn to_match_expression(&self) -> Option<String> {
+        let words: Vec<String> = self
+            .term
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .map(quote_word)
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        let separator = match self.operator {
+            FtsOperator::And => " ",
+            FtsOperator::Or => " OR ",
+        };
+        Some(words.join(separator))
+    }
+}
+
+/// Quotes `word` as an FTS5 string, doubling embedded quotes and dropping
+/// control characters.
+fn quote_word(word: &str) -> String {
+    let escaped = word
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .collect::<String>()
+        .replace('"', "\"\"");
+    format!("\"{escaped}\"*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn and(term: &str) -> Option<String> {
+        FtsQueryBuilder::new(term, FtsOperator::And).to_match_expression()
+    }
+
+    #[test]
+    fn blank_terms_have_no_expression() {
+        assert_eq!(and(""), None);
+        assert_eq!(and("  \t "), None);
+        assert_eq!(and("- * \""), None);
+    }
+
+    #[test]
+    fn single_word_becomes_a_quoted_prefix_query() {
+        assert_eq!(and("Alice").as_deref(), Some("\"Alice\"*"));
+        assert_eq!(and("  Alice ").as_deref(), Some("\"Alice\"*"));
+    }
+
+    #[test]
+    fn words_are_joined_with_the_operator() {
+        assert_eq!(
+            and("Acme  Trading").as_deref(),
+            Some("\"Acme\"* \"Trading\"*")
+        );
+        assert_eq!(
+            FtsQueryBuilder::new("Acme Trading", FtsOperator::Or)
+                .to_match_expression()
+                .as_deref(),
+            Some("\"Acme\"* OR \"Trading\"*")
+        );
+    }
+
+    #[test]
+    fn fts5_special_characters_are_quoted() {
+        assert_eq!(and("say\"hi").as_deref(), Some("\"say\"\"hi\"*"));
+        assert_eq!(and("acme*").as_deref(), Some("\"acme*\"*"));
+        assert_eq!(and("-spam").as_deref(), Some("\"-spam\"*"));
+        assert_eq!(
+            and("NOT name:(bob)").as_deref(),
+            Some("\"NOT\"* \"name:(bob)\"*")
+        );
+        assert_eq!(and("a\u{7}b").as_deref(), Some("\"ab\"*"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Hub settings repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::hub_settings::HubSettings;
+use crate::domain::types::HubId;
+use crate::models::hub_settings::HubSettings as DbHubSettings;
+use crate::repository::{DieselRepository, HubSettingsReader, HubSettingsWriter};
+
+impl HubSettingsReader for DieselRepository {
+    fn get_hub_settings(&self, hub_id: HubId) -> RepositoryResult<HubSettings> {
+        use crate::schema::hub_settings;
+
+        let mut conn = self.conn()?;
+        hub_settings::table
+            .find(hub_id.get())
+            .first::<DbHubSettings>(&mut conn)
+            .optional()?
+            .map_or_else(
+                || Ok(HubSettings::defaults(hub_id)),
+                |settings| HubSettings::try_from(settings).map_err(RepositoryError::from),
+            )
+    }
+
+    fn list_hub_settings_with_event_retention(&self) -> RepositoryResult<Vec<HubSettings>> {
+        use crate::schema::hub_settings;
+
+        let mut conn = self.conn()?;
+        hub_settings::table
+            .filter(hub_settings::event_retention_days.is_not_null())
+            .order(hub_settings::hub_id.asc())
+            .load::<DbHubSettings>(&mut conn)?
+            .into_iter()
+            .map(|settings| HubSettings::try_from(settings).map_err(RepositoryError::from))
+            .collect()
+    }
+}
+
+impl HubSettingsWriter for DieselRepository {
+    fn save_hub_settings(&self, settings: &HubSettings) -> RepositoryResult<HubSettings> {
+        use crate::schema::hub_settings;
+
+        let mut conn = self.conn()?;
+        let db_settings = DbHubSettings::from(settings);
+        let saved = diesel::insert_into(hub_settings::table)
+            .values(&db_settings)
+            .on_conflict(hub_settings::hub_id)
+            .do_update()
+            .set(&db_settings)
+            .get_result::<DbHubSettings>(&mut conn)?;
+
+        HubSettings::try_from(saved).map_err(RepositoryError::from)
+    }
+
+    fn set_hub_client_limit(
+        &self,
+        hub_id: HubId,
+        max_clients: Option<i32>,
+    ) -> RepositoryResult<()> {
+        use crate::schema::hub_limits;
+
+        let mut conn = self.conn()?;
+        match max_clients {
+            Some(max_clients) => {
+                diesel::insert_into(hub_limits::table)
+                    .values((
+                        hub_limits::hub_id.eq(hub_id.get()),
+                        hub_limits::max_clients.eq(max_clients),
+                    ))
+                    .on_conflict(hub_limits::hub_id)
+                    .do_update()
+                    .set(hub_limits::max_clients.eq(max_clients))
+                    .execute(&mut conn)?;
+            }
+            None => {
+                diesel::delete(hub_limits::table.find(hub_id.get())).execute(&mut conn)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Aggregate hub statistics backing the admin dashboard and service metrics.
+
+use chrono::NaiveDateTime;
+use diesel::dsl::{count_distinct, count_star, exists, not};
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::manager::Manager;
+use crate::domain::types::HubId;
+use crate::models::manager::Manager as DbManager;
+use crate::repository::{DieselRepository, HubStatsReader, ServiceStats, ServiceStatsReader};
+
+impl HubStatsReader for DieselRepository {
+    fn count_clients(&self, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::clients;
+
+        let mut conn = self.conn()?;
+        let total = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
+    }
+
+    fn count_active_clients(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize> {
+        use crate::schema::{client_events, clients};
+
+        let mut conn = self.conn()?;
+        let total = client_events::table
+            .inner_join(clients::table)
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(client_events::created_at.ge(since))
+            .select(count_distinct(client_events::client_id))
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
+    }
+
+    fn count_managers(&self, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::managers;
+
+        let mut conn = self.conn()?;
+        let total = managers::table
+            .filter(managers::hub_id.eq(hub_id.get()))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
+    }
+
+    fn count_client_events(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize> {
+        use crate::schema::{client_events, clients};
+
+        let mut conn = self.conn()?;
+        let total = client_events::table
+            .inner_join(clients::table)
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(client_events::created_at.ge(since))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
+    }
+
+    fn top_manager_by_events(
+        &self,
+        hub_id: HubId,
+        since: NaiveDateTime,
+    ) -> RepositoryResult<Option<Manager>> {
+        use crate::schema::{client_events, clients, managers};
+
+        let mut conn = self.conn()?;
+        let top = client_events::table
+            .inner_join(clients::table)
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(client_events::created_at.ge(since))
+            .group_by(client_events::manager_id)
+            .select((client_events::manager_id, count_star()))
+            .order((count_star().desc(), client_events::manager_id.asc()))
+            .first::<(i32, i64)>(&mut conn)
+            .optional()?;
+
+        let Some((manager_id, _)) = top else {
+            return Ok(None);
+        };
+
+        managers::table
+            .filter(managers::id.eq(manager_id))
+            .filter(managers::hub_id.eq(hub_id.get()))
+            .first::<DbManager>(&mut conn)
+            .optional()?
+            .map(|db_manager| Manager::try_from(db_manager).map_err(RepositoryError::from))
+            .transpose()
+    }
+
+    fn count_unassigned_clients(&self, hub_id: HubId) -> RepositoryResult<usize> {
+        use crate::schema::{client_manager, clients};
+
+        let mut conn = self.conn()?;
+        let total = clients::table
+            .filter(clients::hub_id.eq(hub_id.get()))
+            .filter(not(exists(
+                client_manager::table.filter(client_manager::client_id.eq(clients::id)),
+            )))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(total as usize)
+    }
+}
+
+impl ServiceStatsReader for DieselRepository {
+    fn load_service_stats(&self, since: NaiveDateTime) -> RepositoryResult<ServiceStats> {
+        use crate::schema::{client_events, clients, managers};
+
+        let mut conn = self.conn()?;
+        let total_clients = clients::table.count().get_result::<i64>(&mut conn)?;
+        let total_managers = managers::table.count().get_result::<i64>(&mut conn)?;
+        let recent_events = client_events::table
+            .filter(client_events::created_at.ge(since))
+            .count()
+            .get_result::<i64>(&mut conn)?;
+
+        Ok(ServiceStats {
+            total_clients: total_clients as usize,
+            total_managers: total_managers as usize,
+            recent_events: recent_events as usize,
+        })
+    }
+}
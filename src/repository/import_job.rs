@@ -0,0 +1,61 @@
+//! Import job repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::import_job::{ImportJob, ImportJobStatus};
+use crate::domain::types::{HubId, ImportJobId};
+use crate::models::import_job::{
+    ImportJob as DbImportJob, NewImportJob as DbNewImportJob, UpdateImportJob as DbUpdateImportJob,
+};
+use crate::repository::{DieselRepository, ImportJobReader, ImportJobWriter};
+
+impl ImportJobReader for DieselRepository {
+    fn get_import_job(
+        &self,
+        job_id: ImportJobId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<ImportJob>> {
+        use crate::schema::import_jobs;
+
+        let mut conn = self.conn()?;
+        import_jobs::table
+            .filter(import_jobs::id.eq(job_id.get()))
+            .filter(import_jobs::hub_id.eq(hub_id.get()))
+            .first::<DbImportJob>(&mut conn)
+            .optional()?
+            .map(|job| ImportJob::try_from(job).map_err(RepositoryError::from))
+            .transpose()
+    }
+}
+
+impl ImportJobWriter for DieselRepository {
+    fn create_import_job(&self, hub_id: HubId) -> RepositoryResult<ImportJob> {
+        use crate::schema::import_jobs;
+
+        let mut conn = self.conn()?;
+        let db_job = diesel::insert_into(import_jobs::table)
+            .values(&DbNewImportJob {
+                hub_id: hub_id.get(),
+                status: ImportJobStatus::Pending.as_str(),
+            })
+            .get_result::<DbImportJob>(&mut conn)?;
+
+        ImportJob::try_from(db_job).map_err(RepositoryError::from)
+    }
+
+    fn save_import_job(&self, job: &ImportJob) -> RepositoryResult<ImportJob> {
+        use crate::schema::import_jobs;
+
+        let mut conn = self.conn()?;
+        let db_job = diesel::update(
+            import_jobs::table
+                .filter(import_jobs::id.eq(job.id.get()))
+                .filter(import_jobs::hub_id.eq(job.hub_id.get())),
+        )
+        .set(&DbUpdateImportJob::from(job))
+        .get_result::<DbImportJob>(&mut conn)?;
+
+        ImportJob::try_from(db_job).map_err(RepositoryError::from)
+    }
+}
@@ -1,52 +1,137 @@
 //! Repository implementation for CRM managers.
 
-use diesel::{Connection, prelude::*, upsert::excluded};
+use diesel::{Connection, dsl::case_when, prelude::*, sqlite::SqliteConnection, upsert::excluded};
 use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+use serde_json::Value;
 
 use crate::{
     domain::{
-        client::Client,
+        client::{Client, ClientStatus},
+        client_event::{ClientEvent, ClientEventType, NewClientEvent},
         manager::{Manager, NewClientManager, NewManager},
-        types::{ClientId, HubId, ManagerEmail, ManagerId},
+        types::{ClientId, HubId, ManagerEmail, ManagerId, ManagerName, PhoneNumber},
     },
     models::{
         client::Client as DbClient,
+        client_event::{ClientEvent as DbClientEvent, NewClientEvent as DbNewClientEvent},
         manager::{
-            Manager as DbManager, NewClientManager as DbNewClientManager,
-            NewManager as DbNewManager,
+            Manager as DbManager, ManagerContactDetails as DbManagerContactDetails,
+            NewClientManager as DbNewClientManager, NewManager as DbNewManager,
         },
     },
-    repository::{DieselRepository, ManagerReader, ManagerWriter},
+    repository::{DieselRepository, ManagerReader, ManagerSort, ManagerWriter},
 };
 
+/// Moves every hub client assignment of `from_id` to `to_id`, returning the
+/// number of clients that changed hands.
+///
+/// Clients already assigned to the target would violate the
+/// `(client_id, manager_id)` key, so they only drop the source row.
+fn move_client_assignments(
+    conn: &mut SqliteConnection,
+    from_id: ManagerId,
+    to_id: ManagerId,
+    hub_id: HubId,
+) -> QueryResult<usize> {
+    use crate::schema::{client_manager, clients};
+
+    let hub_clients = clients::table
+        .filter(clients::hub_id.eq(hub_id.get()))
+        .select(clients::id);
+    let target_clients = client_manager::table
+        .filter(client_manager::manager_id.eq(to_id.get()))
+        .select(client_manager::client_id);
+
+    let merged = diesel::delete(
+        client_manager::table
+            .filter(client_manager::manager_id.eq(from_id.get()))
+            .filter(client_manager::client_id.eq_any(hub_clients))
+            .filter(client_manager::client_id.eq_any(target_clients)),
+    )
+    .execute(conn)?;
+
+    let hub_clients = clients::table
+        .filter(clients::hub_id.eq(hub_id.get()))
+        .select(clients::id);
+
+    let moved = diesel::update(
+        client_manager::table
+            .filter(client_manager::manager_id.eq(from_id.get()))
+            .filter(client_manager::client_id.eq_any(hub_clients)),
+    )
+    .set(client_manager::manager_id.eq(to_id.get()))
+    .execute(conn)?;
+
+    Ok(merged + moved)
+}
+
+/// Inserts the manager or updates the one with the same hub and email.
+///
+/// Locked names are kept, and `None` company or phone keeps the stored value.
+fn upsert_manager(
+    conn: &mut SqliteConnection,
+    db_new_manager: &DbNewManager,
+) -> QueryResult<DbManager> {
+    use crate::schema::managers;
+
+    diesel::insert_into(managers::table)
+        .values(db_new_manager)
+        .on_conflict((managers::email, managers::hub_id))
+        .do_update()
+        .set((
+            // Names the manager set on their profile survive login syncs.
+            managers::name.eq(case_when(managers::name_locked, managers::name)
+                .otherwise(excluded(managers::name))),
+            managers::is_user.eq(managers::is_user.or(excluded(managers::is_user))),
+            // Upserts without contact details (e.g. login sync) keep stored values.
+            managers::company.eq(case_when(
+                excluded(managers::company).is_not_null(),
+                excluded(managers::company),
+            )
+            .otherwise(managers::company)),
+            managers::phone.eq(case_when(
+                excluded(managers::phone).is_not_null(),
+                excluded(managers::phone),
+            )
+            .otherwise(managers::phone)),
+        ))
+        .get_result::<DbManager>(conn)
+}
+
 impl ManagerWriter for DieselRepository {
     fn create_or_update_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager> {
-        use crate::schema::managers;
-
         let mut conn = self.conn()?;
 
         let db_new_manager: DbNewManager = new_manager.into();
-
-        let db_manager = diesel::insert_into(managers::table)
-            .values(&db_new_manager)
-            .on_conflict((managers::email, managers::hub_id))
-            .do_update()
-            .set((
-                managers::name.eq(excluded(managers::name)),
-                managers::is_user.eq(managers::is_user.or(excluded(managers::is_user))),
-            ))
-            .get_result::<DbManager>(&mut conn)?;
+        let db_manager = upsert_manager(&mut conn, &db_new_manager)?;
 
         let manager = Manager::try_from(db_manager).map_err(RepositoryError::from)?;
         Ok(manager)
     }
 
+    fn save_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager> {
+        use crate::schema::managers;
+
+        let mut conn = self.conn()?;
+
+        let db_new_manager: DbNewManager = new_manager.into();
+        let contact_details: DbManagerContactDetails = new_manager.into();
+        let db_manager = conn.transaction::<DbManager, diesel::result::Error, _>(|conn| {
+            let manager = upsert_manager(conn, &db_new_manager)?;
+            diesel::update(managers::table.find(manager.id))
+                .set(&contact_details)
+                .get_result::<DbManager>(conn)
+        })?;
+
+        Manager::try_from(db_manager).map_err(RepositoryError::from)
+    }
+
     fn assign_clients_to_manager(
         &self,
         manager_id: ManagerId,
         client_ids: &[ClientId],
     ) -> RepositoryResult<usize> {
-        use crate::schema::client_manager;
+        use crate::schema::{client_manager, clients};
 
         let mut conn = self.conn()?;
 
@@ -58,12 +143,19 @@ impl ManagerWriter for DieselRepository {
             .collect::<Vec<_>>();
 
         conn.transaction::<usize, diesel::result::Error, _>(move |conn| {
+            // Archived clients are not offered in the manager modal, so their
+            // assignments are kept rather than dropped by every save.
+            let active_clients = clients::table
+                .filter(clients::status.ne(ClientStatus::Archived.as_str()))
+                .select(clients::id);
             diesel::delete(
-                client_manager::table.filter(client_manager::manager_id.eq(manager_id.get())),
+                client_manager::table
+                    .filter(client_manager::manager_id.eq(manager_id.get()))
+                    .filter(client_manager::client_id.eq_any(active_clients)),
             )
             .execute(conn)?;
 
-            let result = diesel::insert_into(client_manager::table)
+            let result = diesel::insert_or_ignore_into(client_manager::table)
                 .values(db_client_manager)
                 .execute(conn)?;
 
@@ -71,6 +163,207 @@ impl ManagerWriter for DieselRepository {
         })
         .map_err(RepositoryError::from)
     }
+
+    fn add_clients_to_manager(
+        &self,
+        manager_id: ManagerId,
+        client_ids: &[ClientId],
+    ) -> RepositoryResult<usize> {
+        use crate::schema::client_manager;
+
+        let mut conn = self.conn()?;
+
+        let db_client_manager = client_ids
+            .iter()
+            .map(|client_id| {
+                DbNewClientManager::from(NewClientManager::new(*client_id, manager_id))
+            })
+            .collect::<Vec<_>>();
+
+        // Existing `(client_id, manager_id)` rows are kept as they are.
+        let added = diesel::insert_into(client_manager::table)
+            .values(db_client_manager)
+            .on_conflict_do_nothing()
+            .execute(&mut conn)?;
+
+        Ok(added)
+    }
+
+    fn reassign_all_clients(
+        &self,
+        from_id: ManagerId,
+        to_id: ManagerId,
+        hub_id: HubId,
+        event_type: &ClientEventType,
+        event_data: &Value,
+    ) -> RepositoryResult<Vec<ClientEvent>> {
+        use crate::schema::{client_events, client_manager, clients};
+
+        let mut conn = self.conn()?;
+
+        conn.transaction::<Vec<ClientEvent>, RepositoryError, _>(|conn| {
+            let hub_clients = clients::table
+                .filter(clients::hub_id.eq(hub_id.get()))
+                .select(clients::id);
+            let client_ids = client_manager::table
+                .filter(client_manager::manager_id.eq(from_id.get()))
+                .filter(client_manager::client_id.eq_any(hub_clients))
+                .select(client_manager::client_id)
+                .order(client_manager::client_id.asc())
+                .load::<i32>(conn)?;
+
+            move_client_assignments(conn, from_id, to_id, hub_id)?;
+
+            client_ids
+                .into_iter()
+                .map(|client_id| {
+                    let event = NewClientEvent::new(
+                        ClientId::try_from(client_id)?,
+                        to_id,
+                        event_type.clone(),
+                        event_data.clone(),
+                    );
+                    let db_event = diesel::insert_into(client_events::table)
+                        .values(&DbNewClientEvent::from(&event))
+                        .get_result::<DbClientEvent>(conn)?;
+                    ClientEvent::try_from(db_event).map_err(RepositoryError::from)
+                })
+                .collect()
+        })
+    }
+
+    fn set_manager_active(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        is_active: bool,
+    ) -> RepositoryResult<Manager> {
+        use crate::schema::managers;
+
+        let mut conn = self.conn()?;
+
+        let db_manager = diesel::update(
+            managers::table
+                .filter(managers::id.eq(manager_id.get()))
+                .filter(managers::hub_id.eq(hub_id.get())),
+        )
+        .set(managers::is_active.eq(is_active))
+        .get_result::<DbManager>(&mut conn)
+        .optional()?
+        .ok_or(RepositoryError::NotFound)?;
+
+        Manager::try_from(db_manager).map_err(RepositoryError::from)
+    }
+
+    fn update_manager_profile(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        name: &ManagerName,
+        phone: Option<PhoneNumber>,
+    ) -> RepositoryResult<Manager> {
+        use crate::schema::managers;
+
+        let mut conn = self.conn()?;
+
+        let db_manager = diesel::update(
+            managers::table
+                .filter(managers::id.eq(manager_id.get()))
+                .filter(managers::hub_id.eq(hub_id.get())),
+        )
+        .set((
+            managers::name.eq(name.as_str()),
+            managers::phone.eq(phone.as_ref().map(|phone| phone.as_str())),
+            managers::name_locked.eq(true),
+        ))
+        .get_result::<DbManager>(&mut conn)
+        .optional()?
+        .ok_or(RepositoryError::NotFound)?;
+
+        Manager::try_from(db_manager).map_err(RepositoryError::from)
+    }
+
+    fn get_or_create_system_manager(&self, hub_id: HubId) -> RepositoryResult<Manager> {
+        let mut conn = self.conn()?;
+        let system = NewManager::system(hub_id).map_err(RepositoryError::from)?;
+
+        let db_manager = upsert_system_manager(&mut conn, &system)?;
+
+        Manager::try_from(db_manager).map_err(RepositoryError::from)
+    }
+
+    fn delete_manager(&self, manager_id: ManagerId, hub_id: HubId) -> RepositoryResult<()> {
+        use crate::schema::{client_events, client_manager, client_notes, clients, managers};
+
+        let mut conn = self.conn()?;
+        let system = NewManager::system(hub_id).map_err(RepositoryError::from)?;
+
+        conn.transaction::<(), RepositoryError, _>(|conn| {
+            let manager_email = managers::table
+                .filter(managers::id.eq(manager_id.get()))
+                .filter(managers::hub_id.eq(hub_id.get()))
+                .select(managers::email)
+                .first::<String>(conn)
+                .optional()?
+                .ok_or(RepositoryError::NotFound)?;
+
+            if manager_email == system.email {
+                return Err(RepositoryError::ConstraintViolation(format!(
+                    "hub {hub_id} system manager cannot be deleted"
+                )));
+            }
+
+            let system_id = upsert_system_manager(conn, &system)?.id;
+
+            diesel::update(
+                client_events::table.filter(client_events::manager_id.eq(manager_id.get())),
+            )
+            .set(client_events::manager_id.eq(system_id))
+            .execute(conn)?;
+
+            diesel::update(
+                client_notes::table.filter(client_notes::manager_id.eq(manager_id.get())),
+            )
+            .set(client_notes::manager_id.eq(system_id))
+            .execute(conn)?;
+
+            diesel::update(clients::table.filter(clients::last_edited_by.eq(manager_id.get())))
+                .set(clients::last_edited_by.eq(None::<i32>))
+                .execute(conn)?;
+
+            diesel::delete(
+                client_manager::table.filter(client_manager::manager_id.eq(manager_id.get())),
+            )
+            .execute(conn)?;
+
+            diesel::delete(managers::table.filter(managers::id.eq(manager_id.get())))
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Inserts the hub's system manager unless it exists and returns the stored row.
+fn upsert_system_manager(
+    conn: &mut SqliteConnection,
+    system: &NewManager,
+) -> QueryResult<DbManager> {
+    use crate::schema::managers;
+
+    let db_new_manager: DbNewManager = system.into();
+
+    diesel::insert_into(managers::table)
+        .values(&db_new_manager)
+        .on_conflict((managers::email, managers::hub_id))
+        .do_nothing()
+        .execute(conn)?;
+
+    managers::table
+        .filter(managers::hub_id.eq(system.hub_id.get()))
+        .filter(managers::email.eq(system.email.as_str()))
+        .select(DbManager::as_select())
+        .first(conn)
 }
 
 impl ManagerReader for DieselRepository {
@@ -81,6 +374,7 @@ impl ManagerReader for DieselRepository {
         let db_manager = managers::table
             .filter(managers::id.eq(id.get()))
             .filter(managers::hub_id.eq(hub_id.get()))
+            .select(DbManager::as_select())
             .first::<DbManager>(&mut conn)
             .optional()?;
 
@@ -103,6 +397,7 @@ impl ManagerReader for DieselRepository {
         let db_manager = managers::table
             .filter(managers::email.eq(email.as_str()))
             .filter(managers::hub_id.eq(hub_id.get()))
+            .select(DbManager::as_select())
             .first::<DbManager>(&mut conn)
             .optional()?;
 
@@ -114,9 +409,35 @@ impl ManagerReader for DieselRepository {
         }
     }
 
+    fn find_managers_by_name(&self, name: &str, hub_id: HubId) -> RepositoryResult<Vec<Manager>> {
+        use crate::schema::managers;
+
+        let name = name.trim().to_lowercase();
+
+        let mut conn = self.conn()?;
+        // SQLite's `lower` only folds ASCII, so names are compared here.
+        let db_managers = managers::table
+            .filter(managers::hub_id.eq(hub_id.get()))
+            .filter(managers::is_user.eq(true))
+            .filter(managers::is_active.eq(true))
+            .order(managers::id.asc())
+            .select(DbManager::as_select())
+            .load::<DbManager>(&mut conn)?;
+
+        db_managers
+            .into_iter()
+            .filter(|db_manager| {
+                let full_name = db_manager.name.to_lowercase();
+                full_name == name || full_name.split_whitespace().next() == Some(name.as_str())
+            })
+            .map(|db_manager| Manager::try_from(db_manager).map_err(RepositoryError::from))
+            .collect()
+    }
+
     fn list_managers_with_clients(
         &self,
         hub_id: HubId,
+        sort: ManagerSort,
     ) -> RepositoryResult<Vec<(Manager, Vec<Client>)>> {
         use crate::schema::client_manager;
         use crate::schema::clients;
@@ -126,6 +447,9 @@ impl ManagerReader for DieselRepository {
         let managers = managers::table
             .filter(managers::hub_id.eq(hub_id.get()))
             .filter(managers::is_user.eq(true))
+            .filter(managers::is_active.eq(true))
+            .order((managers::name.asc(), managers::id.asc()))
+            .select(DbManager::as_select())
             .load::<DbManager>(&mut conn)?;
 
         let managers_ids = managers
@@ -137,10 +461,10 @@ impl ManagerReader for DieselRepository {
             .inner_join(client_manager::table)
             .filter(client_manager::manager_id.eq_any(managers_ids))
             .filter(clients::hub_id.eq(hub_id.get()))
-            .select((client_manager::manager_id, clients::all_columns))
+            .select((client_manager::manager_id, DbClient::as_select()))
             .load::<(i32, DbClient)>(&mut conn)?;
 
-        let manager_with_clients = managers
+        let mut manager_with_clients = managers
             .into_iter()
             .map(|manager| {
                 let manager_clients = clients
@@ -156,6 +480,11 @@ impl ManagerReader for DieselRepository {
             })
             .collect::<Result<Vec<_>, RepositoryError>>()?;
 
+        if sort == ManagerSort::ClientCount {
+            // Stable sort keeps the name order for managers with equal counts.
+            manager_with_clients.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+        }
+
         Ok(manager_with_clients)
     }
 }
@@ -1,19 +1,43 @@
 //! Mock repository implementations for isolating services in tests.
 
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDateTime};
 use mockall::mock;
+use pushkind_common::pagination::Pagination;
 use pushkind_common::repository::errors::RepositoryResult;
 
-use crate::domain::client::{Client, NewClient, UpdateClient};
-use crate::domain::client_event::{ClientEvent, NewClientEvent};
+use crate::domain::api_key::{ApiKey, NewApiKey};
+use crate::domain::assignment_rule::{ManagerAssignmentRule, NewManagerAssignmentRule};
+use crate::domain::audit::{AuditEntry, NewAuditEntry};
+use crate::domain::client::{
+    Client, ClientStatus, ClientUpsertSummary, ClientWriteOutcome, FieldMeta, NewClient,
+    UpdateClient,
+};
+use crate::domain::client_event::{ClientEvent, ClientEventType, NewClientEvent};
+use crate::domain::contact_policy::ContactPolicy;
+use crate::domain::hub_settings::HubSettings;
+use crate::domain::import_job::ImportJob;
 use crate::domain::important_field::ImportantField;
 use crate::domain::manager::{Manager, NewManager};
+use crate::domain::note::{ClientNote, NewClientNote};
+use crate::domain::segment::{ClientSegment, NewClientSegment};
 use crate::domain::store_otp::{NewStoreOtp, StoreOtp};
-use crate::domain::types::{ClientEmail, ClientId, HubId, ManagerEmail, ManagerId, PhoneNumber};
+use crate::domain::types::{
+    ApiKeyId, ClientEmail, ClientId, ClientName, ClientNoteId, HubId, ImportConflictStrategy,
+    ImportJobId, ImportantFieldName, ManagerEmail, ManagerId, ManagerName, PhoneNumber, RuleId,
+    SegmentId, WebhookId,
+};
+use crate::domain::webhook::{NewWebhook, Webhook};
 use crate::repository::PublicId;
+use crate::repository::errors::ClientWriteResult;
 use crate::repository::{
-    ClientEventListQuery, ClientEventReader, ClientEventWriter, ClientListQuery, ClientReader,
-    ClientWriter, ImportantFieldReader, ImportantFieldWriter, ManagerReader, ManagerWriter,
-    StoreOtpRepository,
+    ApiKeyReader, ApiKeyWriter, AuditReader, AuditWriter, ClientEventListQuery, ClientEventReader,
+    ClientEventWriter, ClientListQuery, ClientReader, ClientWriter, ContactPolicyReader,
+    ContactPolicyWriter, HubSettingsReader, HubSettingsWriter, HubStatsReader, ImportJobReader,
+    ImportJobWriter, ImportantFieldReader, ImportantFieldWriter, ManagerReader, ManagerSort,
+    ManagerWriter, NoteReader, NoteWriter, RuleReader, RuleWriter, SegmentReader, SegmentWriter,
+    ServiceStats, ServiceStatsReader, StoreOtpRepository, WebhookReader, WebhookWriter,
 };
 
 mock! {
@@ -26,6 +50,7 @@ mock! {
             hub_id: HubId,
         ) -> RepositoryResult<Option<Client>>;
         fn get_client_by_id(&self, id: ClientId, hub_id: HubId) -> RepositoryResult<Option<Client>>;
+        fn get_clients_by_ids(&self, ids: &[ClientId], hub_id: HubId) -> RepositoryResult<Vec<Client>>;
         fn get_client_by_email(
             &self,
             email: &ClientEmail,
@@ -37,13 +62,39 @@ mock! {
             hub_id: HubId,
         ) -> RepositoryResult<Option<Client>>;
         fn list_clients(&self, query: ClientListQuery) -> RepositoryResult<(usize, Vec<Client>)>;
-        fn list_managers(&self, id: ClientId) -> RepositoryResult<Vec<Manager>>;
+        fn list_clients_edited_by(
+            &self,
+            manager_id: ManagerId,
+            hub_id: HubId,
+            limit: usize,
+        ) -> RepositoryResult<Vec<Client>>;
+        fn list_recent_clients(
+            &self,
+            hub_id: HubId,
+            manager_email: Option<ManagerEmail>,
+            limit: usize,
+        ) -> RepositoryResult<Vec<Client>>;
+        fn list_managers_for_client(
+            &self,
+            client_id: ClientId,
+            pagination: Option<Pagination>,
+        ) -> RepositoryResult<(usize, Vec<Manager>)>;
+        fn count_clients_by_name(
+            &self,
+            hub_id: HubId,
+            name: &ClientName,
+            except: Option<ClientId>,
+        ) -> RepositoryResult<usize>;
+        fn client_ids_by_name_key(
+            &self,
+            hub_id: HubId,
+        ) -> RepositoryResult<HashMap<String, Vec<ClientId>>>;
         fn check_client_assigned_to_manager(
             &self,
             client_id: ClientId,
             manager_email: &ManagerEmail,
         ) -> RepositoryResult<bool>;
-        fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<String>>;
+        fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<FieldMeta>>;
     }
 
     impl ManagerReader for Repository {
@@ -53,43 +104,159 @@ mock! {
             email: &ManagerEmail,
             hub_id: HubId,
         ) -> RepositoryResult<Option<Manager>>;
+        fn find_managers_by_name(&self, name: &str, hub_id: HubId) -> RepositoryResult<Vec<Manager>>;
         fn list_managers_with_clients(
             &self,
             hub_id: HubId,
+            sort: ManagerSort,
         ) -> RepositoryResult<Vec<(Manager, Vec<Client>)>>;
     }
 
+    impl ContactPolicyReader for Repository {
+        fn get_contact_policy(&self, hub_id: HubId) -> RepositoryResult<ContactPolicy>;
+    }
+
+    impl ContactPolicyWriter for Repository {
+        fn save_contact_policy(&self, hub_id: HubId, policy: &ContactPolicy) -> RepositoryResult<()>;
+    }
+
+    impl HubSettingsReader for Repository {
+        fn get_hub_settings(&self, hub_id: HubId) -> RepositoryResult<HubSettings>;
+        fn list_hub_settings_with_event_retention(&self) -> RepositoryResult<Vec<HubSettings>>;
+    }
+
+    impl HubSettingsWriter for Repository {
+        fn save_hub_settings(&self, settings: &HubSettings) -> RepositoryResult<HubSettings>;
+        fn set_hub_client_limit(&self, hub_id: HubId, max_clients: Option<i32>) -> RepositoryResult<()>;
+    }
+
+    impl HubStatsReader for Repository {
+        fn count_clients(&self, hub_id: HubId) -> RepositoryResult<usize>;
+        fn count_active_clients(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize>;
+        fn count_managers(&self, hub_id: HubId) -> RepositoryResult<usize>;
+        fn count_client_events(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize>;
+        fn top_manager_by_events(
+            &self,
+            hub_id: HubId,
+            since: NaiveDateTime,
+        ) -> RepositoryResult<Option<Manager>>;
+        fn count_unassigned_clients(&self, hub_id: HubId) -> RepositoryResult<usize>;
+    }
+
+    impl ServiceStatsReader for Repository {
+        fn load_service_stats(&self, since: NaiveDateTime) -> RepositoryResult<ServiceStats>;
+    }
+
     impl ImportantFieldReader for Repository {
         fn list_important_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<ImportantField>>;
     }
 
+    impl AuditReader for Repository {
+        fn list_audit_entries(
+            &self,
+            hub_id: HubId,
+            entity_type: &str,
+            entity_id: i32,
+        ) -> RepositoryResult<Vec<AuditEntry>>;
+    }
+
+    impl AuditWriter for Repository {
+        fn record_audit(&self, entry: &NewAuditEntry) -> RepositoryResult<()>;
+    }
+
     impl ClientEventReader for Repository {
         fn list_client_events(
             &self,
             query: ClientEventListQuery,
         ) -> RepositoryResult<(usize, Vec<(ClientEvent, Manager)>)>;
         fn client_event_exists(&self, event: &NewClientEvent) -> RepositoryResult<bool>;
+        fn client_event_exists_within_window(
+            &self,
+            event: &NewClientEvent,
+            window: Duration,
+        ) -> RepositoryResult<bool>;
     }
 
     impl ClientWriter for Repository {
-        fn create_or_replace_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize>;
-        fn create_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize>;
+        fn create_or_replace_clients(
+            &self,
+            new_clients: &[NewClient],
+        ) -> ClientWriteResult<ClientUpsertSummary>;
+        fn upsert_clients(
+            &self,
+            new_clients: &[NewClient],
+        ) -> ClientWriteResult<Vec<ClientWriteOutcome>>;
+        fn create_clients(
+            &self,
+            new_clients: &[NewClient],
+            strategy: ImportConflictStrategy,
+        ) -> ClientWriteResult<ClientUpsertSummary>;
+        fn import_clients(
+            &self,
+            new_clients: &[NewClient],
+            name_matches: &[(ClientId, NewClient)],
+            strategy: ImportConflictStrategy,
+            actor: &ManagerEmail,
+            on_chunk: &dyn Fn(ClientUpsertSummary),
+        ) -> ClientWriteResult<ClientUpsertSummary>;
         fn update_client(
             &self,
             client_id: ClientId,
             updates: &UpdateClient,
+            actor: &ManagerEmail,
+        ) -> ClientWriteResult<Client>;
+        fn replace_client_fields(
+            &self,
+            client_id: ClientId,
+            fields: &BTreeMap<String, String>,
+            actor: &ManagerEmail,
         ) -> RepositoryResult<Client>;
-        fn delete_client(&self, client_id: ClientId) -> RepositoryResult<()>;
+        fn delete_client(&self, client_id: ClientId, actor: &ManagerEmail) -> RepositoryResult<()>;
         fn delete_all_clients(&self, hub_id: HubId) -> RepositoryResult<()>;
+        fn set_client_status(
+            &self,
+            client_id: ClientId,
+            hub_id: HubId,
+            status: ClientStatus,
+        ) -> RepositoryResult<()>;
     }
 
     impl ManagerWriter for Repository {
         fn create_or_update_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager>;
+        fn save_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager>;
         fn assign_clients_to_manager(
             &self,
             manager_id: ManagerId,
             client_ids: &[ClientId],
         ) -> RepositoryResult<usize>;
+        fn add_clients_to_manager(
+            &self,
+            manager_id: ManagerId,
+            client_ids: &[ClientId],
+        ) -> RepositoryResult<usize>;
+        fn reassign_all_clients(
+            &self,
+            from_id: ManagerId,
+            to_id: ManagerId,
+            hub_id: HubId,
+            event_type: &ClientEventType,
+            event_data: &serde_json::Value,
+        ) -> RepositoryResult<Vec<ClientEvent>>;
+        fn set_manager_active(
+            &self,
+            manager_id: ManagerId,
+            hub_id: HubId,
+            is_active: bool,
+        ) -> RepositoryResult<Manager>;
+        fn update_manager_profile(
+            &self,
+            manager_id: ManagerId,
+            hub_id: HubId,
+            name: &ManagerName,
+            phone: Option<PhoneNumber>,
+        ) -> RepositoryResult<Manager>;
+        fn get_or_create_system_manager(&self, hub_id: HubId) -> RepositoryResult<Manager>;
+        fn delete_manager(&self, manager_id: ManagerId, hub_id: HubId) -> RepositoryResult<()>;
     }
 
     impl ImportantFieldWriter for Repository {
@@ -98,10 +265,54 @@ mock! {
             hub_id: HubId,
             fields: &[ImportantField],
         ) -> RepositoryResult<()>;
+        fn delete_important_field(
+            &self,
+            hub_id: HubId,
+            name: &ImportantFieldName,
+        ) -> RepositoryResult<bool>;
     }
 
     impl ClientEventWriter for Repository {
         fn create_client_event(&self, client_event: &NewClientEvent) -> RepositoryResult<ClientEvent>;
+        fn delete_events_older_than(
+            &self,
+            hub_id: HubId,
+            cutoff: NaiveDateTime,
+        ) -> RepositoryResult<usize>;
+    }
+
+    impl NoteReader for Repository {
+        fn list_client_notes(&self, client_id: ClientId) -> RepositoryResult<Vec<ClientNote>>;
+    }
+
+    impl NoteWriter for Repository {
+        fn create_client_note(&self, note: &NewClientNote) -> RepositoryResult<ClientNote>;
+        fn toggle_client_note_pin(
+            &self,
+            note_id: ClientNoteId,
+            client_id: ClientId,
+        ) -> RepositoryResult<Option<ClientNote>>;
+    }
+
+    impl SegmentReader for Repository {
+        fn list_segments(&self, hub_id: HubId) -> RepositoryResult<Vec<ClientSegment>>;
+        fn get_segment(&self, segment_id: SegmentId, hub_id: HubId) -> RepositoryResult<Option<ClientSegment>>;
+        fn evaluate_segment(&self, segment_id: SegmentId, hub_id: HubId) -> RepositoryResult<Vec<Client>>;
+    }
+
+    impl SegmentWriter for Repository {
+        fn create_segment(&self, segment: &NewClientSegment) -> RepositoryResult<ClientSegment>;
+        fn update_segment(&self, segment_id: SegmentId, segment: &NewClientSegment) -> RepositoryResult<Option<ClientSegment>>;
+        fn delete_segment(&self, segment_id: SegmentId, hub_id: HubId) -> RepositoryResult<bool>;
+    }
+
+    impl ImportJobReader for Repository {
+        fn get_import_job(&self, job_id: ImportJobId, hub_id: HubId) -> RepositoryResult<Option<ImportJob>>;
+    }
+
+    impl ImportJobWriter for Repository {
+        fn create_import_job(&self, hub_id: HubId) -> RepositoryResult<ImportJob>;
+        fn save_import_job(&self, job: &ImportJob) -> RepositoryResult<ImportJob>;
     }
 
     impl StoreOtpRepository for Repository {
@@ -109,4 +320,32 @@ mock! {
         fn upsert_store_otp(&self, new_otp: &NewStoreOtp) -> RepositoryResult<StoreOtp>;
         fn delete_store_otp(&self, hub_id: HubId, phone: &PhoneNumber) -> RepositoryResult<()>;
     }
+
+    impl ApiKeyReader for Repository {
+        fn find_api_key(&self, key_hash: &str) -> RepositoryResult<Option<ApiKey>>;
+        fn list_api_keys(&self, hub_id: HubId) -> RepositoryResult<Vec<ApiKey>>;
+    }
+
+    impl ApiKeyWriter for Repository {
+        fn create_api_key(&self, new_api_key: &NewApiKey) -> RepositoryResult<ApiKey>;
+        fn delete_api_key(&self, id: ApiKeyId, hub_id: HubId) -> RepositoryResult<bool>;
+    }
+
+    impl WebhookReader for Repository {
+        fn list_webhooks(&self, hub_id: HubId) -> RepositoryResult<Vec<Webhook>>;
+    }
+
+    impl WebhookWriter for Repository {
+        fn create_webhook(&self, new_webhook: &NewWebhook) -> RepositoryResult<Webhook>;
+        fn delete_webhook(&self, id: WebhookId, hub_id: HubId) -> RepositoryResult<bool>;
+    }
+
+    impl RuleReader for Repository {
+        fn list_rules(&self, hub_id: HubId) -> RepositoryResult<Vec<ManagerAssignmentRule>>;
+    }
+
+    impl RuleWriter for Repository {
+        fn create_rule(&self, rule: &NewManagerAssignmentRule) -> RepositoryResult<ManagerAssignmentRule>;
+        fn delete_rule(&self, rule_id: RuleId, hub_id: HubId) -> RepositoryResult<()>;
+    }
 }
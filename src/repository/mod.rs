@@ -1,40 +1,102 @@
 //! Repository traits and Diesel implementation for the CRM domain.
 
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDateTime};
+use diesel::sql_types::Integer;
+use diesel::{IntoSql, RunQueryDsl};
 use pushkind_common::db::{DbConnection, DbPool};
 use pushkind_common::pagination::Pagination;
 use pushkind_common::repository::errors::RepositoryResult;
+use serde::Deserialize;
 
 use crate::domain::types::{
-    ClientEmail, ClientId, HubId, ManagerEmail, ManagerId, PhoneNumber, PublicId,
+    ApiKeyId, ClientEmail, ClientId, ClientName, ClientNoteId, DateRange, HubId,
+    ImportConflictStrategy, ImportJobId, ImportantFieldName, ManagerEmail, ManagerId, ManagerName,
+    PhoneNumber, PublicId, RuleId, SegmentId, WebhookId,
 };
 use crate::domain::{
-    client::{Client, NewClient, UpdateClient},
+    api_key::{ApiKey, NewApiKey},
+    assignment_rule::{ManagerAssignmentRule, NewManagerAssignmentRule},
+    audit::{AuditEntry, NewAuditEntry},
+    client::{
+        Client, ClientSource, ClientStatus, ClientUpsertSummary, ClientWriteOutcome, FieldMeta,
+        NewClient, UpdateClient,
+    },
     client_event::{ClientEvent, ClientEventType, NewClientEvent},
+    contact_policy::ContactPolicy,
+    hub_settings::HubSettings,
+    import_job::ImportJob,
     important_field::ImportantField as DomainImportantField,
     manager::{Manager, NewManager},
+    note::{ClientNote, NewClientNote},
+    segment::{ClientSegment, NewClientSegment},
     store_otp::{NewStoreOtp, StoreOtp},
+    webhook::{NewWebhook, Webhook},
 };
+use crate::models::client::NEW_CLIENT_COLUMNS;
+use crate::models::config::DEFAULT_BATCH_CHUNK_SIZE;
+use crate::repository::errors::ClientWriteResult;
 
+pub mod api_key;
+pub mod assignment_rule;
+pub mod audit;
 pub mod client;
 pub mod client_event;
+pub mod contact_policy;
+pub mod errors;
+pub mod fts;
+pub mod hub_settings;
+pub mod hub_stats;
+pub mod import_job;
 pub mod manager;
 #[cfg(feature = "test-mocks")]
 pub mod mock;
+pub mod note;
+pub mod segment;
 pub mod store_otp;
+pub mod webhook;
+
+/// Most parameters SQLite binds in one statement.
+pub(crate) const SQLITE_MAX_BIND_PARAMS: usize = 32766;
+
+/// Largest `create_clients` chunk that stays within [`SQLITE_MAX_BIND_PARAMS`].
+const MAX_BATCH_CHUNK_SIZE: usize = SQLITE_MAX_BIND_PARAMS / NEW_CLIENT_COLUMNS;
 
 #[derive(Clone)]
 pub struct DieselRepository {
     pool: DbPool, // r2d2::Pool is cheap to clone
+    /// Rows inserted per statement by `create_clients`.
+    batch_chunk_size: usize,
 }
 
 impl DieselRepository {
     pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            batch_chunk_size: DEFAULT_BATCH_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets how many clients `create_clients` inserts per statement; zero is
+    /// treated as one and sizes past SQLite's bound-parameter limit are
+    /// capped to it.
+    #[must_use]
+    pub fn with_batch_chunk_size(mut self, batch_chunk_size: usize) -> Self {
+        self.batch_chunk_size = batch_chunk_size.clamp(1, MAX_BATCH_CHUNK_SIZE);
+        self
     }
 
     fn conn(&self) -> RepositoryResult<DbConnection> {
         Ok(self.pool.get()?)
     }
+
+    /// Checks out a pooled connection and runs `SELECT 1` against it.
+    pub fn health_check(&self) -> RepositoryResult<()> {
+        let mut conn = self.conn()?;
+        diesel::select(1.into_sql::<Integer>()).get_result::<i32>(&mut conn)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,8 +104,47 @@ pub struct ClientListQuery {
     pub hub_id: HubId,
     pub manager_email: Option<ManagerEmail>,
     pub search: Option<String>,
+    /// Operator joining the words of `search`.
+    pub fts_operator: FtsOperator,
+    /// When FTS finds nothing for `search`, fall back to matching name word
+    /// prefixes and order the results by name similarity.
+    pub fuzzy: bool,
     pub public_id: Option<PublicId>,
+    /// Exact (non-FTS) match on the normalized client email.
+    pub email_exact: Option<ClientEmail>,
+    /// Exact (non-FTS) match on the E.164-normalized client phone.
+    pub phone_exact: Option<PhoneNumber>,
+    /// Keep only clients with a `Reply` newer than the last outbound event of
+    /// the manager in `manager_email`. Ignored without `manager_email`.
+    pub pending_replies: bool,
+    /// Keep only members of this hub segment.
+    pub segment_id: Option<SegmentId>,
+    /// `(field, value)` pairs; the custom field must contain `value`
+    /// (case-insensitive for ASCII).
+    pub field_filters: Vec<(String, String)>,
+    /// Keep clients with [`ClientStatus::Archived`], which are hidden by default.
+    pub include_archived: bool,
+    /// Keep only clients created through this channel.
+    pub source: Option<ClientSource>,
+    /// Keep only clients no manager is assigned to.
+    pub unassigned: bool,
+    /// Keep only clients created on one of these days.
+    pub created: Option<DateRange>,
+    pub sort: ClientSort,
     pub pagination: Option<Pagination>,
+    /// Keyset pagination by client id; overrides `sort` and `pagination`.
+    pub cursor: Option<ClientCursor>,
+}
+
+/// Keyset page of [`ClientReader::list_clients`]: at most `limit` clients with
+/// an id greater than `after_id`, in id ascending order.
+///
+/// The fuzzy fallback orders by similarity and ignores the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientCursor {
+    /// Last id of the previous page; `0` starts from the first client.
+    pub after_id: i32,
+    pub limit: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -53,14 +154,62 @@ pub struct ClientEventListQuery {
     pub pagination: Option<Pagination>,
 }
 
+/// Sort order for [`ClientReader::list_clients`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientSort {
+    /// Client id ascending.
+    #[default]
+    Id,
+    /// Newest clients first, ties by id descending.
+    CreatedDesc,
+    /// Most recently updated clients first, ties by id descending.
+    UpdatedDesc,
+}
+
+/// How the words of a [`ClientListQuery::search`] term are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FtsOperator {
+    /// Every word must match.
+    #[default]
+    And,
+    /// Any word may match.
+    Or,
+}
+
+/// Sort order for [`ManagerReader::list_managers_with_clients`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagerSort {
+    /// Manager name ascending.
+    #[default]
+    Name,
+    /// Number of assigned clients descending, ties by name ascending.
+    ClientCount,
+}
+
 impl ClientListQuery {
     pub fn new(hub_id: HubId) -> Self {
         Self {
             hub_id,
             manager_email: None,
             search: None,
+            fts_operator: FtsOperator::default(),
+            fuzzy: false,
             public_id: None,
+            email_exact: None,
+            phone_exact: None,
+            pending_replies: false,
+            segment_id: None,
+            field_filters: Vec::new(),
+            include_archived: false,
+            source: None,
+            unassigned: false,
+            created: None,
+            sort: ClientSort::default(),
             pagination: None,
+            cursor: None,
         }
     }
 
@@ -74,6 +223,16 @@ impl ClientListQuery {
         self
     }
 
+    pub fn fts_operator(mut self, operator: FtsOperator) -> Self {
+        self.fts_operator = operator;
+        self
+    }
+
+    pub fn fuzzy(mut self, value: bool) -> Self {
+        self.fuzzy = value;
+        self
+    }
+
     pub fn paginate(mut self, page: usize, per_page: usize) -> Self {
         self.pagination = Some(Pagination { page, per_page });
         self
@@ -83,6 +242,61 @@ impl ClientListQuery {
         self.public_id = Some(public_id);
         self
     }
+
+    pub fn email_exact(mut self, email: ClientEmail) -> Self {
+        self.email_exact = Some(email);
+        self
+    }
+
+    pub fn phone_exact(mut self, phone: PhoneNumber) -> Self {
+        self.phone_exact = Some(phone);
+        self
+    }
+
+    pub fn pending_replies(mut self) -> Self {
+        self.pending_replies = true;
+        self
+    }
+
+    pub fn segment(mut self, segment_id: SegmentId) -> Self {
+        self.segment_id = Some(segment_id);
+        self
+    }
+
+    pub fn field_filter(mut self, field: impl Into<String>, value: impl Into<String>) -> Self {
+        self.field_filters.push((field.into(), value.into()));
+        self
+    }
+
+    pub fn include_archived(mut self, value: bool) -> Self {
+        self.include_archived = value;
+        self
+    }
+
+    pub fn source(mut self, source: ClientSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn unassigned(mut self, value: bool) -> Self {
+        self.unassigned = value;
+        self
+    }
+
+    pub fn created_within(mut self, range: DateRange) -> Self {
+        self.created = Some(range);
+        self
+    }
+
+    pub fn sort(mut self, sort: ClientSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn after(mut self, after_id: i32, limit: usize) -> Self {
+        self.cursor = Some(ClientCursor { after_id, limit });
+        self
+    }
 }
 
 impl ClientEventListQuery {
@@ -112,6 +326,9 @@ pub trait ClientReader {
         hub_id: HubId,
     ) -> RepositoryResult<Option<Client>>;
     fn get_client_by_id(&self, id: ClientId, hub_id: HubId) -> RepositoryResult<Option<Client>>;
+    /// Loads the clients of the hub among `ids` in one query; unknown ids and
+    /// clients of other hubs are left out.
+    fn get_clients_by_ids(&self, ids: &[ClientId], hub_id: HubId) -> RepositoryResult<Vec<Client>>;
     fn get_client_by_email(
         &self,
         email: &ClientEmail,
@@ -122,26 +339,201 @@ pub trait ClientReader {
         phone: &PhoneNumber,
         hub_id: HubId,
     ) -> RepositoryResult<Option<Client>>;
+    /// Lists hub clients matching `query`, returning the total count and the requested page.
+    ///
+    /// Setting [`ClientListQuery::search`] enables full-text search over the client FTS index;
+    /// a blank search term is ignored and behaves as if no search was set.
     fn list_clients(&self, query: ClientListQuery) -> RepositoryResult<(usize, Vec<Client>)>;
-    fn list_managers(&self, id: ClientId) -> RepositoryResult<Vec<Manager>>;
+    /// Lists up to `limit` hub clients last edited by the manager, most recent edit first.
+    fn list_clients_edited_by(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>>;
+    /// Lists up to `limit` non-archived hub clients, newest first with ties by
+    /// id descending; with `manager_email`, only clients assigned to that manager.
+    fn list_recent_clients(
+        &self,
+        hub_id: HubId,
+        manager_email: Option<ManagerEmail>,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>>;
+    /// Lists the managers of the client's hub assigned to it, by id, returning
+    /// the total count and the requested page.
+    fn list_managers_for_client(
+        &self,
+        client_id: ClientId,
+        pagination: Option<Pagination>,
+    ) -> RepositoryResult<(usize, Vec<Manager>)>;
+    /// Lists every manager assigned to the client, by id.
+    fn list_all_managers_for_client(&self, client_id: ClientId) -> RepositoryResult<Vec<Manager>> {
+        Ok(self.list_managers_for_client(client_id, None)?.1)
+    }
+    #[deprecated(note = "use `list_all_managers_for_client` or `list_managers_for_client`")]
+    fn list_managers(&self, id: ClientId) -> RepositoryResult<Vec<Manager>> {
+        self.list_all_managers_for_client(id)
+    }
+    /// Counts hub clients whose name matches `name` exactly, leaving out the
+    /// client `except`.
+    fn count_clients_by_name(
+        &self,
+        hub_id: HubId,
+        name: &ClientName,
+        except: Option<ClientId>,
+    ) -> RepositoryResult<usize>;
+    /// Maps the [`ClientName::match_key`] of every hub client name to the
+    /// clients carrying it, by id.
+    fn client_ids_by_name_key(
+        &self,
+        hub_id: HubId,
+    ) -> RepositoryResult<HashMap<String, Vec<ClientId>>>;
     fn check_client_assigned_to_manager(
         &self,
         client_id: ClientId,
         manager_email: &ManagerEmail,
     ) -> RepositoryResult<bool>;
-    fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<String>>;
+    /// Lists custom field names used or marked important in the hub, by name.
+    fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<FieldMeta>>;
 }
 
 pub trait ClientWriter {
-    fn create_or_replace_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize>;
-    fn create_clients(&self, new_clients: &[NewClient]) -> RepositoryResult<usize>;
+    /// Inserts new clients and updates the ones matching an existing email or
+    /// phone of the hub.
+    ///
+    /// Fails with [`ClientWriteError::LimitExceeded`] when a hub would exceed
+    /// its client limit.
+    ///
+    /// [`ClientWriteError::LimitExceeded`]: crate::repository::errors::ClientWriteError::LimitExceeded
+    fn create_or_replace_clients(
+        &self,
+        new_clients: &[NewClient],
+    ) -> ClientWriteResult<ClientUpsertSummary>;
+    /// Same as [`ClientWriter::create_or_replace_clients`], reporting what
+    /// happened to each record in input order.
+    fn upsert_clients(
+        &self,
+        new_clients: &[NewClient],
+    ) -> ClientWriteResult<Vec<ClientWriteOutcome>>;
+    /// Inserts new clients, resolving rows that match an existing client of the
+    /// hub according to `strategy`.
+    ///
+    /// [`ImportConflictStrategy::Error`] fails with
+    /// [`ClientWriteError::ClientExists`] on the first match and rolls the
+    /// whole batch back.
+    ///
+    /// [`ClientWriteError::ClientExists`]: crate::repository::errors::ClientWriteError::ClientExists
+    fn create_clients(
+        &self,
+        new_clients: &[NewClient],
+        strategy: ImportConflictStrategy,
+    ) -> ClientWriteResult<ClientUpsertSummary>;
+    /// Same as [`ClientWriter::create_clients`], writing the rows in chunks of
+    /// the configured batch size and calling `on_chunk` with the running totals
+    /// after each chunk.
+    ///
+    /// Each of `name_matches` first updates its client as
+    /// [`UpdateClient::from_import`] describes, audited as `actor`, and counts
+    /// as updated. The updates and all chunks share one transaction, so a
+    /// failure imports nothing.
+    fn import_clients(
+        &self,
+        new_clients: &[NewClient],
+        name_matches: &[(ClientId, NewClient)],
+        strategy: ImportConflictStrategy,
+        actor: &ManagerEmail,
+        on_chunk: &dyn Fn(ClientUpsertSummary),
+    ) -> ClientWriteResult<ClientUpsertSummary>;
+    /// Applies `updates` and records an audit entry attributed to `actor`.
+    ///
+    /// Returns [`ClientWriteError::StaleVersion`] when
+    /// `updates.expected_version` no longer matches the stored client.
+    ///
+    /// [`ClientWriteError::StaleVersion`]: crate::repository::errors::ClientWriteError::StaleVersion
     fn update_client(
         &self,
         client_id: ClientId,
         updates: &UpdateClient,
+        actor: &ManagerEmail,
+    ) -> ClientWriteResult<Client>;
+    /// Replaces all custom fields of the client, leaving name and contacts
+    /// untouched, and records an audit entry attributed to `actor`.
+    fn replace_client_fields(
+        &self,
+        client_id: ClientId,
+        fields: &BTreeMap<String, String>,
+        actor: &ManagerEmail,
     ) -> RepositoryResult<Client>;
-    fn delete_client(&self, client_id: ClientId) -> RepositoryResult<()>;
+    /// Deletes the client with its related records and records an audit entry.
+    fn delete_client(&self, client_id: ClientId, actor: &ManagerEmail) -> RepositoryResult<()>;
     fn delete_all_clients(&self, hub_id: HubId) -> RepositoryResult<()>;
+    /// Moves a client of the hub to `status`; unknown clients return `NotFound`.
+    fn set_client_status(
+        &self,
+        client_id: ClientId,
+        hub_id: HubId,
+        status: ClientStatus,
+    ) -> RepositoryResult<()>;
+}
+
+pub trait ContactPolicyReader {
+    /// Returns the hub contact policy, or the "at least one" default when the
+    /// hub has none configured.
+    fn get_contact_policy(&self, hub_id: HubId) -> RepositoryResult<ContactPolicy>;
+}
+
+pub trait ContactPolicyWriter {
+    /// Inserts or replaces the contact policy of the hub.
+    fn save_contact_policy(&self, hub_id: HubId, policy: &ContactPolicy) -> RepositoryResult<()>;
+}
+
+pub trait HubSettingsReader {
+    /// Returns the hub settings, or [`HubSettings::defaults`] when the hub has
+    /// none stored.
+    fn get_hub_settings(&self, hub_id: HubId) -> RepositoryResult<HubSettings>;
+    /// Lists the settings of hubs that configured an event retention period.
+    fn list_hub_settings_with_event_retention(&self) -> RepositoryResult<Vec<HubSettings>>;
+}
+
+pub trait HubSettingsWriter {
+    /// Inserts or replaces the settings of `settings.hub_id`.
+    fn save_hub_settings(&self, settings: &HubSettings) -> RepositoryResult<HubSettings>;
+    /// Caps the number of clients the hub may hold; `None` lifts the limit.
+    fn set_hub_client_limit(&self, hub_id: HubId, max_clients: Option<i32>)
+    -> RepositoryResult<()>;
+}
+
+/// Aggregate counts backing the admin hub dashboard.
+pub trait HubStatsReader {
+    fn count_clients(&self, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Counts hub clients with at least one event created at or after `since`.
+    fn count_active_clients(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize>;
+    fn count_managers(&self, hub_id: HubId) -> RepositoryResult<usize>;
+    /// Counts events of hub clients created at or after `since`.
+    fn count_client_events(&self, hub_id: HubId, since: NaiveDateTime) -> RepositoryResult<usize>;
+    /// Returns the manager who authored the most hub events since `since`,
+    /// ties broken by the lowest manager id.
+    fn top_manager_by_events(
+        &self,
+        hub_id: HubId,
+        since: NaiveDateTime,
+    ) -> RepositoryResult<Option<Manager>>;
+    /// Counts hub clients without any assigned manager.
+    fn count_unassigned_clients(&self, hub_id: HubId) -> RepositoryResult<usize>;
+}
+
+/// Counts across every hub reachable through the database, exported as metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServiceStats {
+    pub total_clients: usize,
+    pub total_managers: usize,
+    /// Events created at or after the `since` passed to the reader.
+    pub recent_events: usize,
+}
+
+pub trait ServiceStatsReader {
+    /// Counts clients and managers of all hubs and events created since `since`.
+    fn load_service_stats(&self, since: NaiveDateTime) -> RepositoryResult<ServiceStats>;
 }
 
 pub trait ImportantFieldReader {
@@ -154,6 +546,12 @@ pub trait ImportantFieldWriter {
         hub_id: HubId,
         fields: &[DomainImportantField],
     ) -> RepositoryResult<()>;
+    /// Removes one important field of the hub; returns whether it existed.
+    fn delete_important_field(
+        &self,
+        hub_id: HubId,
+        name: &ImportantFieldName,
+    ) -> RepositoryResult<bool>;
 }
 pub trait ManagerReader {
     fn get_manager_by_id(&self, id: ManagerId, hub_id: HubId) -> RepositoryResult<Option<Manager>>;
@@ -162,19 +560,110 @@ pub trait ManagerReader {
         email: &ManagerEmail,
         hub_id: HubId,
     ) -> RepositoryResult<Option<Manager>>;
+    /// Lists active user managers of the hub whose full or first name equals
+    /// `name`, ignoring case.
+    fn find_managers_by_name(&self, name: &str, hub_id: HubId) -> RepositoryResult<Vec<Manager>>;
+    /// Lists active user managers of the hub with their assigned clients.
     fn list_managers_with_clients(
         &self,
         hub_id: HubId,
+        sort: ManagerSort,
     ) -> RepositoryResult<Vec<(Manager, Vec<Client>)>>;
 }
 
 pub trait ManagerWriter {
+    /// Inserts or updates the manager with the same hub and email; `None`
+    /// company or phone keeps the stored value, as login syncs carry neither.
     fn create_or_update_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager>;
+    /// Same as [`ManagerWriter::create_or_update_manager`], but writes company
+    /// and phone as given, so `None` clears them. Used by the admin form.
+    fn save_manager(&self, new_manager: &NewManager) -> RepositoryResult<Manager>;
+    /// Replaces the manager's clients with `client_ids`; assignments of
+    /// archived clients are kept.
     fn assign_clients_to_manager(
         &self,
         manager_id: ManagerId,
         client_ids: &[ClientId],
     ) -> RepositoryResult<usize>;
+    /// Assigns the clients to the manager on top of its existing clients,
+    /// returning how many assignments were new.
+    ///
+    /// Unlike [`ManagerWriter::assign_clients_to_manager`] no assignment is removed.
+    fn add_clients_to_manager(
+        &self,
+        manager_id: ManagerId,
+        client_ids: &[ClientId],
+    ) -> RepositoryResult<usize>;
+    /// Moves every hub client of `from_id` to `to_id` and records one event of
+    /// `event_type` per moved client, attributed to `to_id`, in one transaction.
+    ///
+    /// Returns the recorded events; nothing is written when any step fails.
+    fn reassign_all_clients(
+        &self,
+        from_id: ManagerId,
+        to_id: ManagerId,
+        hub_id: HubId,
+        event_type: &ClientEventType,
+        event_data: &serde_json::Value,
+    ) -> RepositoryResult<Vec<ClientEvent>>;
+    /// Archives or restores a hub manager, returning the updated record.
+    ///
+    /// Returns `NotFound` when the manager does not belong to the hub.
+    fn set_manager_active(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        is_active: bool,
+    ) -> RepositoryResult<Manager>;
+    /// Sets the name and phone a manager chose on their profile and locks the
+    /// name against [`ManagerWriter::create_or_update_manager`] login syncs.
+    ///
+    /// Returns `NotFound` when the manager does not belong to the hub.
+    fn update_manager_profile(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        name: &ManagerName,
+        phone: Option<PhoneNumber>,
+    ) -> RepositoryResult<Manager>;
+    /// Returns the hub's system manager, creating it on first use.
+    fn get_or_create_system_manager(&self, hub_id: HubId) -> RepositoryResult<Manager>;
+    /// Deletes a hub manager in one transaction.
+    ///
+    /// Events and notes of the manager are attributed to the hub's system manager,
+    /// client assignments are dropped and `last_edited_by` references are cleared.
+    /// Returns [`RepositoryError::NotFound`] for unknown managers and refuses to
+    /// delete the system manager itself.
+    ///
+    /// [`RepositoryError::NotFound`]: pushkind_common::repository::errors::RepositoryError::NotFound
+    fn delete_manager(&self, manager_id: ManagerId, hub_id: HubId) -> RepositoryResult<()>;
+}
+
+pub trait ApiKeyReader {
+    /// Finds the API key whose hex-encoded SHA-256 hash is `key_hash`.
+    fn find_api_key(&self, key_hash: &str) -> RepositoryResult<Option<ApiKey>>;
+    /// Lists the API keys of the hub, oldest first.
+    fn list_api_keys(&self, hub_id: HubId) -> RepositoryResult<Vec<ApiKey>>;
+}
+
+pub trait ApiKeyWriter {
+    fn create_api_key(&self, new_api_key: &NewApiKey) -> RepositoryResult<ApiKey>;
+    /// Deletes the hub's API key, returning whether one was removed.
+    fn delete_api_key(&self, id: ApiKeyId, hub_id: HubId) -> RepositoryResult<bool>;
+}
+
+pub trait AuditReader {
+    /// Lists audit entries recorded for an entity of the hub, newest first.
+    fn list_audit_entries(
+        &self,
+        hub_id: HubId,
+        entity_type: &str,
+        entity_id: i32,
+    ) -> RepositoryResult<Vec<AuditEntry>>;
+}
+
+pub trait AuditWriter {
+    fn record_audit(&self, entry: &NewAuditEntry) -> RepositoryResult<()>;
 }
 
 pub trait ClientEventReader {
@@ -183,10 +672,117 @@ pub trait ClientEventReader {
         query: ClientEventListQuery,
     ) -> RepositoryResult<(usize, Vec<(ClientEvent, Manager)>)>;
     fn client_event_exists(&self, event: &NewClientEvent) -> RepositoryResult<bool>;
+    /// Like [`ClientEventReader::client_event_exists`] but only considers events
+    /// created within `window` of now.
+    fn client_event_exists_within_window(
+        &self,
+        event: &NewClientEvent,
+        window: Duration,
+    ) -> RepositoryResult<bool>;
 }
 
 pub trait ClientEventWriter {
     fn create_client_event(&self, client_event: &NewClientEvent) -> RepositoryResult<ClientEvent>;
+    /// Deletes the hub's client events created before `cutoff` and returns
+    /// how many were removed.
+    fn delete_events_older_than(
+        &self,
+        hub_id: HubId,
+        cutoff: NaiveDateTime,
+    ) -> RepositoryResult<usize>;
+}
+
+pub trait NoteReader {
+    /// Lists notes for a client, pinned notes first, newest first within each group.
+    fn list_client_notes(&self, client_id: ClientId) -> RepositoryResult<Vec<ClientNote>>;
+}
+
+pub trait NoteWriter {
+    fn create_client_note(&self, note: &NewClientNote) -> RepositoryResult<ClientNote>;
+    /// Flips `is_pinned` on a note belonging to the given client.
+    ///
+    /// Returns `None` when the note does not exist or belongs to another client.
+    fn toggle_client_note_pin(
+        &self,
+        note_id: ClientNoteId,
+        client_id: ClientId,
+    ) -> RepositoryResult<Option<ClientNote>>;
+}
+
+pub trait WebhookReader {
+    /// Lists the hub webhooks, oldest first.
+    fn list_webhooks(&self, hub_id: HubId) -> RepositoryResult<Vec<Webhook>>;
+}
+
+pub trait WebhookWriter {
+    fn create_webhook(&self, new_webhook: &NewWebhook) -> RepositoryResult<Webhook>;
+    /// Deletes a hub webhook, returning `false` when it does not exist in the hub.
+    fn delete_webhook(&self, id: WebhookId, hub_id: HubId) -> RepositoryResult<bool>;
+}
+
+pub trait RuleReader {
+    /// Lists the hub manager assignment rules, oldest first.
+    fn list_rules(&self, hub_id: HubId) -> RepositoryResult<Vec<ManagerAssignmentRule>>;
+}
+
+pub trait RuleWriter {
+    /// Stores a rule; a second rule for the same field and value in the hub is
+    /// rejected with [`RepositoryError::ConstraintViolation`].
+    ///
+    /// [`RepositoryError::ConstraintViolation`]: pushkind_common::repository::errors::RepositoryError::ConstraintViolation
+    fn create_rule(
+        &self,
+        rule: &NewManagerAssignmentRule,
+    ) -> RepositoryResult<ManagerAssignmentRule>;
+    /// Deletes a hub rule, failing with [`RepositoryError::NotFound`] when it
+    /// does not exist in the hub.
+    ///
+    /// [`RepositoryError::NotFound`]: pushkind_common::repository::errors::RepositoryError::NotFound
+    fn delete_rule(&self, rule_id: RuleId, hub_id: HubId) -> RepositoryResult<()>;
+}
+
+pub trait SegmentReader {
+    /// Lists the hub segments ordered by name.
+    fn list_segments(&self, hub_id: HubId) -> RepositoryResult<Vec<ClientSegment>>;
+    fn get_segment(
+        &self,
+        segment_id: SegmentId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<ClientSegment>>;
+    /// Returns the hub clients matching the segment filter, ordered by id.
+    /// Fails with `NotFound` when the segment is not in the hub.
+    fn evaluate_segment(
+        &self,
+        segment_id: SegmentId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Client>>;
+}
+
+pub trait SegmentWriter {
+    fn create_segment(&self, segment: &NewClientSegment) -> RepositoryResult<ClientSegment>;
+    /// Replaces the name and filter of a hub segment; `None` when it does not exist.
+    fn update_segment(
+        &self,
+        segment_id: SegmentId,
+        segment: &NewClientSegment,
+    ) -> RepositoryResult<Option<ClientSegment>>;
+    /// Deletes a hub segment, returning `false` when it does not exist in the hub.
+    fn delete_segment(&self, segment_id: SegmentId, hub_id: HubId) -> RepositoryResult<bool>;
+}
+
+pub trait ImportJobReader {
+    fn get_import_job(
+        &self,
+        job_id: ImportJobId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<ImportJob>>;
+}
+
+pub trait ImportJobWriter {
+    /// Records a new `Pending` import job for the hub.
+    fn create_import_job(&self, hub_id: HubId) -> RepositoryResult<ImportJob>;
+    /// Persists the status, counters and completion time of a job.
+    fn save_import_job(&self, job: &ImportJob) -> RepositoryResult<ImportJob>;
 }
 
 pub trait StoreOtpRepository {
@@ -0,0 +1,74 @@
+//! Repository implementation for internal client notes.
+
+use diesel::dsl::{not, now};
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::note::{ClientNote, NewClientNote};
+use crate::domain::types::{ClientId, ClientNoteId};
+use crate::models::note::{ClientNote as DbClientNote, NewClientNote as DbNewClientNote};
+use crate::repository::{DieselRepository, NoteReader, NoteWriter};
+
+impl NoteReader for DieselRepository {
+    fn list_client_notes(&self, client_id: ClientId) -> RepositoryResult<Vec<ClientNote>> {
+        use crate::schema::client_notes;
+
+        let mut conn = self.conn()?;
+
+        let notes = client_notes::table
+            .filter(client_notes::client_id.eq(client_id.get()))
+            .order((
+                client_notes::is_pinned.desc(),
+                client_notes::created_at.desc(),
+                client_notes::id.desc(),
+            ))
+            .load::<DbClientNote>(&mut conn)?
+            .into_iter()
+            .map(|note| ClientNote::try_from(note).map_err(RepositoryError::from))
+            .collect::<Result<Vec<_>, RepositoryError>>()?;
+
+        Ok(notes)
+    }
+}
+
+impl NoteWriter for DieselRepository {
+    fn create_client_note(&self, note: &NewClientNote) -> RepositoryResult<ClientNote> {
+        use crate::schema::client_notes;
+
+        let mut conn = self.conn()?;
+
+        let db_new: DbNewClientNote = note.into();
+
+        let db_note = diesel::insert_into(client_notes::table)
+            .values(&db_new)
+            .get_result::<DbClientNote>(&mut conn)?;
+
+        Ok(ClientNote::try_from(db_note)?)
+    }
+
+    fn toggle_client_note_pin(
+        &self,
+        note_id: ClientNoteId,
+        client_id: ClientId,
+    ) -> RepositoryResult<Option<ClientNote>> {
+        use crate::schema::client_notes;
+
+        let mut conn = self.conn()?;
+
+        let db_note = diesel::update(
+            client_notes::table
+                .filter(client_notes::id.eq(note_id.get()))
+                .filter(client_notes::client_id.eq(client_id.get())),
+        )
+        .set((
+            client_notes::is_pinned.eq(not(client_notes::is_pinned)),
+            client_notes::updated_at.eq(now),
+        ))
+        .get_result::<DbClientNote>(&mut conn)
+        .optional()?;
+
+        db_note
+            .map(|note| ClientNote::try_from(note).map_err(RepositoryError::from))
+            .transpose()
+    }
+}
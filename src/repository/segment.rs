@@ -0,0 +1,125 @@
+//! Client segment repository implementation with Diesel.
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::client::Client;
+use crate::domain::segment::{
+    ClientSegment as DomainClientSegment, NewClientSegment as DomainNewClientSegment,
+};
+use crate::domain::types::{HubId, SegmentId};
+use crate::models::segment::{
+    ClientSegment as DbClientSegment, NewClientSegment as DbNewClientSegment,
+};
+use crate::repository::{
+    ClientListQuery, ClientReader, DieselRepository, SegmentReader, SegmentWriter,
+};
+
+/// Loads a hub segment on an existing connection.
+pub(crate) fn load_segment(
+    conn: &mut SqliteConnection,
+    segment_id: SegmentId,
+    hub_id: HubId,
+) -> RepositoryResult<Option<DomainClientSegment>> {
+    use crate::schema::client_segments;
+
+    client_segments::table
+        .filter(client_segments::id.eq(segment_id.get()))
+        .filter(client_segments::hub_id.eq(hub_id.get()))
+        .first::<DbClientSegment>(conn)
+        .optional()?
+        .map(|segment| DomainClientSegment::try_from(segment).map_err(RepositoryError::from))
+        .transpose()
+}
+
+impl SegmentReader for DieselRepository {
+    fn list_segments(&self, hub_id: HubId) -> RepositoryResult<Vec<DomainClientSegment>> {
+        use crate::schema::client_segments;
+
+        let mut conn = self.conn()?;
+        client_segments::table
+            .filter(client_segments::hub_id.eq(hub_id.get()))
+            .order((client_segments::name.asc(), client_segments::id.asc()))
+            .load::<DbClientSegment>(&mut conn)?
+            .into_iter()
+            .map(|segment| DomainClientSegment::try_from(segment).map_err(RepositoryError::from))
+            .collect()
+    }
+
+    fn get_segment(
+        &self,
+        segment_id: SegmentId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<DomainClientSegment>> {
+        let mut conn = self.conn()?;
+        load_segment(&mut conn, segment_id, hub_id)
+    }
+
+    fn evaluate_segment(
+        &self,
+        segment_id: SegmentId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Vec<Client>> {
+        let (_, clients) = self.list_clients(ClientListQuery::new(hub_id).segment(segment_id))?;
+
+        Ok(clients)
+    }
+}
+
+impl SegmentWriter for DieselRepository {
+    fn create_segment(
+        &self,
+        segment: &DomainNewClientSegment,
+    ) -> RepositoryResult<DomainClientSegment> {
+        use crate::schema::client_segments;
+
+        let mut conn = self.conn()?;
+        let db_segment = diesel::insert_into(client_segments::table)
+            .values(&DbNewClientSegment::from(segment))
+            .get_result::<DbClientSegment>(&mut conn)?;
+
+        DomainClientSegment::try_from(db_segment).map_err(RepositoryError::from)
+    }
+
+    fn update_segment(
+        &self,
+        segment_id: SegmentId,
+        segment: &DomainNewClientSegment,
+    ) -> RepositoryResult<Option<DomainClientSegment>> {
+        use crate::schema::client_segments;
+
+        let mut conn = self.conn()?;
+        let db_segment = diesel::update(
+            client_segments::table
+                .filter(client_segments::id.eq(segment_id.get()))
+                .filter(client_segments::hub_id.eq(segment.hub_id.get())),
+        )
+        .set((
+            client_segments::name.eq(segment.name.as_str()),
+            client_segments::filter_json.eq(segment.filter_json.to_string()),
+            client_segments::updated_at.eq(now),
+        ))
+        .get_result::<DbClientSegment>(&mut conn)
+        .optional()?;
+
+        db_segment
+            .map(|segment| DomainClientSegment::try_from(segment).map_err(RepositoryError::from))
+            .transpose()
+    }
+
+    fn delete_segment(&self, segment_id: SegmentId, hub_id: HubId) -> RepositoryResult<bool> {
+        use crate::schema::client_segments;
+
+        let mut conn = self.conn()?;
+        let deleted = diesel::delete(
+            client_segments::table
+                .filter(client_segments::id.eq(segment_id.get()))
+                .filter(client_segments::hub_id.eq(hub_id.get())),
+        )
+        .execute(&mut conn)?;
+
+        Ok(deleted > 0)
+    }
+}
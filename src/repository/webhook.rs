@@ -0,0 +1,51 @@
+//! Webhook registration repository implementation with Diesel.
+
+use diesel::prelude::*;
+use pushkind_common::repository::errors::{RepositoryError, RepositoryResult};
+
+use crate::domain::types::{HubId, WebhookId};
+use crate::domain::webhook::{NewWebhook as DomainNewWebhook, Webhook as DomainWebhook};
+use crate::models::webhook::{NewWebhook as DbNewWebhook, Webhook as DbWebhook};
+use crate::repository::{DieselRepository, WebhookReader, WebhookWriter};
+
+impl WebhookReader for DieselRepository {
+    fn list_webhooks(&self, hub_id: HubId) -> RepositoryResult<Vec<DomainWebhook>> {
+        use crate::schema::webhooks;
+
+        let mut conn = self.conn()?;
+        webhooks::table
+            .filter(webhooks::hub_id.eq(hub_id.get()))
+            .order(webhooks::id.asc())
+            .load::<DbWebhook>(&mut conn)?
+            .into_iter()
+            .map(|webhook| DomainWebhook::try_from(webhook).map_err(RepositoryError::from))
+            .collect()
+    }
+}
+
+impl WebhookWriter for DieselRepository {
+    fn create_webhook(&self, new_webhook: &DomainNewWebhook) -> RepositoryResult<DomainWebhook> {
+        use crate::schema::webhooks;
+
+        let mut conn = self.conn()?;
+        let db_webhook = diesel::insert_into(webhooks::table)
+            .values(&DbNewWebhook::from(new_webhook))
+            .get_result::<DbWebhook>(&mut conn)?;
+
+        DomainWebhook::try_from(db_webhook).map_err(RepositoryError::from)
+    }
+
+    fn delete_webhook(&self, id: WebhookId, hub_id: HubId) -> RepositoryResult<bool> {
+        use crate::schema::webhooks;
+
+        let mut conn = self.conn()?;
+        let deleted = diesel::delete(
+            webhooks::table
+                .filter(webhooks::id.eq(id.get()))
+                .filter(webhooks::hub_id.eq(hub_id.get())),
+        )
+        .execute(&mut conn)?;
+
+        Ok(deleted > 0)
+    }
+}
@@ -1,15 +1,25 @@
 //! Actix routes serving the CRM API surface.
 
-use actix_web::{HttpResponse, Responder, get, web};
-use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::models::config::CommonServerConfig;
+use actix_web::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use actix_web::{HttpRequest, HttpResponse, Responder, get, patch, post, web};
+use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
 use serde::Deserialize;
+use serde_json::json;
 
-use crate::dto::api::ClientsQuery;
+use crate::domain::types::{ClientId, ExportFormat, ImportJobId, ManagerId, SegmentId};
+use crate::dto::api::{
+    ClientEventsQuery, ClientFieldsPatch, ClientStatusPatch, ClientsQuery, RecentClientsQuery,
+};
+use crate::dto::export::{ClientsExportQuery, ExportOptions};
 use crate::dto::main::IndexQuery;
-use crate::models::config::AppConfig;
-use crate::repository::DieselRepository;
-use crate::services::{ServiceError, api as api_service};
+use crate::dto::managers::ManagersQuery;
+use crate::forms::main::ClientBatchRecord;
+use crate::routes::api_key::ApiUser;
+use crate::routes::streaming::stream_blocking;
+use crate::routes::{hub_dashboard_enabled, segments_enabled, webhooks_enabled};
+use crate::services::main::ImportProgress;
+use crate::services::{ServiceError, api as api_service, export as export_service};
+use crate::state::AppState;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct NoAccessQuery {
@@ -18,11 +28,8 @@ pub struct NoAccessQuery {
 
 #[get("/v1/iam")]
 /// Return typed shell data for React-owned CRM pages.
-pub async fn api_v1_iam(
-    user: AuthenticatedUser,
-    common_config: web::Data<CommonServerConfig>,
-) -> impl Responder {
-    match api_service::get_shell_data(&user, common_config.get_ref()) {
+pub async fn api_v1_iam(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_shell_data(&user, &state.common_config, &state.server_config.features) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
@@ -32,14 +39,27 @@ pub async fn api_v1_iam(
     }
 }
 
+#[get("/v1/features")]
+/// Return the optional features enabled for this deployment.
+pub async fn api_v1_features(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_feature_flags(&user, &state.server_config.features) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM feature flags: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[get("/v1/client-directory")]
 /// Return typed client directory data.
 pub async fn api_v1_client_directory(
     params: web::Query<IndexQuery>,
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match api_service::get_client_directory_data(params.into_inner(), &user, repo.get_ref()) {
+    match api_service::get_client_directory_data(params.into_inner(), &user, &state.repo) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
@@ -49,19 +69,72 @@ pub async fn api_v1_client_directory(
     }
 }
 
+#[get("/v1/clients/export")]
+/// Export the clients of the hub with their custom fields as CSV, JSON or
+/// ND-JSON.
+///
+/// Must be registered before `/v1/clients/{client_id}` so `export` is not
+/// parsed as a client id.
+pub async fn api_v1_clients_export(
+    params: web::Query<ClientsExportQuery>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    // Unsupported formats are rejected with `400` by the query extractor.
+    let options = match ExportOptions::try_from(params.into_inner()) {
+        Ok(options) => options,
+        Err(_) => return HttpResponse::BadRequest().finish(),
+    };
+    let content_type = match options.format {
+        ExportFormat::Csv => "text/csv; charset=utf-8",
+        ExportFormat::Json => "application/json",
+        ExportFormat::NdJson => "application/x-ndjson",
+    };
+
+    let repo = state.repo.clone();
+    let export = move |writer: &mut dyn std::io::Write| {
+        export_service::export_clients(options, &user, &repo, writer)
+    };
+    match stream_blocking(export).await {
+        Ok(body) => HttpResponse::Ok().content_type(content_type).body(body),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to export clients: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/clients/recent")]
+/// Return the most recently added clients visible to the user, newest first.
+pub async fn api_v1_recent_clients(
+    query: web::Query<RecentClientsQuery>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_recent_clients_data(query.into_inner(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to list recent CRM clients: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[get("/v1/clients/{client_id}")]
 /// Return typed client details data.
 pub async fn api_v1_client_details(
-    client_id: web::Path<i32>,
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
-    app_config: web::Data<AppConfig>,
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     match api_service::get_client_details_data(
-        client_id.into_inner(),
+        client_id.into_inner().get(),
         &user,
-        repo.get_ref(),
-        app_config.get_ref(),
+        &state.repo,
+        &state.app_config,
+        &state.server_config,
     ) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
@@ -73,13 +146,259 @@ pub async fn api_v1_client_details(
     }
 }
 
+#[get("/v1/clients/{client_id}/notes")]
+/// Return the internal notes of a client, pinned notes first.
+pub async fn api_v1_client_notes(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_client_notes_data(client_id.into_inner().get(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM client notes: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/clients/{client_id}/events")]
+/// Return one page of a client's events, newest first, with the total count.
+pub async fn api_v1_client_events(
+    client_id: web::Path<ClientId>,
+    params: web::Query<ClientEventsQuery>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_client_events(
+        client_id.into_inner().get(),
+        params.into_inner(),
+        &user,
+        &state.repo,
+    ) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM client events: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/clients/{client_id}/managers")]
+/// Return the managers assigned to a client.
+pub async fn api_v1_client_managers(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_client_managers(client_id.into_inner().get(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM client managers: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/clients/{client_id}/fields")]
+/// Return the custom fields of a client.
+pub async fn api_v1_client_fields(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_client_fields(client_id.into_inner().get(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM client fields: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[patch("/v1/clients/{client_id}/fields")]
+/// Replace the custom fields of a client, leaving name and contacts untouched.
+pub async fn api_v1_patch_client_fields(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+    payload: web::Json<ClientFieldsPatch>,
+) -> impl Responder {
+    match api_service::patch_client_fields(
+        client_id.into_inner().get(),
+        payload.into_inner(),
+        &user,
+        &state.repo,
+    ) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to update CRM client fields: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[patch("/v1/clients/{client_id}/status")]
+/// Archive or restore a client. Admin only.
+pub async fn api_v1_patch_client_status(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+    payload: web::Json<ClientStatusPatch>,
+) -> impl Responder {
+    match api_service::set_client_status(
+        client_id.into_inner().get(),
+        payload.into_inner(),
+        &user,
+        &state.repo,
+    ) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to change CRM client status: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/v1/clients/batch")]
+/// Upsert up to 1000 clients in one transaction, skipping invalid records. Admin only.
+pub async fn api_v1_clients_batch(
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+    payload: web::Json<Vec<ClientBatchRecord>>,
+) -> impl Responder {
+    match api_service::upsert_clients_batch(payload.into_inner(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::Form(message)) => {
+            log::warn!("Rejected client batch: {message}");
+            HttpResponse::BadRequest().json(json!({ "error": message }))
+        }
+        Err(err) => {
+            log::error!("Failed to upsert CRM client batch: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/clients/{client_id}/audit")]
+/// Return the audit history of a client, newest first. Admin only.
+pub async fn api_v1_client_audit(
+    client_id: web::Path<ClientId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_client_audit_data(client_id.into_inner().get(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM client audit log: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/webhooks", guard = "webhooks_enabled")]
+/// Return the webhooks registered for the hub.
+pub async fn api_v1_webhooks(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_webhooks_data(&user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM webhooks: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/api-keys")]
+/// Return the integration API keys of the hub without the keys themselves.
+pub async fn api_v1_api_keys(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_api_keys_data(&user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM API keys: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/segments", guard = "segments_enabled")]
+/// Return the client segments defined for the hub.
+pub async fn api_v1_segments(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_segments_data(&user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM segments: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/segments/{segment_id}/clients", guard = "segments_enabled")]
+/// Return the clients currently matching a segment.
+pub async fn api_v1_segment_clients(
+    segment_id: web::Path<SegmentId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_segment_clients_data(segment_id.into_inner().get(), &user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM segment clients: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/import/{job_id}/status")]
+/// Return the progress of a CSV client import.
+pub async fn api_v1_import_status(
+    job_id: web::Path<ImportJobId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+    import_progress: web::Data<ImportProgress>,
+) -> impl Responder {
+    match api_service::get_import_job_status_data(
+        job_id.into_inner().get(),
+        &user,
+        &state.repo,
+        &import_progress,
+    ) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM import job: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[get("/v1/managers")]
-/// Return typed manager collection data.
+/// Return typed manager collection data, sorted by `sort` (`name` or `client_count`).
 pub async fn api_v1_managers(
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    params: web::Query<ManagersQuery>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match api_service::get_manager_collection_data(&user, repo.get_ref()) {
+    match api_service::get_manager_collection_data(params.into_inner(), &user, &state.repo) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
@@ -92,11 +411,11 @@ pub async fn api_v1_managers(
 #[get("/v1/managers/{manager_id}")]
 /// Return typed manager modal data for React-owned CRM pages.
 pub async fn api_v1_manager_modal(
-    manager_id: web::Path<i32>,
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    manager_id: web::Path<ManagerId>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match api_service::get_manager_modal_data(manager_id.into_inner(), &user, repo.get_ref()) {
+    match api_service::get_manager_modal_data(manager_id.into_inner().get(), &user, &state.repo) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
@@ -110,10 +429,10 @@ pub async fn api_v1_manager_modal(
 #[get("/v1/important-fields")]
 /// Return typed important-field settings data.
 pub async fn api_v1_important_fields(
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match api_service::get_important_field_settings_data(&user, repo.get_ref()) {
+    match api_service::get_important_field_settings_data(&user, &state.repo) {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
@@ -123,16 +442,45 @@ pub async fn api_v1_important_fields(
     }
 }
 
+#[get("/v1/settings")]
+/// Return the hub settings for admins.
+pub async fn api_v1_settings(ApiUser(user): ApiUser, state: web::Data<AppState>) -> impl Responder {
+    match api_service::get_hub_settings_data(&user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM hub settings: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/v1/dashboard", guard = "hub_dashboard_enabled")]
+/// Return hub dashboard counters for admins.
+pub async fn api_v1_dashboard(
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match api_service::get_hub_dashboard_dto(&user, &state.repo) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to load CRM hub dashboard data: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[get("/v1/no-access")]
 /// Return page data for the CRM no-access page.
 pub async fn api_v1_no_access(
     query: web::Query<NoAccessQuery>,
-    user: AuthenticatedUser,
-    common_config: web::Data<CommonServerConfig>,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     HttpResponse::Ok().json(api_service::get_no_access_data(
         &user,
-        common_config.get_ref(),
+        &state.common_config,
         query.required_role.as_deref(),
     ))
 }
@@ -143,12 +491,44 @@ pub async fn api_v1_no_access(
 /// Users without either the `crm` or `crm_admin` role receive a
 /// `401 Unauthorized` response.
 pub async fn api_v1_clients(
-    params: web::Query<ClientsQuery>,
-    user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    req: HttpRequest,
+    ApiUser(user): ApiUser,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match api_service::list_clients(params.into_inner(), &user, repo.get_ref()) {
-        Ok(response) => HttpResponse::Ok().json(response.clients),
+    // Parsed with `serde_html_form` so repeated `field`/`value` pairs collect.
+    let params: ClientsQuery = match serde_html_form::from_str(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => {
+            log::error!("Invalid clients query: {err}");
+            return HttpResponse::BadRequest().finish();
+        }
+    };
+
+    let page = params.page;
+    let keyset = params.after_id.is_some();
+
+    match api_service::list_clients(params, &user, &state.repo) {
+        Ok(response) => {
+            let headers = if keyset {
+                cursor_headers(&req, response.total, response.next_cursor)
+            } else {
+                // Without `page` every matching client fits on a single page.
+                let (page, per_page) = match page {
+                    Some(page) => (page, DEFAULT_ITEMS_PER_PAGE),
+                    None => (1, response.total.max(1)),
+                };
+                pagination_headers(&req, page, response.total, per_page)
+            };
+            let mut builder = HttpResponse::Ok();
+            for header in headers {
+                builder.insert_header(header);
+            }
+            builder.json(response.clients)
+        }
+        Err(ServiceError::Form(message)) => {
+            log::warn!("Rejected clients query: {message}");
+            HttpResponse::BadRequest().finish()
+        }
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
             log::error!("Failed to list clients: {err}");
@@ -156,3 +536,145 @@ pub async fn api_v1_clients(
         }
     }
 }
+
+/// Build `X-Total-Count` and RFC 5988 `Link` headers for a paginated list.
+///
+/// Link targets reuse the request path and query, replacing only `page`.
+pub fn pagination_headers(
+    req: &HttpRequest,
+    page: usize,
+    total: usize,
+    per_page: usize,
+) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        HeaderName::from_static("x-total-count"),
+        HeaderValue::from(total),
+    );
+
+    let total_pages = total.div_ceil(per_page.max(1));
+    let page_url = |target: usize| {
+        let mut query: Vec<&str> = req
+            .query_string()
+            .split('&')
+            .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some("page"))
+            .collect();
+        let page_pair = format!("page={target}");
+        query.push(&page_pair);
+        format!("<{}?{}>", req.path(), query.join("&"))
+    };
+
+    let mut links = Vec::new();
+    if page < total_pages {
+        links.push(format!("{}; rel=\"next\"", page_url(page + 1)));
+    }
+    if page > 1 {
+        links.push(format!(
+            "{}; rel=\"prev\"",
+            page_url((page - 1).min(total_pages.max(1)))
+        ));
+    }
+    if !links.is_empty()
+        && let Ok(value) = HeaderValue::from_str(&links.join(", "))
+    {
+        headers.insert(header::LINK, value);
+    }
+
+    headers
+}
+
+/// Build `X-Total-Count`, `X-Next-Cursor` and a `rel="next"` `Link` header
+/// for a keyset page; the cursor headers are omitted on the last page.
+///
+/// The link reuses the request path and query, replacing only `after_id`.
+pub fn cursor_headers(req: &HttpRequest, total: usize, next_cursor: Option<i32>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        HeaderName::from_static("x-total-count"),
+        HeaderValue::from(total),
+    );
+
+    let Some(next_cursor) = next_cursor else {
+        return headers;
+    };
+    headers.insert(
+        HeaderName::from_static("x-next-cursor"),
+        HeaderValue::from(next_cursor),
+    );
+
+    let mut query: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some("after_id"))
+        .collect();
+    let cursor_pair = format!("after_id={next_cursor}");
+    query.push(&cursor_pair);
+    let link = format!("<{}?{}>; rel=\"next\"", req.path(), query.join("&"));
+    if let Ok(value) = HeaderValue::from_str(&link) {
+        headers.insert(header::LINK, value);
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn pagination_headers_link_neighbours_of_a_middle_page() {
+        let req = TestRequest::get()
+            .uri("/api/v1/clients?search=acme&page=2&field=city&value=Oslo")
+            .to_http_request();
+
+        let headers = pagination_headers(&req, 2, 25, 10);
+
+        assert_eq!(headers.get("x-total-count").unwrap(), "25");
+        assert_eq!(
+            headers.get(header::LINK).unwrap(),
+            "</api/v1/clients?search=acme&field=city&value=Oslo&page=3>; rel=\"next\", \
+             </api/v1/clients?search=acme&field=city&value=Oslo&page=1>; rel=\"prev\""
+        );
+    }
+
+    #[test]
+    fn pagination_headers_omit_link_for_a_single_page() {
+        let req = TestRequest::get().uri("/api/v1/clients").to_http_request();
+
+        let headers = pagination_headers(&req, 1, 3, 10);
+
+        assert_eq!(headers.get("x-total-count").unwrap(), "3");
+        assert!(headers.get(header::LINK).is_none());
+    }
+
+    #[test]
+    fn cursor_headers_link_the_next_keyset_page() {
+        let req = TestRequest::get()
+            .uri("/api/v1/clients?after_id=20&search=acme")
+            .to_http_request();
+
+        let headers = cursor_headers(&req, 75, Some(40));
+
+        assert_eq!(headers.get("x-total-count").unwrap(), "75");
+        assert_eq!(headers.get("x-next-cursor").unwrap(), "40");
+        assert_eq!(
+            headers.get(header::LINK).unwrap(),
+            "</api/v1/clients?search=acme&after_id=40>; rel=\"next\""
+        );
+    }
+
+    #[test]
+    fn cursor_headers_omit_cursor_on_last_page() {
+        let req = TestRequest::get()
+            .uri("/api/v1/clients?after_id=20")
+            .to_http_request();
+
+        let headers = cursor_headers(&req, 25, None);
+
+        assert_eq!(headers.get("x-total-count").unwrap(), "25");
+        assert!(headers.get("x-next-cursor").is_none());
+        assert!(headers.get(header::LINK).is_none());
+    }
+}
@@ -0,0 +1,77 @@
+//! `X-API-Key` authentication for server-to-server calls to the `/api` scope.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, web};
+use pushkind_common::domain::auth::AuthenticatedUser;
+
+use crate::services::{ServiceError, api as api_service};
+use crate::state::AppState;
+
+/// Header carrying the raw API key.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Authenticates requests carrying [`API_KEY_HEADER`].
+///
+/// A valid key stores the user it acts as for [`ApiUser`]; an invalid key is
+/// rejected with `401`. Requests without the header fall through to the
+/// session-based authentication.
+pub async fn api_key_auth<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let Some(header) = req.headers().get(API_KEY_HEADER) else {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body);
+    };
+    let raw_key = header.to_str().unwrap_or_default().to_string();
+
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        log::error!("API key authentication requires the application state");
+        return Ok(req
+            .into_response(HttpResponse::InternalServerError().finish())
+            .map_into_right_body());
+    };
+
+    match api_service::authenticate_api_key(&raw_key, &state.repo) {
+        Ok(user) => {
+            req.extensions_mut().insert(user);
+            next.call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        }
+        Err(ServiceError::Unauthorized) => Ok(req
+            .into_response(HttpResponse::Unauthorized().finish())
+            .map_into_right_body()),
+        Err(err) => {
+            log::error!("Failed to authenticate an API key: {err}");
+            Ok(req
+                .into_response(HttpResponse::InternalServerError().finish())
+                .map_into_right_body())
+        }
+    }
+}
+
+/// User of an `/api` request: the API key's integration when
+/// [`api_key_auth`] accepted one, otherwise the session user.
+pub struct ApiUser(pub AuthenticatedUser);
+
+impl FromRequest for ApiUser {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if let Some(user) = req.extensions().get::<AuthenticatedUser>().cloned() {
+            return Box::pin(async move { Ok(ApiUser(user)) });
+        }
+
+        let session_user = AuthenticatedUser::from_request(req, payload);
+        Box::pin(async move { session_user.await.map(ApiUser).map_err(Into::into) })
+    }
+}
@@ -0,0 +1,63 @@
+//! Routes for provisioning and revoking integration API keys.
+
+use actix_web::{HttpResponse, Responder, post, web};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
+
+use crate::domain::types::ApiKeyId;
+use crate::dto::api::{ApiKeyCreatedDto, ApiKeyDto};
+use crate::forms::api_keys::{AddApiKeyForm, AddApiKeyPayload};
+use crate::routes::{MutationResource, mutation_error_response};
+use crate::services::api_keys as api_keys_service;
+use crate::state::AppState;
+
+#[post("/api-keys")]
+/// Create an API key for the current hub; the response carries the raw key.
+///
+/// Only browser sessions reach this route, so an API key cannot mint others.
+pub async fn add_api_key(
+    web::Form(form): web::Form<AddApiKeyForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match AddApiKeyPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid API key data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match api_keys_service::add_api_key(payload, &user, &state.repo) {
+        Ok((api_key, key)) => HttpResponse::Created().json(ApiKeyCreatedDto {
+            message: "Ключ API создан. Сохраните его: повторно он не показывается.".to_string(),
+            api_key: ApiKeyDto::from(&api_key),
+            key,
+        }),
+        Err(err) => {
+            log::error!("Failed to create an API key: {err}");
+            mutation_error_response(MutationResource::ApiKey, &err)
+        }
+    }
+}
+
+#[post("/api-keys/{api_key_id}/delete")]
+/// Revoke an API key of the current hub.
+pub async fn delete_api_key(
+    api_key_id: web::Path<ApiKeyId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let api_key_id = api_key_id.into_inner().get();
+
+    match api_keys_service::delete_api_key(api_key_id, &user, &state.repo) {
+        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Ключ API отозван.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to revoke API key {api_key_id}: {err}");
+            mutation_error_response(MutationResource::ApiKey, &err)
+        }
+    }
+}
@@ -1,7 +1,51 @@
-use actix_web::{HttpRequest, HttpResponse, get};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use actix_web::{HttpRequest, HttpResponse, get, web};
 use pushkind_common::domain::auth::AuthenticatedUser;
+use serde_json::json;
 
 use crate::frontend::{FrontendAssetError, open_frontend_html};
+use crate::repository::ServiceStats;
+use crate::services::ServiceResult;
+use crate::services::metrics as metrics_service;
+use crate::state::AppState;
+
+/// How long the database gauges served by [`metrics`] are reused.
+const METRICS_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Process-wide counters and cached database gauges exported on `/metrics`.
+#[derive(Debug, Default)]
+pub struct MetricsState {
+    /// Outbound emails enqueued by `add_comment` since the process started.
+    pub emails_enqueued: AtomicU64,
+    cached_stats: Mutex<Option<(Instant, ServiceStats)>>,
+}
+
+impl MetricsState {
+    /// Returns the cached stats, reloading them with `load` once they are
+    /// older than [`METRICS_CACHE_TTL`].
+    fn stats(
+        &self,
+        load: impl FnOnce() -> ServiceResult<ServiceStats>,
+    ) -> ServiceResult<ServiceStats> {
+        let mut cached = self
+            .cached_stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some((loaded_at, stats)) = *cached
+            && loaded_at.elapsed() < METRICS_CACHE_TTL
+        {
+            return Ok(stats);
+        }
+
+        let stats = load()?;
+        *cached = Some((Instant::now(), stats));
+        Ok(stats)
+    }
+}
 
 #[get("/na")]
 pub async fn not_assigned(request: HttpRequest, _user: AuthenticatedUser) -> HttpResponse {
@@ -17,3 +61,57 @@ pub async fn not_assigned(request: HttpRequest, _user: AuthenticatedUser) -> Htt
         }
     }
 }
+
+#[get("/metrics")]
+/// Expose service counters in the Prometheus text format; unauthenticated.
+pub async fn metrics(metrics: web::Data<MetricsState>, state: web::Data<AppState>) -> HttpResponse {
+    match metrics.stats(|| metrics_service::load_service_stats(&state.repo)) {
+        Ok(stats) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(metrics_service::render_prometheus(
+                &stats,
+                metrics.emails_enqueued.load(Ordering::Relaxed),
+            )),
+        Err(err) => {
+            log::error!("Failed to load service metrics: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/health")]
+/// Liveness probe checking that the database is reachable; unauthenticated.
+///
+/// Answers `503` with an empty body when the check fails; the cause is only
+/// logged.
+pub async fn health(state: web::Data<AppState>) -> HttpResponse {
+    match state.repo.health_check() {
+        Ok(()) => HttpResponse::Ok().json(json!({ "status": "ok" })),
+        Err(err) => {
+            log::error!("Database health check failed: {err}");
+            HttpResponse::ServiceUnavailable().finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_state_reuses_fresh_stats() {
+        let state = MetricsState::default();
+        let stats = ServiceStats {
+            total_clients: 1,
+            total_managers: 1,
+            recent_events: 0,
+        };
+
+        assert_eq!(state.stats(|| Ok(stats)).expect("stats load"), stats);
+        let cached = state
+            .stats(|| panic!("fresh stats must not be reloaded"))
+            .expect("cached stats");
+
+        assert_eq!(cached, stats);
+    }
+}
@@ -1,32 +1,39 @@
 //! Actix routes for client CRUD interactions.
 
-use std::sync::Arc;
-
+use actix_multipart::form::MultipartForm;
+use actix_web::http::header;
 use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
 use pushkind_common::routes::redirect;
-use pushkind_common::zmq::ZmqSender;
 
+use crate::domain::types::{ClientId, ClientNoteId, MaskedEmail};
 use crate::forms::client::{
-    AddAttachmentForm, AddAttachmentPayload, AddCommentForm, AddCommentPayload, SaveClientForm,
-    SaveClientPayload,
+    AddAttachmentForm, AddAttachmentPayload, AddCommentPayload, AddCommentUploadForm, AddNoteForm,
+    AddNotePayload, SaveClientForm, SaveClientPayload,
 };
 use crate::frontend::{FrontendAssetError, open_frontend_html};
-use crate::repository::DieselRepository;
+use crate::routes::aux::MetricsState;
+use crate::routes::rate_limit::CommentRateLimiter;
+use crate::routes::streaming::stream_blocking;
 use crate::routes::{MutationResource, mutation_error_response};
-use crate::services::{ServiceError, client as client_service};
+use crate::services::{ServiceError, client as client_service, export as export_service};
+use crate::state::AppState;
+
+/// Shown when a client save was based on an outdated version of the client.
+const STALE_CLIENT_MESSAGE: &str =
+    "Клиент был изменён другим пользователем. Обновите страницу и повторите попытку.";
 
 #[get("/client/{client_id}")]
 /// Render the detail page for a single client, including events and attachments.
 pub async fn show_client(
     request: HttpRequest,
-    client_id: web::Path<i32>,
+    client_id: web::Path<ClientId>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    let client_id = client_id.into_inner();
-    let repo = repo.get_ref();
+    let client_id = client_id.into_inner().get();
+    let repo = &state.repo;
 
     match client_service::verify_client_page_access(client_id, &user, repo) {
         Ok(_) => match open_frontend_html("assets/dist/app/client.html").await {
@@ -52,15 +59,45 @@ pub async fn show_client(
     }
 }
 
+#[get("/client/{client_id}/events/export")]
+/// Download the client's events as CSV, oldest first, for audits and hand-offs.
+pub async fn export_client_events(
+    client_id: web::Path<ClientId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let client_id = client_id.into_inner().get();
+
+    let repo = state.repo.clone();
+    let export = move |writer: &mut dyn std::io::Write| {
+        export_service::export_client_events(client_id, &user, &repo, writer)
+    };
+    match stream_blocking(export).await {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"client-{client_id}-events.csv\""),
+            ))
+            .body(body),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(ServiceError::NotFound) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to export events of client {client_id}: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[post("/client/{client_id}/save")]
 /// Persist updates to a client's profile submitted from the client form.
 pub async fn save_client(
-    client_id: web::Path<i32>,
+    client_id: web::Path<ClientId>,
     form: web::Bytes,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    let repo = repo.get_ref();
+    let repo = &state.repo;
 
     let form: SaveClientForm = match serde_html_form::from_bytes(&form) {
         Ok(form) => form,
@@ -70,7 +107,7 @@ pub async fn save_client(
         }
     };
 
-    let client_id = client_id.into_inner();
+    let client_id = client_id.into_inner().get();
     let payload = match SaveClientPayload::try_from(form) {
         Ok(payload) => payload,
         Err(error) => {
@@ -80,9 +117,13 @@ pub async fn save_client(
     };
 
     match client_service::save_client(client_id, payload, &user, repo) {
-        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
-            message: "Клиент обновлен.".to_string(),
-            redirect_to: None,
+        Ok(outcome) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: outcome.message,
+            redirect_to: Some(outcome.redirect_to),
+        }),
+        Err(ServiceError::Conflict) => HttpResponse::Conflict().json(ApiMutationErrorDto {
+            message: STALE_CLIENT_MESSAGE.to_string(),
+            field_errors: Vec::new(),
         }),
         Err(err) => {
             log::error!("Failed to update client {client_id}: {err}");
@@ -93,16 +134,33 @@ pub async fn save_client(
 
 #[post("/client/{client_id}/comment")]
 /// Queue a new comment event for the client via the ZMQ sender.
+///
+/// Accepts `multipart/form-data` so email comments may carry an uploaded document.
 pub async fn comment_client(
-    client_id: web::Path<i32>,
-    web::Form(form): web::Form<AddCommentForm>,
+    client_id: web::Path<ClientId>,
+    MultipartForm(form): MultipartForm<AddCommentUploadForm>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
-    zmq_sender: web::Data<Arc<ZmqSender>>,
+    state: web::Data<AppState>,
+    rate_limiter: web::Data<CommentRateLimiter>,
+    metrics: web::Data<MetricsState>,
 ) -> impl Responder {
-    let repo = repo.get_ref();
-    let client_id = client_id.into_inner();
-    let sender = zmq_sender.get_ref().as_ref();
+    let repo = &state.repo;
+    let client_id = client_id.into_inner().get();
+    let sender = state.zmq_sender.as_ref();
+
+    if let Err(retry_after) = rate_limiter.check(&user.email, client_id) {
+        log::info!(
+            "Comment rate limit exceeded for {} on client {client_id}",
+            MaskedEmail::new(&user.email)
+        );
+        return HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.as_secs().max(1).to_string()))
+            .json(ApiMutationErrorDto {
+                message: "Слишком много событий, повторите попытку позже.".to_string(),
+                field_errors: Vec::new(),
+            });
+    }
+
     let payload = match AddCommentPayload::try_from(form) {
         Ok(payload) => payload,
         Err(error) => {
@@ -111,10 +169,20 @@ pub async fn comment_client(
         }
     };
 
-    match client_service::add_comment(client_id, payload, &user, repo, sender).await {
-        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
-            message: "Событие добавлено.".to_string(),
-            redirect_to: None,
+    match client_service::add_comment(
+        client_id,
+        payload,
+        &user,
+        repo,
+        sender,
+        &state.webhook_dispatcher,
+        &metrics.emails_enqueued,
+    )
+    .await
+    {
+        Ok(outcome) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: outcome.message,
+            redirect_to: Some(outcome.redirect_to),
         }),
         Err(err) => {
             log::error!("Failed to add comment for client {client_id}: {err}");
@@ -126,13 +194,13 @@ pub async fn comment_client(
 #[post("/client/{client_id}/attachment")]
 /// Upload and associate an attachment with the given client.
 pub async fn attachment_client(
-    client_id: web::Path<i32>,
+    client_id: web::Path<ClientId>,
     web::Form(form): web::Form<AddAttachmentForm>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    let repo = repo.get_ref();
-    let client_id = client_id.into_inner();
+    let repo = &state.repo;
+    let client_id = client_id.into_inner().get();
     let payload = match AddAttachmentPayload::try_from(form) {
         Ok(payload) => payload,
         Err(error) => {
@@ -141,10 +209,11 @@ pub async fn attachment_client(
         }
     };
 
-    match client_service::add_attachment(client_id, payload, &user, repo) {
-        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
-            message: "Событие добавлено.".to_string(),
-            redirect_to: None,
+    match client_service::add_attachment(client_id, payload, &user, repo, &state.webhook_dispatcher)
+    {
+        Ok(outcome) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: outcome.message,
+            redirect_to: Some(outcome.redirect_to),
         }),
         Err(err) => {
             log::error!("Failed to add attachment for client {client_id}: {err}");
@@ -152,3 +221,55 @@ pub async fn attachment_client(
         }
     }
 }
+
+#[post("/client/{client_id}/note")]
+/// Add an internal note to the client.
+pub async fn note_client(
+    client_id: web::Path<ClientId>,
+    web::Form(form): web::Form<AddNoteForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let repo = &state.repo;
+    let client_id = client_id.into_inner().get();
+    let payload = match AddNotePayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid note data for client {client_id}: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match client_service::add_note(client_id, payload, &user, repo) {
+        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Заметка добавлена.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to add note for client {client_id}: {err}");
+            mutation_error_response(MutationResource::Client, &err)
+        }
+    }
+}
+
+#[post("/client/{client_id}/note/{note_id}/pin")]
+/// Toggle the pinned flag of a client's internal note.
+pub async fn pin_client_note(
+    path: web::Path<(ClientId, ClientNoteId)>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let repo = &state.repo;
+    let (client_id, note_id) = path.into_inner();
+
+    match client_service::toggle_note_pin(client_id.get(), note_id.get(), &user, repo) {
+        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Заметка обновлена.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to toggle note {note_id} for client {client_id}: {err}");
+            mutation_error_response(MutationResource::ClientNote, &err)
+        }
+    }
+}
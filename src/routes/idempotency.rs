@@ -0,0 +1,147 @@
+//! Short-lived idempotency tokens guarding the add-client form against repeats.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::dto::api::AddClientCreatedDto;
+
+/// How long a submitted token is remembered.
+pub const TOKEN_TTL_SECONDS: u64 = 600;
+
+/// What a repeated submission of an already seen token should receive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeenToken {
+    /// The first submission is still being processed.
+    Pending,
+    /// The first submission succeeded with this response.
+    Completed(AddClientCreatedDto),
+}
+
+type TokenKey = (i32, String, String);
+
+/// Remembers add-client idempotency tokens per user.
+///
+/// Tokens are keyed by `(hub id, user email, token)`, so one user cannot
+/// replay another user's submission.
+#[derive(Debug, Default)]
+pub struct SeenTokens {
+    state: Mutex<HashMap<TokenKey, (Instant, Option<AddClientCreatedDto>)>>,
+}
+
+impl SeenTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserves `token` for the user, or returns how it was seen before.
+    pub fn begin(&self, hub_id: i32, email: &str, token: &str) -> Option<SeenToken> {
+        let mut guard = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        Self::begin_with_state(hub_id, email, token, Instant::now(), &mut guard)
+    }
+
+    /// Stores the response replayed to later submissions of `token`.
+    pub fn complete(&self, hub_id: i32, email: &str, token: &str, response: AddClientCreatedDto) {
+        let mut guard = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(entry) = guard.get_mut(&token_key(hub_id, email, token)) {
+            entry.1 = Some(response);
+        }
+    }
+
+    /// Forgets `token` so a failed submission can be retried.
+    pub fn release(&self, hub_id: i32, email: &str, token: &str) {
+        let mut guard = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        guard.remove(&token_key(hub_id, email, token));
+    }
+
+    fn begin_with_state(
+        hub_id: i32,
+        email: &str,
+        token: &str,
+        now: Instant,
+        state: &mut HashMap<TokenKey, (Instant, Option<AddClientCreatedDto>)>,
+    ) -> Option<SeenToken> {
+        let ttl = Duration::from_secs(TOKEN_TTL_SECONDS);
+        state.retain(|_, (seen_at, _)| now.saturating_duration_since(*seen_at) < ttl);
+
+        let key = token_key(hub_id, email, token);
+        if let Some((_, response)) = state.get(&key) {
+            return Some(match response {
+                Some(response) => SeenToken::Completed(response.clone()),
+                None => SeenToken::Pending,
+            });
+        }
+
+        state.insert(key, (now, None));
+        None
+    }
+}
+
+fn token_key(hub_id: i32, email: &str, token: &str) -> TokenKey {
+    (hub_id, email.to_lowercase(), token.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created() -> AddClientCreatedDto {
+        AddClientCreatedDto {
+            message: "Клиент добавлен.".to_string(),
+            redirect_to: None,
+            warning: None,
+        }
+    }
+
+    #[test]
+    fn seen_tokens_replay_the_first_response_to_the_same_user() {
+        let tokens = SeenTokens::new();
+
+        assert_eq!(tokens.begin(1, "user@example.com", "abc"), None);
+        assert_eq!(
+            tokens.begin(1, "user@example.com", "abc"),
+            Some(SeenToken::Pending)
+        );
+
+        tokens.complete(1, "user@example.com", "abc", created());
+        assert_eq!(
+            tokens.begin(1, "USER@example.com", "abc"),
+            Some(SeenToken::Completed(created()))
+        );
+        assert_eq!(tokens.begin(1, "other@example.com", "abc"), None);
+        assert_eq!(tokens.begin(2, "user@example.com", "abc"), None);
+    }
+
+    #[test]
+    fn seen_tokens_forget_released_and_expired_tokens() {
+        let tokens = SeenTokens::new();
+
+        assert_eq!(tokens.begin(1, "user@example.com", "abc"), None);
+        tokens.release(1, "user@example.com", "abc");
+        assert_eq!(tokens.begin(1, "user@example.com", "abc"), None);
+
+        let mut state = HashMap::new();
+        let now = Instant::now();
+        assert_eq!(
+            SeenTokens::begin_with_state(1, "user@example.com", "abc", now, &mut state),
+            None
+        );
+        let later = now + Duration::from_secs(TOKEN_TTL_SECONDS);
+        assert_eq!(
+            SeenTokens::begin_with_state(1, "user@example.com", "abc", later, &mut state),
+            None
+        );
+    }
+}
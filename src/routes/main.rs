@@ -1,17 +1,26 @@
 //! Routes for the main dashboard and uploads.
 
 use actix_multipart::form::MultipartForm;
-use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, Responder, get, post, rt, web};
 use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
+use pushkind_common::dto::mutation::ApiMutationErrorDto;
 use pushkind_common::routes::{check_role, redirect};
 
-use crate::SERVICE_ACCESS_ROLE;
-use crate::forms::main::{AddClientForm, AddClientPayload, UploadClientsForm};
+use crate::dto::api::{AddClientCreatedDto, ImportJobAcceptedDto};
+use crate::dto::export::ExportQuery;
+use crate::forms::main::{AddClientForm, UploadClientsForm};
 use crate::frontend::{FrontendAssetError, open_frontend_html};
-use crate::repository::DieselRepository;
-use crate::routes::{MutationResource, mutation_error_response};
+use crate::routes::idempotency::{SeenToken, SeenTokens};
+use crate::routes::{
+    MutationResource, hub_dashboard_enabled, mutation_error_response, xlsx_export_enabled,
+};
+use crate::services::ServiceError;
+use crate::services::export as export_service;
 use crate::services::main as main_service;
+use crate::services::main::ImportProgress;
+use crate::state::AppState;
+use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE};
 
 #[get("/")]
 /// Display the dashboard listing clients with optional search/pagination.
@@ -33,14 +42,47 @@ pub async fn show_index(request: HttpRequest, user: AuthenticatedUser) -> impl R
     }
 }
 
+#[get("/dashboard", guard = "hub_dashboard_enabled")]
+/// Display the admin overview of hub-wide client and event counters.
+pub async fn show_dashboard(request: HttpRequest, user: AuthenticatedUser) -> impl Responder {
+    if !check_role(SERVICE_ADMIN_ROLE, &user.roles) {
+        return redirect("/na?required_role=crm_admin");
+    }
+
+    match open_frontend_html("assets/dist/app/hub-dashboard.html").await {
+        Ok(file) => file.into_response(&request),
+        Err(FrontendAssetError::Read(error)) if error.kind() == std::io::ErrorKind::NotFound => {
+            HttpResponse::ServiceUnavailable()
+                .body("CRM frontend assets are not built yet. Run `cd frontend && npm run build`.")
+        }
+        Err(error) => {
+            log::error!("Failed to open CRM hub dashboard document: {error}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 #[post("/client/add")]
 /// Handle client creation requests submitted from the dashboard.
+///
+/// A repeated `idempotency_token` replays the first response instead of
+/// inserting the client again.
 pub async fn add_client(
     web::Form(form): web::Form<AddClientForm>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
+    seen_tokens: web::Data<SeenTokens>,
 ) -> impl Responder {
-    let payload = match AddClientPayload::try_from(form) {
+    let default_region = match main_service::load_default_phone_region(&user, &state.repo) {
+        Ok(region) => region,
+        Err(err) => {
+            log::error!("Failed to load the hub phone region: {err}");
+            return mutation_error_response(MutationResource::Client, &err);
+        }
+    };
+
+    let token = form.idempotency_token.clone();
+    let payload = match form.into_payload(default_region.as_ref()) {
         Ok(payload) => payload,
         Err(error) => {
             log::error!("Invalid add-client data: {error}");
@@ -48,12 +90,32 @@ pub async fn add_client(
         }
     };
 
-    match main_service::add_client(payload, &user, repo.get_ref()) {
-        Ok(()) => HttpResponse::Created().json(ApiMutationSuccessDto {
-            message: "Клиент добавлен.".to_string(),
-            redirect_to: None,
-        }),
+    if let Some(token) = token.as_deref() {
+        match seen_tokens.begin(user.hub_id, &user.email, token) {
+            None => {}
+            Some(SeenToken::Completed(response)) => return HttpResponse::Created().json(response),
+            Some(SeenToken::Pending) => {
+                return mutation_error_response(MutationResource::Client, &ServiceError::Conflict);
+            }
+        }
+    }
+
+    match main_service::add_client(payload, &user, &state.repo) {
+        Ok(outcome) => {
+            let response = AddClientCreatedDto {
+                message: outcome.message,
+                redirect_to: Some(outcome.redirect_to),
+                warning: outcome.warning,
+            };
+            if let Some(token) = token.as_deref() {
+                seen_tokens.complete(user.hub_id, &user.email, token, response.clone());
+            }
+            HttpResponse::Created().json(response)
+        }
         Err(err) => {
+            if let Some(token) = token.as_deref() {
+                seen_tokens.release(user.hub_id, &user.email, token);
+            }
             log::error!("Failed to add a client: {err}");
             mutation_error_response(MutationResource::Client, &err)
         }
@@ -61,20 +123,92 @@ pub async fn add_client(
 }
 
 #[post("/clients/upload")]
-/// Accept a multipart upload of clients and delegate bulk import logic.
+/// Accept a multipart upload of clients and import it in the background.
+///
+/// Responds with `202 Accepted` and the import job ID; progress is polled via
+/// `/api/v1/import/{id}/status`.
 pub async fn clients_upload(
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
+    import_progress: web::Data<ImportProgress>,
     MultipartForm(mut form): MultipartForm<UploadClientsForm>,
 ) -> impl Responder {
-    match main_service::upload_clients(&mut form, &user, repo.get_ref()) {
-        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
-            message: "Клиенты добавлены.".to_string(),
-            redirect_to: None,
-        }),
+    let job = match main_service::start_clients_import(&user, &state.repo) {
+        Ok(job) => job,
+        Err(err) => {
+            log::error!("Failed to start client import: {err}");
+            return mutation_error_response(MutationResource::ClientImport, &err);
+        }
+    };
+    let job_id = job.id.get();
+
+    let repo = state.repo.clone();
+    rt::task::spawn_blocking(move || {
+        if let Err(err) =
+            main_service::run_clients_import(job, &mut form, &user, &repo, &import_progress)
+        {
+            log::error!("Failed to record client import job {job_id}: {err}");
+        }
+    });
+
+    HttpResponse::Accepted().json(ImportJobAcceptedDto {
+        message: "Загрузка клиентов запущена.".to_string(),
+        redirect_to: None,
+        job_id,
+    })
+}
+
+#[get("/clients/upload-template")]
+/// Download a CSV template for the clients upload with the hub's important fields.
+pub async fn clients_upload_template(
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match main_service::build_upload_template(&user, &state.repo) {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"clients-template.csv\"",
+            ))
+            .body(bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to build clients upload template: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/clients/export.xlsx", guard = "xlsx_export_enabled")]
+/// Download the hub's clients (and optionally their events) as an XLSX workbook.
+pub async fn clients_export_xlsx(
+    params: web::Query<ExportQuery>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let query = params.into_inner();
+    let repo = state.repo.clone();
+    // Paging the clients and building the workbook blocks, so keep it off the
+    // async workers.
+    let export = web::block(move || export_service::export_clients_xlsx(query, &user, &repo))
+        .await
+        .unwrap_or_else(|err| {
+            log::error!("Clients export task failed: {err}");
+            Err(ServiceError::Internal)
+        });
+    match export {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"clients.xlsx\"",
+            ))
+            .body(bytes),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
         Err(err) => {
-            log::error!("Failed to add clients: {err}");
-            mutation_error_response(MutationResource::ClientImport, &err)
+            log::error!("Failed to export clients: {err}");
+            HttpResponse::InternalServerError().finish()
         }
     }
 }
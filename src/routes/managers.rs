@@ -1,26 +1,28 @@
 //! Routes that manage manager assignments.
 
-use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
+use actix_multipart::form::MultipartForm;
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, post, web};
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
 use pushkind_common::routes::{check_role, redirect};
 
 use crate::SERVICE_ADMIN_ROLE;
+use crate::domain::types::{ManagerId, RuleId};
+use crate::dto::managers::ManagerAssignmentRuleDto;
 use crate::forms::managers::{
     AddManagerForm, AddManagerPayload, AssignManagerForm, AssignManagerPayload,
+    NewManagerAssignmentRuleForm, NewManagerAssignmentRulePayload, SetManagerActiveForm,
+    SetManagerActivePayload, TransferClientsForm, TransferClientsPayload, UpdateOwnManagerForm,
+    UpdateOwnManagerPayload, UploadManagerAssignmentsForm,
 };
 use crate::frontend::{FrontendAssetError, open_frontend_html};
-use crate::repository::DieselRepository;
 use crate::routes::{MutationResource, mutation_error_response};
-use crate::services::managers as managers_service;
+use crate::services::{ServiceError, managers as managers_service};
+use crate::state::AppState;
 
 #[get("/managers")]
 /// Render the managers list page, showing assignments and controls.
-pub async fn managers(
-    request: HttpRequest,
-    user: AuthenticatedUser,
-    _repo: web::Data<DieselRepository>,
-) -> impl Responder {
+pub async fn managers(request: HttpRequest, user: AuthenticatedUser) -> impl Responder {
     if !check_role(SERVICE_ADMIN_ROLE, &user.roles) {
         return redirect("/na?required_role=crm_admin");
     }
@@ -43,7 +45,7 @@ pub async fn managers(
 pub async fn add_manager(
     web::Form(form): web::Form<AddManagerForm>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     let payload = match AddManagerPayload::try_from(form) {
         Ok(payload) => payload,
@@ -53,10 +55,10 @@ pub async fn add_manager(
         }
     };
 
-    match managers_service::add_manager(payload, &user, repo.get_ref()) {
-        Ok(()) => HttpResponse::Created().json(ApiMutationSuccessDto {
-            message: "Менеджер добавлен.".to_string(),
-            redirect_to: None,
+    match managers_service::add_manager(payload, &user, &state.repo) {
+        Ok(outcome) => HttpResponse::Created().json(ApiMutationSuccessDto {
+            message: outcome.message,
+            redirect_to: Some(outcome.redirect_to),
         }),
         Err(err) => {
             log::error!("Failed to save the manager: {err}");
@@ -70,7 +72,7 @@ pub async fn add_manager(
 pub async fn assign_manager(
     payload: web::Bytes,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     let form: AssignManagerForm = match serde_html_form::from_bytes(&payload) {
         Ok(form) => form,
@@ -88,14 +90,208 @@ pub async fn assign_manager(
         }
     };
 
-    match managers_service::assign_manager(payload, &user, repo.get_ref()) {
+    match managers_service::assign_manager(payload, &user, &state.repo) {
+        Ok(outcome) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: outcome.message,
+            redirect_to: Some(outcome.redirect_to),
+        }),
+        Err(err) => {
+            log::error!("Failed to assign clients to the manager: {err}");
+            mutation_error_response(MutationResource::Manager, &err)
+        }
+    }
+}
+
+#[post("/managers/assign-upload")]
+/// Add manager assignments from an uploaded `client_email,manager_email` CSV file.
+pub async fn upload_manager_assignments(
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+    MultipartForm(mut form): MultipartForm<UploadManagerAssignmentsForm>,
+) -> impl Responder {
+    match managers_service::upload_manager_assignments(&mut form, &user, &state.repo) {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(err) => {
+            log::error!("Failed to upload manager assignments: {err}");
+            mutation_error_response(MutationResource::Manager, &err)
+        }
+    }
+}
+
+#[post("/managers/reassign")]
+/// Reassign every client of a departing manager to another manager of the hub.
+pub async fn reassign_manager_clients(
+    web::Form(form): web::Form<TransferClientsForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match TransferClientsPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid transfer-clients data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match managers_service::transfer_clients_between_managers(
+        payload.from_id,
+        payload.to_id,
+        &user,
+        &state.repo,
+        &state.webhook_dispatcher,
+    ) {
+        Ok(count) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: format!("Передано клиентов: {count}."),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to transfer clients between managers: {err}");
+            mutation_error_response(MutationResource::Manager, &err)
+        }
+    }
+}
+
+#[post("/managers/active")]
+/// Archive or restore a manager of the hub.
+pub async fn set_manager_active(
+    web::Form(form): web::Form<SetManagerActiveForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match SetManagerActivePayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid manager activity data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match managers_service::set_manager_active(payload, &user, &state.repo) {
+        Ok(manager) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: if manager.is_active {
+                "Менеджер восстановлен.".to_string()
+            } else {
+                "Менеджер перенесён в архив.".to_string()
+            },
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to change manager activity: {err}");
+            mutation_error_response(MutationResource::Manager, &err)
+        }
+    }
+}
+
+#[delete("/managers/{manager_id}")]
+/// Delete a manager of the hub, moving their events to the system manager.
+pub async fn delete_manager(
+    manager_id: web::Path<ManagerId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let manager_id = manager_id.into_inner();
+
+    match managers_service::delete_manager(manager_id, &user, &state.repo) {
         Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
-            message: "Менеджер назначен клиентам.".to_string(),
+            message: "Менеджер удалён.".to_string(),
             redirect_to: None,
         }),
         Err(err) => {
-            log::error!("Failed to assign clients to the manager: {err}");
+            log::error!("Failed to delete manager {manager_id}: {err}");
+            mutation_error_response(MutationResource::Manager, &err)
+        }
+    }
+}
+
+#[post("/profile")]
+/// Update the display name and phone of the current manager.
+pub async fn update_profile(
+    web::Form(form): web::Form<UpdateOwnManagerForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match UpdateOwnManagerPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid profile data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match managers_service::update_own_manager(payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Профиль сохранён.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to update the manager profile: {err}");
             mutation_error_response(MutationResource::Manager, &err)
         }
     }
 }
+
+#[get("/managers/rules")]
+/// Return the manager assignment rules of the hub.
+pub async fn manager_rules(user: AuthenticatedUser, state: web::Data<AppState>) -> impl Responder {
+    match managers_service::list_assignment_rules(&user, &state.repo) {
+        Ok(rules) => HttpResponse::Ok().json(
+            rules
+                .iter()
+                .map(ManagerAssignmentRuleDto::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized().finish(),
+        Err(err) => {
+            log::error!("Failed to list assignment rules: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/managers/rules")]
+/// Add a rule assigning a manager to clients with a custom field value.
+pub async fn add_manager_rule(
+    web::Form(form): web::Form<NewManagerAssignmentRuleForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match NewManagerAssignmentRulePayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid assignment rule data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match managers_service::create_assignment_rule(payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Created().json(ApiMutationSuccessDto {
+            message: "Правило добавлено.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to add an assignment rule: {err}");
+            mutation_error_response(MutationResource::AssignmentRule, &err)
+        }
+    }
+}
+
+#[delete("/managers/rules/{rule_id}")]
+/// Remove a manager assignment rule of the hub.
+pub async fn delete_manager_rule(
+    rule_id: web::Path<RuleId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let rule_id = rule_id.into_inner().get();
+
+    match managers_service::delete_assignment_rule(rule_id, &user, &state.repo) {
+        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Правило удалено.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to delete assignment rule {rule_id}: {err}");
+            mutation_error_response(MutationResource::AssignmentRule, &err)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! `Content-Security-Policy` header for the HTML pages.
+
+use std::future::{Future, Ready, ready};
+use std::pin::Pin;
+
+use actix_web::Error;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready};
+use actix_web::http::header::{
+    CONTENT_SECURITY_POLICY, CONTENT_TYPE, HeaderMap, HeaderValue, InvalidHeaderValue,
+};
+
+/// Adds a `Content-Security-Policy` header to every `text/html` response.
+///
+/// JSON, CSV and static asset responses pass through unchanged.
+#[derive(Clone, Debug)]
+pub struct CspMiddleware {
+    policy: HeaderValue,
+}
+
+impl CspMiddleware {
+    /// Fails when `policy` cannot be sent as a header value.
+    pub fn new(policy: &str) -> Result<Self, InvalidHeaderValue> {
+        Ok(Self {
+            policy: HeaderValue::from_str(policy)?,
+        })
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CspMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = CspService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CspService {
+            service,
+            policy: self.policy.clone(),
+        }))
+    }
+}
+
+/// Service produced by [`CspMiddleware`].
+pub struct CspService<S> {
+    service: S,
+    policy: HeaderValue,
+}
+
+impl<S, B> Service<ServiceRequest> for CspService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let policy = self.policy.clone();
+        let response = self.service.call(req);
+
+        Box::pin(async move {
+            let mut response = response.await?;
+            if is_html(response.headers()) {
+                response
+                    .headers_mut()
+                    .insert(CONTENT_SECURITY_POLICY, policy);
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpResponse, test, web};
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn policy_is_only_added_to_html_responses() {
+        let app = test::init_service(
+            App::new()
+                .wrap(CspMiddleware::new("default-src 'self'").expect("valid policy"))
+                .route(
+                    "/page",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .body("<p>page</p>")
+                    }),
+                )
+                .route(
+                    "/data",
+                    web::get().to(|| async { HttpResponse::Ok().json(["data"]) }),
+                ),
+        )
+        .await;
+
+        let page =
+            test::call_service(&app, test::TestRequest::get().uri("/page").to_request()).await;
+        assert_eq!(
+            page.headers().get(CONTENT_SECURITY_POLICY),
+            Some(&HeaderValue::from_static("default-src 'self'"))
+        );
+
+        let data =
+            test::call_service(&app, test::TestRequest::get().uri("/data").to_request()).await;
+        assert!(data.headers().get(CONTENT_SECURITY_POLICY).is_none());
+    }
+
+    #[test]
+    fn invalid_policy_is_rejected() {
+        assert!(CspMiddleware::new("default-src 'self'\n").is_err());
+    }
+}
@@ -1,26 +1,68 @@
 //! Route modules wired into the Actix application.
 
-use actix_web::{HttpResponse, http::StatusCode};
+use actix_web::guard::GuardContext;
+use actix_web::{HttpResponse, http::StatusCode, web};
 use pushkind_common::dto::mutation::ApiMutationErrorDto;
 
+use crate::models::config::FeaturesConfig;
 use crate::services::ServiceError;
+use crate::state::AppState;
 
 pub mod api;
+pub mod api_key;
+pub mod api_keys;
 pub mod aux;
 pub mod client;
+pub mod idempotency;
 pub mod main;
 pub mod managers;
+pub mod middleware;
 pub mod rate_limit;
+pub mod segments;
 pub mod settings;
 pub mod store;
+pub mod streaming;
+pub mod webhooks;
+
+/// Matches only while the feature selected by `enabled` is switched on, so
+/// routes of a disabled feature answer `404 Not Found` like unknown paths.
+fn feature_guard(ctx: &GuardContext<'_>, enabled: fn(&FeaturesConfig) -> bool) -> bool {
+    ctx.app_data::<web::Data<AppState>>()
+        .is_some_and(|state| enabled(&state.server_config.features))
+}
+
+/// Route guard for [`FeaturesConfig::xlsx_export`].
+pub fn xlsx_export_enabled(ctx: &GuardContext<'_>) -> bool {
+    feature_guard(ctx, |features| features.xlsx_export)
+}
+
+/// Route guard for [`FeaturesConfig::hub_dashboard`].
+pub fn hub_dashboard_enabled(ctx: &GuardContext<'_>) -> bool {
+    feature_guard(ctx, |features| features.hub_dashboard)
+}
+
+/// Route guard for [`FeaturesConfig::webhooks`].
+pub fn webhooks_enabled(ctx: &GuardContext<'_>) -> bool {
+    feature_guard(ctx, |features| features.webhooks)
+}
+
+/// Route guard for [`FeaturesConfig::segments`].
+pub fn segments_enabled(ctx: &GuardContext<'_>) -> bool {
+    feature_guard(ctx, |features| features.segments)
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum MutationResource {
+    ApiKey,
+    AssignmentRule,
     Client,
     ClientComment,
     ClientImport,
+    ClientNote,
     Manager,
+    Segment,
     Settings,
+    Webhook,
 }
 
 pub(crate) fn mutation_error_status(err: &ServiceError) -> StatusCode {
@@ -49,8 +91,13 @@ fn mutation_error_dto(resource: MutationResource, err: &ServiceError) -> ApiMuta
         },
         ServiceError::NotFound => ApiMutationErrorDto {
             message: match resource {
+                MutationResource::ApiKey => "Ключ API не найден.",
+                MutationResource::AssignmentRule => "Правило не найдено.",
                 MutationResource::Client | MutationResource::ClientComment => "Клиент не найден.",
+                MutationResource::ClientNote => "Заметка не найдена.",
                 MutationResource::Manager => "Менеджер не найден.",
+                MutationResource::Segment => "Сегмент не найден.",
+                MutationResource::Webhook => "Вебхук не найден.",
                 MutationResource::ClientImport | MutationResource::Settings => "Ресурс не найден.",
             }
             .to_string(),
@@ -1,12 +1,15 @@
-//! IP-based rate limiting helpers for storefront OTP endpoints.
+//! Sliding-window rate limiters for storefront OTP and client comment endpoints.
 
 use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use actix_web::HttpRequest;
 
+use crate::models::config::DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE;
+
 pub const MAX_REQUESTS: u32 = 10;
 pub const WINDOW_SECONDS: u64 = 60;
 pub const TRUST_FORWARDED_HEADERS: bool = false;
@@ -19,15 +22,24 @@ pub struct RateLimitExceeded {
 
 #[derive(Debug)]
 pub struct StoreOtpIpRateLimiter {
-    state: Mutex<RateLimitState>,
+    state: Mutex<RateLimitState<IpAddr>>,
 }
 
-#[derive(Debug, Default)]
-struct RateLimitState {
-    buckets: HashMap<IpAddr, VecDeque<Instant>>,
+#[derive(Debug)]
+struct RateLimitState<K> {
+    buckets: HashMap<K, VecDeque<Instant>>,
     last_global_cleanup: Option<Instant>,
 }
 
+impl<K> Default for RateLimitState<K> {
+    fn default() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            last_global_cleanup: None,
+        }
+    }
+}
+
 impl StoreOtpIpRateLimiter {
     pub fn new() -> Self {
         Self {
@@ -52,36 +64,97 @@ impl StoreOtpIpRateLimiter {
     fn check_ip_with_state(
         ip: IpAddr,
         now: Instant,
-        state: &mut RateLimitState,
+        state: &mut RateLimitState<IpAddr>,
     ) -> Result<(), RateLimitExceeded> {
         let window = Duration::from_secs(WINDOW_SECONDS);
-        let max_requests = MAX_REQUESTS as usize;
 
-        let should_cleanup = state
-            .last_global_cleanup
-            .is_none_or(|last| now.saturating_duration_since(last) >= window);
+        check_key(state, ip, now, window, MAX_REQUESTS as usize)
+            .map_err(|retry_after| RateLimitExceeded { ip, retry_after })
+    }
+}
+
+/// Limits how often a user may post comments to the same client.
+///
+/// Buckets are keyed by `(user email, client id)` and span one minute.
+#[derive(Debug)]
+pub struct CommentRateLimiter {
+    max_requests: usize,
+    state: Mutex<RateLimitState<(String, i32)>>,
+}
 
-        if should_cleanup {
-            cleanup_stale_buckets(state, now, window);
-            state.last_global_cleanup = Some(now);
+impl CommentRateLimiter {
+    pub fn new(max_requests_per_minute: usize) -> Self {
+        Self {
+            max_requests: max_requests_per_minute,
+            state: Mutex::new(RateLimitState::default()),
         }
+    }
 
-        let entries = state.buckets.entry(ip).or_default();
-        prune_bucket(entries, now, window);
+    /// Records a comment attempt and returns the wait time once the limit is hit.
+    pub fn check(&self, email: &str, client_id: i32) -> Result<(), Duration> {
+        let mut guard = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        self.check_with_state(email, client_id, Instant::now(), &mut guard)
+    }
 
-        if entries.len() >= max_requests {
-            let retry_after = entries
-                .front()
-                .map(|&oldest| window.saturating_sub(now.saturating_duration_since(oldest)))
-                .unwrap_or(window);
+    fn check_with_state(
+        &self,
+        email: &str,
+        client_id: i32,
+        now: Instant,
+        state: &mut RateLimitState<(String, i32)>,
+    ) -> Result<(), Duration> {
+        let window = Duration::from_secs(WINDOW_SECONDS);
+        let key = (email.to_lowercase(), client_id);
 
-            return Err(RateLimitExceeded { ip, retry_after });
-        }
+        check_key(state, key, now, window, self.max_requests)
+    }
+}
+
+impl Default for CommentRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE)
+    }
+}
+
+/// Counts a request for `key`, returning the retry delay when the bucket is full.
+fn check_key<K>(
+    state: &mut RateLimitState<K>,
+    key: K,
+    now: Instant,
+    window: Duration,
+    max_requests: usize,
+) -> Result<(), Duration>
+where
+    K: Eq + Hash,
+{
+    let should_cleanup = state
+        .last_global_cleanup
+        .is_none_or(|last| now.saturating_duration_since(last) >= window);
+
+    if should_cleanup {
+        cleanup_stale_buckets(state, now, window);
+        state.last_global_cleanup = Some(now);
+    }
+
+    let entries = state.buckets.entry(key).or_default();
+    prune_bucket(entries, now, window);
 
-        entries.push_back(now);
+    if entries.len() >= max_requests {
+        let retry_after = entries
+            .front()
+            .map(|&oldest| window.saturating_sub(now.saturating_duration_since(oldest)))
+            .unwrap_or(window);
 
-        Ok(())
+        return Err(retry_after);
     }
+
+    entries.push_back(now);
+
+    Ok(())
 }
 
 fn extract_client_ip(req: &HttpRequest) -> Option<IpAddr> {
@@ -101,7 +174,7 @@ impl Default for StoreOtpIpRateLimiter {
     }
 }
 
-fn cleanup_stale_buckets(state: &mut RateLimitState, now: Instant, window: Duration) {
+fn cleanup_stale_buckets<K>(state: &mut RateLimitState<K>, now: Instant, window: Duration) {
     for entries in state.buckets.values_mut() {
         prune_bucket(entries, now, window);
     }
@@ -134,3 +207,40 @@ fn parse_forwarded_ip(raw_value: &str) -> Option<IpAddr> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_limiter_rejects_requests_above_limit_per_client() {
+        let limiter = CommentRateLimiter::new(2);
+        let mut state = RateLimitState::default();
+        let now = Instant::now();
+
+        for _ in 0..2 {
+            assert!(
+                limiter
+                    .check_with_state("user@example.com", 1, now, &mut state)
+                    .is_ok()
+            );
+        }
+        assert!(
+            limiter
+                .check_with_state("USER@example.com", 1, now, &mut state)
+                .is_err()
+        );
+        assert!(
+            limiter
+                .check_with_state("user@example.com", 2, now, &mut state)
+                .is_ok()
+        );
+
+        let later = now + Duration::from_secs(WINDOW_SECONDS);
+        assert!(
+            limiter
+                .check_with_state("user@example.com", 1, later, &mut state)
+                .is_ok()
+        );
+    }
+}
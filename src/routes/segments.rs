@@ -0,0 +1,89 @@
+//! Routes for defining rule-based client segments.
+
+use actix_web::{HttpResponse, Responder, post, web};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
+
+use crate::domain::types::SegmentId;
+use crate::forms::segments::{SaveSegmentForm, SaveSegmentPayload};
+use crate::routes::{MutationResource, mutation_error_response, segments_enabled};
+use crate::services::segments as segments_service;
+use crate::state::AppState;
+
+#[post("/segments", guard = "segments_enabled")]
+/// Define a new client segment.
+pub async fn add_segment(
+    web::Form(form): web::Form<SaveSegmentForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match SaveSegmentPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid segment data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match segments_service::create_segment(payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Created().json(ApiMutationSuccessDto {
+            message: "Сегмент добавлен.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to add a segment: {err}");
+            mutation_error_response(MutationResource::Segment, &err)
+        }
+    }
+}
+
+#[post("/segments/{segment_id}", guard = "segments_enabled")]
+/// Replace the name and filter of a client segment.
+pub async fn update_segment(
+    segment_id: web::Path<SegmentId>,
+    web::Form(form): web::Form<SaveSegmentForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let segment_id = segment_id.into_inner().get();
+
+    let payload = match SaveSegmentPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid segment data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match segments_service::update_segment(segment_id, payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Сегмент обновлён.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to update segment {segment_id}: {err}");
+            mutation_error_response(MutationResource::Segment, &err)
+        }
+    }
+}
+
+#[post("/segments/{segment_id}/delete", guard = "segments_enabled")]
+/// Remove a client segment of the current hub.
+pub async fn delete_segment(
+    segment_id: web::Path<SegmentId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let segment_id = segment_id.into_inner().get();
+
+    match segments_service::delete_segment(segment_id, &user, &state.repo) {
+        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Сегмент удалён.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to delete segment {segment_id}: {err}");
+            mutation_error_response(MutationResource::Segment, &err)
+        }
+    }
+}
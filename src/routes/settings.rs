@@ -1,4 +1,4 @@
-//! Routes for managing important fields in the CRM.
+//! Routes for hub settings and important fields in the CRM.
 
 use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 use pushkind_common::domain::auth::AuthenticatedUser;
@@ -6,19 +6,20 @@ use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto}
 use pushkind_common::routes::{check_role, redirect};
 
 use crate::SERVICE_ADMIN_ROLE;
-use crate::forms::important_fields::{ImportantFieldsForm, ImportantFieldsPayload};
+use crate::domain::types::ImportantFieldName;
+use crate::forms::important_fields::{
+    DeleteImportantFieldForm, ImportantFieldsForm, ImportantFieldsPayload,
+};
+use crate::forms::settings::{HubSettingsForm, HubSettingsPayload};
 use crate::frontend::{FrontendAssetError, open_frontend_html};
-use crate::repository::DieselRepository;
 use crate::routes::{MutationResource, mutation_error_response};
-use crate::services::settings as important_fields_service;
+use crate::services::client as client_service;
+use crate::services::settings as settings_service;
+use crate::state::AppState;
 
 #[get("/settings")]
 /// Show the list of configured important fields for the current user.
-pub async fn show_settings(
-    request: HttpRequest,
-    user: AuthenticatedUser,
-    _repo: web::Data<DieselRepository>,
-) -> impl Responder {
+pub async fn show_settings(request: HttpRequest, user: AuthenticatedUser) -> impl Responder {
     if !check_role(SERVICE_ADMIN_ROLE, &user.roles) {
         return redirect("/na?required_role=crm_admin");
     }
@@ -36,12 +37,39 @@ pub async fn show_settings(
     }
 }
 
+#[post("/settings")]
+/// Save the hub settings.
+pub async fn save_hub_settings(
+    form: web::Form<HubSettingsForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match HubSettingsPayload::try_from(form.into_inner()) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid hub settings data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match settings_service::save_hub_settings(payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Настройки хаба сохранены.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to save hub settings: {err}");
+            mutation_error_response(MutationResource::Settings, &err)
+        }
+    }
+}
+
 #[post("/important-fields")]
 /// Save the posted list of important field names for the user.
 pub async fn save_important_fields(
     form: web::Form<ImportantFieldsForm>,
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     let payload = match ImportantFieldsPayload::try_from(form.into_inner()) {
         Ok(payload) => payload,
@@ -51,7 +79,7 @@ pub async fn save_important_fields(
         }
     };
 
-    match important_fields_service::save_important_fields(payload, &user, repo.get_ref()) {
+    match settings_service::save_important_fields(payload, &user, &state.repo) {
         Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
             message: "Список полей обновлён.".to_string(),
             redirect_to: None,
@@ -63,13 +91,40 @@ pub async fn save_important_fields(
     }
 }
 
+#[post("/important-fields/delete")]
+/// Remove one important field without resubmitting the whole list.
+pub async fn delete_important_field(
+    form: web::Form<DeleteImportantFieldForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let name = match ImportantFieldName::try_from(form.into_inner()) {
+        Ok(name) => name,
+        Err(error) => {
+            log::error!("Invalid important field name: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match settings_service::delete_important_field(&name, &user, &state.repo) {
+        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: format!("Поле «{name}» удалено."),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to delete important field: {err}");
+            mutation_error_response(MutationResource::Settings, &err)
+        }
+    }
+}
+
 #[post("/settings/cleanup")]
 /// Remove all clients and related data for the current hub.
 pub async fn cleanup_clients(
     user: AuthenticatedUser,
-    repo: web::Data<DieselRepository>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    match important_fields_service::cleanup_clients(&user, repo.get_ref()) {
+    match settings_service::cleanup_clients(&user, &state.repo) {
         Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
             message: "Клиенты очищены.".to_string(),
             redirect_to: None,
@@ -80,3 +135,18 @@ pub async fn cleanup_clients(
         }
     }
 }
+
+#[post("/admin/cleanup-events")]
+/// Remove client events older than the hub's configured retention period.
+pub async fn cleanup_events(user: AuthenticatedUser, state: web::Data<AppState>) -> impl Responder {
+    match client_service::cleanup_events_by_hub_settings(&state.repo, &user) {
+        Ok(deleted) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: format!("Удалено старых событий: {deleted}."),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to cleanup client events: {err}");
+            mutation_error_response(MutationResource::Settings, &err)
+        }
+    }
+}
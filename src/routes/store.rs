@@ -1,36 +1,40 @@
 use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 use log::{error, info};
-use pushkind_common::zmq::ZmqSender;
 use serde::Deserialize;
 use serde_json::json;
 
+use crate::domain::types::HubId;
 use crate::forms::store::{StoreOtpRequestPayload, StoreOtpVerifyPayload};
-use crate::models::config::AppConfig;
-use crate::repository::DieselRepository;
 use crate::routes::rate_limit::StoreOtpIpRateLimiter;
 use crate::services::ServiceError;
 use crate::services::store::{
     clear_store_session_cookie, decode_store_session_cookie, request_store_otp, verify_store_otp,
 };
+use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
 struct HubPath {
     hub_id: String,
 }
 
+impl HubPath {
+    /// Returns the raw hub id when the segment is a positive integer.
+    fn hub_id(&self) -> Option<i32> {
+        let hub_id = self.hub_id.parse::<i32>().ok()?;
+        HubId::new(hub_id).ok().map(HubId::get)
+    }
+}
+
 #[post("/{hub_id}/auth/otp")]
 pub async fn request_store_auth_otp(
     req: HttpRequest,
     path: web::Path<HubPath>,
     payload: web::Json<StoreOtpRequestPayload>,
-    repo: web::Data<DieselRepository>,
-    sms_sender: web::Data<ZmqSender>,
-    app_config: web::Data<AppConfig>,
+    state: web::Data<AppState>,
     rate_limiter: web::Data<StoreOtpIpRateLimiter>,
 ) -> impl Responder {
-    let hub_id = match path.into_inner().hub_id.parse::<i32>() {
-        Ok(value) => value,
-        Err(_) => return HttpResponse::BadRequest().finish(),
+    let Some(hub_id) = path.into_inner().hub_id() else {
+        return HttpResponse::BadRequest().finish();
     };
 
     let request = match payload.into_inner().into_request() {
@@ -47,9 +51,9 @@ pub async fn request_store_auth_otp(
     match request_store_otp(
         hub_id,
         request,
-        repo.get_ref(),
-        sms_sender.get_ref(),
-        &app_config.sms_sender,
+        &state.repo,
+        state.sms_sender.as_ref(),
+        &state.app_config.sms_sender,
     )
     .await
     {
@@ -96,12 +100,10 @@ fn rate_limit_otp_response(
 pub async fn verify_store_auth_otp(
     path: web::Path<HubPath>,
     payload: web::Json<StoreOtpVerifyPayload>,
-    repo: web::Data<DieselRepository>,
-    app_config: web::Data<AppConfig>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    let hub_id = match path.into_inner().hub_id.parse::<i32>() {
-        Ok(value) => value,
-        Err(_) => return HttpResponse::BadRequest().finish(),
+    let Some(hub_id) = path.into_inner().hub_id() else {
+        return HttpResponse::BadRequest().finish();
     };
 
     let request = match payload.into_inner().into_request() {
@@ -114,9 +116,9 @@ pub async fn verify_store_auth_otp(
     match verify_store_otp(
         hub_id,
         request,
-        repo.get_ref(),
-        &app_config.secret,
-        &app_config.domain,
+        &state.repo,
+        &state.app_config.secret,
+        &state.app_config.domain,
     ) {
         Ok((response, cookie)) => HttpResponse::Ok().cookie(cookie).json(response),
         Err(ServiceError::Form(message)) => {
@@ -133,22 +135,25 @@ pub async fn verify_store_auth_otp(
 pub async fn get_store_session(
     path: web::Path<HubPath>,
     req: HttpRequest,
-    repo: web::Data<DieselRepository>,
-    app_config: web::Data<AppConfig>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
-    let hub_id = match path.into_inner().hub_id.parse::<i32>() {
-        Ok(value) => value,
-        Err(_) => return HttpResponse::BadRequest().finish(),
+    let Some(hub_id) = path.into_inner().hub_id() else {
+        return HttpResponse::BadRequest().finish();
     };
 
     let Some(cookie) = req.cookie(crate::domain::store_session::STORE_SESSION_COOKIE_NAME) else {
         return HttpResponse::Unauthorized().finish();
     };
 
-    match decode_store_session_cookie(cookie.value(), hub_id, repo.get_ref(), &app_config.secret) {
+    match decode_store_session_cookie(
+        cookie.value(),
+        hub_id,
+        &state.repo,
+        &state.app_config.secret,
+    ) {
         Ok(customer) => HttpResponse::Ok().json(customer),
         Err(ServiceError::Unauthorized) => HttpResponse::Unauthorized()
-            .cookie(clear_store_session_cookie(&app_config.domain))
+            .cookie(clear_store_session_cookie(&state.app_config.domain))
             .finish(),
         Err(err) => {
             error!("Failed to validate CRM store session for hub {hub_id}: {err}");
@@ -160,9 +165,9 @@ pub async fn get_store_session(
 #[post("/{hub_id}/auth/logout")]
 pub async fn logout_store_session(
     _path: web::Path<HubPath>,
-    app_config: web::Data<AppConfig>,
+    state: web::Data<AppState>,
 ) -> impl Responder {
     HttpResponse::Ok()
-        .cookie(clear_store_session_cookie(&app_config.domain))
+        .cookie(clear_store_session_cookie(&state.app_config.domain))
         .json(json!({ "success": true }))
 }
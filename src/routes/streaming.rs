@@ -0,0 +1,142 @@
+//! Response bodies fed by blocking writers running off the async executor.
+
+use std::io::{self, BufWriter, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::{rt, web};
+use tokio::sync::mpsc;
+
+use crate::services::{ServiceError, ServiceResult};
+
+/// Bytes buffered before a chunk is handed to the response.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Chunks queued ahead of a slow client before the writer blocks.
+const CHANNEL_CAPACITY: usize = 16;
+
+type Chunk = ServiceResult<web::Bytes>;
+
+/// Runs `write` on the blocking pool and streams its output as a response body.
+///
+/// Nothing is returned until `write` produces its first chunk or finishes, so
+/// an error raised before any output (such as a failed role check) is returned
+/// here and can still pick the status code. A later error aborts the body.
+pub async fn stream_blocking<F>(write: F) -> ServiceResult<BlockingBody>
+where
+    F: FnOnce(&mut dyn Write) -> ServiceResult<()> + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    rt::task::spawn_blocking(move || {
+        let mut writer = BufWriter::with_capacity(CHUNK_SIZE, ChannelWriter(sender));
+        let result = write(&mut writer).and_then(|()| {
+            writer.flush().map_err(|err| {
+                log::error!("Failed to flush streamed response: {err}");
+                ServiceError::Internal
+            })
+        });
+        if let Err(err) = result {
+            // Drop whatever is still buffered: the body ends with the error.
+            let (channel, _) = writer.into_parts();
+            let _ = channel.0.blocking_send(Err(err));
+        }
+    });
+
+    match receiver.recv().await {
+        Some(Err(err)) => Err(err),
+        first => Ok(BlockingBody {
+            first: first.and_then(Result::ok),
+            receiver,
+        }),
+    }
+}
+
+/// Forwards every write as one chunk of the response body.
+struct ChannelWriter(mpsc::Sender<Chunk>);
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(web::Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "response body was dropped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Response body returned by [`stream_blocking`].
+pub struct BlockingBody {
+    first: Option<web::Bytes>,
+    receiver: mpsc::Receiver<Chunk>,
+}
+
+impl MessageBody for BlockingBody {
+    type Error = io::Error;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<web::Bytes, Self::Error>>> {
+        let this = self.get_mut();
+        if let Some(first) = this.first.take() {
+            return Poll::Ready(Some(Ok(first)));
+        }
+        this.receiver
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map_err(|err| io::Error::other(err.to_string()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn streams_everything_the_writer_produces() {
+        let body = stream_blocking(|writer| {
+            for line in 0..2_000 {
+                writeln!(writer, "{{\"line\":{line}}}").map_err(|_| ServiceError::Internal)?;
+            }
+            Ok(())
+        })
+        .await
+        .expect("stream should start");
+
+        let bytes = to_bytes(body).await.expect("body should complete");
+        let text = String::from_utf8(bytes.to_vec()).expect("utf-8 body");
+        assert_eq!(text.lines().count(), 2_000);
+        assert!(text.ends_with("{\"line\":1999}\n"));
+    }
+
+    #[actix_web::test]
+    async fn returns_errors_raised_before_any_output() {
+        let result = stream_blocking(|_| Err(ServiceError::Unauthorized)).await;
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[actix_web::test]
+    async fn empty_output_is_an_empty_body() {
+        let body = stream_blocking(|_| Ok(()))
+            .await
+            .expect("stream should start");
+
+        assert!(
+            to_bytes(body)
+                .await
+                .expect("body should complete")
+                .is_empty()
+        );
+    }
+}
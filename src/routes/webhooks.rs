@@ -0,0 +1,59 @@
+//! Routes for registering and removing client event webhooks.
+
+use actix_web::{HttpResponse, Responder, post, web};
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::dto::mutation::{ApiMutationErrorDto, ApiMutationSuccessDto};
+
+use crate::domain::types::WebhookId;
+use crate::forms::webhooks::{AddWebhookForm, AddWebhookPayload};
+use crate::routes::{MutationResource, mutation_error_response, webhooks_enabled};
+use crate::services::webhooks as webhooks_service;
+use crate::state::AppState;
+
+#[post("/webhooks", guard = "webhooks_enabled")]
+/// Register a webhook receiving the hub's client events.
+pub async fn add_webhook(
+    web::Form(form): web::Form<AddWebhookForm>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let payload = match AddWebhookPayload::try_from(form) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("Invalid webhook data: {error}");
+            return HttpResponse::BadRequest().json(ApiMutationErrorDto::from(&error));
+        }
+    };
+
+    match webhooks_service::add_webhook(payload, &user, &state.repo) {
+        Ok(_) => HttpResponse::Created().json(ApiMutationSuccessDto {
+            message: "Вебхук добавлен.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to add a webhook: {err}");
+            mutation_error_response(MutationResource::Webhook, &err)
+        }
+    }
+}
+
+#[post("/webhooks/{webhook_id}/delete", guard = "webhooks_enabled")]
+/// Remove a webhook of the current hub.
+pub async fn delete_webhook(
+    webhook_id: web::Path<WebhookId>,
+    user: AuthenticatedUser,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let webhook_id = webhook_id.into_inner().get();
+
+    match webhooks_service::delete_webhook(webhook_id, &user, &state.repo) {
+        Ok(()) => HttpResponse::Ok().json(ApiMutationSuccessDto {
+            message: "Вебхук удалён.".to_string(),
+            redirect_to: None,
+        }),
+        Err(err) => {
+            log::error!("Failed to delete webhook {webhook_id}: {err}");
+            mutation_error_response(MutationResource::Webhook, &err)
+        }
+    }
+}
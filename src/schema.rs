@@ -1,5 +1,29 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        name -> Text,
+        key_hash -> Text,
+        roles -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        actor_email -> Text,
+        entity_type -> Text,
+        entity_id -> Integer,
+        action -> Text,
+        diff -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     client_events (id) {
         id -> Integer,
@@ -68,6 +92,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    client_notes (id) {
+        id -> Integer,
+        client_id -> Integer,
+        manager_id -> Integer,
+        text -> Text,
+        is_pinned -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    client_segments (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        name -> Text,
+        filter_json -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     clients (id) {
         id -> Integer,
@@ -79,6 +126,54 @@ diesel::table! {
         updated_at -> Timestamp,
         fields -> Nullable<Text>,
         public_id -> Nullable<Binary>,
+        last_edited_by -> Nullable<Integer>,
+        last_edited_at -> Nullable<Timestamp>,
+        status -> Text,
+        source -> Nullable<Text>,
+        raw_phone -> Nullable<Text>,
+        version -> Integer,
+    }
+}
+
+diesel::table! {
+    hub_contact_policies (hub_id) {
+        hub_id -> Integer,
+        require_email -> Bool,
+        require_phone -> Bool,
+    }
+}
+
+diesel::table! {
+    hub_limits (hub_id) {
+        hub_id -> Integer,
+        max_clients -> Integer,
+    }
+}
+
+diesel::table! {
+    hub_settings (hub_id) {
+        hub_id -> Integer,
+        items_per_page -> Integer,
+        email_sender_name -> Nullable<Text>,
+        allow_manager_self_assign -> Bool,
+        default_phone_region -> Nullable<Text>,
+        event_retention_days -> Nullable<Integer>,
+        email_reply_to -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    import_jobs (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        status -> Text,
+        total_rows -> Integer,
+        inserted_rows -> Integer,
+        error_rows -> Integer,
+        error_message -> Nullable<Text>,
+        started_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+        updated_rows -> Integer,
     }
 }
 
@@ -89,6 +184,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    manager_assignment_rules (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        manager_id -> Integer,
+        field -> Text,
+        value -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     managers (id) {
         id -> Integer,
@@ -96,6 +202,10 @@ diesel::table! {
         name -> Text,
         email -> Text,
         is_user -> Bool,
+        company -> Nullable<Text>,
+        phone -> Nullable<Text>,
+        is_active -> Bool,
+        name_locked -> Bool,
     }
 }
 
@@ -109,13 +219,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webhooks (id) {
+        id -> Integer,
+        hub_id -> Integer,
+        url -> Text,
+        event_type -> Nullable<Text>,
+        secret -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::joinable!(client_events -> clients (client_id));
 diesel::joinable!(client_events -> managers (manager_id));
 diesel::joinable!(client_fields -> clients (client_id));
 diesel::joinable!(client_manager -> clients (client_id));
 diesel::joinable!(client_manager -> managers (manager_id));
+diesel::joinable!(client_notes -> clients (client_id));
+diesel::joinable!(client_notes -> managers (manager_id));
+diesel::joinable!(manager_assignment_rules -> managers (manager_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    audit_log,
     client_events,
     client_fields,
     client_fts,
@@ -124,8 +250,16 @@ diesel::allow_tables_to_appear_in_same_query!(
     client_fts_docsize,
     client_fts_idx,
     client_manager,
+    client_notes,
+    client_segments,
     clients,
+    hub_contact_policies,
+    hub_limits,
+    hub_settings,
+    import_jobs,
     important_fields,
+    manager_assignment_rules,
     managers,
     store_otps,
+    webhooks,
 );
@@ -5,21 +5,38 @@ use std::str::FromStr;
 use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::dto::shell::{CurrentUserDto, IamDto, NavigationItemDto, NoAccessPageDto};
 use pushkind_common::models::config::CommonServerConfig;
-use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
-use pushkind_common::routes::check_role;
+use pushkind_common::pagination::{DEFAULT_ITEMS_PER_PAGE, Pagination};
+use pushkind_common::repository::errors::RepositoryError;
+use pushkind_common::routes::{check_role, ensure_role};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
-use crate::domain::types::{HubId, PublicId};
+use crate::domain::client_event::ClientEventType;
+use crate::domain::types::{ClientEmail, ClientId, HubId, ManagerEmail, PhoneNumber, PublicId};
 use crate::dto::api::{
+    ApiKeyDto, AuditEntryDto, ClientBatchItemDto, ClientBatchItemStatus, ClientBatchResponse,
     ClientDetailsDto, ClientDetailsHeaderDto, ClientDirectoryDto, ClientEventDto,
-    ClientFieldDisplayDto, ClientListItemDto, ImportantFieldSettingsDto, ManagerCollectionDto,
-    ManagerModalDto, ManagerWithClientsDto, PaginatedClientListDto,
+    ClientEventItemDto, ClientEventPageDto, ClientEventsQuery, ClientFieldDisplayDto,
+    ClientFieldsPatch, ClientFieldsResponse, ClientListItemDto, ClientNoteDto, ClientSegmentDto,
+    ClientStatusPatch, ClientStatusResponse, EmailThreadDto, FeatureFlagsDto, HubDashboardDto,
+    HubSettingsDto, ImportJobDto, ImportantFieldSettingsDto, ManagerCollectionDto, ManagerDto,
+    ManagerModalDto, ManagerWithClientsDto, PaginatedClientListDto, RecentClientsQuery, WebhookDto,
 };
 pub use crate::dto::api::{ClientsQuery, ClientsResponse};
-use crate::models::config::AppConfig;
-use crate::repository::{ClientListQuery, ClientReader};
-use crate::services::{ServiceError, ServiceResult, client, main, managers, settings};
-use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE};
+use crate::dto::managers::ManagersQuery;
+use crate::forms::main::{ClientBatchRecord, MAX_CLIENT_BATCH_SIZE};
+use crate::models::config::{AppConfig, FeaturesConfig, ServerConfig};
+use crate::repository::{ApiKeyReader, ClientListQuery, ClientReader};
+use crate::services::{
+    ServiceError, ServiceResult, api_keys, client, log_service_call, main, managers, segments,
+    settings, webhooks,
+};
+use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE, SERVICE_MANAGER_ROLE};
+
+/// Number of clients returned by [`get_recent_clients_data`] without a `limit`.
+pub const DEFAULT_RECENT_CLIENTS_LIMIT: usize = 10;
+/// Upper bound on the `limit` accepted by [`get_recent_clients_data`].
+pub const MAX_RECENT_CLIENTS_LIMIT: usize = 50;
 
 #[derive(Debug, Deserialize)]
 struct SerializedPaginated<T> {
@@ -36,7 +53,9 @@ fn has_shell_access(user: &AuthenticatedUser) -> bool {
 pub fn get_shell_data(
     user: &AuthenticatedUser,
     common_config: &CommonServerConfig,
+    features: &FeaturesConfig,
 ) -> ServiceResult<IamDto> {
+    log_service_call("get_shell_data", user);
     if !has_shell_access(user) {
         return Err(ServiceError::Unauthorized);
     }
@@ -59,6 +78,12 @@ pub fn get_shell_data(
             name: "Менеджеры".to_string(),
             url: "/managers".to_string(),
         });
+        if features.hub_dashboard {
+            local_menu_items.push(NavigationItemDto {
+                name: "Обзор".to_string(),
+                url: "/dashboard".to_string(),
+            });
+        }
         local_menu_items.push(NavigationItemDto {
             name: "Настройки".to_string(),
             url: "/settings".to_string(),
@@ -74,12 +99,26 @@ pub fn get_shell_data(
     })
 }
 
+/// Returns the optional features enabled for this deployment.
+pub fn get_feature_flags(
+    user: &AuthenticatedUser,
+    features: &FeaturesConfig,
+) -> ServiceResult<FeatureFlagsDto> {
+    log_service_call("get_feature_flags", user);
+    if !has_shell_access(user) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    Ok(FeatureFlagsDto::from(features))
+}
+
 /// Returns minimal page data for the local no-access page.
 pub fn get_no_access_data(
     user: &AuthenticatedUser,
     common_config: &CommonServerConfig,
     required_role: Option<&str>,
 ) -> NoAccessPageDto {
+    log_service_call("get_no_access_data", user);
     NoAccessPageDto {
         current_user: CurrentUserDto::from(user.clone()),
         home_url: common_config.auth_service_url.clone(),
@@ -96,14 +135,31 @@ pub fn list_clients<R>(
 where
     R: ClientReader + ?Sized,
 {
+    log_service_call("list_clients", user);
     if !has_shell_access(user) {
         return Err(ServiceError::Unauthorized);
     }
 
-    let mut query = ClientListQuery::new(HubId::new(user.hub_id)?);
+    let mut query = ClientListQuery::new(HubId::new(user.hub_id)?)
+        .sort(params.sort.unwrap_or_default())
+        .fts_operator(params.fts.unwrap_or_default())
+        .include_archived(params.include_archived)
+        .unassigned(params.unassigned);
 
-    if let Some(page) = params.page {
-        query = query.paginate(page, DEFAULT_ITEMS_PER_PAGE);
+    match (params.page, params.after_id) {
+        (Some(_), Some(_)) => {
+            return Err(ServiceError::Form(
+                "Параметры `page` и `after_id` нельзя использовать вместе.".to_string(),
+            ));
+        }
+        (_, Some(after_id)) if after_id < 0 => {
+            return Err(ServiceError::Form(
+                "Параметр `after_id` не может быть отрицательным.".to_string(),
+            ));
+        }
+        (Some(page), None) => query = query.paginate(page, DEFAULT_ITEMS_PER_PAGE),
+        (None, Some(after_id)) => query = query.after(after_id, DEFAULT_ITEMS_PER_PAGE),
+        (None, None) => {}
     }
 
     let search = params
@@ -128,14 +184,110 @@ where
                 return Ok(ClientsResponse {
                     total: 0,
                     clients: Vec::new(),
+                    next_cursor: None,
+                });
+            }
+        }
+    }
+    if let Some(email_raw) = params
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        match ClientEmail::new(email_raw) {
+            Ok(email) => {
+                query = query.email_exact(email);
+            }
+            Err(_) => {
+                return Ok(ClientsResponse {
+                    total: 0,
+                    clients: Vec::new(),
+                    next_cursor: None,
+                });
+            }
+        }
+    }
+    if let Some(phone_raw) = params
+        .phone
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        match PhoneNumber::new(phone_raw) {
+            Ok(phone) => {
+                query = query.phone_exact(phone);
+            }
+            Err(_) => {
+                return Ok(ClientsResponse {
+                    total: 0,
+                    clients: Vec::new(),
+                    next_cursor: None,
                 });
             }
         }
     }
 
+    if params.field.len() != params.value.len() {
+        return Err(ServiceError::Form(
+            "Каждому параметру `field` должен соответствовать параметр `value`.".to_string(),
+        ));
+    }
+    for (field, value) in params.field.iter().zip(&params.value) {
+        let field = field.trim();
+        if !field.is_empty() {
+            query = query.field_filter(field, value.trim());
+        }
+    }
+
+    let cursor = query.cursor;
     let (total, clients) = repo.list_clients(query).map_err(ServiceError::from)?;
 
-    Ok(ClientsResponse { total, clients })
+    // A short page is the last one; a full page may be followed by more clients.
+    let next_cursor = cursor
+        .filter(|cursor| clients.len() >= cursor.limit)
+        .and_then(|_| clients.last())
+        .map(|client| client.id.get());
+
+    Ok(ClientsResponse {
+        total,
+        clients,
+        next_cursor,
+    })
+}
+
+/// Returns the most recently added hub clients, newest first.
+///
+/// Managers without the admin role only see clients assigned to them.
+pub fn get_recent_clients_data<R>(
+    params: RecentClientsQuery,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ClientListItemDto>>
+where
+    R: ClientReader + ?Sized,
+{
+    log_service_call("get_recent_clients_data", user);
+    if !has_shell_access(user) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RECENT_CLIENTS_LIMIT)
+        .clamp(1, MAX_RECENT_CLIENTS_LIMIT);
+    let manager_email = if !check_role(SERVICE_ADMIN_ROLE, &user.roles)
+        && check_role(SERVICE_MANAGER_ROLE, &user.roles)
+    {
+        Some(ManagerEmail::new(&user.email)?)
+    } else {
+        None
+    };
+
+    let clients = repo.list_recent_clients(hub_id, manager_email, limit)?;
+
+    Ok(clients.iter().map(ClientListItemDto::from).collect())
 }
 
 /// Returns typed client directory data.
@@ -145,8 +297,12 @@ pub fn get_client_directory_data<R>(
     repo: &R,
 ) -> ServiceResult<ClientDirectoryDto>
 where
-    R: crate::repository::ClientReader + crate::repository::ManagerWriter + ?Sized,
+    R: crate::repository::ClientReader
+        + crate::repository::HubSettingsReader
+        + crate::repository::ManagerWriter
+        + ?Sized,
 {
+    log_service_call("get_client_directory_data", user);
     let data = main::load_index_page(params, user, repo)?;
     let paginated_clients: SerializedPaginated<crate::domain::client::Client> =
         serde_json::from_value(
@@ -156,6 +312,7 @@ where
 
     Ok(ClientDirectoryDto {
         search_query: data.search_query,
+        unassigned: data.unassigned,
         clients: PaginatedClientListDto {
             items: paginated_clients
                 .items
@@ -174,28 +331,43 @@ pub fn get_client_details_data<R>(
     user: &AuthenticatedUser,
     repo: &R,
     app_config: &AppConfig,
+    server_config: &ServerConfig,
 ) -> ServiceResult<ClientDetailsDto>
 where
     R: crate::repository::ClientReader
         + crate::repository::ClientEventReader
         + crate::repository::ImportantFieldReader
+        + crate::repository::NoteReader
+        + crate::repository::HubSettingsReader
         + ?Sized,
 {
+    log_service_call("get_client_details_data", user);
     let data = client::load_client_details(client_id, user, repo)?;
+    // The hub's phone region wins over the service-wide default country.
+    let country = repo
+        .get_hub_settings(data.client.hub_id)?
+        .hub_default_phone_region
+        .or_else(|| server_config.country.clone());
 
     Ok(ClientDetailsDto {
-        client: ClientDetailsHeaderDto::from(&data.client),
+        client: ClientDetailsHeaderDto::new(&data.client, country.as_ref()),
         managers: data.managers.iter().map(Into::into).collect(),
         events: data
             .events_with_managers
             .iter()
             .map(|(event, manager)| ClientEventDto::from_event_pair(event, manager))
             .collect(),
+        email_threads: data
+            .email_threads
+            .iter()
+            .map(EmailThreadDto::from)
+            .collect(),
         documents: data
             .documents
             .iter()
             .map(ClientEventDto::from_document)
             .collect(),
+        notes: data.notes.iter().map(ClientNoteDto::from).collect(),
         available_fields: data.available_fields,
         important_fields: data
             .important_fields
@@ -208,20 +380,319 @@ where
             .map(Into::into)
             .collect::<Vec<ClientFieldDisplayDto>>(),
         total_events: data.total_events,
+        total_managers: data.total_managers,
         todo_service_url: app_config.todo_service_url.clone(),
         files_service_url: app_config.files_service_url.clone(),
+        locale: server_config.locale.to_string(),
+    })
+}
+
+/// Returns the internal notes of a client, pinned notes first.
+pub fn get_client_notes_data<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ClientNoteDto>>
+where
+    R: crate::repository::ClientReader + crate::repository::NoteReader + ?Sized,
+{
+    log_service_call("get_client_notes_data", user);
+    let notes = client::list_client_notes(client_id, user, repo)?;
+
+    Ok(notes.iter().map(ClientNoteDto::from).collect())
+}
+
+/// Returns one page of a client's events with the total for pagination.
+pub fn get_client_events<R>(
+    client_id: i32,
+    params: ClientEventsQuery,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientEventPageDto>
+where
+    R: crate::repository::ClientReader + crate::repository::ClientEventReader + ?Sized,
+{
+    log_service_call("get_client_events", user);
+    let page = params.page.unwrap_or(1).max(1);
+    let event_type = params
+        .event_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|event_type| !event_type.is_empty())
+        .map(ClientEventType::from);
+    let pagination = Pagination {
+        page,
+        per_page: DEFAULT_ITEMS_PER_PAGE,
+    };
+
+    let (total, events) =
+        client::list_client_events_page(client_id, event_type, pagination, user, repo)?;
+
+    Ok(ClientEventPageDto {
+        events: events
+            .iter()
+            .map(|(event, manager)| ClientEventItemDto::from_event_pair(event, manager))
+            .collect(),
+        total,
+        page,
+        per_page: DEFAULT_ITEMS_PER_PAGE,
+    })
+}
+
+/// Returns the custom fields of a client visible to the user.
+pub fn get_client_fields<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientFieldsResponse>
+where
+    R: crate::repository::ClientReader + ?Sized,
+{
+    log_service_call("get_client_fields", user);
+    let fields = client::load_client_fields(client_id, user, repo)?;
+
+    Ok(ClientFieldsResponse { fields })
+}
+
+/// Returns the managers assigned to a client visible to the user.
+pub fn get_client_managers<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ManagerDto>>
+where
+    R: crate::repository::ClientReader + ?Sized,
+{
+    log_service_call("get_client_managers", user);
+    let managers = client::load_client_managers(client_id, user, repo)?;
+
+    Ok(managers.iter().map(ManagerDto::from).collect())
+}
+
+/// Replaces the custom fields of a hub client and returns the stored map.
+pub fn patch_client_fields<R>(
+    client_id: i32,
+    patch: ClientFieldsPatch,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientFieldsResponse>
+where
+    R: crate::repository::ClientReader
+        + crate::repository::ClientWriter
+        + crate::repository::ManagerWriter
+        + ?Sized,
+{
+    log_service_call("patch_client_fields", user);
+    let client = client::replace_client_fields(client_id, patch.fields, user, repo)?;
+
+    Ok(ClientFieldsResponse {
+        fields: client.fields.unwrap_or_default(),
+    })
+}
+
+/// Archives or restores a client of the admin's hub.
+///
+/// Archived clients drop out of client lists, search, segments and the manager
+/// modal but keep their events and manager assignments.
+pub fn set_client_status<R>(
+    client_id: i32,
+    patch: ClientStatusPatch,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientStatusResponse>
+where
+    R: crate::repository::ClientWriter + ?Sized,
+{
+    log_service_call("set_client_status", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    repo.set_client_status(ClientId::new(client_id)?, hub_id, patch.status)
+        .map_err(|err| match err {
+            RepositoryError::NotFound => ServiceError::NotFound,
+            err => err.into(),
+        })?;
+    tracing::info!(client_id, %hub_id, status = %patch.status, "Client status changed");
+
+    Ok(ClientStatusResponse {
+        status: patch.status,
+    })
+}
+
+/// Validates each record independently and upserts the valid ones into the
+/// admin's hub in one transaction.
+///
+/// Invalid records are reported and skipped; batches larger than
+/// [`MAX_CLIENT_BATCH_SIZE`] are rejected as a whole.
+#[tracing::instrument(skip(records, user, repo), fields(records = records.len()))]
+pub fn upsert_clients_batch<R>(
+    records: Vec<ClientBatchRecord>,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientBatchResponse>
+where
+    R: crate::repository::ClientWriter
+        + crate::repository::ContactPolicyReader
+        + crate::repository::HubSettingsReader
+        + ?Sized,
+{
+    log_service_call("upsert_clients_batch", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    if records.len() > MAX_CLIENT_BATCH_SIZE {
+        return Err(ServiceError::Form(format!(
+            "Можно передать не более {MAX_CLIENT_BATCH_SIZE} клиентов за раз."
+        )));
+    }
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let policy = repo.get_contact_policy(hub_id)?;
+    let default_region = repo.get_hub_settings(hub_id)?.hub_default_phone_region;
+
+    let mut clients = Vec::with_capacity(records.len());
+    let mut results = Vec::with_capacity(records.len());
+    // Indexes into `results` of the records handed to the upsert, in order.
+    let mut valid = Vec::with_capacity(records.len());
+    for (index, record) in records.into_iter().enumerate() {
+        match record.into_new_client(hub_id, &policy, default_region.as_ref()) {
+            Ok(client) => {
+                clients.push(client);
+                valid.push(index);
+                results.push(ClientBatchItemDto {
+                    index,
+                    status: ClientBatchItemStatus::Skipped,
+                    error: None,
+                });
+            }
+            Err(err) => results.push(ClientBatchItemDto {
+                index,
+                status: ClientBatchItemStatus::Invalid,
+                error: Some(err.to_string()),
+            }),
+        }
+    }
+
+    if !clients.is_empty() {
+        let outcomes = repo
+            .upsert_clients(&clients)
+            .map_err(main::map_client_limit_error)?;
+        for (index, outcome) in valid.into_iter().zip(outcomes) {
+            results[index].status = outcome.into();
+        }
+    }
+
+    let count = |status: ClientBatchItemStatus| {
+        results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    };
+    let inserted = count(ClientBatchItemStatus::Inserted);
+    let updated = count(ClientBatchItemStatus::Updated);
+    let skipped = results.len() - inserted - updated;
+    tracing::info!(%hub_id, inserted, updated, skipped, "Client batch upserted");
+
+    Ok(ClientBatchResponse {
+        results,
+        inserted,
+        updated,
+        skipped,
     })
 }
 
+/// Returns the audit history of a client for admins.
+pub fn get_client_audit_data<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<AuditEntryDto>>
+where
+    R: crate::repository::AuditReader + ?Sized,
+{
+    log_service_call("get_client_audit_data", user);
+    let entries = client::list_client_audit(client_id, user, repo)?;
+
+    Ok(entries.iter().map(AuditEntryDto::from).collect())
+}
+
+/// Returns the webhooks registered for the hub.
+pub fn get_webhooks_data<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<WebhookDto>>
+where
+    R: crate::repository::WebhookReader + ?Sized,
+{
+    log_service_call("get_webhooks_data", user);
+    let webhooks = webhooks::list_webhooks(user, repo)?;
+
+    Ok(webhooks.iter().map(WebhookDto::from).collect())
+}
+
+/// Returns the API keys of the admin's hub.
+pub fn get_api_keys_data<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<ApiKeyDto>>
+where
+    R: ApiKeyReader + ?Sized,
+{
+    log_service_call("get_api_keys_data", user);
+    let api_keys = api_keys::list_api_keys(user, repo)?;
+
+    Ok(api_keys.iter().map(ApiKeyDto::from).collect())
+}
+
+/// Returns the progress of a hub CSV import job.
+pub fn get_import_job_status_data<R>(
+    job_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+    progress: &main::ImportProgress,
+) -> ServiceResult<ImportJobDto>
+where
+    R: crate::repository::ImportJobReader + ?Sized,
+{
+    log_service_call("get_import_job_status_data", user);
+    let job = main::get_import_job(job_id, user, repo, progress)?;
+
+    Ok(ImportJobDto::from(&job))
+}
+
+/// Returns the client segments defined for the hub.
+pub fn get_segments_data<R>(
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ClientSegmentDto>>
+where
+    R: crate::repository::SegmentReader + ?Sized,
+{
+    log_service_call("get_segments_data", user);
+    let segments = segments::list_segments(user, repo)?;
+
+    Ok(segments.iter().map(ClientSegmentDto::from).collect())
+}
+
+/// Returns the clients currently matching a segment.
+pub fn get_segment_clients_data<R>(
+    segment_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ClientListItemDto>>
+where
+    R: crate::repository::SegmentReader + ?Sized,
+{
+    log_service_call("get_segment_clients_data", user);
+    let clients = segments::list_segment_clients(segment_id, user, repo)?;
+
+    Ok(clients.iter().map(ClientListItemDto::from).collect())
+}
+
 /// Returns typed manager collection data.
 pub fn get_manager_collection_data<R>(
+    query: ManagersQuery,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<ManagerCollectionDto>
 where
     R: crate::repository::ManagerReader + ?Sized,
 {
-    let data = managers::list_managers(user, repo)?;
+    log_service_call("get_manager_collection_data", user);
+    let data = managers::list_managers(query, user, repo)?;
 
     Ok(ManagerCollectionDto {
         managers: data
@@ -244,6 +715,7 @@ pub fn get_manager_modal_data<R>(
 where
     R: crate::repository::ManagerReader + crate::repository::ClientReader + ?Sized,
 {
+    log_service_call("get_manager_modal_data", user);
     let data = managers::load_manager_modal(manager_id, user, repo)?;
 
     Ok(ManagerModalDto {
@@ -252,7 +724,8 @@ where
     })
 }
 
-/// Returns typed important-field settings data.
+/// Returns the hub's important fields to any CRM user, so clients can build
+/// forms matching the admin's configuration.
 pub fn get_important_field_settings_data<R>(
     user: &AuthenticatedUser,
     repo: &R,
@@ -260,19 +733,87 @@ pub fn get_important_field_settings_data<R>(
 where
     R: crate::repository::ImportantFieldReader + ?Sized,
 {
-    let data = settings::load_important_fields(user, repo)?;
+    log_service_call("get_important_field_settings_data", user);
+    if !has_shell_access(user) {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let fields: Vec<String> = repo
+        .list_important_fields(hub_id)?
+        .into_iter()
+        .map(|field| field.field.as_str().to_string())
+        .collect();
 
     Ok(ImportantFieldSettingsDto {
-        fields_text: data.fields.join("\n"),
+        fields_text: fields.join("\n"),
+        fields,
+    })
+}
+
+/// Returns the hub settings for the settings page.
+pub fn get_hub_settings_data<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<HubSettingsDto>
+where
+    R: crate::repository::HubSettingsReader + ?Sized,
+{
+    log_service_call("get_hub_settings_data", user);
+    let settings = settings::load_hub_settings(user, repo)?;
+
+    Ok(HubSettingsDto::from(&settings))
+}
+
+/// Returns typed hub dashboard counters.
+pub fn get_hub_dashboard_dto<R>(
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<HubDashboardDto>
+where
+    R: crate::repository::HubStatsReader + ?Sized,
+{
+    log_service_call("get_hub_dashboard_dto", user);
+    main::get_hub_dashboard_data(user, repo).map(HubDashboardDto::from)
+}
+
+/// Hashes a raw API key the way it is stored in `api_keys.key_hash`.
+pub fn hash_api_key(raw_key: &str) -> String {
+    hex::encode(Sha256::digest(raw_key.as_bytes()))
+}
+
+/// Resolves an `X-API-Key` value into the user its hub integration acts as.
+///
+/// Unknown or blank keys return [`ServiceError::Unauthorized`].
+#[tracing::instrument(skip(raw_key, repo))]
+pub fn authenticate_api_key<R>(raw_key: &str, repo: &R) -> ServiceResult<AuthenticatedUser>
+where
+    R: ApiKeyReader + ?Sized,
+{
+    let raw_key = raw_key.trim();
+    if raw_key.is_empty() {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let Some(api_key) = repo.find_api_key(&hash_api_key(raw_key))? else {
+        return Err(ServiceError::Unauthorized);
+    };
+
+    Ok(AuthenticatedUser {
+        sub: format!("api-key:{}", api_key.id),
+        email: format!("api-key-{}@api-key.invalid", api_key.id),
+        hub_id: api_key.hub_id.get(),
+        name: api_key.name,
+        roles: api_key.roles,
+        exp: 0,
     })
 }
 
 #[cfg(all(test, feature = "test-mocks"))]
 mod tests {
     use super::*;
-    use crate::domain::client::Client;
+    use crate::domain::client::{Client, ClientStatus, ClientWriteOutcome};
+    use crate::domain::important_field::ImportantField;
     use crate::domain::types::{ClientId, ClientName, HubId, PublicId};
     use crate::repository::mock::MockRepository;
+    use crate::repository::{ClientCursor, ClientSort, FtsOperator};
     use crate::services::ServiceError;
     use chrono::Utc;
 
@@ -313,6 +854,7 @@ mod tests {
                 auth_service_url: "https://auth.example.com".to_string(),
                 secret: "secret".to_string(),
             },
+            &FeaturesConfig::default(),
         );
 
         assert!(matches!(result, Err(ServiceError::Unauthorized)));
@@ -329,6 +871,7 @@ mod tests {
                 auth_service_url: "https://auth.example.com".to_string(),
                 secret: "secret".to_string(),
             },
+            &FeaturesConfig::default(),
         )
         .expect("shell data");
 
@@ -360,6 +903,7 @@ mod tests {
                 auth_service_url: "https://auth.example.com".to_string(),
                 secret: "secret".to_string(),
             },
+            &FeaturesConfig::default(),
         )
         .expect("shell data");
 
@@ -378,6 +922,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_shell_data_hides_disabled_dashboard() {
+        let mut user = access_user();
+        user.roles.push(SERVICE_ADMIN_ROLE.to_string());
+
+        let response = get_shell_data(
+            &user,
+            &CommonServerConfig {
+                auth_service_url: "https://auth.example.com".to_string(),
+                secret: "secret".to_string(),
+            },
+            &FeaturesConfig {
+                hub_dashboard: false,
+                ..FeaturesConfig::default()
+            },
+        )
+        .expect("shell data");
+
+        assert!(
+            !response
+                .local_menu_items
+                .iter()
+                .any(|item| item.url == "/dashboard")
+        );
+    }
+
     #[test]
     fn list_clients_requires_access_role() {
         let mut repo = MockRepository::new();
@@ -431,7 +1001,7 @@ mod tests {
         let params = ClientsQuery {
             search: Some("  Alice  ".to_string()),
             page: Some(2),
-            public_id: None,
+            ..Default::default()
         };
 
         let response = list_clients(params, &user, &repo).expect("response ok");
@@ -449,19 +1019,655 @@ mod tests {
     }
 
     #[test]
-    fn list_clients_with_invalid_public_id_returns_empty_without_repo_query() {
+    fn list_clients_pages_by_cursor_and_returns_next_cursor() {
         let mut repo = MockRepository::new();
-        repo.expect_list_clients().times(0);
+        repo.expect_list_clients()
+            .withf(|query| {
+                query.pagination.is_none()
+                    && query.cursor
+                        == Some(ClientCursor {
+                            after_id: 10,
+                            limit: DEFAULT_ITEMS_PER_PAGE,
+                        })
+            })
+            .times(1)
+            .returning(|_| {
+                let clients = (11..)
+                    .take(DEFAULT_ITEMS_PER_PAGE)
+                    .map(|id| sample_client(id, 7))
+                    .collect();
+                Ok((100, clients))
+            });
 
         let user = access_user();
-        let params = ClientsQuery {
-            public_id: Some("not-a-uuid".to_string()),
-            ..Default::default()
-        };
+        let params: ClientsQuery = serde_html_form::from_str("after_id=10").expect("query parses");
 
         let response = list_clients(params, &user, &repo).expect("response ok");
 
-        assert_eq!(response.total, 0);
-        assert!(response.clients.is_empty());
+        assert_eq!(response.total, 100);
+        assert_eq!(
+            response.next_cursor,
+            Some(10 + DEFAULT_ITEMS_PER_PAGE as i32)
+        );
+    }
+
+    #[test]
+    fn list_clients_omits_next_cursor_on_last_page() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .times(1)
+            .returning(|_| Ok((1, vec![sample_client(3, 7)])));
+
+        let user = access_user();
+        let params: ClientsQuery = serde_html_form::from_str("after_id=0").expect("query parses");
+
+        let response = list_clients(params, &user, &repo).expect("response ok");
+
+        assert_eq!(response.clients.len(), 1);
+        assert_eq!(response.next_cursor, None);
+    }
+
+    #[test]
+    fn list_clients_rejects_page_with_cursor() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("page=2&after_id=10").expect("query parses");
+
+        let result = list_clients(params, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[test]
+    fn list_clients_rejects_unpaired_field_filters() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("field=Stage&value=won&field=City").expect("query parses");
+
+        let result = list_clients(params, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[test]
+    fn list_clients_applies_exact_filters_alongside_search() {
+        let mut repo = MockRepository::new();
+        let expected_client = sample_client(1, 7);
+        repo.expect_list_clients()
+            .withf(|query| {
+                query.search.as_deref() == Some("Alice")
+                    && query.email_exact.as_ref().map(ClientEmail::as_str)
+                        == Some("alice@example.com")
+                    && query.phone_exact.as_ref().map(PhoneNumber::as_str) == Some("+14155552671")
+            })
+            .times(1)
+            .returning(move |_| Ok((1, vec![expected_client.clone()])));
+
+        let user = access_user();
+        let params = ClientsQuery {
+            search: Some("Alice".to_string()),
+            email: Some(" Alice@Example.com ".to_string()),
+            phone: Some("+1 (415) 555-2671".to_string()),
+            ..Default::default()
+        };
+
+        let response = list_clients(params, &user, &repo).expect("response ok");
+
+        assert_eq!(response.total, 1);
+    }
+
+    #[test]
+    fn list_clients_defaults_to_id_ascending_order() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.sort == ClientSort::Id)
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+
+        list_clients(ClientsQuery::default(), &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_passes_requested_sort() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.sort == ClientSort::UpdatedDesc)
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("sort=updated_desc").expect("query parses");
+
+        list_clients(params, &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_passes_fts_operator() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.fts_operator == FtsOperator::Or)
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("search=acme+corp&fts=or").expect("query parses");
+
+        list_clients(params, &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_passes_include_archived_flag() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.include_archived)
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("include_archived=true").expect("query parses");
+
+        list_clients(params, &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_passes_unassigned_flag() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.unassigned && query.search.as_deref() == Some("acme"))
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+        let params: ClientsQuery =
+            serde_html_form::from_str("unassigned=true&search=acme").expect("query parses");
+
+        list_clients(params, &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_pairs_repeated_field_filters() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| {
+                query.field_filters
+                    == vec![
+                        ("Stage".to_string(), "Won".to_string()),
+                        ("Tier".to_string(), "VIP".to_string()),
+                    ]
+            })
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let user = access_user();
+        let params: ClientsQuery = serde_html_form::from_str(
+            "field=Stage&value=Won&field=+&value=ignored&field=Tier&value=+VIP+",
+        )
+        .expect("query parses");
+
+        list_clients(params, &user, &repo).expect("response ok");
+    }
+
+    #[test]
+    fn list_clients_with_invalid_exact_phone_returns_empty_without_repo_query() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+
+        let user = access_user();
+        let params = ClientsQuery {
+            phone: Some("not-a-phone".to_string()),
+            ..Default::default()
+        };
+
+        let response = list_clients(params, &user, &repo).expect("response ok");
+
+        assert_eq!(response.total, 0);
+        assert!(response.clients.is_empty());
+    }
+
+    #[test]
+    fn list_clients_with_invalid_public_id_returns_empty_without_repo_query() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+
+        let user = access_user();
+        let params = ClientsQuery {
+            public_id: Some("not-a-uuid".to_string()),
+            ..Default::default()
+        };
+
+        let response = list_clients(params, &user, &repo).expect("response ok");
+
+        assert_eq!(response.total, 0);
+        assert!(response.clients.is_empty());
+    }
+
+    #[test]
+    fn get_recent_clients_data_clamps_limit_for_admins() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_recent_clients()
+            .withf(|hub_id, manager_email, limit| {
+                *hub_id == HubId::new(7).expect("valid hub id")
+                    && manager_email.is_none()
+                    && *limit == MAX_RECENT_CLIENTS_LIMIT
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(vec![sample_client(2, 7), sample_client(1, 7)]));
+        let mut user = access_user();
+        user.roles.push(SERVICE_ADMIN_ROLE.to_string());
+        let params = RecentClientsQuery { limit: Some(500) };
+
+        let clients = get_recent_clients_data(params, &user, &repo).expect("response ok");
+
+        assert_eq!(
+            clients.iter().map(|client| client.id).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn get_recent_clients_data_limits_managers_to_assigned_clients() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_recent_clients()
+            .withf(|_, manager_email, limit| {
+                manager_email.as_ref().map(ManagerEmail::as_str) == Some("viewer@example.com")
+                    && *limit == DEFAULT_RECENT_CLIENTS_LIMIT
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(Vec::new()));
+        let mut user = access_user();
+        user.roles.push(SERVICE_MANAGER_ROLE.to_string());
+
+        let clients = get_recent_clients_data(RecentClientsQuery::default(), &user, &repo)
+            .expect("response ok");
+
+        assert!(clients.is_empty());
+    }
+
+    #[test]
+    fn patch_client_fields_with_empty_map_clears_fields() {
+        let mut repo = MockRepository::new();
+        let client = sample_client(5, 7);
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(move |_, _| Ok(Some(client.clone())));
+        repo.expect_create_or_update_manager()
+            .times(1)
+            .returning(|_| {
+                Ok(crate::domain::manager::Manager::try_new(
+                    1,
+                    7,
+                    "Viewer".to_string(),
+                    "viewer@example.com".to_string(),
+                    true,
+                )
+                .expect("valid manager"))
+            });
+        repo.expect_replace_client_fields()
+            .withf(|client_id, fields, _| client_id.get() == 5 && fields.is_empty())
+            .times(1)
+            .returning(|_, _, _| Ok(sample_client(5, 7)));
+
+        let user = access_user();
+        let patch = ClientFieldsPatch {
+            fields: Default::default(),
+        };
+
+        let response = patch_client_fields(5, patch, &user, &repo).expect("response ok");
+
+        assert!(response.fields.is_empty());
+    }
+
+    #[test]
+    fn patch_client_fields_for_unknown_client_returns_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_replace_client_fields().times(0);
+
+        let user = access_user();
+        let patch = ClientFieldsPatch {
+            fields: [("Tier".to_string(), "Gold".to_string())].into(),
+        };
+
+        let result = patch_client_fields(404, patch, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn set_client_status_archives_a_hub_client() {
+        let mut repo = MockRepository::new();
+        repo.expect_set_client_status()
+            .withf(|client_id, hub_id, status| {
+                client_id.get() == 5 && hub_id.get() == 7 && *status == ClientStatus::Archived
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let mut user = access_user();
+        user.roles = vec![SERVICE_ADMIN_ROLE.to_string()];
+        let patch = ClientStatusPatch {
+            status: ClientStatus::Archived,
+        };
+
+        let response = set_client_status(5, patch, &user, &repo).expect("status changed");
+
+        assert_eq!(response.status, ClientStatus::Archived);
+    }
+
+    #[test]
+    fn set_client_status_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_set_client_status().times(0);
+        let patch = ClientStatusPatch {
+            status: ClientStatus::Archived,
+        };
+
+        let result = set_client_status(5, patch, &access_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn set_client_status_for_unknown_client_returns_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_set_client_status()
+            .times(1)
+            .returning(|_, _, _| Err(RepositoryError::NotFound));
+        let mut user = access_user();
+        user.roles = vec![SERVICE_ADMIN_ROLE.to_string()];
+        let patch = ClientStatusPatch {
+            status: ClientStatus::Active,
+        };
+
+        let result = set_client_status(404, patch, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    fn batch_record(name: &str, email: Option<&str>) -> ClientBatchRecord {
+        ClientBatchRecord {
+            name: name.to_string(),
+            email: email.map(str::to_string),
+            phone: None,
+            fields: Default::default(),
+        }
+    }
+
+    #[test]
+    fn upsert_clients_batch_skips_invalid_records() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(crate::domain::contact_policy::ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(crate::domain::hub_settings::HubSettings::defaults(hub_id)));
+        repo.expect_upsert_clients()
+            .withf(|clients| {
+                clients.len() == 3 && clients.iter().all(|client| client.hub_id.get() == 7)
+            })
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    ClientWriteOutcome::Inserted,
+                    ClientWriteOutcome::Updated,
+                    ClientWriteOutcome::Skipped,
+                ])
+            });
+
+        let mut user = access_user();
+        user.roles = vec![SERVICE_ADMIN_ROLE.to_string()];
+        let records = vec![
+            batch_record("Alice", Some("alice@example.com")),
+            batch_record("No Contact", None),
+            batch_record("Bob", Some("bob@example.com")),
+            batch_record("Carol", Some("carol@example.com")),
+        ];
+
+        let response = upsert_clients_batch(records, &user, &repo).expect("batch ok");
+
+        assert_eq!(
+            (response.inserted, response.updated, response.skipped),
+            (1, 1, 2)
+        );
+        let statuses: Vec<_> = response
+            .results
+            .iter()
+            .map(|result| (result.index, &result.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (0, &ClientBatchItemStatus::Inserted),
+                (1, &ClientBatchItemStatus::Invalid),
+                (2, &ClientBatchItemStatus::Updated),
+                (3, &ClientBatchItemStatus::Skipped),
+            ]
+        );
+        assert!(response.results[1].error.is_some());
+    }
+
+    #[test]
+    fn upsert_clients_batch_requires_admin_and_caps_size() {
+        let mut repo = MockRepository::new();
+        repo.expect_upsert_clients().times(0);
+
+        let result = upsert_clients_batch(
+            vec![batch_record("Alice", Some("alice@example.com"))],
+            &access_user(),
+            &repo,
+        );
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+
+        let mut admin = access_user();
+        admin.roles = vec![SERVICE_ADMIN_ROLE.to_string()];
+        let records = (0..=MAX_CLIENT_BATCH_SIZE)
+            .map(|i| batch_record(&format!("Client {i}"), Some("c@example.com")))
+            .collect();
+
+        let result = upsert_clients_batch(records, &admin, &repo);
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[test]
+    fn get_client_managers_returns_empty_list_when_none_assigned() {
+        let mut repo = MockRepository::new();
+        let client = sample_client(5, 7);
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(move |_, _| Ok(Some(client.clone())));
+        repo.expect_list_managers_for_client()
+            .withf(|_, pagination| pagination.is_none())
+            .times(1)
+            .returning(|_, _| Ok((0, vec![])));
+
+        let user = access_user();
+
+        let managers = get_client_managers(5, &user, &repo).expect("response ok");
+
+        assert!(managers.is_empty());
+    }
+
+    #[test]
+    fn get_client_managers_for_unknown_client_returns_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_list_managers_for_client().times(0);
+
+        let user = access_user();
+
+        let result = get_client_managers(404, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn get_client_events_filters_and_paginates() {
+        let mut repo = MockRepository::new();
+        let client = sample_client(5, 7);
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(move |_, _| Ok(Some(client.clone())));
+        repo.expect_list_client_events()
+            .withf(|query| {
+                query.client_id.get() == 5
+                    && query.event_type == Some(ClientEventType::Email)
+                    && query.pagination.as_ref().map(|p| (p.page, p.per_page))
+                        == Some((2, DEFAULT_ITEMS_PER_PAGE))
+            })
+            .times(1)
+            .returning(|_| {
+                let event = crate::domain::client_event::ClientEvent::try_new(
+                    11,
+                    5,
+                    2,
+                    "Email",
+                    serde_json::json!({ "text": "Invoice" }),
+                    Utc::now().naive_utc(),
+                )
+                .expect("valid event");
+                let manager = crate::domain::manager::Manager::try_new(
+                    2,
+                    7,
+                    "Manager".to_string(),
+                    "m@example.com".to_string(),
+                    true,
+                )
+                .expect("valid manager");
+                Ok((21, vec![(event, manager)]))
+            });
+
+        let response = get_client_events(
+            5,
+            ClientEventsQuery {
+                event_type: Some(" email ".to_string()),
+                page: Some(2),
+            },
+            &access_user(),
+            &repo,
+        )
+        .expect("response ok");
+
+        assert_eq!(response.total, 21);
+        assert_eq!(response.page, 2);
+        assert_eq!(response.events.len(), 1);
+        assert_eq!(response.events[0].event_type, "Email");
+        assert_eq!(response.events[0].manager_name, "Manager");
+    }
+
+    #[test]
+    fn get_client_events_respects_manager_assignment() {
+        let mut repo = MockRepository::new();
+        repo.expect_check_client_assigned_to_manager()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        repo.expect_get_client_by_id().times(0);
+        repo.expect_list_client_events().times(0);
+
+        let mut user = access_user();
+        user.roles.push(crate::SERVICE_MANAGER_ROLE.to_string());
+
+        let result = get_client_events(5, ClientEventsQuery::default(), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn get_client_events_for_unknown_client_returns_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_list_client_events().times(0);
+
+        let result = get_client_events(404, ClientEventsQuery::default(), &access_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn authenticate_api_key_builds_user_from_stored_key() {
+        let mut repo = MockRepository::new();
+        repo.expect_find_api_key()
+            .withf(|key_hash| key_hash == hash_api_key("secret-key"))
+            .times(1)
+            .returning(|_| {
+                Ok(Some(
+                    crate::domain::api_key::ApiKey::try_new(
+                        3,
+                        7,
+                        "ERP".to_string(),
+                        "crm",
+                        Utc::now().naive_utc(),
+                    )
+                    .unwrap(),
+                ))
+            });
+
+        let user = authenticate_api_key(" secret-key ", &repo).expect("known key");
+
+        assert_eq!(user.hub_id, 7);
+        assert_eq!(user.sub, "api-key:3");
+        assert_eq!(user.roles, vec![SERVICE_ACCESS_ROLE.to_string()]);
+    }
+
+    #[test]
+    fn authenticate_api_key_rejects_unknown_and_blank_keys() {
+        let mut repo = MockRepository::new();
+        repo.expect_find_api_key().times(1).returning(|_| Ok(None));
+
+        assert!(matches!(
+            authenticate_api_key("unknown", &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+        assert!(matches!(
+            authenticate_api_key("  ", &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn important_fields_are_readable_by_access_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_important_fields()
+            .withf(|hub_id| hub_id.get() == 7)
+            .times(1)
+            .returning(|hub_id| {
+                Ok(["City", "Tier"]
+                    .into_iter()
+                    .map(|name| {
+                        ImportantField::try_new(hub_id.get(), name.to_string())
+                            .expect("valid field")
+                    })
+                    .collect())
+            });
+
+        let response =
+            get_important_field_settings_data(&access_user(), &repo).expect("important fields");
+
+        assert_eq!(
+            response.fields,
+            vec!["City".to_string(), "Tier".to_string()]
+        );
+        assert_eq!(response.fields_text, "City\nTier");
+
+        let mut user = access_user();
+        user.roles.clear();
+        assert!(matches!(
+            get_important_field_settings_data(&user, &repo),
+            Err(ServiceError::Unauthorized)
+        ));
     }
 }
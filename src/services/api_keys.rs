@@ -0,0 +1,174 @@
+//! Services provisioning integration API keys.
+
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::{check_role, ensure_role};
+use rand::RngExt;
+
+use crate::SERVICE_ADMIN_ROLE;
+use crate::domain::api_key::{ApiKey, NewApiKey};
+use crate::domain::types::{ApiKeyId, HubId};
+use crate::forms::api_keys::AddApiKeyPayload;
+use crate::repository::{ApiKeyReader, ApiKeyWriter};
+use crate::services::api::hash_api_key;
+use crate::services::{ServiceError, ServiceResult, log_service_call};
+
+/// Lists the API keys of the admin's hub.
+pub fn list_api_keys<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<ApiKey>>
+where
+    R: ApiKeyReader + ?Sized,
+{
+    log_service_call("list_api_keys", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.list_api_keys(hub_id)?)
+}
+
+/// Creates an API key for the admin's hub and returns it with the raw key.
+///
+/// Only the hash is stored, so the raw key cannot be shown again. A key may
+/// not grant roles its creator does not hold.
+pub fn add_api_key<R>(
+    payload: AddApiKeyPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<(ApiKey, String)>
+where
+    R: ApiKeyWriter + ?Sized,
+{
+    log_service_call("add_api_key", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+    if !payload
+        .roles
+        .iter()
+        .all(|role| check_role(role, &user.roles))
+    {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let raw_key = hex::encode(rand::rng().random::<[u8; 32]>());
+    let new_api_key = NewApiKey::new(hub_id, payload.name, hash_api_key(&raw_key), payload.roles);
+    let api_key = repo.create_api_key(&new_api_key)?;
+    tracing::info!(api_key_id = %api_key.id, %hub_id, "API key created");
+
+    Ok((api_key, raw_key))
+}
+
+/// Revokes an API key of the admin's hub.
+pub fn delete_api_key<R>(api_key_id: i32, user: &AuthenticatedUser, repo: &R) -> ServiceResult<()>
+where
+    R: ApiKeyWriter + ?Sized,
+{
+    log_service_call("delete_api_key", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let api_key_id = ApiKeyId::new(api_key_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    if !repo.delete_api_key(api_key_id, hub_id)? {
+        return Err(ServiceError::NotFound);
+    }
+    tracing::info!(%api_key_id, %hub_id, "API key revoked");
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "test-mocks"))]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::SERVICE_ACCESS_ROLE;
+    use crate::repository::mock::MockRepository;
+
+    fn admin_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "admin@example.com".to_string(),
+            hub_id: 7,
+            name: "Admin".to_string(),
+            roles: vec![
+                SERVICE_ACCESS_ROLE.to_string(),
+                SERVICE_ADMIN_ROLE.to_string(),
+            ],
+            exp: 0,
+        }
+    }
+
+    fn payload(roles: &[&str]) -> AddApiKeyPayload {
+        AddApiKeyPayload {
+            name: "ERP".to_string(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn add_api_key_stores_only_the_hash_of_the_returned_key() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_api_key()
+            .withf(|new_api_key| {
+                new_api_key.hub_id.get() == 7
+                    && new_api_key.key_hash.len() == 64
+                    && new_api_key.roles == vec![SERVICE_ACCESS_ROLE.to_string()]
+            })
+            .times(1)
+            .returning(|new_api_key| {
+                Ok(ApiKey::try_new(
+                    3,
+                    new_api_key.hub_id.get(),
+                    new_api_key.name.clone(),
+                    &new_api_key.roles.join(","),
+                    Utc::now().naive_utc(),
+                )
+                .expect("valid api key"))
+            });
+
+        let (api_key, raw_key) =
+            add_api_key(payload(&[SERVICE_ACCESS_ROLE]), &admin_user(), &repo).expect("key");
+
+        assert_eq!(api_key.id.get(), 3);
+        assert_eq!(raw_key.len(), 64);
+    }
+
+    #[test]
+    fn add_api_key_rejects_roles_the_creator_lacks() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_api_key().times(0);
+
+        let result = add_api_key(
+            payload(&[crate::SERVICE_MANAGER_ROLE]),
+            &admin_user(),
+            &repo,
+        );
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn add_api_key_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_api_key().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let result = add_api_key(payload(&[SERVICE_ACCESS_ROLE]), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn delete_api_key_of_another_hub_is_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_delete_api_key()
+            .withf(|id, hub_id| id.get() == 3 && hub_id.get() == 7)
+            .times(1)
+            .returning(|_, _| Ok(false));
+
+        let result = delete_api_key(3, &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+}
@@ -1,8 +1,15 @@
 //! Domain services orchestrating client operations.
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use chrono::{Duration, Utc};
 
 use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::pagination::Pagination;
 use pushkind_common::routes::check_role;
 use pushkind_common::routes::ensure_role;
 use pushkind_common::zmq::ZmqSender;
@@ -14,26 +21,51 @@ use pushkind_emailer::domain::types::HubId as EmailerHubId;
 use pushkind_emailer::domain::types::RecipientEmail;
 use pushkind_emailer::domain::types::RecipientName;
 use pushkind_emailer::models::zmq::ZMQSendEmailMessage;
+use serde::Serialize;
 use serde_json::json;
 
 use crate::SERVICE_ACCESS_ROLE;
+use crate::SERVICE_ADMIN_ROLE;
 use crate::SERVICE_MANAGER_ROLE;
+use crate::domain::audit::{AUDIT_ENTITY_CLIENT, AuditEntry};
 use crate::domain::client::{Client, UpdateClient};
-use crate::domain::client_event::{ClientEventType, NewClientEvent};
+use crate::domain::client_event::ClientEvent;
+use crate::domain::client_event::{ClientEventType, Mention, NewClientEvent, parse_mentions};
+use crate::domain::hub_settings::HubSettings;
 use crate::domain::important_field::ImportantField;
+use crate::domain::manager::Manager;
 use crate::domain::manager::NewManager;
+use crate::domain::note::{ClientNote, NewClientNote};
 use crate::domain::types::ClientId;
+use crate::domain::types::ClientNoteId;
 use crate::domain::types::HubId;
 use crate::domain::types::ManagerEmail;
-use crate::dto::client::{ClientFieldDisplay, ClientOperationOutcome, ClientPageData};
+use crate::domain::types::{AttachmentMime, AttachmentUrl};
+use crate::dto::client::{
+    ClientFieldDisplay, ClientOperationOutcome, ClientPageData, EmailDirection, EmailThread,
+    EmailThreadMessage,
+};
 use crate::forms::client::AddAttachmentPayload;
 use crate::forms::client::AddCommentPayload;
+use crate::forms::client::AddNotePayload;
 use crate::forms::client::SaveClientPayload;
+use crate::forms::client::{CommentAttachment, MAX_COMMENT_ATTACHMENT_BYTES};
+use crate::outbound::{PublicAddrResolver, is_public_url};
+use crate::repository::errors::ClientWriteError;
 use crate::repository::{
-    ClientEventListQuery, ClientEventReader, ClientEventWriter, ClientReader, ClientWriter,
-    ImportantFieldReader, ManagerWriter,
+    AuditReader, ClientEventListQuery, ClientEventReader, ClientEventWriter, ClientReader,
+    ClientWriter, ContactPolicyReader, HubSettingsReader, ImportantFieldReader, ManagerReader,
+    ManagerWriter, NoteReader, NoteWriter, WebhookReader,
 };
-use crate::services::{ServiceError, ServiceResult};
+use crate::services::webhooks::notify_client_event;
+use crate::services::{ServiceError, ServiceOutcome, ServiceResult, log_service_call};
+use crate::webhooks::WebhookSender;
+
+/// Managers shown on the client page; [`ClientPageData::total_managers`] has the full count.
+const CLIENT_DETAILS_MANAGERS_LIMIT: usize = 50;
+
+/// Timeout applied when fetching a linked comment attachment.
+const ATTACHMENT_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 /// Splits client fields into configured important labels and the remaining entries.
 fn partition_client_fields(
@@ -90,6 +122,103 @@ fn normalize_field_value(value: String) -> Option<String> {
     }
 }
 
+/// Strips reply/forward prefixes from an email subject, returning `None` when empty.
+fn normalize_email_subject(subject: &str) -> Option<String> {
+    const PREFIXES: [&str; 3] = ["re:", "fwd:", "fw:"];
+
+    let mut rest = subject.trim();
+    while let Some(prefix) = PREFIXES.iter().find(|prefix| {
+        rest.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+    }) {
+        rest = rest[prefix.len()..].trim_start();
+    }
+
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
+/// Reads the subject of an `Email` or `Reply` event.
+///
+/// Outbound worker emails store the subject in `text` (see SPEC.md), so `Email`
+/// events without a `subject` key fall back to it.
+fn email_subject(event: &ClientEvent) -> Option<String> {
+    let data = &event.event_data;
+    let subject = match data.get("subject") {
+        Some(subject) => subject.as_str(),
+        None if event.event_type == ClientEventType::Email => {
+            data.get("text").and_then(|text| text.as_str())
+        }
+        None => None,
+    };
+
+    subject.and_then(normalize_email_subject)
+}
+
+/// Groups `Email` and `Reply` events into conversations keyed by subject.
+///
+/// Subjects are compared case-insensitively after stripping `Re:`/`Fwd:`
+/// prefixes; messages without a subject share a single thread. Messages within
+/// a thread are ordered oldest first, threads by their latest message, newest first.
+pub fn thread_emails(events: &[(ClientEvent, Manager)]) -> Vec<EmailThread> {
+    let mut threads: Vec<(Option<String>, EmailThread)> = Vec::new();
+
+    for (event, manager) in events {
+        let direction = match event.event_type {
+            ClientEventType::Email => EmailDirection::Outbound,
+            ClientEventType::Reply => EmailDirection::Inbound,
+            _ => continue,
+        };
+
+        let subject = email_subject(event);
+        let key = subject.as_deref().map(str::to_lowercase);
+
+        let index = match threads
+            .iter()
+            .position(|(thread_key, _)| *thread_key == key)
+        {
+            Some(index) => index,
+            None => {
+                threads.push((
+                    key,
+                    EmailThread {
+                        subject,
+                        messages: Vec::new(),
+                    },
+                ));
+                threads.len() - 1
+            }
+        };
+
+        threads[index].1.messages.push(EmailThreadMessage {
+            event: event.clone(),
+            manager: manager.clone(),
+            direction,
+        });
+    }
+
+    let mut threads = threads
+        .into_iter()
+        .map(|(_, mut thread)| {
+            thread
+                .messages
+                .sort_by(|a, b| a.event.created_at.cmp(&b.event.created_at));
+            thread
+        })
+        .collect::<Vec<_>>();
+
+    threads.sort_by(|a, b| {
+        let latest = |thread: &EmailThread| thread.messages.last().map(|m| m.event.created_at);
+        latest(b).cmp(&latest(a))
+    });
+
+    threads
+}
+
+/// Path of the client page that mutations of `client_id` return to.
+fn client_page_path(client_id: ClientId) -> String {
+    format!("/client/{client_id}")
+}
+
 /// Ensures that the current user has access to the provided client identifier.
 fn ensure_client_access<R>(
     client_id: ClientId,
@@ -109,6 +238,7 @@ where
 }
 
 /// Performs the minimal access/existence check required before serving the client document.
+#[tracing::instrument(skip(repo, user))]
 pub fn verify_client_page_access<R>(
     client_id: i32,
     user: &AuthenticatedUser,
@@ -117,6 +247,7 @@ pub fn verify_client_page_access<R>(
 where
     R: ClientReader + ?Sized,
 {
+    log_service_call("verify_client_page_access", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let client_id = ClientId::new(client_id)?;
@@ -130,14 +261,16 @@ where
 }
 
 /// Aggregates all data required by the client details page, applying access rules.
+#[tracing::instrument(skip(repo, user))]
 pub fn load_client_details<R>(
     client_id: i32,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<ClientPageData>
 where
-    R: ClientReader + ClientEventReader + ImportantFieldReader + ?Sized,
+    R: ClientReader + ClientEventReader + ImportantFieldReader + NoteReader + ?Sized,
 {
+    log_service_call("load_client_details", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let client_id = ClientId::new(client_id)?;
@@ -149,17 +282,27 @@ where
         .get_client_by_id(client_id, hub_id)?
         .ok_or(ServiceError::NotFound)?;
 
-    let managers = repo.list_managers(client_id)?;
+    let (total_managers, managers) = repo.list_managers_for_client(
+        client_id,
+        Some(Pagination {
+            page: 1,
+            per_page: CLIENT_DETAILS_MANAGERS_LIMIT,
+        }),
+    )?;
 
     let (total_events, events_with_managers) =
         repo.list_client_events(ClientEventListQuery::new(client_id))?;
 
+    let email_threads = thread_emails(&events_with_managers);
+
     let documents = events_with_managers
         .iter()
         .filter(|&(event, _)| event.event_type == ClientEventType::DocumentLink)
         .map(|(event, _)| event.clone())
         .collect::<Vec<_>>();
 
+    let notes = repo.list_client_notes(client_id)?;
+
     let available_fields = repo.list_available_fields(hub_id)?;
 
     let important_field_names = repo.list_important_fields(hub_id)?;
@@ -170,30 +313,73 @@ where
         client,
         managers,
         events_with_managers,
+        email_threads,
         documents,
+        notes,
         available_fields,
         important_fields,
         other_fields,
         total_events,
+        total_managers,
     })
 }
 
+/// Lists one page of a client's events, newest first, optionally limited to
+/// a single event type.
+#[tracing::instrument(skip(repo, user))]
+pub fn list_client_events_page<R>(
+    client_id: i32,
+    event_type: Option<ClientEventType>,
+    pagination: Pagination,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<(usize, Vec<(ClientEvent, Manager)>)>
+where
+    R: ClientReader + ClientEventReader + ?Sized,
+{
+    log_service_call("list_client_events_page", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    repo.get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    let mut query =
+        ClientEventListQuery::new(client_id).paginate(pagination.page.max(1), pagination.per_page);
+    if let Some(event_type) = event_type {
+        query = query.event_type(event_type);
+    }
+
+    Ok(repo.list_client_events(query)?)
+}
+
 /// Applies updates submitted through the save client form.
+///
+/// Returns [`ServiceError::Conflict`] when the form carried a client version
+/// that has since been saved by someone else.
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload))]
 pub fn save_client<R>(
     client_id: i32,
     payload: SaveClientPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<ClientOperationOutcome>
+) -> ServiceResult<ServiceOutcome>
 where
-    R: ClientReader + ClientWriter + ?Sized,
+    R: ClientReader + ClientWriter + ContactPolicyReader + ManagerWriter + ?Sized,
 {
+    log_service_call("save_client", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let client_id = ClientId::new(client_id)?;
     let hub_id = HubId::new(user.hub_id)?;
 
-    let updates: UpdateClient = payload.into();
+    let policy = repo.get_contact_policy(hub_id)?;
+    payload.check_contact_policy(&policy)?;
 
     let client = repo
         .get_client_by_id(client_id, hub_id)?
@@ -201,24 +387,173 @@ where
 
     ensure_client_access(client.id, user, repo)?;
 
-    let updated_client = repo.update_client(client_id, &updates)?;
+    let manager_payload = NewManager::try_from(user).map_err(|err| {
+        tracing::error!("Failed to build manager from user: {err}");
+        ServiceError::Internal
+    })?;
+    let editor = repo.create_or_update_manager(&manager_payload)?;
 
-    Ok(ClientOperationOutcome {
-        client_id: updated_client.id,
-    })
+    let updates = UpdateClient::from(payload).edited_by(editor.id);
+
+    let updated_client = match repo.update_client(client_id, &updates, &editor.email) {
+        Ok(client) => client,
+        Err(err @ ClientWriteError::StaleVersion { .. }) => {
+            tracing::warn!("Rejected stale client save: {err}");
+            return Err(ServiceError::Conflict);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    tracing::info!(client_id = %updated_client.id, "Client saved");
+
+    Ok(ServiceOutcome::new(
+        "Клиент обновлен.",
+        client_page_path(updated_client.id),
+    ))
+}
+
+/// Returns the custom fields of a client after applying access rules.
+#[tracing::instrument(skip(repo, user))]
+pub fn load_client_fields<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<BTreeMap<String, String>>
+where
+    R: ClientReader + ?Sized,
+{
+    log_service_call("load_client_fields", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    Ok(client.fields.unwrap_or_default())
+}
+
+/// Returns the managers assigned to a hub client after applying access rules.
+#[tracing::instrument(skip(repo, user))]
+pub fn load_client_managers<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<Manager>>
+where
+    R: ClientReader + ?Sized,
+{
+    log_service_call("load_client_managers", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    Ok(repo.list_all_managers_for_client(client.id)?)
+}
+
+/// Replaces the custom fields of a hub client without touching its name or
+/// contacts. Blank field names are dropped.
+#[tracing::instrument(skip(repo, user, fields))]
+pub fn replace_client_fields<R>(
+    client_id: i32,
+    fields: BTreeMap<String, String>,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Client>
+where
+    R: ClientReader + ClientWriter + ManagerWriter + ?Sized,
+{
+    log_service_call("replace_client_fields", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    ensure_client_access(client.id, user, repo)?;
+
+    let manager_payload = NewManager::try_from(user).map_err(|err| {
+        tracing::error!("Failed to build manager from user: {err}");
+        ServiceError::Internal
+    })?;
+    let editor = repo.create_or_update_manager(&manager_payload)?;
+
+    let fields = fields
+        .into_iter()
+        .map(|(field, value)| (field.trim().to_string(), value))
+        .filter(|(field, _)| !field.is_empty())
+        .collect::<BTreeMap<_, _>>();
+
+    let updated_client = repo.replace_client_fields(client.id, &fields, &editor.email)?;
+    tracing::info!(client_id = %updated_client.id, fields = fields.len(), "Client fields replaced");
+
+    Ok(updated_client)
+}
+
+/// Identity a client email is sent as: the hub sender name replaces the
+/// manager's own when configured. The email stays the manager's, because the
+/// emailer and `check_events` identify the sending manager by it.
+fn email_sender(user: &AuthenticatedUser, settings: &HubSettings) -> AuthenticatedUser {
+    let mut sender = user.clone();
+    if let Some(name) = &settings.email_sender_name {
+        sender.name = name.as_str().to_string();
+    }
+    sender
+}
+
+/// Email queued for the emailer with the hub Reply-To address alongside it.
+#[derive(Serialize)]
+struct OutboundEmail {
+    #[serde(flatten)]
+    email: NewEmail,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to: Option<String>,
+}
+
+/// Same wire shape as [`ZMQSendEmailMessage::NewEmail`], plus `reply_to`.
+#[derive(Serialize)]
+enum OutboundEmailMessage {
+    NewEmail(Box<(AuthenticatedUser, OutboundEmail)>),
 }
 
 /// Adds a comment or event for a client, sending emails when requested.
-pub async fn add_comment<R>(
+///
+/// Every email handed to the emailer increments `emails_enqueued`.
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload, zmq_sender, webhook_sender, emails_enqueued))]
+pub async fn add_comment<R, S>(
     client_id: i32,
     payload: AddCommentPayload,
     user: &AuthenticatedUser,
     repo: &R,
     zmq_sender: &ZmqSender,
-) -> ServiceResult<ClientOperationOutcome>
+    webhook_sender: &S,
+    emails_enqueued: &AtomicU64,
+) -> ServiceResult<ServiceOutcome>
 where
-    R: ClientReader + ClientEventWriter + ManagerWriter + ?Sized,
+    R: ClientReader
+        + ClientEventWriter
+        + HubSettingsReader
+        + ManagerReader
+        + ManagerWriter
+        + WebhookReader
+        + ?Sized,
+    S: WebhookSender + ?Sized,
 {
+    log_service_call("add_comment", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let client_id = ClientId::new(client_id)?;
@@ -227,7 +562,7 @@ where
     ensure_client_access(client_id, user, repo)?;
 
     let manager_payload = NewManager::try_from(user).map_err(|err| {
-        log::error!("Failed to build manager from user: {err}");
+        tracing::error!("Failed to build manager from user: {err}");
         ServiceError::Internal
     })?;
 
@@ -237,13 +572,39 @@ where
         .get_client_by_id(client_id, hub_id)?
         .ok_or(ServiceError::NotFound)?;
 
+    // Resolved before the email goes out so a lookup failure cannot make the
+    // user retry an email that was already sent.
+    let mentioned = resolve_mentions(payload.message.as_str(), hub_id, &manager, repo)?;
+
+    // Name, type and size of the document sent with an email, kept on the event.
+    let mut attachment_metadata = None;
+
     if matches!(payload.event_type, ClientEventType::Email) {
         let client_email = client.email.as_ref().ok_or_else(|| {
-            log::warn!("Client {client_id} has no email to send message");
+            tracing::warn!("Client {client_id} has no email to send message");
             ServiceError::Form("Клиент не имеет email".to_string())
         })?;
 
+        let (attachment, attachment_name, attachment_mime) = match payload.attachment {
+            Some(attachment) => {
+                let attachment = load_comment_attachment(attachment).await?;
+                attachment_metadata = Some(json!({
+                    "name": attachment.name.as_str(),
+                    "mime": attachment.mime.as_str(),
+                    "size_bytes": attachment.content.len(),
+                }));
+                (
+                    Some(BASE64_STANDARD.encode(&attachment.content)),
+                    Some(attachment.name),
+                    Some(attachment.mime.into_inner()),
+                )
+            }
+            None => (None, None, None),
+        };
+
         let fields: BTreeMap<String, String> = client.fields.clone().unwrap_or_default();
+        let settings = repo.get_hub_settings(hub_id)?;
+        let sender = email_sender(user, &settings);
 
         let hub_id = EmailerHubId::new(user.hub_id)
             .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?;
@@ -257,9 +618,9 @@ where
                 .map(EmailSubject::new)
                 .transpose()
                 .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
-            attachment: None,
-            attachment_name: None,
-            attachment_mime: None,
+            attachment,
+            attachment_name,
+            attachment_mime,
             hub_id,
             recipients: vec![NewEmailRecipient {
                 address: RecipientEmail::new(client_email.clone().into_inner())
@@ -270,38 +631,307 @@ where
             }],
         };
 
-        let zmq_message = ZMQSendEmailMessage::NewEmail(Box::new((user.clone(), new_email)));
+        let zmq_message = OutboundEmailMessage::NewEmail(Box::new((
+            sender,
+            OutboundEmail {
+                email: new_email,
+                reply_to: settings
+                    .email_reply_to
+                    .map(|reply_to| reply_to.as_str().to_string()),
+            },
+        )));
 
         if let Err(err) = zmq_sender.send_json(&zmq_message).await {
-            log::error!("Failed to enqueue email for client {client_id}: {err}");
+            tracing::error!("Failed to enqueue email for client {client_id}: {err}");
             return Err(ServiceError::Internal);
         }
+        emails_enqueued.fetch_add(1, Ordering::Relaxed);
+    } else if payload.attachment.is_some() {
+        tracing::warn!("Ignoring attachment of non-email event for client {client_id}");
     }
 
     let mut event_data = json!({ "text": payload.message.as_str() });
     if let Some(subject) = payload.subject {
         event_data["subject"] = json!(subject.as_str());
     }
+    if let Some(attachment) = attachment_metadata {
+        event_data["attachment"] = attachment;
+    }
+    if !mentioned.is_empty() {
+        let mention_ids: Vec<i32> = mentioned.iter().map(|manager| manager.id.get()).collect();
+        event_data["mentions"] = json!(mention_ids);
+    }
 
     let new_event = NewClientEvent::new(client.id, manager.id, payload.event_type, event_data);
 
-    repo.create_client_event(&new_event)?;
+    let event = repo.create_client_event(&new_event)?;
+    tracing::info!(client_id = %client.id, event_id = %event.id, "Client comment added");
+    notify_client_event(hub_id, &event, repo, webhook_sender);
+
+    if !mentioned.is_empty() {
+        notify_mentioned_managers(
+            &mentioned,
+            &manager,
+            &client,
+            &payload.message.to_plain_text(),
+            user,
+            zmq_sender,
+            emails_enqueued,
+        )
+        .await;
+    }
 
-    Ok(ClientOperationOutcome {
-        client_id: client.id,
+    Ok(ServiceOutcome::new(
+        "Событие добавлено.",
+        client_page_path(client.id),
+    ))
+}
+
+/// Document attached to an outbound comment email.
+struct EmailAttachment {
+    name: String,
+    mime: AttachmentMime,
+    content: Vec<u8>,
+}
+
+/// Reads the document attached to a comment email, fetching linked documents.
+async fn load_comment_attachment(attachment: CommentAttachment) -> ServiceResult<EmailAttachment> {
+    match attachment {
+        CommentAttachment::Upload {
+            name,
+            mime,
+            content,
+        } => Ok(EmailAttachment {
+            name: name.into_inner(),
+            mime,
+            content,
+        }),
+        CommentAttachment::Url(url) => fetch_attachment(&url).await,
+    }
+}
+
+/// Downloads a linked document, enforcing [`MAX_COMMENT_ATTACHMENT_BYTES`] and
+/// the allowed MIME types.
+///
+/// The fetched bytes are emailed to the client, so the request may only reach
+/// public addresses: the host is resolved through [`PublicAddrResolver`], which
+/// pins the connection to the vetted addresses, and redirects are not followed
+/// so a public URL cannot bounce the request to an internal host.
+async fn fetch_attachment(url: &AttachmentUrl) -> ServiceResult<EmailAttachment> {
+    let unavailable = || ServiceError::Form("Не удалось загрузить документ по ссылке.".to_string());
+    let too_large = || ServiceError::Form("Документ больше 10 МБ.".to_string());
+
+    if !is_public_url(url.as_str()) {
+        tracing::warn!("Refusing to fetch attachment from non-public address {url}");
+        return Err(unavailable());
+    }
+
+    let http = reqwest::Client::builder()
+        .timeout(ATTACHMENT_FETCH_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicAddrResolver))
+        .build()
+        .map_err(|err| {
+            tracing::error!("Failed to build attachment HTTP client: {err}");
+            ServiceError::Internal
+        })?;
+
+    let mut response = http.get(url.as_str()).send().await.map_err(|err| {
+        tracing::warn!("Failed to fetch attachment {url}: {err}");
+        unavailable()
+    })?;
+    if !response.status().is_success() {
+        tracing::warn!(
+            "Fetching attachment {url} failed with status {}",
+            response.status()
+        );
+        return Err(unavailable());
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_COMMENT_ATTACHMENT_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mime = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| AttachmentMime::new(value).ok())
+        .ok_or_else(|| ServiceError::Form("Недопустимый тип документа.".to_string()))?;
+
+    let mut content = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|err| {
+        tracing::warn!("Failed to read attachment {url}: {err}");
+        unavailable()
+    })? {
+        if content.len() + chunk.len() > MAX_COMMENT_ATTACHMENT_BYTES {
+            return Err(too_large());
+        }
+        content.extend_from_slice(&chunk);
+    }
+
+    Ok(EmailAttachment {
+        name: attachment_file_name(url),
+        mime,
+        content,
+    })
+}
+
+/// Names a linked document after the last segment of its URL path.
+fn attachment_file_name(url: &AttachmentUrl) -> String {
+    url::Url::parse(url.as_str())
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .rfind(|segment| !segment.is_empty())
+                .map(|segment| {
+                    urlencoding::decode(segment)
+                        .map(|name| name.into_owned())
+                        .unwrap_or_else(|_| segment.to_string())
+                })
+        })
+        .unwrap_or_else(|| "attachment".to_string())
+}
+
+/// Resolves the `@email` and `@name` mentions of a comment to active user
+/// managers of the hub.
+///
+/// Mentions matching no manager or several managers by name are ignored, as is
+/// the author mentioning themself.
+fn resolve_mentions<R>(
+    text: &str,
+    hub_id: HubId,
+    author: &Manager,
+    repo: &R,
+) -> ServiceResult<Vec<Manager>>
+where
+    R: ManagerReader + ?Sized,
+{
+    let mut mentioned: Vec<Manager> = Vec::new();
+
+    for mention in parse_mentions(text) {
+        let manager = match mention {
+            Mention::Email(email) => repo
+                .get_manager_by_email(&email, hub_id)?
+                .filter(|manager| manager.is_user && manager.is_active),
+            Mention::Name(name) => {
+                let mut matches = repo.find_managers_by_name(&name, hub_id)?;
+                if matches.len() == 1 {
+                    matches.pop()
+                } else {
+                    None
+                }
+            }
+        };
+
+        if let Some(manager) = manager
+            && manager.id != author.id
+            && mentioned.iter().all(|known| known.id != manager.id)
+        {
+            mentioned.push(manager);
+        }
+    }
+
+    Ok(mentioned)
+}
+
+/// Builds the email notifying `mentioned` managers about a comment.
+fn mention_email(
+    mentioned: &[Manager],
+    author: &Manager,
+    client: &Client,
+    text: &str,
+    hub_id: i32,
+) -> ServiceResult<NewEmail> {
+    let message = format!(
+        "{} упомянул(а) вас в комментарии к клиенту «{}»:\n\n{text}",
+        author.name, client.name
+    );
+    let subject = format!("Упоминание в CRM: {}", client.name);
+
+    Ok(NewEmail {
+        message: EmailBody::new(message.as_str())
+            .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
+        subject: Some(
+            EmailSubject::new(subject.as_str())
+                .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
+        ),
+        attachment: None,
+        attachment_name: None,
+        attachment_mime: None,
+        hub_id: EmailerHubId::new(hub_id)
+            .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
+        recipients: mentioned
+            .iter()
+            .map(|manager| {
+                Ok(NewEmailRecipient {
+                    address: RecipientEmail::new(manager.email.clone().into_inner())
+                        .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
+                    name: RecipientName::new(manager.name.as_str())
+                        .map_err(|error| ServiceError::TypeConstraint(error.to_string()))?,
+                    fields: BTreeMap::new(),
+                })
+            })
+            .collect::<ServiceResult<_>>()?,
     })
 }
 
+/// Emails the managers mentioned in a comment.
+///
+/// The comment is already stored, so failures are only logged.
+async fn notify_mentioned_managers(
+    mentioned: &[Manager],
+    author: &Manager,
+    client: &Client,
+    text: &str,
+    user: &AuthenticatedUser,
+    zmq_sender: &ZmqSender,
+    emails_enqueued: &AtomicU64,
+) {
+    let new_email = match mention_email(mentioned, author, client, text, user.hub_id) {
+        Ok(new_email) => new_email,
+        Err(err) => {
+            tracing::error!(
+                "Failed to build mention email for client {}: {err}",
+                client.id
+            );
+            return;
+        }
+    };
+
+    let zmq_message = ZMQSendEmailMessage::NewEmail(Box::new((user.clone(), new_email)));
+    if let Err(err) = zmq_sender.send_json(&zmq_message).await {
+        tracing::error!(
+            "Failed to enqueue mention email for client {}: {err}",
+            client.id
+        );
+        return;
+    }
+    emails_enqueued.fetch_add(1, Ordering::Relaxed);
+    tracing::info!(
+        client_id = %client.id,
+        mentioned = mentioned.len(),
+        "Mention notification enqueued"
+    );
+}
+
 /// Adds an attachment event for the client.
-pub fn add_attachment<R>(
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload, webhook_sender))]
+pub fn add_attachment<R, S>(
     client_id: i32,
     payload: AddAttachmentPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<ClientOperationOutcome>
+    webhook_sender: &S,
+) -> ServiceResult<ServiceOutcome>
 where
-    R: ClientReader + ClientEventWriter + ManagerWriter + ?Sized,
+    R: ClientReader + ClientEventWriter + ManagerWriter + WebhookReader + ?Sized,
+    S: WebhookSender + ?Sized,
 {
+    log_service_call("add_attachment", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let client_id = ClientId::new(client_id)?;
@@ -310,7 +940,7 @@ where
     ensure_client_access(client_id, user, repo)?;
 
     let manager_payload = NewManager::try_from(user).map_err(|err| {
-        log::error!("Failed to build manager from user: {err}");
+        tracing::error!("Failed to build manager from user: {err}");
         ServiceError::Internal
     })?;
     let manager = repo.create_or_update_manager(&manager_payload)?;
@@ -319,27 +949,228 @@ where
         .get_client_by_id(client_id, hub_id)?
         .ok_or(ServiceError::NotFound)?;
 
+    let mut event_data = json!({
+        "text": payload.text.as_str(),
+        "url": payload.url.as_str(),
+    });
+    if let Some(mime) = &payload.mime {
+        event_data["mime"] = json!(mime.as_str());
+    }
+    if let Some(size_bytes) = payload.size_bytes {
+        event_data["size_bytes"] = json!(size_bytes);
+    }
+
     let event = NewClientEvent::new(
         client.id,
         manager.id,
         ClientEventType::DocumentLink,
-        json!({
-            "text": payload.text.as_str(),
-            "url": payload.url.as_str(),
-        }),
+        event_data,
     );
 
-    repo.create_client_event(&event)?;
+    let event = repo.create_client_event(&event)?;
+    tracing::info!(client_id = %client.id, event_id = %event.id, "Client attachment added");
+    notify_client_event(hub_id, &event, repo, webhook_sender);
+
+    Ok(ServiceOutcome::new(
+        "Событие добавлено.",
+        client_page_path(client.id),
+    ))
+}
+
+/// Lists internal notes of a client after applying access rules.
+#[tracing::instrument(skip(repo, user))]
+pub fn list_client_notes<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ClientNote>>
+where
+    R: ClientReader + NoteReader + ?Sized,
+{
+    log_service_call("list_client_notes", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    Ok(repo.list_client_notes(client.id)?)
+}
+
+/// Lists the audit history of a client, newest first. Requires the admin role.
+///
+/// Entries stay readable after the client is deleted.
+#[tracing::instrument(skip(repo, user))]
+pub fn list_client_audit<R>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<AuditEntry>>
+where
+    R: AuditReader + ?Sized,
+{
+    log_service_call("list_client_audit", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.list_audit_entries(hub_id, AUDIT_ENTITY_CLIENT, client_id.get())?)
+}
+
+/// Adds an internal note for the client.
+#[tracing::instrument(skip(repo, user, payload))]
+pub fn add_note<R>(
+    client_id: i32,
+    payload: AddNotePayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientOperationOutcome>
+where
+    R: ClientReader + NoteWriter + ManagerWriter + ?Sized,
+{
+    log_service_call("add_note", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    let manager_payload = NewManager::try_from(user).map_err(|err| {
+        tracing::error!("Failed to build manager from user: {err}");
+        ServiceError::Internal
+    })?;
+    let manager = repo.create_or_update_manager(&manager_payload)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    let note = NewClientNote::new(client.id, manager.id, payload.text);
+
+    let note = repo.create_client_note(&note)?;
+    tracing::info!(client_id = %client.id, note_id = %note.id, "Client note added");
+
+    Ok(ClientOperationOutcome {
+        client_id: client.id,
+    })
+}
+
+/// Pins or unpins an internal note of the client.
+#[tracing::instrument(skip(repo, user))]
+pub fn toggle_note_pin<R>(
+    client_id: i32,
+    note_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientOperationOutcome>
+where
+    R: ClientReader + NoteWriter + ?Sized,
+{
+    log_service_call("toggle_note_pin", user);
+    ensure_role(user, SERVICE_ACCESS_ROLE)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let note_id = ClientNoteId::new(note_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    ensure_client_access(client_id, user, repo)?;
+
+    let client = repo
+        .get_client_by_id(client_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    let note = repo
+        .toggle_client_note_pin(note_id, client.id)?
+        .ok_or(ServiceError::NotFound)?;
+    tracing::info!(client_id = %client.id, %note_id, is_pinned = note.is_pinned, "Client note pin toggled");
 
     Ok(ClientOperationOutcome {
         client_id: client.id,
     })
 }
 
+/// Deletes the hub's client events older than `retention_days`.
+#[tracing::instrument(skip(repo, user))]
+pub fn cleanup_old_events<R>(
+    repo: &R,
+    user: &AuthenticatedUser,
+    retention_days: u32,
+) -> ServiceResult<usize>
+where
+    R: ClientEventWriter + ?Sized,
+{
+    log_service_call("cleanup_old_events", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    delete_expired_events(repo, hub_id, retention_days)
+}
+
+/// Deletes the hub's client events older than its configured retention period.
+pub fn cleanup_events_by_hub_settings<R>(repo: &R, user: &AuthenticatedUser) -> ServiceResult<usize>
+where
+    R: HubSettingsReader + ClientEventWriter + ?Sized,
+{
+    log_service_call("cleanup_events_by_hub_settings", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let Some(retention_days) = repo.get_hub_settings(hub_id)?.event_retention_days else {
+        return Err(ServiceError::Form(
+            "Срок хранения событий не настроен.".to_string(),
+        ));
+    };
+
+    cleanup_old_events(repo, user, retention_days)
+}
+
+/// Applies the event retention of every hub that configured one.
+///
+/// Runs without a user, so it is meant for background workers only.
+pub fn cleanup_events_for_all_hubs<R>(repo: &R) -> ServiceResult<usize>
+where
+    R: HubSettingsReader + ClientEventWriter + ?Sized,
+{
+    let mut deleted = 0;
+    for settings in repo.list_hub_settings_with_event_retention()? {
+        if let Some(retention_days) = settings.event_retention_days {
+            deleted += delete_expired_events(repo, settings.hub_id, retention_days)?;
+        }
+    }
+
+    Ok(deleted)
+}
+
+fn delete_expired_events<R>(repo: &R, hub_id: HubId, retention_days: u32) -> ServiceResult<usize>
+where
+    R: ClientEventWriter + ?Sized,
+{
+    let cutoff = Utc::now().naive_utc() - Duration::days(i64::from(retention_days));
+
+    let deleted = repo
+        .delete_events_older_than(hub_id, cutoff)
+        .map_err(|err| {
+            log::error!("Failed to delete old client events: {err}");
+            err
+        })?;
+    tracing::info!(%hub_id, retention_days, deleted, "Old client events deleted");
+
+    Ok(deleted)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::types::{ClientId, ClientName, HubId, PublicId};
+    use crate::domain::client::ClientStatus;
+    use crate::domain::types::{ClientId, ClientName, HubId, NonEmptyString, PublicId};
     use chrono::Utc;
     use std::collections::BTreeMap;
 
@@ -360,6 +1191,10 @@ mod tests {
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
             fields: if map.is_empty() { None } else { Some(map) },
+            status: ClientStatus::Active,
+            source: None,
+            raw_phone: None,
+            version: 1,
         }
     }
 
@@ -428,4 +1263,582 @@ mod tests {
         );
         assert!(other.is_empty());
     }
+
+    fn email_event(
+        id: i32,
+        event_type: ClientEventType,
+        data: serde_json::Value,
+        minute: u32,
+    ) -> (ClientEvent, Manager) {
+        let created_at = chrono::NaiveDate::from_ymd_opt(2026, 1, 1)
+            .and_then(|date| date.and_hms_opt(12, minute, 0))
+            .expect("valid timestamp");
+        let is_user = event_type != ClientEventType::Reply;
+        (
+            ClientEvent::try_new(id, 1, 1, event_type, data, created_at).expect("valid event"),
+            Manager::try_new(
+                1,
+                1,
+                "Manager".to_string(),
+                "manager@example.com".to_string(),
+                is_user,
+            )
+            .expect("valid manager"),
+        )
+    }
+
+    fn event_ids(thread: &EmailThread) -> Vec<i32> {
+        thread
+            .messages
+            .iter()
+            .map(|message| message.event.id.get())
+            .collect()
+    }
+
+    /// Verifies that interleaved subjects are split into chronological threads.
+    #[test]
+    fn thread_emails_groups_interleaved_subjects() {
+        // Events arrive newest first, as returned by the repository.
+        let events = vec![
+            email_event(
+                5,
+                ClientEventType::Reply,
+                json!({"subject": "RE: Invoice", "text": "Paid"}),
+                5,
+            ),
+            email_event(4, ClientEventType::Comment, json!({"text": "Call me"}), 4),
+            email_event(
+                3,
+                ClientEventType::Reply,
+                json!({"subject": "Re: Re: Offer", "text": "Thanks"}),
+                3,
+            ),
+            email_event(2, ClientEventType::Email, json!({"text": "Invoice"}), 2),
+            email_event(
+                1,
+                ClientEventType::Email,
+                json!({"text": "Hello", "subject": "Offer"}),
+                1,
+            ),
+        ];
+
+        let threads = thread_emails(&events);
+
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].subject.as_deref(), Some("Invoice"));
+        assert_eq!(event_ids(&threads[0]), vec![2, 5]);
+        assert_eq!(
+            threads[0]
+                .messages
+                .iter()
+                .map(|message| message.direction)
+                .collect::<Vec<_>>(),
+            vec![EmailDirection::Outbound, EmailDirection::Inbound]
+        );
+        assert_eq!(threads[1].subject.as_deref(), Some("Offer"));
+        assert_eq!(event_ids(&threads[1]), vec![1, 3]);
+    }
+
+    /// Verifies that messages without a subject share a single thread.
+    #[test]
+    fn thread_emails_collects_missing_subjects() {
+        let events = vec![
+            email_event(3, ClientEventType::Reply, json!({"text": "No subject"}), 3),
+            email_event(2, ClientEventType::Email, json!({"text": null}), 2),
+            email_event(
+                1,
+                ClientEventType::Reply,
+                json!({"subject": "  Re:  ", "text": "Empty"}),
+                1,
+            ),
+        ];
+
+        let threads = thread_emails(&events);
+
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].subject, None);
+        assert_eq!(event_ids(&threads[0]), vec![1, 2, 3]);
+    }
+
+    fn hub_manager(id: i32, name: &str, email: &str) -> Manager {
+        Manager::try_new(id, 1, name.to_string(), email.to_string(), true).expect("valid manager")
+    }
+
+    /// Verifies that mentions resolve to distinct hub managers other than the author.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn resolve_mentions_ignores_unknown_ambiguous_and_own_mentions() {
+        use crate::repository::mock::MockRepository;
+
+        let hub_id = HubId::new(1).expect("valid hub id");
+        let author = hub_manager(1, "Author", "author@example.com");
+
+        let mut repo = MockRepository::new();
+        repo.expect_find_managers_by_name().returning(|name, _| {
+            Ok(match name {
+                "alice" => vec![hub_manager(2, "Alice", "alice@example.com")],
+                "ivan" => vec![
+                    hub_manager(3, "Ivan Petrov", "petrov@example.com"),
+                    hub_manager(4, "Ivan Sidorov", "sidorov@example.com"),
+                ],
+                _ => Vec::new(),
+            })
+        });
+        repo.expect_get_manager_by_email().returning(|email, _| {
+            Ok(match email.as_str() {
+                "alice@example.com" => Some(hub_manager(2, "Alice", "alice@example.com")),
+                "author@example.com" => Some(hub_manager(1, "Author", "author@example.com")),
+                "archived@example.com" => {
+                    Some(hub_manager(5, "Archived", "archived@example.com").with_active(false))
+                }
+                _ => None,
+            })
+        });
+
+        let mentioned = resolve_mentions(
+            "@alice @ivan @nobody @alice@example.com @author@example.com @archived@example.com",
+            hub_id,
+            &author,
+            &repo,
+        )
+        .expect("mentions resolved");
+
+        assert_eq!(
+            mentioned
+                .iter()
+                .map(|manager| manager.id.get())
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn attachment_file_name_uses_the_last_path_segment() {
+        let name = |url: &str| attachment_file_name(&AttachmentUrl::new(url).expect("valid url"));
+
+        assert_eq!(
+            name("https://files.example.com/docs/%D0%B4%D0%BE%D0%B3%D0%BE%D0%B2%D0%BE%D1%80.pdf"),
+            "договор.pdf"
+        );
+        assert_eq!(
+            name("https://files.example.com/docs/offer.pdf/"),
+            "offer.pdf"
+        );
+        assert_eq!(name("https://files.example.com"), "attachment");
+    }
+
+    /// Verifies that adding an attachment stores its metadata and reports the
+    /// Russian success message.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn add_attachment_returns_event_added_outcome() {
+        use crate::domain::types::AttachmentName;
+        use crate::repository::mock::MockRepository;
+        use crate::webhooks::WebhookDelivery;
+
+        struct NoopWebhookSender;
+
+        impl WebhookSender for NoopWebhookSender {
+            fn enqueue(&self, _delivery: WebhookDelivery) {}
+        }
+
+        let user = AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 1,
+            name: "Manager".to_string(),
+            roles: vec![SERVICE_ACCESS_ROLE.to_string()],
+            exp: 0,
+        };
+
+        let mut repo = MockRepository::new();
+        repo.expect_create_or_update_manager()
+            .times(1)
+            .returning(|_| Ok(hub_manager(1, "Manager", "manager@example.com")));
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(client_with_fields(Vec::new()))));
+        repo.expect_create_client_event()
+            .withf(|event| {
+                event.event_data["mime"] == "application/pdf"
+                    && event.event_data["size_bytes"] == 2_097_152
+            })
+            .times(1)
+            .returning(|event| {
+                Ok(ClientEvent::try_new(
+                    7,
+                    event.client_id.get(),
+                    event.manager_id.get(),
+                    event.event_type.clone(),
+                    event.event_data.clone(),
+                    Utc::now().naive_utc(),
+                )
+                .expect("valid event"))
+            });
+        repo.expect_list_webhooks().returning(|_| Ok(Vec::new()));
+
+        let payload = AddAttachmentPayload {
+            text: AttachmentName::new("Договор").expect("valid name"),
+            url: AttachmentUrl::new("https://files.example.com/contract.pdf").expect("valid url"),
+            mime: Some(AttachmentMime::new("application/pdf").expect("valid mime")),
+            size_bytes: Some(2_097_152),
+        };
+
+        let outcome =
+            add_attachment(1, payload, &user, &repo, &NoopWebhookSender).expect("attachment added");
+
+        assert_eq!(
+            outcome,
+            ServiceOutcome::new("Событие добавлено.", "/client/1")
+        );
+        assert_eq!(outcome.warning, None);
+    }
+
+    /// Verifies that a save based on an outdated version reports a conflict.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn save_client_rejects_stale_version() {
+        use crate::domain::contact_policy::ContactPolicy;
+        use crate::domain::types::ClientEmail;
+        use crate::repository::mock::MockRepository;
+
+        let user = AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 1,
+            name: "Manager".to_string(),
+            roles: vec![SERVICE_ACCESS_ROLE.to_string()],
+            exp: 0,
+        };
+
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(client_with_fields(Vec::new()).with_version(3))));
+        repo.expect_create_or_update_manager()
+            .times(1)
+            .returning(|_| Ok(hub_manager(1, "Manager", "manager@example.com")));
+        repo.expect_update_client()
+            .withf(|_, updates, _| updates.expected_version == Some(2))
+            .times(1)
+            .returning(|client_id, _, _| {
+                Err(ClientWriteError::StaleVersion {
+                    client_id: client_id.get(),
+                    expected_version: 2,
+                })
+            });
+
+        let payload = SaveClientPayload {
+            name: ClientName::new("Test").expect("valid name"),
+            email: Some(ClientEmail::new("test@example.com").expect("valid email")),
+            phone: None,
+            fields: None,
+            version: Some(2),
+        };
+
+        let result = save_client(1, payload, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Conflict)));
+    }
+
+    #[cfg(feature = "test-mocks")]
+    fn note_user(roles: &[&str]) -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 1,
+            name: "Manager".to_string(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            exp: 0,
+        }
+    }
+
+    #[cfg(feature = "test-mocks")]
+    fn client_note(id: i32, text: &str, is_pinned: bool) -> ClientNote {
+        let now = Utc::now().naive_utc();
+        ClientNote::try_new(id, 1, 1, text.to_string(), now, now, is_pinned).expect("valid note")
+    }
+
+    /// Verifies that notes are listed for the client resolved within the user's hub.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn list_client_notes_returns_notes_of_the_hub_client() {
+        use crate::repository::mock::MockRepository;
+
+        let user = note_user(&[SERVICE_ACCESS_ROLE]);
+
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .withf(|client_id, hub_id| client_id.get() == 1 && hub_id.get() == 1)
+            .times(1)
+            .returning(|_, _| Ok(Some(client_with_fields(Vec::new()))));
+        repo.expect_list_client_notes()
+            .withf(|client_id| client_id.get() == 1)
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    client_note(2, "Pinned", true),
+                    client_note(1, "Older", false),
+                ])
+            });
+
+        let notes = list_client_notes(1, &user, &repo).expect("notes listed");
+
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].text.as_str(), "Pinned");
+    }
+
+    /// Verifies that a manager cannot read notes of a client not assigned to them.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn list_client_notes_rejects_unassigned_manager() {
+        use crate::repository::mock::MockRepository;
+
+        let user = note_user(&[SERVICE_ACCESS_ROLE, SERVICE_MANAGER_ROLE]);
+
+        let mut repo = MockRepository::new();
+        repo.expect_check_client_assigned_to_manager()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        repo.expect_list_client_notes().never();
+
+        let result = list_client_notes(1, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    /// Verifies that a note is stored for the client by the acting manager.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn add_note_stores_note_for_acting_manager() {
+        use crate::repository::mock::MockRepository;
+
+        let user = note_user(&[SERVICE_ACCESS_ROLE]);
+
+        let mut repo = MockRepository::new();
+        repo.expect_create_or_update_manager()
+            .times(1)
+            .returning(|_| Ok(hub_manager(5, "Manager", "manager@example.com")));
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(client_with_fields(Vec::new()))));
+        repo.expect_create_client_note()
+            .withf(|note| {
+                note.client_id.get() == 1
+                    && note.manager_id.get() == 5
+                    && note.text.as_str() == "Call back on Monday"
+            })
+            .times(1)
+            .returning(|_| Ok(client_note(3, "Call back on Monday", false)));
+
+        let payload = AddNotePayload {
+            text: crate::domain::types::CommentMessage::new("Call back on Monday")
+                .expect("valid text"),
+        };
+
+        let outcome = add_note(1, payload, &user, &repo).expect("note added");
+
+        assert_eq!(outcome.client_id.get(), 1);
+    }
+
+    /// Verifies that adding a note to a client outside the hub reports not found.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn add_note_reports_missing_client() {
+        use crate::repository::mock::MockRepository;
+
+        let user = note_user(&[SERVICE_ACCESS_ROLE]);
+
+        let mut repo = MockRepository::new();
+        repo.expect_create_or_update_manager()
+            .returning(|_| Ok(hub_manager(5, "Manager", "manager@example.com")));
+        repo.expect_get_client_by_id().returning(|_, _| Ok(None));
+        repo.expect_create_client_note().never();
+
+        let payload = AddNotePayload {
+            text: crate::domain::types::CommentMessage::new("Note").expect("valid text"),
+        };
+
+        let result = add_note(1, payload, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    /// Verifies that toggling the pin of an unknown note reports not found.
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn toggle_note_pin_reports_missing_note() {
+        use crate::repository::mock::MockRepository;
+
+        let user = note_user(&[SERVICE_ACCESS_ROLE]);
+
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .returning(|_, _| Ok(Some(client_with_fields(Vec::new()))));
+        repo.expect_toggle_client_note_pin()
+            .withf(|note_id, client_id| note_id.get() == 9 && client_id.get() == 1)
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let result = toggle_note_pin(1, 9, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn email_sender_applies_hub_branding() {
+        let user = AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 1,
+            name: "Manager".to_string(),
+            roles: vec![SERVICE_ACCESS_ROLE.to_string()],
+            exp: 0,
+        };
+        let hub_id = HubId::new(1).expect("valid hub id");
+
+        let sender = email_sender(&user, &HubSettings::defaults(hub_id));
+        assert_eq!(sender.name, "Manager");
+        assert_eq!(sender.email, "manager@example.com");
+
+        let settings = HubSettings::new(
+            hub_id,
+            25,
+            Some(NonEmptyString::new("Acme CRM").expect("valid name")),
+            false,
+        )
+        .expect("valid settings")
+        .with_email_reply_to(Some(
+            ManagerEmail::new("sales@example.com").expect("valid email"),
+        ));
+        let sender = email_sender(&user, &settings);
+        assert_eq!(sender.name, "Acme CRM");
+        assert_eq!(sender.email, "manager@example.com");
+        assert_eq!(sender.sub, user.sub);
+        assert_eq!(sender.roles, user.roles);
+    }
+
+    #[test]
+    fn outbound_email_carries_reply_to_next_to_the_email() {
+        let user = AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 1,
+            name: "Manager".to_string(),
+            roles: vec![SERVICE_ACCESS_ROLE.to_string()],
+            exp: 0,
+        };
+        let email = NewEmail {
+            message: EmailBody::new("Hello").expect("valid body"),
+            subject: None,
+            attachment: None,
+            attachment_name: None,
+            attachment_mime: None,
+            hub_id: EmailerHubId::new(1).expect("valid hub id"),
+            recipients: vec![],
+        };
+        let message = OutboundEmailMessage::NewEmail(Box::new((
+            user,
+            OutboundEmail {
+                email,
+                reply_to: Some("sales@example.com".to_string()),
+            },
+        )));
+
+        let value = serde_json::to_value(&message).expect("message serializes");
+        assert_eq!(value["NewEmail"][0]["email"], "manager@example.com");
+        assert_eq!(value["NewEmail"][1]["message"], "Hello");
+        assert_eq!(value["NewEmail"][1]["reply_to"], "sales@example.com");
+    }
+
+    #[cfg(feature = "test-mocks")]
+    fn admin_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "admin@example.com".to_string(),
+            hub_id: 1,
+            name: "Admin".to_string(),
+            roles: vec![SERVICE_ADMIN_ROLE.to_string()],
+            exp: 0,
+        }
+    }
+
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn cleanup_old_events_deletes_events_past_the_retention() {
+        use crate::repository::mock::MockRepository;
+
+        let mut repo = MockRepository::new();
+        repo.expect_delete_events_older_than()
+            .withf(|hub_id, cutoff| {
+                let expected = Utc::now().naive_utc() - Duration::days(30);
+                hub_id.get() == 1 && (expected - *cutoff).num_seconds().abs() < 60
+            })
+            .times(1)
+            .returning(|_, _| Ok(4));
+
+        let deleted = cleanup_old_events(&repo, &admin_user(), 30).expect("cleanup");
+
+        assert_eq!(deleted, 4);
+    }
+
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn cleanup_old_events_requires_admin_role() {
+        use crate::repository::mock::MockRepository;
+
+        let mut repo = MockRepository::new();
+        repo.expect_delete_events_older_than().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let result = cleanup_old_events(&repo, &user, 30);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn cleanup_events_by_hub_settings_requires_a_configured_retention() {
+        use crate::domain::hub_settings::HubSettings;
+        use crate::repository::mock::MockRepository;
+
+        let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .times(1)
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_delete_events_older_than().times(0);
+
+        let result = cleanup_events_by_hub_settings(&repo, &admin_user());
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[cfg(feature = "test-mocks")]
+    #[test]
+    fn cleanup_events_for_all_hubs_applies_each_hub_retention() {
+        use crate::domain::hub_settings::HubSettings;
+        use crate::repository::mock::MockRepository;
+
+        let mut repo = MockRepository::new();
+        repo.expect_list_hub_settings_with_event_retention()
+            .times(1)
+            .returning(|| {
+                Ok(vec![
+                    HubSettings::defaults(HubId::new(1).expect("valid hub id"))
+                        .with_event_retention_days(Some(7)),
+                    HubSettings::defaults(HubId::new(2).expect("valid hub id"))
+                        .with_event_retention_days(Some(90)),
+                ])
+            });
+        repo.expect_delete_events_older_than()
+            .times(2)
+            .returning(|hub_id, _| Ok(usize::try_from(hub_id.get()).expect("small id")));
+
+        let deleted = cleanup_events_for_all_hubs(&repo).expect("cleanup");
+
+        assert_eq!(deleted, 3);
+    }
 }
@@ -0,0 +1,991 @@
+//! Services producing spreadsheet, CSV, JSON and ND-JSON exports of hub clients.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::ensure_role;
+use rust_xlsxwriter::{Workbook, Worksheet, XlsxError};
+use serde_json::Value;
+
+use crate::SERVICE_ADMIN_ROLE;
+use crate::domain::client::Client;
+use crate::domain::client_event::ClientEvent;
+use crate::domain::manager::Manager;
+use crate::domain::types::{ClientId, ExportFormat, HubId};
+use crate::dto::export::{ClientExportRecord, ExportOptions, ExportQuery};
+use crate::repository::{ClientEventListQuery, ClientEventReader, ClientListQuery, ClientReader};
+use crate::services::{ServiceError, ServiceResult, client as client_service, log_service_call};
+
+/// Maximum number of data rows written to a single sheet.
+pub const MAX_EXPORT_ROWS: usize = 50_000;
+
+/// Number of clients loaded from the repository per query.
+const EXPORT_PAGE_SIZE: usize = 500;
+
+const CLIENT_COLUMNS: [&str; 8] = [
+    "id",
+    "public_id",
+    "name",
+    "email",
+    "phone",
+    "raw_phone",
+    "created_at",
+    "updated_at",
+];
+
+const CLIENT_EVENT_COLUMNS: [&str; 4] = ["created_at", "manager_email", "event_type", "text"];
+
+const EVENT_COLUMNS: [&str; 6] = [
+    "client_id",
+    "client_name",
+    "event_type",
+    "created_at",
+    "manager_name",
+    "manager_email",
+];
+
+/// Builds an XLSX workbook with a `Clients` sheet (core columns followed by one
+/// column per custom field) and, when requested, an `Events` sheet whose
+/// `event_data` keys are flattened into `data.<key>` columns.
+///
+/// Each sheet holds at most [`MAX_EXPORT_ROWS`] rows; clients, archived ones
+/// included, are read in pages so a large hub is never loaded at once.
+pub fn export_clients_xlsx<R>(
+    query: ExportQuery,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<u8>>
+where
+    R: ClientReader + ClientEventReader + ?Sized,
+{
+    log_service_call("export_clients_xlsx", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let clients = load_clients(hub_id, repo)?;
+    let field_names: Vec<String> = repo
+        .list_available_fields(hub_id)?
+        .into_iter()
+        .map(|field| field.name)
+        .collect();
+
+    let events = if query.include_events {
+        Some(load_events(&clients, repo)?)
+    } else {
+        None
+    };
+
+    build_workbook(&clients, &field_names, events.as_deref()).map_err(|err| {
+        log::error!("Failed to build clients XLSX export: {err}");
+        ServiceError::Internal
+    })
+}
+
+/// Writes the clients of the user's hub, archived ones included, to `writer` in
+/// `options.format`.
+///
+/// CSV output has the core columns followed by one column per custom field
+/// (the requested ones, or every field of the hub); JSON is a single
+/// pretty-printed array of [`ClientExportRecord`]s and ND-JSON holds one record
+/// per line. CSV cells, header included, are escaped with [`guard_formula`].
+/// Clients are read page by page and written as they arrive, so the export is
+/// not capped at [`MAX_EXPORT_ROWS`].
+pub fn export_clients<R, W>(
+    options: ExportOptions,
+    user: &AuthenticatedUser,
+    repo: &R,
+    writer: W,
+) -> ServiceResult<()>
+where
+    R: ClientReader + ?Sized,
+    W: Write,
+{
+    log_service_call("export_clients", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let field_names = match options.format {
+        ExportFormat::Csv if options.include_fields.is_empty() => repo
+            .list_available_fields(hub_id)?
+            .into_iter()
+            .map(|field| field.name)
+            .collect(),
+        _ => options.include_fields.clone(),
+    };
+
+    let mut sink = ExportSink::new(options.format, writer, field_names);
+    sink.start().map_err(export_write_error)?;
+
+    let mut page = 1;
+    let mut read = 0;
+    loop {
+        let mut query = ClientListQuery::new(hub_id)
+            .include_archived(true)
+            .paginate(page, EXPORT_PAGE_SIZE);
+        if let Some(range) = options.date_range {
+            query = query.created_within(range);
+        }
+        let (total, batch) = repo.list_clients(query)?;
+
+        for client in &batch {
+            sink.write_client(client).map_err(export_write_error)?;
+        }
+        read += batch.len();
+
+        if batch.len() < EXPORT_PAGE_SIZE || read >= total {
+            break;
+        }
+        page += 1;
+    }
+
+    sink.finish().map_err(export_write_error)
+}
+
+/// Writes the events of one client to `writer` as CSV, oldest first.
+///
+/// Rows hold [`CLIENT_EVENT_COLUMNS`]; `text` is the `text` key of the event
+/// payload. Cells that a spreadsheet would run as a formula are escaped with
+/// [`guard_formula`]. Access follows [`client_service::verify_client_page_access`], so
+/// managers only export clients assigned to them.
+pub fn export_client_events<R, W>(
+    client_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+    writer: W,
+) -> ServiceResult<()>
+where
+    R: ClientReader + ClientEventReader + ?Sized,
+    W: Write,
+{
+    log_service_call("export_client_events", user);
+    client_service::verify_client_page_access(client_id, user, repo)?;
+
+    let client_id = ClientId::new(client_id)?;
+    let (_, mut events) = repo.list_client_events(ClientEventListQuery::new(client_id))?;
+    events.sort_by_key(|(event, _)| (event.created_at, event.id));
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(CLIENT_EVENT_COLUMNS)
+        .map_err(|err| export_write_error(err.into()))?;
+    for (event, manager) in &events {
+        let text = event
+            .event_data
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        csv.write_record([
+            event.created_at.to_string().as_str(),
+            &*guard_formula(manager.email.as_str()),
+            &*guard_formula(&event.event_type.to_string()),
+            &*guard_formula(text),
+        ])
+        .map_err(|err| export_write_error(err.into()))?;
+    }
+    csv.flush().map_err(export_write_error)
+}
+
+/// Prefixes `cell` with `'` when it starts with a character spreadsheets treat
+/// as the start of a formula, so user-entered text is shown, not run.
+fn guard_formula(cell: &str) -> Cow<'_, str> {
+    if cell.starts_with(['=', '+', '-', '@']) {
+        Cow::Owned(format!("'{cell}"))
+    } else {
+        Cow::Borrowed(cell)
+    }
+}
+
+fn export_write_error(err: std::io::Error) -> ServiceError {
+    log::error!("Failed to write clients export: {err}");
+    ServiceError::Internal
+}
+
+/// Serializes clients one at a time in the chosen export format.
+struct ExportSink<W: Write> {
+    format: ExportFormat,
+    writer: W,
+    /// Custom fields written per client; empty means every field (JSON only).
+    field_names: Vec<String>,
+    written: usize,
+}
+
+impl<W: Write> ExportSink<W> {
+    fn new(format: ExportFormat, writer: W, field_names: Vec<String>) -> Self {
+        Self {
+            format,
+            writer,
+            field_names,
+            written: 0,
+        }
+    }
+
+    fn start(&mut self) -> std::io::Result<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                let headers = CLIENT_COLUMNS
+                    .iter()
+                    .map(|column| column.to_string())
+                    .chain(self.field_names.iter().cloned());
+                self.write_csv_row(headers)
+            }
+            ExportFormat::Json => self.writer.write_all(b"["),
+            ExportFormat::NdJson => Ok(()),
+        }
+    }
+
+    fn write_client(&mut self, client: &Client) -> std::io::Result<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                let row = client_row(client, &self.field_names);
+                self.write_csv_row(row)?;
+            }
+            ExportFormat::Json => {
+                let record = self.record(client);
+                let pretty = serde_json::to_string_pretty(&record)?;
+                let separator: &[u8] = if self.written == 0 { b"\n  " } else { b",\n  " };
+                self.writer.write_all(separator)?;
+                self.writer
+                    .write_all(pretty.replace('\n', "\n  ").as_bytes())?;
+            }
+            ExportFormat::NdJson => {
+                let record = self.record(client);
+                write_ndjson_line(&mut self.writer, &record)?;
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        if self.format == ExportFormat::Json {
+            let closing: &[u8] = if self.written == 0 { b"]" } else { b"\n]" };
+            self.writer.write_all(closing)?;
+        }
+        self.writer.flush()
+    }
+
+    fn record(&self, client: &Client) -> ClientExportRecord {
+        let mut record = ClientExportRecord::from(client);
+        if !self.field_names.is_empty() {
+            record
+                .fields
+                .retain(|name, _| self.field_names.contains(name));
+        }
+        record
+    }
+
+    fn write_csv_row(&mut self, values: impl IntoIterator<Item = String>) -> std::io::Result<()> {
+        let mut csv = csv::Writer::from_writer(&mut self.writer);
+        for value in values {
+            csv.write_field(guard_formula(&value).as_bytes())?;
+        }
+        csv.write_record(None::<&[u8]>)?;
+        csv.flush()
+    }
+}
+
+fn write_ndjson_line<W: Write>(writer: &mut W, record: &ClientExportRecord) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")
+}
+
+fn load_clients<R>(hub_id: HubId, repo: &R) -> ServiceResult<Vec<Client>>
+where
+    R: ClientReader + ?Sized,
+{
+    let mut clients = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let (total, batch) = repo.list_clients(
+            ClientListQuery::new(hub_id)
+                .include_archived(true)
+                .paginate(page, EXPORT_PAGE_SIZE),
+        )?;
+        let exhausted = batch.len() < EXPORT_PAGE_SIZE;
+        clients.extend(batch);
+
+        if exhausted || clients.len() >= total.min(MAX_EXPORT_ROWS) {
+            break;
+        }
+        page += 1;
+    }
+
+    if clients.len() > MAX_EXPORT_ROWS {
+        log::warn!("Clients export for hub {hub_id} truncated to {MAX_EXPORT_ROWS} rows");
+        clients.truncate(MAX_EXPORT_ROWS);
+    }
+
+    Ok(clients)
+}
+
+fn load_events<R>(clients: &[Client], repo: &R) -> ServiceResult<Vec<(ClientEvent, Manager)>>
+where
+    R: ClientEventReader + ?Sized,
+{
+    let mut events = Vec::new();
+
+    for client in clients {
+        let remaining = MAX_EXPORT_ROWS - events.len();
+        if remaining == 0 {
+            log::warn!("Events export truncated to {MAX_EXPORT_ROWS} rows");
+            break;
+        }
+        let (_, batch) =
+            repo.list_client_events(ClientEventListQuery::new(client.id).paginate(1, remaining))?;
+        events.extend(batch);
+    }
+
+    Ok(events)
+}
+
+fn build_workbook(
+    clients: &[Client],
+    field_names: &[String],
+    events: Option<&[(ClientEvent, Manager)]>,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    let sheet = workbook.add_worksheet().set_name("Clients")?;
+    write_clients_sheet(sheet, clients, field_names)?;
+
+    if let Some(events) = events {
+        let sheet = workbook.add_worksheet().set_name("Events")?;
+        write_events_sheet(sheet, clients, events)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+fn write_clients_sheet(
+    sheet: &mut Worksheet,
+    clients: &[Client],
+    field_names: &[String],
+) -> Result<(), XlsxError> {
+    let headers = CLIENT_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .chain(field_names.iter().cloned());
+    write_row(sheet, 0, headers)?;
+
+    for (index, client) in clients.iter().enumerate() {
+        write_row(sheet, index as u32 + 1, client_row(client, field_names))?;
+    }
+
+    Ok(())
+}
+
+/// Core column values of `client` followed by its `field_names` values.
+fn client_row(client: &Client, field_names: &[String]) -> Vec<String> {
+    let core = [
+        client.id.get().to_string(),
+        client
+            .public_id
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        client.name.as_str().to_string(),
+        client
+            .email
+            .as_ref()
+            .map(|email| email.as_str().to_string())
+            .unwrap_or_default(),
+        client
+            .phone
+            .as_ref()
+            .map(|phone| phone.as_str().to_string())
+            .unwrap_or_default(),
+        client
+            .raw_phone
+            .as_ref()
+            .map(|raw_phone| raw_phone.as_str().to_string())
+            .unwrap_or_default(),
+        client.created_at.to_string(),
+        client.updated_at.to_string(),
+    ];
+    let fields = field_names.iter().map(|name| {
+        client
+            .fields
+            .as_ref()
+            .and_then(|fields| fields.get(name))
+            .cloned()
+            .unwrap_or_default()
+    });
+    core.into_iter().chain(fields).collect()
+}
+
+fn write_events_sheet(
+    sheet: &mut Worksheet,
+    clients: &[Client],
+    events: &[(ClientEvent, Manager)],
+) -> Result<(), XlsxError> {
+    let data_keys = events
+        .iter()
+        .flat_map(|(event, _)| event_data_keys(&event.event_data))
+        .collect::<BTreeSet<_>>();
+
+    let headers = EVENT_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .chain(data_keys.iter().map(|key| format!("data.{key}")));
+    write_row(sheet, 0, headers)?;
+
+    let client_names = clients
+        .iter()
+        .map(|client| (client.id, client.name.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    for (index, (event, manager)) in events.iter().enumerate() {
+        let client_name = client_names
+            .get(&event.client_id)
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+        let core = [
+            event.client_id.get().to_string(),
+            client_name,
+            event.event_type.to_string(),
+            event.created_at.to_string(),
+            manager.name.as_str().to_string(),
+            manager.email.as_str().to_string(),
+        ];
+        let data = data_keys
+            .iter()
+            .map(|key| event_data_cell(&event.event_data, key));
+        write_row(sheet, index as u32 + 1, core.into_iter().chain(data))?;
+    }
+
+    Ok(())
+}
+
+fn write_row(
+    sheet: &mut Worksheet,
+    row: u32,
+    values: impl IntoIterator<Item = String>,
+) -> Result<(), XlsxError> {
+    for (col, value) in values.into_iter().enumerate() {
+        if !value.is_empty() {
+            sheet.write_string(row, col as u16, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Top-level keys of an event payload; non-object payloads use the `value` key.
+fn event_data_keys(data: &Value) -> Vec<String> {
+    match data {
+        Value::Object(map) => map.keys().cloned().collect(),
+        Value::Null => Vec::new(),
+        _ => vec!["value".to_string()],
+    }
+}
+
+fn event_data_cell(data: &Value, key: &str) -> String {
+    let value = match data {
+        Value::Object(map) => map.get(key),
+        Value::Null => None,
+        other if key == "value" => Some(other),
+        _ => None,
+    };
+
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "test-mocks"))]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::Cursor;
+
+    use calamine::{Data, Reader, Xlsx};
+    use chrono::{NaiveDate, Utc};
+    use serde_json::json;
+
+    use super::*;
+    use crate::domain::client::FieldMeta;
+    use crate::domain::types::{ClientId, ClientName, DateRange};
+    use crate::repository::mock::MockRepository;
+    use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE};
+
+    fn admin_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "admin@example.com".to_string(),
+            hub_id: 7,
+            name: "Admin".to_string(),
+            roles: vec![
+                SERVICE_ACCESS_ROLE.to_string(),
+                SERVICE_ADMIN_ROLE.to_string(),
+            ],
+            exp: 0,
+        }
+    }
+
+    fn sample_client() -> Client {
+        let now = Utc::now().naive_utc();
+        Client::try_new(
+            3,
+            None,
+            7,
+            "Alice".to_string(),
+            Some("alice@example.com".to_string()),
+            None,
+            now,
+            now,
+            Some(BTreeMap::from([("Tier".to_string(), "gold".to_string())])),
+        )
+        .expect("valid client")
+    }
+
+    fn cell(workbook: &mut Xlsx<Cursor<Vec<u8>>>, sheet: &str, row: u32, col: u32) -> Data {
+        workbook
+            .worksheet_range(sheet)
+            .expect("sheet exists")
+            .get_value((row, col))
+            .cloned()
+            .unwrap_or(Data::Empty)
+    }
+
+    #[test]
+    fn export_writes_clients_and_flattened_events() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.include_archived)
+            .times(1)
+            .returning(|_| Ok((1, vec![sample_client()])));
+        repo.expect_list_available_fields().returning(|_| {
+            Ok(vec![FieldMeta {
+                name: "Tier".to_string(),
+                value_count: 1,
+                is_important: false,
+            }])
+        });
+        repo.expect_list_client_events().times(1).returning(|_| {
+            let event = ClientEvent::try_new(
+                1,
+                3,
+                2,
+                "Comment",
+                json!({ "text": "Called back", "subject": null }),
+                Utc::now().naive_utc(),
+            )
+            .expect("valid event");
+            let manager = Manager::try_new(
+                2,
+                7,
+                "Manager".to_string(),
+                "m@example.com".to_string(),
+                true,
+            )
+            .expect("valid manager");
+            Ok((1, vec![(event, manager)]))
+        });
+
+        let bytes = export_clients_xlsx(
+            ExportQuery {
+                include_events: true,
+            },
+            &admin_user(),
+            &repo,
+        )
+        .expect("export succeeds");
+
+        let mut workbook = Xlsx::new(Cursor::new(bytes)).expect("valid xlsx");
+        assert_eq!(
+            cell(&mut workbook, "Clients", 0, 8),
+            Data::String("Tier".to_string())
+        );
+        assert_eq!(
+            cell(&mut workbook, "Clients", 1, 2),
+            Data::String("Alice".to_string())
+        );
+        assert_eq!(
+            cell(&mut workbook, "Clients", 1, 8),
+            Data::String("gold".to_string())
+        );
+        assert_eq!(
+            cell(&mut workbook, "Events", 0, 7),
+            Data::String("data.text".to_string())
+        );
+        assert_eq!(
+            cell(&mut workbook, "Events", 1, 7),
+            Data::String("Called back".to_string())
+        );
+    }
+
+    fn event_at(id: i32, hour: u32, event_type: &str, data: Value) -> (ClientEvent, Manager) {
+        let created_at = NaiveDate::from_ymd_opt(2026, 3, 1)
+            .and_then(|day| day.and_hms_opt(hour, 0, 0))
+            .expect("valid timestamp");
+        let event =
+            ClientEvent::try_new(id, 3, 2, event_type, data, created_at).expect("valid event");
+        let manager = Manager::try_new(
+            2,
+            7,
+            "Manager".to_string(),
+            "m@example.com".to_string(),
+            true,
+        )
+        .expect("valid manager");
+        (event, manager)
+    }
+
+    #[test]
+    fn client_events_export_writes_events_oldest_first() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(sample_client())));
+        repo.expect_list_client_events()
+            .withf(|query| query.client_id.get() == 3 && query.pagination.is_none())
+            .times(1)
+            .returning(|_| {
+                // The repository lists events newest first.
+                Ok((
+                    3,
+                    vec![
+                        event_at(2, 12, "Call", json!({ "text": "Called, \"no answer\"" })),
+                        event_at(
+                            1,
+                            9,
+                            "Comment",
+                            json!({ "text": "First contact", "subject": "Hi" }),
+                        ),
+                        event_at(3, 9, "Email", json!({ "subject": "No text" })),
+                    ],
+                ))
+            });
+
+        let mut output = Vec::new();
+        export_client_events(3, &admin_user(), &repo, &mut output).expect("export succeeds");
+
+        let mut reader = csv::Reader::from_reader(output.as_slice());
+        let headers = reader.headers().expect("header row").clone();
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            ["created_at", "manager_email", "event_type", "text"]
+        );
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("valid rows");
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row[0].to_string(), row[2].to_string(), row[3].to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "2026-03-01 09:00:00".to_string(),
+                    "Comment".to_string(),
+                    "First contact".to_string()
+                ),
+                (
+                    "2026-03-01 09:00:00".to_string(),
+                    "Email".to_string(),
+                    String::new()
+                ),
+                (
+                    "2026-03-01 12:00:00".to_string(),
+                    "Call".to_string(),
+                    "Called, \"no answer\"".to_string()
+                ),
+            ]
+        );
+        assert!(rows.iter().all(|row| &row[1] == "m@example.com"));
+    }
+
+    #[test]
+    fn client_events_export_escapes_formula_cells() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(sample_client())));
+        repo.expect_list_client_events().times(1).returning(|_| {
+            Ok((
+                4,
+                vec![
+                    event_at(1, 9, "Comment", json!({ "text": "=HYPERLINK(\"x\")" })),
+                    event_at(2, 10, "Email", json!({ "text": "+7 900 000" })),
+                    event_at(3, 11, "Comment", json!({ "text": "-1" })),
+                    event_at(4, 12, "Email", json!({ "text": "@SUM(A1)" })),
+                    event_at(5, 13, "Comment", json!({ "text": "Plain = text" })),
+                ],
+            ))
+        });
+
+        let mut output = Vec::new();
+        export_client_events(3, &admin_user(), &repo, &mut output).expect("export succeeds");
+
+        let mut reader = csv::Reader::from_reader(output.as_slice());
+        let texts = reader
+            .records()
+            .map(|row| row.expect("valid row")[3].to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                "'=HYPERLINK(\"x\")",
+                "'+7 900 000",
+                "'-1",
+                "'@SUM(A1)",
+                "Plain = text"
+            ]
+        );
+    }
+
+    #[test]
+    fn client_events_export_rejects_unassigned_manager() {
+        let mut repo = MockRepository::new();
+        repo.expect_check_client_assigned_to_manager()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        repo.expect_list_client_events().times(0);
+        let user = AuthenticatedUser {
+            roles: vec![
+                SERVICE_ACCESS_ROLE.to_string(),
+                crate::SERVICE_MANAGER_ROLE.to_string(),
+            ],
+            ..admin_user()
+        };
+
+        let mut output = Vec::new();
+        let result = export_client_events(3, &user, &repo, &mut output);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+        assert!(output.is_empty());
+    }
+
+    fn two_clients() -> Vec<Client> {
+        let mut bob = sample_client();
+        bob.id = ClientId::new(4).expect("valid id");
+        bob.name = ClientName::new("Bob").expect("valid name");
+        bob.fields = None;
+        vec![sample_client(), bob]
+    }
+
+    fn export(options: ExportOptions, repo: &MockRepository) -> String {
+        let mut output = Vec::new();
+        export_clients(options, &admin_user(), repo, &mut output).expect("export succeeds");
+        String::from_utf8(output).expect("utf-8 output")
+    }
+
+    #[test]
+    fn csv_export_writes_header_and_one_row_per_client() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .times(1)
+            .returning(|_| Ok((2, two_clients())));
+        repo.expect_list_available_fields().times(1).returning(|_| {
+            Ok(vec![FieldMeta {
+                name: "Tier".to_string(),
+                value_count: 1,
+                is_important: false,
+            }])
+        });
+
+        let text = export(ExportOptions::new(ExportFormat::Csv), &repo);
+
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader.headers().expect("header row").clone();
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            [
+                "id",
+                "public_id",
+                "name",
+                "email",
+                "phone",
+                "raw_phone",
+                "created_at",
+                "updated_at",
+                "Tier"
+            ]
+        );
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("valid rows");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][0], "3");
+        assert_eq!(&rows[0][2], "Alice");
+        assert_eq!(&rows[0][3], "alice@example.com");
+        assert_eq!(&rows[0][8], "gold");
+        assert_eq!(&rows[1][2], "Bob");
+        assert_eq!(&rows[1][8], "");
+    }
+
+    #[test]
+    fn csv_export_escapes_formula_cells() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(1).returning(|_| {
+            let clients = ["=HYPERLINK(\"x\")", "+7 900 000", "-1", "@SUM(A1)"]
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let mut client = sample_client();
+                    client.id = ClientId::new(index as i32 + 1).expect("valid id");
+                    client.name = ClientName::new(name).expect("valid name");
+                    client.fields =
+                        Some(BTreeMap::from([("=Tier".to_string(), "-gold".to_string())]));
+                    client
+                })
+                .collect::<Vec<_>>();
+            Ok((clients.len(), clients))
+        });
+        let options = ExportOptions {
+            include_fields: vec!["=Tier".to_string()],
+            ..ExportOptions::new(ExportFormat::Csv)
+        };
+
+        let text = export(options, &repo);
+
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader.headers().expect("header row").clone();
+        assert_eq!(&headers[8], "'=Tier");
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("valid rows");
+        assert_eq!(
+            rows.iter().map(|row| &row[2]).collect::<Vec<_>>(),
+            ["'=HYPERLINK(\"x\")", "'+7 900 000", "'-1", "'@SUM(A1)"]
+        );
+        assert!(rows.iter().all(|row| &row[8] == "'-gold"));
+    }
+
+    #[test]
+    fn json_export_writes_a_pretty_printed_array() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .withf(|query| query.include_archived)
+            .times(1)
+            .returning(|_| Ok((2, two_clients())));
+        repo.expect_list_available_fields().times(0);
+
+        let text = export(ExportOptions::new(ExportFormat::Json), &repo);
+
+        let records =
+            serde_json::from_str::<Vec<ClientExportRecord>>(&text).expect("valid JSON array");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name, "Alice");
+        assert_eq!(
+            records[0].fields,
+            BTreeMap::from([("Tier".to_string(), "gold".to_string())])
+        );
+        assert_eq!(records[1].name, "Bob");
+        assert_eq!(
+            text,
+            serde_json::to_string_pretty(&records).expect("serializable records")
+        );
+    }
+
+    #[test]
+    fn json_export_of_no_clients_is_an_empty_array() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let text = export(ExportOptions::new(ExportFormat::Json), &repo);
+
+        assert_eq!(text, "[]");
+    }
+
+    #[test]
+    fn ndjson_export_writes_one_client_per_line() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients()
+            .times(1)
+            .returning(|_| Ok((2, two_clients())));
+
+        let text = export(ExportOptions::new(ExportFormat::NdJson), &repo);
+
+        let records = text
+            .lines()
+            .map(|line| serde_json::from_str::<ClientExportRecord>(line).expect("valid line"))
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert!(text.ends_with('\n'));
+        assert_eq!(records[0].id, 3);
+        assert_eq!(records[0].name, "Alice");
+        assert_eq!(records[0].email.as_deref(), Some("alice@example.com"));
+        assert_eq!(records[0].phone, None);
+        assert_eq!(
+            records[0].fields,
+            BTreeMap::from([("Tier".to_string(), "gold".to_string())])
+        );
+        assert_eq!(records[1].id, 4);
+        assert_eq!(records[1].name, "Bob");
+        assert!(records[1].fields.is_empty());
+    }
+
+    #[test]
+    fn export_applies_included_fields_and_date_range() {
+        let mut repo = MockRepository::new();
+        let today = Utc::now().date_naive();
+        let range = DateRange::new(Some(today), None).expect("valid range");
+        repo.expect_list_clients()
+            .withf(move |query| query.created == Some(range))
+            .times(1)
+            .returning(|_| {
+                let mut clients = two_clients();
+                clients.truncate(1);
+                clients[0].fields = Some(BTreeMap::from([
+                    ("Tier".to_string(), "gold".to_string()),
+                    ("City".to_string(), "Omsk".to_string()),
+                ]));
+                Ok((1, clients))
+            });
+        let options = ExportOptions {
+            format: ExportFormat::NdJson,
+            include_fields: vec!["City".to_string()],
+            date_range: Some(range),
+        };
+
+        let text = export(options, &repo);
+
+        let records = text
+            .lines()
+            .map(|line| serde_json::from_str::<ClientExportRecord>(line).expect("valid line"))
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].name, "Alice");
+        assert_eq!(
+            records[0].fields,
+            BTreeMap::from([("City".to_string(), "Omsk".to_string())])
+        );
+    }
+
+    #[test]
+    fn clients_export_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let result = export_clients(
+            ExportOptions::new(ExportFormat::NdJson),
+            &user,
+            &repo,
+            Vec::new(),
+        );
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn export_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_clients().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let result = export_clients_xlsx(ExportQuery::default(), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+}
@@ -1,36 +1,56 @@
 //! Services for the dashboard and bulk actions.
 
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{Datelike, Duration, NaiveTime, Utc};
 
 use pushkind_common::domain::auth::AuthenticatedUser;
-use pushkind_common::pagination::{DEFAULT_ITEMS_PER_PAGE, Paginated};
+use pushkind_common::pagination::Paginated;
 use pushkind_common::routes::{check_role, ensure_role};
 
+use crate::domain::client::{ClientSource, ClientUpsertSummary, NewClient};
+use crate::domain::import_job::{ImportJob, ImportJobStatus};
 use crate::domain::manager::NewManager;
-use crate::domain::types::{HubId, PublicId};
-use crate::dto::main::IndexPageData;
+use crate::domain::types::{
+    ClientId, HubId, ImportConflictStrategy, ImportJobId, ManagerEmail, MatchBy, PhoneRegion,
+    PublicId,
+};
 pub use crate::dto::main::IndexQuery;
-use crate::forms::main::{AddClientPayload, UploadClientsForm};
-use crate::repository::{ClientListQuery, ClientReader, ClientWriter, ManagerWriter};
-use crate::services::{ServiceError, ServiceResult};
+use crate::dto::main::{HubDashboardData, IndexPageData};
+use crate::forms::main::{AddClientPayload, UploadClientsForm, UploadClientsFormError};
+use crate::repository::errors::ClientWriteError;
+use crate::repository::{
+    ClientListQuery, ClientReader, ClientWriter, ContactPolicyReader, HubSettingsReader,
+    HubStatsReader, ImportJobReader, ImportJobWriter, ImportantFieldReader, ManagerWriter,
+};
+use crate::services::{ServiceError, ServiceOutcome, ServiceResult, log_service_call};
 use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE, SERVICE_MANAGER_ROLE};
 
 /// Loads the clients list for the main index page.
+#[tracing::instrument(skip(repo, user))]
 pub fn load_index_page<R>(
     query: IndexQuery,
     user: &AuthenticatedUser,
     repo: &R,
 ) -> ServiceResult<IndexPageData>
 where
-    R: ClientReader + ManagerWriter + ?Sized,
+    R: ClientReader + HubSettingsReader + ManagerWriter + ?Sized,
 {
+    log_service_call("load_index_page", user);
     ensure_role(user, SERVICE_ACCESS_ROLE)?;
 
     let page = query.page.unwrap_or(1);
 
     let hub_id = HubId::new(user.hub_id)?;
+    let items_per_page = repo.get_hub_settings(hub_id)?.page_size();
 
-    let mut list_query = ClientListQuery::new(hub_id).paginate(page, DEFAULT_ITEMS_PER_PAGE);
+    let mut list_query = ClientListQuery::new(hub_id)
+        .sort(query.sort.unwrap_or_default())
+        .fts_operator(query.fts.unwrap_or_default())
+        .unassigned(query.unassigned)
+        .paginate(page, items_per_page);
 
     let search_query = query
         .search
@@ -54,6 +74,7 @@ where
                 return Ok(IndexPageData {
                     clients: Paginated::new(Vec::new(), page, 0),
                     search_query,
+                    unassigned: query.unassigned,
                 });
             }
         }
@@ -63,7 +84,7 @@ where
         repo.list_clients(list_query).map_err(ServiceError::from)?
     } else if check_role(SERVICE_MANAGER_ROLE, &user.roles) {
         let manager_payload = NewManager::try_from(user).map_err(|err| {
-            log::error!("Failed to build manager from user: {err}");
+            tracing::error!("Failed to build manager from user: {err}");
             ServiceError::Internal
         })?;
         let manager = repo.create_or_update_manager(&manager_payload)?;
@@ -73,68 +94,509 @@ where
         (0, Vec::new())
     };
 
-    let total_pages = total.div_ceil(DEFAULT_ITEMS_PER_PAGE);
+    let total_pages = total.div_ceil(items_per_page);
     let clients = Paginated::new(clients, page, total_pages);
 
     Ok(IndexPageData {
         clients,
         search_query,
+        unassigned: query.unassigned,
     })
 }
 
+/// Clients with an event within this many days count as active.
+const ACTIVE_CLIENT_DAYS: i64 = 30;
+
+/// Loads the hub-wide counters for the admin dashboard.
+///
+/// "Today" starts at midnight UTC and "this week" at Monday midnight UTC; the
+/// top manager is ranked by events created this week.
+#[tracing::instrument(skip(repo, user))]
+pub fn get_hub_dashboard_data<R>(
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<HubDashboardData>
+where
+    R: HubStatsReader + ?Sized,
+{
+    log_service_call("get_hub_dashboard_data", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let now = Utc::now().naive_utc();
+    let today_start = now.date().and_time(NaiveTime::MIN);
+    let week_start = today_start - Duration::days(i64::from(now.weekday().num_days_from_monday()));
+    let active_since = now - Duration::days(ACTIVE_CLIENT_DAYS);
+
+    Ok(HubDashboardData {
+        total_clients: repo.count_clients(hub_id)?,
+        active_clients: repo.count_active_clients(hub_id, active_since)?,
+        total_managers: repo.count_managers(hub_id)?,
+        events_today: repo.count_client_events(hub_id, today_start)?,
+        events_this_week: repo.count_client_events(hub_id, week_start)?,
+        top_manager_by_events: repo.top_manager_by_events(hub_id, week_start)?,
+        unassigned_count: repo.count_unassigned_clients(hub_id)?,
+    })
+}
+
+/// Turns a hub client-limit violation into a user-facing form error.
+pub(crate) fn map_client_limit_error(err: ClientWriteError) -> ServiceError {
+    match err {
+        ClientWriteError::LimitExceeded { .. } => {
+            tracing::warn!("Rejected client creation: {err}");
+            ServiceError::Form(
+                "Достигнут лимит клиентов для вашего тарифа. Обратитесь к администратору."
+                    .to_string(),
+            )
+        }
+        err => ServiceError::from(err),
+    }
+}
+
+/// Reason shown when [`ImportConflictStrategy::Error`] meets an existing client.
+const EXISTING_CLIENTS_MESSAGE: &str =
+    "В файле есть клиенты, которые уже существуют. Импорт отменён.";
+
+/// Maps a failed bulk import to a user-facing form error.
+fn map_import_error(err: ClientWriteError) -> ServiceError {
+    match err {
+        ClientWriteError::ClientExists { .. } => {
+            tracing::warn!("Rejected client import: {err}");
+            ServiceError::Form(EXISTING_CLIENTS_MESSAGE.to_string())
+        }
+        err => map_client_limit_error(err),
+    }
+}
+
+/// Returns the region used to parse phone numbers entered on the add-client
+/// form, if the admin's hub configured one.
+#[tracing::instrument(skip(repo, user))]
+pub fn load_default_phone_region<R>(
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Option<PhoneRegion>>
+where
+    R: HubSettingsReader + ?Sized,
+{
+    log_service_call("load_default_phone_region", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.get_hub_settings(hub_id)?.hub_default_phone_region)
+}
+
 /// Validates the add-client form and persists a new client record.
+///
+/// Existing clients with the same name do not block creation; they are reported
+/// through [`ServiceOutcome::warning`].
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload))]
 pub fn add_client<R>(
     payload: AddClientPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<()>
+) -> ServiceResult<ServiceOutcome>
 where
-    R: ClientWriter + ?Sized,
+    R: ClientReader + ClientWriter + ContactPolicyReader + ?Sized,
 {
+    log_service_call("add_client", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
 
-    let new_client = payload.into_domain(hub_id);
+    let policy = repo.get_contact_policy(hub_id)?;
+    payload.check_contact_policy(&policy)?;
+
+    let new_client = payload.into_domain(hub_id).with_source(ClientSource::Form);
+    // The save replaces a client with the same email or phone; it is not a
+    // namesake of itself.
+    let replaced = match &new_client.email {
+        Some(email) => repo.get_client_by_email(email, hub_id)?,
+        None => None,
+    };
+    let replaced = match (replaced, &new_client.phone) {
+        (None, Some(phone)) => repo.get_client_by_phone(phone, hub_id)?,
+        (replaced, _) => replaced,
+    };
+    let namesakes =
+        repo.count_clients_by_name(hub_id, &new_client.name, replaced.map(|client| client.id))?;
+    let duplicate_note = (namesakes > 0).then(|| {
+        format!(
+            "Возможный дубликат: клиентов с именем «{}» уже {namesakes}.",
+            new_client.name.as_str()
+        )
+    });
+
+    repo.create_or_replace_clients(&[new_client])
+        .map_err(map_client_limit_error)?;
+    tracing::info!(%hub_id, namesakes, "Client added");
+
+    Ok(ServiceOutcome::new("Клиент добавлен.", "/").with_warning(duplicate_note))
+}
+
+/// Records a pending import job for the admin's hub before the upload is processed.
+#[tracing::instrument(skip(repo, user))]
+pub fn start_clients_import<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<ImportJob>
+where
+    R: ImportJobWriter + ?Sized,
+{
+    log_service_call("start_clients_import", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
 
-    repo.create_or_replace_clients(&[new_client])?;
+    let job = repo.create_import_job(hub_id)?;
+    tracing::info!(job_id = %job.id, %hub_id, "Client import queued");
 
-    Ok(())
+    Ok(job)
 }
 
-/// Parses the uploaded CSV file and creates client records in bulk.
-pub fn upload_clients<R>(
+/// Example values of the core columns in [`build_upload_template`].
+const TEMPLATE_EXAMPLE_ROW: [&str; 3] = ["Иван Петров", "ivan@example.com", "+79001234567"];
+
+/// Builds a CSV template for the clients upload: the `name,email,phone`
+/// header followed by the hub's important fields, and one example row.
+#[tracing::instrument(skip(repo, user))]
+pub fn build_upload_template<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<u8>>
+where
+    R: ImportantFieldReader + ?Sized,
+{
+    log_service_call("build_upload_template", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let fields = repo.list_important_fields(hub_id)?;
+
+    let header = ["name", "email", "phone"]
+        .into_iter()
+        .chain(fields.iter().map(|field| field.field.as_str()));
+    let example = TEMPLATE_EXAMPLE_ROW
+        .into_iter()
+        .chain(fields.iter().map(|_| ""));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(header)
+        .and_then(|()| writer.write_record(example))
+        .map_err(|err| {
+            log::error!("Failed to write upload template: {err}");
+            ServiceError::Internal
+        })?;
+
+    writer.into_inner().map_err(|err| {
+        log::error!("Failed to finish upload template: {err}");
+        ServiceError::Internal
+    })
+}
+
+/// Row counts of the imports running in this process.
+///
+/// An import writes all rows in one transaction, so `import_jobs` only sees
+/// its counts once it commits; status polls read the running counts here.
+#[derive(Debug, Default)]
+pub struct ImportProgress {
+    running: Mutex<HashMap<ImportJobId, ClientUpsertSummary>>,
+}
+
+impl ImportProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the rows written so far by the job.
+    pub fn record(&self, job_id: ImportJobId, summary: ClientUpsertSummary) {
+        self.running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(job_id, summary);
+    }
+
+    /// Returns the rows written so far by a running job.
+    pub fn get(&self, job_id: ImportJobId) -> Option<ClientUpsertSummary> {
+        self.running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&job_id)
+            .copied()
+    }
+
+    /// Forgets a job whose final counts are stored.
+    pub fn finish(&self, job_id: ImportJobId) {
+        self.running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&job_id);
+    }
+}
+
+/// Parses the uploaded CSV file and creates client records in bulk, reporting
+/// the rows written after every chunk to `progress`.
+///
+/// With [`MatchBy::Name`] rows naming exactly one existing client update it
+/// instead of being inserted, attributed to `user`; rows naming several
+/// clients are skipped and counted as error rows.
+///
+/// Runs outside the request, so failures are recorded on the job instead of
+/// being returned to the uploader. Returns the job in its final state.
+#[tracing::instrument(skip(job, form, user, repo, progress), fields(job_id = %job.id))]
+pub fn run_clients_import<R>(
+    mut job: ImportJob,
     form: &mut UploadClientsForm,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<()>
+    progress: &ImportProgress,
+) -> ServiceResult<ImportJob>
 where
-    R: ClientWriter + ?Sized,
+    R: ClientReader
+        + ClientWriter
+        + ContactPolicyReader
+        + HubSettingsReader
+        + ImportJobWriter
+        + ImportantFieldReader
+        + ?Sized,
 {
+    log_service_call("run_clients_import", user);
+    let hub_id = job.hub_id;
+
+    // Important fields only widen the accepted headers of a strict upload.
+    let important_fields = if form.strict() {
+        repo.list_important_fields(hub_id).map(|fields| {
+            fields
+                .into_iter()
+                .map(|field| field.field)
+                .collect::<Vec<_>>()
+        })
+    } else {
+        Ok(Vec::new())
+    };
+    let parsed = repo
+        .get_contact_policy(hub_id)
+        .and_then(|policy| Ok((policy, repo.get_hub_settings(hub_id)?, important_fields?)))
+        .map_err(ServiceError::from)
+        .and_then(|(policy, settings, important_fields)| {
+            form.parse(
+                hub_id,
+                &policy,
+                settings.hub_default_phone_region.as_ref(),
+                &important_fields,
+            )
+            .map_err(map_parse_error)
+        });
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => return fail_import(job, &err, repo),
+    };
+
+    for row in &parsed.rejected {
+        tracing::warn!(
+            "Skipping CSV line {} for hub {hub_id}: contact policy violations {:?}",
+            row.line,
+            row.violations
+        );
+    }
+
+    let total_rows = parsed.clients.len() + parsed.rejected.len();
+    let match_by = form.match_by();
+    let matches = match match_by {
+        MatchBy::Contact => NameMatches {
+            unmatched: parsed.clients,
+            ..NameMatches::default()
+        },
+        MatchBy::Name => match match_clients_by_name(hub_id, parsed.clients, repo) {
+            Ok(matches) => matches,
+            Err(err) => return fail_import(job, &err, repo),
+        },
+    };
+
+    if let Err(err) = job.start(total_rows, parsed.rejected.len() + matches.ambiguous) {
+        return fail_import(job, &err.into(), repo);
+    }
+    let mut job = match repo.save_import_job(&job) {
+        Ok(job) => job,
+        Err(err) => return fail_import(job, &err.into(), repo),
+    };
+
+    let strategy = form.conflict_strategy();
+    let job_id = job.id;
+    let result = name_match_updates(&matches.matched, strategy).and_then(|name_matches| {
+        let actor = ManagerEmail::new(&user.email)?;
+        repo.import_clients(
+            &matches.unmatched,
+            name_matches,
+            strategy,
+            &actor,
+            &|written| progress.record(job_id, written),
+        )
+        .map_err(map_import_error)
+    });
+    let finished = match result {
+        Ok(summary) => job
+            .record_progress(summary.inserted, summary.updated)
+            .and_then(|()| job.complete(Utc::now().naive_utc()))
+            .map_err(ServiceError::from)
+            .and_then(|()| {
+                tracing::info!(
+                    %hub_id,
+                    inserted = summary.inserted,
+                    updated = summary.updated,
+                    rejected = job.error_rows,
+                    %strategy,
+                    %match_by,
+                    "Clients uploaded"
+                );
+                Ok(repo.save_import_job(&job)?)
+            }),
+        Err(err) => fail_import(job, &err, repo),
+    };
+    // Only forget the running counts once the final ones are stored.
+    progress.finish(job_id);
+    finished
+}
+
+/// Parsed rows of a [`MatchBy::Name`] import split by their name matches.
+#[derive(Default)]
+struct NameMatches {
+    /// Rows naming exactly one existing client, with that client's id.
+    matched: Vec<(ClientId, NewClient)>,
+    /// Rows naming no existing client; they go through the contact match.
+    unmatched: Vec<NewClient>,
+    /// Rows skipped because their name matches several clients.
+    ambiguous: usize,
+}
+
+/// Matches each row against the name keys of the hub's existing clients,
+/// loaded once for the whole upload.
+fn match_clients_by_name<R>(
+    hub_id: HubId,
+    clients: Vec<NewClient>,
+    repo: &R,
+) -> ServiceResult<NameMatches>
+where
+    R: ClientReader + ?Sized,
+{
+    let by_key = repo.client_ids_by_name_key(hub_id)?;
+    let mut matches = NameMatches::default();
+    for row in clients {
+        match by_key.get(&row.name.match_key()).map(Vec::as_slice) {
+            None | Some([]) => matches.unmatched.push(row),
+            Some([client_id]) => matches.matched.push((*client_id, row)),
+            Some(found) => {
+                tracing::warn!(
+                    %hub_id,
+                    count = found.len(),
+                    "Skipping CSV row «{}»: the name matches several clients",
+                    row.name.as_str()
+                );
+                matches.ambiguous += 1;
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Returns the rows matched by name that `strategy` lets update their client,
+/// as [`UpdateClient::from_import`] describes.
+///
+/// [`UpdateClient::from_import`]: crate::domain::client::UpdateClient::from_import
+fn name_match_updates(
+    matched: &[(ClientId, NewClient)],
+    strategy: ImportConflictStrategy,
+) -> ServiceResult<&[(ClientId, NewClient)]> {
+    if matched.is_empty() || strategy == ImportConflictStrategy::Skip {
+        return Ok(&[]);
+    }
+    if strategy == ImportConflictStrategy::Error {
+        tracing::warn!(
+            matched = matched.len(),
+            "Rejected client import: rows match existing clients by name"
+        );
+        return Err(ServiceError::Form(EXISTING_CLIENTS_MESSAGE.to_string()));
+    }
+    Ok(matched)
+}
+
+/// Turns a CSV parse failure into the reason recorded on the import job.
+fn map_parse_error(err: UploadClientsFormError) -> ServiceError {
+    tracing::error!("Failed to parse clients: {err}");
+    let message = match err {
+        UploadClientsFormError::UnexpectedColumn(column) => {
+            format!("Неизвестная колонка «{column}» в файле.")
+        }
+        UploadClientsFormError::MissingRequiredColumn => {
+            "В файле нет обязательной колонки «name».".to_string()
+        }
+        _ => "Ошибка при парсинге клиентов".to_string(),
+    };
+    ServiceError::Form(message)
+}
+
+/// Returns an import job of the admin's hub with the rows a running import
+/// has written so far.
+#[tracing::instrument(skip(repo, user, progress))]
+pub fn get_import_job<R>(
+    job_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+    progress: &ImportProgress,
+) -> ServiceResult<ImportJob>
+where
+    R: ImportJobReader + ?Sized,
+{
+    log_service_call("get_import_job", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
+    let job_id = ImportJobId::new(job_id)?;
     let hub_id = HubId::new(user.hub_id)?;
 
-    let clients = form.parse(hub_id).map_err(|err| {
-        log::error!("Failed to parse clients: {err}");
-        ServiceError::Form("Ошибка при парсинге клиентов".to_string())
-    })?;
+    let mut job = repo
+        .get_import_job(job_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+    let running = progress
+        .get(job.id)
+        .filter(|_| job.status == ImportJobStatus::Running);
+    if let Some(summary) = running {
+        job.record_progress(summary.inserted, summary.updated)?;
+    }
 
-    repo.create_or_replace_clients(&clients)?;
+    Ok(job)
+}
 
-    Ok(())
+/// Marks the job as failed with a user-facing reason derived from `err`.
+fn fail_import<R>(mut job: ImportJob, err: &ServiceError, repo: &R) -> ServiceResult<ImportJob>
+where
+    R: ImportJobWriter + ?Sized,
+{
+    tracing::error!("Client import failed: {err}");
+    let message = match err {
+        ServiceError::Form(message) => message.clone(),
+        _ => "Не удалось загрузить клиентов.".to_string(),
+    };
+    job.fail(message, Utc::now().naive_utc())?;
+
+    Ok(repo.save_import_job(&job)?)
 }
 
 #[cfg(all(test, feature = "test-mocks"))]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
-    use crate::domain::client::Client;
+    use crate::domain::client::{Client, ClientUpsertSummary};
+    use crate::domain::contact_policy::ContactPolicy;
+    use crate::domain::hub_settings::HubSettings;
+    use crate::domain::import_job::ImportJobStatus;
     use crate::domain::manager::Manager;
     use crate::domain::types::{ClientName, HubId, ManagerEmail, ManagerName, PublicId};
     use crate::forms::main::AddClientForm;
     use crate::repository::mock::MockRepository;
     use crate::services::ServiceError;
+    use actix_multipart::form::text::Text;
     use chrono::Utc;
+    use mockall::Sequence;
+    use pushkind_common::pagination::DEFAULT_ITEMS_PER_PAGE;
+    use pushkind_common::repository::errors::RepositoryError;
 
     fn access_user() -> AuthenticatedUser {
         AuthenticatedUser {
@@ -201,6 +663,8 @@ mod tests {
     #[test]
     fn load_index_page_for_admin_applies_search() {
         let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
         repo.expect_create_or_update_manager().times(0);
         let expected_client = sample_client(1, 11);
         repo.expect_list_clients()
@@ -219,7 +683,7 @@ mod tests {
         let query = IndexQuery {
             search: Some("  Delta  ".to_string()),
             page: Some(2),
-            public_id: None,
+            ..Default::default()
         };
 
         let data = load_index_page(query, &user, &repo).expect("page data");
@@ -230,6 +694,8 @@ mod tests {
     #[test]
     fn load_index_page_for_manager_scopes_clients() {
         let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
         let manager = sample_manager(3, 11);
         let manager_email = manager.email.clone();
         repo.expect_create_or_update_manager()
@@ -261,9 +727,56 @@ mod tests {
         assert_eq!(data.search_query, None);
     }
 
+    #[test]
+    fn load_index_page_echoes_unassigned_filter() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_list_clients()
+            .withf(|query| query.unassigned)
+            .times(1)
+            .returning(|_| Ok((0, Vec::new())));
+
+        let query = IndexQuery {
+            unassigned: true,
+            ..Default::default()
+        };
+
+        let data = load_index_page(query, &admin_user(), &repo).expect("page data");
+
+        assert!(data.unassigned);
+    }
+
+    #[test]
+    fn load_index_page_uses_hub_page_size() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .withf(|hub_id| hub_id.get() == 11)
+            .times(1)
+            .returning(|hub_id| Ok(HubSettings::new(hub_id, 5, None, false).expect("settings")));
+        repo.expect_list_clients()
+            .withf(|query| {
+                query
+                    .pagination
+                    .as_ref()
+                    .is_some_and(|pagination| pagination.page == 1 && pagination.per_page == 5)
+            })
+            .times(1)
+            .returning(|_| Ok((12, Vec::new())));
+
+        let data = load_index_page(IndexQuery::default(), &admin_user(), &repo).expect("page data");
+
+        let clients_value = serde_json::to_value(&data.clients).expect("serialize clients");
+        let pages = clients_value["pages"].as_array().expect("pages array");
+        assert!(pages.contains(&serde_json::json!(3)));
+        assert!(!pages.contains(&serde_json::json!(4)));
+    }
+
     #[test]
     fn load_index_page_for_viewer_returns_empty() {
         let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
         repo.expect_list_clients().times(0);
         repo.expect_create_or_update_manager().times(0);
         let user = access_user();
@@ -276,29 +789,179 @@ mod tests {
     #[test]
     fn add_client_persists_new_client() {
         let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_client_by_email().returning(|_, _| Ok(None));
+        repo.expect_count_clients_by_name()
+            .returning(|_, _, _| Ok(0));
         repo.expect_create_or_replace_clients()
             .withf(|clients| {
                 clients.len() == 1
                     && clients[0].hub_id == HubId::new(11).expect("valid hub id")
                     && clients[0].name == ClientName::new("Alice").expect("name")
+                    && clients[0].source == Some(ClientSource::Form)
+            })
+            .times(1)
+            .returning(|_| {
+                Ok(ClientUpsertSummary {
+                    inserted: 1,
+                    updated: 0,
+                })
+            });
+
+        let user = admin_user();
+        let payload = AddClientPayload::try_from(AddClientForm {
+            name: "Alice".to_string(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            idempotency_token: None,
+        })
+        .expect("valid payload");
+
+        let outcome = add_client(payload, &user, &repo).expect("client created");
+
+        assert_eq!(outcome, ServiceOutcome::new("Клиент добавлен.", "/"));
+    }
+
+    #[test]
+    fn add_client_with_existing_name_still_creates_and_warns() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_client_by_email().returning(|_, _| Ok(None));
+        repo.expect_count_clients_by_name()
+            .withf(|hub_id, name, except| {
+                hub_id.get() == 11 && name.as_str() == "Alice" && except.is_none()
             })
             .times(1)
-            .returning(|_| Ok(1));
+            .returning(|_, _, _| Ok(2));
+        repo.expect_create_or_replace_clients()
+            .times(1)
+            .returning(|_| {
+                Ok(ClientUpsertSummary {
+                    inserted: 1,
+                    updated: 0,
+                })
+            });
+
+        let user = admin_user();
+        let payload = AddClientPayload::try_from(AddClientForm {
+            name: "Alice".to_string(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            idempotency_token: None,
+        })
+        .expect("valid payload");
+
+        let outcome = add_client(payload, &user, &repo).expect("client created");
+
+        assert_eq!(outcome.message, "Клиент добавлен.");
+        let note = outcome.warning.expect("duplicate note");
+        assert!(note.contains("«Alice»"));
+        assert!(note.contains('2'));
+    }
+
+    #[test]
+    fn add_client_does_not_count_the_replaced_client_as_namesake() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_client_by_email()
+            .withf(|email, _| email.as_str() == "alice@example.com")
+            .times(1)
+            .returning(|_, hub_id| Ok(Some(sample_client(5, hub_id.get()))));
+        repo.expect_count_clients_by_name()
+            .withf(|_, _, except| except.map(|id| id.get()) == Some(5))
+            .times(1)
+            .returning(|_, _, _| Ok(0));
+        repo.expect_create_or_replace_clients()
+            .times(1)
+            .returning(|_| {
+                Ok(ClientUpsertSummary {
+                    inserted: 0,
+                    updated: 1,
+                })
+            });
+
+        let payload = AddClientPayload::try_from(AddClientForm {
+            name: "Alice".to_string(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            idempotency_token: None,
+        })
+        .expect("valid payload");
+
+        let outcome = add_client(payload, &admin_user(), &repo).expect("client saved");
+
+        assert_eq!(outcome.warning, None);
+    }
+
+    #[test]
+    fn add_client_over_hub_limit_returns_form_error() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_client_by_email().returning(|_, _| Ok(None));
+        repo.expect_count_clients_by_name()
+            .returning(|_, _, _| Ok(0));
+        repo.expect_create_or_replace_clients()
+            .times(1)
+            .returning(|_| {
+                Err(ClientWriteError::LimitExceeded {
+                    hub_id: 11,
+                    max_clients: 1,
+                })
+            });
+
+        let user = admin_user();
+        let payload = AddClientPayload::try_from(AddClientForm {
+            name: "Alice".to_string(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            idempotency_token: None,
+        })
+        .expect("valid payload");
+
+        let result = add_client(payload, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Form(_))));
+    }
+
+    #[test]
+    fn add_client_rejects_payload_violating_hub_contact_policy() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .withf(|hub_id| hub_id.get() == 11)
+            .returning(|_| {
+                Ok(ContactPolicy {
+                    require_email: false,
+                    require_phone: true,
+                })
+            });
+        repo.expect_create_or_replace_clients().times(0);
 
         let user = admin_user();
         let payload = AddClientPayload::try_from(AddClientForm {
             name: "Alice".to_string(),
             email: Some("alice@example.com".to_string()),
             phone: None,
+            idempotency_token: None,
         })
         .expect("valid payload");
 
-        add_client(payload, &user, &repo).expect("client created");
+        let result = add_client(payload, &user, &repo);
+
+        assert!(matches!(
+            result,
+            Err(ServiceError::Form(message)) if message == "Укажите телефон."
+        ));
     }
 
     #[test]
     fn load_index_page_with_invalid_public_id_returns_empty_without_repo_query() {
         let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
         repo.expect_list_clients().times(0);
         repo.expect_create_or_update_manager().times(0);
 
@@ -317,4 +980,573 @@ mod tests {
             .expect("items array");
         assert!(items.is_empty());
     }
+
+    #[test]
+    fn get_hub_dashboard_data_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_count_clients().times(0);
+
+        let result = get_hub_dashboard_data(&access_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn get_hub_dashboard_data_collects_hub_counters() {
+        let hub_id = HubId::new(11).expect("valid hub id");
+        let mut repo = MockRepository::new();
+        repo.expect_count_clients()
+            .withf(move |id| *id == hub_id)
+            .times(1)
+            .returning(|_| Ok(12));
+        repo.expect_count_active_clients()
+            .withf(move |id, _| *id == hub_id)
+            .times(1)
+            .returning(|_, _| Ok(5));
+        repo.expect_count_managers().times(1).returning(|_| Ok(3));
+        let mut sequence = Sequence::new();
+        repo.expect_count_client_events()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(2));
+        repo.expect_count_client_events()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(9));
+        repo.expect_top_manager_by_events()
+            .times(1)
+            .returning(|_, _| Ok(Some(sample_manager(4, 11))));
+        repo.expect_count_unassigned_clients()
+            .times(1)
+            .returning(|_| Ok(7));
+
+        let data = get_hub_dashboard_data(&admin_user(), &repo).expect("dashboard data");
+
+        assert_eq!(data.total_clients, 12);
+        assert_eq!(data.active_clients, 5);
+        assert_eq!(data.total_managers, 3);
+        assert_eq!(data.events_today, 2);
+        assert_eq!(data.events_this_week, 9);
+        assert_eq!(
+            data.top_manager_by_events.map(|manager| manager.id.get()),
+            Some(4)
+        );
+        assert_eq!(data.unassigned_count, 7);
+    }
+
+    #[test]
+    fn get_hub_dashboard_data_week_window_starts_on_monday() {
+        let mut repo = MockRepository::new();
+        repo.expect_count_clients().returning(|_| Ok(0));
+        repo.expect_count_active_clients().returning(|_, _| Ok(0));
+        repo.expect_count_managers().returning(|_| Ok(0));
+        repo.expect_count_client_events().returning(|_, _| Ok(0));
+        repo.expect_top_manager_by_events()
+            .withf(|_, since| {
+                since.weekday() == chrono::Weekday::Mon && since.time() == NaiveTime::MIN
+            })
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_count_unassigned_clients().returning(|_| Ok(0));
+
+        let data = get_hub_dashboard_data(&admin_user(), &repo).expect("dashboard data");
+
+        assert!(data.top_manager_by_events.is_none());
+    }
+
+    fn pending_import_job() -> ImportJob {
+        ImportJob {
+            id: ImportJobId::new(3).expect("valid job id"),
+            hub_id: HubId::new(11).expect("valid hub id"),
+            status: ImportJobStatus::Pending,
+            total_rows: 0,
+            inserted_rows: 0,
+            updated_rows: 0,
+            error_rows: 0,
+            error_message: None,
+            started_at: Utc::now().naive_utc(),
+            completed_at: None,
+        }
+    }
+
+    fn upload_form(csv: &str) -> UploadClientsForm {
+        use actix_multipart::form::tempfile::TempFile;
+        use std::io::{Seek, Write};
+
+        let mut file = tempfile::NamedTempFile::new().expect("temp file");
+        file.write_all(csv.as_bytes()).expect("write csv");
+        file.rewind().expect("rewind csv");
+
+        UploadClientsForm {
+            csv: TempFile {
+                file,
+                content_type: None,
+                file_name: None,
+                size: csv.len(),
+            },
+            require_contact: None,
+            conflict_strategy: None,
+            match_by: None,
+            strict: None,
+        }
+    }
+
+    #[test]
+    fn start_clients_import_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_import_job().times(0);
+
+        let result = start_clients_import(&access_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn build_upload_template_appends_important_fields() {
+        use crate::domain::important_field::ImportantField;
+
+        let mut repo = MockRepository::new();
+        repo.expect_list_important_fields()
+            .withf(|hub_id| hub_id.get() == 11)
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    ImportantField::try_new(11, "Город".to_string()).expect("valid field"),
+                    ImportantField::try_new(11, "Stage".to_string()).expect("valid field"),
+                ])
+            });
+
+        let bytes = build_upload_template(&admin_user(), &repo).expect("template built");
+
+        assert_eq!(
+            String::from_utf8(bytes).expect("utf-8 csv"),
+            "name,email,phone,Город,Stage\nИван Петров,ivan@example.com,+79001234567,,\n"
+        );
+    }
+
+    #[test]
+    fn build_upload_template_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_important_fields().times(0);
+
+        let result = build_upload_template(&access_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn run_clients_import_completes_job_with_row_counts() {
+        let mut repo = MockRepository::new();
+        let mut seq = Sequence::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings().returning(|hub_id| {
+            Ok(HubSettings::defaults(hub_id)
+                .with_default_phone_region(Some(PhoneRegion::new("DE").expect("valid region"))))
+        });
+        repo.expect_save_import_job()
+            .withf(|job| {
+                job.status == ImportJobStatus::Running && job.total_rows == 2 && job.error_rows == 0
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|job| Ok(job.clone()));
+        repo.expect_import_clients()
+            .withf(|clients, name_matches, strategy, _, _| {
+                clients.len() == 2
+                    && name_matches.is_empty()
+                    && clients[1].phone.as_ref().map(|phone| phone.as_str())
+                        == Some("+493012345678")
+                    && *strategy == ImportConflictStrategy::Upsert
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _, _, _| {
+                Ok(ClientUpsertSummary {
+                    inserted: 1,
+                    updated: 1,
+                })
+            });
+        repo.expect_save_import_job()
+            .withf(|job| {
+                job.status == ImportJobStatus::Completed
+                    && job.inserted_rows == 1
+                    && job.updated_rows == 1
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form(
+            "name,email,phone\nAlice,alice@example.com,\nBob,bob@example.com,030 12345678\n",
+        );
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Completed);
+        assert!(job.completed_at.is_some());
+    }
+
+    #[test]
+    fn run_clients_import_reports_chunk_progress_until_the_job_is_stored() {
+        let progress = Arc::new(ImportProgress::new());
+        let job_id = pending_import_job().id;
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        let chunk_progress = Arc::clone(&progress);
+        repo.expect_import_clients()
+            .times(1)
+            .returning(move |_, _, _, _, on_chunk| {
+                let written = ClientUpsertSummary {
+                    inserted: 1,
+                    updated: 0,
+                };
+                on_chunk(written);
+                assert_eq!(chunk_progress.get(job_id), Some(written));
+                on_chunk(ClientUpsertSummary {
+                    inserted: 2,
+                    updated: 0,
+                });
+                Ok(ClientUpsertSummary {
+                    inserted: 2,
+                    updated: 0,
+                })
+            });
+        repo.expect_save_import_job()
+            .times(2)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email\nAlice,alice@example.com\nBob,bob@example.com\n");
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &progress,
+        )
+        .expect("import ran");
+
+        assert_eq!(job.inserted_rows, 2);
+        assert_eq!(progress.get(job_id), None);
+    }
+
+    #[test]
+    fn run_clients_import_marks_job_failed_over_hub_limit() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_import_clients()
+            .times(1)
+            .returning(|_, _, _, _, _| {
+                Err(ClientWriteError::LimitExceeded {
+                    hub_id: 11,
+                    max_clients: 1,
+                })
+            });
+        repo.expect_save_import_job()
+            .times(2)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email\nAlice,alice@example.com\n");
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Failed);
+        assert_eq!(job.inserted_rows, 0);
+        assert!(
+            job.error_message
+                .as_deref()
+                .is_some_and(|message| message.starts_with("Достигнут лимит клиентов"))
+        );
+    }
+
+    #[test]
+    fn run_clients_import_marks_job_failed_when_it_cannot_start() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_import_clients().times(0);
+        repo.expect_save_import_job()
+            .withf(|job| job.status == ImportJobStatus::Failed)
+            .times(1)
+            .returning(|job| Ok(job.clone()));
+
+        // A job that is already running cannot be started again.
+        let mut running = pending_import_job();
+        running.status = ImportJobStatus::Running;
+        let mut form = upload_form("name,email\nAlice,alice@example.com\n");
+        let job = run_clients_import(
+            running,
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Failed);
+    }
+
+    #[test]
+    fn run_clients_import_marks_job_failed_when_start_is_not_stored() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_import_clients().times(0);
+        repo.expect_save_import_job()
+            .withf(|job| job.status == ImportJobStatus::Running)
+            .times(1)
+            .returning(|_| Err(RepositoryError::ConstraintViolation("locked".to_string())));
+        repo.expect_save_import_job()
+            .withf(|job| job.status == ImportJobStatus::Failed)
+            .times(1)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email\nAlice,alice@example.com\n");
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Failed);
+        assert_eq!(
+            job.error_message.as_deref(),
+            Some("Не удалось загрузить клиентов.")
+        );
+    }
+
+    #[test]
+    fn run_clients_import_reports_existing_clients_with_error_strategy() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_import_clients()
+            .withf(|_, _, strategy, _, _| *strategy == ImportConflictStrategy::Error)
+            .times(1)
+            .returning(|_, _, _, _, _| {
+                Err(ClientWriteError::ClientExists {
+                    hub_id: 11,
+                    name: "Alice".to_string(),
+                })
+            });
+        repo.expect_save_import_job()
+            .times(2)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email\nAlice,alice@example.com\n");
+        form.conflict_strategy = Some(Text(ImportConflictStrategy::Error));
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Failed);
+        assert!(
+            job.error_message
+                .as_deref()
+                .is_some_and(|message| message.contains("уже существуют"))
+        );
+    }
+
+    #[test]
+    fn run_clients_import_fails_strict_upload_with_unknown_column() {
+        use crate::domain::important_field::ImportantField;
+
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_list_important_fields().times(1).returning(|_| {
+            Ok(vec![
+                ImportantField::try_new(11, "Город".to_string()).expect("valid field"),
+            ])
+        });
+        repo.expect_import_clients().times(0);
+        repo.expect_save_import_job()
+            .withf(|job| job.status == ImportJobStatus::Failed)
+            .times(1)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email,Город,Notes\nAlice,alice@example.com,Казань,VIP\n");
+        form.strict = Some(Text(true));
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(
+            job.error_message.as_deref(),
+            Some("Неизвестная колонка «Notes» в файле.")
+        );
+    }
+
+    #[test]
+    fn run_clients_import_updates_single_name_matches_and_skips_ambiguous_rows() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_client_ids_by_name_key()
+            .withf(|hub_id| hub_id.get() == 11)
+            .times(1)
+            .returning(|_| {
+                let id = |id| ClientId::new(id).expect("valid client id");
+                Ok(HashMap::from([
+                    ("client".to_string(), vec![id(5)]),
+                    ("namesake".to_string(), vec![id(6), id(7)]),
+                ]))
+            });
+        repo.expect_update_client().times(0);
+        repo.expect_import_clients()
+            .withf(|clients, name_matches, _, actor, _| {
+                clients.len() == 1
+                    && clients[0].name.as_str() == "Fresh"
+                    && name_matches.len() == 1
+                    && name_matches[0].0.get() == 5
+                    && name_matches[0].1.email.as_ref().map(|email| email.as_str())
+                        == Some("new@example.com")
+                    && actor.as_str() == "viewer@example.com"
+            })
+            .times(1)
+            .returning(|_, _, _, _, _| {
+                Ok(ClientUpsertSummary {
+                    inserted: 1,
+                    updated: 1,
+                })
+            });
+        repo.expect_save_import_job()
+            .times(2)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form(
+            "name,email\nclient,new@example.com\nNamesake,namesake@example.com\nFresh,fresh@example.com\n",
+        );
+        form.match_by = Some(Text(MatchBy::Name));
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Completed);
+        assert_eq!(
+            (
+                job.total_rows,
+                job.inserted_rows,
+                job.updated_rows,
+                job.error_rows
+            ),
+            (3, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn run_clients_import_rejects_name_matches_with_error_strategy() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_contact_policy()
+            .returning(|_| Ok(ContactPolicy::default()));
+        repo.expect_get_hub_settings()
+            .returning(|hub_id| Ok(HubSettings::defaults(hub_id)));
+        repo.expect_client_ids_by_name_key().returning(|_| {
+            Ok(HashMap::from([(
+                "client".to_string(),
+                vec![ClientId::new(5).expect("valid client id")],
+            )]))
+        });
+        repo.expect_update_client().times(0);
+        repo.expect_import_clients().times(0);
+        repo.expect_save_import_job()
+            .times(2)
+            .returning(|job| Ok(job.clone()));
+
+        let mut form = upload_form("name,email\nClient,new@example.com\n");
+        form.match_by = Some(Text(MatchBy::Name));
+        form.conflict_strategy = Some(Text(ImportConflictStrategy::Error));
+        let job = run_clients_import(
+            pending_import_job(),
+            &mut form,
+            &admin_user(),
+            &repo,
+            &ImportProgress::new(),
+        )
+        .expect("import ran");
+
+        assert_eq!(job.status, ImportJobStatus::Failed);
+        assert_eq!(job.error_message.as_deref(), Some(EXISTING_CLIENTS_MESSAGE));
+    }
+
+    #[test]
+    fn get_import_job_hides_jobs_of_other_hubs() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_import_job()
+            .withf(|job_id, hub_id| job_id.get() == 3 && hub_id.get() == 11)
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let result = get_import_job(3, &admin_user(), &repo, &ImportProgress::new());
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn get_import_job_reports_rows_of_a_running_import() {
+        let progress = ImportProgress::new();
+        let mut running = pending_import_job();
+        running.start(10, 0).expect("pending job starts");
+        progress.record(
+            running.id,
+            ClientUpsertSummary {
+                inserted: 4,
+                updated: 1,
+            },
+        );
+        let mut repo = MockRepository::new();
+        repo.expect_get_import_job()
+            .times(1)
+            .returning(move |_, _| Ok(Some(running.clone())));
+
+        let job = get_import_job(3, &admin_user(), &repo, &progress).expect("job found");
+
+        assert_eq!((job.inserted_rows, job.updated_rows), (4, 1));
+    }
 }
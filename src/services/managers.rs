@@ -1,50 +1,88 @@
 //! Services handling manager administration workflows.
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
 use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::repository::errors::RepositoryError;
 use pushkind_common::routes::ensure_role;
+use serde_json::json;
+
+use crate::domain::assignment_rule::ManagerAssignmentRule;
+use crate::domain::client_event::ClientEventType;
+use crate::domain::manager::{Manager, NewManager};
+use crate::domain::types::{ClientEmail, ClientId, HubId, ManagerEmail, ManagerId, RuleId};
+use crate::dto::managers::{
+    ManagerAssignmentsUploadDto, ManagerModalData, ManagersPageData, ManagersQuery,
+};
+use crate::forms::managers::{
+    AddManagerPayload, AssignManagerPayload, NewManagerAssignmentRulePayload,
+    SetManagerActivePayload, UpdateOwnManagerPayload, UploadManagerAssignmentsForm,
+    UploadManagerAssignmentsFormError,
+};
+use crate::repository::{
+    ClientListQuery, ClientReader, ManagerReader, ManagerWriter, RuleReader, RuleWriter,
+    WebhookReader,
+};
+use crate::services::webhooks::notify_client_events;
+use crate::services::{ServiceError, ServiceOutcome, ServiceResult, log_service_call};
+use crate::webhooks::WebhookSender;
+use crate::{SERVICE_ADMIN_ROLE, SERVICE_MANAGER_ROLE};
 
-use crate::SERVICE_ADMIN_ROLE;
-use crate::domain::types::{HubId, ManagerId};
-use crate::dto::managers::{ManagerModalData, ManagersPageData};
-use crate::forms::managers::{AddManagerPayload, AssignManagerPayload};
-use crate::repository::{ClientListQuery, ClientReader, ManagerReader, ManagerWriter};
-use crate::services::{ServiceError, ServiceResult};
+/// Page that manager administration mutations return to.
+const MANAGERS_PAGE_PATH: &str = "/managers";
 
-/// Loads all managers with the clients assigned to them.
-pub fn list_managers<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<ManagersPageData>
+/// Loads all managers with the clients assigned to them in the requested order.
+#[tracing::instrument(skip(repo, user))]
+pub fn list_managers<R>(
+    query: ManagersQuery,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ManagersPageData>
 where
     R: ManagerReader + ?Sized,
 {
+    log_service_call("list_managers", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
 
-    let managers = repo.list_managers_with_clients(hub_id)?;
+    let managers = repo.list_managers_with_clients(hub_id, query.sort.unwrap_or_default())?;
 
     Ok(ManagersPageData { managers })
 }
 
 /// Validates the incoming form and persists the manager entity.
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload))]
 pub fn add_manager<R>(
     payload: AddManagerPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<()>
+) -> ServiceResult<ServiceOutcome>
 where
     R: ManagerWriter + ?Sized,
 {
+    log_service_call("add_manager", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
 
     let new_manager = payload.into_domain(hub_id);
 
-    repo.create_or_update_manager(&new_manager)?;
+    let manager = repo.save_manager(&new_manager)?;
+    tracing::info!(manager_id = %manager.id, %hub_id, "Manager added");
 
-    Ok(())
+    Ok(ServiceOutcome::new(
+        "Менеджер добавлен.",
+        MANAGERS_PAGE_PATH,
+    ))
 }
 
 /// Loads data necessary to render the manager modal body.
+///
+/// Only active clients are listed; saving the modal keeps the assignments of
+/// archived ones.
+#[tracing::instrument(skip(repo, user))]
 pub fn load_manager_modal<R>(
     manager_id: i32,
     user: &AuthenticatedUser,
@@ -53,6 +91,7 @@ pub fn load_manager_modal<R>(
 where
     R: ManagerReader + ClientReader + ?Sized,
 {
+    log_service_call("load_manager_modal", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
@@ -69,14 +108,17 @@ where
 }
 
 /// Assigns the provided client identifiers to the given manager.
+#[must_use = "the outcome carries the message shown to the user"]
+#[tracing::instrument(skip(repo, user, payload))]
 pub fn assign_manager<R>(
     payload: AssignManagerPayload,
     user: &AuthenticatedUser,
     repo: &R,
-) -> ServiceResult<()>
+) -> ServiceResult<ServiceOutcome>
 where
     R: ClientReader + ManagerReader + ManagerWriter + ?Sized,
 {
+    log_service_call("assign_manager", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
@@ -85,30 +127,381 @@ where
         .get_manager_by_id(payload.manager_id, hub_id)?
         .ok_or(ServiceError::NotFound)?;
 
-    for client_id in &payload.client_ids {
-        if repo.get_client_by_id(*client_id, hub_id)?.is_none() {
-            return Err(ServiceError::Form(
-                "Некорректный список клиентов".to_string(),
-            ));
+    let clients = repo.get_clients_by_ids(&payload.client_ids, hub_id)?;
+    let all_found = payload
+        .client_ids
+        .iter()
+        .all(|client_id| clients.iter().any(|client| client.id == *client_id));
+    if !all_found {
+        return Err(ServiceError::Form(
+            "Некорректный список клиентов".to_string(),
+        ));
+    }
+
+    let assigned = repo.assign_clients_to_manager(manager.id, &payload.client_ids)?;
+    tracing::info!(manager_id = %manager.id, assigned, "Clients assigned to manager");
+
+    Ok(ServiceOutcome::new(
+        "Менеджер назначен клиентам.",
+        MANAGERS_PAGE_PATH,
+    ))
+}
+
+/// Assigns clients to managers from an uploaded `client_email,manager_email` file.
+///
+/// Assignments are added on top of the existing ones. Rows whose client or
+/// manager is not found in the hub are reported back instead of failing the
+/// whole upload.
+#[tracing::instrument(skip(form, repo, user))]
+pub fn upload_manager_assignments<R>(
+    form: &mut UploadManagerAssignmentsForm,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ManagerAssignmentsUploadDto>
+where
+    R: ClientReader + ManagerReader + ManagerWriter + ?Sized,
+{
+    log_service_call("upload_manager_assignments", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let rows = form.parse().map_err(|err| {
+        tracing::error!("Failed to parse manager assignments: {err}");
+        ServiceError::Form(match err {
+            UploadManagerAssignmentsFormError::MissingColumn(column) => {
+                format!("В файле нет обязательной колонки «{column}».")
+            }
+            _ => "Ошибка при парсинге файла назначений".to_string(),
+        })
+    })?;
+
+    // Files usually repeat the same managers, so every email is looked up once.
+    let mut clients: HashMap<String, Option<ClientId>> = HashMap::new();
+    let mut managers: HashMap<String, Option<ManagerId>> = HashMap::new();
+    let mut assignments: BTreeMap<ManagerId, BTreeSet<ClientId>> = BTreeMap::new();
+    let mut unresolved = Vec::new();
+
+    for row in rows {
+        let client_id = match clients.get(&row.client_email) {
+            Some(client_id) => *client_id,
+            None => {
+                let client_id = match ClientEmail::new(row.client_email.as_str()) {
+                    Ok(email) => repo
+                        .get_client_by_email(&email, hub_id)?
+                        .map(|client| client.id),
+                    Err(_) => None,
+                };
+                clients.insert(row.client_email.clone(), client_id);
+                client_id
+            }
+        };
+        let manager_id = match managers.get(&row.manager_email) {
+            Some(manager_id) => *manager_id,
+            None => {
+                let manager_id = match ManagerEmail::new(row.manager_email.as_str()) {
+                    Ok(email) => repo
+                        .get_manager_by_email(&email, hub_id)?
+                        .map(|manager| manager.id),
+                    Err(_) => None,
+                };
+                managers.insert(row.manager_email.clone(), manager_id);
+                manager_id
+            }
+        };
+
+        match (client_id, manager_id) {
+            (Some(client_id), Some(manager_id)) => {
+                assignments.entry(manager_id).or_default().insert(client_id);
+            }
+            _ => unresolved.push(row.into()),
+        }
+    }
+
+    let mut assigned = 0;
+    for (manager_id, client_ids) in assignments {
+        let client_ids: Vec<ClientId> = client_ids.into_iter().collect();
+        assigned += repo.add_clients_to_manager(manager_id, &client_ids)?;
+    }
+    tracing::info!(
+        %hub_id,
+        assigned,
+        unresolved = unresolved.len(),
+        "Manager assignments uploaded"
+    );
+
+    let message = if unresolved.is_empty() {
+        format!("Назначено клиентов: {assigned}.")
+    } else {
+        format!(
+            "Назначено клиентов: {assigned}, не найдено строк: {}.",
+            unresolved.len()
+        )
+    };
+
+    Ok(ManagerAssignmentsUploadDto {
+        message,
+        assigned,
+        unresolved,
+    })
+}
+
+/// Archives or restores a hub manager.
+///
+/// Archived managers disappear from the managers list but keep their events.
+#[tracing::instrument(skip(repo, user, payload))]
+pub fn set_manager_active<R>(
+    payload: SetManagerActivePayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Manager>
+where
+    R: ManagerWriter + ?Sized,
+{
+    log_service_call("set_manager_active", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let manager = match repo.set_manager_active(payload.manager_id, hub_id, payload.is_active) {
+        Ok(manager) => manager,
+        Err(RepositoryError::NotFound) => return Err(ServiceError::NotFound),
+        Err(err) => return Err(err.into()),
+    };
+    tracing::info!(
+        manager_id = %manager.id,
+        is_active = manager.is_active,
+        "Manager activity changed"
+    );
+
+    Ok(manager)
+}
+
+/// Deletes a manager of the admin's hub.
+///
+/// A manager the admin's hub does not hold, whether it belongs to another hub
+/// or does not exist, is refused as unauthorized. The hub's system manager
+/// cannot be deleted.
+#[tracing::instrument(skip(repo, user))]
+pub fn delete_manager<R>(
+    manager_id: ManagerId,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<()>
+where
+    R: ManagerReader + ManagerWriter + ?Sized,
+{
+    log_service_call("delete_manager", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    if repo.get_manager_by_id(manager_id, hub_id)?.is_none() {
+        return Err(ServiceError::Unauthorized);
+    }
+
+    match repo.delete_manager(manager_id, hub_id) {
+        Ok(()) => {
+            tracing::info!(%manager_id, "Manager deleted");
+            Ok(())
         }
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
+        Err(err) => Err(err.into()),
     }
+}
 
-    repo.assign_clients_to_manager(manager.id, &payload.client_ids)?;
+/// Lets a manager change their own display name and phone without admin rights.
+///
+/// The manager row is synced from the token first so it exists; afterwards the
+/// name is locked and later login syncs keep it.
+#[tracing::instrument(skip(repo, user, payload))]
+pub fn update_own_manager<R>(
+    payload: UpdateOwnManagerPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Manager>
+where
+    R: ManagerWriter + ?Sized,
+{
+    log_service_call("update_own_manager", user);
+    ensure_role(user, SERVICE_MANAGER_ROLE)?;
 
-    Ok(())
+    let manager_payload = NewManager::try_from(user).map_err(|err| {
+        tracing::error!("Failed to build manager from user: {err}");
+        ServiceError::Internal
+    })?;
+    let manager = repo.create_or_update_manager(&manager_payload)?;
+
+    let manager =
+        match repo.update_manager_profile(manager.id, manager.hub_id, &payload.name, payload.phone)
+        {
+            Ok(manager) => manager,
+            Err(RepositoryError::NotFound) => return Err(ServiceError::NotFound),
+            Err(err) => return Err(err.into()),
+        };
+    tracing::info!(manager_id = %manager.id, "Manager profile updated");
+
+    Ok(manager)
+}
+
+/// Moves all clients of one manager to another manager of the same hub.
+///
+/// Each transferred client gets a `Transfer` event attributed to the receiving
+/// manager. Returns the number of clients that changed hands.
+#[tracing::instrument(skip(repo, user, webhook_sender))]
+pub fn transfer_clients_between_managers<R, S>(
+    from_id: ManagerId,
+    to_id: ManagerId,
+    user: &AuthenticatedUser,
+    repo: &R,
+    webhook_sender: &S,
+) -> ServiceResult<usize>
+where
+    R: ManagerReader + ManagerWriter + WebhookReader + ?Sized,
+    S: WebhookSender + ?Sized,
+{
+    log_service_call("transfer_clients_between_managers", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    if from_id == to_id {
+        return Err(ServiceError::Form(
+            "Выберите другого менеджера для передачи клиентов".to_string(),
+        ));
+    }
+
+    // Both lookups are hub-scoped, so a manager from another hub is reported as missing.
+    let from = repo
+        .get_manager_by_id(from_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+    let to = repo
+        .get_manager_by_id(to_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    let events = repo.reassign_all_clients(
+        from.id,
+        to.id,
+        hub_id,
+        &ClientEventType::Other("Transfer".to_string()),
+        &json!({
+            "text": format!(
+                "Клиент передан от {} к {}",
+                from.name.as_str(),
+                to.name.as_str()
+            ),
+            "from_manager_id": from.id.get(),
+            "to_manager_id": to.id.get(),
+        }),
+    )?;
+    let transferred = events.len();
+
+    notify_client_events(hub_id, &events, repo, webhook_sender);
+    tracing::info!(
+        from_manager_id = %from.id,
+        to_manager_id = %to.id,
+        transferred,
+        "Clients transferred between managers"
+    );
+
+    Ok(transferred)
+}
+
+/// Lists the manager assignment rules of the admin's hub.
+#[tracing::instrument(skip(repo, user))]
+pub fn list_assignment_rules<R>(
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<ManagerAssignmentRule>>
+where
+    R: RuleReader + ?Sized,
+{
+    log_service_call("list_assignment_rules", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.list_rules(hub_id)?)
+}
+
+/// Stores a rule assigning a hub manager to clients with a custom field value.
+///
+/// Managers of other hubs are reported as missing and a second rule for the
+/// same field and value is a conflict.
+#[tracing::instrument(skip(repo, user, payload))]
+pub fn create_assignment_rule<R>(
+    payload: NewManagerAssignmentRulePayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ManagerAssignmentRule>
+where
+    R: ManagerReader + RuleWriter + ?Sized,
+{
+    log_service_call("create_assignment_rule", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    repo.get_manager_by_id(payload.manager_id, hub_id)?
+        .ok_or(ServiceError::NotFound)?;
+
+    let rule = match repo.create_rule(&payload.into_domain(hub_id)) {
+        Ok(rule) => rule,
+        Err(RepositoryError::ConstraintViolation(_)) => return Err(ServiceError::Conflict),
+        Err(err) => return Err(err.into()),
+    };
+    tracing::info!(rule_id = %rule.id, manager_id = %rule.manager_id, "Assignment rule added");
+
+    Ok(rule)
+}
+
+/// Removes a manager assignment rule of the admin's hub.
+#[tracing::instrument(skip(repo, user))]
+pub fn delete_assignment_rule<R>(
+    rule_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<()>
+where
+    R: RuleWriter + ?Sized,
+{
+    log_service_call("delete_assignment_rule", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let rule_id = RuleId::new(rule_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    match repo.delete_rule(rule_id, hub_id) {
+        Ok(()) => Ok(()),
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
+        Err(err) => Err(err.into()),
+    }
 }
 
 #[cfg(all(test, feature = "test-mocks"))]
 mod tests {
     use super::*;
     use crate::domain::client::Client;
+    use crate::domain::client_event::ClientEvent;
     use crate::domain::manager::Manager;
-    use crate::domain::types::{ClientId, HubId, ManagerEmail, ManagerId, ManagerName, PublicId};
-    use crate::forms::managers::{AddManagerForm, AssignManagerForm};
+    use crate::domain::types::{
+        ClientEventId, HubId, ManagerEmail, ManagerId, ManagerName, PublicId,
+    };
+    use crate::forms::managers::{
+        AddManagerForm, AssignManagerForm, NewManagerAssignmentRuleForm, SetManagerActiveForm,
+        UpdateOwnManagerForm,
+    };
     use crate::repository::mock::MockRepository;
     use crate::services::ServiceError;
+    use crate::webhooks::WebhookDelivery;
     use chrono::Utc;
 
+    struct NoopWebhookSender;
+
+    impl WebhookSender for NoopWebhookSender {
+        fn enqueue(&self, _delivery: WebhookDelivery) {}
+    }
+
     fn admin_user() -> AuthenticatedUser {
         AuthenticatedUser {
             sub: "1".to_string(),
@@ -163,7 +556,7 @@ mod tests {
         repo.expect_list_managers_with_clients().times(0);
         let user = viewer_user();
 
-        let result = list_managers(&user, &repo);
+        let result = list_managers(ManagersQuery::default(), &user, &repo);
 
         assert!(matches!(result, Err(ServiceError::Unauthorized)));
     }
@@ -172,12 +565,14 @@ mod tests {
     fn add_manager_creates_manager() {
         let mut repo = MockRepository::new();
         let manager = sample_manager(1, 22);
-        repo.expect_create_or_update_manager()
+        repo.expect_save_manager()
             .withf(|payload| {
                 payload.hub_id == HubId::new(22).expect("valid hub id")
                     && payload.is_user
                     && payload.email == ManagerEmail::new("manager@example.com").expect("email")
                     && payload.name == ManagerName::new("Manager").expect("name")
+                    && payload.company.as_ref().map(|company| company.as_str()) == Some("Acme")
+                    && payload.phone.is_none()
             })
             .times(1)
             .returning(move |_| Ok(manager.clone()));
@@ -185,10 +580,17 @@ mod tests {
         let payload = AddManagerPayload::try_from(AddManagerForm {
             name: "Manager".to_string(),
             email: "manager@example.com".to_string(),
+            company: Some("Acme".to_string()),
+            phone: None,
         })
         .expect("valid payload");
 
-        add_manager(payload, &user, &repo).expect("manager created");
+        let outcome = add_manager(payload, &user, &repo).expect("manager created");
+
+        assert_eq!(
+            outcome,
+            ServiceOutcome::new("Менеджер добавлен.", "/managers")
+        );
     }
 
     #[test]
@@ -227,16 +629,10 @@ mod tests {
         repo.expect_get_manager_by_id()
             .times(1)
             .returning(move |_, _| Ok(Some(manager.clone())));
-        let valid_client_id = ClientId::new(1).expect("client id");
-        repo.expect_get_client_by_id()
-            .times(2)
-            .returning(move |client_id, _| {
-                if client_id == valid_client_id {
-                    Ok(Some(sample_client(1, 22)))
-                } else {
-                    Ok(None)
-                }
-            });
+        repo.expect_get_clients_by_ids()
+            .withf(|ids, hub_id| ids.len() == 2 && hub_id.get() == 22)
+            .times(1)
+            .returning(|_, _| Ok(vec![sample_client(1, 22)]));
         repo.expect_assign_clients_to_manager().times(0);
         let user = admin_user();
         let payload = AssignManagerPayload::try_from(AssignManagerForm {
@@ -257,9 +653,14 @@ mod tests {
         repo.expect_get_manager_by_id()
             .times(1)
             .returning(move |_, _| Ok(Some(manager.clone())));
-        repo.expect_get_client_by_id()
-            .times(2)
-            .returning(move |client_id, _| Ok(Some(sample_client(client_id.get(), 22))));
+        repo.expect_get_clients_by_ids()
+            .times(1)
+            .returning(|ids, _| {
+                Ok(ids
+                    .iter()
+                    .map(|client_id| sample_client(client_id.get(), 22))
+                    .collect())
+            });
         repo.expect_assign_clients_to_manager()
             .withf(|manager_id, client_ids| {
                 manager_id == &ManagerId::new(2).expect("manager id") && client_ids.len() == 2
@@ -273,6 +674,373 @@ mod tests {
         })
         .expect("valid payload");
 
-        assign_manager(payload, &user, &repo).expect("assignment ok");
+        let outcome = assign_manager(payload, &user, &repo).expect("assignment ok");
+
+        assert_eq!(
+            outcome,
+            ServiceOutcome::new("Менеджер назначен клиентам.", "/managers")
+        );
+    }
+
+    fn assignments_upload(csv: &str) -> UploadManagerAssignmentsForm {
+        use actix_multipart::form::tempfile::TempFile;
+        use std::io::{Seek, Write};
+
+        let mut file = tempfile::NamedTempFile::new().expect("temp file");
+        file.write_all(csv.as_bytes()).expect("write csv");
+        file.rewind().expect("rewind csv");
+
+        UploadManagerAssignmentsForm {
+            csv: TempFile {
+                file,
+                content_type: None,
+                file_name: None,
+                size: csv.len(),
+            },
+        }
+    }
+
+    #[test]
+    fn upload_manager_assignments_adds_resolved_rows_and_reports_the_rest() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_client_by_email()
+            .times(3)
+            .returning(|email, hub_id| {
+                Ok(match email.as_str() {
+                    "alice@example.com" => Some(sample_client(1, hub_id.get())),
+                    "bob@example.com" => Some(sample_client(2, hub_id.get())),
+                    _ => None,
+                })
+            });
+        repo.expect_get_manager_by_email()
+            .times(1)
+            .returning(|_, hub_id| Ok(Some(sample_manager(5, hub_id.get()))));
+        repo.expect_add_clients_to_manager()
+            .withf(|manager_id, client_ids| {
+                manager_id.get() == 5
+                    && client_ids
+                        == [
+                            ClientId::new(1).expect("client id"),
+                            ClientId::new(2).expect("client id"),
+                        ]
+            })
+            .times(1)
+            .returning(|_, client_ids| Ok(client_ids.len()));
+        repo.expect_assign_clients_to_manager().times(0);
+        let mut form = assignments_upload(
+            "client_email,manager_email\n\
+             alice@example.com,manager@example.com\n\
+             bob@example.com,manager@example.com\n\
+             alice@example.com,manager@example.com\n\
+             ghost@example.com,manager@example.com\n\
+             not-an-email,manager@example.com\n",
+        );
+
+        let result =
+            upload_manager_assignments(&mut form, &admin_user(), &repo).expect("upload ok");
+
+        assert_eq!(result.assigned, 2);
+        assert_eq!(
+            result
+                .unresolved
+                .iter()
+                .map(|row| row.line)
+                .collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert_eq!(
+            result.message,
+            "Назначено клиентов: 2, не найдено строк: 2."
+        );
+    }
+
+    #[test]
+    fn upload_manager_assignments_requires_admin() {
+        let mut repo = MockRepository::new();
+        repo.expect_add_clients_to_manager().times(0);
+        let mut form = assignments_upload("client_email,manager_email\n");
+
+        let result = upload_manager_assignments(&mut form, &viewer_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn transfer_clients_between_managers_moves_clients_and_records_events() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id()
+            .times(2)
+            .returning(|manager_id, hub_id| {
+                Ok(Some(sample_manager(manager_id.get(), hub_id.get())))
+            });
+        repo.expect_reassign_all_clients()
+            .withf(|from_id, to_id, hub_id, event_type, event_data| {
+                from_id == &ManagerId::new(1).expect("manager id")
+                    && to_id == &ManagerId::new(2).expect("manager id")
+                    && hub_id == &HubId::new(22).expect("hub id")
+                    && event_type == &ClientEventType::Other("Transfer".to_string())
+                    && event_data["from_manager_id"] == 1
+                    && event_data["to_manager_id"] == 2
+            })
+            .times(1)
+            .returning(|_, to_id, _, event_type, event_data| {
+                Ok([7, 8]
+                    .into_iter()
+                    .map(|client_id| {
+                        ClientEvent::new(
+                            ClientEventId::new(client_id).expect("event id"),
+                            ClientId::new(client_id).expect("client id"),
+                            to_id,
+                            event_type.clone(),
+                            event_data.clone(),
+                            Utc::now().naive_utc(),
+                        )
+                    })
+                    .collect())
+            });
+
+        repo.expect_list_webhooks()
+            .times(1)
+            .returning(|_| Ok(Vec::new()));
+
+        let transferred = transfer_clients_between_managers(
+            ManagerId::new(1).expect("manager id"),
+            ManagerId::new(2).expect("manager id"),
+            &admin_user(),
+            &repo,
+            &NoopWebhookSender,
+        )
+        .expect("transfer ok");
+
+        assert_eq!(transferred, 2);
+    }
+
+    #[test]
+    fn transfer_clients_between_managers_rejects_foreign_hub_manager() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id()
+            .returning(|manager_id, hub_id| {
+                Ok((manager_id.get() == 1).then(|| sample_manager(1, hub_id.get())))
+            });
+        repo.expect_reassign_all_clients().times(0);
+
+        let result = transfer_clients_between_managers(
+            ManagerId::new(1).expect("manager id"),
+            ManagerId::new(9).expect("manager id"),
+            &admin_user(),
+            &repo,
+            &NoopWebhookSender,
+        );
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn set_manager_active_archives_hub_manager() {
+        let mut repo = MockRepository::new();
+        repo.expect_set_manager_active()
+            .withf(|manager_id, hub_id, is_active| {
+                manager_id.get() == 5 && hub_id.get() == 22 && !is_active
+            })
+            .times(1)
+            .returning(|_, _, is_active| Ok(sample_manager(5, 22).with_active(is_active)));
+
+        let payload = SetManagerActivePayload::try_from(SetManagerActiveForm {
+            manager_id: 5,
+            is_active: false,
+        })
+        .expect("valid payload");
+
+        let manager = set_manager_active(payload, &admin_user(), &repo).expect("manager archived");
+
+        assert!(!manager.is_active);
+    }
+
+    #[test]
+    fn set_manager_active_for_unknown_manager_returns_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_set_manager_active()
+            .returning(|_, _, _| Err(RepositoryError::NotFound));
+
+        let payload = SetManagerActivePayload::try_from(SetManagerActiveForm {
+            manager_id: 404,
+            is_active: false,
+        })
+        .expect("valid payload");
+
+        let result = set_manager_active(payload, &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn delete_manager_deletes_manager_of_admin_hub_once() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id()
+            .withf(|manager_id, hub_id| manager_id.get() == 5 && hub_id.get() == 22)
+            .times(1)
+            .returning(|_, _| Ok(Some(sample_manager(5, 22))));
+        repo.expect_delete_manager()
+            .withf(|manager_id, hub_id| manager_id.get() == 5 && hub_id.get() == 22)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let manager_id = ManagerId::new(5).expect("valid manager id");
+        delete_manager(manager_id, &admin_user(), &repo).expect("manager deleted");
+    }
+
+    #[test]
+    fn delete_manager_requires_admin() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id().times(0);
+        repo.expect_delete_manager().times(0);
+        let manager_id = ManagerId::new(7).expect("valid manager id");
+
+        let result = delete_manager(manager_id, &viewer_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn delete_manager_rejects_manager_of_another_hub() {
+        let mut repo = MockRepository::new();
+        // The lookup is scoped to the admin's hub, so another hub's manager is not found.
+        repo.expect_get_manager_by_id()
+            .withf(|manager_id, hub_id| manager_id.get() == 7 && hub_id.get() == 22)
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_delete_manager().times(0);
+        let manager_id = ManagerId::new(7).expect("valid manager id");
+
+        let result = delete_manager(manager_id, &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    fn rule_payload(manager_id: i32) -> NewManagerAssignmentRulePayload {
+        NewManagerAssignmentRulePayload::try_from(NewManagerAssignmentRuleForm {
+            manager_id,
+            field: "Region".to_string(),
+            value: "North".to_string(),
+        })
+        .expect("valid payload")
+    }
+
+    #[test]
+    fn create_assignment_rule_rejects_manager_of_another_hub() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id()
+            .withf(|_, hub_id| hub_id.get() == 22)
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_create_rule().times(0);
+
+        let result = create_assignment_rule(rule_payload(5), &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn create_assignment_rule_reports_duplicate_as_conflict() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_manager_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(sample_manager(5, 22))));
+        repo.expect_create_rule()
+            .withf(|rule| {
+                rule.hub_id.get() == 22
+                    && rule.manager_id.get() == 5
+                    && rule.field.as_str() == "Region"
+                    && rule.value.as_str() == "North"
+            })
+            .times(1)
+            .returning(|_| {
+                Err(RepositoryError::ConstraintViolation(
+                    "UNIQUE constraint failed".to_string(),
+                ))
+            });
+
+        let result = create_assignment_rule(rule_payload(5), &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Conflict)));
+    }
+
+    #[test]
+    fn delete_assignment_rule_requires_admin_and_reports_missing_rule() {
+        let mut repo = MockRepository::new();
+        repo.expect_delete_rule()
+            .times(1)
+            .returning(|_, _| Err(RepositoryError::NotFound));
+
+        assert!(matches!(
+            delete_assignment_rule(3, &viewer_user(), &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+        assert!(matches!(
+            delete_assignment_rule(3, &admin_user(), &repo),
+            Err(ServiceError::NotFound)
+        ));
+    }
+
+    fn manager_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "3".to_string(),
+            email: "manager@example.com".to_string(),
+            hub_id: 22,
+            name: "Token Name".to_string(),
+            roles: vec![SERVICE_MANAGER_ROLE.to_string()],
+            exp: 0,
+        }
+    }
+
+    fn profile_payload() -> UpdateOwnManagerPayload {
+        UpdateOwnManagerPayload::try_from(UpdateOwnManagerForm {
+            name: "  Display Name ".to_string(),
+            phone: Some("+14155550111".to_string()),
+        })
+        .expect("valid payload")
+    }
+
+    #[test]
+    fn update_own_manager_updates_the_token_manager() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_or_update_manager()
+            .withf(|new_manager| {
+                new_manager.email == "manager@example.com" && new_manager.hub_id.get() == 22
+            })
+            .times(1)
+            .returning(|_| Ok(sample_manager(8, 22)));
+        repo.expect_update_manager_profile()
+            .withf(|manager_id, hub_id, name, phone| {
+                manager_id.get() == 8
+                    && hub_id.get() == 22
+                    && name.as_str() == "Display Name"
+                    && phone.as_ref().map(|phone| phone.as_str()) == Some("+14155550111")
+            })
+            .times(1)
+            .returning(|_, _, name, phone| {
+                let mut manager = sample_manager(8, 22)
+                    .with_contact_details(None, phone)
+                    .with_name_locked(true);
+                manager.name = name.clone();
+                Ok(manager)
+            });
+
+        let manager =
+            update_own_manager(profile_payload(), &manager_user(), &repo).expect("profile saved");
+
+        assert_eq!(manager.name.as_str(), "Display Name");
+        assert!(manager.name_locked);
+    }
+
+    #[test]
+    fn update_own_manager_requires_manager_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_or_update_manager().times(0);
+        repo.expect_update_manager_profile().times(0);
+
+        let result = update_own_manager(profile_payload(), &viewer_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
     }
 }
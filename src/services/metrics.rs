@@ -0,0 +1,122 @@
+//! Services producing the Prometheus metrics exposed on `/metrics`.
+
+use std::fmt::Write;
+
+use chrono::{Duration, Utc};
+
+use crate::repository::{ServiceStats, ServiceStatsReader};
+use crate::services::ServiceResult;
+
+/// Window of the `crm_client_events_last_24h` gauge.
+const RECENT_EVENTS_WINDOW_HOURS: i64 = 24;
+
+/// Loads the service-wide counters backing the metrics gauges.
+#[tracing::instrument(skip(repo))]
+pub fn load_service_stats<R>(repo: &R) -> ServiceResult<ServiceStats>
+where
+    R: ServiceStatsReader + ?Sized,
+{
+    let since = Utc::now().naive_utc() - Duration::hours(RECENT_EVENTS_WINDOW_HOURS);
+
+    Ok(repo.load_service_stats(since)?)
+}
+
+/// Renders the gauges and the enqueued email counter in the Prometheus text
+/// exposition format.
+pub fn render_prometheus(stats: &ServiceStats, emails_enqueued: u64) -> String {
+    let mut output = String::new();
+    let metrics = [
+        (
+            "crm_clients_total",
+            "gauge",
+            "Clients stored across all hubs.",
+            stats.total_clients as u64,
+        ),
+        (
+            "crm_managers_total",
+            "gauge",
+            "Managers stored across all hubs.",
+            stats.total_managers as u64,
+        ),
+        (
+            "crm_client_events_last_24h",
+            "gauge",
+            "Client events created in the last 24 hours.",
+            stats.recent_events as u64,
+        ),
+        (
+            "crm_emails_enqueued_total",
+            "counter",
+            "Outbound emails enqueued since the process started.",
+            emails_enqueued,
+        ),
+    ];
+
+    for (name, kind, help, value) in metrics {
+        // Writing into a `String` cannot fail.
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} {kind}");
+        let _ = writeln!(output, "{name} {value}");
+    }
+
+    output
+}
+
+#[cfg(all(test, feature = "test-mocks"))]
+mod tests {
+    use pushkind_common::repository::errors::RepositoryError;
+
+    use super::*;
+    use crate::repository::mock::MockRepository;
+
+    #[test]
+    fn load_service_stats_counts_events_of_the_last_day() {
+        let mut repo = MockRepository::new();
+        repo.expect_load_service_stats()
+            .withf(|since| {
+                let age = Utc::now().naive_utc() - *since;
+                age >= Duration::hours(24) && age < Duration::hours(25)
+            })
+            .times(1)
+            .returning(|_| {
+                Ok(ServiceStats {
+                    total_clients: 3,
+                    total_managers: 2,
+                    recent_events: 1,
+                })
+            });
+
+        let stats = load_service_stats(&repo).expect("stats load");
+
+        assert_eq!(stats.total_clients, 3);
+    }
+
+    #[test]
+    fn load_service_stats_propagates_repository_errors() {
+        let mut repo = MockRepository::new();
+        repo.expect_load_service_stats()
+            .returning(|_| Err(RepositoryError::NotFound));
+
+        assert!(load_service_stats(&repo).is_err());
+    }
+
+    #[test]
+    fn render_prometheus_emits_typed_metrics() {
+        let stats = ServiceStats {
+            total_clients: 10,
+            total_managers: 4,
+            recent_events: 7,
+        };
+
+        let output = render_prometheus(&stats, 2);
+
+        assert!(output.contains("# TYPE crm_clients_total gauge\ncrm_clients_total 10\n"));
+        assert!(output.contains("crm_managers_total 4\n"));
+        assert!(output.contains("crm_client_events_last_24h 7\n"));
+        assert!(
+            output.contains(
+                "# TYPE crm_emails_enqueued_total counter\ncrm_emails_enqueued_total 2\n"
+            )
+        );
+    }
+}
@@ -1,10 +1,59 @@
 //! Service modules defining CRM business logic.
 
+use pushkind_common::domain::auth::AuthenticatedUser;
 pub use pushkind_common::services::errors::{ServiceError, ServiceResult};
 
+use crate::domain::types::MaskedEmail;
+
 pub mod api;
+pub mod api_keys;
 pub mod client;
+pub mod export;
 pub mod main;
 pub mod managers;
+pub mod metrics;
+pub mod segments;
 pub mod settings;
 pub mod store;
+pub mod webhooks;
+
+/// User-facing result of a successful mutation: the message to show and the
+/// page the user should land on next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceOutcome {
+    pub message: String,
+    pub redirect_to: String,
+    /// Advisory note shown next to the message, e.g. a possible duplicate.
+    pub warning: Option<String>,
+}
+
+impl ServiceOutcome {
+    /// Create an outcome without a warning.
+    pub fn new(message: impl Into<String>, redirect_to: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            redirect_to: redirect_to.into(),
+            warning: None,
+        }
+    }
+
+    /// Attach an optional advisory note.
+    #[must_use]
+    pub fn with_warning(mut self, warning: Option<String>) -> Self {
+        self.warning = warning;
+        self
+    }
+}
+
+/// Logs at debug level which user of which hub invoked the service `action`.
+///
+/// Called first thing in every public service function taking a user, so
+/// cross-hub issues can be traced with `RUST_LOG=pushkind_crm::services=debug`.
+/// The email is masked like every other address in the logs.
+pub(crate) fn log_service_call(action: &str, user: &AuthenticatedUser) {
+    log::debug!(
+        "service call {action}: user={} hub_id={}",
+        MaskedEmail::new(&user.email),
+        user.hub_id
+    );
+}
@@ -0,0 +1,173 @@
+//! Services managing rule-based client segments.
+
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::repository::errors::RepositoryError;
+use pushkind_common::routes::ensure_role;
+
+use crate::SERVICE_ADMIN_ROLE;
+use crate::domain::client::Client;
+use crate::domain::segment::{ClientSegment, NewClientSegment};
+use crate::domain::types::{HubId, SegmentId};
+use crate::forms::segments::SaveSegmentPayload;
+use crate::repository::{SegmentReader, SegmentWriter};
+use crate::services::{ServiceError, ServiceResult, log_service_call};
+
+/// Lists the segments defined for the admin's hub.
+pub fn list_segments<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<ClientSegment>>
+where
+    R: SegmentReader + ?Sized,
+{
+    log_service_call("list_segments", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.list_segments(hub_id)?)
+}
+
+/// Defines a new segment for the admin's hub.
+pub fn create_segment<R>(
+    payload: SaveSegmentPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientSegment>
+where
+    R: SegmentWriter + ?Sized,
+{
+    log_service_call("create_segment", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let new_segment = NewClientSegment::try_new(hub_id, payload.name, payload.filter_json)?;
+
+    Ok(repo.create_segment(&new_segment)?)
+}
+
+/// Replaces the name and filter of a hub segment.
+pub fn update_segment<R>(
+    segment_id: i32,
+    payload: SaveSegmentPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<ClientSegment>
+where
+    R: SegmentWriter + ?Sized,
+{
+    log_service_call("update_segment", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let segment_id = SegmentId::new(segment_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+    let segment = NewClientSegment::try_new(hub_id, payload.name, payload.filter_json)?;
+
+    repo.update_segment(segment_id, &segment)?
+        .ok_or(ServiceError::NotFound)
+}
+
+/// Removes a segment of the admin's hub.
+pub fn delete_segment<R>(segment_id: i32, user: &AuthenticatedUser, repo: &R) -> ServiceResult<()>
+where
+    R: SegmentWriter + ?Sized,
+{
+    log_service_call("delete_segment", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let segment_id = SegmentId::new(segment_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    if !repo.delete_segment(segment_id, hub_id)? {
+        return Err(ServiceError::NotFound);
+    }
+
+    Ok(())
+}
+
+/// Returns the hub clients currently matching a segment.
+pub fn list_segment_clients<R>(
+    segment_id: i32,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Vec<Client>>
+where
+    R: SegmentReader + ?Sized,
+{
+    log_service_call("list_segment_clients", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let segment_id = SegmentId::new(segment_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    match repo.evaluate_segment(segment_id, hub_id) {
+        Ok(clients) => Ok(clients),
+        Err(RepositoryError::NotFound) => Err(ServiceError::NotFound),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(all(test, feature = "test-mocks"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::domain::types::NonEmptyString;
+    use crate::repository::mock::MockRepository;
+    use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE};
+
+    fn admin_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "admin@example.com".to_string(),
+            hub_id: 5,
+            name: "Admin".to_string(),
+            roles: vec![
+                SERVICE_ACCESS_ROLE.to_string(),
+                SERVICE_ADMIN_ROLE.to_string(),
+            ],
+            exp: 0,
+        }
+    }
+
+    fn payload() -> SaveSegmentPayload {
+        SaveSegmentPayload {
+            name: NonEmptyString::new("VIP").expect("valid name"),
+            filter_json: json!({"fields": {"Tier": "VIP"}}),
+        }
+    }
+
+    #[test]
+    fn create_segment_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_segment().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let result = create_segment(payload(), &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn update_segment_reports_missing_segment() {
+        let mut repo = MockRepository::new();
+        repo.expect_update_segment()
+            .withf(|segment_id, segment| segment_id.get() == 4 && segment.hub_id.get() == 5)
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let result = update_segment(4, payload(), &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn list_segment_clients_maps_missing_segment_to_not_found() {
+        let mut repo = MockRepository::new();
+        repo.expect_evaluate_segment()
+            .times(1)
+            .returning(|_, _| Err(RepositoryError::NotFound));
+
+        let result = list_segment_clients(4, &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+}
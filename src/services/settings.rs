@@ -4,11 +4,15 @@ use pushkind_common::domain::auth::AuthenticatedUser;
 use pushkind_common::routes::ensure_role;
 
 use crate::SERVICE_ADMIN_ROLE;
-use crate::domain::types::HubId;
+use crate::domain::hub_settings::HubSettings;
+use crate::domain::types::{HubId, ImportantFieldName};
 use crate::dto::important_fields::ImportantFieldsPageData;
 use crate::forms::important_fields::ImportantFieldsPayload;
-use crate::repository::{ClientWriter, ImportantFieldReader, ImportantFieldWriter};
-use crate::services::ServiceResult;
+use crate::forms::settings::HubSettingsPayload;
+use crate::repository::{
+    ClientWriter, HubSettingsReader, HubSettingsWriter, ImportantFieldReader, ImportantFieldWriter,
+};
+use crate::services::{ServiceError, ServiceResult, log_service_call};
 
 /// Loads the existing important field names for the admin interface.
 pub fn load_important_fields<R>(
@@ -18,6 +22,7 @@ pub fn load_important_fields<R>(
 where
     R: ImportantFieldReader + ?Sized,
 {
+    log_service_call("load_important_fields", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
@@ -44,6 +49,7 @@ pub fn save_important_fields<R>(
 where
     R: ImportantFieldWriter + ?Sized,
 {
+    log_service_call("save_important_fields", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
@@ -58,11 +64,78 @@ where
     Ok(())
 }
 
+/// Removes one important field of the hub, leaving the others untouched.
+pub fn delete_important_field<R>(
+    name: &ImportantFieldName,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<()>
+where
+    R: ImportantFieldWriter + ?Sized,
+{
+    log_service_call("delete_important_field", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let deleted = repo.delete_important_field(hub_id, name).map_err(|err| {
+        log::error!("Failed to delete important field: {err}");
+        err
+    })?;
+    if !deleted {
+        return Err(ServiceError::NotFound);
+    }
+
+    Ok(())
+}
+
+/// Loads the hub settings, falling back to defaults for unconfigured hubs.
+pub fn load_hub_settings<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<HubSettings>
+where
+    R: HubSettingsReader + ?Sized,
+{
+    log_service_call("load_hub_settings", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let settings = repo.get_hub_settings(hub_id).map_err(|err| {
+        log::error!("Failed to load hub settings: {err}");
+        err
+    })?;
+
+    Ok(settings)
+}
+
+/// Stores the submitted settings for the user's hub.
+pub fn save_hub_settings<R>(
+    payload: HubSettingsPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<HubSettings>
+where
+    R: HubSettingsWriter + ?Sized,
+{
+    log_service_call("save_hub_settings", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+    let settings = payload.into_domain(hub_id)?;
+
+    let settings = repo.save_hub_settings(&settings).map_err(|err| {
+        log::error!("Failed to save hub settings: {err}");
+        err
+    })?;
+
+    Ok(settings)
+}
+
 /// Removes all client data for the user's hub.
 pub fn cleanup_clients<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<()>
 where
     R: ClientWriter + ?Sized,
 {
+    log_service_call("cleanup_clients", user);
     ensure_role(user, SERVICE_ADMIN_ROLE)?;
 
     let hub_id = HubId::new(user.hub_id)?;
@@ -139,6 +212,40 @@ mod tests {
         assert!(matches!(result, Err(ServiceError::Unauthorized)));
     }
 
+    /// Confirms a single field is deleted within the user's hub only.
+    #[test]
+    fn delete_important_field_scopes_to_user_hub() {
+        let mut repo = MockRepository::new();
+        repo.expect_delete_important_field()
+            .withf(|hub_id, name| {
+                hub_id == &HubId::new(42).expect("valid hub id") && name.as_str() == "Tier"
+            })
+            .times(1)
+            .returning(|_, _| Ok(true));
+        let name = ImportantFieldName::new("Tier").expect("valid name");
+
+        delete_important_field(&name, &admin_user(), &repo).expect("should delete field");
+    }
+
+    /// Ensures deleting an unknown field or without admin rights fails.
+    #[test]
+    fn delete_important_field_rejects_unknown_fields_and_viewers() {
+        let mut repo = MockRepository::new();
+        repo.expect_delete_important_field()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        let name = ImportantFieldName::new("Tier").expect("valid name");
+
+        assert!(matches!(
+            delete_important_field(&name, &admin_user(), &repo),
+            Err(ServiceError::NotFound)
+        ));
+        assert!(matches!(
+            delete_important_field(&name, &viewer_user(), &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+    }
+
     /// Verifies that normalization trims, sanitizes, and deduplicates input.
     #[test]
     fn normalize_fields_trims_sanitizes_and_deduplicates() {
@@ -207,6 +314,79 @@ mod tests {
         cleanup_clients(&user, &repo).expect("should cleanup clients");
     }
 
+    /// Ensures hub settings are admin-only.
+    #[test]
+    fn hub_settings_require_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_get_hub_settings().times(0);
+        repo.expect_save_hub_settings().times(0);
+        let user = viewer_user();
+        let payload = HubSettingsPayload {
+            items_per_page: 10,
+            email_sender_name: None,
+            allow_manager_self_assign: false,
+            default_phone_region: None,
+            event_retention_days: None,
+            email_reply_to: None,
+        };
+
+        assert!(matches!(
+            load_hub_settings(&user, &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+        assert!(matches!(
+            save_hub_settings(payload, &user, &repo),
+            Err(ServiceError::Unauthorized)
+        ));
+    }
+
+    /// Confirms saving stores the settings under the user's hub.
+    #[test]
+    fn save_hub_settings_binds_user_hub() {
+        let mut repo = MockRepository::new();
+        repo.expect_save_hub_settings()
+            .withf(|settings| {
+                settings.hub_id == HubId::new(42).expect("valid hub id")
+                    && settings.items_per_page == 10
+                    && settings.allow_manager_self_assign
+            })
+            .times(1)
+            .returning(|settings| Ok(settings.clone()));
+        let payload = HubSettingsPayload {
+            items_per_page: 10,
+            email_sender_name: None,
+            allow_manager_self_assign: true,
+            default_phone_region: None,
+            event_retention_days: None,
+            email_reply_to: None,
+        };
+
+        let settings =
+            save_hub_settings(payload, &admin_user(), &repo).expect("should save settings");
+
+        assert_eq!(settings.items_per_page, 10);
+    }
+
+    /// Ensures a payload built outside the form still goes through validation.
+    #[test]
+    fn save_hub_settings_rejects_invalid_page_size() {
+        let mut repo = MockRepository::new();
+        repo.expect_save_hub_settings().times(0);
+        let payload = HubSettingsPayload {
+            items_per_page: 0,
+            email_sender_name: None,
+            allow_manager_self_assign: false,
+            default_phone_region: None,
+            event_retention_days: None,
+            email_reply_to: None,
+        };
+
+        assert!(matches!(
+            save_hub_settings(payload, &admin_user(), &repo),
+            Err(ServiceError::TypeConstraint(_))
+        ));
+    }
+
     /// Checks that loading returns already saved field names.
     #[test]
     fn load_returns_existing_fields() {
@@ -13,7 +13,7 @@ use crate::domain::store_otp::NewStoreOtp;
 use crate::domain::store_session::{
     STORE_SESSION_COOKIE_NAME, STORE_SESSION_TTL_DAYS, StoreSessionClaims,
 };
-use crate::domain::types::{HubId, PhoneNumber, PublicId};
+use crate::domain::types::{HubId, MaskedPhone, PhoneNumber, PublicId};
 use crate::dto::store::{StoreOtpAcceptResponse, StoreOtpVerifyResponse, StoreSessionUser};
 use crate::forms::store::{StoreOtpRequestInput, StoreOtpVerifyInput};
 use crate::repository::{ClientReader, ClientWriter, StoreOtpRepository};
@@ -61,7 +61,7 @@ where
 
     info!(
         "CRM storefront OTP request accepted for hub {hub_id} and phone {}",
-        phone.as_str()
+        MaskedPhone::new(phone.as_str())
     );
 
     Ok(StoreOtpAcceptResponse { success: true })
@@ -204,7 +204,7 @@ where
 #[cfg(all(test, feature = "test-mocks"))]
 mod tests {
     use super::*;
-    use crate::domain::client::Client;
+    use crate::domain::client::{Client, ClientUpsertSummary};
     use crate::repository::mock::MockRepository;
     use mockall::Sequence;
     use pushkind_common::zmq::{SendFuture, ZmqSenderError};
@@ -356,7 +356,7 @@ mod tests {
         repo.expect_create_or_replace_clients()
             .times(1)
             .in_sequence(&mut sequence)
-            .returning(|_| Ok(0));
+            .returning(|_| Ok(ClientUpsertSummary::default()));
         repo.expect_get_client_by_phone()
             .times(1)
             .in_sequence(&mut sequence)
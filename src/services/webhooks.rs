@@ -0,0 +1,311 @@
+//! Services managing client event webhooks and queueing their deliveries.
+
+use pushkind_common::domain::auth::AuthenticatedUser;
+use pushkind_common::routes::ensure_role;
+use rand::RngExt;
+use serde_json::json;
+
+use crate::SERVICE_ADMIN_ROLE;
+use crate::domain::client_event::ClientEvent;
+use crate::domain::types::{HubId, WebhookId};
+use crate::domain::webhook::{NewWebhook, Webhook};
+use crate::forms::webhooks::AddWebhookPayload;
+use crate::repository::{WebhookReader, WebhookWriter};
+use crate::services::{ServiceError, ServiceResult, log_service_call};
+use crate::webhooks::{WebhookDelivery, WebhookSender};
+
+/// Lists the webhooks registered for the admin's hub.
+pub fn list_webhooks<R>(user: &AuthenticatedUser, repo: &R) -> ServiceResult<Vec<Webhook>>
+where
+    R: WebhookReader + ?Sized,
+{
+    log_service_call("list_webhooks", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    Ok(repo.list_webhooks(hub_id)?)
+}
+
+/// Registers a webhook with a freshly generated signing secret.
+pub fn add_webhook<R>(
+    payload: AddWebhookPayload,
+    user: &AuthenticatedUser,
+    repo: &R,
+) -> ServiceResult<Webhook>
+where
+    R: WebhookWriter + ?Sized,
+{
+    log_service_call("add_webhook", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let hub_id = HubId::new(user.hub_id)?;
+
+    let secret = hex::encode(rand::rng().random::<[u8; 32]>());
+    let new_webhook = NewWebhook::new(hub_id, payload.url, payload.event_type, secret);
+
+    Ok(repo.create_webhook(&new_webhook)?)
+}
+
+/// Removes a webhook of the admin's hub.
+pub fn delete_webhook<R>(webhook_id: i32, user: &AuthenticatedUser, repo: &R) -> ServiceResult<()>
+where
+    R: WebhookWriter + ?Sized,
+{
+    log_service_call("delete_webhook", user);
+    ensure_role(user, SERVICE_ADMIN_ROLE)?;
+
+    let webhook_id = WebhookId::new(webhook_id)?;
+    let hub_id = HubId::new(user.hub_id)?;
+
+    if !repo.delete_webhook(webhook_id, hub_id)? {
+        return Err(ServiceError::NotFound);
+    }
+
+    Ok(())
+}
+
+/// Queues a signed delivery of `event` to every matching hub webhook.
+///
+/// The event is already stored, so lookup failures are logged instead of
+/// failing the caller.
+pub fn notify_client_event<R, S>(hub_id: HubId, event: &ClientEvent, repo: &R, sender: &S)
+where
+    R: WebhookReader + ?Sized,
+    S: WebhookSender + ?Sized,
+{
+    notify_client_events(hub_id, std::slice::from_ref(event), repo, sender);
+}
+
+/// Batch form of [`notify_client_event`] that loads the hub webhooks once.
+pub fn notify_client_events<R, S>(hub_id: HubId, events: &[ClientEvent], repo: &R, sender: &S)
+where
+    R: WebhookReader + ?Sized,
+    S: WebhookSender + ?Sized,
+{
+    if events.is_empty() {
+        return;
+    }
+
+    let webhooks = match repo.list_webhooks(hub_id) {
+        Ok(webhooks) => webhooks,
+        Err(err) => {
+            log::error!("Failed to load webhooks for hub {hub_id}: {err}");
+            return;
+        }
+    };
+    if webhooks.is_empty() {
+        return;
+    }
+
+    for event in events {
+        let body = event_payload(hub_id, event);
+
+        for webhook in webhooks
+            .iter()
+            .filter(|webhook| webhook.matches(&event.event_type))
+        {
+            sender.enqueue(WebhookDelivery {
+                url: webhook.url.as_str().to_string(),
+                secret: webhook.secret.clone(),
+                body: body.clone(),
+            });
+        }
+    }
+}
+
+fn event_payload(hub_id: HubId, event: &ClientEvent) -> Vec<u8> {
+    json!({
+        "hub_id": hub_id.get(),
+        "event": {
+            "id": event.id.get(),
+            "client_id": event.client_id.get(),
+            "manager_id": event.manager_id.get(),
+            "event_type": event.event_type.to_string(),
+            "event_data": event.event_data,
+            "created_at": event.created_at.to_string(),
+        },
+    })
+    .to_string()
+    .into_bytes()
+}
+
+#[cfg(all(test, feature = "test-mocks"))]
+mod tests {
+    use std::cell::RefCell;
+
+    use chrono::Utc;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::domain::client_event::ClientEventType;
+    use crate::domain::types::WebhookUrl;
+    use crate::repository::mock::MockRepository;
+    use crate::webhooks::sign_payload;
+    use crate::{SERVICE_ACCESS_ROLE, SERVICE_ADMIN_ROLE};
+
+    #[derive(Default)]
+    struct RecordingSender {
+        deliveries: RefCell<Vec<WebhookDelivery>>,
+    }
+
+    impl WebhookSender for RecordingSender {
+        fn enqueue(&self, delivery: WebhookDelivery) {
+            self.deliveries.borrow_mut().push(delivery);
+        }
+    }
+
+    fn admin_user() -> AuthenticatedUser {
+        AuthenticatedUser {
+            sub: "1".to_string(),
+            email: "admin@example.com".to_string(),
+            hub_id: 5,
+            name: "Admin".to_string(),
+            roles: vec![
+                SERVICE_ACCESS_ROLE.to_string(),
+                SERVICE_ADMIN_ROLE.to_string(),
+            ],
+            exp: 0,
+        }
+    }
+
+    fn webhook(id: i32, url: &str, event_type: Option<&str>) -> Webhook {
+        Webhook::try_new(
+            id,
+            5,
+            url.to_string(),
+            event_type.map(str::to_string),
+            format!("secret-{id}"),
+            Utc::now().naive_utc(),
+        )
+        .expect("valid webhook")
+    }
+
+    fn comment_event() -> ClientEvent {
+        ClientEvent::try_new(
+            9,
+            3,
+            2,
+            ClientEventType::Comment,
+            serde_json::json!({"text": "hello"}),
+            Utc::now().naive_utc(),
+        )
+        .expect("valid event")
+    }
+
+    #[test]
+    fn add_webhook_requires_admin_role() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_webhook().times(0);
+        let mut user = admin_user();
+        user.roles = vec![SERVICE_ACCESS_ROLE.to_string()];
+
+        let payload = AddWebhookPayload {
+            url: WebhookUrl::new("https://hooks.example.com/crm").expect("valid url"),
+            event_type: None,
+        };
+
+        let result = add_webhook(payload, &user, &repo);
+
+        assert!(matches!(result, Err(ServiceError::Unauthorized)));
+    }
+
+    #[test]
+    fn add_webhook_generates_secret_for_hub() {
+        let mut repo = MockRepository::new();
+        repo.expect_create_webhook()
+            .withf(|new_webhook| {
+                new_webhook.hub_id.get() == 5
+                    && new_webhook.secret.len() == 64
+                    && new_webhook.event_type == Some(ClientEventType::Call)
+            })
+            .times(1)
+            .returning(|new_webhook| {
+                Ok(Webhook {
+                    id: WebhookId::new(1).expect("valid id"),
+                    hub_id: new_webhook.hub_id,
+                    url: new_webhook.url.clone(),
+                    event_type: new_webhook.event_type.clone(),
+                    secret: new_webhook.secret.clone(),
+                    created_at: Utc::now().naive_utc(),
+                })
+            });
+
+        let payload = AddWebhookPayload {
+            url: WebhookUrl::new("https://hooks.example.com/crm").expect("valid url"),
+            event_type: Some(ClientEventType::Call),
+        };
+
+        let webhook = add_webhook(payload, &admin_user(), &repo).expect("webhook created");
+
+        assert_eq!(webhook.url.as_str(), "https://hooks.example.com/crm");
+    }
+
+    #[test]
+    fn delete_webhook_reports_missing_webhook() {
+        let mut repo = MockRepository::new();
+        repo.expect_delete_webhook()
+            .times(1)
+            .returning(|_, _| Ok(false));
+
+        let result = delete_webhook(4, &admin_user(), &repo);
+
+        assert!(matches!(result, Err(ServiceError::NotFound)));
+    }
+
+    #[test]
+    fn notify_client_event_enqueues_signed_deliveries_for_matching_webhooks() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_webhooks().times(1).returning(|_| {
+            Ok(vec![
+                webhook(1, "https://a.example.com/hook", None),
+                webhook(2, "https://b.example.com/hook", Some("Call")),
+                webhook(3, "https://c.example.com/hook", Some("Comment")),
+            ])
+        });
+        let sender = RecordingSender::default();
+
+        notify_client_event(
+            HubId::new(5).expect("valid hub id"),
+            &comment_event(),
+            &repo,
+            &sender,
+        );
+
+        let deliveries = sender.deliveries.borrow();
+        let urls = deliveries
+            .iter()
+            .map(|delivery| delivery.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec!["https://a.example.com/hook", "https://c.example.com/hook"]
+        );
+
+        let body: Value = serde_json::from_slice(&deliveries[0].body).expect("json body");
+        assert_eq!(body["event"]["event_type"], "Comment");
+        assert_eq!(body["event"]["event_data"]["text"], "hello");
+        assert_eq!(
+            deliveries[1].signature(),
+            sign_payload("secret-3", &deliveries[1].body)
+        );
+    }
+
+    #[test]
+    fn notify_client_event_swallows_repository_errors() {
+        let mut repo = MockRepository::new();
+        repo.expect_list_webhooks()
+            .times(1)
+            .returning(|_| Err(pushkind_common::repository::errors::RepositoryError::NotFound));
+        let sender = RecordingSender::default();
+
+        notify_client_event(
+            HubId::new(5).expect("valid hub id"),
+            &comment_event(),
+            &repo,
+            &sender,
+        );
+
+        assert!(sender.deliveries.borrow().is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+//! Shared application state handed to the route handlers.
+
+use std::sync::Arc;
+
+use pushkind_common::models::config::CommonServerConfig;
+use pushkind_common::zmq::ZmqSender;
+
+use crate::models::config::{AppConfig, ServerConfig};
+use crate::repository::DieselRepository;
+use crate::webhooks::WebhookDispatcher;
+
+/// Services and configuration registered once as `web::Data<AppState>`.
+///
+/// Request-scoped guards such as rate limiters and idempotency tokens keep
+/// their own `web::Data` so their counters are not copied with the state.
+#[derive(Clone)]
+pub struct AppState {
+    pub repo: DieselRepository,
+    pub common_config: CommonServerConfig,
+    pub app_config: AppConfig,
+    pub server_config: ServerConfig,
+    /// Publisher of outbound email notifications.
+    pub zmq_sender: Arc<ZmqSender>,
+    /// Publisher of storefront OTP text messages.
+    pub sms_sender: Arc<ZmqSender>,
+    pub webhook_dispatcher: WebhookDispatcher,
+}
+
+impl AppState {
+    pub fn new(
+        repo: DieselRepository,
+        common_config: CommonServerConfig,
+        app_config: AppConfig,
+        server_config: ServerConfig,
+        zmq_sender: Arc<ZmqSender>,
+        sms_sender: Arc<ZmqSender>,
+        webhook_dispatcher: WebhookDispatcher,
+    ) -> Self {
+        Self {
+            repo,
+            common_config,
+            app_config,
+            server_config,
+            zmq_sender,
+            sms_sender,
+            webhook_dispatcher,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+
+    #[test]
+    fn app_state_can_be_shared_between_workers() {
+        assert_shareable::<AppState>();
+    }
+}
@@ -0,0 +1,156 @@
+//! Background delivery of signed webhook requests.
+
+use std::sync::Arc;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::outbound::{PublicAddrResolver, is_public_url};
+
+/// Header carrying the `sha256=<hex>` HMAC signature of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Crm-Signature";
+
+/// Timeout applied to each delivery request.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deliveries waiting for the worker; further ones are dropped while it is full.
+const DELIVERY_QUEUE_CAPACITY: usize = 1024;
+
+/// Single HTTP POST queued for delivery.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    pub url: String,
+    pub secret: String,
+    /// JSON request body.
+    pub body: Vec<u8>,
+}
+
+impl WebhookDelivery {
+    /// Returns the signature header value for the delivery body.
+    #[must_use]
+    pub fn signature(&self) -> Option<String> {
+        sign_payload(&self.secret, &self.body)
+    }
+}
+
+/// Signs `body` with HMAC-SHA256 keyed by `secret`, formatted as `sha256=<hex>`.
+#[must_use]
+pub fn sign_payload(secret: &str, body: &[u8]) -> Option<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(body);
+    Some(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+/// Queues webhook deliveries without waiting for them to complete.
+pub trait WebhookSender {
+    fn enqueue(&self, delivery: WebhookDelivery);
+}
+
+/// Delivers queued webhooks from a dedicated worker thread.
+///
+/// Failed deliveries are logged and dropped; there is no retry. Redirects are
+/// not followed and hosts resolving to non-public addresses are refused, so a
+/// registered URL cannot be turned against internal services.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    sender: SyncSender<WebhookDelivery>,
+}
+
+impl WebhookDispatcher {
+    /// Spawns the delivery worker thread.
+    pub fn start() -> std::io::Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel::<WebhookDelivery>(DELIVERY_QUEUE_CAPACITY);
+
+        thread::Builder::new()
+            .name("webhook-dispatcher".to_string())
+            .spawn(move || {
+                let client = match reqwest::blocking::Client::builder()
+                    .timeout(DELIVERY_TIMEOUT)
+                    .redirect(reqwest::redirect::Policy::none())
+                    .dns_resolver(Arc::new(PublicAddrResolver))
+                    .build()
+                {
+                    Ok(client) => client,
+                    Err(err) => {
+                        log::error!("Failed to build webhook HTTP client: {err}");
+                        return;
+                    }
+                };
+
+                for delivery in receiver {
+                    deliver(&client, &delivery);
+                }
+            })?;
+
+        Ok(Self { sender })
+    }
+}
+
+impl WebhookSender for WebhookDispatcher {
+    fn enqueue(&self, delivery: WebhookDelivery) {
+        match self.sender.try_send(delivery) {
+            Ok(()) => {}
+            Err(TrySendError::Full(delivery)) => {
+                log::warn!(
+                    "Webhook queue is full, dropping delivery to {}",
+                    delivery.url
+                );
+            }
+            Err(TrySendError::Disconnected(delivery)) => {
+                log::error!("Failed to queue webhook delivery to {}", delivery.url);
+            }
+        }
+    }
+}
+
+fn deliver(client: &reqwest::blocking::Client, delivery: &WebhookDelivery) {
+    if !is_public_url(&delivery.url) {
+        log::warn!(
+            "Refusing webhook delivery to non-public address {}",
+            delivery.url
+        );
+        return;
+    }
+
+    let Some(signature) = delivery.signature() else {
+        log::error!("Failed to sign webhook delivery to {}", delivery.url);
+        return;
+    };
+
+    let result = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .body(delivery.body.clone())
+        .send();
+
+    match result {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => log::warn!(
+            "Webhook delivery to {} failed with status {}",
+            delivery.url,
+            response.status()
+        ),
+        Err(err) => log::warn!("Webhook delivery to {} failed: {err}", delivery.url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_payload_matches_known_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?").as_deref(),
+            Some("sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+}
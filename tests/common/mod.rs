@@ -14,8 +14,10 @@ use actix_session::{SessionMiddleware, storage::CookieSessionStore};
 use actix_web::cookie::Key;
 use actix_web::rt::time::sleep;
 use actix_web::{
-    App, HttpMessage, HttpRequest, HttpResponse, HttpServer, Responder, get, middleware, post, web,
+    App, HttpMessage, HttpRequest, HttpResponse, HttpServer, Responder, middleware, post, web,
 };
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use pushkind_common::db::{DbPool, establish_connection_pool};
 use pushkind_common::domain::auth::AuthenticatedUser;
@@ -25,23 +27,71 @@ use pushkind_common::routes::logout;
 use pushkind_common::zmq::{ZmqSender, ZmqSenderOptions};
 use reqwest::{Client, StatusCode, redirect::Policy};
 use tempfile::NamedTempFile;
-
-use pushkind_crm::models::config::AppConfig;
+use tracing_actix_web::TracingLogger;
+use tracing_subscriber::EnvFilter;
+
+use pushkind_crm::domain::types::{ClientEventId, ClientId, Locale};
+use pushkind_crm::models::config::{
+    AppConfig, DEFAULT_BATCH_CHUNK_SIZE, DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE,
+    DEFAULT_DB_BUSY_TIMEOUT_MS, DEFAULT_DB_POOL_SIZE, DEFAULT_DEDUP_WINDOW_HOURS,
+    DEFAULT_MAINTENANCE_INTERVAL_HOURS, FeaturesConfig, ServerConfig, WorkerConfig,
+};
 use pushkind_crm::repository::DieselRepository;
 use pushkind_crm::routes::api::{
-    api_v1_client_details, api_v1_client_directory, api_v1_clients, api_v1_iam,
-    api_v1_important_fields, api_v1_manager_modal, api_v1_managers, api_v1_no_access,
+    api_v1_api_keys, api_v1_client_audit, api_v1_client_details, api_v1_client_directory,
+    api_v1_client_events, api_v1_client_fields, api_v1_client_managers, api_v1_client_notes,
+    api_v1_clients, api_v1_clients_batch, api_v1_clients_export, api_v1_dashboard, api_v1_features,
+    api_v1_iam, api_v1_import_status, api_v1_important_fields, api_v1_manager_modal,
+    api_v1_managers, api_v1_no_access, api_v1_patch_client_fields, api_v1_patch_client_status,
+    api_v1_recent_clients, api_v1_segment_clients, api_v1_segments, api_v1_settings,
+    api_v1_webhooks,
+};
+use pushkind_crm::routes::api_key::api_key_auth;
+use pushkind_crm::routes::api_keys::{add_api_key, delete_api_key};
+use pushkind_crm::routes::aux::{MetricsState, health, metrics, not_assigned};
+use pushkind_crm::routes::client::{
+    attachment_client, comment_client, export_client_events, note_client, pin_client_note,
+    save_client, show_client,
+};
+use pushkind_crm::routes::idempotency::SeenTokens;
+use pushkind_crm::routes::main::{
+    add_client, clients_export_xlsx, clients_upload, clients_upload_template, show_dashboard,
+    show_index,
+};
+use pushkind_crm::routes::managers::{
+    add_manager, add_manager_rule, assign_manager, delete_manager, delete_manager_rule,
+    manager_rules, managers, reassign_manager_clients, set_manager_active, update_profile,
+    upload_manager_assignments,
+};
+use pushkind_crm::routes::middleware::CspMiddleware;
+use pushkind_crm::routes::rate_limit::CommentRateLimiter;
+use pushkind_crm::routes::segments::{add_segment, delete_segment, update_segment};
+use pushkind_crm::routes::settings::{
+    cleanup_clients, cleanup_events, delete_important_field, save_hub_settings,
+    save_important_fields, show_settings,
 };
-use pushkind_crm::routes::aux::not_assigned;
-use pushkind_crm::routes::client::{attachment_client, comment_client, save_client, show_client};
-use pushkind_crm::routes::main::{add_client, clients_upload, show_index};
-use pushkind_crm::routes::managers::{add_manager, assign_manager, managers};
-use pushkind_crm::routes::settings::{cleanup_clients, save_important_fields, show_settings};
+use pushkind_crm::routes::webhooks::{add_webhook, delete_webhook};
+use pushkind_crm::services::main::ImportProgress;
+use pushkind_crm::state::AppState;
+use pushkind_crm::webhooks::WebhookDispatcher;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!(); // assumes migrations/ exists
 pub const HUB_ID: i32 = 7;
 
 static FRONTEND_ASSETS: Once = Once::new();
+static TRACING: Once = Once::new();
+
+/// Installs a test-friendly tracing subscriber so output includes span info.
+pub fn init_tracing() {
+    TRACING.call_once(|| {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+            )
+            .with_test_writer()
+            .try_init();
+    });
+}
 
 /// Temporary database used in integration tests.
 pub struct TestDb {
@@ -56,6 +106,8 @@ pub struct TestApp {
 
 impl TestDb {
     pub fn new() -> Self {
+        init_tracing();
+
         let tempfile = NamedTempFile::new().expect("Failed to create temp file");
         let pool = establish_connection_pool(tempfile.path().to_str().unwrap())
             .expect("Failed to establish SQLite connection.");
@@ -77,6 +129,47 @@ impl TestDb {
     pub fn get_db_path(&self) -> String {
         self._tempfile.path().to_str().unwrap().to_string()
     }
+
+    /// Overrides the timestamps the repository stamps on a client when it is written.
+    pub fn set_client_timestamps(
+        &self,
+        client_id: ClientId,
+        created_at: NaiveDateTime,
+        updated_at: NaiveDateTime,
+    ) {
+        use pushkind_crm::schema::clients;
+
+        let mut conn = self.pool.get().expect("Failed to get SQLite connection.");
+        diesel::update(clients::table.find(client_id.get()))
+            .set((
+                clients::created_at.eq(created_at),
+                clients::updated_at.eq(updated_at),
+            ))
+            .execute(&mut conn)
+            .expect("Failed to update client timestamps.");
+    }
+
+    /// Overrides when the client was last edited by a manager.
+    pub fn set_client_last_edited_at(&self, client_id: ClientId, last_edited_at: NaiveDateTime) {
+        use pushkind_crm::schema::clients;
+
+        let mut conn = self.pool.get().expect("Failed to get SQLite connection.");
+        diesel::update(clients::table.find(client_id.get()))
+            .set(clients::last_edited_at.eq(last_edited_at))
+            .execute(&mut conn)
+            .expect("Failed to update client edit time.");
+    }
+
+    /// Overrides the creation time the database stamps on a client event.
+    pub fn set_event_created_at(&self, event_id: ClientEventId, created_at: NaiveDateTime) {
+        use pushkind_crm::schema::client_events;
+
+        let mut conn = self.pool.get().expect("Failed to get SQLite connection.");
+        diesel::update(client_events::table.find(event_id.get()))
+            .set(client_events::created_at.eq(created_at))
+            .execute(&mut conn)
+            .expect("Failed to update event creation time.");
+    }
 }
 
 impl TestApp {
@@ -125,11 +218,6 @@ async fn test_login(
     HttpResponse::Ok().finish()
 }
 
-#[get("/health")]
-async fn test_health() -> impl Responder {
-    HttpResponse::Ok().finish()
-}
-
 async fn wait_until_server_is_ready(address: &str) {
     let client = Client::builder()
         .redirect(Policy::none())
@@ -167,6 +255,10 @@ fn ensure_test_frontend_assets() {
                 "assets/dist/app/settings.html",
                 "<!doctype html><html><head><title>CRM Settings</title></head><body>crm-settings</body></html>",
             ),
+            (
+                "assets/dist/app/hub-dashboard.html",
+                "<!doctype html><html><head><title>CRM Dashboard</title></head><body>crm-hub-dashboard</body></html>",
+            ),
             (
                 "assets/dist/app/no-access.html",
                 "<!doctype html><html><head><title>CRM No Access</title></head><body>crm-no-access</body></html>",
@@ -188,7 +280,63 @@ fn ensure_test_frontend_assets() {
     });
 }
 
+/// Server settings of the test app: every tunable at its default.
+pub fn test_server_config(port: u16, features: FeaturesConfig) -> ServerConfig {
+    ServerConfig {
+        address: "127.0.0.1".to_string(),
+        port,
+        features,
+        dedup_window_hours: DEFAULT_DEDUP_WINDOW_HOURS,
+        comment_rate_limit_per_minute: DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE,
+        db_pool_size: DEFAULT_DB_POOL_SIZE,
+        db_busy_timeout_ms: DEFAULT_DB_BUSY_TIMEOUT_MS,
+        maintenance_interval_hours: DEFAULT_MAINTENANCE_INTERVAL_HOURS,
+        batch_chunk_size: DEFAULT_BATCH_CHUNK_SIZE,
+        csp_policy: None,
+        worker: WorkerConfig::default(),
+        locale: Locale::default(),
+        country: None,
+    }
+}
+
+/// Builds the shared handler state around `repo`, publishing to local ZeroMQ
+/// endpoints nothing listens on.
+pub fn app_state(
+    repo: DieselRepository,
+    app_config: AppConfig,
+    server_config: ServerConfig,
+) -> web::Data<AppState> {
+    let common_config = CommonServerConfig {
+        auth_service_url: app_config.auth_service_url.clone(),
+        secret: app_config.secret.clone(),
+    };
+    let zmq_sender = Arc::new(
+        ZmqSender::start(ZmqSenderOptions::pub_default("tcp://127.0.0.1:35559"))
+            .expect("Failed to start test ZMQ sender."),
+    );
+    let sms_sender = Arc::new(
+        ZmqSender::start(ZmqSenderOptions::pub_default(&app_config.zmq_sms_pub))
+            .expect("Failed to start test ZMQ SMS sender."),
+    );
+    let webhook_dispatcher =
+        WebhookDispatcher::start().expect("Failed to start test webhook dispatcher.");
+
+    web::Data::new(AppState::new(
+        repo,
+        common_config,
+        app_config,
+        server_config,
+        zmq_sender,
+        sms_sender,
+        webhook_dispatcher,
+    ))
+}
+
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with_features(FeaturesConfig::default()).await
+}
+
+pub async fn spawn_app_with_features(features: FeaturesConfig) -> TestApp {
     ensure_test_frontend_assets();
 
     let test_db = TestDb::new();
@@ -207,22 +355,31 @@ pub async fn spawn_app() -> TestApp {
         zmq_clients_sub: "tcp://127.0.0.1:35566".to_string(),
         zmq_replier_sub: "tcp://127.0.0.1:35560".to_string(),
         zmq_tasks_sub: "tcp://127.0.0.1:35564".to_string(),
+        zmq_tracker_sub: "tcp://127.0.0.1:35567".to_string(),
         sms_sender: "crm-test".to_string(),
         secret: "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef".to_string(),
         auth_service_url: "https://users.pushkind.test/auth/signin".to_string(),
         todo_service_url: "https://todo.pushkind.test".to_string(),
         files_service_url: "https://files.pushkind.test".to_string(),
     };
+    let secret_key = Key::from(app_config.secret.as_bytes());
     let common_config = CommonServerConfig {
         auth_service_url: app_config.auth_service_url.clone(),
         secret: app_config.secret.clone(),
     };
-    let secret_key = Key::from(app_config.secret.as_bytes());
-    let repo = DieselRepository::new(test_db.pool());
-    let zmq_sender = Arc::new(
-        ZmqSender::start(ZmqSenderOptions::pub_default("tcp://127.0.0.1:35559"))
-            .expect("Failed to start test ZMQ sender."),
+    let server_config = test_server_config(port, features);
+    let csp =
+        CspMiddleware::new(&server_config.content_security_policy(&app_config.auth_service_url))
+            .expect("Default CSP policy is a valid header value.");
+    let state = app_state(
+        DieselRepository::new(test_db.pool()),
+        app_config,
+        server_config,
     );
+    let comment_rate_limiter = web::Data::new(CommentRateLimiter::default());
+    let metrics_state = web::Data::new(MetricsState::default());
+    let seen_tokens = web::Data::new(SeenTokens::new());
+    let import_progress = web::Data::new(ImportProgress::new());
 
     let server = HttpServer::new(move || {
         App::new()
@@ -233,45 +390,93 @@ pub async fn spawn_app() -> TestApp {
                     .cookie_secure(false)
                     .build(),
             )
+            .wrap(csp.clone())
             .wrap(middleware::Compress::default())
             .wrap(middleware::Logger::default())
+            .wrap(TracingLogger::default())
             .service(actix_files::Files::new("/assets", "./assets"))
             .service(test_login)
-            .service(test_health)
             .service(not_assigned)
+            .service(metrics)
+            .service(health)
             .service(
                 web::scope("/api")
+                    .wrap(middleware::from_fn(api_key_auth))
                     .service(api_v1_iam)
+                    .service(api_v1_features)
                     .service(api_v1_clients)
                     .service(api_v1_client_directory)
+                    .service(api_v1_clients_export)
+                    .service(api_v1_recent_clients)
                     .service(api_v1_client_details)
+                    .service(api_v1_client_events)
+                    .service(api_v1_client_notes)
+                    .service(api_v1_client_audit)
+                    .service(api_v1_client_fields)
+                    .service(api_v1_client_managers)
+                    .service(api_v1_patch_client_fields)
+                    .service(api_v1_patch_client_status)
+                    .service(api_v1_clients_batch)
+                    .service(api_v1_webhooks)
+                    .service(api_v1_api_keys)
+                    .service(api_v1_segments)
+                    .service(api_v1_segment_clients)
                     .service(api_v1_managers)
                     .service(api_v1_manager_modal)
+                    .service(api_v1_dashboard)
                     .service(api_v1_no_access)
-                    .service(api_v1_important_fields),
+                    .service(api_v1_important_fields)
+                    .service(api_v1_settings)
+                    .service(api_v1_import_status),
             )
             .service(add_client)
             .service(clients_upload)
+            .service(clients_upload_template)
+            .service(clients_export_xlsx)
             .service(save_client)
             .service(comment_client)
             .service(attachment_client)
+            .service(note_client)
+            .service(pin_client_note)
+            .service(export_client_events)
             .service(save_important_fields)
+            .service(save_hub_settings)
             .service(cleanup_clients)
+            .service(cleanup_events)
+            .service(delete_important_field)
             .service(add_manager)
             .service(assign_manager)
+            .service(upload_manager_assignments)
+            .service(reassign_manager_clients)
+            .service(set_manager_active)
+            .service(delete_manager)
+            .service(manager_rules)
+            .service(add_manager_rule)
+            .service(delete_manager_rule)
+            .service(update_profile)
+            .service(add_webhook)
+            .service(delete_webhook)
+            .service(add_api_key)
+            .service(delete_api_key)
+            .service(add_segment)
+            .service(update_segment)
+            .service(delete_segment)
             .service(
                 web::scope("")
                     .wrap(RedirectUnauthorized)
                     .service(show_index)
                     .service(show_client)
+                    .service(show_dashboard)
                     .service(show_settings)
                     .service(managers)
                     .service(logout),
             )
-            .app_data(web::Data::new(repo.clone()))
+            .app_data(state.clone())
             .app_data(web::Data::new(common_config.clone()))
-            .app_data(web::Data::new(zmq_sender.clone()))
-            .app_data(web::Data::new(app_config.clone()))
+            .app_data(comment_rate_limiter.clone())
+            .app_data(metrics_state.clone())
+            .app_data(seen_tokens.clone())
+            .app_data(import_progress.clone())
     })
     .listen(listener)
     .expect("Failed to listen with the test server.")
@@ -0,0 +1,576 @@
+//! Contract tests for [`ClientReader`] implementations.
+//!
+//! [`run_client_reader_contract`] asserts the postconditions every reader must
+//! honour against a fixed data set, and runs against both the Diesel
+//! repository and a hand-written in-memory stub so the two stay in agreement.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::RepositoryResult;
+
+use pushkind_crm::domain::client::{Client, ClientStatus, FieldMeta, NewClient, UpdateClient};
+use pushkind_crm::domain::manager::{Manager, NewManager};
+use pushkind_crm::domain::types::{
+    ClientEmail, ClientId, ClientName, HubId, ManagerEmail, ManagerId, PhoneNumber, PublicId,
+};
+use pushkind_crm::repository::{
+    ClientListQuery, ClientReader, ClientWriter, DieselRepository, ManagerWriter,
+};
+
+mod common;
+
+const ALICE_EMAIL: &str = "alice@example.com";
+const ALICE_PHONE: &str = "+14155552671";
+const CAROL_PHONE: &str = "+14155552672";
+const MANAGER_EMAIL: &str = "manager@example.com";
+
+fn hub(id: i32) -> HubId {
+    HubId::new(id).expect("valid hub id")
+}
+
+fn fields(pairs: &[(&str, &str)]) -> Option<BTreeMap<String, String>> {
+    (!pairs.is_empty()).then(|| {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    })
+}
+
+/// The clients every implementation is seeded with, as
+/// `(hub, name, email, phone, fields)`.
+///
+/// Hub 1 holds Alice, Bob and Carol in that order; hub 2 holds another Alice
+/// sharing the hub 1 Alice's email. The manager is assigned to and last edited
+/// the hub 1 Alice.
+type Seed = (
+    i32,
+    &'static str,
+    Option<&'static str>,
+    Option<&'static str>,
+    &'static [(&'static str, &'static str)],
+);
+
+const SEED: [Seed; 4] = [
+    (
+        1,
+        "Alice",
+        Some(ALICE_EMAIL),
+        Some(ALICE_PHONE),
+        &[("Tier", "Gold")],
+    ),
+    (
+        1,
+        "Bob",
+        Some("bob@example.com"),
+        None,
+        &[("Tier", "Silver"), ("City", "Oslo")],
+    ),
+    (1, "Carol", None, Some(CAROL_PHONE), &[]),
+    (2, "Alice", Some(ALICE_EMAIL), None, &[]),
+];
+
+fn names(clients: &[Client]) -> Vec<&str> {
+    clients.iter().map(|client| client.name.as_str()).collect()
+}
+
+/// Exercises every [`ClientReader`] method against the [`SEED`] data set.
+fn run_client_reader_contract<R: ClientReader>(repo: R) {
+    let hub_one = hub(1);
+    let hub_two = hub(2);
+    let missing_id = ClientId::new(999_999).expect("valid client id");
+
+    // `list_clients` returns the hub's clients by id with the full total.
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_one)).unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(names(&clients), vec!["Alice", "Bob", "Carol"]);
+    assert!(clients.iter().all(|client| client.hub_id == hub_one));
+    let (alice, bob, carol) = (&clients[0], &clients[1], &clients[2]);
+
+    // Pagination keeps the total and slices the page.
+    let (total, page) = repo
+        .list_clients(ClientListQuery::new(hub_one).paginate(1, 2))
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(names(&page), vec!["Alice", "Bob"]);
+    let (total, page) = repo
+        .list_clients(ClientListQuery::new(hub_one).paginate(2, 2))
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(names(&page), vec!["Carol"]);
+    let (total, page) = repo
+        .list_clients(ClientListQuery::new(hub_one).paginate(3, 2))
+        .unwrap();
+    assert_eq!(total, 3);
+    assert!(page.is_empty());
+
+    let (total, others) = repo.list_clients(ClientListQuery::new(hub_two)).unwrap();
+    assert_eq!(total, 1);
+    let other_alice = &others[0];
+    assert_eq!(other_alice.hub_id, hub_two);
+
+    // Lookups by id are scoped to the hub and return `None` when nothing matches.
+    assert_eq!(
+        repo.get_client_by_id(alice.id, hub_one).unwrap().as_ref(),
+        Some(alice)
+    );
+    assert!(
+        repo.get_client_by_id(missing_id, hub_one)
+            .unwrap()
+            .is_none()
+    );
+    assert!(repo.get_client_by_id(alice.id, hub_two).unwrap().is_none());
+
+    let public_id = alice.public_id.expect("seeded clients have a public id");
+    assert_eq!(
+        repo.get_client_by_public_id(public_id, hub_one)
+            .unwrap()
+            .map(|client| client.id),
+        Some(alice.id)
+    );
+    assert!(
+        repo.get_client_by_public_id(public_id, hub_two)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.get_client_by_public_id(PublicId::new(), hub_one)
+            .unwrap()
+            .is_none()
+    );
+
+    // Batch lookups drop unknown ids and other hubs' clients, keeping id order.
+    let batch = repo
+        .get_clients_by_ids(&[carol.id, missing_id, other_alice.id, alice.id], hub_one)
+        .unwrap();
+    assert_eq!(names(&batch), vec!["Alice", "Carol"]);
+    assert!(repo.get_clients_by_ids(&[], hub_one).unwrap().is_empty());
+
+    // Contact lookups pick the client of the requested hub.
+    let alice_email = ClientEmail::new(ALICE_EMAIL).expect("valid email");
+    assert_eq!(
+        repo.get_client_by_email(&alice_email, hub_one)
+            .unwrap()
+            .map(|client| client.id),
+        Some(alice.id)
+    );
+    assert_eq!(
+        repo.get_client_by_email(&alice_email, hub_two)
+            .unwrap()
+            .map(|client| client.id),
+        Some(other_alice.id)
+    );
+    let unknown_email = ClientEmail::new("nobody@example.com").expect("valid email");
+    assert!(
+        repo.get_client_by_email(&unknown_email, hub_one)
+            .unwrap()
+            .is_none()
+    );
+
+    let carol_phone = PhoneNumber::new(CAROL_PHONE).expect("valid phone");
+    assert_eq!(
+        repo.get_client_by_phone(&carol_phone, hub_one)
+            .unwrap()
+            .map(|client| client.id),
+        Some(carol.id)
+    );
+    assert!(
+        repo.get_client_by_phone(&carol_phone, hub_two)
+            .unwrap()
+            .is_none()
+    );
+
+    // Name lookups: counting is exact, matching ignores case and spacing.
+    let alice_name = ClientName::new("Alice").expect("valid name");
+    assert_eq!(
+        repo.count_clients_by_name(hub_one, &alice_name, None)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        repo.count_clients_by_name(hub_one, &alice_name, Some(alice.id))
+            .unwrap(),
+        0
+    );
+    let shouted = ClientName::new("  ALICE ").expect("valid name");
+    assert_eq!(
+        repo.count_clients_by_name(hub_one, &shouted, None).unwrap(),
+        0
+    );
+    let by_key = repo.client_ids_by_name_key(hub_one).unwrap();
+    assert_eq!(by_key.get(&shouted.match_key()), Some(&vec![alice.id]));
+    let nobody = ClientName::new("Nobody").expect("valid name");
+    assert_eq!(by_key.get(&nobody.match_key()), None);
+
+    // Manager assignments.
+    let (total, managers) = repo.list_managers_for_client(alice.id, None).unwrap();
+    assert_eq!(total, 1);
+    let manager = &managers[0];
+    assert_eq!(manager.email.as_str(), MANAGER_EMAIL);
+    assert_eq!(
+        repo.list_all_managers_for_client(alice.id).unwrap(),
+        managers
+    );
+    let (total, page) = repo
+        .list_managers_for_client(
+            alice.id,
+            Some(Pagination {
+                page: 2,
+                per_page: 1,
+            }),
+        )
+        .unwrap();
+    assert_eq!(total, 1);
+    assert!(page.is_empty());
+    assert_eq!(
+        repo.list_managers_for_client(bob.id, None).unwrap(),
+        (0, vec![])
+    );
+
+    assert!(
+        repo.check_client_assigned_to_manager(alice.id, &manager.email)
+            .unwrap()
+    );
+    assert!(
+        !repo
+            .check_client_assigned_to_manager(bob.id, &manager.email)
+            .unwrap()
+    );
+    let stranger = ManagerEmail::new("stranger@example.com").expect("valid email");
+    assert!(
+        !repo
+            .check_client_assigned_to_manager(alice.id, &stranger)
+            .unwrap()
+    );
+
+    // Edits are attributed to the manager within the hub only.
+    let edited = repo
+        .list_clients_edited_by(manager.id, hub_one, 10)
+        .unwrap();
+    assert_eq!(
+        edited.iter().map(|client| client.id).collect::<Vec<_>>(),
+        vec![alice.id]
+    );
+    assert!(
+        repo.list_clients_edited_by(manager.id, hub_one, 0)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        repo.list_clients_edited_by(manager.id, hub_two, 10)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Recent clients come newest first and honour the manager filter.
+    let recent = repo.list_recent_clients(hub_one, None, 2).unwrap();
+    assert_eq!(names(&recent), vec!["Carol", "Bob"]);
+    let recent = repo
+        .list_recent_clients(hub_one, Some(manager.email.clone()), 10)
+        .unwrap();
+    assert_eq!(
+        recent.iter().map(|client| client.id).collect::<Vec<_>>(),
+        vec![alice.id]
+    );
+    assert!(
+        repo.list_recent_clients(hub_two, Some(manager.email.clone()), 10)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Field metadata counts the hub's clients per field, by name.
+    assert_eq!(
+        repo.list_available_fields(hub_one).unwrap(),
+        vec![
+            FieldMeta {
+                name: "City".to_string(),
+                value_count: 1,
+                is_important: false,
+            },
+            FieldMeta {
+                name: "Tier".to_string(),
+                value_count: 2,
+                is_important: false,
+            },
+        ]
+    );
+    assert!(repo.list_available_fields(hub_two).unwrap().is_empty());
+}
+
+/// In-memory [`ClientReader`] over a fixed set of clients.
+///
+/// Supports only the default [`ClientListQuery`] with optional pagination.
+struct StubClientReader {
+    clients: Vec<Client>,
+    /// `(client, manager)` assignment pairs.
+    assignments: Vec<(ClientId, Manager)>,
+    /// `(client, editor)` pairs, most recent edit last.
+    edits: Vec<(ClientId, ManagerId)>,
+}
+
+impl StubClientReader {
+    fn seeded() -> Self {
+        let timestamp = NaiveDate::from_ymd_opt(2026, 1, 1)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .expect("valid timestamp");
+        let clients: Vec<Client> = SEED
+            .iter()
+            .zip(1..)
+            .map(|(&(hub_id, name, email, phone, pairs), id)| {
+                Client::try_new(
+                    id,
+                    Some(PublicId::new().as_bytes()),
+                    hub_id,
+                    name.to_string(),
+                    email.map(str::to_string),
+                    phone.map(str::to_string),
+                    timestamp,
+                    timestamp,
+                    fields(pairs),
+                )
+                .expect("valid client")
+            })
+            .collect();
+        let manager =
+            Manager::try_new(1, 1, "Manager".to_string(), MANAGER_EMAIL.to_string(), true)
+                .expect("valid manager");
+        let alice = clients[0].id;
+
+        Self {
+            clients,
+            edits: vec![(alice, manager.id)],
+            assignments: vec![(alice, manager)],
+        }
+    }
+
+    fn hub_clients(&self, hub_id: HubId) -> impl Iterator<Item = &Client> {
+        self.clients
+            .iter()
+            .filter(move |client| client.hub_id == hub_id)
+    }
+
+    fn find(&self, hub_id: HubId, predicate: impl Fn(&Client) -> bool) -> Option<Client> {
+        self.hub_clients(hub_id)
+            .find(|client| predicate(client))
+            .cloned()
+    }
+}
+
+fn paginate<T>(items: Vec<T>, pagination: Option<Pagination>) -> (usize, Vec<T>) {
+    let total = items.len();
+    let page = match pagination {
+        Some(Pagination { page, per_page }) => items
+            .into_iter()
+            .skip(page.saturating_sub(1) * per_page)
+            .take(per_page)
+            .collect(),
+        None => items,
+    };
+    (total, page)
+}
+
+impl ClientReader for StubClientReader {
+    fn get_client_by_public_id(
+        &self,
+        public_id: PublicId,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Client>> {
+        Ok(self.find(hub_id, |client| client.public_id == Some(public_id)))
+    }
+
+    fn get_client_by_id(&self, id: ClientId, hub_id: HubId) -> RepositoryResult<Option<Client>> {
+        Ok(self.find(hub_id, |client| client.id == id))
+    }
+
+    fn get_clients_by_ids(&self, ids: &[ClientId], hub_id: HubId) -> RepositoryResult<Vec<Client>> {
+        Ok(self
+            .hub_clients(hub_id)
+            .filter(|client| ids.contains(&client.id))
+            .cloned()
+            .collect())
+    }
+
+    fn get_client_by_email(
+        &self,
+        email: &ClientEmail,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Client>> {
+        Ok(self.find(hub_id, |client| client.email.as_ref() == Some(email)))
+    }
+
+    fn get_client_by_phone(
+        &self,
+        phone: &PhoneNumber,
+        hub_id: HubId,
+    ) -> RepositoryResult<Option<Client>> {
+        Ok(self.find(hub_id, |client| client.phone.as_ref() == Some(phone)))
+    }
+
+    fn list_clients(&self, query: ClientListQuery) -> RepositoryResult<(usize, Vec<Client>)> {
+        let clients = self.hub_clients(query.hub_id).cloned().collect();
+        Ok(paginate(clients, query.pagination))
+    }
+
+    fn list_clients_edited_by(
+        &self,
+        manager_id: ManagerId,
+        hub_id: HubId,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>> {
+        Ok(self
+            .edits
+            .iter()
+            .rev()
+            .filter(|(_, editor)| *editor == manager_id)
+            .filter_map(|(client_id, _)| self.find(hub_id, |client| client.id == *client_id))
+            .take(limit)
+            .collect())
+    }
+
+    fn list_recent_clients(
+        &self,
+        hub_id: HubId,
+        manager_email: Option<ManagerEmail>,
+        limit: usize,
+    ) -> RepositoryResult<Vec<Client>> {
+        let mut clients: Vec<Client> = self
+            .hub_clients(hub_id)
+            .filter(|client| client.status != ClientStatus::Archived)
+            .filter(|client| {
+                manager_email.as_ref().is_none_or(|email| {
+                    self.assignments.iter().any(|(assigned, manager)| {
+                        *assigned == client.id && manager.email == *email
+                    })
+                })
+            })
+            .cloned()
+            .collect();
+        clients.sort_by(|a, b| (b.created_at, b.id).cmp(&(a.created_at, a.id)));
+        clients.truncate(limit);
+        Ok(clients)
+    }
+
+    fn list_managers_for_client(
+        &self,
+        client_id: ClientId,
+        pagination: Option<Pagination>,
+    ) -> RepositoryResult<(usize, Vec<Manager>)> {
+        let managers = self
+            .assignments
+            .iter()
+            .filter(|(assigned, _)| *assigned == client_id)
+            .map(|(_, manager)| manager.clone())
+            .collect();
+        Ok(paginate(managers, pagination))
+    }
+
+    fn count_clients_by_name(
+        &self,
+        hub_id: HubId,
+        name: &ClientName,
+        except: Option<ClientId>,
+    ) -> RepositoryResult<usize> {
+        Ok(self
+            .hub_clients(hub_id)
+            .filter(|client| client.name == *name && Some(client.id) != except)
+            .count())
+    }
+
+    fn client_ids_by_name_key(
+        &self,
+        hub_id: HubId,
+    ) -> RepositoryResult<HashMap<String, Vec<ClientId>>> {
+        let mut by_key: HashMap<String, Vec<ClientId>> = HashMap::new();
+        for client in self.hub_clients(hub_id) {
+            by_key
+                .entry(client.name.match_key())
+                .or_default()
+                .push(client.id);
+        }
+        Ok(by_key)
+    }
+
+    fn check_client_assigned_to_manager(
+        &self,
+        client_id: ClientId,
+        manager_email: &ManagerEmail,
+    ) -> RepositoryResult<bool> {
+        Ok(self
+            .assignments
+            .iter()
+            .any(|(assigned, manager)| *assigned == client_id && manager.email == *manager_email))
+    }
+
+    fn list_available_fields(&self, hub_id: HubId) -> RepositoryResult<Vec<FieldMeta>> {
+        let mut counts = BTreeMap::<String, usize>::new();
+        for client in self.hub_clients(hub_id) {
+            for name in client.fields.iter().flat_map(BTreeMap::keys) {
+                *counts.entry(name.clone()).or_default() += 1;
+            }
+        }
+        Ok(counts
+            .into_iter()
+            .map(|(name, value_count)| FieldMeta {
+                name,
+                value_count,
+                is_important: false,
+            })
+            .collect())
+    }
+}
+
+/// Writes [`SEED`] through the Diesel repository.
+fn seed_diesel(repo: &DieselRepository) {
+    let new_clients: Vec<NewClient> = SEED
+        .iter()
+        .map(|&(hub_id, name, email, phone, pairs)| {
+            NewClient::new(
+                hub(hub_id),
+                ClientName::new(name).expect("valid name"),
+                email.map(|value| ClientEmail::new(value).expect("valid email")),
+                phone.map(|value| PhoneNumber::new(value).expect("valid phone")),
+                fields(pairs),
+            )
+        })
+        .collect();
+    repo.create_or_replace_clients(&new_clients).unwrap();
+
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), MANAGER_EMAIL.to_string(), true)
+                .expect("valid manager"),
+        )
+        .unwrap();
+    let alice = repo
+        .get_client_by_email(&ClientEmail::new(ALICE_EMAIL).unwrap(), hub(1))
+        .unwrap()
+        .expect("seeded client");
+    repo.assign_clients_to_manager(manager.id, &[alice.id])
+        .unwrap();
+
+    let updates = UpdateClient::new(
+        alice.name.clone(),
+        alice.email.clone(),
+        alice.phone.clone(),
+        alice.fields.clone(),
+    )
+    .edited_by(manager.id);
+    let actor = ManagerEmail::new("admin@example.com").expect("valid email");
+    repo.update_client(alice.id, &updates, &actor).unwrap();
+}
+
+#[test]
+fn diesel_repository_honours_client_reader_contract() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    seed_diesel(&repo);
+
+    run_client_reader_contract(repo);
+}
+
+#[test]
+fn stub_client_reader_honours_client_reader_contract() {
+    run_client_reader_contract(StubClientReader::seeded());
+}
@@ -23,6 +23,7 @@ use pushkind_crm::{
         manager::NewManager,
         types::{ClientEmail, HubId, ManagerEmail},
     },
+    models::config::{DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE, DEFAULT_CSP_POLICY, FeaturesConfig},
     repository::{
         ClientEventListQuery, ClientEventReader, ClientListQuery, ClientReader, ClientWriter,
         DieselRepository, ImportantFieldReader, ManagerReader, ManagerWriter,
@@ -39,6 +40,24 @@ async fn response_json(response: reqwest::Response) -> Value {
     serde_json::from_str(&body).expect("Response body should be valid JSON.")
 }
 
+/// Polls the import status endpoint until the job reaches a terminal state.
+async fn wait_for_import_job(client: &reqwest::Client, address: &str, job_id: i64) -> Value {
+    for _ in 0..100 {
+        let response = client
+            .get(format!("{address}/api/v1/import/{job_id}/status"))
+            .send()
+            .await
+            .expect("Failed to fetch import job status.");
+        assert_eq!(response.status(), StatusCode::OK);
+        let job = response_json(response).await;
+        if matches!(job["status"].as_str(), Some("completed" | "failed")) {
+            return job;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Import job {job_id} did not finish in time.");
+}
+
 fn repo(app: &common::TestApp) -> DieselRepository {
     app.repo()
 }
@@ -51,6 +70,10 @@ fn other_hub_id() -> HubId {
     HubId::new(OTHER_HUB_ID).expect("valid other hub id")
 }
 
+fn dedup_window() -> chrono::Duration {
+    chrono::Duration::hours(24)
+}
+
 fn form_body(fields: Vec<(impl Into<String>, impl Into<String>)>) -> String {
     let fields = fields
         .into_iter()
@@ -59,6 +82,14 @@ fn form_body(fields: Vec<(impl Into<String>, impl Into<String>)>) -> String {
     serde_html_form::to_string(&fields).expect("Form body should serialize.")
 }
 
+fn comment_form(fields: Vec<(&'static str, &'static str)>) -> multipart::Form {
+    fields
+        .into_iter()
+        .fold(multipart::Form::new(), |form, (key, value)| {
+            form.text(key, value)
+        })
+}
+
 #[actix_web::test]
 async fn test_crm_logged_out_user_is_redirected_to_auth() {
     let app = common::spawn_app().await;
@@ -99,6 +130,183 @@ async fn test_crm_logged_out_user_is_redirected_to_auth() {
     assert!(mutation_response.headers().get(header::LOCATION).is_none());
 }
 
+#[actix_web::test]
+async fn test_health_probe_reports_ok_without_login() {
+    let app = common::spawn_app().await;
+    let client = common::build_no_redirect_client();
+
+    let response = client
+        .get(format!("{}/health", app.address()))
+        .send()
+        .await
+        .expect("Failed to request health probe.");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response_json(response).await["status"], "ok");
+}
+
+#[actix_web::test]
+async fn test_index_page_sends_content_security_policy() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+
+    common::login_as(
+        &client,
+        app.address(),
+        "manager@example.com",
+        "Manager User",
+        common::HUB_ID,
+        &["crm"],
+    )
+    .await;
+
+    let index_response = client
+        .get(format!("{}/", app.address()))
+        .send()
+        .await
+        .expect("Failed to request CRM index.");
+
+    assert_eq!(index_response.status(), StatusCode::OK);
+    assert_eq!(
+        index_response
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .and_then(|value| value.to_str().ok()),
+        Some(
+            format!("{DEFAULT_CSP_POLICY}; connect-src 'self' https://users.pushkind.test")
+                .as_str()
+        )
+    );
+
+    let health_response = client
+        .get(format!("{}/health", app.address()))
+        .send()
+        .await
+        .expect("Failed to request health probe.");
+
+    assert_eq!(health_response.status(), StatusCode::OK);
+    assert!(
+        health_response
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .is_none()
+    );
+}
+
+#[actix_web::test]
+async fn test_crm_api_key_integration_story() {
+    let app = common::spawn_app().await;
+    let client = common::build_no_redirect_client();
+    let repo = repo(&app);
+
+    repo.create_or_replace_clients(&[
+        NewClient::try_new(
+            common::HUB_ID,
+            "Keyed Client".to_string(),
+            Some("keyed@example.com".to_string()),
+            None,
+            None,
+        )
+        .expect("valid client"),
+        NewClient::try_new(
+            OTHER_HUB_ID,
+            "Other Hub Client".to_string(),
+            Some("outside@example.com".to_string()),
+            None,
+            None,
+        )
+        .expect("valid other-hub client"),
+    ])
+    .expect("Clients should be created.");
+
+    let admin = common::build_reqwest_client();
+    common::login_as(
+        &admin,
+        app.address(),
+        "admin@example.com",
+        "Admin User",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+    let response = admin
+        .post(format!("{}/api-keys", app.address()))
+        .form(&[("name", "ERP"), ("roles", "crm")])
+        .send()
+        .await
+        .expect("Failed to create an API key.");
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = response_json(response).await;
+    let api_key = created["key"]
+        .as_str()
+        .expect("The raw key should be returned once.")
+        .to_string();
+    let api_key_id = created["api_key"]["id"]
+        .as_i64()
+        .expect("The created key should have an id.");
+
+    let response = admin
+        .get(format!("{}/api/v1/api-keys", app.address()))
+        .send()
+        .await
+        .expect("Failed to list API keys.");
+    assert_eq!(response.status(), StatusCode::OK);
+    let listed = response_json(response).await;
+    assert_eq!(listed[0]["name"], "ERP");
+    assert_eq!(listed[0]["roles"], serde_json::json!(["crm"]));
+    assert!(listed[0].get("key").is_none());
+
+    let response = client
+        .get(format!("{}/api/v1/clients", app.address()))
+        .header("X-API-Key", &api_key)
+        .send()
+        .await
+        .expect("Failed to request clients with an API key.");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let clients = response_json(response).await;
+    let names = clients
+        .as_array()
+        .expect("Clients response should be an array.")
+        .iter()
+        .map(|client| client["name"].as_str().unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Keyed Client".to_string()]);
+
+    let response = client
+        .get(format!("{}/api/v1/clients", app.address()))
+        .header("X-API-Key", "wrong-key")
+        .send()
+        .await
+        .expect("Failed to request clients with a wrong API key.");
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{}/api/v1/clients", app.address()))
+        .send()
+        .await
+        .expect("Failed to request clients without credentials.");
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = admin
+        .post(format!("{}/api-keys/{api_key_id}/delete", app.address()))
+        .send()
+        .await
+        .expect("Failed to revoke the API key.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .get(format!("{}/api/v1/clients", app.address()))
+        .header("X-API-Key", &api_key)
+        .send()
+        .await
+        .expect("Failed to request clients with a revoked API key.");
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
 #[actix_web::test]
 async fn test_crm_admin_full_management_story() {
     let app = common::spawn_app().await;
@@ -122,6 +330,16 @@ async fn test_crm_admin_full_management_story() {
         .expect("Failed to request CRM index.");
 
     assert_eq!(index_response.status(), StatusCode::OK);
+    assert_eq!(
+        index_response
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .and_then(|value| value.to_str().ok()),
+        Some(
+            format!("{DEFAULT_CSP_POLICY}; connect-src 'self' https://users.pushkind.test")
+                .as_str()
+        )
+    );
     let index_html = index_response
         .text()
         .await
@@ -135,6 +353,12 @@ async fn test_crm_admin_full_management_story() {
         .expect("Failed to request IAM payload.");
 
     assert_eq!(iam_response.status(), StatusCode::OK);
+    assert!(
+        iam_response
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .is_none()
+    );
     let iam_payload = response_json(iam_response).await;
     assert_eq!(iam_payload["current_user"]["email"], "admin@example.com");
     assert!(
@@ -194,7 +418,17 @@ async fn test_crm_admin_full_management_story() {
         .await
         .expect("Failed to upload clients.");
 
-    assert_eq!(upload_response.status(), StatusCode::OK);
+    assert_eq!(upload_response.status(), StatusCode::ACCEPTED);
+    let upload_body = response_json(upload_response).await;
+    let job_id = upload_body["job_id"]
+        .as_i64()
+        .expect("Upload should return the import job id.");
+    let import_job = wait_for_import_job(&client, app.address(), job_id).await;
+    assert_eq!(import_job["status"], "completed");
+    assert_eq!(import_job["inserted_rows"], 1);
+    assert_eq!(import_job["updated_rows"], 0);
+    assert!(import_job["completed_at"].is_string());
+
     let imported_client = repo
         .get_client_by_email(&ClientEmail::new("bob@example.com").unwrap(), hub_id())
         .expect("Imported client lookup should succeed.")
@@ -310,6 +544,30 @@ async fn test_crm_admin_full_management_story() {
         .expect("Settings page should be readable.");
     assert!(settings_html.contains("<title>CRM Settings</title>"));
 
+    let dashboard_page_response = client
+        .get(format!("{}/dashboard", app.address()))
+        .send()
+        .await
+        .expect("Failed to request dashboard page.");
+
+    assert_eq!(dashboard_page_response.status(), StatusCode::OK);
+    let dashboard_html = dashboard_page_response
+        .text()
+        .await
+        .expect("Dashboard page should be readable.");
+    assert!(dashboard_html.contains("<title>CRM Dashboard</title>"));
+
+    let dashboard_response = client
+        .get(format!("{}/api/v1/dashboard", app.address()))
+        .send()
+        .await
+        .expect("Failed to request dashboard API.");
+
+    assert_eq!(dashboard_response.status(), StatusCode::OK);
+    let dashboard_payload = response_json(dashboard_response).await;
+    assert!(dashboard_payload["total_clients"].as_u64().is_some());
+    assert!(dashboard_payload["unassigned_count"].as_u64().is_some());
+
     let save_important_fields_response = client
         .post(format!("{}/important-fields", app.address()))
         .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
@@ -339,6 +597,10 @@ async fn test_crm_admin_full_management_story() {
 
     assert_eq!(important_fields_response.status(), StatusCode::OK);
     let important_fields_payload = response_json(important_fields_response).await;
+    assert_eq!(
+        important_fields_payload["fields"],
+        serde_json::json!(["City", "Tier"])
+    );
     assert_eq!(important_fields_payload["fields_text"], "City\nTier");
 
     let directory_response = client
@@ -419,15 +681,69 @@ async fn test_crm_admin_full_management_story() {
             .and_then(|fields| fields.get("Tier")),
         Some(&"gold".to_string())
     );
+    assert_eq!(updated_client.version, 2);
 
-    let comment_response = client
+    // A form loaded before the save above is stale and must not overwrite it.
+    let stale_save_response = client
         .post(format!(
-            "{}/client/{}/comment",
+            "{}/client/{}/save",
             app.address(),
             created_client_id.get()
         ))
         .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
         .body(form_body(vec![
+            ("name", "Alice Stale"),
+            ("email", "alice.updated@example.com"),
+            ("version", "1"),
+        ]))
+        .send()
+        .await
+        .expect("Failed to send stale client save.");
+    assert_eq!(stale_save_response.status(), StatusCode::CONFLICT);
+    let stale_body: serde_json::Value = stale_save_response
+        .json()
+        .await
+        .expect("Stale save error should be JSON.");
+    assert!(
+        stale_body["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("Обновите страницу"))
+    );
+    assert_eq!(
+        repo.get_client_by_id(created_client_id, hub_id())
+            .expect("Client lookup should succeed.")
+            .expect("Client should exist.")
+            .name
+            .as_str(),
+        "Alice Updated"
+    );
+
+    let audit: Vec<serde_json::Value> = client
+        .get(format!(
+            "{}/api/v1/clients/{}/audit",
+            app.address(),
+            created_client_id.get()
+        ))
+        .send()
+        .await
+        .expect("Failed to load client audit log.")
+        .json()
+        .await
+        .expect("Audit log should be JSON.");
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0]["action"], "update");
+    assert_eq!(
+        audit[0]["diff"]["name"],
+        serde_json::json!({ "before": "Alice Admin", "after": "Alice Updated" })
+    );
+
+    let comment_response = client
+        .post(format!(
+            "{}/client/{}/comment",
+            app.address(),
+            created_client_id.get()
+        ))
+        .multipart(comment_form(vec![
             ("subject", ""),
             ("message", "Met at the expo"),
             ("event_type", "comment"),
@@ -470,6 +786,26 @@ async fn test_crm_admin_full_management_story() {
             && event.event_data["url"] == "https://example.com/spec.pdf"
     ));
 
+    let events_response = client
+        .get(format!(
+            "{}/api/v1/clients/{}/events?event_type=comment&page=1",
+            app.address(),
+            created_client_id.get()
+        ))
+        .send()
+        .await
+        .expect("Failed to request client events API.");
+
+    assert_eq!(events_response.status(), StatusCode::OK);
+    let events_payload = response_json(events_response).await;
+    assert_eq!(events_payload["total"], 1);
+    assert_eq!(events_payload["events"][0]["event_type"], "Comment");
+    assert_eq!(
+        events_payload["events"][0]["event_data"]["text"],
+        "Met at the expo"
+    );
+    assert!(events_payload["events"][0]["manager_name"].is_string());
+
     let client_details_response = client
         .get(format!(
             "{}/api/v1/clients/{}",
@@ -695,6 +1031,16 @@ async fn test_crm_admin_public_id_pagination_and_cross_hub_isolation_story() {
         StatusCode::NOT_FOUND
     );
 
+    for invalid_id in ["0", "-1"] {
+        let invalid_client_response = client
+            .get(format!("{}/api/v1/clients/{invalid_id}", app.address()))
+            .send()
+            .await
+            .expect("Failed to request client details with an invalid id.");
+
+        assert_eq!(invalid_client_response.status(), StatusCode::NOT_FOUND);
+    }
+
     let other_hub_client_page_response = client
         .get(format!(
             "{}/client/{}",
@@ -819,6 +1165,20 @@ async fn test_crm_manager_user_scoped_access_story() {
     assert_eq!(items.len(), 1);
     assert_eq!(items[0]["email"], "assigned@example.com");
 
+    let recent_response = client
+        .get(format!("{}/api/v1/clients/recent?limit=5", app.address()))
+        .send()
+        .await
+        .expect("Failed to request manager recent clients.");
+
+    assert_eq!(recent_response.status(), StatusCode::OK);
+    let recent_payload = response_json(recent_response).await;
+    let recent = recent_payload
+        .as_array()
+        .expect("Recent clients should be an array.");
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0]["id"], assigned_client.id.get());
+
     let allowed_client_response = client
         .get(format!(
             "{}/api/v1/clients/{}",
@@ -888,8 +1248,7 @@ async fn test_crm_manager_user_scoped_access_story() {
             app.address(),
             assigned_client.id.get()
         ))
-        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .body(form_body(vec![
+        .multipart(comment_form(vec![
             ("subject", ""),
             ("message", "Assigned-only note"),
             ("event_type", "comment"),
@@ -924,8 +1283,7 @@ async fn test_crm_manager_user_scoped_access_story() {
             app.address(),
             hidden_client.id.get()
         ))
-        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .body(form_body(vec![
+        .multipart(comment_form(vec![
             ("subject", ""),
             ("message", "Should be blocked"),
             ("event_type", "comment"),
@@ -1175,10 +1533,51 @@ async fn test_crm_admin_manager_assignment_replacement_and_not_found_story() {
     assert_eq!(missing_manager_response.status(), StatusCode::NOT_FOUND);
     let missing_manager_payload = response_json(missing_manager_response).await;
     assert_eq!(missing_manager_payload["message"], "Менеджер не найден.");
+
+    let cross_hub_manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                OTHER_HUB_ID,
+                "Cross Hub Manager".to_string(),
+                "cross-hub.manager@example.com".to_string(),
+                false,
+            )
+            .unwrap(),
+        )
+        .expect("Cross-hub manager should be created.");
+    let cross_hub_delete_response = client
+        .delete(format!(
+            "{}/managers/{}",
+            app.address(),
+            cross_hub_manager.id.get()
+        ))
+        .send()
+        .await
+        .expect("Failed to attempt cross-hub manager deletion.");
+
+    assert_eq!(cross_hub_delete_response.status(), StatusCode::FORBIDDEN);
+    assert!(
+        repo.get_manager_by_id(cross_hub_manager.id, other_hub_id())
+            .expect("Cross-hub manager lookup should succeed.")
+            .is_some()
+    );
+
+    let delete_response = client
+        .delete(format!("{}/managers/{}", app.address(), manager.id.get()))
+        .send()
+        .await
+        .expect("Failed to delete manager.");
+
+    assert_eq!(delete_response.status(), StatusCode::OK);
+    assert!(
+        repo.get_manager_by_id(manager.id, hub_id())
+            .expect("Manager lookup should succeed.")
+            .is_none()
+    );
 }
 
 #[actix_web::test]
-async fn test_crm_client_details_sanitize_and_order_events_story() {
+async fn test_crm_admin_reassign_all_clients_story() {
     let app = common::spawn_app().await;
     let client = common::build_reqwest_client();
     let repo = repo(&app);
@@ -1186,33 +1585,147 @@ async fn test_crm_client_details_sanitize_and_order_events_story() {
     common::login_as(
         &client,
         app.address(),
-        "admin.client@example.com",
-        "Client Admin",
+        "admin.reassign@example.com",
+        "Reassign Admin",
         common::HUB_ID,
         &["crm", "crm_admin"],
     )
     .await;
 
-    let add_client_response = client
-        .post(format!("{}/client/add", app.address()))
+    repo.create_or_replace_clients(&[
+        NewClient::try_new(
+            common::HUB_ID,
+            "Leaving Client A".to_string(),
+            Some("leaving.a@example.com".to_string()),
+            None,
+            None,
+        )
+        .unwrap(),
+        NewClient::try_new(
+            common::HUB_ID,
+            "Leaving Client B".to_string(),
+            Some("leaving.b@example.com".to_string()),
+            None,
+            None,
+        )
+        .unwrap(),
+    ])
+    .expect("Seed clients should be created.");
+    let (_, seeded) = repo
+        .list_clients(ClientListQuery::new(hub_id()))
+        .expect("Seed clients should be listed.");
+    let client_ids = seeded.iter().map(|client| client.id).collect::<Vec<_>>();
+
+    let leaving = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                common::HUB_ID,
+                "Leaving Rep".to_string(),
+                "leaving.rep@example.com".to_string(),
+                false,
+            )
+            .unwrap(),
+        )
+        .expect("Leaving manager should be created.");
+    let successor = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                common::HUB_ID,
+                "Successor Rep".to_string(),
+                "successor.rep@example.com".to_string(),
+                false,
+            )
+            .unwrap(),
+        )
+        .expect("Successor manager should be created.");
+    let foreign = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                OTHER_HUB_ID,
+                "Foreign Rep".to_string(),
+                "foreign.rep@example.com".to_string(),
+                false,
+            )
+            .unwrap(),
+        )
+        .expect("Foreign manager should be created.");
+    repo.assign_clients_to_manager(leaving.id, &client_ids)
+        .expect("Clients should be assigned.");
+
+    let foreign_response = client
+        .post(format!("{}/managers/reassign", app.address()))
         .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
         .body(form_body(vec![
-            ("name", "Detail Story Client"),
-            ("email", "detail.story@example.com"),
-            ("phone", ""),
+            ("from_manager_id", leaving.id.get().to_string()),
+            ("to_manager_id", foreign.id.get().to_string()),
         ]))
         .send()
         .await
-        .expect("Failed to add detail client.");
+        .expect("Failed to attempt cross-hub reassignment.");
 
-    assert_eq!(add_client_response.status(), StatusCode::CREATED);
-    let detail_client = repo
-        .get_client_by_email(
-            &ClientEmail::new("detail.story@example.com").unwrap(),
-            hub_id(),
-        )
-        .expect("Detail client lookup should succeed.")
-        .expect("Detail client should exist.");
+    assert_eq!(foreign_response.status(), StatusCode::NOT_FOUND);
+
+    let reassign_response = client
+        .post(format!("{}/managers/reassign", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("from_manager_id", leaving.id.get().to_string()),
+            ("to_manager_id", successor.id.get().to_string()),
+        ]))
+        .send()
+        .await
+        .expect("Failed to reassign clients.");
+
+    assert_eq!(reassign_response.status(), StatusCode::OK);
+    let reassign_payload = response_json(reassign_response).await;
+    assert_eq!(reassign_payload["message"], "Передано клиентов: 2.");
+
+    let (leaving_total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id()).manager_email(leaving.email.clone()))
+        .expect("Leaving manager clients should be listed.");
+    assert_eq!(leaving_total, 0);
+    let (successor_total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id()).manager_email(successor.email.clone()))
+        .expect("Successor clients should be listed.");
+    assert_eq!(successor_total, 2);
+}
+
+#[actix_web::test]
+async fn test_crm_client_details_sanitize_and_order_events_story() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+    let repo = repo(&app);
+
+    common::login_as(
+        &client,
+        app.address(),
+        "admin.client@example.com",
+        "Client Admin",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+
+    let add_client_response = client
+        .post(format!("{}/client/add", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("name", "Detail Story Client"),
+            ("email", "detail.story@example.com"),
+            ("phone", ""),
+        ]))
+        .send()
+        .await
+        .expect("Failed to add detail client.");
+
+    assert_eq!(add_client_response.status(), StatusCode::CREATED);
+    let detail_client = repo
+        .get_client_by_email(
+            &ClientEmail::new("detail.story@example.com").unwrap(),
+            hub_id(),
+        )
+        .expect("Detail client lookup should succeed.")
+        .expect("Detail client should exist.");
 
     let add_manager_response = client
         .post(format!("{}/managers/add", app.address()))
@@ -1253,8 +1766,7 @@ async fn test_crm_client_details_sanitize_and_order_events_story() {
             app.address(),
             detail_client.id.get()
         ))
-        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .body(form_body(vec![
+        .multipart(comment_form(vec![
             ("subject", "Quarterly Follow-up"),
             ("message", "Please review the latest deck"),
             ("event_type", "email"),
@@ -1273,8 +1785,7 @@ async fn test_crm_client_details_sanitize_and_order_events_story() {
             app.address(),
             detail_client.id.get()
         ))
-        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .body(form_body(vec![
+        .multipart(comment_form(vec![
             ("subject", ""),
             ("message", "<script>alert(1)</script><b>Met at the expo</b>"),
             ("event_type", "comment"),
@@ -1353,6 +1864,41 @@ async fn test_crm_client_details_sanitize_and_order_events_story() {
             .iter()
             .any(|event| event["event_data"]["url"] == "https://example.com/expo-notes.pdf")
     );
+
+    let events_export_response = client
+        .get(format!(
+            "{}/client/{}/events/export",
+            app.address(),
+            detail_client.id.get()
+        ))
+        .send()
+        .await
+        .expect("Failed to export client events.");
+
+    assert_eq!(events_export_response.status(), StatusCode::OK);
+    assert_eq!(
+        events_export_response.headers()[header::CONTENT_TYPE],
+        "text/csv; charset=utf-8"
+    );
+    let events_csv = events_export_response
+        .text()
+        .await
+        .expect("Events export should be text.");
+    let mut events_lines = events_csv.lines();
+    assert_eq!(
+        events_lines.next(),
+        Some("created_at,manager_email,event_type,text")
+    );
+    let exported_types = events_lines
+        .map(|line| {
+            line.split(',')
+                .nth(2)
+                .expect("event_type column")
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(exported_types, ["Email", "Comment", "DocumentLink"]);
+    assert!(events_csv.contains("Please review the latest deck"));
 }
 
 #[actix_web::test]
@@ -1390,6 +1936,7 @@ async fn test_crm_worker_event_ingestion_story() {
             subject: Some("RE: CRM".to_string()),
         },
         repo.clone(),
+        dedup_window(),
     )
     .expect("Reply message processing should succeed.");
 
@@ -1400,6 +1947,7 @@ async fn test_crm_worker_event_ingestion_story() {
             reason: Some("No longer interested".to_string()),
         },
         repo.clone(),
+        dedup_window(),
     )
     .expect("Unsubscribe processing should succeed.");
 
@@ -1430,6 +1978,7 @@ async fn test_crm_worker_event_ingestion_story() {
             track: Some("CRM".to_string()),
         },
         repo.clone(),
+        dedup_window(),
     )
     .expect("Task message processing should succeed.");
 
@@ -1671,10 +2220,26 @@ async fn test_crm_basic_user_is_blocked_from_admin_management_apis_and_mutations
         .await
         .expect("Failed to request important-fields API as basic user.");
 
-    assert_eq!(
-        important_fields_api_response.status(),
-        StatusCode::UNAUTHORIZED
-    );
+    // Basic users read the field configuration to build client forms.
+    assert_eq!(important_fields_api_response.status(), StatusCode::OK);
+
+    let settings_api_response = client
+        .get(format!("{}/api/v1/settings", app.address()))
+        .send()
+        .await
+        .expect("Failed to request hub settings API as basic user.");
+
+    assert_eq!(settings_api_response.status(), StatusCode::UNAUTHORIZED);
+
+    let save_settings_response = client
+        .post(format!("{}/settings", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![("items_per_page", "5")]))
+        .send()
+        .await
+        .expect("Failed to submit hub settings as basic user.");
+
+    assert_eq!(save_settings_response.status(), StatusCode::FORBIDDEN);
 
     let upload_response = client
         .post(format!("{}/clients/upload", app.address()))
@@ -1736,4 +2301,448 @@ async fn test_crm_basic_user_is_blocked_from_admin_management_apis_and_mutations
         .expect("Failed to attempt cleanup as basic user.");
 
     assert_eq!(cleanup_response.status(), StatusCode::FORBIDDEN);
+
+    let webhooks_api_response = client
+        .get(format!("{}/api/v1/webhooks", app.address()))
+        .send()
+        .await
+        .expect("Failed to request webhooks API as basic user.");
+
+    assert_eq!(webhooks_api_response.status(), StatusCode::UNAUTHORIZED);
+
+    let add_webhook_response = client
+        .post(format!("{}/webhooks", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![("url", "https://hooks.example.com/crm")]))
+        .send()
+        .await
+        .expect("Failed to attempt add webhook as basic user.");
+
+    assert_eq!(add_webhook_response.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn test_crm_admin_webhook_registration_story() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+
+    common::login_as(
+        &client,
+        app.address(),
+        "admin.webhooks@example.com",
+        "Admin Webhooks",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+
+    let internal_url_response = client
+        .post(format!("{}/webhooks", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![("url", "http://127.0.0.1:9000/hook")]))
+        .send()
+        .await
+        .expect("Failed to attempt internal webhook registration.");
+
+    assert_eq!(internal_url_response.status(), StatusCode::BAD_REQUEST);
+
+    let add_webhook_response = client
+        .post(format!("{}/webhooks", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("url", "https://hooks.example.com/crm"),
+            ("event_type", "Comment"),
+        ]))
+        .send()
+        .await
+        .expect("Failed to register webhook.");
+
+    assert_eq!(add_webhook_response.status(), StatusCode::CREATED);
+
+    let webhooks_response = client
+        .get(format!("{}/api/v1/webhooks", app.address()))
+        .send()
+        .await
+        .expect("Failed to request webhooks API.");
+
+    assert_eq!(webhooks_response.status(), StatusCode::OK);
+    let webhooks_payload = response_json(webhooks_response).await;
+    let webhooks = webhooks_payload.as_array().expect("webhooks array");
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0]["url"], "https://hooks.example.com/crm");
+    assert_eq!(webhooks[0]["event_type"], "Comment");
+    assert_eq!(
+        webhooks[0]["secret"].as_str().map(str::len),
+        Some(64),
+        "secret should be 32 random bytes in hex"
+    );
+    let webhook_id = webhooks[0]["id"].as_i64().expect("webhook id");
+
+    let delete_response = client
+        .post(format!("{}/webhooks/{webhook_id}/delete", app.address()))
+        .send()
+        .await
+        .expect("Failed to delete webhook.");
+
+    assert_eq!(delete_response.status(), StatusCode::OK);
+
+    let missing_delete_response = client
+        .post(format!("{}/webhooks/{webhook_id}/delete", app.address()))
+        .send()
+        .await
+        .expect("Failed to repeat webhook deletion.");
+
+    assert_eq!(missing_delete_response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_crm_admin_segment_story() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+    let repo = repo(&app);
+
+    common::login_as(
+        &client,
+        app.address(),
+        "admin.segments@example.com",
+        "Admin Segments",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+
+    let tier = |value: &str| Some(BTreeMap::from([("Tier".to_string(), value.to_string())]));
+    repo.create_or_replace_clients(&[
+        NewClient::try_new(
+            common::HUB_ID,
+            "Vip Client".to_string(),
+            Some("vip.segment@example.com".to_string()),
+            None,
+            tier("VIP"),
+        )
+        .unwrap(),
+        NewClient::try_new(
+            common::HUB_ID,
+            "Regular Client".to_string(),
+            Some("regular.segment@example.com".to_string()),
+            None,
+            tier("Regular"),
+        )
+        .unwrap(),
+        NewClient::try_new(
+            OTHER_HUB_ID,
+            "Cross Hub Vip".to_string(),
+            Some("cross-hub.segment@example.com".to_string()),
+            None,
+            tier("VIP"),
+        )
+        .unwrap(),
+    ])
+    .expect("Failed to seed segment clients.");
+
+    let invalid_filter_response = client
+        .post(format!("{}/segments", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![("name", "VIP"), ("filter_json", "{}")]))
+        .send()
+        .await
+        .expect("Failed to attempt invalid segment creation.");
+
+    assert_eq!(invalid_filter_response.status(), StatusCode::BAD_REQUEST);
+
+    let add_segment_response = client
+        .post(format!("{}/segments", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("name", "VIP"),
+            ("filter_json", r#"{"fields": {"Tier": "VIP"}}"#),
+        ]))
+        .send()
+        .await
+        .expect("Failed to create segment.");
+
+    assert_eq!(add_segment_response.status(), StatusCode::CREATED);
+
+    let segments_response = client
+        .get(format!("{}/api/v1/segments", app.address()))
+        .send()
+        .await
+        .expect("Failed to request segments API.");
+
+    assert_eq!(segments_response.status(), StatusCode::OK);
+    let segments_payload = response_json(segments_response).await;
+    let segments = segments_payload.as_array().expect("segments array");
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0]["name"], "VIP");
+    let segment_id = segments[0]["id"].as_i64().expect("segment id");
+
+    let members_response = client
+        .get(format!(
+            "{}/api/v1/segments/{segment_id}/clients",
+            app.address()
+        ))
+        .send()
+        .await
+        .expect("Failed to request segment clients.");
+
+    assert_eq!(members_response.status(), StatusCode::OK);
+    let members = response_json(members_response).await;
+    let member_names = members
+        .as_array()
+        .expect("segment clients array")
+        .iter()
+        .filter_map(|member| member["name"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(member_names, vec!["Vip Client"]);
+
+    let update_response = client
+        .post(format!("{}/segments/{segment_id}", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("name", "Regular"),
+            ("filter_json", r#"{"fields": {"Tier": "Regular"}}"#),
+        ]))
+        .send()
+        .await
+        .expect("Failed to update segment.");
+
+    assert_eq!(update_response.status(), StatusCode::OK);
+
+    let updated_members = response_json(
+        client
+            .get(format!(
+                "{}/api/v1/segments/{segment_id}/clients",
+                app.address()
+            ))
+            .send()
+            .await
+            .expect("Failed to request updated segment clients."),
+    )
+    .await;
+    assert_eq!(updated_members[0]["name"], "Regular Client");
+
+    let delete_response = client
+        .post(format!("{}/segments/{segment_id}/delete", app.address()))
+        .send()
+        .await
+        .expect("Failed to delete segment.");
+
+    assert_eq!(delete_response.status(), StatusCode::OK);
+
+    let missing_members_response = client
+        .get(format!(
+            "{}/api/v1/segments/{segment_id}/clients",
+            app.address()
+        ))
+        .send()
+        .await
+        .expect("Failed to request deleted segment clients.");
+
+    assert_eq!(missing_members_response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_crm_admin_hub_settings_story() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+    let repo = repo(&app);
+
+    common::login_as(
+        &client,
+        app.address(),
+        "admin.settings@example.com",
+        "Admin Settings",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+
+    let new_clients = (1..=3)
+        .map(|index| {
+            NewClient::try_new(
+                common::HUB_ID,
+                format!("Paged Client {index}"),
+                Some(format!("paged.{index}@example.com")),
+                None,
+                None,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    repo.create_or_replace_clients(&new_clients)
+        .expect("Failed to seed paged clients.");
+
+    let default_settings = response_json(
+        client
+            .get(format!("{}/api/v1/settings", app.address()))
+            .send()
+            .await
+            .expect("Failed to request hub settings."),
+    )
+    .await;
+    assert_eq!(
+        default_settings["items_per_page"].as_u64(),
+        Some(DEFAULT_ITEMS_PER_PAGE as u64)
+    );
+    assert!(default_settings["email_sender_name"].is_null());
+    assert_eq!(default_settings["allow_manager_self_assign"], false);
+
+    let invalid_response = client
+        .post(format!("{}/settings", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![("items_per_page", "0")]))
+        .send()
+        .await
+        .expect("Failed to submit invalid hub settings.");
+
+    assert_eq!(invalid_response.status(), StatusCode::BAD_REQUEST);
+
+    let save_response = client
+        .post(format!("{}/settings", app.address()))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_body(vec![
+            ("items_per_page", "2"),
+            ("email_sender_name", "Sales Team"),
+            ("allow_manager_self_assign", "true"),
+        ]))
+        .send()
+        .await
+        .expect("Failed to save hub settings.");
+
+    assert_eq!(save_response.status(), StatusCode::OK);
+
+    let saved_settings = response_json(
+        client
+            .get(format!("{}/api/v1/settings", app.address()))
+            .send()
+            .await
+            .expect("Failed to request saved hub settings."),
+    )
+    .await;
+    assert_eq!(saved_settings["items_per_page"], 2);
+    assert_eq!(saved_settings["email_sender_name"], "Sales Team");
+    assert_eq!(saved_settings["allow_manager_self_assign"], true);
+
+    let directory = response_json(
+        client
+            .get(format!("{}/api/v1/client-directory", app.address()))
+            .send()
+            .await
+            .expect("Failed to request client directory."),
+    )
+    .await;
+    assert_eq!(
+        directory["clients"]["items"].as_array().map(Vec::len),
+        Some(2)
+    );
+}
+
+#[actix_web::test]
+async fn test_crm_comment_flood_is_rate_limited() {
+    let app = common::spawn_app().await;
+    let client = common::build_reqwest_client();
+    let repo = repo(&app);
+
+    common::login_as(
+        &client,
+        app.address(),
+        "flood@example.com",
+        "Flood User",
+        common::HUB_ID,
+        &["crm"],
+    )
+    .await;
+
+    repo.create_or_replace_clients(&[NewClient::try_new(
+        common::HUB_ID,
+        "Flooded Client".to_string(),
+        Some("flooded@example.com".to_string()),
+        None,
+        None,
+    )
+    .unwrap()])
+        .expect("Failed to seed client.");
+    let (_, clients) = repo
+        .list_clients(ClientListQuery::new(hub_id()))
+        .expect("Client lookup should succeed.");
+    let client_id = clients[0].id.get();
+
+    let comment = || {
+        client
+            .post(format!("{}/client/{client_id}/comment", app.address()))
+            .multipart(comment_form(vec![
+                ("message", ""),
+                ("event_type", "comment"),
+            ]))
+            .send()
+    };
+
+    // Rejected forms still count towards the limit.
+    for _ in 0..DEFAULT_COMMENT_RATE_LIMIT_PER_MINUTE {
+        let response = comment().await.expect("Failed to post comment.");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let limited = comment().await.expect("Failed to post comment.");
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(limited.headers().contains_key(header::RETRY_AFTER));
+}
+
+#[actix_web::test]
+async fn test_disabled_feature_routes_return_not_found() {
+    let enabled_app = common::spawn_app().await;
+    let disabled_app = common::spawn_app_with_features(FeaturesConfig {
+        xlsx_export: false,
+        hub_dashboard: false,
+        ..FeaturesConfig::default()
+    })
+    .await;
+
+    for (app, expected_status) in [
+        (&enabled_app, StatusCode::OK),
+        (&disabled_app, StatusCode::NOT_FOUND),
+    ] {
+        let client = common::build_reqwest_client();
+        common::login_as(
+            &client,
+            app.address(),
+            "admin.features@example.com",
+            "Admin Features",
+            common::HUB_ID,
+            &["crm", "crm_admin"],
+        )
+        .await;
+
+        for path in ["/clients/export.xlsx", "/api/v1/dashboard"] {
+            let response = client
+                .get(format!("{}{path}", app.address()))
+                .send()
+                .await
+                .expect("Failed to request feature route.");
+
+            assert_eq!(response.status(), expected_status, "{path}");
+        }
+    }
+
+    let client = common::build_reqwest_client();
+    common::login_as(
+        &client,
+        disabled_app.address(),
+        "admin.features@example.com",
+        "Admin Features",
+        common::HUB_ID,
+        &["crm", "crm_admin"],
+    )
+    .await;
+
+    let features = response_json(
+        client
+            .get(format!("{}/api/v1/features", disabled_app.address()))
+            .send()
+            .await
+            .expect("Failed to request features API."),
+    )
+    .await;
+    assert_eq!(features["xlsx_export"], false);
+    assert_eq!(features["webhooks"], true);
 }
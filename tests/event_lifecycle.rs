@@ -0,0 +1,176 @@
+//! End-to-end repository test of a client's event lifecycle: a client and its
+//! manager are created and linked, events are recorded and read back.
+
+use serde_json::json;
+
+use pushkind_crm::domain::client::NewClient;
+use pushkind_crm::domain::client_event::{ClientEvent, ClientEventType, NewClientEvent};
+use pushkind_crm::domain::manager::{Manager, NewManager};
+use pushkind_crm::domain::types::{
+    ClientEmail, ClientName, HubId, ImportConflictStrategy, ManagerEmail,
+};
+use pushkind_crm::repository::{
+    ClientEventListQuery, ClientEventReader, ClientEventWriter, ClientReader, ClientWriter,
+    DieselRepository, ManagerWriter,
+};
+
+mod common;
+
+/// Sorts listed events by id so assertions do not depend on timestamp ties.
+fn by_id(mut events: Vec<(ClientEvent, Manager)>) -> Vec<(ClientEvent, Manager)> {
+    events.sort_by_key(|(event, _)| event.id);
+    events
+}
+
+#[test]
+fn test_client_event_lifecycle() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let summary = repo
+        .create_clients(
+            &[NewClient::new(
+                hub_id,
+                ClientName::new("Alice").expect("valid name"),
+                Some(ClientEmail::new("alice@example.com").expect("valid email")),
+                None,
+                None,
+            )],
+            ImportConflictStrategy::Error,
+        )
+        .unwrap();
+    assert_eq!(summary.inserted, 1);
+    let client = repo
+        .get_client_by_email(
+            &ClientEmail::new("alice@example.com").expect("valid email"),
+            hub_id,
+        )
+        .unwrap()
+        .expect("client was created");
+
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let admin = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                1,
+                "Admin".to_string(),
+                "admin@example.com".to_string(),
+                true,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        repo.assign_clients_to_manager(manager.id, &[client.id])
+            .unwrap(),
+        1
+    );
+    assert!(
+        repo.check_client_assigned_to_manager(
+            client.id,
+            &ManagerEmail::new("m@example.com").expect("valid email")
+        )
+        .unwrap()
+    );
+
+    let comment = NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Comment,
+        json!({"text": "hello"}),
+    );
+    let created_comment = repo.create_client_event(&comment).unwrap();
+    assert_eq!(created_comment.event_type, ClientEventType::Comment);
+    // Identical events are not deduplicated on insert.
+    let repeated_comment = repo.create_client_event(&comment).unwrap();
+    assert_ne!(repeated_comment.id, created_comment.id);
+
+    let attachment = repo
+        .create_client_event(&NewClientEvent::new(
+            client.id,
+            admin.id,
+            ClientEventType::DocumentLink,
+            json!({"text": "Contract", "url": "https://files.example.com/contract.pdf"}),
+        ))
+        .unwrap();
+    assert_eq!(attachment.event_type, ClientEventType::DocumentLink);
+
+    // Events without any data are stored and listed too.
+    let call = repo
+        .create_client_event(&NewClientEvent::new(
+            client.id,
+            manager.id,
+            ClientEventType::Call,
+            json!({}),
+        ))
+        .unwrap();
+    assert_eq!(call.event_type, ClientEventType::Call);
+
+    let (total, events) = repo
+        .list_client_events(ClientEventListQuery::new(client.id))
+        .unwrap();
+    assert_eq!(total, 4);
+    let events = by_id(events);
+    assert_eq!(
+        events
+            .iter()
+            .map(|(event, manager)| (event.id, event.event_type.clone(), manager.email.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                created_comment.id,
+                ClientEventType::Comment,
+                "m@example.com"
+            ),
+            (
+                repeated_comment.id,
+                ClientEventType::Comment,
+                "m@example.com"
+            ),
+            (
+                attachment.id,
+                ClientEventType::DocumentLink,
+                "admin@example.com"
+            ),
+            (call.id, ClientEventType::Call, "m@example.com"),
+        ]
+    );
+    assert!(events.iter().all(|(event, _)| event.client_id == client.id));
+    assert_eq!(
+        events[2].0.event_data["url"],
+        "https://files.example.com/contract.pdf"
+    );
+
+    let (total_comments, comments) = repo
+        .list_client_events(
+            ClientEventListQuery::new(client.id).event_type(ClientEventType::Comment),
+        )
+        .unwrap();
+    assert_eq!(total_comments, 2);
+    assert_eq!(comments.len(), 2);
+    assert!(comments.iter().all(|(event, manager)| {
+        event.event_type == ClientEventType::Comment && manager.email == "m@example.com"
+    }));
+
+    let (total_documents, documents) = repo
+        .list_client_events(
+            ClientEventListQuery::new(client.id).event_type(ClientEventType::DocumentLink),
+        )
+        .unwrap();
+    assert_eq!(total_documents, 1);
+    assert_eq!(documents[0].0.id, attachment.id);
+    assert_eq!(documents[0].1.id, admin.id);
+
+    let (total_calls, calls) = repo
+        .list_client_events(ClientEventListQuery::new(client.id).event_type(ClientEventType::Call))
+        .unwrap();
+    assert_eq!(total_calls, 1);
+    assert_eq!(calls[0].0.id, call.id);
+    assert_eq!(calls[0].0.event_data, json!({}));
+}
@@ -0,0 +1,48 @@
+//! Checks that client reads never leak records across hubs.
+
+use pushkind_crm::domain::client::NewClient;
+use pushkind_crm::domain::types::{ClientEmail, ClientName, HubId};
+use pushkind_crm::repository::{ClientListQuery, ClientReader, ClientWriter, DieselRepository};
+
+mod common;
+
+fn new_client(hub_id: HubId, name: &str, email: &str) -> NewClient {
+    NewClient::new(
+        hub_id,
+        ClientName::new(name).expect("valid name"),
+        Some(ClientEmail::new(email).expect("valid email")),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn test_hub_isolation_in_list_clients() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_one = HubId::new(1).expect("valid hub id");
+    let hub_two = HubId::new(2).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client(hub_one, "Alice", "alice@example.com"),
+        new_client(hub_one, "Bob", "bob@example.com"),
+        // Same email as a hub 1 client: contacts are unique per hub only.
+        new_client(hub_two, "Carol", "alice@example.com"),
+    ])
+    .unwrap();
+
+    let (total_one, clients_one) = repo.list_clients(ClientListQuery::new(hub_one)).unwrap();
+    assert_eq!(total_one, 2);
+    assert_eq!(clients_one.len(), 2);
+    assert!(clients_one.iter().all(|client| client.hub_id == hub_one));
+
+    let (total_two, clients_two) = repo.list_clients(ClientListQuery::new(hub_two)).unwrap();
+    assert_eq!(total_two, 1);
+    assert_eq!(clients_two.len(), 1);
+    assert_eq!(clients_two[0].name.as_str(), "Carol");
+    assert_eq!(clients_two[0].hub_id, hub_two);
+
+    let alice = &clients_one[0];
+    assert!(repo.get_client_by_id(alice.id, hub_one).unwrap().is_some());
+    assert!(repo.get_client_by_id(alice.id, hub_two).unwrap().is_none());
+}
@@ -0,0 +1,156 @@
+//! Guards the client indexes used by hot repository queries.
+
+use diesel::query_builder::{AstPass, Query, QueryFragment, QueryId};
+use diesel::sql_types::{Integer, Text};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use diesel::{QueryDsl, QueryResult, RunQueryDsl};
+use serde_json::json;
+
+use pushkind_crm::domain::client::NewClient;
+use pushkind_crm::domain::client_event::{ClientEventType, NewClientEvent};
+use pushkind_crm::domain::manager::NewManager;
+use pushkind_crm::domain::types::{ClientEmail, ClientName, HubId};
+use pushkind_crm::repository::client::{client_by_email_query, client_page_query};
+use pushkind_crm::repository::client_event::client_events_page_query;
+use pushkind_crm::repository::{
+    ClientEventListQuery, ClientEventReader, ClientEventWriter, ClientListQuery, ClientReader,
+    ClientSort, ClientWriter, DieselRepository, ManagerWriter,
+};
+
+mod common;
+
+/// `EXPLAIN QUERY PLAN` of a Diesel query, run with the query's own binds.
+struct ExplainQueryPlan<Q>(Q);
+
+impl<Q: QueryFragment<Sqlite>> QueryFragment<Sqlite> for ExplainQueryPlan<Q> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+        out.push_sql("EXPLAIN QUERY PLAN ");
+        self.0.walk_ast(out.reborrow())
+    }
+}
+
+impl<Q> QueryId for ExplainQueryPlan<Q> {
+    type QueryId = ();
+
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<Q> Query for ExplainQueryPlan<Q> {
+    type SqlType = (Integer, Integer, Integer, Text);
+}
+
+impl<Q> RunQueryDsl<SqliteConnection> for ExplainQueryPlan<Q> {}
+
+/// Plan details of a repository query as SQLite would run it.
+fn diesel_query_plan<Q>(test_db: &common::TestDb, query: Q) -> Vec<String>
+where
+    Q: QueryFragment<Sqlite>,
+{
+    let sql = diesel::debug_query::<Sqlite, _>(&query).to_string();
+    let mut conn = test_db.pool().get().unwrap();
+    let plan = ExplainQueryPlan(query)
+        .load::<(i32, i32, i32, String)>(&mut conn)
+        .unwrap()
+        .into_iter()
+        .map(|(_, _, _, detail)| detail)
+        .collect::<Vec<_>>();
+    assert!(!plan.is_empty(), "no plan for {sql}");
+    plan
+}
+
+fn assert_uses_clients_index(plan: &[String]) {
+    assert!(
+        plan.iter()
+            .any(|detail| detail.contains("clients USING") && detail.contains("INDEX")),
+        "expected an index search on clients, got {plan:?}"
+    );
+    assert!(
+        !plan
+            .iter()
+            .any(|detail| detail == "SCAN clients" || detail.starts_with("SCAN TABLE clients")),
+        "expected no full scan of clients, got {plan:?}"
+    );
+}
+
+#[test]
+fn test_list_clients_by_updated_at_uses_index() {
+    let test_db = common::TestDb::new();
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let query = ClientListQuery::new(hub_id)
+        .sort(ClientSort::UpdatedDesc)
+        .paginate(1, 20);
+
+    let plan = diesel_query_plan(&test_db, client_page_query(&query, &[]));
+
+    assert_uses_clients_index(&plan);
+}
+
+#[test]
+fn test_get_client_by_email_uses_index() {
+    let test_db = common::TestDb::new();
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let email = ClientEmail::new("alice@example.com").expect("valid email");
+
+    let plan = diesel_query_plan(&test_db, client_by_email_query(&email, hub_id).limit(1));
+
+    assert_uses_clients_index(&plan);
+}
+
+#[test]
+fn test_list_client_events_uses_client_created_at_index() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[NewClient::new(
+        hub_id,
+        ClientName::new("Alice").expect("valid name"),
+        Some(ClientEmail::new("alice@example.com").expect("valid email")),
+        None,
+        None,
+    )])
+    .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(hub_id))
+        .unwrap()
+        .1
+        .remove(0);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    for i in 0..200 {
+        repo.create_client_event(&NewClientEvent::new(
+            client.id,
+            manager.id,
+            ClientEventType::Comment,
+            json!({ "text": format!("Event {i}") }),
+        ))
+        .unwrap();
+    }
+
+    let (total, page) = repo
+        .list_client_events(ClientEventListQuery::new(client.id).paginate(2, 20))
+        .unwrap();
+    assert_eq!(total, 200);
+    assert_eq!(page.len(), 20);
+    assert!(
+        page.windows(2)
+            .all(|pair| pair[0].0.created_at >= pair[1].0.created_at)
+    );
+
+    let query = ClientEventListQuery::new(client.id).paginate(2, 20);
+    let plan = diesel_query_plan(&test_db, client_events_page_query(&query));
+
+    assert!(
+        plan.iter()
+            .any(|detail| detail.contains("client_events_client_id_created_at_idx")),
+        "expected the (client_id, created_at) index, got {plan:?}"
+    );
+    assert!(
+        !plan.iter().any(|detail| detail.contains("TEMP B-TREE")),
+        "expected the index to provide the order, got {plan:?}"
+    );
+}
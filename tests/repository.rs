@@ -1,12 +1,45 @@
 use std::collections::BTreeMap;
 
-use pushkind_crm::domain::client::{NewClient, UpdateClient};
+use pushkind_common::pagination::Pagination;
+use pushkind_common::repository::errors::RepositoryError;
+
+use pushkind_crm::domain::api_key::NewApiKey;
+use pushkind_crm::domain::assignment_rule::NewManagerAssignmentRule;
+use pushkind_crm::domain::client::{
+    ClientSource, ClientStatus, ClientUpsertSummary, ClientWriteOutcome, FieldMeta, NewClient,
+    UpdateClient,
+};
 use pushkind_crm::domain::client_event::{ClientEventType, NewClientEvent};
-use pushkind_crm::domain::manager::NewManager;
-use pushkind_crm::domain::types::{ClientEmail, ClientName, HubId, ManagerEmail, PhoneNumber};
-use pushkind_crm::repository::{ClientEventListQuery, ClientEventReader, ClientEventWriter};
-use pushkind_crm::repository::{ClientListQuery, ClientReader, ClientWriter};
-use pushkind_crm::repository::{DieselRepository, ManagerReader, ManagerWriter};
+use pushkind_crm::domain::contact_policy::ContactPolicy;
+use pushkind_crm::domain::hub_settings::HubSettings;
+use pushkind_crm::domain::important_field::ImportantField;
+use pushkind_crm::domain::manager::{NewManager, SYSTEM_MANAGER_NAME};
+use pushkind_crm::domain::note::NewClientNote;
+use pushkind_crm::domain::segment::NewClientSegment;
+use pushkind_crm::domain::types::{
+    ClientEmail, ClientId, ClientName, ClientNoteId, DateRange, HubId, ImportConflictStrategy,
+    ImportantFieldName, ManagerEmail, ManagerName, NonEmptyString, PhoneNumber, PhoneRegion,
+    RawPhone, WebhookUrl,
+};
+use pushkind_crm::domain::webhook::NewWebhook;
+use pushkind_crm::repository::errors::ClientWriteError;
+use pushkind_crm::repository::{
+    ApiKeyReader, ApiKeyWriter, AuditReader, ClientEventListQuery, ClientEventReader,
+    ClientEventWriter,
+};
+use pushkind_crm::repository::{
+    ClientListQuery, ClientReader, ClientSort, ClientWriter, ContactPolicyReader,
+    ContactPolicyWriter, FtsOperator,
+};
+use pushkind_crm::repository::{
+    DieselRepository, HubSettingsReader, HubSettingsWriter, HubStatsReader, ImportantFieldReader,
+    ImportantFieldWriter, ManagerReader, ManagerSort, ManagerWriter, ServiceStats,
+    ServiceStatsReader,
+};
+use pushkind_crm::repository::{
+    NoteReader, NoteWriter, RuleReader, RuleWriter, SegmentReader, SegmentWriter, WebhookReader,
+    WebhookWriter,
+};
 use serde_json::json;
 
 mod common;
@@ -21,6 +54,14 @@ fn new_client_record(name: &str, email: Option<&str>, phone: Option<&str>) -> Ne
     )
 }
 
+fn audit_actor() -> ManagerEmail {
+    ManagerEmail::new("admin@example.com").expect("valid email")
+}
+
+fn timestamp(value: &str) -> chrono::NaiveDateTime {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").expect("valid timestamp")
+}
+
 #[test]
 fn test_client_repository_crud() {
     let test_db = common::TestDb::new();
@@ -32,7 +73,10 @@ fn test_client_repository_crud() {
         client_repo
             .create_or_replace_clients(&[c1.clone(), c2.clone()])
             .unwrap(),
-        2
+        ClientUpsertSummary {
+            inserted: 2,
+            updated: 0,
+        }
     );
 
     let (total, mut items) = client_repo
@@ -59,6 +103,7 @@ fn test_client_repository_crud() {
                 alice.phone.clone(),
                 Some(BTreeMap::from([("vip".to_string(), "true".to_string())])),
             ),
+            &audit_actor(),
         )
         .unwrap();
     assert_eq!(
@@ -75,11 +120,12 @@ fn test_client_repository_crud() {
                 bob.phone.clone(),
                 Some(BTreeMap::new()),
             ),
+            &audit_actor(),
         )
         .unwrap();
     assert_eq!(bob.name.as_str(), "Bobby");
 
-    client_repo.delete_client(alice.id).unwrap();
+    client_repo.delete_client(alice.id, &audit_actor()).unwrap();
     assert!(
         client_repo
             .get_client_by_id(alice.id, HubId::new(1).expect("valid hub id"))
@@ -95,157 +141,2942 @@ fn test_client_repository_crud() {
 }
 
 #[test]
-fn test_client_event_repository_crud() {
+fn test_list_clients_exact_email_and_phone_filters() {
     let test_db = common::TestDb::new();
     let client_repo = DieselRepository::new(test_db.pool());
-    let manager_repo = DieselRepository::new(test_db.pool());
-    let client = {
-        let new_client =
-            new_client_record("Alice", Some("alice@example.com"), Some("+14155550111"));
-        client_repo
-            .create_or_replace_clients(&[new_client])
+    let hub_id = HubId::new(1).expect("valid hub id");
+    client_repo
+        .create_or_replace_clients(&[
+            new_client_record("Alice", Some("alice@example.com"), Some("+14155550111")),
+            new_client_record("Alicia", Some("alicia@example.com"), Some("+14155550222")),
+        ])
+        .unwrap();
+
+    let (total, items) = client_repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .email_exact(ClientEmail::new("ALICE@example.com").expect("valid email")),
+        )
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(items[0].name.as_str(), "Alice");
+
+    let (total, items) = client_repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .phone_exact(PhoneNumber::new("+1 (415) 555-0222").expect("valid phone")),
+        )
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(items[0].name.as_str(), "Alicia");
+
+    let (total, _) = client_repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .search("Alicia")
+                .email_exact(ClientEmail::new("alice@example.com").expect("valid email")),
+        )
+        .unwrap();
+    assert_eq!(total, 0);
+}
+
+#[test]
+fn test_list_clients_blank_search_matches_unfiltered_list() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+    ])
+    .unwrap();
+
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 2);
+
+    for term in ["", "   "] {
+        let (search_total, search_clients) = repo
+            .list_clients(ClientListQuery::new(hub_id).search(term))
             .unwrap();
-        client_repo
-            .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
-            .unwrap()
-            .1
-            .remove(0)
+        assert_eq!(search_total, total);
+        assert_eq!(search_clients, clients);
+    }
+}
+
+#[test]
+fn test_list_clients_fts_operator_or_matches_any_word() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Acme Trading", Some("trading@example.com"), None),
+        new_client_record("Acme Logistics", Some("logistics@example.com"), None),
+        new_client_record("Globex", Some("globex@example.com"), None),
+    ])
+    .unwrap();
+
+    let search = |term: &str, operator: FtsOperator| {
+        let (total, clients) = repo
+            .list_clients(
+                ClientListQuery::new(hub_id)
+                    .search(term)
+                    .fts_operator(operator),
+            )
+            .unwrap();
+        let mut names: Vec<String> = clients
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect();
+        names.sort();
+        (total, names)
     };
-    let manager_payload =
-        NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true).unwrap();
-    let manager = manager_repo
-        .create_or_update_manager(&manager_payload)
-        .unwrap();
 
-    let client_event_repo = DieselRepository::new(test_db.pool());
+    assert_eq!(search("Trading Logistics", FtsOperator::And), (0, vec![]));
+    assert_eq!(
+        search("Trading Logistics", FtsOperator::Or),
+        (
+            2,
+            vec!["Acme Logistics".to_string(), "Acme Trading".to_string()]
+        )
+    );
+    assert_eq!(search("Acme Trading", FtsOperator::And).0, 1);
+}
 
-    let new_event = NewClientEvent::new(
-        client.id,
-        manager.id,
-        ClientEventType::Comment,
-        json!({"text": "hello"}),
+#[test]
+fn test_list_clients_fuzzy_falls_back_to_name_prefixes() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Joanna Lee", Some("joanna@example.com"), None),
+        new_client_record("John Smith", Some("john@example.com"), None),
+        new_client_record("Peter Parker", Some("peter@example.com"), None),
+    ])
+    .unwrap();
+
+    let search = |term: &str, fuzzy: bool| {
+        let (total, clients) = repo
+            .list_clients(ClientListQuery::new(hub_id).search(term).fuzzy(fuzzy))
+            .unwrap();
+        let names: Vec<String> = clients
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect();
+        (total, names)
+    };
+
+    assert_eq!(search("Jonh", false), (0, vec![]));
+    assert_eq!(
+        search("Jonh", true),
+        (2, vec!["John Smith".to_string(), "Joanna Lee".to_string()])
     );
-    let created = client_event_repo.create_client_event(&new_event).unwrap();
-    assert_eq!(created.event_type, ClientEventType::Comment);
+    // FTS hits are returned as is; the fallback only runs on empty results.
+    assert_eq!(search("Peter", true), (1, vec!["Peter Parker".to_string()]));
 
-    let duplicate_attempt = new_event.clone();
-    let duplicate = client_event_repo
-        .create_client_event(&duplicate_attempt)
+    let (total, page) = repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .search("jonh")
+                .fuzzy(true)
+                .paginate(2, 1),
+        )
         .unwrap();
-    assert_ne!(duplicate.id, created.id);
+    assert_eq!(total, 2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].name.as_str(), "Joanna Lee");
+}
 
-    let (total_after_duplicate, events_after_duplicate) = client_event_repo
-        .list_client_events(ClientEventListQuery::new(client.id))
+#[test]
+fn test_list_clients_hides_archived_clients_unless_requested() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice Active", Some("alice@example.com"), None),
+        new_client_record("Alice Archived", Some("archived@example.com"), None),
+    ])
+    .unwrap();
+    let archived = repo
+        .list_clients(ClientListQuery::new(hub_id).search("Archived"))
+        .unwrap()
+        .1
+        .remove(0);
+    repo.set_client_status(archived.id, hub_id, ClientStatus::Archived)
         .unwrap();
-    assert_eq!(total_after_duplicate, 2);
-    assert_eq!(events_after_duplicate.len(), 2);
 
-    let _ = client_event_repo
-        .create_client_event(&NewClientEvent::new(
-            client.id,
-            manager.id,
-            ClientEventType::Call,
-            json!({}),
-        ))
+    let names = |query: ClientListQuery| {
+        let (total, clients) = repo.list_clients(query).unwrap();
+        let names = clients
+            .iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(total, names.len());
+        names
+    };
+
+    assert_eq!(names(ClientListQuery::new(hub_id)), vec!["Alice Active"]);
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).include_archived(true)),
+        vec!["Alice Active", "Alice Archived"]
+    );
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).search("Alice")),
+        vec!["Alice Active"]
+    );
+    assert_eq!(
+        names(
+            ClientListQuery::new(hub_id)
+                .search("Alice")
+                .include_archived(true)
+        ),
+        vec!["Alice Active", "Alice Archived"]
+    );
+
+    let (_, all) = repo
+        .list_clients(ClientListQuery::new(hub_id).include_archived(true))
         .unwrap();
+    assert_eq!(all[1].status, ClientStatus::Archived);
 
-    let (total, events) = client_event_repo
-        .list_client_events(ClientEventListQuery::new(client.id))
+    assert!(matches!(
+        repo.set_client_status(archived.id, HubId::new(2).unwrap(), ClientStatus::Active),
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[test]
+fn test_assign_clients_to_manager_keeps_archived_assignments() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice Active", Some("alice@example.com"), None),
+        new_client_record("Alice Archived", Some("archived@example.com"), None),
+        new_client_record("Bob Active", Some("bob@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let ids = clients.iter().map(|client| client.id).collect::<Vec<_>>();
+    repo.assign_clients_to_manager(manager.id, &ids).unwrap();
+    repo.set_client_status(clients[1].id, hub_id, ClientStatus::Archived)
+        .unwrap();
+
+    // The manager modal only lists active clients, so a save submits those.
+    repo.assign_clients_to_manager(manager.id, &[clients[2].id])
         .unwrap();
-    assert_eq!(total, 3);
-    assert_eq!(events.len(), 3);
-    assert_eq!(events[0].1.id, manager.id);
 
-    let (total_comment, comments) = client_event_repo
-        .list_client_events(
-            ClientEventListQuery::new(client.id).event_type(ClientEventType::Comment),
+    let (_, assigned) = repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .manager_email(manager.email.clone())
+                .include_archived(true),
         )
         .unwrap();
-    assert_eq!(total_comment, 2);
-    assert!(
-        comments
-            .iter()
-            .all(|(event, _)| event.event_type == ClientEventType::Comment)
-    );
+    let names = assigned
+        .iter()
+        .map(|client| client.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Alice Archived", "Bob Active"]);
 }
 
 #[test]
-fn test_manager_repository_crud() {
+fn test_list_clients_sort_orders() {
     let test_db = common::TestDb::new();
     let client_repo = DieselRepository::new(test_db.pool());
-    let manager_repo = DieselRepository::new(test_db.pool());
-
-    // create clients
-    let clients = vec![
-        new_client_record("Alice", Some("alice@example.com"), Some("+14155550111")),
-        new_client_record("Bob", Some("bob@example.com"), Some("+14155550222")),
-    ];
-    client_repo.create_or_replace_clients(&clients).unwrap();
-    let (_, stored_clients) = client_repo
-        .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
+    let hub_id = HubId::new(1).expect("valid hub id");
+    client_repo
+        .create_or_replace_clients(&[
+            new_client_record("Alice", Some("alice@example.com"), None),
+            new_client_record("Bob", Some("bob@example.com"), None),
+            new_client_record("Carol", Some("carol@example.com"), None),
+        ])
         .unwrap();
-    let client_ids = stored_clients.iter().map(|c| c.id).collect::<Vec<_>>();
 
-    // create or update manager
-    let manager_payload =
-        NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true).unwrap();
-    let manager = manager_repo
-        .create_or_update_manager(&manager_payload)
+    let (_, clients) = client_repo
+        .list_clients(ClientListQuery::new(hub_id))
         .unwrap();
-    assert!(manager.id.get() > 0);
+    for (name, created_at, updated_at) in [
+        ("Alice", "2026-01-02 00:00:00", "2026-01-02 00:00:00"),
+        ("Bob", "2026-01-03 00:00:00", "2026-01-03 00:00:00"),
+        ("Carol", "2026-01-01 00:00:00", "2026-01-04 00:00:00"),
+    ] {
+        let client = clients
+            .iter()
+            .find(|client| client.name.as_str() == name)
+            .expect("client exists");
+        test_db.set_client_timestamps(client.id, timestamp(created_at), timestamp(updated_at));
+    }
 
-    let updated_payload =
-        NewManager::try_new(1, "Updated".to_string(), "m@example.com".to_string(), true).unwrap();
-    let updated = manager_repo
-        .create_or_update_manager(&updated_payload)
+    let names = |sort: Option<ClientSort>| {
+        let mut query = ClientListQuery::new(hub_id);
+        if let Some(sort) = sort {
+            query = query.sort(sort);
+        }
+        let (_, items) = client_repo.list_clients(query).unwrap();
+        items
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // No sort requested keeps the historical `id asc` order.
+    assert_eq!(names(None), vec!["Alice", "Bob", "Carol"]);
+    assert_eq!(
+        names(Some(ClientSort::CreatedDesc)),
+        vec!["Bob", "Alice", "Carol"]
+    );
+    assert_eq!(
+        names(Some(ClientSort::UpdatedDesc)),
+        vec!["Carol", "Bob", "Alice"]
+    );
+}
+
+#[test]
+fn test_list_clients_created_range_bounds_are_inclusive_days() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    for (name, created_at) in [
+        ("Alice", "2026-01-01 23:59:59"),
+        ("Bob", "2026-01-02 00:00:00"),
+        ("Carol", "2026-01-03 23:59:59"),
+    ] {
+        let client = clients
+            .iter()
+            .find(|client| client.name.as_str() == name)
+            .expect("client exists");
+        test_db.set_client_timestamps(client.id, timestamp(created_at), timestamp(created_at));
+    }
+
+    let day = |value: &str| value.parse::<chrono::NaiveDate>().expect("valid day");
+    let names = |range: DateRange| {
+        let (total, items) = repo
+            .list_clients(ClientListQuery::new(hub_id).created_within(range))
+            .unwrap();
+        assert_eq!(total, items.len());
+        items
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(DateRange::new(Some(day("2026-01-02")), None).unwrap()),
+        vec!["Bob", "Carol"]
+    );
+    assert_eq!(
+        names(DateRange::new(None, Some(day("2026-01-01"))).unwrap()),
+        vec!["Alice"]
+    );
+    assert_eq!(
+        names(DateRange::new(Some(day("2026-01-02")), Some(day("2026-01-03"))).unwrap()),
+        vec!["Bob", "Carol"]
+    );
+}
+
+#[test]
+fn test_list_clients_keyset_cursor_pages_by_id() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+
+    let page = |after_id: i32| {
+        let (total, items) = repo
+            .list_clients(
+                ClientListQuery::new(hub_id)
+                    .sort(ClientSort::CreatedDesc)
+                    .after(after_id, 2),
+            )
+            .unwrap();
+        let names = items
+            .iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>();
+        (total, names, items.last().map(|client| client.id.get()))
+    };
+
+    // The cursor keeps id ascending order regardless of `sort`.
+    let (total, names, last_id) = page(0);
+    assert_eq!(total, 3);
+    assert_eq!(names, vec!["Alice", "Bob"]);
+
+    let (total, names, _) = page(last_id.expect("first page is not empty"));
+    assert_eq!(total, 3);
+    assert_eq!(names, vec!["Carol"]);
+}
+
+#[test]
+fn test_list_clients_unassigned_filter_composes_with_search_and_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice Smith", Some("smith@example.com"), None),
+        new_client_record("Alice Jones", Some("jones@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        NewClient::new(
+            HubId::new(2).expect("valid hub id"),
+            ClientName::new("Alice Other").expect("valid name"),
+            Some(ClientEmail::new("other@example.com").expect("valid email")),
+            None,
+            None,
+        ),
+    ])
+    .unwrap();
+    let smith = repo
+        .get_client_by_email(
+            &ClientEmail::new("smith@example.com").expect("valid email"),
+            hub_id,
+        )
+        .unwrap()
+        .expect("client exists");
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(manager.id, &[smith.id])
         .unwrap();
-    assert_eq!(updated.id, manager.id);
-    assert_eq!(updated.name.as_str(), "Updated");
 
-    let preserved_payload =
-        NewManager::try_new(1, "Updated".to_string(), "m@example.com".to_string(), false).unwrap();
-    let preserved = manager_repo
-        .create_or_update_manager(&preserved_payload)
+    let names = |query: ClientListQuery| {
+        let (total, items) = repo.list_clients(query).unwrap();
+        let names = items
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>();
+        (total, names)
+    };
+
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).unassigned(true)),
+        (2, vec!["Alice Jones".to_string(), "Bob".to_string()])
+    );
+    assert_eq!(
+        names(
+            ClientListQuery::new(hub_id)
+                .unassigned(true)
+                .search("Alice")
+        ),
+        (1, vec!["Alice Jones".to_string()])
+    );
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).unassigned(true).paginate(2, 1)),
+        (2, vec!["Bob".to_string()])
+    );
+    assert_eq!(names(ClientListQuery::new(hub_id)).0, 3);
+}
+
+#[test]
+fn test_list_clients_source_filter_keeps_original_source() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None).with_source(ClientSource::Form),
+        new_client_record("Bob", Some("bob@example.com"), None).with_source(ClientSource::Csv),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+    // Re-importing an existing client updates it without changing its source.
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice Updated",
+        Some("alice@example.com"),
+        None,
+    )
+    .with_source(ClientSource::Csv)])
         .unwrap();
-    assert!(preserved.is_user);
 
-    let by_id = manager_repo
-        .get_manager_by_id(manager.id, HubId::new(1).expect("valid hub id"))
+    let names = |source: ClientSource| {
+        let (_, items) = repo
+            .list_clients(ClientListQuery::new(hub_id).source(source))
+            .unwrap();
+        items
+            .into_iter()
+            .map(|client| (client.name.as_str().to_string(), client.source))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(ClientSource::Form),
+        vec![("Alice Updated".to_string(), Some(ClientSource::Form))]
+    );
+    assert_eq!(
+        names(ClientSource::Csv),
+        vec![("Bob".to_string(), Some(ClientSource::Csv))]
+    );
+    assert!(names(ClientSource::Zmq).is_empty());
+
+    let (total, _) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn test_raw_phone_roundtrip_and_upsert() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let email = ClientEmail::new("alice@example.com").expect("valid email");
+    repo.create_or_replace_clients(
+        &[new_client_record("Alice", Some("alice@example.com"), None)
+            .with_raw_phone(RawPhone::new("12-34").expect("raw phone"))],
+    )
+    .unwrap();
+
+    let client = repo
+        .get_client_by_email(&email, hub_id)
+        .unwrap()
+        .expect("client stored");
+    assert!(client.phone.is_none());
+    assert_eq!(
+        client.raw_phone.as_ref().map(RawPhone::as_str),
+        Some("12-34")
+    );
+
+    // A later import with a valid number replaces the raw contact.
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice",
+        Some("alice@example.com"),
+        Some("+14155552671"),
+    )])
+    .unwrap();
+
+    let client = repo
+        .get_client_by_email(&email, hub_id)
         .unwrap()
+        .expect("client stored");
+    assert_eq!(
+        client.phone.as_ref().map(PhoneNumber::as_str),
+        Some("+14155552671")
+    );
+    assert!(client.raw_phone.is_none());
+}
+
+#[test]
+fn test_raw_phone_only_rows_match_existing_clients() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let raw_only = |name: &str| {
+        new_client_record(name, None, None).with_raw_phone(RawPhone::new("12-34").expect("raw"))
+    };
+
+    repo.create_or_replace_clients(&[raw_only("Alice")])
         .unwrap();
-    assert_eq!(by_id.name.as_str(), "Updated");
+    let summary = repo
+        .create_or_replace_clients(&[raw_only("Alicia")])
+        .unwrap();
+    assert_eq!((summary.inserted, summary.updated), (0, 1));
 
-    let by_email = manager_repo
-        .get_manager_by_email(
-            &ManagerEmail::new("m@example.com").expect("valid manager email"),
-            HubId::new(1).expect("valid hub id"),
+    let summary = repo
+        .create_clients(
+            &[raw_only("Alice"), raw_only("Alice")],
+            ImportConflictStrategy::Skip,
         )
-        .unwrap()
         .unwrap();
-    assert_eq!(by_email.id, manager.id);
+    assert_eq!(summary.inserted, 0);
+    assert!(matches!(
+        repo.create_clients(&[raw_only("Alice")], ImportConflictStrategy::Error),
+        Err(ClientWriteError::ClientExists { .. })
+    ));
 
-    // assign clients to manager
-    manager_repo
-        .assign_clients_to_manager(manager.id, &client_ids)
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(clients[0].name.as_str(), "Alicia");
+}
+
+#[test]
+fn test_update_client_rejects_stale_version() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let email = ClientEmail::new("alice@example.com").expect("valid email");
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
         .unwrap();
+    let loaded = repo
+        .get_client_by_email(&email, hub_id)
+        .unwrap()
+        .expect("client stored");
+    assert_eq!(loaded.version, 1);
 
-    let managers_with_clients = manager_repo
-        .list_managers_with_clients(HubId::new(1).expect("valid hub id"))
+    // Two managers open the edit form on the same version; the first save wins.
+    let first = UpdateClient::new(
+        ClientName::new("Alice Smith").expect("valid name"),
+        loaded.email.clone(),
+        None,
+        None,
+    )
+    .expected_version(loaded.version);
+    let saved = repo
+        .update_client(loaded.id, &first, &audit_actor())
         .unwrap();
-    assert_eq!(managers_with_clients.len(), 1);
-    assert_eq!(managers_with_clients[0].0.id, manager.id);
-    assert_eq!(managers_with_clients[0].1.len(), client_ids.len());
+    assert_eq!(saved.version, 2);
 
-    let client_id = client_ids[0];
-    let managers = client_repo.list_managers(client_id).unwrap();
-    assert_eq!(managers.len(), 1);
-    assert_eq!(managers[0].id, manager.id);
-    assert!(
-        client_repo
-            .check_client_assigned_to_manager(
-                client_id,
-                &ManagerEmail::new("m@example.com").expect("valid manager email"),
-            )
-            .unwrap()
+    let second = UpdateClient::new(
+        ClientName::new("Alice Jones").expect("valid name"),
+        loaded.email.clone(),
+        None,
+        None,
+    )
+    .expected_version(loaded.version);
+    let result = repo.update_client(loaded.id, &second, &audit_actor());
+    assert!(matches!(
+        result,
+        Err(ClientWriteError::StaleVersion {
+            expected_version: 1,
+            ..
+        })
+    ));
+
+    let stored = repo
+        .get_client_by_id(loaded.id, hub_id)
+        .unwrap()
+        .expect("client stored");
+    assert_eq!(stored.name.as_str(), "Alice Smith");
+    assert_eq!(stored.version, 2);
+
+    // Field edits also make open forms stale.
+    let stored = repo
+        .replace_client_fields(
+            loaded.id,
+            &BTreeMap::from([("tier".to_string(), "gold".to_string())]),
+            &audit_actor(),
+        )
+        .unwrap();
+    assert_eq!(stored.version, 3);
+
+    // Updates without an expected version always apply.
+    let unguarded = UpdateClient::new(
+        ClientName::new("Alice Jones").expect("valid name"),
+        loaded.email.clone(),
+        None,
+        None,
     );
+    let stored = repo
+        .update_client(loaded.id, &unguarded, &audit_actor())
+        .unwrap();
+    assert_eq!(stored.name.as_str(), "Alice Jones");
+    assert_eq!(stored.version, 4);
+
+    // A CSV upsert of the same email also makes open forms stale.
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice Imported",
+        Some("alice@example.com"),
+        None,
+    )])
+    .unwrap();
+    let stale = UpdateClient::new(
+        ClientName::new("Alice Edited").expect("valid name"),
+        loaded.email.clone(),
+        None,
+        None,
+    )
+    .expected_version(4);
+    assert!(matches!(
+        repo.update_client(loaded.id, &stale, &audit_actor()),
+        Err(ClientWriteError::StaleVersion { .. })
+    ));
+    let stored = repo
+        .get_client_by_id(loaded.id, hub_id)
+        .unwrap()
+        .expect("client stored");
+    assert_eq!(stored.name.as_str(), "Alice Imported");
+    assert_eq!(stored.version, 5);
+}
+
+#[test]
+fn test_list_clients_pending_replies_filter() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let client_id = |name: &str| {
+        clients
+            .iter()
+            .find(|client| client.name.as_str() == name)
+            .expect("client exists")
+            .id
+    };
+
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(
+        manager.id,
+        &[client_id("Alice"), client_id("Bob"), client_id("Carol")],
+    )
+    .unwrap();
+
+    let add_event = |name: &str, event_type: ClientEventType, created_at: &str| {
+        let event = repo
+            .create_client_event(&NewClientEvent::new(
+                client_id(name),
+                manager.id,
+                event_type,
+                json!({"text": "event"}),
+            ))
+            .unwrap();
+        test_db.set_event_created_at(event.id, timestamp(created_at));
+    };
+
+    // Alice replied after the last comment.
+    add_event("Alice", ClientEventType::Comment, "2026-01-01 10:00:00");
+    add_event("Alice", ClientEventType::Reply, "2026-01-02 10:00:00");
+    // Bob's reply was already answered.
+    add_event("Bob", ClientEventType::Reply, "2026-01-01 10:00:00");
+    add_event("Bob", ClientEventType::Email, "2026-01-02 10:00:00");
+    // Carol has no reply at all.
+    add_event("Carol", ClientEventType::Comment, "2026-01-02 10:00:00");
+
+    let (total, pending) = repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .manager_email(manager.email.clone())
+                .pending_replies(),
+        )
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(pending[0].name.as_str(), "Alice");
+
+    let other = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Other".to_string(), "o@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let (other_total, _) = repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .manager_email(other.email.clone())
+                .pending_replies(),
+        )
+        .unwrap();
+    assert_eq!(other_total, 0);
+}
+
+#[test]
+fn test_list_clients_field_filters() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let with_fields = |name: &str, email: &str, fields: &[(&str, &str)]| {
+        let mut client = new_client_record(name, Some(email), None);
+        client.fields = Some(
+            fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+        );
+        client
+    };
+    repo.create_or_replace_clients(&[
+        with_fields(
+            "Alice",
+            "alice@example.com",
+            &[("Stage", "Won"), ("City", "Moscow")],
+        ),
+        with_fields(
+            "Bob",
+            "bob@example.com",
+            &[("Stage", "Won back"), ("City", "Kazan")],
+        ),
+        with_fields(
+            "Carol",
+            "carol@example.com",
+            &[("Stage", "Lost"), ("Note", "Won")],
+        ),
+        with_fields("Dave", "dave@example.com", &[("Stage", "100% done")]),
+    ])
+    .unwrap();
+
+    let names = |query: ClientListQuery| {
+        let (total, clients) = repo.list_clients(query).unwrap();
+        let names = clients
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>();
+        (total, names)
+    };
+
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).field_filter("Stage", "won")),
+        (2, vec!["Alice".to_string(), "Bob".to_string()])
+    );
+    assert_eq!(
+        names(
+            ClientListQuery::new(hub_id)
+                .field_filter("Stage", "Won")
+                .field_filter("City", "Kaz")
+        ),
+        (1, vec!["Bob".to_string()])
+    );
+    // Totals reflect the filter even when the page is smaller.
+    assert_eq!(
+        names(
+            ClientListQuery::new(hub_id)
+                .field_filter("Stage", "Won")
+                .paginate(1, 1)
+        ),
+        (2, vec!["Alice".to_string()])
+    );
+    // Wildcards in the value match literally.
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).field_filter("Stage", "0%")),
+        (1, vec!["Dave".to_string()])
+    );
+    assert_eq!(
+        names(ClientListQuery::new(hub_id).field_filter("Stage", "_")).0,
+        0
+    );
+}
+
+#[test]
+fn test_create_clients_enforces_hub_limit() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    // No `hub_limits` row: unlimited.
+    repo.create_clients(
+        &[
+            new_client_record("Alice", Some("alice@example.com"), None),
+            new_client_record("Bob", Some("bob@example.com"), None),
+        ],
+        ImportConflictStrategy::Skip,
+    )
+    .unwrap();
+
+    repo.set_hub_client_limit(hub_id, Some(3)).unwrap();
+
+    // Duplicates are skipped and do not count towards the limit.
+    let summary = repo
+        .create_clients(
+            &[
+                new_client_record("Alice", Some("alice@example.com"), None),
+                new_client_record("Carol", Some("carol@example.com"), None),
+            ],
+            ImportConflictStrategy::Skip,
+        )
+        .unwrap();
+    assert_eq!(summary.inserted, 1);
+    assert_eq!(summary.updated, 0);
+
+    let result = repo.create_clients(
+        &[new_client_record("Dave", Some("dave@example.com"), None)],
+        ImportConflictStrategy::Skip,
+    );
+    assert!(matches!(
+        result,
+        Err(ClientWriteError::LimitExceeded {
+            hub_id: 1,
+            max_clients: 3
+        })
+    ));
+
+    let result = repo.create_or_replace_clients(&[
+        new_client_record("Alice Updated", Some("alice@example.com"), None),
+        new_client_record("Erin", Some("erin@example.com"), None),
+    ]);
+    assert!(matches!(
+        result,
+        Err(ClientWriteError::LimitExceeded { .. })
+    ));
+
+    // The rejected batch is rolled back entirely.
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 3);
+    assert!(clients.iter().any(|client| client.name.as_str() == "Alice"));
+
+    // Upserting existing clients stays within the limit.
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice Updated",
+        Some("alice@example.com"),
+        None,
+    )])
+    .unwrap();
+
+    // Lifting the limit accepts new clients again.
+    repo.set_hub_client_limit(hub_id, None).unwrap();
+    repo.create_clients(
+        &[new_client_record("Dave", Some("dave@example.com"), None)],
+        ImportConflictStrategy::Skip,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_create_or_replace_clients_matches_emails_case_insensitively() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("Alice@Example.com"), None)])
+        .unwrap();
+    let summary = repo
+        .create_or_replace_clients(&[new_client_record(
+            "Alice Updated",
+            Some("alice@example.com"),
+            None,
+        )])
+        .unwrap();
+    assert_eq!(
+        summary,
+        ClientUpsertSummary {
+            inserted: 0,
+            updated: 1,
+        }
+    );
+
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(clients[0].name.as_str(), "Alice Updated");
+    assert_eq!(
+        clients[0].email.as_ref().map(|email| email.as_str()),
+        Some("alice@example.com")
+    );
+}
+
+#[test]
+fn test_create_or_replace_clients_counts_inserted_and_updated_rows() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", None, Some("+14155550222")),
+    ])
+    .unwrap();
+
+    // Matched by email, matched by phone, and a new client.
+    let summary = repo
+        .create_or_replace_clients(&[
+            new_client_record("Alice Updated", Some("alice@example.com"), None),
+            new_client_record("Bob Updated", Some("bob@example.com"), Some("+14155550222")),
+            new_client_record("Carol", Some("carol@example.com"), None),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        ClientUpsertSummary {
+            inserted: 1,
+            updated: 2,
+        }
+    );
+    assert_eq!(summary.total(), 3);
+}
+
+#[test]
+fn test_create_clients_resolves_existing_clients_by_strategy() {
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let batch = [
+        new_client_record("Alice Updated", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+    ];
+
+    let import = |strategy| {
+        let test_db = common::TestDb::new();
+        let repo = DieselRepository::new(test_db.pool());
+        repo.create_clients(
+            &[new_client_record("Alice", Some("alice@example.com"), None)],
+            ImportConflictStrategy::Upsert,
+        )
+        .unwrap();
+
+        let result = repo.create_clients(&batch, strategy);
+        let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+        let mut names: Vec<String> = clients
+            .iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect();
+        names.sort();
+        (result, names)
+    };
+
+    let (result, names) = import(ImportConflictStrategy::Upsert);
+    let summary = result.unwrap();
+    assert_eq!((summary.inserted, summary.updated), (1, 1));
+    assert_eq!(names, ["Alice Updated", "Bob"]);
+
+    let (result, names) = import(ImportConflictStrategy::Skip);
+    let summary = result.unwrap();
+    assert_eq!((summary.inserted, summary.updated), (1, 0));
+    assert_eq!(names, ["Alice", "Bob"]);
+
+    // The first existing client aborts the batch and nothing is written.
+    let (result, names) = import(ImportConflictStrategy::Error);
+    assert!(matches!(result, Err(ClientWriteError::ClientExists { .. })));
+    assert_eq!(names, ["Alice"]);
+}
+
+#[test]
+fn test_upsert_clients_reports_each_record() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), Some("+15551230001")),
+        new_client_record("Bob", Some("bob@example.com"), Some("+15551230002")),
+    ])
+    .unwrap();
+
+    let outcomes = repo
+        .upsert_clients(&[
+            new_client_record("Carol", Some("carol@example.com"), None),
+            new_client_record("Alice Renamed", Some("alice@example.com"), None),
+            // Bob's email with Alice's phone cannot be stored on either client.
+            new_client_record("Mixed", Some("bob@example.com"), Some("+15551230001")),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        outcomes,
+        [
+            ClientWriteOutcome::Inserted,
+            ClientWriteOutcome::Updated,
+            ClientWriteOutcome::Skipped,
+        ]
+    );
+    let (total, _) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn test_create_clients_inserts_in_chunks() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool()).with_batch_chunk_size(2);
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let with_stage = |name: &str, email: &str, stage: &str| {
+        let mut client = new_client_record(name, Some(email), None);
+        client.fields = Some(BTreeMap::from([("Stage".to_string(), stage.to_string())]));
+        client
+    };
+    repo.create_clients(
+        &[new_client_record("Alice", Some("alice@example.com"), None)],
+        ImportConflictStrategy::Skip,
+    )
+    .unwrap();
+
+    // Five rows over three chunks; the existing client and the in-batch
+    // duplicate are skipped.
+    let summary = repo
+        .create_clients(
+            &[
+                with_stage("Bob", "bob@example.com", "Lead"),
+                with_stage("Alice Again", "alice@example.com", "Won"),
+                with_stage("Carol", "carol@example.com", "Won"),
+                with_stage("Carol Again", "carol@example.com", "Lost"),
+                new_client_record("Dave", Some("dave@example.com"), None),
+            ],
+            ImportConflictStrategy::Skip,
+        )
+        .unwrap();
+    assert_eq!((summary.inserted, summary.updated), (3, 0));
+
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 4);
+    let stage = |name: &str| {
+        clients
+            .iter()
+            .find(|client| client.name.as_str() == name)
+            .and_then(|client| client.fields.as_ref())
+            .and_then(|fields| fields.get("Stage").cloned())
+    };
+    assert_eq!(stage("Alice"), None);
+    assert_eq!(stage("Bob").as_deref(), Some("Lead"));
+    assert_eq!(stage("Carol").as_deref(), Some("Won"));
+    assert_eq!(
+        repo.list_clients(ClientListQuery::new(hub_id).search("Won"))
+            .unwrap()
+            .0,
+        1
+    );
+
+    // A conflict in a later chunk still names the client and rolls back.
+    let result = repo.create_clients(
+        &[
+            new_client_record("Erin", Some("erin@example.com"), None),
+            new_client_record("Frank", Some("frank@example.com"), None),
+            new_client_record("Bob Again", Some("bob@example.com"), None),
+        ],
+        ImportConflictStrategy::Error,
+    );
+    match result {
+        Err(ClientWriteError::ClientExists { name, .. }) => assert_eq!(name, "Bob Again"),
+        other => panic!("expected an existing client error, got {other:?}"),
+    }
+    assert_eq!(
+        repo.list_clients(ClientListQuery::new(hub_id)).unwrap().0,
+        4
+    );
+}
+
+#[test]
+fn test_create_clients_caps_chunks_at_the_bind_parameter_limit() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool()).with_batch_chunk_size(usize::MAX);
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    // Seven bound columns per row: one statement would need 35,000 parameters.
+    let clients: Vec<NewClient> = (0..5_000)
+        .map(|index| {
+            let mut client = new_client_record(
+                &format!("Client {index}"),
+                Some(&format!("client{index}@example.com")),
+                None,
+            );
+            client.fields = Some(BTreeMap::from([
+                ("Stage".to_string(), "Lead".to_string()),
+                ("Region".to_string(), "North".to_string()),
+                ("Source".to_string(), "Fair".to_string()),
+            ]));
+            client
+        })
+        .collect();
+    let summary = repo
+        .create_clients(&clients, ImportConflictStrategy::Skip)
+        .unwrap();
+    assert_eq!(summary.inserted, 5_000);
+
+    let (total, _) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 5_000);
+}
+
+#[test]
+fn test_clients_without_contacts_are_always_inserted() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    // NULL email/phone never hit the unique indexes, so nothing is upserted.
+    assert_eq!(
+        repo.create_or_replace_clients(&[
+            new_client_record("Lead", None, None),
+            new_client_record("Lead", None, None),
+        ])
+        .unwrap()
+        .inserted,
+        2
+    );
+    assert_eq!(
+        repo.create_clients(
+            &[new_client_record("Lead", None, None)],
+            ImportConflictStrategy::Skip
+        )
+        .unwrap()
+        .inserted,
+        1
+    );
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+
+    let (total, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    assert_eq!(total, 4);
+    assert_eq!(
+        clients
+            .iter()
+            .filter(|client| client.email.is_none() && client.phone.is_none())
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn test_list_clients_edited_by_tracks_last_editor() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let other_manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Other".to_string(), "o@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let edit = |name: &str, editor| {
+        let client = clients
+            .iter()
+            .find(|client| client.name.as_str() == name)
+            .expect("client exists");
+        let updates = UpdateClient::new(
+            client.name.clone(),
+            client.email.clone(),
+            client.phone.clone(),
+            None,
+        );
+        let updates = match editor {
+            Some(editor) => updates.edited_by(editor),
+            None => updates,
+        };
+        repo.update_client(client.id, &updates, &audit_actor())
+            .unwrap();
+    };
+
+    edit("Alice", Some(manager.id));
+    edit("Bob", Some(manager.id));
+    edit("Carol", Some(other_manager.id));
+    // Updates without an editor keep the previous attribution.
+    edit("Bob", None);
+
+    let bob = clients
+        .iter()
+        .find(|client| client.name.as_str() == "Bob")
+        .expect("client exists");
+    test_db.set_client_last_edited_at(bob.id, timestamp("2000-01-01 00:00:00"));
+
+    let names = |manager_id, limit| {
+        repo.list_clients_edited_by(manager_id, hub_id, limit)
+            .unwrap()
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(manager.id, 10), vec!["Alice", "Bob"]);
+    assert_eq!(names(manager.id, 1), vec!["Alice"]);
+    assert_eq!(names(other_manager.id, 10), vec!["Carol"]);
+
+    // The latest editor takes over the attribution.
+    edit("Alice", Some(other_manager.id));
+    assert_eq!(names(manager.id, 10), vec!["Bob"]);
+    assert_eq!(names(other_manager.id, 10).len(), 2);
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert!(
+        repo.list_clients_edited_by(manager.id, other_hub, 10)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_update_and_delete_client_record_audit_entries() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let alice = clients[0].clone();
+    let entries = || {
+        repo.list_audit_entries(hub_id, "client", alice.id.get())
+            .unwrap()
+    };
+
+    repo.update_client(
+        alice.id,
+        &UpdateClient::new(
+            ClientName::new("Alice Smith").expect("valid name"),
+            alice.email.clone(),
+            PhoneNumber::new("+14155550111").ok(),
+            Some(BTreeMap::from([("Tier".to_string(), "gold".to_string())])),
+        ),
+        &audit_actor(),
+    )
+    .unwrap();
+
+    let audit = entries();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].actor_email, audit_actor());
+    assert_eq!(audit[0].action, "update");
+    assert_eq!(
+        audit[0].diff,
+        json!({
+            "fields.Tier": { "before": null, "after": "gold" },
+            "name": { "before": "Alice", "after": "Alice Smith" },
+            "phone": { "before": null, "after": "+14155550111" },
+        })
+    );
+
+    // Every update is recorded, even when nothing changed.
+    let current = repo.get_client_by_id(alice.id, hub_id).unwrap().unwrap();
+    repo.update_client(
+        alice.id,
+        &UpdateClient::new(
+            current.name.clone(),
+            current.email.clone(),
+            current.phone.clone(),
+            current.fields.clone(),
+        ),
+        &audit_actor(),
+    )
+    .unwrap();
+    let audit = entries();
+    assert_eq!(audit.len(), 2);
+    assert_eq!(
+        audit.iter().filter(|entry| entry.diff == json!({})).count(),
+        1
+    );
+
+    repo.delete_client(alice.id, &audit_actor()).unwrap();
+    let audit = entries();
+    assert_eq!(audit.len(), 3);
+    let deleted = audit
+        .iter()
+        .find(|entry| entry.action == "delete")
+        .expect("delete entry");
+    assert_eq!(
+        deleted.diff["name"],
+        json!({ "before": "Alice Smith", "after": null })
+    );
+
+    // Entries are scoped to the hub.
+    assert!(
+        repo.list_audit_entries(
+            HubId::new(2).expect("valid hub id"),
+            "client",
+            alice.id.get()
+        )
+        .unwrap()
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_count_clients_by_name_is_hub_scoped_and_exact() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let mut other_hub = new_client_record("Alice", Some("alice@other.example.com"), None);
+    other_hub.hub_id = HubId::new(2).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Alice", Some("alice2@example.com"), None),
+        new_client_record("Alice Smith", Some("smith@example.com"), None),
+        other_hub,
+    ])
+    .unwrap();
+
+    let name = |value: &str| ClientName::new(value).expect("valid name");
+    assert_eq!(
+        repo.count_clients_by_name(hub_id, &name("Alice"), None)
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        repo.count_clients_by_name(hub_id, &name("Bob"), None)
+            .unwrap(),
+        0
+    );
+
+    let alice = repo
+        .get_client_by_email(&ClientEmail::new("alice@example.com").unwrap(), hub_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        repo.count_clients_by_name(hub_id, &name("Alice"), Some(alice.id))
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_client_ids_by_name_key_ignore_case_and_spacing() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let mut other_hub = new_client_record("Анна Смирнова", Some("anna@other.example.com"), None);
+    other_hub.hub_id = HubId::new(2).expect("valid hub id");
+    repo.create_or_replace_clients(&[
+        new_client_record("Анна Смирнова", Some("anna@example.com"), None),
+        new_client_record("АННА  смирнова", Some("anna2@example.com"), None),
+        new_client_record("Анна", Some("anna3@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        other_hub,
+    ])
+    .unwrap();
+
+    let email_of = |id: &ClientId| {
+        repo.get_client_by_id(*id, hub_id)
+            .unwrap()
+            .and_then(|client| client.email)
+            .map(|email| email.as_str().to_string())
+    };
+    let key = |value: &str| ClientName::new(value).expect("valid name").match_key();
+    let by_key = repo.client_ids_by_name_key(hub_id).unwrap();
+    assert_eq!(by_key.len(), 3);
+    assert_eq!(
+        by_key[&key(" анна   СМИРНОВА")]
+            .iter()
+            .map(email_of)
+            .collect::<Vec<_>>(),
+        [
+            Some("anna@example.com".to_string()),
+            Some("anna2@example.com".to_string())
+        ]
+    );
+    assert_eq!(by_key[&key("BOB")].len(), 1);
+    assert!(!by_key.contains_key(&key("Carol")));
+}
+
+#[test]
+fn test_import_clients_rolls_back_name_match_updates_with_the_batch() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let alice = clients[0].id;
+    let renamed = new_client_record("ALICE", Some("alice@new.example.com"), None);
+
+    // The chunk fails on the existing email, so the name match update is
+    // rolled back with it.
+    let result = repo.import_clients(
+        &[new_client_record("Alias", Some("alice@example.com"), None)],
+        &[(alice, renamed.clone())],
+        ImportConflictStrategy::Error,
+        &audit_actor(),
+        &|_| {},
+    );
+    assert!(matches!(result, Err(ClientWriteError::ClientExists { .. })));
+    let stored = repo.get_client_by_id(alice, hub_id).unwrap().unwrap();
+    assert_eq!(
+        stored.email.map(|email| email.as_str().to_string()),
+        Some("alice@example.com".to_string())
+    );
+
+    let summary = repo
+        .import_clients(
+            &[new_client_record("Bob", Some("bob@example.com"), None)],
+            &[(alice, renamed)],
+            ImportConflictStrategy::Upsert,
+            &audit_actor(),
+            &|_| {},
+        )
+        .unwrap();
+    assert_eq!((summary.inserted, summary.updated), (1, 1));
+    let stored = repo.get_client_by_id(alice, hub_id).unwrap().unwrap();
+    assert_eq!(stored.name.as_str(), "Alice");
+    assert_eq!(
+        stored.email.map(|email| email.as_str().to_string()),
+        Some("alice@new.example.com".to_string())
+    );
+    assert_eq!(stored.version, 2);
+}
+
+#[test]
+fn test_list_available_fields_counts_values_and_flags_important_fields() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let with_fields = |name: &str, fields: &[(&str, &str)]| {
+        NewClient::new(
+            hub_id,
+            ClientName::new(name).expect("valid name"),
+            None,
+            None,
+            Some(
+                fields
+                    .iter()
+                    .map(|(field, value)| (field.to_string(), value.to_string()))
+                    .collect(),
+            ),
+        )
+    };
+    repo.create_or_replace_clients(&[
+        with_fields("Alice", &[("Tier", "Gold"), ("City", "Oslo")]),
+        with_fields("Bob", &[("Tier", "Silver")]),
+        with_fields(
+            "Carol",
+            &[("Tier", "Gold"), ("City", "Bergen"), ("Source", "Ads")],
+        ),
+    ])
+    .unwrap();
+    repo.replace_important_fields(
+        hub_id,
+        &[
+            ImportantField::try_new(1, "City".to_string()).unwrap(),
+            ImportantField::try_new(1, "Birthday".to_string()).unwrap(),
+        ],
+    )
+    .unwrap();
+
+    let fields = repo.list_available_fields(hub_id).unwrap();
+
+    let meta = |name: &str, value_count: usize, is_important: bool| FieldMeta {
+        name: name.to_string(),
+        value_count,
+        is_important,
+    };
+    assert_eq!(
+        fields,
+        vec![
+            meta("Birthday", 0, true),
+            meta("City", 2, true),
+            meta("Source", 1, false),
+            meta("Tier", 3, false),
+        ]
+    );
+    assert!(
+        repo.list_available_fields(HubId::new(2).unwrap())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_delete_important_field_removes_only_the_named_hub_field() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub_id = HubId::new(2).expect("valid hub id");
+
+    repo.replace_important_fields(
+        hub_id,
+        &[
+            ImportantField::try_new(1, "City".to_string()).unwrap(),
+            ImportantField::try_new(1, "Tier".to_string()).unwrap(),
+        ],
+    )
+    .unwrap();
+    repo.replace_important_fields(
+        other_hub_id,
+        &[ImportantField::try_new(2, "Tier".to_string()).unwrap()],
+    )
+    .unwrap();
+
+    let tier = ImportantFieldName::new("Tier").unwrap();
+    assert!(repo.delete_important_field(hub_id, &tier).unwrap());
+    assert!(!repo.delete_important_field(hub_id, &tier).unwrap());
+
+    let names = |hub_id| {
+        repo.list_important_fields(hub_id)
+            .unwrap()
+            .into_iter()
+            .map(|field| field.field.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(hub_id), vec!["City".to_string()]);
+    assert_eq!(names(other_hub_id), vec!["Tier".to_string()]);
+}
+
+#[test]
+fn test_replace_client_fields_keeps_contacts_and_records_audit() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let mut record = new_client_record("Alice", Some("alice@example.com"), None);
+    record.fields = Some(BTreeMap::from([("Tier".to_string(), "gold".to_string())]));
+    repo.create_or_replace_clients(&[record]).unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let alice = clients[0].clone();
+
+    let updated = repo
+        .replace_client_fields(
+            alice.id,
+            &BTreeMap::from([("Region".to_string(), "North".to_string())]),
+            &audit_actor(),
+        )
+        .unwrap();
+
+    assert_eq!(updated.name, alice.name);
+    assert_eq!(updated.email, alice.email);
+    assert_eq!(
+        updated.fields,
+        Some(BTreeMap::from([(
+            "Region".to_string(),
+            "North".to_string()
+        )]))
+    );
+
+    let cleared = repo
+        .replace_client_fields(alice.id, &BTreeMap::new(), &audit_actor())
+        .unwrap();
+    assert!(cleared.fields.unwrap_or_default().is_empty());
+
+    let audit = repo
+        .list_audit_entries(hub_id, "client", alice.id.get())
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+    assert!(audit.iter().all(|entry| entry.action == "update"));
+}
+
+#[test]
+fn test_hub_stats_counts() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let first = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                1,
+                "First".to_string(),
+                "first@example.com".to_string(),
+                true,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    let second = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                1,
+                "Second".to_string(),
+                "second@example.com".to_string(),
+                true,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(first.id, &[clients[0].id])
+        .unwrap();
+
+    let comment = |client_id, manager_id| {
+        repo.create_client_event(&NewClientEvent::new(
+            client_id,
+            manager_id,
+            ClientEventType::Comment,
+            json!({"text": "hello"}),
+        ))
+        .unwrap()
+    };
+    let stale = comment(clients[0].id, first.id);
+    comment(clients[1].id, second.id);
+    comment(clients[1].id, second.id);
+
+    test_db.set_event_created_at(stale.id, timestamp("2000-01-01 00:00:00"));
+
+    let since = chrono::NaiveDate::from_ymd_opt(2020, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(repo.count_clients(hub_id).unwrap(), 3);
+    assert_eq!(repo.count_active_clients(hub_id, since).unwrap(), 1);
+    assert_eq!(repo.count_managers(hub_id).unwrap(), 2);
+    assert_eq!(repo.count_client_events(hub_id, since).unwrap(), 2);
+    assert_eq!(
+        repo.top_manager_by_events(hub_id, since)
+            .unwrap()
+            .map(|manager| manager.id),
+        Some(second.id)
+    );
+    assert_eq!(repo.count_unassigned_clients(hub_id).unwrap(), 2);
+
+    assert_eq!(
+        repo.load_service_stats(since).unwrap(),
+        ServiceStats {
+            total_clients: 3,
+            total_managers: 2,
+            recent_events: 2,
+        }
+    );
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert_eq!(repo.count_clients(other_hub).unwrap(), 0);
+    assert!(
+        repo.top_manager_by_events(other_hub, since)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_webhook_repository_crud() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub = HubId::new(2).expect("valid hub id");
+
+    let created = repo
+        .create_webhook(&NewWebhook::new(
+            hub_id,
+            WebhookUrl::new("https://hooks.example.com/crm").unwrap(),
+            Some(ClientEventType::Comment),
+            "secret".to_string(),
+        ))
+        .unwrap();
+    assert_eq!(created.event_type, Some(ClientEventType::Comment));
+
+    let listed = repo.list_webhooks(hub_id).unwrap();
+    assert_eq!(listed, vec![created.clone()]);
+    assert!(repo.list_webhooks(other_hub).unwrap().is_empty());
+
+    assert!(!repo.delete_webhook(created.id, other_hub).unwrap());
+    assert!(repo.delete_webhook(created.id, hub_id).unwrap());
+    assert!(repo.list_webhooks(hub_id).unwrap().is_empty());
+}
+
+#[test]
+fn test_api_key_repository_crud() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub = HubId::new(2).expect("valid hub id");
+
+    let created = repo
+        .create_api_key(&NewApiKey::new(
+            hub_id,
+            "ERP".to_string(),
+            "a".repeat(64),
+            vec!["crm".to_string(), "crm_admin".to_string()],
+        ))
+        .unwrap();
+    assert_eq!(
+        created.roles,
+        vec!["crm".to_string(), "crm_admin".to_string()]
+    );
+
+    assert_eq!(
+        repo.find_api_key(&"a".repeat(64)).unwrap(),
+        Some(created.clone())
+    );
+    assert_eq!(repo.list_api_keys(hub_id).unwrap(), vec![created.clone()]);
+    assert!(repo.list_api_keys(other_hub).unwrap().is_empty());
+
+    assert!(!repo.delete_api_key(created.id, other_hub).unwrap());
+    assert!(repo.delete_api_key(created.id, hub_id).unwrap());
+    assert!(repo.find_api_key(&"a".repeat(64)).unwrap().is_none());
+}
+
+#[test]
+fn test_manager_assignment_rule_repository_crud() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub = HubId::new(2).expect("valid hub id");
+
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let new_rule = |hub_id: HubId, value: &str| {
+        NewManagerAssignmentRule::new(
+            hub_id,
+            manager.id,
+            ImportantFieldName::new("Region").unwrap(),
+            NonEmptyString::new(value).unwrap(),
+        )
+    };
+
+    let created = repo.create_rule(&new_rule(hub_id, "North")).unwrap();
+    assert_eq!(created.hub_id, hub_id);
+    assert_eq!(created.manager_id, manager.id);
+    assert_eq!(created.field.as_str(), "Region");
+    assert_eq!(created.value.as_str(), "North");
+    assert!(matches!(
+        repo.create_rule(&new_rule(hub_id, "North")),
+        Err(RepositoryError::ConstraintViolation(_))
+    ));
+    let other_rule = repo.create_rule(&new_rule(other_hub, "North")).unwrap();
+
+    assert_eq!(repo.list_rules(hub_id).unwrap(), vec![created.clone()]);
+    assert_eq!(repo.list_rules(other_hub).unwrap(), vec![other_rule]);
+
+    assert!(matches!(
+        repo.delete_rule(created.id, other_hub),
+        Err(RepositoryError::NotFound)
+    ));
+    repo.delete_rule(created.id, hub_id).unwrap();
+    assert!(repo.list_rules(hub_id).unwrap().is_empty());
+    assert_eq!(repo.list_rules(other_hub).unwrap().len(), 1);
+}
+
+#[test]
+fn test_segment_repository_evaluates_field_filters() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let with_fields = |name: &str, email: &str, fields: &[(&str, &str)]| {
+        let mut client = new_client_record(name, Some(email), None);
+        client.fields = Some(
+            fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+        );
+        client
+    };
+    repo.create_or_replace_clients(&[
+        with_fields(
+            "Alice",
+            "alice@example.com",
+            &[("Tier", "VIP"), ("Stage", "Won")],
+        ),
+        with_fields(
+            "Bob",
+            "bob@example.com",
+            &[("Tier", "VIP"), ("Stage", "Lead")],
+        ),
+        with_fields("Carol", "carol@example.com", &[("Tier", "Regular")]),
+    ])
+    .unwrap();
+
+    let segment = repo
+        .create_segment(
+            &NewClientSegment::try_new(
+                hub_id,
+                NonEmptyString::new("VIP winners").unwrap(),
+                json!({"fields": {"Tier": "VIP", "Stage": "Won"}}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    let names = |segment_id| {
+        repo.evaluate_segment(segment_id, hub_id)
+            .unwrap()
+            .into_iter()
+            .map(|client| client.name.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(segment.id), vec!["Alice"]);
+
+    let (total, clients) = repo
+        .list_clients(
+            ClientListQuery::new(hub_id)
+                .segment(segment.id)
+                .search("Bob"),
+        )
+        .unwrap();
+    assert_eq!(total, 0);
+    assert!(clients.is_empty());
+
+    let updated = repo
+        .update_segment(
+            segment.id,
+            &NewClientSegment::try_new(
+                hub_id,
+                NonEmptyString::new("VIP").unwrap(),
+                json!({"fields": {"Tier": "VIP"}}),
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .expect("segment exists");
+    assert_eq!(updated.name.as_str(), "VIP");
+    assert_eq!(names(segment.id), vec!["Alice", "Bob"]);
+    assert_eq!(repo.list_segments(hub_id).unwrap().len(), 1);
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert!(matches!(
+        repo.evaluate_segment(segment.id, other_hub),
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(!repo.delete_segment(segment.id, other_hub).unwrap());
+    assert!(repo.delete_segment(segment.id, hub_id).unwrap());
+    assert!(repo.get_segment(segment.id, hub_id).unwrap().is_none());
+}
+
+#[test]
+fn test_get_contact_policy_defaults_without_row() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    let policy = repo
+        .get_contact_policy(HubId::new(1).expect("valid hub id"))
+        .unwrap();
+    assert_eq!(policy, ContactPolicy::default());
+
+    repo.save_contact_policy(
+        HubId::new(2).expect("valid hub id"),
+        &ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        },
+    )
+    .unwrap();
+
+    let policy = repo
+        .get_contact_policy(HubId::new(2).expect("valid hub id"))
+        .unwrap();
+    assert_eq!(
+        policy,
+        ContactPolicy {
+            require_email: false,
+            require_phone: true,
+        }
+    );
+}
+
+#[test]
+fn test_hub_settings_persist_per_hub() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub_id = HubId::new(2).expect("valid hub id");
+
+    assert_eq!(
+        repo.get_hub_settings(hub_id).unwrap(),
+        HubSettings::defaults(hub_id)
+    );
+
+    let settings = HubSettings::new(
+        hub_id,
+        5,
+        Some(NonEmptyString::new("Sales Team").unwrap()),
+        true,
+    )
+    .unwrap()
+    .with_default_phone_region(Some(PhoneRegion::new("DE").unwrap()))
+    .with_email_reply_to(Some(ManagerEmail::new("sales@example.com").unwrap()));
+    assert_eq!(repo.save_hub_settings(&settings).unwrap(), settings);
+    assert_eq!(repo.get_hub_settings(hub_id).unwrap(), settings);
+    assert_eq!(
+        repo.get_hub_settings(other_hub_id).unwrap(),
+        HubSettings::defaults(other_hub_id)
+    );
+
+    let updated = HubSettings::new(hub_id, 50, None, false).unwrap();
+    repo.save_hub_settings(&updated).unwrap();
+    assert_eq!(repo.get_hub_settings(hub_id).unwrap(), updated);
+}
+
+#[test]
+fn test_delete_events_older_than_keeps_recent_and_other_hub_events() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub_id = HubId::new(2).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        NewClient::new(
+            other_hub_id,
+            ClientName::new("Bob").expect("valid name"),
+            Some(ClientEmail::new("bob@example.com").expect("valid email")),
+            None,
+            None,
+        ),
+    ])
+    .unwrap();
+    let alice = repo.list_clients(ClientListQuery::new(hub_id)).unwrap().1[0].clone();
+    let bob = repo
+        .list_clients(ClientListQuery::new(other_hub_id))
+        .unwrap()
+        .1[0]
+        .clone();
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let comment = |client_id, text: &str| {
+        repo.create_client_event(&NewClientEvent::new(
+            client_id,
+            manager.id,
+            ClientEventType::Comment,
+            json!({ "text": text }),
+        ))
+        .unwrap()
+    };
+    let old = comment(alice.id, "old");
+    let recent = comment(alice.id, "recent");
+    let other_hub_old = comment(bob.id, "old");
+
+    let long_ago = chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    test_db.set_event_created_at(old.id, long_ago);
+    test_db.set_event_created_at(other_hub_old.id, long_ago);
+
+    let cutoff = chrono::NaiveDate::from_ymd_opt(2020, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(repo.delete_events_older_than(hub_id, cutoff).unwrap(), 1);
+    assert_eq!(repo.delete_events_older_than(hub_id, cutoff).unwrap(), 0);
+
+    let (total, events) = repo
+        .list_client_events(ClientEventListQuery::new(alice.id))
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(events[0].0.id, recent.id);
+    let (other_total, _) = repo
+        .list_client_events(ClientEventListQuery::new(bob.id))
+        .unwrap();
+    assert_eq!(other_total, 1);
+
+    assert!(
+        repo.list_hub_settings_with_event_retention()
+            .unwrap()
+            .is_empty()
+    );
+    let settings = HubSettings::defaults(hub_id).with_event_retention_days(Some(30));
+    repo.save_hub_settings(&settings).unwrap();
+    assert_eq!(
+        repo.list_hub_settings_with_event_retention().unwrap(),
+        vec![settings]
+    );
+}
+
+#[test]
+fn test_client_event_exists_on_client_with_many_events() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice",
+        Some("alice@example.com"),
+        Some("+14155550111"),
+    )])
+    .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
+        .unwrap()
+        .1
+        .remove(0);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let other_manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Other".to_string(), "o@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+
+    for index in 0..500 {
+        repo.create_client_event(&NewClientEvent::new(
+            client.id,
+            manager.id,
+            ClientEventType::Email,
+            json!({"text": format!("Subject {index}")}),
+        ))
+        .unwrap();
+    }
+
+    let existing = NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Email,
+        json!({"text": "Subject 499"}),
+    );
+    assert!(repo.client_event_exists(&existing).unwrap());
+
+    let new_content = NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Email,
+        json!({"text": "Subject 500"}),
+    );
+    assert!(!repo.client_event_exists(&new_content).unwrap());
+
+    let other_type = NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Reply,
+        json!({"text": "Subject 499"}),
+    );
+    assert!(!repo.client_event_exists(&other_type).unwrap());
+
+    let other_author = NewClientEvent::new(
+        client.id,
+        other_manager.id,
+        ClientEventType::Email,
+        json!({"text": "Subject 499"}),
+    );
+    assert!(!repo.client_event_exists(&other_author).unwrap());
+}
+
+#[test]
+fn test_client_event_exists_within_window_ignores_old_events() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
+        .unwrap()
+        .1
+        .remove(0);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let event = |text: &str| {
+        NewClientEvent::new(
+            client.id,
+            manager.id,
+            ClientEventType::Email,
+            json!({ "text": text }),
+        )
+    };
+    let window = chrono::Duration::hours(24);
+
+    repo.create_client_event(&event("Recent")).unwrap();
+    let old = repo.create_client_event(&event("Old")).unwrap();
+    test_db.set_event_created_at(old.id, chrono::Utc::now().naive_utc() - window * 2);
+
+    assert!(
+        repo.client_event_exists_within_window(&event("Recent"), window)
+            .unwrap()
+    );
+    assert!(
+        !repo
+            .client_event_exists_within_window(&event("Old"), window)
+            .unwrap()
+    );
+    // Without a window the old event still counts as a duplicate.
+    assert!(repo.client_event_exists(&event("Old")).unwrap());
+}
+
+#[test]
+fn test_client_note_repository_crud() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), Some("+14155550111")),
+        new_client_record("Bob", Some("bob@example.com"), Some("+14155550222")),
+    ])
+    .unwrap();
+    let (_, clients) = repo
+        .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
+        .unwrap();
+    let alice = clients
+        .iter()
+        .find(|client| client.name.as_str() == "Alice")
+        .unwrap();
+    let bob = clients
+        .iter()
+        .find(|client| client.name.as_str() == "Bob")
+        .unwrap();
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let first = repo
+        .create_client_note(
+            &NewClientNote::try_new(alice.id.get(), manager.id.get(), "First".to_string()).unwrap(),
+        )
+        .unwrap();
+    assert!(!first.is_pinned);
+    assert_eq!(first.text.as_str(), "First");
+    let second = repo
+        .create_client_note(
+            &NewClientNote::try_new(alice.id.get(), manager.id.get(), "Second".to_string())
+                .unwrap(),
+        )
+        .unwrap();
+
+    let notes = repo.list_client_notes(alice.id).unwrap();
+    assert_eq!(
+        notes.iter().map(|note| note.id).collect::<Vec<_>>(),
+        vec![second.id, first.id]
+    );
+    assert!(repo.list_client_notes(bob.id).unwrap().is_empty());
+
+    let pinned = repo
+        .toggle_client_note_pin(first.id, alice.id)
+        .unwrap()
+        .expect("note belongs to the client");
+    assert!(pinned.is_pinned);
+
+    let notes = repo.list_client_notes(alice.id).unwrap();
+    assert_eq!(
+        notes.iter().map(|note| note.id).collect::<Vec<_>>(),
+        vec![first.id, second.id]
+    );
+
+    // A note cannot be toggled through another client.
+    assert!(
+        repo.toggle_client_note_pin(first.id, bob.id)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.toggle_client_note_pin(ClientNoteId::new(9999).unwrap(), alice.id)
+            .unwrap()
+            .is_none()
+    );
+
+    let unpinned = repo
+        .toggle_client_note_pin(first.id, alice.id)
+        .unwrap()
+        .expect("note belongs to the client");
+    assert!(!unpinned.is_pinned);
+
+    repo.delete_client(alice.id, &audit_actor()).unwrap();
+    assert!(repo.list_client_notes(alice.id).unwrap().is_empty());
+}
+
+#[test]
+fn test_get_clients_by_ids_excludes_other_hub_clients() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+    let other_hub_id = HubId::new(2).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        NewClient::new(
+            hub_id,
+            ClientName::new("Alice").expect("valid name"),
+            Some(ClientEmail::new("alice@example.com").expect("valid email")),
+            None,
+            Some(BTreeMap::from([("Stage".to_string(), "Lead".to_string())])),
+        ),
+        new_client_record("Carol", Some("carol@example.com"), None),
+        NewClient::new(
+            other_hub_id,
+            ClientName::new("Bob").expect("valid name"),
+            Some(ClientEmail::new("bob@example.com").expect("valid email")),
+            None,
+            None,
+        ),
+    ])
+    .unwrap();
+    let (_, own_clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let (_, other_clients) = repo
+        .list_clients(ClientListQuery::new(other_hub_id))
+        .unwrap();
+    let alice = own_clients
+        .iter()
+        .find(|client| client.name.as_str() == "Alice")
+        .unwrap();
+
+    let clients = repo
+        .get_clients_by_ids(&[alice.id, other_clients[0].id], hub_id)
+        .unwrap();
+
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].id, alice.id);
+    assert_eq!(
+        clients[0]
+            .fields
+            .as_ref()
+            .and_then(|fields| fields.get("Stage"))
+            .map(String::as_str),
+        Some("Lead")
+    );
+    assert!(repo.get_clients_by_ids(&[], hub_id).unwrap().is_empty());
+}
+
+#[test]
+fn test_manager_repository_crud() {
+    let test_db = common::TestDb::new();
+    let client_repo = DieselRepository::new(test_db.pool());
+    let manager_repo = DieselRepository::new(test_db.pool());
+
+    // create clients
+    let clients = vec![
+        new_client_record("Alice", Some("alice@example.com"), Some("+14155550111")),
+        new_client_record("Bob", Some("bob@example.com"), Some("+14155550222")),
+    ];
+    client_repo.create_or_replace_clients(&clients).unwrap();
+    let (_, stored_clients) = client_repo
+        .list_clients(ClientListQuery::new(HubId::new(1).expect("valid hub id")))
+        .unwrap();
+    let client_ids = stored_clients.iter().map(|c| c.id).collect::<Vec<_>>();
+
+    // create or update manager
+    let manager_payload =
+        NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true).unwrap();
+    let manager = manager_repo
+        .create_or_update_manager(&manager_payload)
+        .unwrap();
+    assert!(manager.id > 0);
+
+    let updated_payload =
+        NewManager::try_new(1, "Updated".to_string(), "m@example.com".to_string(), true).unwrap();
+    let updated = manager_repo
+        .create_or_update_manager(&updated_payload)
+        .unwrap();
+    assert_eq!(updated.id, manager.id);
+    assert_eq!(updated.name.as_str(), "Updated");
+
+    let preserved_payload =
+        NewManager::try_new(1, "Updated".to_string(), "m@example.com".to_string(), false).unwrap();
+    let preserved = manager_repo
+        .create_or_update_manager(&preserved_payload)
+        .unwrap();
+    assert!(preserved.is_user);
+
+    let by_id = manager_repo
+        .get_manager_by_id(manager.id, HubId::new(1).expect("valid hub id"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_id.name.as_str(), "Updated");
+
+    let by_email = manager_repo
+        .get_manager_by_email(
+            &ManagerEmail::new("m@example.com").expect("valid manager email"),
+            HubId::new(1).expect("valid hub id"),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_email.id, manager.id);
+
+    // assign clients to manager
+    manager_repo
+        .assign_clients_to_manager(manager.id, &client_ids)
+        .unwrap();
+
+    let managers_with_clients = manager_repo
+        .list_managers_with_clients(HubId::new(1).expect("valid hub id"), ManagerSort::Name)
+        .unwrap();
+    assert_eq!(managers_with_clients.len(), 1);
+    assert_eq!(managers_with_clients[0].0.id, manager.id);
+    assert_eq!(managers_with_clients[0].1.len(), client_ids.len());
+
+    let client_id = client_ids[0];
+    let managers = client_repo.list_all_managers_for_client(client_id).unwrap();
+    assert_eq!(managers.len(), 1);
+    assert_eq!(managers[0].id, manager.id);
+    assert!(
+        client_repo
+            .check_client_assigned_to_manager(
+                client_id,
+                &ManagerEmail::new("m@example.com").expect("valid manager email"),
+            )
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_list_managers_for_client_pages_by_id() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(hub_id))
+        .unwrap()
+        .1
+        .remove(0);
+    let mut manager_ids = Vec::new();
+    for i in 0..50 {
+        let manager = repo
+            .create_or_update_manager(
+                &NewManager::try_new(
+                    1,
+                    format!("Manager {i:02}"),
+                    format!("m{i:02}@example.com"),
+                    true,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        repo.assign_clients_to_manager(manager.id, &[client.id])
+            .unwrap();
+        manager_ids.push(manager.id);
+    }
+    manager_ids.sort();
+
+    let (total, page) = repo
+        .list_managers_for_client(
+            client.id,
+            Some(Pagination {
+                page: 2,
+                per_page: 20,
+            }),
+        )
+        .unwrap();
+
+    assert_eq!(total, 50);
+    let page_ids: Vec<_> = page.iter().map(|manager| manager.id).collect();
+    assert_eq!(page_ids, manager_ids[20..40]);
+
+    let (total, all) = repo.list_managers_for_client(client.id, None).unwrap();
+    assert_eq!(total, 50);
+    assert_eq!(all.len(), 50);
+}
+
+#[test]
+fn test_delete_manager_moves_events_to_system_manager() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(hub_id))
+        .unwrap()
+        .1
+        .remove(0);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(manager.id, &[client.id])
+        .unwrap();
+    for text in ["First", "Second"] {
+        repo.create_client_event(&NewClientEvent::new(
+            client.id,
+            manager.id,
+            ClientEventType::Comment,
+            json!({ "text": text }),
+        ))
+        .unwrap();
+    }
+
+    repo.delete_manager(manager.id, hub_id).unwrap();
+
+    assert!(
+        repo.get_manager_by_id(manager.id, hub_id)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.list_all_managers_for_client(client.id)
+            .unwrap()
+            .is_empty()
+    );
+
+    let system = repo.get_or_create_system_manager(hub_id).unwrap();
+    assert_eq!(system.name.as_str(), SYSTEM_MANAGER_NAME);
+    let (total, events) = repo
+        .list_client_events(ClientEventListQuery::new(client.id))
+        .unwrap();
+    assert_eq!(total, 2);
+    assert!(
+        events
+            .iter()
+            .all(|(event, author)| event.manager_id == system.id && author.id == system.id)
+    );
+
+    // The system manager is reused and cannot be deleted.
+    assert_eq!(
+        repo.get_or_create_system_manager(hub_id).unwrap().id,
+        system.id
+    );
+    assert!(matches!(
+        repo.delete_manager(system.id, hub_id),
+        Err(RepositoryError::ConstraintViolation(_))
+    ));
+    assert!(matches!(
+        repo.delete_manager(manager.id, hub_id),
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[test]
+fn test_archived_manager_is_hidden_but_keeps_event_authorship() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record("Alice", Some("alice@example.com"), None)])
+        .unwrap();
+    let client = repo
+        .list_clients(ClientListQuery::new(hub_id))
+        .unwrap()
+        .1
+        .remove(0);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    repo.create_client_event(&NewClientEvent::new(
+        client.id,
+        manager.id,
+        ClientEventType::Comment,
+        json!({ "text": "Hello" }),
+    ))
+    .unwrap();
+
+    let archived = repo.set_manager_active(manager.id, hub_id, false).unwrap();
+    assert!(!archived.is_active);
+    assert!(
+        repo.list_managers_with_clients(hub_id, ManagerSort::Name)
+            .unwrap()
+            .is_empty()
+    );
+
+    let (_, events) = repo
+        .list_client_events(ClientEventListQuery::new(client.id))
+        .unwrap();
+    assert_eq!(events[0].1.name.as_str(), "Manager");
+
+    let restored = repo.set_manager_active(manager.id, hub_id, true).unwrap();
+    assert!(restored.is_active);
+    assert_eq!(
+        repo.list_managers_with_clients(hub_id, ManagerSort::Name)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert!(matches!(
+        repo.set_manager_active(manager.id, other_hub, false),
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[test]
+fn test_find_managers_by_name_matches_full_or_first_name() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    for (hub, name, email) in [
+        (1, "Алиса Смирнова", "alice@example.com"),
+        (1, "Bob", "bob@example.com"),
+        (2, "Алиса", "alice@other.example.com"),
+    ] {
+        repo.create_or_update_manager(
+            &NewManager::try_new(hub, name.to_string(), email.to_string(), true).unwrap(),
+        )
+        .unwrap();
+    }
+    let archived = repo
+        .create_or_update_manager(
+            &NewManager::try_new(
+                1,
+                "Bob Old".to_string(),
+                "old@example.com".to_string(),
+                true,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    repo.set_manager_active(archived.id, hub_id, false).unwrap();
+
+    let names = |name: &str| -> Vec<String> {
+        repo.find_managers_by_name(name, hub_id)
+            .unwrap()
+            .into_iter()
+            .map(|manager| manager.email.into_inner())
+            .collect()
+    };
+
+    assert_eq!(names("алиса"), ["alice@example.com"]);
+    assert_eq!(names("Алиса Смирнова"), ["alice@example.com"]);
+    assert_eq!(names("BOB"), ["bob@example.com"]);
+    assert!(names("Смирнова").is_empty());
+}
+
+#[test]
+fn test_create_or_update_manager_keeps_contact_details() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    let payload = NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), false)
+        .unwrap()
+        .with_contact_details(
+            Some(NonEmptyString::new("Acme").expect("valid company")),
+            Some(PhoneNumber::new("+14155550333").expect("valid phone")),
+        );
+    let manager = repo.create_or_update_manager(&payload).unwrap();
+    assert_eq!(
+        manager.company.as_ref().map(|company| company.as_str()),
+        Some("Acme")
+    );
+    assert_eq!(
+        manager.phone.as_ref().map(|phone| phone.as_str()),
+        Some("+14155550333")
+    );
+
+    // Upserts without contact details (e.g. from login) keep the stored values.
+    let login_payload =
+        NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true).unwrap();
+    let updated = repo.create_or_update_manager(&login_payload).unwrap();
+    assert_eq!(updated.id, manager.id);
+    assert!(updated.is_user);
+    assert_eq!(updated.company, manager.company);
+    assert_eq!(updated.phone, manager.phone);
+}
+
+#[test]
+fn test_save_manager_clears_blank_contact_details() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+
+    let payload = NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), false)
+        .unwrap()
+        .with_contact_details(
+            Some(NonEmptyString::new("Acme").expect("valid company")),
+            Some(PhoneNumber::new("+14155550333").expect("valid phone")),
+        );
+    let manager = repo.save_manager(&payload).unwrap();
+    assert!(manager.company.is_some());
+    assert!(manager.phone.is_some());
+
+    // The admin form saved with both fields blanked clears them.
+    let blanked =
+        NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), false).unwrap();
+    let updated = repo.save_manager(&blanked).unwrap();
+    assert_eq!(updated.id, manager.id);
+    assert!(updated.company.is_none());
+    assert!(updated.phone.is_none());
+}
+
+#[test]
+fn test_update_manager_profile_locks_name_against_login_sync() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    let login_payload = NewManager::try_new(
+        1,
+        "Token Name".to_string(),
+        "m@example.com".to_string(),
+        true,
+    )
+    .unwrap();
+    let manager = repo.create_or_update_manager(&login_payload).unwrap();
+    assert!(!manager.name_locked);
+
+    let updated = repo
+        .update_manager_profile(
+            manager.id,
+            hub_id,
+            &ManagerName::new("Display Name").unwrap(),
+            Some(PhoneNumber::new("+14155550444").expect("valid phone")),
+        )
+        .unwrap();
+    assert_eq!(updated.name.as_str(), "Display Name");
+    assert!(updated.name_locked);
+    assert_eq!(
+        updated.phone.as_ref().map(|phone| phone.as_str()),
+        Some("+14155550444")
+    );
+
+    let synced = repo.create_or_update_manager(&login_payload).unwrap();
+    assert_eq!(synced.id, manager.id);
+    assert_eq!(synced.name.as_str(), "Display Name");
+    assert_eq!(synced.phone, updated.phone);
+
+    let other_hub = HubId::new(2).expect("valid hub id");
+    assert!(matches!(
+        repo.update_manager_profile(
+            manager.id,
+            other_hub,
+            &ManagerName::new("Intruder").unwrap(),
+            None
+        ),
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[test]
+fn test_transfer_manager_assignments() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+        new_client_record("Carol", Some("carol@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let client_ids = clients.iter().map(|c| c.id).collect::<Vec<_>>();
+
+    let from = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "From".to_string(), "from@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let to = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "To".to_string(), "to@example.com".to_string(), true).unwrap(),
+        )
+        .unwrap();
+
+    repo.assign_clients_to_manager(from.id, &client_ids)
+        .unwrap();
+    // Carol is already shared with the target manager.
+    repo.assign_clients_to_manager(to.id, &client_ids[2..])
+        .unwrap();
+
+    let event_type = ClientEventType::Other("Transfer".to_string());
+    let transferred = repo
+        .reassign_all_clients(from.id, to.id, hub_id, &event_type, &json!({}))
+        .unwrap();
+    assert_eq!(transferred.len(), 3);
+
+    let (from_total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id).manager_email(from.email.clone()))
+        .unwrap();
+    assert_eq!(from_total, 0);
+
+    let (to_total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id).manager_email(to.email.clone()))
+        .unwrap();
+    assert_eq!(to_total, 3);
+
+    assert!(
+        repo.reassign_all_clients(from.id, to.id, hub_id, &event_type, &json!({}))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_reassign_all_clients_records_events_with_the_move() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let client_ids = clients.iter().map(|c| c.id).collect::<Vec<_>>();
+
+    let from = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "From".to_string(), "from@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+    let to = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "To".to_string(), "to@example.com".to_string(), true).unwrap(),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(from.id, &client_ids)
+        .unwrap();
+
+    let event_type = ClientEventType::Other("Transfer".to_string());
+    let events = repo
+        .reassign_all_clients(
+            from.id,
+            to.id,
+            hub_id,
+            &event_type,
+            &json!({"text": "moved"}),
+        )
+        .unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.manager_id == to.id
+        && event.event_type == event_type
+        && event.event_data["text"] == "moved"));
+    let mut event_clients = events
+        .iter()
+        .map(|event| event.client_id)
+        .collect::<Vec<_>>();
+    event_clients.sort();
+    let mut expected = client_ids.clone();
+    expected.sort();
+    assert_eq!(event_clients, expected);
+
+    let (to_total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id).manager_email(to.email.clone()))
+        .unwrap();
+    assert_eq!(to_total, 2);
+
+    // A manager without clients transfers nothing and records no events.
+    let events = repo
+        .reassign_all_clients(
+            from.id,
+            to.id,
+            hub_id,
+            &event_type,
+            &json!({"text": "moved"}),
+        )
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn test_add_clients_to_manager_keeps_existing_assignments() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let (alice, bob) = (clients[0].id, clients[1].id);
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap(),
+        )
+        .unwrap();
+
+    repo.assign_clients_to_manager(manager.id, &[alice])
+        .unwrap();
+    assert_eq!(
+        repo.add_clients_to_manager(manager.id, &[alice, bob])
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        repo.add_clients_to_manager(manager.id, &[alice, bob])
+            .unwrap(),
+        0
+    );
+
+    let (total, _) = repo
+        .list_clients(ClientListQuery::new(hub_id).manager_email(manager.email.clone()))
+        .unwrap();
+    assert_eq!(total, 2);
+}
+
+#[test]
+fn test_list_managers_with_clients_sort_order() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[
+        new_client_record("Alice", Some("alice@example.com"), None),
+        new_client_record("Bob", Some("bob@example.com"), None),
+    ])
+    .unwrap();
+    let (_, clients) = repo.list_clients(ClientListQuery::new(hub_id)).unwrap();
+    let client_ids = clients.iter().map(|c| c.id).collect::<Vec<_>>();
+
+    let mut managers = Vec::new();
+    for (name, email) in [
+        ("Zoe", "zoe@example.com"),
+        ("Adam", "adam@example.com"),
+        ("Mia", "mia@example.com"),
+    ] {
+        managers.push(
+            repo.create_or_update_manager(
+                &NewManager::try_new(1, name.to_string(), email.to_string(), true).unwrap(),
+            )
+            .unwrap(),
+        );
+    }
+    repo.assign_clients_to_manager(managers[0].id, &client_ids)
+        .unwrap();
+    repo.assign_clients_to_manager(managers[2].id, &client_ids[..1])
+        .unwrap();
+
+    let names = |sort| {
+        repo.list_managers_with_clients(hub_id, sort)
+            .unwrap()
+            .into_iter()
+            .map(|(manager, _)| manager.name.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(ManagerSort::Name), vec!["Adam", "Mia", "Zoe"]);
+    assert_eq!(names(ManagerSort::ClientCount), vec!["Zoe", "Mia", "Adam"]);
+}
+
+#[test]
+fn test_list_managers_with_clients_round_trips_columns() {
+    let test_db = common::TestDb::new();
+    let repo = DieselRepository::new(test_db.pool());
+    let hub_id = HubId::new(1).expect("valid hub id");
+
+    repo.create_or_replace_clients(&[new_client_record(
+        "Alice",
+        Some("alice@example.com"),
+        Some("+14155550111"),
+    )])
+    .unwrap();
+    let client = repo
+        .get_client_by_email(&ClientEmail::new("alice@example.com").unwrap(), hub_id)
+        .unwrap()
+        .expect("client exists");
+
+    let manager = repo
+        .create_or_update_manager(
+            &NewManager::try_new(1, "Manager".to_string(), "m@example.com".to_string(), true)
+                .unwrap()
+                .with_contact_details(
+                    Some(NonEmptyString::new("Acme").expect("valid company")),
+                    Some(PhoneNumber::new("+14155550222").expect("valid phone")),
+                ),
+        )
+        .unwrap();
+    repo.assign_clients_to_manager(manager.id, &[client.id])
+        .unwrap();
+
+    let managers = repo
+        .list_managers_with_clients(hub_id, ManagerSort::Name)
+        .unwrap();
+    assert_eq!(managers.len(), 1);
+    let (loaded_manager, loaded_clients) = &managers[0];
+    assert_eq!(loaded_manager, &manager);
+
+    assert_eq!(loaded_clients.len(), 1);
+    let loaded_client = &loaded_clients[0];
+    assert_eq!(loaded_client.id, client.id);
+    assert_eq!(loaded_client.public_id, client.public_id);
+    assert_eq!(loaded_client.hub_id, client.hub_id);
+    assert_eq!(loaded_client.name, client.name);
+    assert_eq!(loaded_client.email, client.email);
+    assert_eq!(loaded_client.phone, client.phone);
+    assert_eq!(loaded_client.created_at, client.created_at);
+    assert_eq!(loaded_client.updated_at, client.updated_at);
 }
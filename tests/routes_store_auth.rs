@@ -4,7 +4,7 @@ use chrono::{Duration, Utc};
 use jsonwebtoken::{EncodingKey, Header, encode};
 use pushkind_crm::domain::client::NewClient;
 use pushkind_crm::domain::store_session::{STORE_SESSION_COOKIE_NAME, StoreSessionClaims};
-use pushkind_crm::models::config::AppConfig;
+use pushkind_crm::models::config::{AppConfig, FeaturesConfig};
 use pushkind_crm::repository::{ClientReader, ClientWriter, DieselRepository};
 use pushkind_crm::routes::store::{get_store_session, logout_store_session};
 
@@ -20,6 +20,7 @@ fn test_config() -> AppConfig {
         zmq_clients_sub: "tcp://127.0.0.1:5566".to_string(),
         zmq_replier_sub: "tcp://127.0.0.1:5560".to_string(),
         zmq_tasks_sub: "tcp://127.0.0.1:5564".to_string(),
+        zmq_tracker_sub: "tcp://127.0.0.1:5567".to_string(),
         sms_sender: "cns.shared".to_string(),
         secret: "secret".to_string(),
         auth_service_url: "".to_string(),
@@ -66,8 +67,11 @@ async fn get_store_session_returns_customer_for_valid_cookie() {
 
     let app = test::init_service(
         App::new()
-            .app_data(web::Data::new(repo))
-            .app_data(web::Data::new(test_config()))
+            .app_data(common::app_state(
+                repo,
+                test_config(),
+                common::test_server_config(0, FeaturesConfig::default()),
+            ))
             .service(web::scope("/api/v1/store").service(get_store_session)),
     )
     .await;
@@ -83,9 +87,14 @@ async fn get_store_session_returns_customer_for_valid_cookie() {
 
 #[actix_web::test]
 async fn logout_store_session_clears_cookie() {
+    let test_db = common::TestDb::new();
     let app = test::init_service(
         App::new()
-            .app_data(web::Data::new(test_config()))
+            .app_data(common::app_state(
+                DieselRepository::new(test_db.pool()),
+                test_config(),
+                common::test_server_config(0, FeaturesConfig::default()),
+            ))
             .service(web::scope("/api/v1/store").service(logout_store_session)),
     )
     .await;
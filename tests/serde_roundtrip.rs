@@ -0,0 +1,326 @@
+//! JSON round-trip tests for the serializable domain types.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use pushkind_crm::domain::assignment_rule::ManagerAssignmentRule;
+use pushkind_crm::domain::audit::AuditEntry;
+use pushkind_crm::domain::client::{Client, ClientStatus, ClientUpsertSummary, FieldMeta};
+use pushkind_crm::domain::client_event::{ClientEvent, ClientEventType};
+use pushkind_crm::domain::contact_policy::{ContactPolicy, ContactPolicyViolation};
+use pushkind_crm::domain::hub_settings::HubSettings;
+use pushkind_crm::domain::import_job::{ImportJob, ImportJobStatus};
+use pushkind_crm::domain::important_field::ImportantField;
+use pushkind_crm::domain::manager::{ClientManager, Manager};
+use pushkind_crm::domain::note::ClientNote;
+use pushkind_crm::domain::segment::ClientSegment;
+use pushkind_crm::domain::store_session::StoreSessionClaims;
+use pushkind_crm::domain::types::{
+    ClientEmail, ClientId, CommentMessage, DateRange, ExportFormat, HubId, ImportJobId,
+    ManagerEmail, NonEmptyString, PhoneNumber, PhoneRegion, PublicId,
+};
+use pushkind_crm::domain::webhook::Webhook;
+
+/// Serializes `value` to JSON, reads it back and checks nothing was lost.
+fn assert_roundtrip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let encoded = serde_json::to_string(value).expect("value serializes");
+    let decoded: T = serde_json::from_str(&encoded).expect("value deserializes");
+
+    assert_eq!(&decoded, value, "round trip through {encoded}");
+}
+
+/// Timestamp with sub-second precision so truncation would be noticed.
+fn timestamp() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 3, 14)
+        .and_then(|date| date.and_hms_micro_opt(15, 9, 26, 535_897))
+        .expect("valid timestamp")
+}
+
+fn sample_client(fields: Option<BTreeMap<String, String>>, phone: Option<&str>) -> Client {
+    Client::try_new(
+        7,
+        Some(PublicId::new().as_bytes()),
+        3,
+        "Анна Смирнова".to_string(),
+        Some("anna@example.com".to_string()),
+        phone.map(str::to_string),
+        timestamp(),
+        timestamp(),
+        fields,
+    )
+    .expect("valid client")
+}
+
+#[test]
+fn client_roundtrips_with_custom_fields() {
+    let fields = BTreeMap::from([
+        ("Город".to_string(), "Казань".to_string()),
+        ("Stage".to_string(), "Won".to_string()),
+    ]);
+
+    assert_roundtrip(&sample_client(Some(fields), Some("+79001234567")));
+}
+
+#[test]
+fn client_roundtrips_without_optional_values() {
+    let client = Client::try_new(
+        8,
+        None,
+        3,
+        "Bare".to_string(),
+        None,
+        None,
+        timestamp(),
+        timestamp(),
+        None,
+    )
+    .expect("valid client")
+    .with_status(ClientStatus::Archived);
+
+    assert_roundtrip(&client);
+}
+
+#[test]
+fn client_phone_roundtrips_in_both_option_states() {
+    assert_roundtrip(&Some(
+        PhoneNumber::new("+1 (415) 555-2671").expect("valid phone"),
+    ));
+    assert_roundtrip(&None::<PhoneNumber>);
+    assert_roundtrip(&sample_client(None, Some("+14155552671")));
+    assert_roundtrip(&sample_client(None, None));
+}
+
+#[test]
+fn client_summaries_roundtrip() {
+    assert_roundtrip(&ClientStatus::Active);
+    assert_roundtrip(&ClientStatus::Archived);
+    assert_roundtrip(&FieldMeta {
+        name: "Stage".to_string(),
+        value_count: 12,
+        is_important: true,
+    });
+    assert_roundtrip(&ClientUpsertSummary {
+        inserted: 4,
+        updated: 2,
+    });
+}
+
+#[test]
+fn client_event_roundtrips_every_event_type() {
+    for event_type in [
+        ClientEventType::Comment,
+        ClientEventType::DocumentLink,
+        ClientEventType::Call,
+        ClientEventType::Email,
+        ClientEventType::Reply,
+        ClientEventType::Unsubscribed,
+        ClientEventType::Task,
+        ClientEventType::Other("custom".to_string()),
+    ] {
+        assert_roundtrip(&event_type);
+
+        let event = ClientEvent::try_new(
+            1,
+            7,
+            2,
+            event_type,
+            json!({"text": "Привет", "nested": {"n": 1}}),
+            timestamp(),
+        )
+        .expect("valid event");
+        assert_roundtrip(&event);
+    }
+}
+
+#[test]
+fn naive_datetime_roundtrips_with_microseconds() {
+    assert_roundtrip(&timestamp());
+}
+
+#[test]
+fn manager_roundtrips_with_optional_contacts() {
+    let mut manager = Manager::try_new(
+        2,
+        3,
+        "Manager".to_string(),
+        "manager@example.com".to_string(),
+        true,
+    )
+    .expect("valid manager")
+    .with_active(false)
+    .with_name_locked(true);
+    assert_roundtrip(&manager);
+
+    manager.company = Some(NonEmptyString::new("Acme").expect("valid company"));
+    manager.phone = Some(PhoneNumber::new("+79001234567").expect("valid phone"));
+    assert_roundtrip(&manager);
+
+    assert_roundtrip(&ClientManager::try_new(7, 2).expect("valid link"));
+}
+
+#[test]
+fn note_and_audit_entry_roundtrip() {
+    assert_roundtrip(
+        &ClientNote::try_new(
+            5,
+            7,
+            2,
+            "Позвонить в понедельник".to_string(),
+            timestamp(),
+            timestamp(),
+            true,
+        )
+        .expect("valid note"),
+    );
+    assert_roundtrip(
+        &AuditEntry::try_new(
+            9,
+            3,
+            "admin@example.com".to_string(),
+            "client".to_string(),
+            7,
+            "update".to_string(),
+            json!({"name": {"before": "Old", "after": "New"}}),
+            timestamp(),
+        )
+        .expect("valid audit entry"),
+    );
+}
+
+#[test]
+fn hub_configuration_roundtrips() {
+    assert_roundtrip(&ImportantField::try_new(3, "Stage".to_string()).expect("valid field"));
+    assert_roundtrip(&ContactPolicy {
+        require_email: true,
+        require_phone: false,
+    });
+    for violation in [
+        ContactPolicyViolation::MissingContact,
+        ContactPolicyViolation::MissingEmail,
+        ContactPolicyViolation::MissingPhone,
+    ] {
+        assert_roundtrip(&violation);
+    }
+
+    let hub_id = HubId::new(3).expect("valid hub id");
+    assert_roundtrip(&HubSettings::defaults(hub_id));
+    assert_roundtrip(
+        &HubSettings::new(
+            hub_id,
+            25,
+            Some(NonEmptyString::new("Acme CRM").expect("valid sender")),
+            true,
+        )
+        .expect("valid settings")
+        .with_default_phone_region(Some(PhoneRegion::new("ru").expect("valid region")))
+        .with_event_retention_days(Some(90))
+        .with_email_reply_to(Some(
+            ManagerEmail::new("sales@example.com").expect("valid reply-to"),
+        )),
+    );
+}
+
+#[test]
+fn segment_rule_and_webhook_roundtrip() {
+    assert_roundtrip(
+        &ClientSegment::try_new(
+            4,
+            3,
+            "VIP".to_string(),
+            json!({"fields": {"Tier": "VIP"}}),
+            timestamp(),
+            timestamp(),
+        )
+        .expect("valid segment"),
+    );
+    assert_roundtrip(
+        &ManagerAssignmentRule::try_new(
+            6,
+            3,
+            2,
+            "Город".to_string(),
+            "Казань".to_string(),
+            timestamp(),
+        )
+        .expect("valid rule"),
+    );
+    for event_type in [None, Some("Comment"), Some("custom")] {
+        assert_roundtrip(
+            &Webhook::try_new(
+                1,
+                3,
+                "https://hooks.example.com/crm".to_string(),
+                event_type.map(str::to_string),
+                "secret".to_string(),
+                timestamp(),
+            )
+            .expect("valid webhook"),
+        );
+    }
+}
+
+#[test]
+fn import_job_roundtrips_in_every_status() {
+    for status in [
+        ImportJobStatus::Pending,
+        ImportJobStatus::Running,
+        ImportJobStatus::Completed,
+        ImportJobStatus::Failed,
+    ] {
+        let finished = status.is_terminal();
+        assert_roundtrip(&ImportJob {
+            id: ImportJobId::new(1).expect("valid job id"),
+            hub_id: HubId::new(3).expect("valid hub id"),
+            status,
+            total_rows: 10,
+            inserted_rows: 6,
+            updated_rows: 3,
+            error_rows: 1,
+            error_message: (status == ImportJobStatus::Failed)
+                .then(|| "Превышен лимит клиентов.".to_string()),
+            started_at: timestamp(),
+            completed_at: finished.then(timestamp),
+        });
+    }
+}
+
+#[test]
+fn store_session_claims_roundtrip() {
+    assert_roundtrip(&StoreSessionClaims {
+        sub: PublicId::new().to_string(),
+        hub_id: 3,
+        name: "Анна".to_string(),
+        phone: "+79001234567".to_string(),
+        email: None,
+        exp: 1_800_000_000,
+    });
+}
+
+#[test]
+fn value_objects_roundtrip() {
+    assert_roundtrip(&ClientId::new(7).expect("valid client id"));
+    assert_roundtrip(&PublicId::new());
+    assert_roundtrip(&ClientEmail::new("Ivan@Пример.РФ").expect("valid email"));
+    assert_roundtrip(
+        &CommentMessage::new("<p>Договорились на <b>вторник</b> &amp; среду</p>")
+            .expect("valid message"),
+    );
+    for format in [ExportFormat::Csv, ExportFormat::Json, ExportFormat::NdJson] {
+        assert_roundtrip(&format);
+    }
+    assert_roundtrip(
+        &DateRange::new(
+            NaiveDate::from_ymd_opt(2026, 1, 1),
+            NaiveDate::from_ymd_opt(2026, 1, 31),
+        )
+        .expect("valid range"),
+    );
+}